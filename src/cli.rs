@@ -1,7 +1,9 @@
 use chrono::NaiveDate;
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
 
+use openvital::core::export::MergeStrategy;
+
 #[derive(Parser)]
 #[command(
     name = "openvital",
@@ -16,8 +18,12 @@ pub struct Cli {
     #[arg(long = "human", short = 'H', global = true)]
     pub human: bool,
 
-    /// Override date (YYYY-MM-DD)
-    #[arg(long, global = true)]
+    /// Color --human output: always, never, or auto (when stdout is a terminal)
+    #[arg(long, global = true, default_value = "auto", value_parser = ["always", "never", "auto"])]
+    pub color: String,
+
+    /// Override date (YYYY-MM-DD, today, yesterday, "3 days ago", "last monday", ...)
+    #[arg(long, global = true, value_parser = parse_date_arg)]
     pub date: Option<NaiveDate>,
 
     /// Minimal output (just confirmation or error)
@@ -27,6 +33,59 @@ pub struct Cli {
     /// Custom config file path
     #[arg(long = "config", global = true)]
     pub config_path: Option<String>,
+
+    /// Profile to use instead of the default one (see `profile list`)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+}
+
+/// The `--profile` value, read ahead of full parsing so that value parsers
+/// (such as relative `--date`s in the profile's timezone) see the right config.
+pub fn profile_from_args(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--profile" {
+            return args.next().cloned();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// Date flag values: `YYYY-MM-DD` or a relative phrase, against today in the
+/// configured timezone.
+fn parse_date_arg(s: &str) -> Result<NaiveDate, String> {
+    let config = openvital::models::config::Config::load().unwrap_or_default();
+    openvital::core::time::parse_date_in_week(
+        s,
+        openvital::core::time::local_today(&config.timezone()),
+        config.week_start.weekday(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// `import --merge` values.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum MergeArg {
+    #[default]
+    Skip,
+    Overwrite,
+    Newest,
+}
+
+impl From<MergeArg> for MergeStrategy {
+    fn from(arg: MergeArg) -> Self {
+        match arg {
+            MergeArg::Skip => Self::Skip,
+            MergeArg::Overwrite => Self::Overwrite,
+            MergeArg::Newest => Self::Newest,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -44,13 +103,17 @@ pub enum Commands {
     /// Log a metric entry
     Log {
         /// Metric type (e.g. weight, cardio, pain) or alias
-        #[arg(required_unless_present = "batch")]
+        #[arg(required_unless_present_any = ["batch", "file"])]
         r#type: Option<String>,
 
-        /// Metric value
-        #[arg(required_unless_present = "batch")]
+        /// Metric value; `+0.5` or `-0.5` adjusts the latest entry (or adds to a daily total)
+        #[arg(required_unless_present_any = ["batch", "file", "expression"], allow_negative_numbers = true)]
         value: Option<String>,
 
+        /// Arithmetic expression for the value, e.g. "350 * 0.9" (+ - * / and parentheses)
+        #[arg(long, visible_alias = "expr", conflicts_with_all = ["value", "batch", "file"], allow_hyphen_values = true)]
+        expression: Option<String>,
+
         /// Free-text note
         #[arg(long)]
         note: Option<String>,
@@ -59,13 +122,74 @@ pub enum Commands {
         #[arg(long)]
         tags: Option<String>,
 
-        /// Data source (default: manual)
+        /// Data source, e.g. garmin; `device` records <hostname>:<tool>
+        /// (default: log.default_source, else manual)
         #[arg(long)]
         source: Option<String>,
 
-        /// Batch entries: JSON array or simple "type:value,type:value" format
+        /// Store the value in this unit instead of the type's default (no conversion)
+        #[arg(long, conflicts_with_all = ["batch", "file"])]
+        unit: Option<String>,
+
+        /// Ask for the unit and category of a type seen for the first time (terminal only)
+        #[arg(long, short = 'i', conflicts_with_all = ["batch", "file"])]
+        interactive: bool,
+
+        /// Log a likely typo of a known type (e.g. "wheight") as that type
+        #[arg(long, conflicts_with_all = ["batch", "file"])]
+        fuzzy: bool,
+
+        /// Show the entry and ask before saving it (ignored when stdin is not a terminal)
+        #[arg(long)]
+        confirm: bool,
+
+        /// Skip reporting a daily or weekly goal the entry completes (e.g. for batch imports)
+        #[arg(long)]
+        no_goal_check: bool,
+
+        /// Batch entries: JSON array or simple "type:value,type:value" format; `-` reads stdin
         #[arg(long, conflicts_with_all = ["type", "value"])]
         batch: Option<String>,
+
+        /// Read batch entries from a file (`-` for stdin): JSON array, or CSV in the export schema
+        #[arg(long, conflicts_with_all = ["type", "value", "batch"])]
+        file: Option<String>,
+    },
+
+    /// Log several measurements taken together, e.g. "waist=84,hips=98,chest=101"
+    Measure {
+        /// type=value pairs (comma-separated or as separate arguments)
+        #[arg(required = true, value_delimiter = ',')]
+        pairs: Vec<String>,
+
+        /// Free-text note for every entry
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Comma-separated tags, added to "measurement-session"
+        #[arg(long)]
+        tags: Option<String>,
+    },
+
+    /// Log a night from bed and wake times, e.g. "23:30 07:10"
+    Sleep {
+        /// Time went to bed (HH:MM)
+        bed: String,
+
+        /// Time woke up (HH:MM); earlier than bed means the next morning
+        wake: String,
+
+        /// Sleep quality (1-5), logged as sleep_quality
+        #[arg(long)]
+        quality: Option<f64>,
+
+        /// Free-text note for every entry
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Comma-separated tags for every entry
+        #[arg(long)]
+        tags: Option<String>,
     },
 
     /// Show metric history
@@ -73,40 +197,151 @@ pub enum Commands {
         /// Metric type, alias, or "today"
         r#type: Option<String>,
 
+        /// Show several metric types at once (comma-separated)
+        #[arg(long, conflicts_with = "type")]
+        types: Option<String>,
+
         /// Number of recent entries to show
         #[arg(long)]
         last: Option<u32>,
 
         /// Show entries from this date
-        #[arg(long)]
+        #[arg(long, value_parser = parse_date_arg)]
         from: Option<NaiveDate>,
 
         /// Show entries to this date
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<NaiveDate>,
+
+        /// Only entries carrying all of these tags (repeatable or comma-separated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Include count, mean, min, max and std_dev of the shown entries
         #[arg(long)]
+        stats: bool,
+
+        /// List entries oldest first (still the most recent --last entries)
+        #[arg(long)]
+        reverse: bool,
+
+        /// Output format: envelope (JSON result), jsonl (one entry per line)
+        /// or csv (export's columns)
+        #[arg(
+            long,
+            default_value = "envelope",
+            value_parser = ["envelope", "jsonl", "csv"],
+            conflicts_with_all = ["human", "stats"]
+        )]
+        format: String,
+    },
+
+    /// Search notes on entries and medications
+    Search {
+        /// Text to look for (case-insensitive substring)
+        query: String,
+
+        /// Only entries of this metric type (or this medication)
+        #[arg(long = "type")]
+        r#type: Option<String>,
+
+        /// Start date
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<NaiveDate>,
+
+        /// End date
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<NaiveDate>,
+
+        /// Maximum number of entries (and of medication hits) to return
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+
+    /// Descriptive statistics per metric type
+    Stats {
+        /// Metric type (all types if omitted)
+        #[arg(long = "type")]
+        r#type: Option<String>,
+
+        /// Only use the most recent N entries per type
+        #[arg(long)]
+        last: Option<u32>,
+
+        /// Start date
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<NaiveDate>,
+
+        /// End date
+        #[arg(long, value_parser = parse_date_arg)]
         to: Option<NaiveDate>,
     },
 
     /// Analyze trends and projections
     Trend {
         /// Metric type (e.g. weight, cardio)
-        #[arg(required_unless_present = "correlate")]
+        #[arg(required_unless_present_any = ["correlate", "correlate_all"])]
         r#type: Option<String>,
 
         /// Period: daily, weekly, or monthly
         #[arg(long)]
         period: Option<String>,
 
-        /// Number of periods to show
+        /// Number of periods to show (weeks with --by weekday)
         #[arg(long)]
         last: Option<u32>,
 
+        /// Group by day of week instead of by period: `--by weekday`
+        #[arg(
+            long,
+            value_parser = ["weekday"],
+            conflicts_with_all = ["period", "correlate", "correlate_all", "smooth", "fill"]
+        )]
+        by: Option<String>,
+
         /// Correlation analysis between two metrics (comma-separated)
         #[arg(long)]
         correlate: Option<String>,
+
+        /// Correlation matrix across all metric types
+        #[arg(long, conflicts_with_all = ["type", "correlate"])]
+        correlate_all: bool,
+
+        /// Shift the second correlated metric by N days (may be negative)
+        #[arg(long, requires = "correlate", allow_hyphen_values = true)]
+        lag: Option<i64>,
+
+        /// Try lags -7..7 and report the strongest correlation
+        #[arg(long, requires = "correlate", conflicts_with = "lag")]
+        scan_lag: bool,
+
+        /// Smooth with a moving average over N periods
+        #[arg(long)]
+        smooth: Option<u32>,
+
+        /// Bucket aggregation: sum, avg, min, or max (default depends on metric)
+        #[arg(long)]
+        agg: Option<String>,
+
+        /// Fill days without readings: forward, linear, or none (gaps up to
+        /// trend.fill_max_gap_days)
+        #[arg(long, conflicts_with = "correlate_all")]
+        fill: Option<String>,
     },
 
     /// Quick status overview
-    Status,
+    Status {
+        /// Redraw every N seconds (1-3600) until Ctrl-C; --human only
+        #[arg(long, value_name = "SECONDS")]
+        watch: Option<u64>,
+    },
+
+    /// List what hasn't been logged today (exit code 10 if anything is missing)
+    Remind {
+        /// Print nothing; only set the exit code
+        #[arg(long, short = 'q')]
+        quiet: bool,
+    },
 
     /// Manage goals
     Goal {
@@ -122,7 +357,7 @@ pub enum Commands {
 
     /// Generate a report for a time period
     Report {
-        /// Period: week or month
+        /// Period: week, month, quarter, or ytd
         #[arg(long)]
         period: Option<String>,
 
@@ -130,18 +365,52 @@ pub enum Commands {
         #[arg(long)]
         month: Option<String>,
 
-        /// Start date
+        /// Year for --period quarter or ytd (defaults to the current year)
         #[arg(long)]
+        year: Option<u16>,
+
+        /// Start date
+        #[arg(long, value_parser = parse_date_arg)]
         from: Option<NaiveDate>,
 
         /// End date
-        #[arg(long)]
+        #[arg(long, value_parser = parse_date_arg)]
         to: Option<NaiveDate>,
+
+        /// Summary aggregation for every type: sum, avg, min, or max
+        #[arg(long)]
+        agg: Option<String>,
+
+        /// Compare with the previous period of equal length (previous calendar
+        /// month for --period month): `--compare` or `--compare prev`
+        #[arg(long, num_args = 0..=1, default_missing_value = "prev", value_parser = ["prev"])]
+        compare: Option<String>,
+
+        /// Include current goal progress
+        #[arg(long)]
+        goals: bool,
+
+        /// Output format: json, html (standalone page with sparklines), or markdown
+        #[arg(long, default_value = "json")]
+        format: String,
+
+        /// Write the report to a file (html or markdown)
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Compact plain-text weekly digest (for mail): deltas, goals,
+        /// adherence, streaks and warnings
+        #[arg(long, conflicts_with_all = ["format", "output", "agg"])]
+        digest: bool,
+
+        /// Only entries carrying all of these tags (repeatable or comma-separated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
     },
 
     /// Export data for backup or analysis
     Export {
-        /// Output format: csv or json
+        /// Output format: csv, json, jsonl (one entry per line), fhir (FHIR R4 Bundle), or parquet (needs --output)
         #[arg(long, default_value = "json")]
         format: String,
 
@@ -154,27 +423,200 @@ pub enum Commands {
         r#type: Option<String>,
 
         /// Filter from date
-        #[arg(long)]
+        #[arg(long, value_parser = parse_date_arg)]
         from: Option<NaiveDate>,
 
         /// Filter to date
-        #[arg(long)]
+        #[arg(long, value_parser = parse_date_arg)]
         to: Option<NaiveDate>,
 
         /// Include medication records in export
         #[arg(long)]
         with_medications: bool,
+
+        /// Only entries carrying all of these tags (repeatable or comma-separated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Only entries logged since the last --since-last export, then record this export
+        #[arg(long, conflicts_with = "from")]
+        since_last: bool,
+
+        /// Gzip the output file (automatic when --output ends in .gz)
+        #[arg(long, visible_alias = "gzip", requires = "output")]
+        compress: bool,
     },
 
     /// Import data from external sources
     Import {
-        /// Source format: csv, json
+        /// Source format: csv, json, jsonl, apple_health, garmin
         #[arg(long)]
         source: String,
 
-        /// Input file path
+        /// Input file path (`-` reads stdin, except for apple_health); gzipped files are detected
         #[arg(long)]
         file: String,
+
+        /// Commit every N rows instead of all at once (csv, json and jsonl)
+        #[arg(long)]
+        chunk_size: Option<usize>,
+
+        /// Skip rows already stored (same id, or same type, timestamp, value and source)
+        #[arg(long)]
+        dedupe: bool,
+
+        /// Rows matching a stored entry (same id, or same type within 1 s): skip, overwrite, or newest
+        #[arg(long, value_enum, default_value = "skip")]
+        merge: MergeArg,
+    },
+
+    /// Back up the database (default: ~/.openvital/backups/data-<timestamp>.db)
+    Backup {
+        /// Destination file
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Gzip the backup (.db.gz)
+        #[arg(long)]
+        compress: bool,
+    },
+
+    /// Replace the database with a backup
+    Restore {
+        /// Backup file (.db or .db.gz)
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        path: Option<String>,
+
+        /// Backup file (same as the positional path)
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Confirm overwriting the current database
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+
+    /// Copy the database to the configured WebDAV server (`[sync]` in config)
+    Sync {
+        /// Replace the local database with the remote copy
+        #[arg(long, conflicts_with = "status")]
+        download: bool,
+
+        /// Check the server with a HEAD request without transferring anything
+        #[arg(long)]
+        status: bool,
+
+        /// Confirm overwriting the current database with --download
+        #[arg(long, short = 'y', requires = "download")]
+        yes: bool,
+    },
+
+    /// Encrypt the database at rest (SQLCipher) and enable encryption in config
+    Encrypt {
+        /// Passphrase source: env:<VAR>, keychain, or plain:<passphrase> (testing only)
+        #[arg(long)]
+        key_source: Option<String>,
+    },
+
+    /// Database maintenance: size and row counts, integrity check, compaction
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    /// Manage custom metric type definitions
+    Types {
+        #[command(subcommand)]
+        action: TypesAction,
+    },
+
+    /// Rename a metric type across all history, merging into the target if it exists
+    MigrateType {
+        /// Type to rename (e.g., "run")
+        from: String,
+
+        /// New type name (e.g., "cardio")
+        to: String,
+
+        /// Report how many rows would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Confirm the rename
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// Also move goals on the old type
+        #[arg(long)]
+        goals: bool,
+
+        /// Also repoint aliases that resolve to the old type
+        #[arg(long)]
+        aliases: bool,
+    },
+
+    /// Free-form journal notes
+    Note {
+        #[command(subcommand)]
+        action: NoteAction,
+    },
+
+    /// Log a workout session, e.g. "workout run --duration 45 --distance 7.5 --intensity 7"
+    #[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+    Workout {
+        #[command(subcommand)]
+        action: Option<WorkoutAction>,
+
+        /// Workout kind (e.g. run, ride, swim); distance is logged as <kind>_distance
+        #[arg(required = true)]
+        kind: Option<String>,
+
+        /// Duration in minutes (logged as cardio)
+        #[arg(long, required = true)]
+        duration: Option<f64>,
+
+        /// Distance in km
+        #[arg(long)]
+        distance: Option<f64>,
+
+        /// Perceived exertion, 1-10 (logged as perceived_exertion)
+        #[arg(long)]
+        intensity: Option<f64>,
+
+        /// Free-text note for every entry of the session
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Comma-separated tags, added to "workout"
+        #[arg(long)]
+        tags: Option<String>,
+    },
+
+    /// Weight entries with their BMI, newest first (needs a configured height)
+    WeightHistory {
+        /// Number of recent entries to show
+        #[arg(long)]
+        last: Option<usize>,
+
+        /// Start date
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<NaiveDate>,
+
+        /// End date
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<NaiveDate>,
+    },
+
+    /// Manage profiles, each with its own config and database
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
+    /// Menstrual cycle day, average length and predicted next start
+    Cycle {
+        #[command(subcommand)]
+        action: CycleAction,
     },
 
     /// Manage medications
@@ -186,15 +628,42 @@ pub enum Commands {
     /// Detect anomalous health readings against personal baselines
     Anomaly {
         /// Metric type to check (all if omitted)
+        #[arg(conflicts_with = "all")]
         r#type: Option<String>,
 
+        /// Scan every type with enough data, grouped by type and skipping
+        /// types whose values never vary
+        #[arg(long)]
+        all: bool,
+
+        /// With --all, also scan medication intakes
+        #[arg(long, requires = "all")]
+        include_meds: bool,
+
         /// Baseline window in days (default: 30)
         #[arg(long, default_value = "30")]
         days: u32,
 
-        /// Sensitivity: relaxed, moderate, strict (default: moderate)
+        /// Sensitivity: relaxed, moderate, strict, or a multiplier such as 1.5
+        /// (IQR fence factor for iqr, z limit for zscore/rolling; default: moderate)
         #[arg(long, default_value = "moderate")]
         threshold: String,
+
+        /// Detection method: iqr, zscore, rolling (default: iqr)
+        #[arg(long, default_value = "iqr")]
+        method: String,
+
+        /// Preceding-days window for --method rolling (default: 7)
+        #[arg(long, default_value = "7")]
+        window: u32,
+    },
+
+    /// Notable findings from recent data: correlations, trends, missed goals,
+    /// low medication adherence and weekday patterns
+    Insights {
+        /// Window in days (default: 30)
+        #[arg(long, default_value = "30")]
+        days: u32,
     },
 
     /// AI health briefing — complete health state in one response
@@ -206,12 +675,23 @@ pub enum Commands {
         /// Filter to specific metric types (comma-separated)
         #[arg(long)]
         types: Option<String>,
+
+        /// Sections to include (comma-separated: metrics,goals,meds,profile; default: all)
+        #[arg(long)]
+        sections: Option<String>,
+
+        /// Add a plain-language summary (with --human, print only that)
+        #[arg(long)]
+        narrative: bool,
     },
 
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
         shell: Shell,
+        /// Write the script to the shell's completion directory instead of stdout
+        #[arg(long)]
+        install: bool,
     },
 }
 
@@ -265,6 +745,123 @@ pub enum ConfigAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum DbAction {
+    /// File size, row counts per table and metric type, data date range
+    Stats,
+    /// Run SQLite's integrity check
+    Check,
+    /// Reclaim free space (VACUUM)
+    Compact,
+    /// Decrypt the database in place and disable encryption
+    Decrypt,
+}
+
+#[derive(Subcommand)]
+pub enum TypesAction {
+    /// List known metric types with units, categories, aliases and entry counts
+    List {
+        /// Show detail (date and value range) for one type
+        #[arg(long = "type")]
+        r#type: Option<String>,
+    },
+    /// Define a custom metric type
+    Add {
+        /// Type name (e.g., "hrv")
+        name: String,
+        /// Default unit for new entries (e.g., "ms")
+        #[arg(long, default_value = "")]
+        unit: String,
+        /// Category: body, exercise, sleep, nutrition, pain, habit, or custom
+        #[arg(long, default_value = "custom")]
+        category: String,
+    },
+    /// Remove a custom metric type definition
+    Remove {
+        /// Type name
+        name: String,
+        /// Remove even if entries of this type exist (the entries are kept)
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NoteAction {
+    /// Write a journal note (uses --date for past days)
+    Add {
+        /// Note text
+        text: String,
+        /// Comma-separated tags
+        #[arg(long)]
+        tags: Option<String>,
+    },
+    /// Show journal notes, newest first
+    Show {
+        /// Number of notes to show (default: 10)
+        #[arg(long)]
+        last: Option<usize>,
+        /// Start date
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<NaiveDate>,
+        /// End date
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<NaiveDate>,
+        /// Only notes carrying all of these tags (repeatable or comma-separated)
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorkoutAction {
+    /// Show logged workout sessions, newest first
+    List {
+        /// Number of sessions to show
+        #[arg(long)]
+        last: Option<usize>,
+        /// Start date
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<NaiveDate>,
+        /// End date
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<NaiveDate>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    /// List profiles, marking the default and active ones
+    List,
+    /// Create a profile with a default config
+    Create {
+        /// Profile name (lowercase letters, digits, '-' or '_')
+        name: String,
+        /// Also make it the default profile
+        #[arg(long)]
+        default: bool,
+    },
+    /// Make a profile the one used when --profile is not given
+    Default {
+        /// Profile name
+        name: String,
+    },
+    /// Delete a profile with its config, database and backups
+    Remove {
+        /// Profile name
+        name: String,
+        /// Confirm deleting the profile's data
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CycleAction {
+    /// Current cycle day, average length over the last 6 cycles, and predicted next start
+    Status,
+}
+
 #[derive(Subcommand)]
 pub enum MedAction {
     /// Add a medication to the active list
@@ -274,7 +871,7 @@ pub enum MedAction {
         /// Dosage (e.g., "400mg", "5ml", "thin layer")
         #[arg(long)]
         dose: Option<String>,
-        /// Frequency: daily, 2x_daily, 3x_daily, weekly, as_needed
+        /// Frequency: daily, 2x_daily, 3x_daily, weekly, as_needed, or weekdays (mon,wed,fri)
         #[arg(long)]
         freq: String,
         /// Administration route (default: oral)
@@ -284,8 +881,12 @@ pub enum MedAction {
         #[arg(long)]
         note: Option<String>,
         /// Start date (default: today)
-        #[arg(long)]
+        #[arg(long, value_parser = parse_date_arg)]
         started: Option<NaiveDate>,
+        /// Medication not to take within N minutes of this one: other_med[:minutes]
+        /// (default 120; repeatable)
+        #[arg(long, value_name = "MED[:MINUTES]")]
+        interacts_with: Vec<String>,
     },
     /// Record a dose taken
     Take {
@@ -300,6 +901,29 @@ pub enum MedAction {
         /// Comma-separated tags
         #[arg(long)]
         tags: Option<String>,
+        /// Number of doses taken at once (default: 1)
+        #[arg(long)]
+        quantity: Option<u32>,
+        /// Reason for an as-needed (PRN) dose
+        #[arg(long)]
+        indication: Option<String>,
+        /// Record an intentionally skipped dose instead of an intake
+        #[arg(long, conflicts_with_all = ["dose", "note", "tags", "quantity", "indication"])]
+        skip: bool,
+        /// Why the dose was skipped (with --skip)
+        #[arg(long, requires = "skip")]
+        reason: Option<String>,
+    },
+    /// Record a refill to track remaining supply
+    Refill {
+        /// Medication name
+        name: String,
+        /// Number of doses dispensed
+        #[arg(long)]
+        quantity: u32,
+        /// Days the refill is expected to last
+        #[arg(long)]
+        days_supply: u32,
     },
     /// List medications (active by default)
     List {
@@ -307,6 +931,29 @@ pub enum MedAction {
         #[arg(long)]
         all: bool,
     },
+    /// Update a medication's details without stopping it
+    Edit {
+        /// Medication name
+        name: String,
+        /// New dosage (e.g., "600mg")
+        #[arg(long = "dose")]
+        new_dose: Option<String>,
+        /// New frequency: daily, 2x_daily, 3x_daily, weekly, as_needed, or weekdays (mon,wed,fri)
+        #[arg(long = "freq")]
+        new_freq: Option<String>,
+        /// New administration route
+        #[arg(long = "route")]
+        new_route: Option<String>,
+        /// New note
+        #[arg(long = "note")]
+        new_note: Option<String>,
+        /// Rename the medication and move its entries to the new name
+        #[arg(long, requires = "yes")]
+        rename: Option<String>,
+        /// Confirm --rename
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
     /// Mark a medication as stopped
     Stop {
         /// Medication name
@@ -331,10 +978,30 @@ pub enum MedAction {
         #[arg(long, default_value = "7")]
         last: u32,
     },
+    /// List individual doses taken, newest first
+    History {
+        /// Medication name
+        #[arg(long)]
+        name: String,
+        /// Include doses from the last N days (default: 30)
+        #[arg(long, default_value = "30")]
+        last: u32,
+    },
+    /// Show today's dose schedule
+    Schedule,
 }
 
 /// Generate shell completions and print to stdout.
 pub fn print_completions(shell: Shell) {
-    let mut cmd = Cli::command();
-    clap_complete::generate(shell, &mut cmd, "openvital", &mut std::io::stdout());
+    write_completions(shell, &mut std::io::stdout());
+}
+
+/// Generate shell completions into `out`.
+pub fn write_completions(shell: Shell, out: &mut dyn std::io::Write) {
+    // Offer the profiles that exist when the script is generated
+    let names = openvital::core::profile::names().unwrap_or_default();
+    let mut cmd = Cli::command().mut_arg("profile", |a| {
+        a.value_parser(clap::builder::PossibleValuesParser::new(names))
+    });
+    clap_complete::generate(shell, &mut cmd, "openvital", out);
 }
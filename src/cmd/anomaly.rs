@@ -3,20 +3,52 @@ use std::str::FromStr;
 
 use openvital::core::anomaly;
 use openvital::db::Database;
-use openvital::models::anomaly::Threshold;
+use openvital::models::anomaly::{Method, Threshold};
 use openvital::models::config::Config;
 use openvital::output;
 use openvital::output::human;
 
-pub fn run(metric_type: Option<&str>, days: u32, threshold: &str, human_flag: bool) -> Result<()> {
+pub struct AnomalyArgs<'a> {
+    pub metric_type: Option<&'a str>,
+    /// Scan every type, grouped by type (`--all`).
+    pub all: bool,
+    pub include_meds: bool,
+    pub days: u32,
+    pub threshold: &'a str,
+    pub method: &'a str,
+    pub window: u32,
+}
+
+pub fn run(args: AnomalyArgs<'_>, human_flag: bool) -> Result<()> {
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
-    let threshold = Threshold::from_str(threshold)?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let threshold = Threshold::from_str(args.threshold)?;
+    let method = Method::from_str(args.method)?;
 
-    let resolved = metric_type.map(|t| config.resolve_alias(t));
-    let result = anomaly::detect(&db, resolved.as_deref(), days, threshold)?;
+    let result = if args.all {
+        anomaly::detect_all(
+            &db,
+            args.days,
+            threshold,
+            method,
+            args.window,
+            args.include_meds,
+        )?
+    } else {
+        let resolved = args.metric_type.map(|t| config.resolve_alias(t));
+        anomaly::detect_with(
+            &db,
+            resolved.as_deref(),
+            args.days,
+            threshold,
+            method,
+            args.window,
+        )?
+    };
 
-    if human_flag {
+    if human_flag && args.all {
+        println!("{}", human::format_anomaly_groups(&result));
+    } else if human_flag {
         println!("{}", human::format_anomaly(&result));
     } else {
         let out = output::success("anomaly", serde_json::to_value(&result)?);
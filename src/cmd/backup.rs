@@ -0,0 +1,146 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use openvital::core::backup;
+use openvital::core::sync;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+
+pub fn run_backup(output_path: Option<&str>, compress: bool, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let dest = match output_path {
+        Some(p) => PathBuf::from(p),
+        None => backup::default_path(&Config::data_dir(), chrono::Utc::now(), compress),
+    };
+    let mut result = backup::backup(&db, &dest, compress)?;
+    // Only rotate the default backup directory, never a user-chosen location.
+    if output_path.is_none()
+        && let Some(dir) = dest.parent()
+    {
+        result.pruned = backup::prune(dir, config.backup.keep)?
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+    }
+
+    if human {
+        println!("Backed up to {} ({} bytes)", result.path, result.size_bytes);
+        if !result.pruned.is_empty() {
+            println!("Removed {} old backup(s)", result.pruned.len());
+        }
+    } else {
+        let out = output::success("backup", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_restore(file: &str, yes: bool, human: bool) -> Result<()> {
+    if !yes {
+        openvital::fail!(
+            validation,
+            "restore replaces the current database with {}; re-run with --yes to confirm",
+            file
+        );
+    }
+
+    let config = Config::load()?;
+    let key = config.encryption.passphrase()?;
+    let result = backup::restore(Path::new(file), &Config::db_path(), key.as_deref())?;
+
+    if human {
+        println!("Restored {} to {}", result.file, result.path);
+        if let Some(ref previous) = result.previous {
+            println!("Previous database kept at {}", previous);
+        }
+    } else {
+        let out = output::success("restore", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_encrypt(key_source: Option<&str>, human: bool) -> Result<()> {
+    let mut config = Config::load()?;
+    if config.encryption.enabled {
+        openvital::fail!(conflict, "database encryption is already enabled");
+    }
+    if let Some(src) = key_source {
+        config.encryption.key_source = src.parse()?;
+    }
+
+    let db_path = Config::db_path();
+    backup::set_encryption(&mut config, &db_path, true)?;
+    config.save()?;
+
+    if human {
+        println!("Encrypted {}", db_path.display());
+    } else {
+        let out = output::success(
+            "encrypt",
+            serde_json::json!({"path": db_path.display().to_string(), "key_source": config.encryption.key_source}),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_sync(download: bool, status: bool, yes: bool, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let client = reqwest::blocking::Client::new();
+    if status {
+        let result = sync::check(&client, &config.sync)?;
+        if human {
+            let state = if result.remote_exists {
+                "database present"
+            } else if result.reachable {
+                "no database yet"
+            } else {
+                "unreachable"
+            };
+            println!(
+                "{}: {} (HTTP {})",
+                result.remote_url, state, result.http_status
+            );
+        } else {
+            let out = output::success("sync", serde_json::to_value(&result)?);
+            println!("{}", serde_json::to_string(&out)?);
+        }
+        return Ok(());
+    }
+
+    let result = if download {
+        if !yes {
+            openvital::fail!(
+                validation,
+                "sync --download replaces the current database with the remote copy; re-run with --yes to confirm"
+            );
+        }
+        let key = config.encryption.passphrase()?;
+        sync::pull(&client, &Config::db_path(), key.as_deref(), &config.sync)?
+    } else {
+        let db = Database::open_with_config(&Config::db_path(), &config)?;
+        sync::push(&client, &db, &Config::db_path(), &config.sync)?
+    };
+
+    if human {
+        let verb = if download { "Downloaded" } else { "Uploaded" };
+        println!(
+            "{} {} bytes {} {}",
+            verb,
+            result.bytes_transferred,
+            if download { "from" } else { "to" },
+            result.remote_url
+        );
+        if let Some(ref previous) = result.previous {
+            println!("Previous database kept at {}", previous);
+        }
+    } else {
+        let out = output::success("sync", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use clap_complete::Shell;
+use serde_json::json;
+use std::path::PathBuf;
+
+use openvital::output;
+
+use crate::cli;
+
+/// Write the completion script for `shell` where that shell picks it up.
+pub fn run_install(shell: Shell, human: bool) -> Result<()> {
+    let path = install_path(shell)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            openvital::error::io(&e, format!("cannot create {}: {}", parent.display(), e))
+        })?;
+    }
+    let mut script = Vec::new();
+    cli::write_completions(shell, &mut script);
+    std::fs::write(&path, script)
+        .map_err(|e| openvital::error::io(&e, format!("cannot write {}: {}", path.display(), e)))?;
+
+    if human {
+        println!("Installed {} completions to {}", shell, path.display());
+        if let Some(hint) = activation_hint(shell, &path) {
+            println!("{}", hint);
+        }
+    } else {
+        let out = output::success(
+            "completions",
+            json!({
+                "shell": shell.to_string(),
+                "installed": true,
+                "path": path.display().to_string(),
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+/// Where `--install` writes the script for `shell`.
+fn install_path(shell: Shell) -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| {
+        openvital::error::validation("cannot find the home directory to install completions")
+    })?;
+    let config_dir = home.join(".config");
+    Ok(match shell {
+        Shell::Bash => home.join(".bash_completion.d").join("openvital"),
+        Shell::Zsh => zsh_fpath_dir()?.join("_openvital"),
+        Shell::Fish => config_dir
+            .join("fish")
+            .join("completions")
+            .join("openvital.fish"),
+        Shell::PowerShell => powershell_dir(&home)
+            .join("Completions")
+            .join("openvital.ps1"),
+        Shell::Elvish => config_dir.join("elvish").join("lib").join("openvital.elv"),
+        other => openvital::fail!(validation, "--install does not support {}", other),
+    })
+}
+
+/// First writable directory in `$FPATH` (zsh's `$fpath`, exported).
+fn zsh_fpath_dir() -> Result<PathBuf> {
+    let fpath = std::env::var("FPATH").unwrap_or_default();
+    fpath
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .find(|d| is_writable_dir(d))
+        .ok_or_else(|| {
+            openvital::error::validation(
+                "no writable directory in $FPATH; run `export FPATH` in zsh or add one to fpath",
+            )
+        })
+}
+
+/// Whether we can create files in `dir`. The permission bits alone don't
+/// say (a root-owned 0755 directory isn't "readonly"), so try a real write.
+fn is_writable_dir(dir: &std::path::Path) -> bool {
+    let probe = dir.join(format!(".openvital-write-test-{}", std::process::id()));
+    match std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// PowerShell's profile directory: `Documents\PowerShell` on Windows,
+/// `~/.config/powershell` elsewhere.
+fn powershell_dir(home: &std::path::Path) -> PathBuf {
+    if cfg!(windows) {
+        dirs::document_dir()
+            .unwrap_or_else(|| home.join("Documents"))
+            .join("PowerShell")
+    } else {
+        home.join(".config").join("powershell")
+    }
+}
+
+/// What to add to the shell's startup file, for shells that don't load the
+/// script on their own.
+fn activation_hint(shell: Shell, path: &std::path::Path) -> Option<String> {
+    match shell {
+        Shell::Bash => Some(format!("Add to ~/.bashrc: source {}", path.display())),
+        Shell::PowerShell => Some(format!("Add to $PROFILE: . {}", path.display())),
+        Shell::Elvish => Some("Add to rc.elv: use openvital".to_string()),
+        _ => None,
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use serde_json::json;
 
 use openvital::models::config::Config;
+use openvital::models::range::MetricRange;
 use openvital::output;
 
 pub fn run_show(human: bool) -> Result<()> {
@@ -31,18 +32,159 @@ pub fn run_set(key: &str, value: &str) -> Result<()> {
             config.profile.conditions = value.split(',').map(|s| s.trim().to_string()).collect();
         }
         "primary_exercise" => config.profile.primary_exercise = Some(value.to_string()),
-        "units.system" => match value {
-            "metric" => config.units = openvital::models::config::Units::default(),
-            "imperial" => config.units = openvital::models::config::Units::imperial(),
-            _ => anyhow::bail!("units.system must be 'metric' or 'imperial'"),
-        },
+        "units.system" => {
+            let custom = std::mem::take(&mut config.units.custom);
+            match value {
+                "metric" => config.units = openvital::models::config::Units::default(),
+                "imperial" => config.units = openvital::models::config::Units::imperial(),
+                _ => openvital::fail!(validation, "units.system must be 'metric' or 'imperial'"),
+            }
+            config.units.custom = custom;
+        }
+        k if k.starts_with("units.") => {
+            let rest = k.strip_prefix("units.").unwrap();
+            let metric_type = rest.strip_prefix("custom.").unwrap_or(rest);
+            if metric_type.is_empty() {
+                openvital::fail!(validation, "missing metric type in config key '{}'", k);
+            }
+            let resolved = config.resolve_alias(metric_type);
+            let unit = openvital::core::units::parse_override(&resolved, value)?;
+            config.units.custom.insert(resolved, unit);
+        }
+        "timezone" => {
+            openvital::core::time::parse_timezone(value)?;
+            config.profile.timezone = Some(value.to_string());
+        }
+        "week_start" => config.week_start = value.parse()?,
+        k if k.starts_with("range.") => {
+            let metric_type = config.resolve_alias(k.strip_prefix("range.").unwrap());
+            if value == "none" {
+                config.ranges.remove(&metric_type);
+            } else {
+                let range: MetricRange = value.parse()?;
+                let range = range
+                    .map(|v| openvital::core::units::from_input(v, &metric_type, &config.units));
+                config.ranges.insert(metric_type, range);
+            }
+        }
+        k if k.starts_with("aggregation.") => {
+            let metric_type = k.strip_prefix("aggregation.").unwrap();
+            let agg: openvital::models::metric::Aggregation = value.parse()?;
+            config.aggregation.insert(metric_type.to_string(), agg);
+        }
+        "streaks.track" => {
+            config.streaks.track = value
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        "encryption.enabled" => config.encryption.enabled = value.parse()?,
+        "database.auto_vacuum" => config.database.auto_vacuum = value.parse()?,
+        "database.journal_mode" => config.database.journal_mode = value.parse()?,
+        "log.fuzzy_match" => config.log.fuzzy_match = value.parse()?,
+        "log.default_source" => {
+            openvital::core::logging::Source::parse(value)?;
+            config.log.default_source = Some(value.trim().to_string())
+        }
+        "medications.skips_preserve_streak" => {
+            config.medications.skips_preserve_streak = value.parse()?
+        }
+        // Unlike encryption.enabled, this also converts the existing database.
+        "security.encrypt" => {
+            openvital::core::backup::set_encryption(
+                &mut config,
+                &Config::db_path(),
+                value.parse()?,
+            )?;
+        }
+        "backup.keep" => config.backup.keep = value.parse()?,
+        "sync.enabled" => config.sync.enabled = value.parse()?,
+        "sync.provider" => {
+            if value != "webdav" {
+                openvital::fail!(validation, "sync.provider must be 'webdav'");
+            }
+            config.sync.provider = value.to_string();
+        }
+        "sync.url" => config.sync.url = value.trim().to_string(),
+        "sync.username" => config.sync.username = value.to_string(),
+        "sync.password_env" => config.sync.password_env = value.to_string(),
+        "glucose.target_low" | "glucose.target_high" => {
+            let entered: f64 = value.parse()?;
+            if !entered.is_finite() || entered <= 0.0 {
+                openvital::fail!(validation, "{} must be positive", key);
+            }
+            let mg_dl = openvital::core::units::from_input(entered, "glucose", &config.units);
+            let (low, high) = if key == "glucose.target_low" {
+                (mg_dl, config.glucose.target_high)
+            } else {
+                (config.glucose.target_low, mg_dl)
+            };
+            if low >= high {
+                openvital::fail!(
+                    validation,
+                    "glucose.target_low must be below glucose.target_high"
+                );
+            }
+            config.glucose.target_low = low;
+            config.glucose.target_high = high;
+        }
+        "trend.fill_max_gap_days" => {
+            let days: u32 = value.parse()?;
+            if days == 0 {
+                openvital::fail!(validation, "trend.fill_max_gap_days must be at least 1");
+            }
+            config.trend.fill_max_gap_days = days;
+        }
+        "cycle.gap_days" => {
+            let days: u32 = value.parse()?;
+            if days == 0 {
+                openvital::fail!(validation, "cycle.gap_days must be at least 1");
+            }
+            config.cycle.gap_days = days;
+        }
+        "alerts.resting_hr_change_pct" => {
+            let pct: f64 = value.parse()?;
+            if !pct.is_finite() || pct <= 0.0 {
+                openvital::fail!(validation, "alerts.resting_hr_change_pct must be positive");
+            }
+            config.alerts.resting_hr_change_pct = pct;
+        }
+        k if k.starts_with("alerts.blood_pressure.") => {
+            let field = k.strip_prefix("alerts.blood_pressure.").unwrap();
+            let mmhg: f64 = value.parse()?;
+            if !mmhg.is_finite() || mmhg <= 0.0 {
+                openvital::fail!(validation, "{} must be a positive mmHg value", k);
+            }
+            let Some(slot) = config.alerts.blood_pressure.field_mut(field) else {
+                openvital::fail!(
+                    validation,
+                    "unknown blood pressure threshold '{}' (expected {})",
+                    field,
+                    openvital::models::config::BpThresholds::FIELDS.join(", ")
+                );
+            };
+            *slot = mmhg;
+        }
+        "reminders.daily" => {
+            config.reminders.daily = value
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        "encryption.key_source" | "database.encryption_key" => {
+            config.encryption.key_source = value.parse()?;
+        }
         k if k.starts_with("alias.") => {
             let alias = k.strip_prefix("alias.").unwrap();
             config.aliases.insert(alias.to_string(), value.to_string());
         }
-        _ => anyhow::bail!(
+        _ => openvital::fail!(
+            validation,
             "unknown config key: '{}'. Valid keys: height, birth_year, gender, \
-             conditions, primary_exercise, units.system, alias.<name>",
+             conditions, primary_exercise, units.system, units.<type>, units.custom.<type>, timezone, week_start, alias.<name>, aggregation.<type>, range.<type>, \
+             streaks.track, encryption.enabled, encryption.key_source, database.auto_vacuum, database.journal_mode, log.fuzzy_match, log.default_source, medications.skips_preserve_streak, security.encrypt, backup.keep, sync.enabled, sync.provider, sync.url, sync.username, sync.password_env, cycle.gap_days, trend.fill_max_gap_days, glucose.target_low, glucose.target_high, reminders.daily, alerts.resting_hr_change_pct, alerts.blood_pressure.<threshold>",
             key
         ),
     }
@@ -6,9 +6,15 @@ use openvital::models::config::Config;
 use openvital::output;
 use openvital::output::human;
 
-pub fn run(days: u32, types: Option<&str>, human_flag: bool) -> Result<()> {
+pub fn run(
+    days: u32,
+    types: Option<&str>,
+    sections: Option<&str>,
+    narrative: bool,
+    human_flag: bool,
+) -> Result<()> {
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
     let type_filter: Option<Vec<String>> = types.map(|t| {
         t.split(',')
@@ -20,9 +26,22 @@ pub fn run(days: u32, types: Option<&str>, human_flag: bool) -> Result<()> {
         .map(|v| v.iter().map(|s| s.as_str()).collect());
     let type_refs: Option<&[&str]> = type_refs.as_deref();
 
-    let result = context::compute(&db, &config, days, type_refs)?;
+    let sections: Vec<context::Section> = match sections {
+        Some(s) => s
+            .split(',')
+            .map(|p| p.trim().parse())
+            .collect::<Result<_>>()?,
+        None => context::Section::ALL.to_vec(),
+    };
 
-    if human_flag {
+    let mut result = context::compute_with(&db, &config, days, type_refs, &sections)?;
+    if narrative {
+        result.narrative = Some(context::generate_narrative(&result, &config));
+    }
+
+    if human_flag && let Some(text) = &result.narrative {
+        println!("{}", text);
+    } else if human_flag {
         println!("{}", human::format_context(&result));
     } else {
         let out = output::success("context", serde_json::to_value(&result)?);
@@ -0,0 +1,21 @@
+use anyhow::Result;
+
+use openvital::core::cycle;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run_status(human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let status = cycle::status(&db, config.cycle.gap_days)?;
+
+    if human_flag {
+        println!("{}", human::format_cycle_status(&status));
+    } else {
+        let out = output::success("cycle_status", serde_json::to_value(&status)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
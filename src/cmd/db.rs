@@ -0,0 +1,90 @@
+use anyhow::Result;
+
+use openvital::core::{backup, maintenance};
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run_stats(human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let stats = maintenance::stats(&db)?;
+
+    if human_flag {
+        println!("{}", human::format_db_stats(&stats, &Config::db_path()));
+    } else {
+        let mut data = serde_json::to_value(&stats)?;
+        data["path"] = serde_json::json!(Config::db_path().display().to_string());
+        let out = output::success("db_stats", data);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_check(human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let result = maintenance::check(&db)?;
+
+    if human_flag {
+        if result.ok {
+            println!("Integrity check passed");
+        } else {
+            println!(
+                "Integrity check found {} problem(s):",
+                result.problems.len()
+            );
+            for p in &result.problems {
+                println!("  {}", p);
+            }
+        }
+    } else {
+        let out = output::success("db_check", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_compact(human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let result = maintenance::compact(&db)?;
+
+    if human_flag {
+        println!(
+            "Compacted {} → {} ({} reclaimed)",
+            human::format_bytes(result.size_before),
+            human::format_bytes(result.size_after),
+            human::format_bytes(result.reclaimed_bytes)
+        );
+    } else {
+        let out = output::success("db_compact", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_decrypt(human_flag: bool) -> Result<()> {
+    let mut config = Config::load()?;
+    if !config.encryption.enabled {
+        openvital::fail!(
+            validation,
+            "database encryption is not enabled; set encryption.enabled and its key source first if the file is encrypted"
+        );
+    }
+    let db_path = Config::db_path();
+    backup::set_encryption(&mut config, &db_path, false)?;
+    config.save()?;
+
+    if human_flag {
+        println!("Decrypted {}", db_path.display());
+    } else {
+        let out = output::success(
+            "db_decrypt",
+            serde_json::json!({"path": db_path.display().to_string()}),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
@@ -1,79 +1,259 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, Utc};
+use std::path::Path;
 
 use openvital::core::export;
 use openvital::db::Database;
 use openvital::models::config::Config;
 use openvital::output;
+use openvital::output::human;
 
-pub fn run_export(
-    format: &str,
-    output_path: Option<&str>,
-    metric_type: Option<&str>,
-    from: Option<NaiveDate>,
-    to: Option<NaiveDate>,
-    with_medications: bool,
-    human: bool,
-) -> Result<()> {
-    let db = Database::open(&Config::db_path())?;
+pub struct ExportArgs<'a> {
+    pub format: &'a str,
+    pub output: Option<&'a str>,
+    pub metric_type: Option<&'a str>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub with_medications: bool,
+    pub tags: &'a [String],
+    pub since_last: bool,
+    pub compress: bool,
+}
 
-    let content = match format {
-        "csv" => export::to_csv(&db, metric_type, from, to)?,
-        "json" if with_medications => export::to_json_with_medications(&db, metric_type, from, to)?,
-        "json" => export::to_json(&db, metric_type, from, to)?,
-        other => anyhow::bail!("unsupported format: {} (expected csv/json)", other),
-    };
+pub fn run_export(args: ExportArgs<'_>, human: bool) -> Result<()> {
+    let ExportArgs {
+        format,
+        output: output_path,
+        metric_type,
+        from,
+        to,
+        with_medications,
+        tags,
+        since_last,
+        compress,
+    } = args;
+    let compress = compress || output_path.is_some_and(|p| export::is_gz_path(Path::new(p)));
+    if format == "parquet" {
+        if output_path.is_none() {
+            openvital::fail!(validation, "parquet export needs --output <file>");
+        }
+        if compress {
+            openvital::fail!(
+                validation,
+                "parquet export cannot be gzipped; drop --compress or the .gz extension"
+            );
+        }
+    }
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
-    if let Some(path) = output_path {
-        std::fs::write(path, &content)?;
+    // Taken before querying so entries logged during the export go out next time.
+    let started = Utc::now();
+    let since = if since_last {
+        export::get_last_export_time()?
+    } else {
+        None
+    };
+    let entries = if since_last {
+        export::entries_since(&db, metric_type, since, to, tags)?
+    } else {
+        export::metric_entries(&db, metric_type, from, to, tags)?
+    };
+    if let (Some(path), "parquet") = (output_path, format) {
+        export::write_parquet(&entries, Path::new(path))?;
+        if since_last {
+            export::set_last_export_time(started)?;
+        }
         if human {
-            println!("Exported to {}", path);
+            println!("Exported {} rows to {}", entries.len(), path);
         } else {
-            let out = output::success(
-                "export",
-                serde_json::json!({"path": path, "format": format}),
-            );
+            let mut data =
+                serde_json::json!({"format": format, "rows": entries.len(), "path": path});
+            if since_last {
+                data["since"] = serde_json::to_value(since)?;
+                data["count"] = entries.len().into();
+            }
+            let out = output::success("export", data);
             println!("{}", serde_json::to_string(&out)?);
         }
+        return Ok(());
+    }
+    let content = export::render(&db, format, &entries, with_medications)?;
+
+    if let Some(path) = output_path {
+        if compress {
+            export::write_compressed(&content, Path::new(path))?;
+        } else {
+            std::fs::write(path, &content)?;
+        }
     } else {
         print!("{}", content);
     }
+    if since_last {
+        export::set_last_export_time(started)?;
+    }
+
+    if let Some(path) = output_path {
+        if human {
+            if since_last {
+                let since = since.map_or_else(|| "the beginning".to_string(), |t| t.to_rfc3339());
+                println!(
+                    "Exported {} entries since {} to {}",
+                    entries.len(),
+                    since,
+                    path
+                );
+            } else if compress {
+                println!(
+                    "Exported to {} ({} compressed to {})",
+                    path,
+                    human::format_bytes(content.len() as u64),
+                    human::format_bytes(std::fs::metadata(path)?.len())
+                );
+            } else {
+                println!("Exported to {}", path);
+            }
+        } else {
+            let mut data = serde_json::json!({"path": path, "format": format});
+            if since_last {
+                data["since"] = serde_json::to_value(since)?;
+                data["count"] = entries.len().into();
+            }
+            if compress {
+                data["compressed"] = true.into();
+                data["original_size"] = content.len().into();
+                data["compressed_size"] = std::fs::metadata(path)?.len().into();
+            }
+            let out = output::success("export", data);
+            println!("{}", serde_json::to_string(&out)?);
+        }
+    }
     Ok(())
 }
 
-pub fn run_import(source: &str, file_path: &str, human: bool) -> Result<()> {
-    let db = Database::open(&Config::db_path())?;
-    let content = std::fs::read_to_string(file_path)?;
+pub fn run_import(
+    source: &str,
+    file_path: &str,
+    opts: export::ImportOptions,
+    human: bool,
+) -> Result<()> {
+    if (opts.dedupe || opts.chunk_size.is_some() || opts.merge != export::MergeStrategy::Skip)
+        && !matches!(source, "csv" | "json" | "jsonl")
+    {
+        openvital::fail!(
+            validation,
+            "--dedupe, --merge and --chunk-size are only supported for csv, json and jsonl imports"
+        );
+    }
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    if source == "apple_health" {
+        if file_path == "-" {
+            openvital::fail!(
+                validation,
+                "apple_health import needs a file path; it cannot read stdin"
+            );
+        }
+        let result = export::import_apple_health(&db, std::path::Path::new(file_path))?;
+        if human {
+            println!(
+                "Imported {} entries from {} ({} skipped)",
+                result.imported, file_path, result.skipped
+            );
+            if !result.unknown_types.is_empty() {
+                println!("  Unknown types: {}", result.unknown_types.join(", "));
+            }
+        } else {
+            let out = output::success(
+                "import",
+                serde_json::json!({"imported": result.imported, "skipped": result.skipped, "unknown_types": result.unknown_types, "source": source, "file": file_path}),
+            );
+            println!("{}", serde_json::to_string(&out)?);
+        }
+        return Ok(());
+    }
+
+    let content = export::decode_input(super::read_input_bytes(file_path)?)?;
 
     match source {
         "json" => {
-            let (metric_count, med_count) = export::import_json_auto(&db, &content)?;
+            let (counts, med_count) = export::import_json_auto_with(&db, &content, opts)?;
+            if human {
+                println!(
+                    "Imported {} metrics, {} medications from {}{}",
+                    counts.imported,
+                    med_count,
+                    file_path,
+                    skipped_summary(&counts)
+                );
+            } else {
+                let out = output::success(
+                    "import",
+                    serde_json::json!({"metric_count": counts.imported, "medication_count": med_count, "imported": counts.imported, "skipped_duplicates": counts.skipped_duplicates, "overwritten": counts.overwritten, "errors": counts.errors, "source": source, "file": file_path}),
+                );
+                println!("{}", serde_json::to_string(&out)?);
+            }
+        }
+        "garmin" => {
+            let result = export::import_garmin_csv(&db, &content)?;
             if human {
                 println!(
-                    "Imported {} metrics, {} medications from {}",
-                    metric_count, med_count, file_path
+                    "Imported {} entries from {} ({} rows skipped)",
+                    result.imported, file_path, result.skipped
                 );
             } else {
                 let out = output::success(
                     "import",
-                    serde_json::json!({"metric_count": metric_count, "medication_count": med_count, "source": source, "file": file_path}),
+                    serde_json::json!({"imported": result.imported, "skipped": result.skipped, "source": source, "file": file_path}),
                 );
                 println!("{}", serde_json::to_string(&out)?);
             }
         }
-        "csv" => {
-            let count = export::import_csv(&db, &content)?;
+        "csv" | "jsonl" => {
+            let counts = if source == "csv" {
+                export::import_csv_with(&db, &content, opts)?
+            } else {
+                export::import_jsonl_with(&db, &content, opts)?
+            };
             if human {
-                println!("Imported {} entries from {}", count, file_path);
+                println!(
+                    "Imported {} entries from {}{}",
+                    counts.imported,
+                    file_path,
+                    skipped_summary(&counts)
+                );
             } else {
                 let out = output::success(
                     "import",
-                    serde_json::json!({"count": count, "source": source, "file": file_path}),
+                    serde_json::json!({"count": counts.imported, "imported": counts.imported, "skipped_duplicates": counts.skipped_duplicates, "overwritten": counts.overwritten, "errors": counts.errors, "source": source, "file": file_path}),
                 );
                 println!("{}", serde_json::to_string(&out)?);
             }
         }
-        other => anyhow::bail!("unsupported import source: {} (expected csv/json)", other),
+        other => openvital::fail!(
+            validation,
+            "unsupported import source: {} (expected csv/json/jsonl/apple_health/garmin)",
+            other
+        ),
     }
     Ok(())
 }
+
+/// " (3 duplicates skipped, 1 malformed row skipped)", or empty when nothing was skipped.
+fn skipped_summary(counts: &export::ImportCounts) -> String {
+    let mut parts = Vec::new();
+    if counts.skipped_duplicates > 0 {
+        parts.push(format!("{} duplicates skipped", counts.skipped_duplicates));
+    }
+    if counts.overwritten > 0 {
+        parts.push(format!("{} overwritten", counts.overwritten));
+    }
+    if counts.errors > 0 {
+        parts.push(format!("{} malformed rows skipped", counts.errors));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
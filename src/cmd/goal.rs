@@ -5,6 +5,7 @@ use openvital::db::Database;
 use openvital::models::config::Config;
 use openvital::models::goal::{Direction, Timeframe};
 use openvital::output;
+use openvital::output::color::ColorConfig;
 
 pub fn run_set(
     metric_type: &str,
@@ -15,7 +16,7 @@ pub fn run_set(
 ) -> Result<()> {
     let config = Config::load()?;
     let resolved = config.resolve_alias(metric_type);
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
     let dir: Direction = direction.parse()?;
     let tf: Timeframe = timeframe.parse()?;
@@ -37,10 +38,10 @@ pub fn run_set(
     Ok(())
 }
 
-pub fn run_status(metric_type: Option<&str>, human: bool) -> Result<()> {
+pub fn run_status(metric_type: Option<&str>, human: bool, color: &ColorConfig) -> Result<()> {
     let config = Config::load()?;
     let resolved = metric_type.map(|t| config.resolve_alias(t));
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
     let statuses = openvital::core::goal::goal_status(&db, resolved.as_deref())?;
 
@@ -49,22 +50,37 @@ pub fn run_status(metric_type: Option<&str>, human: bool) -> Result<()> {
             println!("No active goals.");
         } else {
             for s in &statuses {
-                let met = if s.is_met { "MET" } else { "..." };
+                let met = if s.is_met {
+                    color.green("MET")
+                } else {
+                    color.red("...")
+                };
                 let (display_target, display_unit) = openvital::core::units::to_display(
                     s.target_value,
                     &s.metric_type,
                     &config.units,
                 );
-                let progress = openvital::output::human::format_progress_human(s, &config.units);
+                let mut progress =
+                    openvital::output::human::format_progress_human(s, &config.units);
+                if let Some(pct) = s.percent_complete {
+                    progress.push_str(&format!(
+                        " {}",
+                        openvital::output::human::format_percent_bar(pct)
+                    ));
+                }
+                let track = openvital::output::human::format_goal_track(s)
+                    .map(|t| format!(" [{}]", t))
+                    .unwrap_or_default();
                 println!(
-                    "[{}] {} {} {:.1} {} ({}) — {}",
+                    "[{}] {} {} {:.1} {} ({}) — {}{}",
                     met,
                     s.metric_type,
                     s.direction,
                     display_target,
                     display_unit,
                     s.timeframe,
-                    progress
+                    progress,
+                    track
                 );
             }
         }
@@ -76,11 +92,12 @@ pub fn run_status(metric_type: Option<&str>, human: bool) -> Result<()> {
 }
 
 pub fn run_remove(goal_id: &str, human: bool) -> Result<()> {
-    let db = Database::open(&Config::db_path())?;
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
     let removed = openvital::core::goal::remove_goal(&db, goal_id)?;
 
     if !removed {
-        anyhow::bail!("goal not found or already inactive: {}", goal_id);
+        openvital::fail!(not_found, "goal not found or already inactive: {}", goal_id);
     }
 
     if human {
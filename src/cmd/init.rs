@@ -17,7 +17,8 @@ pub fn run(skip: bool, units_arg: Option<&str>) -> Result<()> {
         match system {
             "imperial" => config.units = Units::imperial(),
             "metric" => config.units = Units::default(),
-            other => anyhow::bail!(
+            other => openvital::fail!(
+                validation,
                 "Unknown unit system '{}'. Use 'metric' or 'imperial'.",
                 other
             ),
@@ -67,10 +68,24 @@ pub fn run(skip: bool, units_arg: Option<&str>) -> Result<()> {
 
         config.profile.primary_exercise = Some(prompt_string("Primary exercise type")?);
 
+        if prompt_yes_no("Encrypt the database at rest? [y/N]")? {
+            let source =
+                prompt_string("Key source (env:<VAR> or keychain) [env:OPENVITAL_DB_KEY]")?;
+            if !source.is_empty() {
+                config.encryption.key_source = source.parse()?;
+            }
+            let db_path = Config::db_path();
+            if db_path.exists() && !config.encryption.enabled {
+                let key = config.encryption.key_source.resolve()?;
+                openvital::core::backup::encrypt(&db_path, &key)?;
+            }
+            config.encryption.enabled = true;
+        }
+
         config.save()?;
 
         // Log initial weight (always stored in kg)
-        let db = Database::open(&Config::db_path())?;
+        let db = Database::open_with_config(&Config::db_path(), &config)?;
         let mut m = Metric::new("weight".into(), weight_kg);
         m.source = "init".to_string();
         db.insert_metric(&m)?;
@@ -95,6 +110,15 @@ fn prompt_string(label: &str) -> Result<String> {
     Ok(buf.trim().to_string())
 }
 
+/// Yes/no question; end of input counts as no.
+fn prompt_yes_no(label: &str) -> Result<bool> {
+    print!("{}: ", label);
+    io::stdout().flush()?;
+    let mut buf = String::new();
+    io::stdin().read_line(&mut buf)?;
+    Ok(buf.trim().eq_ignore_ascii_case("y"))
+}
+
 fn prompt_f64(label: &str) -> Result<f64> {
     loop {
         let s = prompt_string(label)?;
@@ -0,0 +1,21 @@
+use anyhow::Result;
+
+use openvital::core::insights;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run(days: u32, human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let result = insights::generate(&db, &config, days)?;
+
+    if human_flag {
+        println!("{}", human::format_insights(&result));
+    } else {
+        let out = output::success("insights", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
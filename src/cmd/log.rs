@@ -1,31 +1,76 @@
 use anyhow::Result;
 use chrono::NaiveDate;
 use serde_json::json;
+use std::io::{self, BufRead, IsTerminal, Write};
 
-use openvital::core::logging::LogEntry;
+use openvital::core::goal::GoalCompleted;
+use openvital::core::logging::{LogEntry, Source};
 use openvital::db::Database;
 use openvital::models::config::Config;
+use openvital::models::metric::{Category, Metric, is_builtin};
 use openvital::output;
 use openvital::output::human;
 
-pub fn run(
-    metric_type: &str,
-    value_str: &str,
-    note: Option<&str>,
-    tags: Option<&str>,
-    source: Option<&str>,
-    date: Option<NaiveDate>,
-    human_flag: bool,
-) -> Result<()> {
-    let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
-    let resolved_type = config.resolve_alias(metric_type);
+pub struct LogArgs<'a> {
+    pub metric_type: &'a str,
+    /// Literal value; exactly one of `value` and `expression` is set.
+    pub value: Option<&'a str>,
+    pub expression: Option<&'a str>,
+    pub note: Option<&'a str>,
+    pub tags: Option<&'a str>,
+    pub source: Option<&'a str>,
+    pub date: Option<NaiveDate>,
+    pub unit_override: Option<&'a str>,
+    /// Prompt for the definition of a type not seen before.
+    pub interactive: bool,
+    /// Log a likely typo as the closest known type (or `log.fuzzy_match`).
+    pub fuzzy: bool,
+    /// Show the entry and ask before saving it (terminal only).
+    pub confirm: bool,
+    /// Report a daily or weekly goal the entry completes.
+    pub goal_check: bool,
+}
+
+pub fn run(args: LogArgs<'_>, human_flag: bool) -> Result<()> {
+    let LogArgs {
+        metric_type,
+        value,
+        expression,
+        note,
+        tags,
+        source,
+        date,
+        unit_override,
+        interactive,
+        fuzzy,
+        confirm,
+        goal_check,
+    } = args;
+    let confirm = confirm && io::stdin().is_terminal();
+    let mut config = Config::load()?;
+    config.log.fuzzy_match |= fuzzy;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let source = Source::resolve(source, &config)?;
+    let resolved_type = openvital::core::logging::resolve_type(&db, &config, metric_type)?;
+    if human_flag && resolved_type != config.resolve_alias(metric_type) {
+        eprintln!("Note: logging '{}' as '{}'", metric_type, resolved_type);
+    }
+    let value_str = value.unwrap_or_default();
 
     // Check for blood pressure compound value (e.g., "120/80")
     if (resolved_type == "blood_pressure" || resolved_type == "bp") && value_str.contains('/') {
-        let (m1, m2) = openvital::core::logging::log_blood_pressure(
-            &db, &config, value_str, note, tags, source, date,
+        if unit_override.is_some() {
+            openvital::fail!(validation, "--unit is not supported for blood pressure");
+        }
+        let (m1, m2) = openvital::core::logging::prepare_blood_pressure(
+            &db, &config, value_str, note, tags, &source, date,
         )?;
+        let mut entries = [m1, m2];
+        if !save(&db, &mut entries, confirm)? {
+            return print_cancelled(human_flag);
+        }
+        let completed = completed_goals(&db, &entries, goal_check)?;
+        let [m1, m2] = entries;
 
         if human_flag {
             println!(
@@ -36,28 +81,71 @@ pub fn run(
                 "Logged: {}",
                 human::format_metric_with_units(&m2, &config.units)
             );
+            print_completed(&completed, &config);
         } else {
-            let out = output::success(
-                "log",
-                json!({
-                    "entries": [
-                        {"id": m1.id, "type": m1.metric_type, "value": m1.value, "unit": m1.unit},
-                        {"id": m2.id, "type": m2.metric_type, "value": m2.value, "unit": m2.unit}
-                    ]
-                }),
-            );
+            let mut data = json!({
+                "entries": [
+                    {"id": m1.id, "type": m1.metric_type, "value": m1.value, "unit": m1.unit},
+                    {"id": m2.id, "type": m2.metric_type, "value": m2.value, "unit": m2.unit}
+                ]
+            });
+            if !completed.is_empty() {
+                data["goals_completed"] = serde_json::to_value(&completed)?;
+            }
+            let out = output::success("log", data);
             println!("{}", serde_json::to_string(&out)?);
         }
         return Ok(());
     }
 
+    // A first-time type: ask how to describe it, when someone is at the terminal
+    let mut unit_override = unit_override.map(String::from);
+    if interactive
+        && io::stdin().is_terminal()
+        && !is_builtin(&resolved_type)
+        && db.get_custom_type(&resolved_type)?.is_none()
+    {
+        let answers =
+            prompt_new_metric_type(&mut io::stdin().lock(), &mut io::stderr(), &resolved_type)?;
+        if answers.save {
+            super::types::run_add(
+                &resolved_type,
+                &answers.unit,
+                &answers.category.to_string(),
+                human_flag,
+            )?;
+        } else if unit_override.is_none() && !answers.unit.is_empty() {
+            unit_override = Some(answers.unit);
+        }
+    }
+    let unit_override = unit_override.as_deref();
+
     // Normal single-value log
-    let parsed: f64 = value_str
-        .parse()
-        .map_err(|_| anyhow::anyhow!("invalid value: {}", value_str))?;
-    // Convert from user units (e.g., imperial) to metric for storage
-    let value = openvital::core::units::from_input(parsed, &resolved_type, &config.units);
-    let m = openvital::core::logging::log_metric(
+    let parsed: f64 = match expression {
+        Some(expr) => openvital::core::expr::eval(expr)?,
+        None => value_str
+            .parse()
+            .map_err(|_| openvital::error::parse(format!("invalid value: {}", value_str)))?,
+    };
+    // `+0.5` / `-0.5` adjust the latest entry (or add to a daily total)
+    let delta = (expression.is_none() && openvital::core::logging::is_relative(value_str))
+        .then_some(parsed);
+    // Convert from user units (e.g., imperial) to metric for storage, unless
+    // the value is given in an explicit unit
+    let value = if let Some(d) = delta {
+        openvital::core::logging::resolve_delta(
+            &db,
+            &config,
+            &resolved_type,
+            d,
+            unit_override.is_some(),
+        )?
+    } else if unit_override.is_some() {
+        parsed
+    } else {
+        openvital::core::units::from_input(parsed, &resolved_type, &config.units)
+    };
+    let m = openvital::core::logging::prepare_metric(
         &db,
         &config,
         LogEntry {
@@ -67,35 +155,164 @@ pub fn run(
             tags,
             source,
             date,
+            unit: unit_override,
         },
     )?;
+    let mut entries = [m];
+    if !save(&db, &mut entries, confirm)? {
+        return print_cancelled(human_flag);
+    }
+    let completed = completed_goals(&db, &entries, goal_check)?;
+    let [m] = entries;
 
     if human_flag {
-        println!(
-            "Logged: {}",
-            human::format_metric_with_units(&m, &config.units)
-        );
+        let default = openvital::core::types::default_unit(&db, &resolved_type)?;
+        if unit_override.is_some() && m.unit != default {
+            eprintln!("Note: logging in '{}' (default is '{}')", m.unit, default);
+        }
+        let line = human::format_metric_with_units(&m, &config.units);
+        match delta {
+            Some(d) => println!("Logged: {} ({:+})", line, d),
+            None => println!("Logged: {}", line),
+        }
+        print_completed(&completed, &config);
     } else {
-        let out = output::success(
-            "log",
-            json!({
-                "entry": {
-                    "id": m.id,
-                    "timestamp": m.timestamp.to_rfc3339(),
-                    "type": m.metric_type,
-                    "value": m.value,
-                    "unit": m.unit
-                }
-            }),
-        );
+        let mut entry = json!({
+            "id": m.id,
+            "timestamp": m.timestamp.to_rfc3339(),
+            "type": m.metric_type,
+            "value": m.value,
+            "unit": m.unit
+        });
+        if let Some(d) = delta {
+            entry["delta"] = json!(d);
+        }
+        let mut data = json!({ "entry": entry });
+        if let Some(g) = completed.first() {
+            data["goal_completed"] = serde_json::to_value(g)?;
+        }
+        let out = output::success("log", data);
         println!("{}", serde_json::to_string(&out)?);
     }
     Ok(())
 }
 
-pub fn run_batch(batch_input: &str, human_flag: bool) -> Result<()> {
+/// Store `entries`, first asking on the terminal when `confirm` is set.
+/// Returns false if the user declined.
+fn save(db: &Database, entries: &mut [Metric], confirm: bool) -> Result<bool> {
+    if confirm && !confirm_save(&mut io::stdin().lock(), &mut io::stderr(), entries)? {
+        return Ok(false);
+    }
+    openvital::core::logging::insert_batch(db, entries, |_, _| {})?;
+    Ok(true)
+}
+
+/// Show the entries about to be logged and ask whether to save them. Only
+/// `y` or `yes` (any case) saves; end of input declines.
+pub fn confirm_save(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    entries: &[Metric],
+) -> Result<bool> {
+    for m in entries {
+        writeln!(output, "{}", human::format_metric(m))?;
+    }
+    let answer = ask(input, output, "Save this entry? [y/N]")?;
+    Ok(answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"))
+}
+
+/// Goals the just-stored `entries` completed, unless the check is turned off.
+fn completed_goals(
+    db: &Database,
+    entries: &[Metric],
+    goal_check: bool,
+) -> Result<Vec<GoalCompleted>> {
+    if !goal_check {
+        return Ok(Vec::new());
+    }
+    let today = openvital::core::time::local_today(db.timezone().name());
+    openvital::core::goal::completed_by(db, entries, today)
+}
+
+fn print_completed(completed: &[GoalCompleted], config: &Config) {
+    for g in completed {
+        println!("{}", human::format_goal_completed(g, &config.units));
+    }
+}
+
+fn print_cancelled(human_flag: bool) -> Result<()> {
+    if human_flag {
+        println!("Cancelled");
+    } else {
+        let out = output::success("log", json!({ "cancelled": true }));
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+/// Answers to the prompts for a metric type logged for the first time.
+pub struct NewTypeAnswers {
+    pub unit: String,
+    pub category: Category,
+    pub save: bool,
+}
+
+/// Ask for the unit and category of `metric_type` and whether to save them as
+/// a custom type. End of input takes the defaults: no unit, custom, not saved.
+pub fn prompt_new_metric_type(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    metric_type: &str,
+) -> Result<NewTypeAnswers> {
+    let unit = ask(
+        input,
+        output,
+        &format!("Unit for '{}' (leave blank for none):", metric_type),
+    )?;
+    let category = loop {
+        let answer = ask(
+            input,
+            output,
+            "Category (body/exercise/sleep/nutrition/pain/habit/custom):",
+        )?;
+        if answer.is_empty() {
+            break Category::Custom;
+        }
+        match answer.parse::<Category>() {
+            Ok(c) => break c,
+            Err(e) => writeln!(output, "{}", e)?,
+        }
+    };
+    let save = ask(input, output, "Save this definition for future logs? [y/N]")?;
+    Ok(NewTypeAnswers {
+        unit,
+        category,
+        save: save.eq_ignore_ascii_case("y"),
+    })
+}
+
+fn ask(input: &mut impl BufRead, output: &mut impl Write, label: &str) -> Result<String> {
+    write!(output, "{} ", label)?;
+    output.flush()?;
+    let mut buf = String::new();
+    input.read_line(&mut buf)?;
+    Ok(buf.trim().to_string())
+}
+
+/// Batches longer than this report a count and only the first entries.
+const ENTRIES_SHOWN: usize = 100;
+
+pub fn run_batch(batch_input: &str, goal_check: bool, human_flag: bool) -> Result<()> {
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let stdin;
+    let batch_input = if batch_input == "-" {
+        stdin = super::read_input("-")?;
+        stdin.as_str()
+    } else {
+        batch_input
+    };
 
     // Detect format: JSON array starts with '[', otherwise simple format
     let batch_json = if batch_input.trim_start().starts_with('[') {
@@ -104,18 +321,59 @@ pub fn run_batch(batch_input: &str, human_flag: bool) -> Result<()> {
         openvital::core::logging::parse_simple_batch(batch_input)?
     };
 
-    let metrics = openvital::core::logging::log_batch(&db, &config, &batch_json)?;
+    let metrics = openvital::core::logging::parse_batch_json(&config, &batch_json)?;
+    log_metrics(&db, &config, metrics, goal_check, human_flag)
+}
+
+/// Log entries from CSV in the export schema (`timestamp` optional).
+pub fn run_batch_csv(csv: &str, goal_check: bool, human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let metrics = openvital::core::logging::parse_batch_csv(&config, csv)?;
+    log_metrics(&db, &config, metrics, goal_check, human_flag)
+}
+
+/// Log entries from a file (`-` for stdin): a JSON array if it starts with `[`, CSV otherwise.
+pub fn run_file(path: &str, goal_check: bool, human_flag: bool) -> Result<()> {
+    let content = super::read_input(path)?;
+    if content.trim_start().starts_with('[') {
+        run_batch(&content, goal_check, human_flag)
+    } else {
+        run_batch_csv(&content, goal_check, human_flag)
+    }
+}
+
+fn log_metrics(
+    db: &Database,
+    config: &Config,
+    mut metrics: Vec<Metric>,
+    goal_check: bool,
+    human_flag: bool,
+) -> Result<()> {
+    let chunked = metrics.len() > openvital::core::logging::LARGE_BATCH;
+    openvital::core::logging::insert_batch(db, &mut metrics, |logged, total| {
+        if human_flag && chunked {
+            println!("Logged {} of {} entries...", logged, total);
+        }
+    })?;
+    let completed = completed_goals(db, &metrics, goal_check)?;
 
     if human_flag {
-        for m in &metrics {
-            println!(
-                "Logged: {}",
-                human::format_metric_with_units(m, &config.units)
-            );
+        if metrics.len() > ENTRIES_SHOWN {
+            println!("Logged {} entries", metrics.len());
+        } else {
+            for m in &metrics {
+                println!(
+                    "Logged: {}",
+                    human::format_metric_with_units(m, &config.units)
+                );
+            }
         }
+        print_completed(&completed, config);
     } else {
         let entries: Vec<_> = metrics
             .iter()
+            .take(ENTRIES_SHOWN)
             .map(|m| {
                 json!({
                     "id": m.id,
@@ -125,8 +383,75 @@ pub fn run_batch(batch_input: &str, human_flag: bool) -> Result<()> {
                 })
             })
             .collect();
-        let out = output::success("log", json!({ "entries": entries }));
+        let mut data = json!({ "entries": entries, "count": metrics.len() });
+        if metrics.len() > ENTRIES_SHOWN {
+            data["truncated"] = json!(true);
+        }
+        if !completed.is_empty() {
+            data["goals_completed"] = serde_json::to_value(&completed)?;
+        }
+        let out = output::success("log", data);
         println!("{}", serde_json::to_string(&out)?);
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(piped: &str) -> (NewTypeAnswers, String) {
+        let mut out = Vec::new();
+        let answers = prompt_new_metric_type(&mut piped.as_bytes(), &mut out, "mymood").unwrap();
+        (answers, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_prompt_reads_piped_answers() {
+        let (a, out) = answer("1-10\nhabit\ny\n");
+        assert_eq!(a.unit, "1-10");
+        assert_eq!(a.category, Category::Habit);
+        assert!(a.save);
+        assert!(out.starts_with("Unit for 'mymood' (leave blank for none):"));
+        assert!(out.contains("Save this definition for future logs? [y/N]"));
+    }
+
+    #[test]
+    fn test_prompt_retries_bad_category_and_defaults_on_eof() {
+        let (a, out) = answer("\nmood\nsleep\n");
+        assert_eq!(a.unit, "");
+        assert_eq!(a.category, Category::Sleep);
+        assert!(!a.save);
+        assert!(out.contains("invalid category"));
+
+        let (a, _) = answer("");
+        assert_eq!(a.category, Category::Custom);
+        assert!(!a.save);
+    }
+
+    fn confirm(piped: &str) -> (bool, String) {
+        let mut m = Metric::new("weight".to_string(), 80.5);
+        m.unit = "kg".to_string();
+        m.note = Some("after run".to_string());
+        let mut out = Vec::new();
+        let saved = confirm_save(&mut piped.as_bytes(), &mut out, &[m]).unwrap();
+        (saved, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_confirm_shows_entry_and_accepts_yes() {
+        let (saved, out) = confirm("y\n");
+        assert!(saved);
+        assert!(out.contains("weight = 80.5 kg  # after run"));
+        assert!(out.ends_with("Save this entry? [y/N] "));
+        assert!(confirm("YES\n").0);
+    }
+
+    #[test]
+    fn test_confirm_declines_by_default() {
+        assert!(!confirm("n\n").0);
+        assert!(!confirm("\n").0);
+        assert!(!confirm("").0);
+        assert!(!confirm("yep\n").0);
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::json;
+
+use openvital::core::logging;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run(
+    pairs: &[String],
+    note: Option<&str>,
+    tags: Option<&str>,
+    date: Option<NaiveDate>,
+    human_flag: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let metrics = logging::log_measurements(&db, &config, pairs, note, tags, date)?;
+
+    if human_flag {
+        for m in &metrics {
+            println!(
+                "Logged: {}",
+                human::format_metric_with_units(m, &config.units)
+            );
+        }
+    } else {
+        let entries: Vec<_> = metrics
+            .iter()
+            .map(|m| {
+                json!({
+                    "id": m.id,
+                    "type": m.metric_type,
+                    "value": m.value,
+                    "unit": m.unit
+                })
+            })
+            .collect();
+        let out = output::success(
+            "measure",
+            json!({
+                "entries": entries,
+                "count": metrics.len(),
+                "timestamp": metrics[0].timestamp.to_rfc3339(),
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
@@ -2,30 +2,27 @@ use anyhow::Result;
 use chrono::NaiveDate;
 use serde_json::json;
 
+use openvital::core::med::{
+    AddMedicationParams, EditMedicationParams, MedStatus, MedSummary, PrnSummary,
+    TakeMedicationParams,
+};
 use openvital::db::Database;
 use openvital::models::config::Config;
+use openvital::models::med::Frequency;
 use openvital::output;
+use openvital::output::color::ColorConfig;
 
-pub fn run_add(
-    name: &str,
-    dose: Option<&str>,
-    freq: &str,
-    route: &str,
-    note: Option<&str>,
-    started: Option<NaiveDate>,
-    human: bool,
-) -> Result<()> {
+/// Window for the as-needed section of `med status`.
+const PRN_SUMMARY_DAYS: u32 = 7;
+
+pub fn run_add(params: AddMedicationParams<'_>, human: bool) -> Result<()> {
     let config = Config::load()?;
-    let resolved = config.resolve_alias(name);
-    let db = Database::open(&Config::db_path())?;
+    let resolved = config.resolve_alias(params.name);
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
-    let params = openvital::core::med::AddMedicationParams {
+    let params = AddMedicationParams {
         name: &resolved,
-        dose,
-        freq,
-        route: Some(route),
-        note,
-        started,
+        ..params
     };
     let medication = openvital::core::med::add_medication(&db, &config, params)?;
 
@@ -45,37 +42,39 @@ pub fn run_add(
             medication.started_at.format("%b %d"),
             note_str,
         );
+        if !medication.interactions.is_empty() {
+            println!(
+                "  Interacts with {}",
+                openvital::output::human::format_interactions(&medication.interactions)
+            );
+        }
     } else {
-        let out = output::success(
-            "med_add",
-            json!({
-                "id": medication.id,
-                "name": medication.name,
-                "dose": medication.dose,
-                "route": medication.route,
-                "frequency": medication.frequency,
-                "active": medication.active,
-                "started_at": medication.started_at.to_rfc3339(),
-            }),
-        );
+        let mut data = json!({
+            "id": medication.id,
+            "name": medication.name,
+            "dose": medication.dose,
+            "route": medication.route,
+            "frequency": medication.frequency,
+            "active": medication.active,
+            "started_at": medication.started_at.to_rfc3339(),
+        });
+        if !medication.interactions.is_empty() {
+            data["interactions"] = json!(medication.interactions);
+        }
+        let out = output::success("med_add", data);
         println!("{}", serde_json::to_string(&out)?);
     }
     Ok(())
 }
 
-pub fn run_take(
-    name: &str,
-    dose: Option<&str>,
-    note: Option<&str>,
-    tags: Option<&str>,
-    date: Option<NaiveDate>,
-    human: bool,
-) -> Result<()> {
+pub fn run_take(params: TakeMedicationParams<'_>, human: bool, color: &ColorConfig) -> Result<()> {
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
-    let (metric, medication) =
-        openvital::core::med::take_medication(&db, &config, name, dose, note, tags, date)?;
+    let dose = params.dose;
+    let quantity = params.quantity;
+    let (metric, medication) = openvital::core::med::take_medication_with(&db, &config, params)?;
+    let warnings = openvital::core::med::interaction_warnings(&db, &metric)?;
 
     let is_stopped = !medication.active;
 
@@ -86,10 +85,13 @@ pub fn run_take(
                 medication.name
             );
         }
-        let dose_str = dose
+        let mut dose_str = dose
             .map(String::from)
             .or(medication.dose.clone())
             .unwrap_or_else(|| "1 dose".to_string());
+        if quantity > 1 {
+            dose_str = format!("{} × {}", quantity, dose_str);
+        }
         let ts = metric.timestamp.format("%b %d, %Y %H:%M");
         println!(
             "{}",
@@ -100,20 +102,29 @@ pub fn run_take(
                 &ts.to_string(),
             )
         );
+        for w in &warnings {
+            println!("{}", color.bold(&color.red(&format!("! {}", w.message))));
+        }
     } else {
+        let entry = json!({
+            "id": metric.id,
+            "timestamp": metric.timestamp.to_rfc3339(),
+            "type": metric.metric_type,
+            "value": metric.value,
+            "unit": metric.unit,
+            "note": metric.note,
+        });
         let mut data = json!({
             "medication": medication.name,
             "dose": dose.map(String::from).or(medication.dose),
             "route": medication.route,
-            "entry": {
-                "id": metric.id,
-                "timestamp": metric.timestamp.to_rfc3339(),
-                "type": metric.metric_type,
-                "value": metric.value,
-                "unit": metric.unit,
-                "note": metric.note,
-            }
         });
+        if quantity > 1 {
+            data["quantity"] = json!(quantity);
+            data["entries"] = json!([entry]);
+        } else {
+            data["entry"] = entry;
+        }
         if is_stopped {
             data["warning"] = json!(format!(
                 "Medication '{}' is stopped. Recording anyway.",
@@ -124,19 +135,26 @@ pub fn run_take(
                 medication.name
             );
         }
+        if !warnings.is_empty() {
+            data["warnings"] = json!(warnings);
+        }
         let out = output::success("med_take", data);
         println!("{}", serde_json::to_string(&out)?);
     }
     Ok(())
 }
 
-pub fn run_list(all: bool, human: bool) -> Result<()> {
-    let db = Database::open(&Config::db_path())?;
+pub fn run_list(all: bool, human: bool, color: &ColorConfig) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
     let meds = openvital::core::med::list_medications(&db, all)?;
 
     if human {
-        println!("{}", openvital::output::human::format_med_list(&meds, all));
+        println!(
+            "{}",
+            openvital::output::human::format_med_list(&meds, all, color)
+        );
     } else {
         let count = meds.len();
         let out = output::success(
@@ -151,6 +169,26 @@ pub fn run_list(all: bool, human: bool) -> Result<()> {
     Ok(())
 }
 
+pub fn run_edit(params: EditMedicationParams<'_>, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let edit = openvital::core::med::edit_medication(&db, &config, params)?;
+
+    if human {
+        println!("{}", openvital::output::human::format_med_edit(&edit));
+    } else {
+        let mut data = json!(edit.medication);
+        if let Some(n) = edit.renamed_entries {
+            data["renamed_from"] = json!(edit.before.name);
+            data["renamed_entries"] = json!(n);
+        }
+        let out = output::success("med_edit", data);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
 pub fn run_stop(
     name: &str,
     reason: Option<&str>,
@@ -159,12 +197,16 @@ pub fn run_stop(
 ) -> Result<()> {
     let config = Config::load()?;
     let resolved = config.resolve_alias(name);
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
     let stopped = openvital::core::med::stop_medication(&db, &resolved, reason, date)?;
 
     if !stopped {
-        anyhow::bail!("Medication '{}' not found or already stopped.", resolved);
+        openvital::fail!(
+            not_found,
+            "Medication '{}' not found or already stopped.",
+            resolved
+        );
     }
 
     if human {
@@ -189,7 +231,7 @@ pub fn run_stop(
 pub fn run_remove(name: &str, yes: bool, human: bool) -> Result<()> {
     let config = Config::load()?;
     let resolved = config.resolve_alias(name);
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
     if !yes {
         eprint!(
@@ -208,7 +250,7 @@ pub fn run_remove(name: &str, yes: bool, human: bool) -> Result<()> {
     let removed = openvital::core::med::remove_medication(&db, &resolved)?;
 
     if !removed {
-        anyhow::bail!("Medication '{}' not found.", resolved);
+        openvital::fail!(not_found, "Medication '{}' not found.", resolved);
     }
 
     if human {
@@ -226,36 +268,142 @@ pub fn run_remove(name: &str, yes: bool, human: bool) -> Result<()> {
     Ok(())
 }
 
-pub fn run_status(name: Option<&str>, last: u32, human: bool) -> Result<()> {
+pub fn run_skip(
+    name: &str,
+    reason: Option<&str>,
+    date: Option<NaiveDate>,
+    human: bool,
+) -> Result<()> {
     let config = Config::load()?;
-    let resolved = name.map(|n| config.resolve_alias(n));
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
-    let statuses = openvital::core::med::adherence_status(&db, resolved.as_deref(), last)?;
+    let (metric, medication) =
+        openvital::core::med::skip_medication(&db, &config, name, reason, date)?;
 
     if human {
-        let today = chrono::Utc::now().date_naive();
+        let ts = metric.timestamp.format("%b %d, %Y %H:%M");
         println!(
             "{}",
-            openvital::output::human::format_med_status(&statuses, today)
+            openvital::output::human::format_med_skip(
+                &medication.name,
+                metric.note.as_deref(),
+                &ts.to_string(),
+            )
         );
+    } else {
+        let out = output::success(
+            "med_skip",
+            json!({
+                "medication": medication.name,
+                "skipped": true,
+                "reason": metric.note,
+                "entry": {
+                    "id": metric.id,
+                    "timestamp": metric.timestamp.to_rfc3339(),
+                    "type": metric.metric_type,
+                    "value": metric.value,
+                    "unit": metric.unit,
+                    "note": metric.note,
+                    "source": metric.source,
+                },
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_refill(
+    name: &str,
+    quantity: u32,
+    days_supply: u32,
+    date: Option<NaiveDate>,
+    human: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let (refill, medication) =
+        openvital::core::med::refill_medication(&db, &config, name, quantity, days_supply, date)?;
+
+    if human {
+        println!(
+            "Refilled {}: {} doses, {} days supply",
+            medication.name, refill.quantity, refill.days_supply
+        );
+    } else {
+        let out = output::success(
+            "med_refill",
+            json!({
+                "name": medication.name,
+                "quantity": refill.quantity,
+                "days_supply": refill.days_supply,
+                "refill_date": refill.refill_date.to_rfc3339(),
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_status(name: Option<&str>, last: u32, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let resolved = name.map(|n| config.resolve_alias(n));
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let statuses = openvital::core::med::adherence_status(&db, &config, resolved.as_deref(), last)?;
+    // As-needed medications have no schedule; report their recent use instead.
+    let is_prn = |s: &MedStatus| s.frequency == Frequency::AsNeeded.to_string();
+    let prn = statuses
+        .iter()
+        .filter(|s| is_prn(s))
+        .map(|s| openvital::core::med::prn_summary(&db, &s.name, PRN_SUMMARY_DAYS))
+        .collect::<Result<Vec<PrnSummary>>>()?;
+
+    let today = openvital::core::time::local_today(&config.timezone());
+    if human {
+        let mut sections = Vec::new();
+        if name.is_some() {
+            sections.push(openvital::output::human::format_med_status(
+                &statuses, today,
+            ));
+        } else {
+            let scheduled: Vec<MedStatus> = statuses.into_iter().filter(|s| !is_prn(s)).collect();
+            if !scheduled.is_empty() || prn.is_empty() {
+                sections.push(openvital::output::human::format_med_summary(
+                    &scheduled, today,
+                ));
+            }
+        }
+        if !prn.is_empty() {
+            sections.push(openvital::output::human::format_prn_summary(&prn));
+        }
+        println!("{}", sections.join("\n\n"));
     } else {
         let data = if name.is_some() && statuses.len() == 1 {
-            // Single medication: output directly
-            json!(statuses.into_iter().next().unwrap())
+            // Single medication: output directly, with its PRN summary if as-needed
+            let mut data = json!(statuses.into_iter().next().unwrap());
+            if let Some(p) = prn.into_iter().next() {
+                data["prn"] = json!(p);
+            }
+            data
         } else {
-            // All medications: wrap with date and overall adherence
-            let today = chrono::Utc::now().date_naive();
+            // All medications: compact rows with date and overall adherence,
+            // as-needed medications in their own section
+            let scheduled: Vec<&MedStatus> = statuses.iter().filter(|s| !is_prn(s)).collect();
             let adherence_values: Vec<f64> =
-                statuses.iter().filter_map(|s| s.adherence_7d).collect();
+                scheduled.iter().filter_map(|s| s.adherence_7d).collect();
             let overall = if adherence_values.is_empty() {
                 None
             } else {
                 Some(adherence_values.iter().sum::<f64>() / adherence_values.len() as f64)
             };
+            let summaries: Vec<MedSummary> = scheduled.into_iter().map(MedSummary::from).collect();
             json!({
                 "date": today.format("%Y-%m-%d").to_string(),
-                "medications": statuses,
+                "summary_mode": true,
+                "medications": summaries,
+                "prn": prn,
                 "overall_adherence_7d": overall,
             })
         };
@@ -264,3 +412,52 @@ pub fn run_status(name: Option<&str>, last: u32, human: bool) -> Result<()> {
     }
     Ok(())
 }
+
+pub fn run_history(name: &str, last: u32, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let resolved = config.resolve_alias(name);
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let history = openvital::core::med::dose_history(&db, &resolved, last)?;
+
+    if human {
+        println!(
+            "{}",
+            openvital::output::human::format_med_history(&resolved, &history, last)
+        );
+    } else {
+        let out = output::success(
+            "med_history",
+            json!({
+                "name": resolved,
+                "last_days": last,
+                "history": history,
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_schedule(human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let today = openvital::core::time::local_today(&config.timezone());
+    let slots = openvital::core::med::schedule(&db)?;
+
+    if human {
+        println!(
+            "{}",
+            openvital::output::human::format_med_schedule(&slots, today)
+        );
+    } else {
+        let out = output::success(
+            "med_schedule",
+            json!({
+                "date": today.format("%Y-%m-%d").to_string(),
+                "schedule": slots,
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
@@ -1,12 +1,57 @@
 pub mod anomaly;
+pub mod backup;
+pub mod completions;
 pub mod config;
 pub mod context;
+pub mod cycle;
+pub mod db;
 pub mod export;
 pub mod goal;
 pub mod init;
+pub mod insights;
 pub mod log;
+pub mod measure;
 pub mod med;
+pub mod note;
+pub mod profile;
+pub mod remind;
 pub mod report;
+pub mod search;
 pub mod show;
+pub mod sleep;
+pub mod stats;
 pub mod status;
 pub mod trend;
+pub mod types;
+pub mod weight_history;
+pub mod workout;
+
+/// Read a command's input argument: `-` reads stdin to EOF, anything else is a file path.
+pub fn read_input(path: &str) -> anyhow::Result<String> {
+    use std::io::Read;
+
+    if path == "-" {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| openvital::error::io(&e, format!("cannot read stdin: {}", e)))?;
+        return Ok(content);
+    }
+    std::fs::read_to_string(path)
+        .map_err(|e| openvital::error::io(&e, format!("cannot read {}: {}", path, e)))
+}
+
+/// [`read_input`] as raw bytes, for input that may be compressed.
+pub fn read_input_bytes(path: &str) -> anyhow::Result<Vec<u8>> {
+    use std::io::Read;
+
+    if path == "-" {
+        let mut content = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut content)
+            .map_err(|e| openvital::error::io(&e, format!("cannot read stdin: {}", e)))?;
+        return Ok(content);
+    }
+    std::fs::read(path)
+        .map_err(|e| openvital::error::io(&e, format!("cannot read {}: {}", path, e)))
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::json;
+
+use openvital::core::journal;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run_add(
+    text: &str,
+    tags: Option<&str>,
+    date: Option<NaiveDate>,
+    human_flag: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let entry = journal::add(&db, &config, text, tags, date)?;
+
+    if human_flag {
+        println!("Noted: {}", human::format_note(&entry));
+    } else {
+        let out = output::success(
+            "note_add",
+            json!({
+                "entry": {
+                    "id": entry.id,
+                    "timestamp": entry.timestamp,
+                    "note": entry.note,
+                    "tags": entry.tags,
+                }
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_show(
+    last: Option<usize>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+    human_flag: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let entries = journal::list(&db, last, from, to, tags)?;
+
+    if human_flag {
+        if entries.is_empty() {
+            println!("No notes");
+        }
+        for entry in &entries {
+            println!("{}", human::format_note(entry));
+        }
+    } else {
+        let notes: Vec<_> = entries
+            .iter()
+            .map(|e| {
+                json!({
+                    "id": e.id,
+                    "timestamp": e.timestamp,
+                    "note": e.note,
+                    "tags": e.tags,
+                })
+            })
+            .collect();
+        let out = output::success("note_show", json!({ "entries": notes }));
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
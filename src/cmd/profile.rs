@@ -0,0 +1,89 @@
+use anyhow::Result;
+use serde_json::json;
+
+use openvital::core::profile;
+use openvital::output;
+
+pub fn run_list(human: bool) -> Result<()> {
+    let list = profile::list()?;
+
+    if human {
+        for p in &list.profiles {
+            let mut marks = Vec::new();
+            if p.is_default {
+                marks.push("default");
+            }
+            if p.is_active {
+                marks.push("active");
+            }
+            if marks.is_empty() {
+                println!("  {}", p.name);
+            } else {
+                println!("  {} ({})", p.name, marks.join(", "));
+            }
+        }
+    } else {
+        let out = output::success("profile", serde_json::to_value(&list)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_create(name: &str, make_default: bool, human: bool) -> Result<()> {
+    let dir = profile::create(name)?;
+    if make_default {
+        profile::set_default(name)?;
+    }
+
+    if human {
+        println!("Profile created: {} ({})", name, dir.display());
+        if make_default {
+            println!("Default profile: {}", name);
+        }
+    } else {
+        let out = output::success(
+            "profile",
+            json!({
+                "created": name,
+                "path": dir.display().to_string(),
+                "default": make_default,
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_default(name: &str, human: bool) -> Result<()> {
+    profile::set_default(name)?;
+
+    if human {
+        println!("Default profile: {}", name);
+    } else {
+        let out = output::success("profile", json!({ "default": name }));
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_remove(name: &str, yes: bool, human: bool) -> Result<()> {
+    if !yes {
+        openvital::fail!(
+            validation,
+            "removing profile '{}' deletes its config, database and backups; re-run with --yes to confirm",
+            name
+        );
+    }
+    let dir = profile::remove(name)?;
+
+    if human {
+        println!("Profile removed: {}", name);
+    } else {
+        let out = output::success(
+            "profile",
+            json!({ "removed": name, "path": dir.display().to_string() }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
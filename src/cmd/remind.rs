@@ -0,0 +1,43 @@
+use anyhow::Result;
+
+use openvital::core::remind::{self, ReminderKind};
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+
+/// Exit code when something is still missing today; kept apart from the
+/// error exit codes so scripts can tell "nag" from "failed".
+pub const EXIT_MISSING: i32 = 10;
+
+pub fn run(quiet: bool, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let reminders = remind::check(&db, &config)?;
+
+    if quiet {
+        // Exit status only
+    } else if human {
+        if reminders.is_empty() {
+            println!("All caught up for today");
+        }
+        for r in &reminders {
+            match r.kind {
+                ReminderKind::Metric => println!("Not logged today: {}", r.item),
+                ReminderKind::Medication => println!(
+                    "Medication due: {} ({} dose{} left)",
+                    r.item,
+                    r.missing,
+                    if r.missing == 1 { "" } else { "s" }
+                ),
+            }
+        }
+    } else {
+        let out = output::success("remind", serde_json::to_value(&reminders)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+
+    if !reminders.is_empty() {
+        std::process::exit(EXIT_MISSING);
+    }
+    Ok(())
+}
@@ -1,47 +1,233 @@
 use anyhow::Result;
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{Datelike, NaiveDate};
 
 use openvital::core::report;
 use openvital::db::Database;
 use openvital::models::config::Config;
 use openvital::output;
 
-pub fn run(
-    period: Option<&str>,
-    month: Option<&str>,
-    from: Option<NaiveDate>,
-    to: Option<NaiveDate>,
-    human: bool,
-) -> Result<()> {
+pub struct ReportArgs<'a> {
+    pub period: Option<&'a str>,
+    pub month: Option<&'a str>,
+    pub year: Option<u16>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub agg: Option<&'a str>,
+    pub compare: bool,
+    pub goals: bool,
+    pub format: &'a str,
+    pub output: Option<&'a str>,
+    pub tags: &'a [String],
+    pub digest: bool,
+}
+
+pub fn run(args: ReportArgs<'_>, human: bool) -> Result<()> {
+    let ReportArgs {
+        period,
+        month,
+        year,
+        from,
+        to,
+        agg,
+        compare,
+        goals,
+        format,
+        output: output_path,
+        tags,
+        digest,
+    } = args;
+    match (format, output_path) {
+        ("json" | "html" | "markdown", None) | ("html" | "markdown", Some(_)) => {}
+        ("json", Some(_)) => {
+            openvital::fail!(
+                validation,
+                "--output is only supported with --format html or markdown"
+            )
+        }
+        (other, _) => openvital::fail!(
+            validation,
+            "unsupported format: {} (expected json/html/markdown)",
+            other
+        ),
+    }
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let today = openvital::core::time::local_today(&config.timezone());
+    let week_start = config.week_start.weekday();
+    let (from_date, to_date) = resolve_range(period, month, from, to, year, today, week_start)?;
+    let calendar_month = period == Some("month") && !(from.is_some() && to.is_some());
+    if digest {
+        let label = (!(from.is_some() && to.is_some())).then(|| period.unwrap_or("week"));
+        let d = report::digest(&db, &config, from_date, to_date, calendar_month, tags)?;
+        print!(
+            "{}",
+            output::digest::format_digest(&d, label, &config.units)
+        );
+        return Ok(());
+    }
+    let agg = agg
+        .map(|a| a.parse::<openvital::models::metric::Aggregation>())
+        .transpose()?;
+    let mut result = if compare {
+        report::generate_compared(&db, &config, agg, from_date, to_date, calendar_month, tags)?
+    } else {
+        report::generate_with(&db, &config, agg, from_date, to_date, tags)?
+    };
+    if !(from.is_some() && to.is_some()) {
+        result.period = Some(period.unwrap_or("week").to_string());
+    }
+    // Markdown always carries the goal checklist, like the HTML page.
+    if goals || format == "markdown" {
+        report::attach_goals(&db, &mut result)?;
+    }
 
-    let (from_date, to_date) = resolve_range(period, month, from, to)?;
-    let result = report::generate(&db, from_date, to_date)?;
+    if format == "html" || format == "markdown" {
+        let rendered = if format == "html" {
+            report::to_html(&db, &config, &result)?
+        } else {
+            output::markdown::format_report(&result, &config.units)
+        };
+        if let Some(path) = output_path {
+            std::fs::write(path, &rendered)?;
+            if human {
+                println!("Report written to {}", path);
+            } else {
+                let out = output::success(
+                    "report",
+                    serde_json::json!({"path": path, "format": format}),
+                );
+                println!("{}", serde_json::to_string(&out)?);
+            }
+        } else {
+            print!("{}", rendered);
+        }
+        return Ok(());
+    }
 
     if human {
-        println!(
-            "=== OpenVital Report: {} to {} ===\n",
-            result.from, result.to
-        );
+        match result.period.as_deref() {
+            Some("quarter") => println!(
+                "=== OpenVital Report: Q{} {} ({} to {}) ===\n",
+                result.from.month0() / 3 + 1,
+                result.from.year(),
+                result.from,
+                result.to
+            ),
+            Some("ytd") => println!(
+                "=== OpenVital Report: {} YTD ({} to {}) ===\n",
+                result.from.year(),
+                result.from,
+                result.to
+            ),
+            _ => println!(
+                "=== OpenVital Report: {} to {} ===\n",
+                result.from, result.to
+            ),
+        }
         println!(
             "  Days with entries: {} | Total entries: {}",
             result.days_with_entries, result.total_entries
         );
+        if let Some(prev) = &result.compared_to {
+            println!("  Compared with: {} to {}", prev.from, prev.to);
+        }
         if result.metrics.is_empty() {
             println!("\n  No data in this period.");
         } else {
             println!();
             for s in &result.metrics {
+                if s.count == 0 {
+                    let prev = s.comparison.as_ref().and_then(|c| c.prev_avg);
+                    let (prev, unit) = openvital::core::units::to_display(
+                        prev.unwrap_or(0.0),
+                        &s.metric_type,
+                        &config.units,
+                    );
+                    println!(
+                        "  {:16} | not logged (prev avg: {:.1}) [{}]",
+                        s.metric_type, prev, unit
+                    );
+                    continue;
+                }
                 let (avg, _) =
                     openvital::core::units::to_display(s.avg, &s.metric_type, &config.units);
                 let (min, _) =
                     openvital::core::units::to_display(s.min, &s.metric_type, &config.units);
                 let (max, unit) =
                     openvital::core::units::to_display(s.max, &s.metric_type, &config.units);
+                let total = match s.aggregation {
+                    openvital::models::metric::Aggregation::Sum => {
+                        let (v, _) = openvital::core::units::to_display(
+                            s.value,
+                            &s.metric_type,
+                            &config.units,
+                        );
+                        format!(" total: {:8.1}", v)
+                    }
+                    _ => String::new(),
+                };
+                let marker = s
+                    .range_status
+                    .map(|r| format!(" {}", openvital::output::human::range_marker(r)))
+                    .unwrap_or_default();
                 println!(
-                    "  {:16} | avg: {:8.1} min: {:8.1} max: {:8.1} (n={}) [{}]",
-                    s.metric_type, avg, min, max, s.count, unit
+                    "  {:16} | avg: {:8.1}{} min: {:8.1} max: {:8.1}{} (n={}) [{}]",
+                    s.metric_type, avg, marker, min, max, total, s.count, unit
+                );
+            }
+        }
+        if !result.medications.is_empty() {
+            println!("\n  Medications:");
+            for m in &result.medications {
+                println!(
+                    "  {}",
+                    openvital::output::human::format_report_medication(m)
+                );
+            }
+        }
+        if !result.comparison.is_empty() {
+            println!("\n  Compared with prior period:");
+            for c in &result.comparison {
+                println!(
+                    "  {}",
+                    openvital::output::human::format_metric_comparison(c, &config.units)
+                );
+            }
+        }
+        if let Some(tir) = &result.glucose {
+            let (low, unit) =
+                openvital::core::units::to_display(tir.target_low, "glucose", &config.units);
+            let (high, _) =
+                openvital::core::units::to_display(tir.target_high, "glucose", &config.units);
+            println!("\n  Glucose time in range ({}-{} {}):", low, high, unit);
+            println!(
+                "  {}",
+                openvital::output::human::format_time_in_range(tir, &config.units)
+            );
+            for d in &tir.days {
+                println!(
+                    "  {} | {}",
+                    d.date,
+                    openvital::output::human::format_glucose_day(d, &config.units)
+                );
+            }
+        }
+        if !result.workouts.is_empty() {
+            println!("\n  Workouts:");
+            for w in &result.workouts {
+                println!("  {}", openvital::output::human::format_workout_week(w));
+            }
+        }
+        if let Some(goals) = &result.goals {
+            println!("\n  Goals ({}/{} met):", goals.goals_met, goals.goals_total);
+            if goals.goals.is_empty() {
+                println!("  No active goals");
+            }
+            for g in &goals.goals {
+                println!(
+                    "  {}",
+                    openvital::output::human::format_report_goal(g, &config.units)
                 );
             }
         }
@@ -58,42 +244,33 @@ fn resolve_range(
     month: Option<&str>,
     from: Option<NaiveDate>,
     to: Option<NaiveDate>,
+    year: Option<u16>,
+    today: NaiveDate,
+    week_start: chrono::Weekday,
 ) -> Result<(NaiveDate, NaiveDate)> {
     if let (Some(f), Some(t)) = (from, to) {
         return Ok((f, t));
     }
 
-    let today = Local::now().date_naive();
-
-    match period.unwrap_or("week") {
-        "week" => {
-            let from = today - chrono::Duration::days(6);
-            Ok((from, today))
-        }
-        "month" => {
-            if let Some(m) = month {
-                // Parse "2026-01" format
-                let parts: Vec<&str> = m.split('-').collect();
-                if parts.len() == 2 {
-                    let year: i32 = parts[0].parse()?;
-                    let mon: u32 = parts[1].parse()?;
-                    let first = NaiveDate::from_ymd_opt(year, mon, 1)
-                        .ok_or_else(|| anyhow::anyhow!("invalid month: {}", m))?;
-                    let last = if mon == 12 {
-                        NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap() - chrono::Duration::days(1)
-                    } else {
-                        NaiveDate::from_ymd_opt(year, mon + 1, 1).unwrap()
-                            - chrono::Duration::days(1)
-                    };
-                    Ok((first, last))
-                } else {
-                    anyhow::bail!("invalid month format: {} (expected YYYY-MM)", m)
-                }
-            } else {
-                let first = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap();
-                Ok((first, today))
-            }
+    let period = period.unwrap_or("week");
+    if period == "month"
+        && let Some(m) = month
+    {
+        // Parse "2026-01" format
+        let parts: Vec<&str> = m.split('-').collect();
+        if parts.len() != 2 {
+            openvital::fail!(parse, "invalid month format: {} (expected YYYY-MM)", m)
         }
-        other => anyhow::bail!("invalid period: {} (expected week/month)", other),
+        let year: i32 = parts[0].parse()?;
+        let mon: u32 = parts[1].parse()?;
+        let first = NaiveDate::from_ymd_opt(year, mon, 1)
+            .ok_or_else(|| openvital::error::parse(format!("invalid month: {}", m)))?;
+        let last = if mon == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap() - chrono::Duration::days(1)
+        } else {
+            NaiveDate::from_ymd_opt(year, mon + 1, 1).unwrap() - chrono::Duration::days(1)
+        };
+        return Ok((first, last));
     }
+    report::period_bounds(period, today, year, week_start)
 }
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use openvital::core::search;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run(
+    query: &str,
+    metric_type: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: Option<u32>,
+    human_flag: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let result = search::search(&db, &config, query, metric_type, from, to, limit)?;
+
+    if human_flag {
+        if result.entries.is_empty() && result.medications.is_empty() {
+            println!("No notes matching '{}'", result.query);
+        }
+        for hit in &result.entries {
+            println!(
+                "{}",
+                human::format_metric_with_units(&hit.entry, &config.units)
+            );
+        }
+        for hit in &result.medications {
+            println!("med {} | {}  # {}", hit.name, hit.field, hit.text);
+        }
+    } else {
+        let out = output::success("search", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
@@ -2,21 +2,63 @@ use anyhow::Result;
 use chrono::NaiveDate;
 use serde_json::json;
 
+use std::collections::BTreeMap;
+
+use openvital::core::analytics::{self, SummaryStats};
+use openvital::core::bp::{self, BpReading};
+use openvital::core::export;
 use openvital::core::query::{self, ShowResult};
-use openvital::db::Database;
+use openvital::db::{Database, SortOrder};
+use openvital::models::Metric;
 use openvital::models::config::Config;
 use openvital::output;
 use openvital::output::human;
 
-pub fn run(
-    metric_type: Option<&str>,
-    last: Option<u32>,
-    date: Option<NaiveDate>,
-    human_flag: bool,
-) -> Result<()> {
+pub struct ShowArgs<'a> {
+    pub last: Option<u32>,
+    pub date: Option<NaiveDate>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub tags: &'a [String],
+    pub with_stats: bool,
+    pub reverse: bool,
+    /// `envelope` (the default), `jsonl` or `csv`
+    pub format: &'a str,
+}
+
+pub fn run(metric_type: Option<&str>, args: ShowArgs<'_>, human_flag: bool) -> Result<()> {
+    let ShowArgs {
+        last,
+        date,
+        from,
+        to,
+        tags,
+        with_stats,
+        reverse,
+        format,
+    } = args;
+    check_format(format, human_flag)?;
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
-    let result = query::show(&db, &config, metric_type, last, date)?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let order = sort_order(reverse);
+    let opts = query::ShowOptions {
+        last,
+        date,
+        from,
+        to,
+        tags,
+        order,
+    };
+    let result = query::show(&db, &config, metric_type, &opts)?;
+
+    if format != "envelope" {
+        return match result {
+            ShowResult::ByType { entries, .. } | ShowResult::ByDate { entries, .. } => {
+                print_entries(&entries, format)
+            }
+            ShowResult::BloodPressure { readings } => print_readings(&readings, format),
+        };
+    }
 
     match result {
         ShowResult::ByType {
@@ -27,13 +69,48 @@ pub fn run(
                 if entries.is_empty() {
                     println!("No entries found for '{}'", metric_type);
                 } else {
-                    for m in &entries {
-                        println!("{}", human::format_metric_with_units(m, &config.units));
+                    for e in &query::annotate(&db, &entries, &config)? {
+                        println!("{}", format_entry(e, &config));
+                    }
+                    if with_stats {
+                        let s = summarize(&entries);
+                        println!();
+                        println!(
+                            "{}",
+                            human::format_show_statistics(None, &metric_type, &s, &config.units)
+                        );
                     }
                 }
             } else {
-                let out =
-                    output::success("show", json!({ "type": metric_type, "entries": entries }));
+                let mut data = json!({
+                    "type": metric_type,
+                    "order": order,
+                    "entries": query::annotate(&db, &entries, &config)?,
+                });
+                if with_stats {
+                    data["statistics"] = json!(summarize(&entries));
+                }
+                let out = output::success("show", data);
+                println!("{}", serde_json::to_string(&out)?);
+            }
+        }
+        ShowResult::BloodPressure { readings } => {
+            if human_flag {
+                if readings.is_empty() {
+                    println!("No entries found for 'blood_pressure'");
+                }
+                for r in &readings {
+                    println!("{}", human::format_bp_reading(r));
+                }
+                if with_stats && !readings.is_empty() {
+                    print_statistics(&bp_statistics(&readings), &config);
+                }
+            } else {
+                let mut data = json!({ "type": bp::BP_TYPE, "readings": readings });
+                if with_stats {
+                    data["statistics"] = json!(bp_statistics(&readings));
+                }
+                let out = output::success("show", data);
                 println!("{}", serde_json::to_string(&out)?);
             }
         }
@@ -43,21 +120,194 @@ pub fn run(
                     println!("No entries for {}", date);
                 } else {
                     println!("--- {} ---", date);
-                    for m in &entries {
-                        println!("{}", human::format_metric_with_units(m, &config.units));
+                    for e in &query::annotate(&db, &entries, &config)? {
+                        println!("{}", format_entry(e, &config));
+                    }
+                    if with_stats {
+                        print_statistics(&summarize_by_type(&entries), &config);
                     }
                 }
                 if metric_type.is_none() {
                     println!("\nTip: use 'show <type>' to see history for a specific metric.");
                 }
             } else {
-                let out = output::success(
-                    "show",
-                    json!({ "date": date.to_string(), "entries": entries }),
-                );
+                let mut data = json!({
+                    "date": date.to_string(),
+                    "order": SortOrder::Asc,
+                    "entries": query::annotate(&db, &entries, &config)?,
+                });
+                if with_stats {
+                    data["statistics"] = json!(summarize_by_type(&entries));
+                }
+                let out = output::success("show", data);
                 println!("{}", serde_json::to_string(&out)?);
             }
         }
     }
     Ok(())
 }
+
+pub fn run_types(types: &str, args: ShowArgs<'_>, human_flag: bool) -> Result<()> {
+    let ShowArgs {
+        last,
+        date,
+        from,
+        to,
+        tags,
+        with_stats,
+        reverse,
+        format,
+    } = args;
+    check_format(format, human_flag)?;
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let requested: Vec<&str> = types.split(',').filter(|t| !t.trim().is_empty()).collect();
+    let order = sort_order(reverse);
+    let opts = query::ShowOptions {
+        last,
+        date,
+        from,
+        to,
+        tags,
+        order,
+    };
+    let groups = query::show_types(&db, &config, &requested, &opts)?;
+
+    if format != "envelope" {
+        let entries: Vec<Metric> = groups.into_iter().flat_map(|g| g.entries).collect();
+        return print_entries(&entries, format);
+    }
+
+    if human_flag {
+        for (i, g) in groups.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("--- {} ---", g.metric_type);
+            if g.entries.is_empty() {
+                println!("No entries found for '{}'", g.metric_type);
+            } else {
+                for e in &query::annotate(&db, &g.entries, &config)? {
+                    println!("{}", format_entry(e, &config));
+                }
+                if with_stats {
+                    let s = summarize(&g.entries);
+                    println!();
+                    println!(
+                        "{}",
+                        human::format_show_statistics(None, &g.metric_type, &s, &config.units)
+                    );
+                }
+            }
+        }
+    } else {
+        let listed = groups
+            .iter()
+            .map(|g| {
+                Ok(json!({
+                    "type": g.metric_type,
+                    "entries": query::annotate(&db, &g.entries, &config)?,
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // A day's entries are oldest first whatever the requested order
+        let order = if date.is_some() {
+            SortOrder::Asc
+        } else {
+            order
+        };
+        let mut data = json!({ "order": order, "groups": listed });
+        if with_stats {
+            let stats: BTreeMap<&str, SummaryStats> = groups
+                .iter()
+                .map(|g| (g.metric_type.as_str(), summarize(&g.entries)))
+                .collect();
+            data["statistics"] = json!(stats);
+        }
+        let out = output::success("show", data);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+/// `--reverse` lists history oldest first.
+fn sort_order(reverse: bool) -> SortOrder {
+    if reverse {
+        SortOrder::Asc
+    } else {
+        SortOrder::Desc
+    }
+}
+
+/// clap rejects `show --format csv --human`, but a global `--human` given
+/// before the subcommand slips past it.
+fn check_format(format: &str, human_flag: bool) -> Result<()> {
+    if human_flag && format != "envelope" {
+        openvital::fail!(validation, "--format cannot be used with --human");
+    }
+    Ok(())
+}
+
+/// Print entries without the envelope, in the same layout as `export`:
+/// one metric object per line (`jsonl`) or export's CSV columns (`csv`).
+fn print_entries(entries: &[Metric], format: &str) -> Result<()> {
+    if format == "csv" {
+        print!("{}", export::render_csv(entries)?);
+    } else {
+        let lines = export::render_jsonl(entries)?;
+        if !lines.is_empty() {
+            println!("{}", lines);
+        }
+    }
+    Ok(())
+}
+
+/// Blood pressure readings pair two entries, so they have no row in
+/// export's CSV layout; `jsonl` prints one reading per line.
+fn print_readings(readings: &[BpReading], format: &str) -> Result<()> {
+    if format == "csv" {
+        openvital::fail!(
+            validation,
+            "--format csv is not available for blood_pressure (use --types bp_systolic,bp_diastolic)"
+        );
+    }
+    for r in readings {
+        println!("{}", serde_json::to_string(r)?);
+    }
+    Ok(())
+}
+
+fn format_entry(e: &query::RangedEntry<'_>, config: &Config) -> String {
+    human::format_metric_in_range(e.entry, &config.units, e.range_status, e.bp_category)
+}
+
+fn summarize(entries: &[Metric]) -> SummaryStats {
+    analytics::describe(entries).into()
+}
+
+fn summarize_by_type(entries: &[Metric]) -> BTreeMap<String, SummaryStats> {
+    analytics::describe_by_type(entries)
+        .into_iter()
+        .map(|(t, s)| (t, s.into()))
+        .collect()
+}
+
+/// Per-component statistics of blood pressure readings.
+fn bp_statistics(readings: &[BpReading]) -> BTreeMap<String, SummaryStats> {
+    let systolic = readings.iter().filter_map(|r| r.systolic).collect();
+    let diastolic = readings.iter().filter_map(|r| r.diastolic).collect();
+    [(bp::SYSTOLIC, systolic), (bp::DIASTOLIC, diastolic)]
+        .into_iter()
+        .map(|(t, values)| (t.to_string(), analytics::describe_values(values).into()))
+        .collect()
+}
+
+fn print_statistics(stats: &BTreeMap<String, SummaryStats>, config: &Config) {
+    for (t, s) in stats {
+        println!();
+        println!(
+            "{}",
+            human::format_show_statistics(Some(t), t, s, &config.units)
+        );
+    }
+}
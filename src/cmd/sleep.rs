@@ -0,0 +1,45 @@
+use anyhow::Result;
+use serde_json::json;
+
+use openvital::core::sleep::{self, SleepInput};
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+
+pub fn run(input: SleepInput<'_>, human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let logged = sleep::log_sleep(&db, &input)?;
+
+    if human_flag {
+        println!(
+            "Slept {} h {} min",
+            logged.minutes / 60,
+            logged.minutes % 60
+        );
+    } else {
+        let entries: Vec<_> = logged
+            .entries
+            .iter()
+            .map(|m| {
+                json!({
+                    "id": m.id,
+                    "type": m.metric_type,
+                    "value": m.value,
+                    "unit": m.unit
+                })
+            })
+            .collect();
+        let out = output::success(
+            "sleep",
+            json!({
+                "entries": entries,
+                "count": logged.entries.len(),
+                "duration_minutes": logged.minutes,
+                "timestamp": logged.entries[0].timestamp.to_rfc3339(),
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::json;
+
+use openvital::core::analytics;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+
+pub fn run(
+    metric_type: Option<&str>,
+    last: Option<u32>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    human: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let resolved = metric_type.map(|t| config.resolve_alias(t));
+    let results = analytics::stats(&db, resolved.as_deref(), from, to, last)?;
+
+    if human {
+        println!(
+            "{}",
+            openvital::output::human::format_stats(&results, &config.units)
+        );
+    } else {
+        let period = json!({ "from": from, "to": to, "last": last });
+        let data = match resolved {
+            Some(t) => {
+                let stats = results.into_iter().next().map(|r| r.stats);
+                json!({ "type": t, "stats": stats, "period": period })
+            }
+            None => json!({ "types": results, "period": period }),
+        };
+        let out = output::success("stats", data);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
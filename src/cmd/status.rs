@@ -1,20 +1,67 @@
 use anyhow::Result;
+use chrono::NaiveDate;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 use openvital::db::Database;
 use openvital::models::config::Config;
 use openvital::output;
+use openvital::output::color::ColorConfig;
 use openvital::output::human;
 
-pub fn run(human_flag: bool) -> Result<()> {
+pub fn run(date: Option<NaiveDate>, human_flag: bool, color: &ColorConfig) -> Result<()> {
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
-    let status = openvital::core::status::compute(&db, &config)?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let status = match date {
+        Some(d) => openvital::core::status::compute_on(&db, &config, d)?,
+        None => openvital::core::status::compute(&db, &config)?,
+    };
 
     if human_flag {
-        println!("{}", human::format_status(&status, &config.units));
+        println!("{}", human::format_status(&status, &config.units, color));
     } else {
         let out = output::success("status", serde_json::to_value(&status)?);
         println!("{}", serde_json::to_string(&out)?);
     }
     Ok(())
 }
+
+/// Bounds of the `--watch` refresh period, in seconds.
+const WATCH_MIN_SECS: u64 = 1;
+const WATCH_MAX_SECS: u64 = 3600;
+
+/// Set by the SIGINT handler; the watch loop stops at its next check.
+static STOP: AtomicBool = AtomicBool::new(false);
+
+/// Redraw the human status every `secs` seconds (clamped to 1–3600) until Ctrl-C.
+pub fn run_watch(
+    secs: u64,
+    date: Option<NaiveDate>,
+    human_flag: bool,
+    color: &ColorConfig,
+) -> Result<()> {
+    if !human_flag {
+        openvital::fail!(validation, "Watch mode only supported with --human");
+    }
+    let period = Duration::from_secs(secs.clamp(WATCH_MIN_SECS, WATCH_MAX_SECS));
+    catch_sigint()?;
+    while !STOP.load(Ordering::SeqCst) {
+        print!("\x1B[2J\x1B[H");
+        println!("Refreshing every {}s (Ctrl-C to stop)\n", period.as_secs());
+        run(date, true, color)?;
+        // Sleep in short steps so Ctrl-C doesn't wait out the period
+        let started = Instant::now();
+        while started.elapsed() < period && !STOP.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    println!("Stopped watching");
+    Ok(())
+}
+
+fn catch_sigint() -> Result<()> {
+    if let Err(e) = ctrlc::set_handler(|| STOP.store(true, Ordering::SeqCst)) {
+        openvital::fail!(conflict, "cannot watch for Ctrl-C: {}", e);
+    }
+    Ok(())
+}
@@ -1,77 +1,242 @@
 use anyhow::Result;
 
-use openvital::core::trend::{self, TrendPeriod};
+use openvital::core::trend::{self, GapFill, TrendOptions, TrendPeriod, TrendResult};
 use openvital::db::Database;
 use openvital::models::config::Config;
+use openvital::models::metric::Aggregation;
 use openvital::output;
 
-pub fn run(metric_type: &str, period: Option<&str>, last: Option<u32>, human: bool) -> Result<()> {
+pub fn run(
+    metric_type: &str,
+    period: Option<&str>,
+    last: Option<u32>,
+    smooth: Option<u32>,
+    agg: Option<&str>,
+    fill: Option<&str>,
+    human: bool,
+) -> Result<()> {
     let config = Config::load()?;
     let resolved = config.resolve_alias(metric_type);
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
     let period: TrendPeriod = period.unwrap_or("weekly").parse()?;
-    let result = trend::compute(&db, &resolved, period, last)?;
+    let aggregation = match agg {
+        Some(a) => Some(a.parse::<Aggregation>()?),
+        None => config.aggregation.get(&resolved).copied(),
+    };
+    let opts = TrendOptions {
+        last,
+        smooth,
+        aggregation,
+        fill: gap_fill(fill, &config)?,
+    };
+    if openvital::core::bp::is_bp(&resolved) {
+        let result = openvital::core::bp::trend(&db, period, opts)?;
+        if human {
+            print_trend(&result.systolic, &config);
+            println!();
+            print_trend(&result.diastolic, &config);
+        } else {
+            let out = output::success("trend", serde_json::to_value(&result)?);
+            println!("{}", serde_json::to_string(&out)?);
+        }
+        return Ok(());
+    }
+    let result = trend::compute_with(&db, &resolved, period, opts)?;
 
     if human {
-        if result.data.is_empty() {
-            println!("No data for '{}'", resolved);
-        } else {
-            println!("Trend: {} ({})\n", resolved, result.period);
-            for d in &result.data {
-                let (avg, _) = openvital::core::units::to_display(d.avg, &resolved, &config.units);
-                let (min, _) = openvital::core::units::to_display(d.min, &resolved, &config.units);
-                let (max, unit) =
-                    openvital::core::units::to_display(d.max, &resolved, &config.units);
+        print_trend(&result, &config);
+    } else {
+        let out = output::success("trend", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_by_weekday(
+    metric_type: &str,
+    weeks: Option<u32>,
+    agg: Option<&str>,
+    human: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let resolved = config.resolve_alias(metric_type);
+    if openvital::core::bp::is_bp(&resolved) {
+        openvital::fail!(
+            validation,
+            "--by weekday needs a single series; use bp_systolic or bp_diastolic"
+        );
+    }
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let aggregation = match agg {
+        Some(a) => Some(a.parse::<Aggregation>()?),
+        None => config.aggregation.get(&resolved).copied(),
+    };
+    let result = trend::by_weekday(&db, &resolved, weeks, aggregation)?;
+
+    if human {
+        println!(
+            "{}",
+            openvital::output::human::format_weekday_trend(&result, &config.units)
+        );
+    } else {
+        let out = output::success("trend", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+fn print_trend(result: &TrendResult, config: &Config) {
+    let resolved = result.metric_type.as_str();
+    if result.data.is_empty() {
+        println!("No data for '{}'", resolved);
+    } else {
+        println!(
+            "Trend: {} ({}, {})\n",
+            resolved, result.period, result.aggregation
+        );
+        for d in &result.data {
+            let (avg, _) = openvital::core::units::to_display(d.avg, resolved, &config.units);
+            let (min, _) = openvital::core::units::to_display(d.min, resolved, &config.units);
+            let (max, unit) = openvital::core::units::to_display(d.max, resolved, &config.units);
+            let n = if d.filled > 0 {
+                format!("n={}, +{} filled", d.count, d.filled)
+            } else {
+                format!("n={}", d.count)
+            };
+            if let Some(ma) = d.moving_avg {
+                let (ma, _) = openvital::core::units::to_display(ma, resolved, &config.units);
                 println!(
-                    "  {} | avg: {:.1}  min: {:.1}  max: {:.1}  (n={}) [{}]",
-                    d.label, avg, min, max, d.count, unit
+                    "  {} | smoothed: {:.1} ({:.1})  min: {:.1}  max: {:.1}  ({}) [{}]",
+                    d.label, ma, avg, min, max, n, unit
+                );
+            } else {
+                println!(
+                    "  {} | {}: {:.1}  min: {:.1}  max: {:.1}  ({}) [{}]",
+                    d.label, result.aggregation, avg, min, max, n, unit
                 );
             }
-            println!();
+        }
+        println!();
+        println!(
+            "  Direction: {} ({:+.1} {})",
+            result.trend.direction,
+            openvital::core::units::to_display_rate(result.trend.rate, resolved, &config.units),
+            format!(
+                "{} {}",
+                openvital::core::units::display_unit(resolved, &config.units),
+                result.trend.rate_unit
+            )
+            .trim()
+        );
+        if let (Some(lo), Some(hi)) = (
+            result.trend.confidence_95_lower,
+            result.trend.confidence_95_upper,
+        ) {
+            let slope =
+                openvital::core::units::to_display_rate((lo + hi) / 2.0, resolved, &config.units);
+            let half =
+                openvital::core::units::to_display_rate((hi - lo) / 2.0, resolved, &config.units);
+            let noun = result.trend.rate_unit.trim_start_matches("per ");
+            let unit = openvital::core::units::display_unit(resolved, &config.units);
+            let slope_unit = if unit.is_empty() {
+                format!("per {}", noun)
+            } else {
+                format!("{}/{}", unit, noun)
+            };
             println!(
-                "  Direction: {} ({:+.1} {})",
-                result.trend.direction,
-                openvital::core::units::to_display_rate(
-                    result.trend.rate,
-                    &resolved,
-                    &config.units
-                ),
-                format!(
-                    "{} {}",
-                    openvital::core::units::display_unit(&resolved, &config.units),
-                    result.trend.rate_unit
-                )
-                .trim()
+                "  Slope: {:.1} ± {:.1} {} (95% CI)",
+                slope, half, slope_unit
+            );
+        }
+        if let Some(f) = &result.fill {
+            println!(
+                "  Gaps filled ({}, up to {} days): {} synthetic value(s)",
+                f.method, f.max_gap_days, f.synthetic_points
+            );
+        }
+        if let Some(p) = result.trend.projected_30d {
+            let (pv, pu) = openvital::core::units::to_display(p, resolved, &config.units);
+            println!("  30-day projection: {:.1} {}", pv, pu);
+        }
+        if let Some(p) = &result.projection {
+            println!(
+                "  Goal: {}",
+                openvital::output::human::format_projection(p, resolved, &config.units)
             );
-            if let Some(p) = result.trend.projected_30d {
-                let (pv, pu) = openvital::core::units::to_display(p, &resolved, &config.units);
-                println!("  30-day projection: {:.1} {}", pv, pu);
-            }
         }
-    } else {
-        let out = output::success("trend", serde_json::to_value(&result)?);
-        println!("{}", serde_json::to_string(&out)?);
     }
-    Ok(())
 }
 
-pub fn run_correlate(metrics: &str, last: Option<u32>, human: bool) -> Result<()> {
+pub fn run_correlate(
+    metrics: &str,
+    last: Option<u32>,
+    lag: Option<i64>,
+    scan_lag: bool,
+    fill: Option<&str>,
+    human: bool,
+) -> Result<()> {
     let config = Config::load()?;
-    let db = Database::open(&Config::db_path())?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
 
     let parts: Vec<&str> = metrics.split(',').collect();
     if parts.len() != 2 {
-        anyhow::bail!("--correlate requires exactly two metric types separated by comma");
+        openvital::fail!(
+            validation,
+            "--correlate requires exactly two metric types separated by comma"
+        );
     }
     let a = config.resolve_alias(parts[0].trim());
     let b = config.resolve_alias(parts[1].trim());
+    let fill = gap_fill(fill, &config)?;
+
+    if scan_lag {
+        let scan = trend::scan_lag_with(&db, &a, &b, last, config.cycle.gap_days, fill)?;
+        if human {
+            println!(
+                "Lag scan: {} vs {}\n",
+                scan.best.metric_a, scan.best.metric_b
+            );
+            for l in &scan.lags {
+                println!(
+                    "  lag {:+}: {:+.2} (n={})",
+                    l.lag, l.coefficient, l.data_points
+                );
+            }
+            println!();
+            println!(
+                "  Strongest: lag {:+} ({:.2}, {})",
+                scan.best.lag, scan.best.coefficient, scan.best.interpretation
+            );
+        } else {
+            let out = output::success("correlate", serde_json::to_value(&scan)?);
+            println!("{}", serde_json::to_string(&out)?);
+        }
+        return Ok(());
+    }
 
-    let result = trend::correlate(&db, &a, &b, last)?;
+    let result = trend::correlate_with(
+        &db,
+        &a,
+        &b,
+        last,
+        lag.unwrap_or(0),
+        config.cycle.gap_days,
+        fill,
+    )?;
 
     if human {
         println!("Correlation: {} vs {}\n", result.metric_a, result.metric_b);
+        if result.lag != 0 {
+            println!("  Lag: {:+} days", result.lag);
+        }
         println!("  Coefficient: {:.2}", result.coefficient);
         println!("  Data points: {}", result.data_points);
+        if let Some(f) = &result.fill {
+            println!(
+                "  Filled ({}): {} of them use filled days",
+                f.method, f.synthetic_points
+            );
+        }
         println!("  Strength: {}", result.interpretation);
     } else {
         let out = output::success("correlate", serde_json::to_value(&result)?);
@@ -79,3 +244,25 @@ pub fn run_correlate(metrics: &str, last: Option<u32>, human: bool) -> Result<()
     }
     Ok(())
 }
+
+/// `--fill` with the configured maximum gap.
+fn gap_fill(method: Option<&str>, config: &Config) -> Result<GapFill> {
+    Ok(GapFill {
+        method: method.map(str::parse).transpose()?.unwrap_or_default(),
+        max_gap_days: config.trend.fill_max_gap_days,
+    })
+}
+
+pub fn run_correlate_all(last: Option<u32>, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let matrix = trend::correlation_matrix(&db, last.map(|d| d as usize))?;
+
+    if human {
+        println!("{}", output::human::format_correlation_matrix(&matrix));
+    } else {
+        let out = output::success("correlate", serde_json::to_value(&matrix)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use serde_json::json;
+
+use openvital::core::types;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::models::metric::Category;
+use openvital::output;
+
+pub fn run_list(metric_type: Option<&str>, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    if let Some(name) = metric_type {
+        let detail = types::detail(&db, &config, name)?;
+        if human {
+            println!("{}", output::human::format_type_detail(&detail));
+        } else {
+            let out = output::success("types", serde_json::to_value(&detail)?);
+            println!("{}", serde_json::to_string(&out)?);
+        }
+        return Ok(());
+    }
+
+    let list = types::list(&db, &config)?;
+    if human {
+        println!("{}", output::human::format_types_list(&list));
+    } else {
+        let out = output::success("types", serde_json::to_value(&list)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_add(name: &str, unit: &str, category: &str, human: bool) -> Result<()> {
+    let category: Category = category.parse()?;
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let added = types::add(&db, name, unit, category)?;
+
+    if human {
+        println!(
+            "Type added: {} ({}, {})",
+            added.name,
+            if added.unit.is_empty() {
+                "no unit"
+            } else {
+                &added.unit
+            },
+            added.category
+        );
+    } else {
+        let out = output::success("types", json!({ "added": added }));
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_remove(name: &str, force: bool, human: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let removed = types::remove(&db, name, force)?;
+
+    if human {
+        if removed.entries > 0 {
+            println!(
+                "Type removed: {} ({} entries kept)",
+                removed.name, removed.entries
+            );
+        } else {
+            println!("Type removed: {}", removed.name);
+        }
+    } else {
+        let out = output::success("types", json!({ "removed": removed }));
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_migrate(
+    from: &str,
+    to: &str,
+    opts: types::MigrateOptions,
+    yes: bool,
+    human: bool,
+) -> Result<()> {
+    if !opts.dry_run && !yes {
+        openvital::fail!(
+            validation,
+            "migrate-type rewrites every '{}' entry; preview with --dry-run, then re-run with --yes",
+            from
+        );
+    }
+    let mut config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let result = types::migrate_type(&db, &mut config, from, to, opts)?;
+    if !result.dry_run && !result.aliases.is_empty() {
+        config.save()?;
+    }
+
+    if human {
+        let verb = if result.dry_run {
+            "Would move"
+        } else {
+            "Moved"
+        };
+        println!(
+            "{} {} {} entries to {}{}",
+            verb,
+            result.metrics,
+            result.from,
+            result.to,
+            if result.merged { " (merged)" } else { "" }
+        );
+        if result.units_filled > 0 {
+            println!(
+                "  {} entries without a unit take the default unit",
+                result.units_filled
+            );
+        }
+        if opts.goals {
+            println!("  Goals: {}", result.goals);
+        }
+        if !result.aliases.is_empty() {
+            println!("  Aliases: {}", result.aliases.join(", "));
+        }
+    } else {
+        let out = output::success("migrate_type", serde_json::to_value(&result)?);
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
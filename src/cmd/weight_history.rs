@@ -0,0 +1,50 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::json;
+
+use openvital::core::analytics;
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run(
+    last: Option<usize>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    human_flag: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let Some(height_cm) = config.profile.height_cm else {
+        openvital::fail!(
+            validation,
+            "height is not configured; set it with `openvital config set height <value>`"
+        );
+    };
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+
+    let mut weights = db.query_all(Some("weight"), from, to)?;
+    weights.reverse();
+    if let Some(n) = last {
+        weights.truncate(n);
+    }
+    let entries = analytics::enrich_with_bmi(&weights, height_cm);
+
+    if human_flag {
+        if entries.is_empty() {
+            println!("No weight entries found");
+        } else {
+            println!(
+                "{}",
+                human::format_weight_history(&entries, &config.units, db.timezone())
+            );
+        }
+    } else {
+        let out = output::success(
+            "weight_history",
+            json!({ "height_cm": height_cm, "entries": entries }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
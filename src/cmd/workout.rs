@@ -0,0 +1,79 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::json;
+
+use openvital::core::workout::{self, WorkoutInput};
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::output;
+use openvital::output::human;
+
+pub fn run(input: WorkoutInput<'_>, human_flag: bool) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let metrics = workout::log_workout(&db, &config, &input)?;
+
+    if human_flag {
+        for m in &metrics {
+            println!(
+                "Logged: {}",
+                human::format_metric_with_units(m, &config.units)
+            );
+        }
+    } else {
+        let session = metrics[0]
+            .tags
+            .iter()
+            .find_map(|t| t.strip_prefix(workout::SESSION_PREFIX))
+            .unwrap_or_default();
+        let entries: Vec<_> = metrics
+            .iter()
+            .map(|m| {
+                json!({
+                    "id": m.id,
+                    "type": m.metric_type,
+                    "value": m.value,
+                    "unit": m.unit
+                })
+            })
+            .collect();
+        let out = output::success(
+            "workout",
+            json!({
+                "session": session,
+                "entries": entries,
+                "count": metrics.len(),
+                "timestamp": metrics[0].timestamp.to_rfc3339(),
+            }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
+
+pub fn run_list(
+    last: Option<usize>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    human_flag: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let db = Database::open_with_config(&Config::db_path(), &config)?;
+    let sessions = workout::list(&db, from, to, last)?;
+
+    if human_flag {
+        if sessions.is_empty() {
+            println!("No workouts logged");
+        }
+        for s in &sessions {
+            println!("{}", human::format_workout_session(s, &config.units));
+        }
+    } else {
+        let out = output::success(
+            "workout_list",
+            json!({ "sessions": sessions, "count": sessions.len() }),
+        );
+        println!("{}", serde_json::to_string(&out)?);
+    }
+    Ok(())
+}
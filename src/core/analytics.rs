@@ -0,0 +1,354 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::db::Database;
+use crate::models::anomaly::Severity;
+use crate::models::metric::Metric;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DescriptiveStats {
+    pub count: u32,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p25: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypeStats {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub stats: DescriptiveStats,
+}
+
+/// The headline numbers of [`DescriptiveStats`], as shown by `show --stats`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryStats {
+    pub count: u32,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub std_dev: f64,
+}
+
+impl From<DescriptiveStats> for SummaryStats {
+    fn from(s: DescriptiveStats) -> Self {
+        Self {
+            count: s.count,
+            mean: s.mean,
+            min: s.min,
+            max: s.max,
+            std_dev: s.std_dev,
+        }
+    }
+}
+
+/// A weight entry with the BMI it gives at the configured height.
+#[derive(Debug, Serialize)]
+pub struct MetricWithBmi {
+    #[serde(flatten)]
+    pub metric: Metric,
+    pub bmi: f64,
+    pub bmi_category: &'static str,
+}
+
+/// BMI and WHO category for each entry, treating its value as weight in kg.
+pub fn enrich_with_bmi(metrics: &[Metric], height_cm: f64) -> Vec<MetricWithBmi> {
+    metrics
+        .iter()
+        .filter_map(|m| {
+            let (bmi, category) = crate::core::status::bmi(Some(height_cm), Some(m.value));
+            Some(MetricWithBmi {
+                metric: m.clone(),
+                bmi: bmi?,
+                bmi_category: category?,
+            })
+        })
+        .collect()
+}
+
+/// Largest edit distance at which an unknown metric type is taken for a typo
+/// of a known one.
+pub const MAX_TYPO_DISTANCE: usize = 2;
+
+/// Levenshtein edit distance between two strings, by character.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != *cb);
+            row[j + 1] = substitute.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// The known type closest to `input` and its edit distance, if within
+/// [`MAX_TYPO_DISTANCE`]. Ties go to the earlier entry of `known_types`.
+pub fn closest_metric_type(input: &str, known_types: &[String]) -> Option<(String, usize)> {
+    known_types
+        .iter()
+        .map(|t| (t, levenshtein(input, t)))
+        .filter(|(_, d)| *d <= MAX_TYPO_DISTANCE)
+        .min_by_key(|(_, d)| *d)
+        .map(|(t, d)| (t.clone(), d))
+}
+
+/// Compute descriptive statistics over a set of entries.
+/// `std_dev` is the sample standard deviation (0 for fewer than two entries).
+pub fn describe(metrics: &[Metric]) -> DescriptiveStats {
+    describe_values(metrics.iter().map(|m| m.value).collect())
+}
+
+/// [`describe`] over bare values.
+pub fn describe_values(mut sorted: Vec<f64>) -> DescriptiveStats {
+    sorted.sort_by(f64::total_cmp);
+
+    let n = sorted.len();
+    if n == 0 {
+        return DescriptiveStats {
+            count: 0,
+            mean: 0.0,
+            median: 0.0,
+            std_dev: 0.0,
+            min: 0.0,
+            max: 0.0,
+            p25: 0.0,
+            p75: 0.0,
+            p95: 0.0,
+        };
+    }
+
+    let mean = sorted.iter().sum::<f64>() / n as f64;
+    let std_dev = if n < 2 {
+        0.0
+    } else {
+        let var = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        var.sqrt()
+    };
+
+    DescriptiveStats {
+        count: n as u32,
+        mean,
+        median: percentile(&sorted, 50.0),
+        std_dev,
+        min: sorted[0],
+        max: sorted[n - 1],
+        p25: percentile(&sorted, 25.0),
+        p75: percentile(&sorted, 75.0),
+        p95: percentile(&sorted, 95.0),
+    }
+}
+
+/// [`describe`] per metric type, keyed by type.
+pub fn describe_by_type(metrics: &[Metric]) -> BTreeMap<String, DescriptiveStats> {
+    let mut by_type: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for m in metrics {
+        by_type
+            .entry(m.metric_type.clone())
+            .or_default()
+            .push(m.value);
+    }
+    by_type
+        .into_iter()
+        .map(|(t, values)| (t, describe_values(values)))
+        .collect()
+}
+
+/// Compute percentile using linear interpolation. `sorted` must be ascending.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let k = (p / 100.0) * (sorted.len() - 1) as f64;
+    let f = k.floor() as usize;
+    let c = k.ceil() as usize;
+    if f == c {
+        sorted[f]
+    } else {
+        sorted[f] + (k - f as f64) * (sorted[c] - sorted[f])
+    }
+}
+
+/// One value judged against the sample it belongs to.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlierCheck {
+    pub value: f64,
+    /// IQRs past the nearest quartile (0 inside), or |z|.
+    pub score: f64,
+    /// `None` unless the value is an outlier.
+    pub severity: Option<Severity>,
+}
+
+/// Flag values outside the standard Tukey fences (Q1 - 1.5·IQR, Q3 + 1.5·IQR).
+/// The result is parallel to `values`.
+pub fn detect_iqr_anomalies(values: &[f64]) -> Vec<bool> {
+    detect_iqr_anomalies_with(values, 1.5)
+}
+
+/// Flag values outside Q1 - k·IQR and Q3 + k·IQR, with quartiles taken over
+/// `values` themselves. The result is parallel to `values`.
+pub fn detect_iqr_anomalies_with(values: &[f64], k: f64) -> Vec<bool> {
+    classify_iqr(values, k)
+        .iter()
+        .map(|c| c.severity.is_some())
+        .collect()
+}
+
+/// Judge each value against Q1 - k·IQR and Q3 + k·IQR of `values`, with a
+/// severity for the ones outside. The result is parallel to `values`.
+pub fn classify_iqr(values: &[f64], k: f64) -> Vec<OutlierCheck> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let q1 = percentile(&sorted, 25.0);
+    let q3 = percentile(&sorted, 75.0);
+    let iqr = q3 - q1;
+    // Zero-IQR samples (constant values) fall back to 1% of the median.
+    let normalizer = iqr.max(percentile(&sorted, 50.0).abs() * 0.01).max(0.01);
+    values
+        .iter()
+        .map(|&value| {
+            let outlier = value < q1 - k * iqr || value > q3 + k * iqr;
+            let score = ((value - q3).max(q1 - value) / normalizer).max(0.0);
+            OutlierCheck {
+                value,
+                score,
+                severity: outlier.then(|| Severity::from_iqr_distance(score)),
+            }
+        })
+        .collect()
+}
+
+/// Judge each value by its z-score within `values` (sample standard
+/// deviation); |z| above `limit` is an outlier. The result is parallel to `values`.
+pub fn classify_zscore(values: &[f64], limit: f64) -> Vec<OutlierCheck> {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std_dev = if values.len() > 1 {
+        (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+    } else {
+        0.0
+    };
+    let spread = std_dev.max(mean.abs() * 0.01).max(0.01);
+    values
+        .iter()
+        .map(|&value| {
+            let score = ((value - mean) / spread).abs();
+            OutlierCheck {
+                value,
+                score,
+                severity: (score > limit).then(|| Severity::from_z(score)),
+            }
+        })
+        .collect()
+}
+
+/// Descriptive statistics per metric type within an optional date range.
+/// With `last`, only the most recent N entries of each type are considered.
+/// When `metric_type` is `None`, every type with entries in the range is included.
+pub fn stats(
+    db: &Database,
+    metric_type: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    last: Option<u32>,
+) -> Result<Vec<TypeStats>> {
+    let entries = db.query_all(metric_type, from, to)?;
+
+    let mut grouped: BTreeMap<String, Vec<Metric>> = BTreeMap::new();
+    for e in entries {
+        grouped.entry(e.metric_type.clone()).or_default().push(e);
+    }
+
+    let mut results = Vec::new();
+    for (t, mut group) in grouped {
+        // Entries are ascending, so the most recent N are at the tail
+        if let Some(n) = last
+            && group.len() > n as usize
+        {
+            group = group.split_off(group.len() - n as usize);
+        }
+        results.push(TypeStats {
+            metric_type: t,
+            stats: describe(&group),
+        });
+    }
+
+    // A requested type with no entries still gets an (empty) result
+    if let Some(t) = metric_type
+        && results.is_empty()
+    {
+        results.push(TypeStats {
+            metric_type: t.to_string(),
+            stats: describe(&[]),
+        });
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(values: &[f64]) -> Vec<Metric> {
+        values
+            .iter()
+            .map(|v| Metric::new("weight".to_string(), *v))
+            .collect()
+    }
+
+    #[test]
+    fn test_describe_median_odd_count() {
+        let s = describe(&metrics(&[5.0, 1.0, 3.0]));
+        assert_eq!(s.count, 3);
+        assert_eq!(s.median, 3.0);
+    }
+
+    #[test]
+    fn test_describe_median_even_count() {
+        let s = describe(&metrics(&[4.0, 1.0, 3.0, 2.0]));
+        assert_eq!(s.median, 2.5);
+    }
+
+    #[test]
+    fn test_describe_quartiles() {
+        let s = describe(&metrics(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]));
+        assert_eq!(s.p25, 3.0);
+        assert_eq!(s.p75, 7.0);
+        assert!((s.p95 - 8.6).abs() < 1e-9);
+        assert_eq!(s.min, 1.0);
+        assert_eq!(s.max, 9.0);
+    }
+
+    #[test]
+    fn test_describe_std_dev_known_values() {
+        // Sample variance of 2,4,4,4,5,5,7,9 is 32/7
+        let s = describe(&metrics(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]));
+        assert_eq!(s.mean, 5.0);
+        assert!((s.std_dev - (32.0f64 / 7.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_describe_single_and_empty() {
+        let s = describe(&metrics(&[42.0]));
+        assert_eq!(s.count, 1);
+        assert_eq!(s.std_dev, 0.0);
+        assert_eq!(s.p95, 42.0);
+
+        let e = describe(&[]);
+        assert_eq!(e.count, 0);
+    }
+}
@@ -1,113 +1,252 @@
 use std::collections::HashSet;
 
 use anyhow::Result;
-use chrono::{Duration, Local};
+use chrono::Duration;
 
+use crate::core::analytics::percentile;
+use crate::core::glucose;
+use crate::core::time;
 use crate::db::Database;
+use crate::models::Metric;
 use crate::models::anomaly::{
-    Anomaly, AnomalyPeriod, AnomalyResult, Baseline, Bounds, Severity, Threshold,
+    Anomaly, AnomalyPeriod, AnomalyResult, Baseline, Bounds, Method, Severity, Threshold,
+    TypeAnomalies,
 };
+use crate::models::metric::{Category, JOURNAL_TYPE};
 
 /// Minimum data points required to compute a meaningful baseline.
 const MIN_DATA_POINTS: usize = 7;
 
-/// Detect anomalies across one or all metric types.
+/// Minimum readings in the preceding window before the rolling method judges a value.
+const MIN_ROLLING_POINTS: usize = 3;
+
+/// Default preceding-days window for the rolling method.
+pub const DEFAULT_ROLLING_WINDOW: u32 = 7;
+
+/// Types whose readings have a smaller standard deviation are skipped by `--all`.
+const MIN_STD_DEV: f64 = 1e-9;
+
+/// Which entries and baselines a scan considers.
+#[derive(Clone, Copy)]
+struct ScanFilter {
+    /// Keep `med take` entries (category `medication`).
+    include_meds: bool,
+    /// Skip types whose readings in the window do not vary.
+    skip_flat: bool,
+}
+
+impl ScanFilter {
+    /// What a scan of one type or of every type without `--all` uses.
+    const ALL_ENTRIES: Self = Self {
+        include_meds: true,
+        skip_flat: false,
+    };
+}
+
+/// Detect anomalies across one or all metric types using the IQR method.
 pub fn detect(
     db: &Database,
     metric_type: Option<&str>,
     baseline_days: u32,
     threshold: Threshold,
 ) -> Result<AnomalyResult> {
-    let today = Local::now().date_naive();
-    let baseline_start = today - Duration::days(baseline_days as i64);
+    detect_with(
+        db,
+        metric_type,
+        baseline_days,
+        threshold,
+        Method::Iqr,
+        DEFAULT_ROLLING_WINDOW,
+    )
+}
 
+/// Detect anomalies with an explicit method.
+///
+/// `iqr` and `zscore` check today's readings against the whole baseline window;
+/// `rolling` checks every reading in the window against the `window_days` before it.
+pub fn detect_with(
+    db: &Database,
+    metric_type: Option<&str>,
+    baseline_days: u32,
+    threshold: Threshold,
+    method: Method,
+    window_days: u32,
+) -> Result<AnomalyResult> {
     let types_to_scan: Vec<String> = if let Some(t) = metric_type {
+        if t == JOURNAL_TYPE {
+            crate::fail!(
+                validation,
+                "journal notes have no values to check for anomalies"
+            );
+        }
         vec![t.to_string()]
     } else {
-        db.distinct_metric_types()?
+        all_types(db)?
+    };
+    scan(
+        db,
+        &types_to_scan,
+        baseline_days,
+        threshold,
+        method,
+        window_days,
+        ScanFilter::ALL_ENTRIES,
+    )
+}
+
+/// `anomaly --all`: scan every type with enough readings in the window and
+/// group the findings by type, most severe first. Types whose readings do not
+/// vary are skipped, and medication intakes (always 1 dose) are left out
+/// unless `include_meds` is set.
+pub fn detect_all(
+    db: &Database,
+    baseline_days: u32,
+    threshold: Threshold,
+    method: Method,
+    window_days: u32,
+    include_meds: bool,
+) -> Result<AnomalyResult> {
+    let filter = ScanFilter {
+        include_meds,
+        skip_flat: true,
     };
+    let mut result = scan(
+        db,
+        &all_types(db)?,
+        baseline_days,
+        threshold,
+        method,
+        window_days,
+        filter,
+    )?;
+    result.anomalies.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(b.score.total_cmp(&a.score))
+    });
+    for a in &result.anomalies {
+        match result
+            .by_type
+            .iter_mut()
+            .find(|g| g.metric_type == a.metric_type)
+        {
+            Some(g) => g.anomalies.push(a.clone()),
+            None => result.by_type.push(TypeAnomalies {
+                metric_type: a.metric_type.clone(),
+                anomalies: vec![a.clone()],
+            }),
+        }
+    }
+    Ok(result)
+}
+
+/// Every logged type that carries values.
+fn all_types(db: &Database) -> Result<Vec<String>> {
+    Ok(db
+        .distinct_metric_types()?
+        .into_iter()
+        .filter(|t| t != JOURNAL_TYPE)
+        .collect())
+}
+
+/// Check each of `types_to_scan` with enough readings in the window;
+/// shared by [`detect_with`] and [`detect_all`].
+fn scan(
+    db: &Database,
+    types_to_scan: &[String],
+    baseline_days: u32,
+    threshold: Threshold,
+    method: Method,
+    window_days: u32,
+    filter: ScanFilter,
+) -> Result<AnomalyResult> {
+    let tz = db.timezone();
+    let today = time::local_today(tz.name());
+    let baseline_start = today - Duration::days(baseline_days as i64);
 
     let mut anomalies = Vec::new();
     let mut scanned_types = Vec::new();
     let mut clean_types = Vec::new();
+    let mut low_variance_types = Vec::new();
 
-    for metric in &types_to_scan {
-        // Widen the query by ±1 day to capture entries near day boundaries
-        // (UTC storage vs local timezone). In-memory filters below use local
-        // timezone for correct classification.
-        let entries = db.query_all(
-            Some(metric),
-            Some(baseline_start - Duration::days(1)),
-            Some(today + Duration::days(1)),
-        )?;
+    for metric in types_to_scan {
+        let mut entries = db.query_all(Some(metric), Some(baseline_start), Some(today))?;
+        if !filter.include_meds {
+            entries.retain(|e| e.category != Category::Medication);
+        }
 
         if entries.len() < MIN_DATA_POINTS {
             continue;
         }
 
-        scanned_types.push(metric.clone());
-
-        // Separate today's entries from baseline (filter by local date)
-        let baseline_values: Vec<f64> = entries
-            .iter()
-            .filter(|e| {
-                let d = e.timestamp.with_timezone(&Local).date_naive();
-                d >= baseline_start && d < today
-            })
-            .map(|e| e.value)
-            .collect();
-
-        if baseline_values.len() < MIN_DATA_POINTS {
-            continue;
+        if filter.skip_flat {
+            let values: Vec<f64> = entries.iter().map(|e| e.value).collect();
+            if compute_baseline(&values).std_dev < MIN_STD_DEV {
+                low_variance_types.push(metric.clone());
+                continue;
+            }
         }
 
-        let baseline = compute_baseline(&baseline_values);
-        let factor = threshold.factor();
-        let lower = baseline.q1 - factor * baseline.iqr;
-        let upper = baseline.q3 + factor * baseline.iqr;
+        scanned_types.push(metric.clone());
+        let before = anomalies.len();
+
+        if method == Method::Rolling {
+            entries.sort_by_key(|e| e.timestamp);
+            let dated: Vec<_> = entries
+                .iter()
+                .map(|e| (time::local_date(&e.timestamp, tz), e))
+                .collect();
+            for (date, entry) in &dated {
+                let window_start = *date - Duration::days(window_days as i64);
+                let window: Vec<f64> = dated
+                    .iter()
+                    .filter(|(d, _)| *d >= window_start && d < date)
+                    .map(|(_, e)| e.value)
+                    .collect();
+                if window.len() < MIN_ROLLING_POINTS {
+                    continue;
+                }
+                let baseline = compute_baseline(&window);
+                if let Some(a) = check(metric, entry, &baseline, method, threshold) {
+                    anomalies.push(a);
+                }
+            }
+        } else {
+            // Separate today's entries from baseline (filter by local date)
+            let baseline_values: Vec<f64> = entries
+                .iter()
+                .filter(|e| {
+                    let d = time::local_date(&e.timestamp, tz);
+                    d >= baseline_start && d < today
+                })
+                .map(|e| e.value)
+                .collect();
+
+            if baseline_values.len() < MIN_DATA_POINTS {
+                continue;
+            }
 
-        // Check today's entries against baseline
-        let today_entries: Vec<_> = entries
-            .iter()
-            .filter(|e| e.timestamp.with_timezone(&Local).date_naive() == today)
-            .collect();
+            let baseline = compute_baseline(&baseline_values);
 
-        if today_entries.is_empty() {
-            // Nothing logged today — neither anomalous nor clean
-            continue;
-        }
+            // Check today's entries against baseline
+            let today_entries: Vec<_> = entries
+                .iter()
+                .filter(|e| time::local_date(&e.timestamp, tz) == today)
+                .collect();
+
+            if today_entries.is_empty() {
+                // Nothing logged today — neither anomalous nor clean
+                continue;
+            }
 
-        let mut found_anomaly = false;
-        for entry in &today_entries {
-            if entry.value < lower || entry.value > upper {
-                found_anomaly = true;
-                let deviation = if entry.value > upper {
-                    "above"
-                } else {
-                    "below"
-                };
-
-                let severity = compute_severity(entry.value, &baseline, deviation);
-
-                let summary = format!(
-                    "{} {:.1} is {} your normal range ({:.1}-{:.1})",
-                    metric, entry.value, deviation, lower, upper
-                );
-
-                anomalies.push(Anomaly {
-                    metric_type: metric.clone(),
-                    value: entry.value,
-                    timestamp: entry.timestamp,
-                    baseline: baseline.clone(),
-                    bounds: Bounds { lower, upper },
-                    deviation: deviation.to_string(),
-                    severity,
-                    summary,
-                });
+            for entry in today_entries {
+                if let Some(a) = check(metric, entry, &baseline, method, threshold) {
+                    anomalies.push(a);
+                }
             }
         }
 
-        if !found_anomaly {
+        if anomalies.len() == before {
             clean_types.push(metric.clone());
         }
     }
@@ -143,67 +282,128 @@ pub fn detect(
             baseline_end: today.to_string(),
             days: baseline_days,
         },
+        method,
         threshold,
+        window_days: (method == Method::Rolling).then_some(window_days),
         anomalies,
         scanned_types,
         clean_types,
+        by_type: Vec::new(),
+        low_variance_types,
         summary,
     })
 }
 
-/// Compute IQR-based baseline statistics.
+/// Judge one reading against a baseline; `None` when it falls inside the expected
+/// range (and, for glucose, inside the safe range).
+fn check(
+    metric: &str,
+    entry: &Metric,
+    baseline: &Baseline,
+    method: Method,
+    threshold: Threshold,
+) -> Option<Anomaly> {
+    let value = entry.value;
+    let (lower, upper, score, outside) = match method {
+        Method::Iqr => {
+            let factor = threshold.factor();
+            let lower = baseline.q1 - factor * baseline.iqr;
+            let upper = baseline.q3 + factor * baseline.iqr;
+            let deviation = if value > upper { "above" } else { "below" };
+            let score = iqr_distance(value, baseline, deviation).max(0.0);
+            (lower, upper, score, value < lower || value > upper)
+        }
+        Method::Zscore | Method::Rolling => {
+            let limit = threshold.z_limit();
+            // Same zero-spread fallback as the IQR normalizer.
+            let spread = baseline.std_dev.max(baseline.mean.abs() * 0.01).max(0.01);
+            let lower = baseline.mean - limit * baseline.std_dev;
+            let upper = baseline.mean + limit * baseline.std_dev;
+            let z = ((value - baseline.mean) / spread).abs();
+            (lower, upper, z, z > limit)
+        }
+    };
+    // Clinically dangerous readings are severe even inside the personal range.
+    let critical = glucose::is_critical(metric, value);
+    if !outside && !critical {
+        return None;
+    }
+    let severity = match method {
+        _ if critical => Severity::Severe,
+        Method::Iqr => Severity::from_iqr_distance(score),
+        Method::Zscore | Method::Rolling => Severity::from_z(score),
+    };
+    let above = if critical {
+        value > glucose::CRITICAL_HIGH
+    } else {
+        value > upper
+    };
+    let deviation = if above { "above" } else { "below" };
+    let summary = if critical {
+        format!(
+            "{} {:.1} is {} the safe range ({}-{} mg/dL)",
+            metric,
+            value,
+            deviation,
+            glucose::CRITICAL_LOW,
+            glucose::CRITICAL_HIGH
+        )
+    } else {
+        format!(
+            "{} {:.1} is {} your normal range ({:.1}-{:.1})",
+            metric, value, deviation, lower, upper
+        )
+    };
+    Some(Anomaly {
+        metric_type: metric.to_string(),
+        value,
+        timestamp: entry.timestamp,
+        baseline: baseline.clone(),
+        bounds: Bounds { lower, upper },
+        deviation: deviation.to_string(),
+        severity,
+        score,
+        summary,
+    })
+}
+
+/// Compute baseline statistics (quartiles plus mean and sample standard deviation).
 fn compute_baseline(values: &[f64]) -> Baseline {
     let mut sorted = values.to_vec();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted.sort_by(f64::total_cmp);
 
     let median = percentile(&sorted, 50.0);
     let q1 = percentile(&sorted, 25.0);
     let q3 = percentile(&sorted, 75.0);
     let iqr = q3 - q1;
 
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std_dev = if values.len() > 1 {
+        (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+    } else {
+        0.0
+    };
+
     Baseline {
         q1,
         median,
         q3,
         iqr,
+        mean,
+        std_dev,
     }
 }
 
-/// Compute percentile using linear interpolation.
-fn percentile(sorted: &[f64], p: f64) -> f64 {
-    if sorted.is_empty() {
-        return 0.0;
-    }
-    if sorted.len() == 1 {
-        return sorted[0];
-    }
-    let k = (p / 100.0) * (sorted.len() - 1) as f64;
-    let f = k.floor() as usize;
-    let c = k.ceil() as usize;
-    if f == c {
-        sorted[f]
-    } else {
-        sorted[f] + (k - f as f64) * (sorted[c] - sorted[f])
-    }
-}
-
-/// Determine severity based on how far the value is from bounds.
-fn compute_severity(value: f64, baseline: &Baseline, deviation: &str) -> Severity {
+/// Distance past the nearest quartile, in IQR units.
+fn iqr_distance(value: f64, baseline: &Baseline, deviation: &str) -> f64 {
     // Use IQR as normalizer, but fall back to 1% of median for zero-IQR baselines
     // (common when a metric has constant values, e.g., fixed medication doses).
     let normalizer = baseline.iqr.max(baseline.median.abs() * 0.01).max(0.01);
-    let distance = if deviation == "above" {
+    if deviation == "above" {
         (value - baseline.q3) / normalizer
     } else {
         (baseline.q1 - value) / normalizer
-    };
-
-    if distance > 2.0 {
-        Severity::Alert
-    } else if distance > 1.5 {
-        Severity::Warning
-    } else {
-        Severity::Info
     }
 }
 
@@ -262,12 +462,28 @@ mod tests {
             median: 72.0,
             q3: 72.0,
             iqr: 0.0,
+            mean: 72.0,
+            std_dev: 0.0,
         };
-        // A tiny deviation of 0.1 from a zero-IQR baseline should NOT be Alert
-        let severity = compute_severity(72.1, &baseline, "above");
-        assert!(
-            !matches!(severity, Severity::Alert),
-            "tiny deviation from zero-IQR baseline should not be Alert"
-        );
+        // A tiny deviation of 0.1 from a zero-IQR baseline should NOT be severe
+        let distance = iqr_distance(72.1, &baseline, "above");
+        assert_eq!(Severity::from_iqr_distance(distance), Severity::Mild);
+    }
+
+    #[test]
+    fn test_compute_baseline_mean_std() {
+        let b = compute_baseline(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert!((b.mean - 5.0).abs() < 1e-9);
+        assert!((b.std_dev - 2.138).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_severity_levels() {
+        assert_eq!(Severity::from_z(2.6), Severity::Mild);
+        assert_eq!(Severity::from_z(-3.2), Severity::Moderate);
+        assert_eq!(Severity::from_z(4.1), Severity::Severe);
+        assert_eq!(Severity::from_iqr_distance(1.2), Severity::Mild);
+        assert_eq!(Severity::from_iqr_distance(3.0), Severity::Moderate);
+        assert_eq!(Severity::from_iqr_distance(4.6), Severity::Severe);
     }
 }
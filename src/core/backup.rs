@@ -0,0 +1,279 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::Serialize;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::db::Database;
+use crate::models::config::Config;
+
+#[derive(Debug, Serialize)]
+pub struct BackupResult {
+    pub path: String,
+    pub size_bytes: u64,
+    pub timestamp: DateTime<Utc>,
+    pub compressed: bool,
+    /// Older backups removed to honour `backup.keep`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pruned: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreResult {
+    pub file: String,
+    pub path: String,
+    pub schema_version: i64,
+    /// Where the replaced database was kept, if there was one.
+    pub previous: Option<String>,
+}
+
+/// Default backup location: `<data_dir>/backups/data-<timestamp>.db[.gz]`.
+pub fn default_path(data_dir: &Path, now: DateTime<Utc>, compress: bool) -> PathBuf {
+    let ext = if compress { "db.gz" } else { "db" };
+    data_dir
+        .join("backups")
+        .join(format!("data-{}.{}", now.format("%Y%m%d-%H%M%S"), ext))
+}
+
+/// Delete all but the newest `keep` timestamped backups in `dir`, returning
+/// the removed paths. Other files are left alone; `keep == 0` removes nothing.
+pub fn prune(dir: &Path, keep: u32) -> Result<Vec<PathBuf>> {
+    if keep == 0 || !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<(String, PathBuf)> = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if let Some(stamp) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(backup_stamp)
+        {
+            backups.push((stamp.to_string(), path));
+        }
+    }
+    backups.sort_by(|a, b| b.cmp(a));
+
+    let mut removed = Vec::new();
+    for (_, path) in backups.into_iter().skip(keep as usize) {
+        std::fs::remove_file(&path)?;
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
+/// The `YYYYMMDD-HHMMSS` part of a default backup file name
+/// (`data-<stamp>.db[.gz]`, or the older `openvital-<stamp>...`).
+fn backup_stamp(name: &str) -> Option<&str> {
+    let rest = name
+        .strip_prefix("data-")
+        .or_else(|| name.strip_prefix("openvital-"))?;
+    let stamp = rest
+        .strip_suffix(".db")
+        .or_else(|| rest.strip_suffix(".db.gz"))?;
+    let valid = stamp.len() == 15
+        && stamp
+            .char_indices()
+            .all(|(i, c)| if i == 8 { c == '-' } else { c.is_ascii_digit() });
+    valid.then_some(stamp)
+}
+
+/// Copy the live database to `dest`, gzip-compressing it when `compress` is set.
+pub fn backup(db: &Database, dest: &Path, compress: bool) -> Result<BackupResult> {
+    let timestamp = Utc::now();
+    if let Some(parent) = dest.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if compress {
+        let tmp = sibling(dest, "tmp");
+        let _ = std::fs::remove_file(&tmp);
+        let written = db.backup_to(&tmp).and_then(|_| {
+            let mut encoder = GzEncoder::new(File::create(dest)?, Compression::default());
+            io::copy(&mut File::open(&tmp)?, &mut encoder)?;
+            encoder.finish()?;
+            Ok(())
+        });
+        let _ = std::fs::remove_file(&tmp);
+        written?;
+    } else {
+        db.backup_to(dest)?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dest, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(BackupResult {
+        path: dest.display().to_string(),
+        size_bytes: std::fs::metadata(dest)?.len(),
+        timestamp,
+        compressed: compress,
+        pruned: Vec::new(),
+    })
+}
+
+/// Replace the database at `db_path` with the backup at `src` (plain or `.gz`),
+/// after checking the backup is a valid OpenVital database. The replaced
+/// database is kept as `<db_path>.pre-restore-<timestamp>`, so earlier
+/// pre-restore copies survive. `key` unlocks an encrypted backup.
+pub fn restore(src: &Path, db_path: &Path, key: Option<&str>) -> Result<RestoreResult> {
+    if !src.exists() {
+        crate::fail!(not_found, "backup file not found: {}", src.display());
+    }
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // Stage next to the target so the final rename stays on one filesystem.
+    let staged = sibling(db_path, "restore");
+    let _ = std::fs::remove_file(&staged);
+    let result = stage(src, &staged).and_then(|_| {
+        let schema_version = Database::verify_file(&staged, key)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        }
+        let previous = if db_path.exists() {
+            let previous = pre_restore_path(db_path, Utc::now());
+            std::fs::rename(db_path, &previous)?;
+            // A WAL left behind by a crashed writer belongs to the old file.
+            for suffix in ["-wal", "-shm"] {
+                let mut side = db_path.as_os_str().to_owned();
+                side.push(suffix);
+                if Path::new(&side).exists() {
+                    let mut moved = previous.as_os_str().to_owned();
+                    moved.push(suffix);
+                    std::fs::rename(&side, &moved)?;
+                }
+            }
+            if let Err(e) = std::fs::rename(&staged, db_path) {
+                let _ = std::fs::rename(&previous, db_path);
+                return Err(e.into());
+            }
+            Some(previous.display().to_string())
+        } else {
+            std::fs::rename(&staged, db_path)?;
+            None
+        };
+        Ok((schema_version, previous))
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    let (schema_version, previous) = result?;
+
+    Ok(RestoreResult {
+        file: src.display().to_string(),
+        path: db_path.display().to_string(),
+        schema_version,
+        previous,
+    })
+}
+
+/// Encrypt the unencrypted database at `db_path` in place with `key`.
+/// The encrypted copy is verified before it replaces the original.
+pub fn encrypt(db_path: &Path, key: &str) -> Result<()> {
+    let staged = sibling(db_path, "encrypting");
+    let _ = std::fs::remove_file(&staged);
+    let result = Database::open(db_path)
+        .and_then(|db| db.export_encrypted(&staged, key))
+        .and_then(|_| Database::verify_file(&staged, Some(key)))
+        .and_then(|_| {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+            }
+            std::fs::rename(&staged, db_path)?;
+            Ok(())
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    result
+}
+
+/// Decrypt the database at `db_path` in place, given its `key`.
+/// The plaintext copy is verified before it replaces the original.
+pub fn decrypt(db_path: &Path, key: &str) -> Result<()> {
+    let staged = sibling(db_path, "decrypting");
+    let _ = std::fs::remove_file(&staged);
+    let result = Database::open_encrypted(db_path, "UTC", Some(key))
+        .and_then(|db| db.export_decrypted(&staged))
+        .and_then(|_| Database::verify_file(&staged, None))
+        .and_then(|_| {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+            }
+            std::fs::rename(&staged, db_path)?;
+            Ok(())
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    result
+}
+
+/// Encrypt or decrypt the database at `db_path` so it matches `enable`, using
+/// the configured key source, and update `config.encryption.enabled`.
+/// Returns whether anything changed; the caller saves the config.
+pub fn set_encryption(config: &mut Config, db_path: &Path, enable: bool) -> Result<bool> {
+    if config.encryption.enabled == enable {
+        return Ok(false);
+    }
+    let key = config.encryption.key_source.resolve()?;
+    if db_path.exists() {
+        if enable {
+            encrypt(db_path, &key)?;
+        } else {
+            decrypt(db_path, &key)?;
+        }
+    }
+    config.encryption.enabled = enable;
+    Ok(true)
+}
+
+fn stage(src: &Path, staged: &Path) -> Result<()> {
+    if src.extension().is_some_and(|e| e == "gz") {
+        let mut decoder = GzDecoder::new(File::open(src)?);
+        io::copy(&mut decoder, &mut File::create(staged)?).map_err(|e| {
+            crate::error::io(&e, format!("cannot decompress {}: {}", src.display(), e))
+        })?;
+    } else {
+        std::fs::copy(src, staged)?;
+    }
+    Ok(())
+}
+
+/// `<db_path>.pre-restore-<timestamp>`, with `-2`, `-3`, … added when a
+/// restore in the same second already took the name.
+pub fn pre_restore_path(db_path: &Path, now: DateTime<Utc>) -> PathBuf {
+    let base = format!("pre-restore-{}", now.format("%Y%m%d-%H%M%S"));
+    let mut path = sibling(db_path, &base);
+    let mut n = 2;
+    while path.exists() {
+        path = sibling(db_path, &format!("{base}-{n}"));
+        n += 1;
+    }
+    path
+}
+
+/// `path` with an extra suffix, e.g. `data.db` → `data.db.restore`.
+pub(crate) fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::core::query;
+use crate::core::trend::{self, TrendOptions, TrendPeriod, TrendResult};
+use crate::db::Database;
+use crate::models::config::BpThresholds;
+use crate::models::metric::Metric;
+
+/// Name of the virtual combined type; `bp` is accepted too.
+pub const BP_TYPE: &str = "blood_pressure";
+pub const SYSTOLIC: &str = "bp_systolic";
+pub const DIASTOLIC: &str = "bp_diastolic";
+
+/// Systolic and diastolic entries this close together form one reading.
+const PAIR_WINDOW: chrono::Duration = chrono::Duration::minutes(1);
+
+/// A lone systolic or diastolic entry is staged with a partner logged this
+/// close, so readings entered as two separate `log` calls still get one.
+pub const CATEGORY_WINDOW_SECS: i64 = 5 * 60;
+
+/// Whether `metric_type` names the combined blood pressure view.
+pub fn is_bp(metric_type: &str) -> bool {
+    metric_type == BP_TYPE || metric_type == "bp"
+}
+
+/// Hypertension stage of a reading.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BpCategory {
+    Normal,
+    Elevated,
+    #[serde(rename = "stage_1")]
+    Stage1,
+    #[serde(rename = "stage_2")]
+    Stage2,
+    Crisis,
+}
+
+impl std::fmt::Display for BpCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Normal => write!(f, "normal"),
+            Self::Elevated => write!(f, "elevated"),
+            Self::Stage1 => write!(f, "stage 1"),
+            Self::Stage2 => write!(f, "stage 2"),
+            Self::Crisis => write!(f, "crisis"),
+        }
+    }
+}
+
+/// Stage a systolic/diastolic pair: the highest stage either side reaches.
+pub fn classify(systolic: f64, diastolic: f64, t: &BpThresholds) -> BpCategory {
+    if systolic >= t.crisis_systolic || diastolic >= t.crisis_diastolic {
+        BpCategory::Crisis
+    } else if systolic >= t.stage2_systolic || diastolic >= t.stage2_diastolic {
+        BpCategory::Stage2
+    } else if systolic >= t.stage1_systolic || diastolic >= t.stage1_diastolic {
+        BpCategory::Stage1
+    } else if systolic >= t.elevated_systolic {
+        BpCategory::Elevated
+    } else {
+        BpCategory::Normal
+    }
+}
+
+/// Stage of the reading a stored systolic or diastolic entry belongs to;
+/// `None` for other types or when no partner lies within [`CATEGORY_WINDOW_SECS`].
+pub fn category_of(db: &Database, m: &Metric, t: &BpThresholds) -> Result<Option<BpCategory>> {
+    if m.metric_type != SYSTOLIC && m.metric_type != DIASTOLIC {
+        return Ok(None);
+    }
+    let Some(partner) = db.find_paired_bp_entry(&m.id, CATEGORY_WINDOW_SECS)? else {
+        return Ok(None);
+    };
+    let (systolic, diastolic) = if m.metric_type == SYSTOLIC {
+        (m.value, partner.value)
+    } else {
+        (partner.value, m.value)
+    };
+    Ok(Some(classify(systolic, diastolic, t)))
+}
+
+/// One blood pressure reading; a side is `None` when its entry has no partner.
+#[derive(Debug, Clone, Serialize)]
+pub struct BpReading {
+    pub timestamp: DateTime<Utc>,
+    pub systolic: Option<f64>,
+    pub diastolic: Option<f64>,
+    pub unit: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl BpReading {
+    fn from_entry(m: &Metric) -> Self {
+        let systolic = m.metric_type == SYSTOLIC;
+        Self {
+            timestamp: m.timestamp,
+            systolic: systolic.then_some(m.value),
+            diastolic: (!systolic).then_some(m.value),
+            unit: "mmHg",
+            note: m.note.clone(),
+        }
+    }
+
+    pub fn is_paired(&self) -> bool {
+        self.systolic.is_some() && self.diastolic.is_some()
+    }
+}
+
+/// Zip systolic and diastolic entries logged within a minute of each other
+/// into readings, newest first. Each entry pairs with the closest unused
+/// partner; entries left over become one-sided readings.
+pub fn pair(systolic: &[Metric], diastolic: &[Metric]) -> Vec<BpReading> {
+    let mut used = vec![false; diastolic.len()];
+    let mut readings: Vec<BpReading> = systolic
+        .iter()
+        .map(|s| {
+            let mut reading = BpReading::from_entry(s);
+            let partner = diastolic
+                .iter()
+                .enumerate()
+                .filter(|(i, d)| !used[*i] && (d.timestamp - s.timestamp).abs() < PAIR_WINDOW)
+                .min_by_key(|(_, d)| (d.timestamp - s.timestamp).abs());
+            if let Some((i, d)) = partner {
+                used[i] = true;
+                reading.diastolic = Some(d.value);
+                if reading.note.is_none() {
+                    reading.note = d.note.clone();
+                }
+            }
+            reading
+        })
+        .collect();
+    readings.extend(
+        diastolic
+            .iter()
+            .zip(&used)
+            .filter(|(_, used)| !**used)
+            .map(|(d, _)| BpReading::from_entry(d)),
+    );
+    readings.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    readings
+}
+
+/// The last `n` readings, from the last `n` entries of each component
+/// (carrying all of `tags`, if any).
+pub fn recent(db: &Database, n: u32, tags: &[String]) -> Result<Vec<BpReading>> {
+    let systolic = query::recent(db, SYSTOLIC, n, tags)?;
+    let diastolic = query::recent(db, DIASTOLIC, n, tags)?;
+    let mut readings = pair(&systolic, &diastolic);
+    readings.truncate(n as usize);
+    Ok(readings)
+}
+
+/// Readings in a date range with tags, newest first; the newest `last` when given.
+pub fn between(
+    db: &Database,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    last: Option<u32>,
+    tags: &[String],
+) -> Result<Vec<BpReading>> {
+    let newest_first = |t| -> Result<Vec<Metric>> {
+        let mut entries = db.query_tagged(Some(t), from, to, tags)?;
+        entries.reverse();
+        Ok(entries)
+    };
+    let mut readings = pair(&newest_first(SYSTOLIC)?, &newest_first(DIASTOLIC)?);
+    if let Some(n) = last {
+        readings.truncate(n as usize);
+    }
+    Ok(readings)
+}
+
+/// Both component entries in a date range with tags, oldest first.
+pub fn entries(
+    db: &Database,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<Vec<Metric>> {
+    let mut entries = db.query_tagged(Some(SYSTOLIC), from, to, tags)?;
+    entries.extend(db.query_tagged(Some(DIASTOLIC), from, to, tags)?);
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(entries)
+}
+
+/// Separate systolic and diastolic trends over the same periods.
+#[derive(Debug, Serialize)]
+pub struct BpTrend {
+    #[serde(rename = "type")]
+    pub metric_type: &'static str,
+    pub systolic: TrendResult,
+    pub diastolic: TrendResult,
+}
+
+pub fn trend(db: &Database, period: TrendPeriod, opts: TrendOptions) -> Result<BpTrend> {
+    Ok(BpTrend {
+        metric_type: BP_TYPE,
+        systolic: trend::compute_with(db, SYSTOLIC, period.clone(), opts.clone())?,
+        diastolic: trend::compute_with(db, DIASTOLIC, period, opts)?,
+    })
+}
@@ -1,26 +1,93 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
 
 use anyhow::Result;
-use chrono::{Duration, Local};
+use chrono::{Duration, NaiveDate};
 use serde::Serialize;
 
 use crate::core::anomaly;
+use crate::core::cycle::{self, CycleStatus};
 use crate::core::status;
-use crate::db::Database;
+use crate::core::time;
+use crate::core::units;
+use crate::db::{Database, SortOrder};
 use crate::models::anomaly::{Anomaly, Threshold};
 use crate::models::config::Config;
 
+/// A part of the briefing that `--sections` can include or leave out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Metrics,
+    Goals,
+    Meds,
+    Profile,
+}
+
+impl Section {
+    pub const ALL: [Section; 4] = [Self::Metrics, Self::Goals, Self::Meds, Self::Profile];
+}
+
+impl FromStr for Section {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "metrics" => Ok(Self::Metrics),
+            "goals" => Ok(Self::Goals),
+            "meds" | "medications" => Ok(Self::Meds),
+            "profile" => Ok(Self::Profile),
+            _ => crate::fail!(
+                validation,
+                "invalid section: {} (expected metrics/goals/meds/profile)",
+                s
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Metrics => write!(f, "metrics"),
+            Self::Goals => write!(f, "goals"),
+            Self::Meds => write!(f, "meds"),
+            Self::Profile => write!(f, "profile"),
+        }
+    }
+}
+
+/// The briefing. Every key is always present; sections left out via
+/// `sections` are empty (`{}`, `[]` or `null`).
 #[derive(Debug, Serialize)]
 pub struct ContextResult {
     pub generated_at: String,
     pub period: ContextPeriod,
+    pub sections: Vec<Section>,
     pub summary: String,
+    pub profile: Option<ProfileContext>,
     pub metrics: HashMap<String, MetricContext>,
     pub goals: Vec<GoalContext>,
     pub medications: Option<MedicationContext>,
+    /// Current cycle day and prediction, when `cycle` entries exist (metrics section).
+    pub cycle: Option<CycleStatus>,
     pub streaks: status::Streaks,
+    pub consecutive_pain_alerts: Vec<status::ConsecutivePainAlert>,
     pub alerts: Vec<AlertItem>,
     pub anomalies: Vec<Anomaly>,
+    /// Plain-language summary, filled in by `context --narrative`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub narrative: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileContext {
+    pub height_cm: Option<f64>,
+    pub latest_weight_kg: Option<f64>,
+    pub bmi: Option<f64>,
+    pub bmi_category: Option<&'static str>,
+    pub birth_year: Option<u16>,
+    pub gender: Option<String>,
+    pub conditions: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -35,6 +102,10 @@ pub struct MetricContext {
     pub latest: Option<LatestValue>,
     pub trend: Option<TrendInfo>,
     pub stats: MetricStats,
+    /// Days in the period with an entry at or above the pain alert threshold
+    /// (`pain` and `soreness` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elevated_days: Option<u32>,
     pub summary: String,
 }
 
@@ -50,6 +121,8 @@ pub struct TrendInfo {
     pub direction: String,
     pub rate: f64,
     pub rate_unit: String,
+    /// Last daily average minus the first one in the window.
+    pub change: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -68,6 +141,8 @@ pub struct GoalContext {
     pub timeframe: String,
     pub current: Option<f64>,
     pub is_met: bool,
+    /// Progress toward the target, e.g. "3.0 kg to go".
+    pub progress: Option<String>,
     pub summary: String,
 }
 
@@ -77,9 +152,21 @@ pub struct MedicationContext {
     pub adherence_today: f64,
     pub adherence_7d: Option<f64>,
     pub medications: Vec<MedBrief>,
+    /// Medications started or stopped within the period, oldest first.
+    pub changes: Vec<MedChange>,
     pub summary: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct MedChange {
+    pub name: String,
+    /// "started" or "stopped".
+    pub change: &'static str,
+    pub date: NaiveDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct MedBrief {
     pub name: String,
@@ -102,7 +189,20 @@ pub fn compute(
     days: u32,
     type_filter: Option<&[&str]>,
 ) -> Result<ContextResult> {
-    let today = Local::now().date_naive();
+    compute_with(db, config, days, type_filter, &Section::ALL)
+}
+
+/// Compute the briefing with only the given `sections` filled in.
+/// `type_filter` narrows the metrics, goals and anomalies.
+pub fn compute_with(
+    db: &Database,
+    config: &Config,
+    days: u32,
+    type_filter: Option<&[&str]>,
+    sections: &[Section],
+) -> Result<ContextResult> {
+    let tz = db.timezone();
+    let today = time::local_today(tz.name());
     let start_date = today - Duration::days(days as i64);
     let now = chrono::Utc::now();
 
@@ -120,21 +220,13 @@ pub fn compute(
 
     // 2. Build per-metric context
     let mut metrics = HashMap::new();
-    for metric_type in &types {
-        // Widen query by ±1 day to capture entries near day boundaries
-        // (UTC storage vs local timezone), then filter in-memory.
-        let raw_entries = db.query_all(
-            Some(metric_type),
-            Some(start_date - Duration::days(1)),
-            Some(today + Duration::days(1)),
-        )?;
-        let entries: Vec<_> = raw_entries
-            .into_iter()
-            .filter(|e| {
-                let d = e.timestamp.with_timezone(&Local).date_naive();
-                d >= start_date && d <= today
-            })
-            .collect();
+    let metric_types: &[&str] = if sections.contains(&Section::Metrics) {
+        &types
+    } else {
+        &[]
+    };
+    for metric_type in metric_types {
+        let entries = db.query_all(Some(metric_type), Some(start_date), Some(today))?;
         if entries.is_empty() {
             continue;
         }
@@ -163,7 +255,19 @@ pub fn compute(
         // Compute trend from windowed entries only (not via trend::compute
         // which fetches all history and limits by period count, not date).
         let trend = if count >= 2 {
-            Some(compute_windowed_trend(&entries))
+            Some(compute_windowed_trend(&entries, tz))
+        } else {
+            None
+        };
+
+        let elevated_days = if *metric_type == "pain" || *metric_type == "soreness" {
+            let threshold = config.alerts.pain_threshold as f64;
+            let days: BTreeSet<NaiveDate> = entries
+                .iter()
+                .filter(|e| e.value >= threshold)
+                .map(|e| time::local_date(&e.timestamp, tz))
+                .collect();
+            Some(days.len() as u32)
         } else {
             None
         };
@@ -177,13 +281,25 @@ pub fn compute(
                 latest,
                 trend,
                 stats,
+                elevated_days,
                 summary,
             },
         );
     }
 
+    // Cycle day, for reading pain, mood etc. against the cycle
+    let cycle = if metric_types.contains(&cycle::CYCLE_TYPE) {
+        Some(cycle::status_on(db, config.cycle.gap_days, today)?)
+    } else {
+        None
+    };
+
     // 3. Goals
-    let goal_statuses = crate::core::goal::goal_status(db, None)?;
+    let goal_statuses = if sections.contains(&Section::Goals) {
+        crate::core::goal::goal_status(db, None)?
+    } else {
+        Vec::new()
+    };
     let goals: Vec<GoalContext> = goal_statuses
         .into_iter()
         .filter(|g| type_filter.is_none() || type_filter.unwrap().contains(&g.metric_type.as_str()))
@@ -208,80 +324,87 @@ pub fn compute(
                 timeframe: g.timeframe,
                 current: g.current_value,
                 is_met: g.is_met,
+                progress: g.progress,
                 summary,
             }
         })
         .collect();
 
     // 4. Medications
-    let medications = match crate::core::med::adherence_status(db, None, 7) {
-        Ok(med_statuses) if !med_statuses.is_empty() => {
-            let active_count = med_statuses.len();
-            let total_scheduled: usize = med_statuses
-                .iter()
-                .filter(|s| s.adherent_today.is_some())
-                .count();
-            let adherent_count: usize = med_statuses
-                .iter()
-                .filter(|s| s.adherent_today == Some(true))
-                .count();
-            let adherence_today = if total_scheduled > 0 {
-                adherent_count as f64 / total_scheduled as f64
-            } else {
-                1.0
-            };
-
-            let adherence_values: Vec<f64> =
-                med_statuses.iter().filter_map(|s| s.adherence_7d).collect();
-            let adherence_7d = if adherence_values.is_empty() {
-                None
-            } else {
-                Some(adherence_values.iter().sum::<f64>() / adherence_values.len() as f64)
-            };
+    let (med_statuses, changes) = if sections.contains(&Section::Meds) {
+        (
+            crate::core::med::adherence_status(db, config, None, 7)?,
+            medication_changes(db, start_date, today)?,
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    let medications = if !med_statuses.is_empty() || !changes.is_empty() {
+        let active_count = med_statuses.len();
+        let total_scheduled: usize = med_statuses
+            .iter()
+            .filter(|s| s.adherent_today.is_some())
+            .count();
+        let adherent_count: usize = med_statuses
+            .iter()
+            .filter(|s| s.adherent_today == Some(true))
+            .count();
+        let adherence_today = if total_scheduled > 0 {
+            adherent_count as f64 / total_scheduled as f64
+        } else {
+            1.0
+        };
 
-            let meds: Vec<MedBrief> = med_statuses
-                .iter()
-                .map(|s| MedBrief {
-                    name: s.name.clone(),
-                    adherent_today: s.adherent_today,
-                    adherence_7d: s.adherence_7d,
-                    streak: s.streak_days,
-                })
-                .collect();
+        let adherence_values: Vec<f64> =
+            med_statuses.iter().filter_map(|s| s.adherence_7d).collect();
+        let adherence_7d = if adherence_values.is_empty() {
+            None
+        } else {
+            Some(adherence_values.iter().sum::<f64>() / adherence_values.len() as f64)
+        };
 
-            let summary = format!(
-                "{} active medication(s). {}/{} taken today.{}",
-                active_count,
-                adherent_count,
-                total_scheduled,
-                adherence_7d
-                    .map(|a| format!(" {:.0}% adherence (7d).", a * 100.0))
-                    .unwrap_or_default()
-            );
-
-            Some(MedicationContext {
-                active_count,
-                adherence_today,
-                adherence_7d,
-                medications: meds,
-                summary,
+        let meds: Vec<MedBrief> = med_statuses
+            .iter()
+            .map(|s| MedBrief {
+                name: s.name.clone(),
+                adherent_today: s.adherent_today,
+                adherence_7d: s.adherence_7d,
+                streak: s.streak_days,
             })
+            .collect();
+
+        let mut summary = format!(
+            "{} active medication(s). {}/{} taken today.{}",
+            active_count,
+            adherent_count,
+            total_scheduled,
+            adherence_7d
+                .map(|a| format!(" {:.0}% adherence (7d).", a * 100.0))
+                .unwrap_or_default()
+        );
+        if !changes.is_empty() {
+            summary.push_str(&format!(" {} change(s) this period.", changes.len()));
         }
-        _ => None,
+
+        Some(MedicationContext {
+            active_count,
+            adherence_today,
+            adherence_7d,
+            medications: meds,
+            changes,
+            summary,
+        })
+    } else {
+        None
     };
 
     // 5. Streaks
-    let streaks = status::compute_streaks(db, today)?;
+    let mut streaks = status::compute_streaks(db, today)?;
+    streaks.by_type = status::compute_type_streaks(db, config, today)?;
 
     // 6. Alerts
     let mut alerts = Vec::new();
-    // Widen by ±1 day for timezone safety, then filter in-memory
-    let today_entries_raw =
-        db.query_by_date_range(today - Duration::days(1), today + Duration::days(1))?;
-    let today_entries: Vec<_> = today_entries_raw
-        .into_iter()
-        .filter(|e| e.timestamp.with_timezone(&Local).date_naive() == today)
-        .collect();
+    let today_entries = db.query_by_date(today, SortOrder::Asc)?;
     let threshold = config.alerts.pain_threshold as f64;
     for entry in &today_entries {
         if (entry.metric_type == "pain" || entry.metric_type == "soreness")
@@ -297,8 +420,8 @@ pub fn compute(
         }
     }
 
-    let consecutive = status::check_consecutive_pain(db, today, &config.alerts)?;
-    for alert in &consecutive {
+    let consecutive_pain_alerts = status::check_consecutive_pain(db, today, &config.alerts)?;
+    for alert in &consecutive_pain_alerts {
         alerts.push(AlertItem {
             alert_type: "consecutive_pain".to_string(),
             message: format!(
@@ -324,7 +447,27 @@ pub fn compute(
         });
     }
 
-    // 8. Generate top-level summary
+    // 8. Profile
+    let profile = if sections.contains(&Section::Profile) {
+        let latest_weight_kg = db
+            .query_all(Some("weight"), None, Some(today))?
+            .last()
+            .map(|m| m.value);
+        let (bmi, bmi_category) = status::bmi(config.profile.height_cm, latest_weight_kg);
+        Some(ProfileContext {
+            height_cm: config.profile.height_cm,
+            latest_weight_kg,
+            bmi,
+            bmi_category,
+            birth_year: config.profile.birth_year,
+            gender: config.profile.gender.clone(),
+            conditions: config.profile.conditions.clone(),
+        })
+    } else {
+        None
+    };
+
+    // 9. Generate top-level summary
     let summary = generate_top_summary(&metrics, &goals, &medications, &streaks, &anomalies);
 
     Ok(ContextResult {
@@ -334,21 +477,62 @@ pub fn compute(
             end: today.to_string(),
             days,
         },
+        sections: Section::ALL
+            .into_iter()
+            .filter(|s| sections.contains(s))
+            .collect(),
         summary,
+        profile,
         metrics,
         goals,
         medications,
+        cycle,
         streaks,
+        consecutive_pain_alerts,
         alerts,
         anomalies,
+        narrative: None,
     })
 }
 
+/// Medications started or stopped between `from` and `to` (inclusive).
+fn medication_changes(db: &Database, from: NaiveDate, to: NaiveDate) -> Result<Vec<MedChange>> {
+    let tz = db.timezone();
+    let mut changes = Vec::new();
+    for med in db.list_medications(true)? {
+        let started = time::local_date(&med.started_at, tz);
+        if (from..=to).contains(&started) {
+            changes.push(MedChange {
+                name: med.name.clone(),
+                change: "started",
+                date: started,
+                reason: None,
+            });
+        }
+        if let Some(stopped_at) = med.stopped_at {
+            let stopped = time::local_date(&stopped_at, tz);
+            if (from..=to).contains(&stopped) {
+                changes.push(MedChange {
+                    name: med.name.clone(),
+                    change: "stopped",
+                    date: stopped,
+                    reason: med.stop_reason.clone(),
+                });
+            }
+        }
+    }
+    changes.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.name.cmp(&b.name)));
+    Ok(changes)
+}
+
 /// Compute trend direction and rate from entries already filtered to the time window.
-fn compute_windowed_trend(entries: &[crate::models::metric::Metric]) -> TrendInfo {
+fn compute_windowed_trend(
+    entries: &[crate::models::metric::Metric],
+    tz: chrono_tz::Tz,
+) -> TrendInfo {
     let mut day_data: BTreeMap<chrono::NaiveDate, (f64, u32)> = BTreeMap::new();
     for e in entries {
-        let date = e.timestamp.with_timezone(&Local).date_naive();
+        let date = time::local_date(&e.timestamp, tz);
         let entry = day_data.entry(date).or_insert((0.0, 0));
         entry.0 += e.value;
         entry.1 += 1;
@@ -363,6 +547,7 @@ fn compute_windowed_trend(entries: &[crate::models::metric::Metric]) -> TrendInf
             direction: "stable".to_string(),
             rate: 0.0,
             rate_unit: "per day".to_string(),
+            change: 0.0,
         };
     }
 
@@ -382,11 +567,13 @@ fn compute_windowed_trend(entries: &[crate::models::metric::Metric]) -> TrendInf
         "stable"
     };
     let rate = (slope * 10.0).round() / 10.0;
+    let change = avgs[avgs.len() - 1] - avgs[0];
 
     TrendInfo {
         direction: direction.to_string(),
         rate,
         rate_unit: "per day".to_string(),
+        change: (change * 10.0).round() / 10.0,
     }
 }
 
@@ -464,3 +651,219 @@ fn generate_top_summary(
 
     parts.join(" ")
 }
+
+/// Types preferred for the opening sentence of the narrative, in order.
+const NARRATIVE_LEAD: &[&str] = &["weight", "body_fat", "waist"];
+/// Types preferred for the follow-up sentence of the narrative, in order.
+const NARRATIVE_FOLLOW: &[&str] = &["sleep_hours", "sleep", "steps", "cardio", "water"];
+
+/// A few sentences of plain prose summarizing the briefing: the leading
+/// metric's trend and goal, one more metric, goals, pain and medication
+/// adherence. Built from the structured data only, so the same briefing
+/// always yields the same text. Values use the configured unit system.
+pub fn generate_narrative(context: &ContextResult, config: &Config) -> String {
+    let window = match context.period.days {
+        1 => "Over the past day".to_string(),
+        n => format!("Over the past {} days", n),
+    };
+
+    let mut names: Vec<&str> = context
+        .metrics
+        .keys()
+        .map(|k| k.as_str())
+        .filter(|k| *k != "pain" && *k != "soreness")
+        .collect();
+    names.sort_unstable();
+    let has_goal = |t: &str| context.goals.iter().any(|g| g.metric_type == t);
+    let lead = NARRATIVE_LEAD
+        .iter()
+        .copied()
+        .find(|t| names.contains(t))
+        .or_else(|| names.iter().copied().find(|t| has_goal(t)))
+        .or_else(|| names.first().copied());
+    let follow = NARRATIVE_FOLLOW
+        .iter()
+        .copied()
+        .chain(names.iter().copied())
+        .find(|t| Some(*t) != lead && names.contains(t));
+
+    let mut sentences = Vec::new();
+    match lead {
+        Some(t) => sentences.push(format!(
+            "{}, {}.",
+            window,
+            lead_clause(t, &context.metrics[t], context, config)
+        )),
+        None if context.metrics.is_empty() => {
+            sentences.push(format!("{}, no health metrics were logged.", window))
+        }
+        None => {
+            let mut logged: Vec<&str> = context.metrics.keys().map(|k| k.as_str()).collect();
+            logged.sort_unstable();
+            sentences.push(format!("{}, you logged {}.", window, logged.join(" and ")));
+        }
+    }
+
+    if let Some(t) = follow {
+        let m = &context.metrics[t];
+        sentences.push(format!(
+            "{} averaged {}.",
+            capitalize(&display_name(t)),
+            quantity(m.stats.avg, t, m, config)
+        ));
+    }
+
+    let other_goals: Vec<&GoalContext> = context
+        .goals
+        .iter()
+        .filter(|g| Some(g.metric_type.as_str()) != lead)
+        .collect();
+    if !other_goals.is_empty() {
+        let met = other_goals.iter().filter(|g| g.is_met).count();
+        sentences.push(format!(
+            "You are meeting {} of {} {}goal{}.",
+            met,
+            other_goals.len(),
+            if lead.is_some_and(has_goal) {
+                "other "
+            } else {
+                ""
+            },
+            if other_goals.len() == 1 { "" } else { "s" }
+        ));
+    }
+
+    for t in ["pain", "soreness"] {
+        let Some(m) = context.metrics.get(t) else {
+            continue;
+        };
+        let sentence = match m.elevated_days.unwrap_or(0) {
+            0 => format!("{} stayed low (avg {:.1}).", capitalize(t), m.stats.avg),
+            n => format!(
+                "{} was elevated (avg {:.1}) on {} day{}.",
+                capitalize(t),
+                m.stats.avg,
+                n,
+                if n == 1 { "" } else { "s" }
+            ),
+        };
+        sentences.push(sentence);
+    }
+
+    if let Some(meds) = &context.medications {
+        let taken: Vec<String> = meds
+            .medications
+            .iter()
+            .filter_map(|b| {
+                b.adherence_7d
+                    .map(|a| format!("{} with {:.0}% adherence", b.name, a * 100.0))
+            })
+            .collect();
+        if !taken.is_empty() {
+            sentences.push(format!("You took {}.", join_and(&taken)));
+        }
+    }
+
+    if sentences.len() == 1 && context.metrics.is_empty() {
+        sentences.push("Log a few entries to see trends here.".to_string());
+    }
+    sentences.join(" ")
+}
+
+/// "your weight has decreased by 0.3 kg to 82.4 kg, putting you on track for
+/// your monthly goal"
+fn lead_clause(t: &str, m: &MetricContext, context: &ContextResult, config: &Config) -> String {
+    let name = display_name(t);
+    let latest = m.latest.as_ref().map_or(m.stats.avg, |l| l.value);
+    let mut clause = match &m.trend {
+        Some(trend) if trend.change != 0.0 => {
+            let change = units::to_display_rate(trend.change.abs(), t, &config.units);
+            format!(
+                "your {} has {} by {} to {}",
+                name,
+                if trend.change < 0.0 {
+                    "decreased"
+                } else {
+                    "increased"
+                },
+                quantity(change, t, m, config),
+                quantity_display(latest, t, m, config)
+            )
+        }
+        Some(_) => format!(
+            "your {} held steady at {}",
+            name,
+            quantity_display(latest, t, m, config)
+        ),
+        None => format!(
+            "your {} was {}",
+            name,
+            quantity_display(latest, t, m, config)
+        ),
+    };
+
+    if let Some(g) = context.goals.iter().find(|g| g.metric_type == t) {
+        let change = m.trend.as_ref().map_or(0.0, |tr| tr.change);
+        let toward = match g.direction.as_str() {
+            "below" => change < 0.0,
+            "above" => change > 0.0,
+            _ => (latest - g.target).abs() < (latest - change - g.target).abs(),
+        };
+        let goal = if g.is_met {
+            format!(", meeting your {} goal", g.timeframe)
+        } else if toward {
+            format!(", putting you on track for your {} goal", g.timeframe)
+        } else if change != 0.0 {
+            format!(", moving away from your {} goal", g.timeframe)
+        } else {
+            format!(", short of your {} goal", g.timeframe)
+        };
+        clause.push_str(&goal);
+    }
+    clause
+}
+
+/// Format a stored (metric-system) value in the configured unit system.
+fn quantity_display(value: f64, t: &str, m: &MetricContext, config: &Config) -> String {
+    let (v, _) = units::to_display(value, t, &config.units);
+    quantity(v, t, m, config)
+}
+
+/// Format an already-converted value with its display unit. Scale and
+/// clock "units" such as `1-10` or `HH:MM` are left off.
+fn quantity(value: f64, t: &str, m: &MetricContext, config: &Config) -> String {
+    let mut unit = units::display_unit(t, &config.units);
+    if unit.is_empty()
+        && let Some(l) = &m.latest
+    {
+        unit = l.unit.clone();
+    }
+    let value = format!("{:.1}", value);
+    match unit.as_str() {
+        "" | "count" => value,
+        "%" => format!("{}%", value),
+        u if u.contains(|c: char| c.is_ascii_digit() || c == ':') => value,
+        u => format!("{} {}", value, u),
+    }
+}
+
+fn display_name(t: &str) -> String {
+    t.replace('_', " ")
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// "a", "a and b", "a, b and c"
+fn join_and(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [one] => one.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
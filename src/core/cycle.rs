@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::core::time;
+use crate::db::Database;
+
+/// Metric type for cycle entries: a flag or flow intensity (1-5) on bleeding days.
+pub const CYCLE_TYPE: &str = "cycle";
+/// Virtual daily series for `trend --correlate`: the day of the cycle (1-based).
+pub const CYCLE_DAY: &str = "cycle_day";
+/// Completed cycles averaged for the length and prediction.
+const AVERAGE_CYCLES: usize = 6;
+
+/// Where the current cycle stands and when the next is expected.
+#[derive(Debug, Serialize)]
+pub struct CycleStatus {
+    /// Cycle starts found in the log.
+    pub cycles_logged: usize,
+    pub current_start: Option<NaiveDate>,
+    pub current_day: Option<u32>,
+    /// Lengths in days of the last completed cycles (up to 6), oldest first.
+    pub recent_lengths: Vec<u32>,
+    pub average_length: Option<f64>,
+    /// Needs at least one completed cycle.
+    pub predicted_next_start: Option<NaiveDate>,
+    /// Negative when the predicted start has passed.
+    pub days_until_next: Option<i64>,
+}
+
+/// Cycle start dates among the days with a `cycle` entry: the first day, and
+/// every day more than `gap_days` after the previous logged day.
+pub fn cycle_starts(days: &BTreeSet<NaiveDate>, gap_days: u32) -> Vec<NaiveDate> {
+    let mut starts = Vec::new();
+    let mut prev: Option<NaiveDate> = None;
+    for &day in days {
+        if prev.is_none_or(|p| (day - p).num_days() > gap_days as i64) {
+            starts.push(day);
+        }
+        prev = Some(day);
+    }
+    starts
+}
+
+/// Local days with a `cycle` entry.
+pub fn logged_days(db: &Database) -> Result<BTreeSet<NaiveDate>> {
+    let tz = db.timezone();
+    Ok(db
+        .query_by_type_asc(CYCLE_TYPE, None)?
+        .iter()
+        .map(|m| time::local_date(&m.timestamp, tz))
+        .collect())
+}
+
+/// Day of the cycle `date` falls in (1 on a start day), or `None` before the first start.
+pub fn cycle_day(starts: &[NaiveDate], date: NaiveDate) -> Option<u32> {
+    starts
+        .iter()
+        .rev()
+        .find(|s| **s <= date)
+        .map(|s| (date - *s).num_days() as u32 + 1)
+}
+
+/// Summarize `starts` (ascending) as of `today`.
+pub fn summarize(starts: &[NaiveDate], today: NaiveDate) -> CycleStatus {
+    let current_start = starts.iter().rev().find(|s| **s <= today).copied();
+    let lengths: Vec<u32> = starts
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_days() as u32)
+        .collect();
+    let recent_lengths = lengths[lengths.len().saturating_sub(AVERAGE_CYCLES)..].to_vec();
+    let average_length = (!recent_lengths.is_empty()).then(|| {
+        let avg = recent_lengths.iter().sum::<u32>() as f64 / recent_lengths.len() as f64;
+        (avg * 10.0).round() / 10.0
+    });
+    let predicted_next_start = current_start
+        .zip(average_length)
+        .map(|(start, avg)| start + chrono::Duration::days(avg.round() as i64));
+    CycleStatus {
+        cycles_logged: starts.len(),
+        current_start,
+        current_day: cycle_day(starts, today),
+        recent_lengths,
+        average_length,
+        predicted_next_start,
+        days_until_next: predicted_next_start.map(|d| (d - today).num_days()),
+    }
+}
+
+/// The cycle status as of today.
+pub fn status(db: &Database, gap_days: u32) -> Result<CycleStatus> {
+    status_on(db, gap_days, time::local_today(db.timezone().name()))
+}
+
+/// The cycle status as of `today`.
+pub fn status_on(db: &Database, gap_days: u32, today: NaiveDate) -> Result<CycleStatus> {
+    let starts = cycle_starts(&logged_days(db)?, gap_days);
+    Ok(summarize(&starts, today))
+}
+
+/// Cycle day for every date from the first cycle start through today, for
+/// correlating other metrics against the cycle.
+pub fn day_series(db: &Database, gap_days: u32) -> Result<BTreeMap<NaiveDate, f64>> {
+    let starts = cycle_starts(&logged_days(db)?, gap_days);
+    let today = time::local_today(db.timezone().name());
+    let Some(&first) = starts.first() else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(first
+        .iter_days()
+        .take_while(|d| *d <= today)
+        .filter_map(|d| cycle_day(&starts, d).map(|n| (d, n as f64)))
+        .collect())
+}
@@ -1,21 +1,48 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, NaiveDate, Utc};
-use serde::Deserialize;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use quick_xml::events::{BytesStart, Event};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 
 use crate::db::Database;
+use crate::models::config::Config;
 use crate::models::med::Medication;
 use crate::models::metric::{Category, Metric, default_unit};
 
+/// Entries to export; `blood_pressure` selects both of its component types.
+pub fn metric_entries(
+    db: &Database,
+    metric_type: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<Vec<Metric>> {
+    match metric_type {
+        Some(t) if crate::core::bp::is_bp(t) => crate::core::bp::entries(db, from, to, tags),
+        _ => db.query_tagged(metric_type, from, to, tags),
+    }
+}
+
 /// Export metrics to CSV format.
 pub fn to_csv(
     db: &Database,
     metric_type: Option<&str>,
     from: Option<NaiveDate>,
     to: Option<NaiveDate>,
+    tags: &[String],
 ) -> Result<String> {
-    let entries = db.query_all(metric_type, from, to)?;
+    render_csv(&metric_entries(db, metric_type, from, to, tags)?)
+}
+
+/// Format entries as CSV.
+pub fn render_csv(entries: &[Metric]) -> Result<String> {
     let mut out = String::from("timestamp,type,value,unit,note,tags,source\n");
-    for e in &entries {
+    for e in entries {
         let note = e.note.as_deref().unwrap_or("");
         let tags = if e.tags.is_empty() {
             "[]".to_string()
@@ -42,13 +69,257 @@ pub fn to_json(
     metric_type: Option<&str>,
     from: Option<NaiveDate>,
     to: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<String> {
+    render_json(&metric_entries(db, metric_type, from, to, tags)?)
+}
+
+/// Format entries as a JSON array.
+pub fn render_json(entries: &[Metric]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(entries)?)
+}
+
+/// Export metrics as JSON Lines: one compact metric object per line.
+pub fn to_jsonl(
+    db: &Database,
+    metric_type: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<String> {
+    render_jsonl(&metric_entries(db, metric_type, from, to, tags)?)
+}
+
+/// Format entries as JSON Lines; no entries give an empty string.
+pub fn render_jsonl(entries: &[Metric]) -> Result<String> {
+    let lines = entries
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// LOINC codes for metric types that have a standard FHIR Observation code.
+pub const LOINC_CODES: &[(&str, &str)] = &[
+    ("weight", "29463-7"),
+    ("body_fat", "41982-0"),
+    ("height", "8302-2"),
+    ("waist", "8280-0"),
+    ("heart_rate", "8867-4"),
+    ("bp_systolic", "8480-6"),
+    ("bp_diastolic", "8462-4"),
+    ("temperature", "8310-5"),
+    ("steps", "55423-8"),
+    ("sleep_hours", "93832-4"),
+    ("calories_in", "9052-2"),
+    ("pain", "72514-3"),
+];
+
+/// Extension URL carrying the raw openvital metric type on unmapped Observations.
+const FHIR_METRIC_TYPE_EXTENSION: &str = "urn:openvital:fhir:metric-type";
+
+fn loinc_code(metric_type: &str) -> Option<&'static str> {
+    LOINC_CODES
+        .iter()
+        .find(|(t, _)| *t == metric_type)
+        .map(|(_, code)| *code)
+}
+
+/// Export metrics as a FHIR R4 `collection` Bundle with one Observation per entry.
+pub fn to_fhir(
+    db: &Database,
+    metric_type: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<String> {
+    render_fhir(&metric_entries(db, metric_type, from, to, tags)?)
+}
+
+/// Format entries as a FHIR R4 `collection` Bundle.
+pub fn render_fhir(entries: &[Metric]) -> Result<String> {
+    let resources: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|m| {
+            let mut obs = serde_json::json!({
+                "resourceType": "Observation",
+                "id": m.id,
+                "status": "final",
+                "effectiveDateTime": m.timestamp.to_rfc3339(),
+                "valueQuantity": {
+                    "value": m.value,
+                    "unit": m.unit,
+                },
+            });
+            match loinc_code(&m.metric_type) {
+                Some(code) => {
+                    obs["code"] = serde_json::json!({
+                        "coding": [{
+                            "system": "http://loinc.org",
+                            "code": code,
+                            "display": m.metric_type,
+                        }],
+                        "text": m.metric_type,
+                    });
+                }
+                None => {
+                    obs["code"] = serde_json::json!({ "text": m.metric_type });
+                    obs["extension"] = serde_json::json!([{
+                        "url": FHIR_METRIC_TYPE_EXTENSION,
+                        "valueString": m.metric_type,
+                    }]);
+                }
+            }
+            if let Some(note) = &m.note {
+                obs["note"] = serde_json::json!([{ "text": note }]);
+            }
+            serde_json::json!({
+                "fullUrl": format!("urn:uuid:{}", m.id),
+                "resource": obs,
+            })
+        })
+        .collect();
+
+    let bundle = serde_json::json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "timestamp": Utc::now().to_rfc3339(),
+        // No `total`: bdl-1 only allows it on searchset and history bundles
+        "entry": resources,
+    });
+    Ok(serde_json::to_string_pretty(&bundle)?)
+}
+
+/// Export metrics as a Parquet file at `output`.
+pub fn to_parquet(
+    db: &Database,
+    metric_type: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+    output: &Path,
+) -> Result<()> {
+    write_parquet(&metric_entries(db, metric_type, from, to, tags)?, output)
+}
+
+/// Write entries to `path` as a Parquet file (see [`crate::core::parquet`]).
+pub fn write_parquet(entries: &[Metric], path: &Path) -> Result<()> {
+    use crate::core::parquet;
+    std::fs::write(
+        path,
+        parquet::encode_metrics(entries, parquet::ROW_GROUP_ROWS)?,
+    )?;
+    Ok(())
+}
+
+/// Format entries as `csv`, `json` (optionally with medications), `jsonl` or `fhir`.
+pub fn render(
+    db: &Database,
+    format: &str,
+    entries: &[Metric],
+    with_medications: bool,
 ) -> Result<String> {
-    let entries = db.query_all(metric_type, from, to)?;
-    Ok(serde_json::to_string_pretty(&entries)?)
+    match format {
+        "csv" => render_csv(entries),
+        "json" if with_medications => render_json_with_medications(db, entries),
+        "json" => render_json(entries),
+        "jsonl" => render_jsonl(entries),
+        "fhir" => render_fhir(entries),
+        "parquet" => crate::fail!(validation, "parquet export needs --output <file>"),
+        other => crate::fail!(
+            validation,
+            "unsupported format: {} (expected csv/json/jsonl/fhir/parquet)",
+            other
+        ),
+    }
+}
+
+/// Entries logged strictly after `since` (all entries when `None`).
+pub fn entries_since(
+    db: &Database,
+    metric_type: Option<&str>,
+    since: Option<DateTime<Utc>>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<Vec<Metric>> {
+    let from = since.map(|t| crate::core::time::local_date(&t, db.timezone()));
+    let mut entries = metric_entries(db, metric_type, from, to, tags)?;
+    if let Some(t) = since {
+        entries.retain(|m| m.timestamp > t);
+    }
+    Ok(entries)
+}
+
+/// Leading bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Whether an export to `path` is gzipped without `--compress`.
+pub fn is_gz_path(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "gz")
+}
+
+/// Write `data` to `path` as a gzip stream.
+pub fn write_compressed(data: &str, path: &Path) -> Result<()> {
+    let mut encoder = GzEncoder::new(std::fs::File::create(path)?, Compression::default());
+    encoder.write_all(data.as_bytes())?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Import input as text, decompressing it first if it starts with the gzip magic bytes.
+pub fn decode_input(bytes: Vec<u8>) -> Result<String> {
+    let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+        let mut out = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut out)
+            .map_err(|e| crate::error::io(&e, format!("cannot decompress input: {}", e)))?;
+        out
+    } else {
+        bytes
+    };
+    match String::from_utf8(bytes) {
+        Ok(s) => Ok(s),
+        Err(_) => crate::fail!(parse, "import input is not valid UTF-8 text"),
+    }
+}
+
+/// State file recording when `export --since-last` last ran.
+pub fn last_export_path() -> PathBuf {
+    Config::data_dir().join("last_export.json")
+}
+
+#[derive(Serialize, Deserialize)]
+struct LastExport {
+    timestamp: DateTime<Utc>,
+}
+
+/// When `export --since-last` last completed, or `None` if it never has.
+pub fn get_last_export_time() -> Result<Option<DateTime<Utc>>> {
+    let path = last_export_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    let state: LastExport = serde_json::from_str(&content)
+        .with_context(|| format!("invalid export state file {}", path.display()))?;
+    Ok(Some(state.timestamp))
+}
+
+/// Record `t` as the time of the last `export --since-last`.
+pub fn set_last_export_time(t: DateTime<Utc>) -> Result<()> {
+    let path = last_export_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string(&LastExport { timestamp: t })?)?;
+    Ok(())
 }
 
 #[derive(Deserialize)]
 struct ImportEntry {
+    /// Kept when present so re-importing an export is idempotent with `dedupe`.
+    #[serde(default)]
+    id: Option<String>,
     #[serde(rename = "type")]
     metric_type: String,
     value: f64,
@@ -62,22 +333,181 @@ struct ImportEntry {
     source: Option<String>,
 }
 
+/// How an imported row that matches a stored entry is merged.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MergeStrategy {
+    /// Keep the stored entry.
+    #[default]
+    Skip,
+    /// Replace the stored entry with the imported row.
+    Overwrite,
+    /// Keep whichever of the two has the later timestamp.
+    Newest,
+}
+
+/// Rows without an id match a stored entry of the same type this close in time.
+const MERGE_TOLERANCE: chrono::Duration = chrono::Duration::seconds(1);
+
+/// Options for CSV and JSON imports.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImportOptions {
+    /// Commit every this many rows instead of all at once.
+    pub chunk_size: Option<usize>,
+    /// Skip rows whose id, or (type, timestamp, value, source), is already stored.
+    pub dedupe: bool,
+    /// Merge rows matching a stored entry: by id, or for rows without one by
+    /// type and timestamp within a second. `Skip` by default, so re-importing
+    /// an export adds nothing.
+    pub merge: MergeStrategy,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportCounts {
+    pub imported: usize,
+    pub skipped_duplicates: usize,
+    /// Stored entries replaced under `overwrite` or `newest`.
+    pub overwritten: usize,
+    /// Malformed rows (too few fields, bad timestamp or value) that were skipped.
+    pub errors: usize,
+}
+
 /// Import metrics from JSON string (array of entries).
 pub fn import_json(db: &Database, json_str: &str) -> Result<usize> {
+    Ok(import_json_with(db, json_str, ImportOptions::default())?.imported)
+}
+
+/// Import metrics from a JSON array with the given chunking and deduplication.
+pub fn import_json_with(
+    db: &Database,
+    json_str: &str,
+    opts: ImportOptions,
+) -> Result<ImportCounts> {
     let entries: Vec<ImportEntry> = serde_json::from_str(json_str)?;
-    let mut count = 0;
-    for e in entries {
-        let mut m = Metric::new(e.metric_type.clone(), e.value);
-        if let Some(ts) = &e.timestamp {
+    insert_chunked(db, entries.into_iter().map(ImportEntry::into_row), opts)
+}
+
+/// Import metrics from JSON Lines, one entry object per line. Blank lines
+/// are ignored; lines that aren't an entry object are skipped and counted
+/// as errors.
+pub fn import_jsonl_with(
+    db: &Database,
+    content: &str,
+    opts: ImportOptions,
+) -> Result<ImportCounts> {
+    let rows = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|line| serde_json::from_str::<ImportEntry>(line)?.into_row());
+    insert_chunked(db, rows, opts)
+}
+
+impl ImportEntry {
+    fn into_row(self) -> Result<ImportRow> {
+        let mut m = Metric::new(self.metric_type, self.value);
+        let has_id = self.id.is_some();
+        if let Some(id) = self.id {
+            m.id = id;
+        }
+        if let Some(ts) = &self.timestamp {
             m.timestamp = ts.parse::<DateTime<Utc>>()?;
         }
-        m.note = e.note;
-        m.tags = e.tags.unwrap_or_default();
-        m.source = e.source.unwrap_or_else(|| "import".to_string());
-        db.insert_metric(&m)?;
-        count += 1;
+        m.note = self.note;
+        m.tags = self.tags.unwrap_or_default();
+        m.source = self.source.unwrap_or_else(|| "import".to_string());
+        Ok(ImportRow { metric: m, has_id })
+    }
+}
+
+/// A parsed row and whether its id came from the input.
+struct ImportRow {
+    metric: Metric,
+    has_id: bool,
+}
+
+/// The stored entry `row` collides with under `opts.merge`, if any. Rows
+/// without an id only match entries stored before the import (up to `mark`),
+/// so rows of one file sharing a timestamp are all kept.
+fn merge_target(db: &Database, row: &ImportRow, mark: i64) -> Result<Option<Metric>> {
+    if row.has_id {
+        return db.get_metric(&row.metric.id);
+    }
+    db.find_metric_near_upto(
+        &row.metric.metric_type,
+        row.metric.timestamp,
+        MERGE_TOLERANCE,
+        mark,
+    )
+}
+
+/// Insert `rows` in one transaction, or in transactions of `opts.chunk_size`
+/// rows. Rows that failed to parse are skipped and counted in `errors`. A
+/// database failure rolls back the current chunk; earlier chunks stay
+/// committed and the error says how many rows that was.
+fn insert_chunked(
+    db: &Database,
+    rows: impl Iterator<Item = Result<ImportRow>>,
+    opts: ImportOptions,
+) -> Result<ImportCounts> {
+    let chunk_size = opts.chunk_size.filter(|&n| n > 0).unwrap_or(usize::MAX);
+    let mut rows = rows.peekable();
+    let mut counts = ImportCounts::default();
+    let mark = db.metric_mark()?;
+    while rows.peek().is_some() {
+        let inserted = db.in_transaction(|db| {
+            let (mut n, mut skipped, mut overwritten, mut errors) = (0, 0, 0, 0);
+            for row in rows.by_ref().take(chunk_size) {
+                let Ok(row) = row else {
+                    errors += 1;
+                    continue;
+                };
+                if let Some(stored) = merge_target(db, &row, mark)? {
+                    let replace = match opts.merge {
+                        MergeStrategy::Skip => false,
+                        MergeStrategy::Overwrite => true,
+                        MergeStrategy::Newest => row.metric.timestamp > stored.timestamp,
+                    };
+                    if replace {
+                        let mut m = row.metric;
+                        m.id = stored.id;
+                        crate::core::types::apply(db, &mut m)?;
+                        db.replace_metric(&m)?;
+                        overwritten += 1;
+                    } else {
+                        skipped += 1;
+                    }
+                    continue;
+                }
+                let mut m = row.metric;
+                if opts.dedupe && db.metric_duplicate_exists(&m)? {
+                    skipped += 1;
+                    continue;
+                }
+                crate::core::types::apply(db, &mut m)?;
+                db.insert_metric(&m)?;
+                n += 1;
+            }
+            Ok((n, skipped, overwritten, errors))
+        });
+        match inserted {
+            Ok((n, skipped, overwritten, errors)) => {
+                counts.imported += n;
+                counts.skipped_duplicates += skipped;
+                counts.overwritten += overwritten;
+                counts.errors += errors;
+            }
+            Err(e) if counts.imported > 0 => {
+                // Keep the original error so its kind sets the exit code.
+                let message = format!(
+                    "import stopped after {} committed rows: {}",
+                    counts.imported, e
+                );
+                return Err(e.context(message));
+            }
+            Err(e) => return Err(e),
+        }
     }
-    Ok(count)
+    Ok(counts)
 }
 
 /// Export metrics and medications to JSON format.
@@ -86,8 +516,12 @@ pub fn to_json_with_medications(
     metric_type: Option<&str>,
     from: Option<NaiveDate>,
     to: Option<NaiveDate>,
+    tags: &[String],
 ) -> Result<String> {
-    let entries = db.query_all(metric_type, from, to)?;
+    render_json_with_medications(db, &metric_entries(db, metric_type, from, to, tags)?)
+}
+
+fn render_json_with_medications(db: &Database, entries: &[Metric]) -> Result<String> {
     let medications = db.list_medications(true)?; // include stopped for full export
     let combined = serde_json::json!({
         "metrics": entries,
@@ -99,93 +533,409 @@ pub fn to_json_with_medications(
 /// Import JSON with auto-detection of format (new combined or old array).
 /// Returns (metric_count, medication_count).
 pub fn import_json_auto(db: &Database, json_str: &str) -> Result<(usize, usize)> {
+    let (counts, meds) = import_json_auto_with(db, json_str, ImportOptions::default())?;
+    Ok((counts.imported, meds))
+}
+
+/// [`import_json_auto`] with chunking and deduplication, returning metric
+/// counts and the number of medications imported. Without a chunk size,
+/// metrics and medications are imported in one transaction.
+pub fn import_json_auto_with(
+    db: &Database,
+    json_str: &str,
+    opts: ImportOptions,
+) -> Result<(ImportCounts, usize)> {
     let parsed: serde_json::Value = serde_json::from_str(json_str)?;
+    if opts.chunk_size.is_some() {
+        return import_parsed(db, &parsed, opts);
+    }
+    db.in_transaction(|db| import_parsed(db, &parsed, opts))
+}
 
+fn import_parsed(
+    db: &Database,
+    parsed: &serde_json::Value,
+    opts: ImportOptions,
+) -> Result<(ImportCounts, usize)> {
     // Check if it's an object with "metrics" and/or "medications" keys
     if let Some(obj) = parsed.as_object()
         && (obj.contains_key("metrics") || obj.contains_key("medications"))
     {
-        let mut metric_count = 0;
+        let mut counts = ImportCounts::default();
         let mut med_count = 0;
 
         if let Some(metrics) = obj.get("metrics") {
             let entries_str = serde_json::to_string(metrics)?;
-            metric_count = import_json(db, &entries_str)?;
+            counts = import_json_with(db, &entries_str, opts)?;
         }
         if let Some(meds) = obj.get("medications") {
-            med_count = import_medications(db, meds)?;
+            // Stored medications are kept under every merge strategy.
+            let (imported, skipped) = import_medications(db, meds)?;
+            med_count = imported;
+            counts.skipped_duplicates += skipped;
         }
-        return Ok((metric_count, med_count));
+        return Ok((counts, med_count));
     }
 
     // Otherwise it's an array (old format) -- treat as metrics
-    let count = import_json(db, json_str)?;
-    Ok((count, 0))
+    let counts = import_json_with(db, &serde_json::to_string(parsed)?, opts)?;
+    Ok((counts, 0))
 }
 
-/// Import medications from a JSON value (array of Medication objects).
-fn import_medications(db: &Database, meds_value: &serde_json::Value) -> Result<usize> {
+/// Import medications from a JSON value (array of Medication objects),
+/// returning (imported, skipped as already stored by id).
+fn import_medications(db: &Database, meds_value: &serde_json::Value) -> Result<(usize, usize)> {
     let meds: Vec<Medication> = serde_json::from_value(meds_value.clone())?;
-    let mut count = 0;
-    for med in meds {
-        db.insert_medication(&med)?;
-        count += 1;
-    }
-    Ok(count)
+    db.in_transaction(|db| {
+        let (mut count, mut skipped) = (0, 0);
+        for med in meds {
+            if db.medication_id_exists(&med.id)? {
+                skipped += 1;
+                continue;
+            }
+            db.insert_medication(&med)?;
+            count += 1;
+        }
+        Ok((count, skipped))
+    })
 }
 
 /// Import metrics from CSV string.
 pub fn import_csv(db: &Database, csv_str: &str) -> Result<usize> {
-    let mut lines = csv_str.lines();
-    let _header = lines.next(); // skip header
-    let mut count = 0;
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let fields: Vec<&str> = line.splitn(7, ',').collect();
-        if fields.len() < 3 {
-            continue;
-        }
+    Ok(import_csv_with(db, csv_str, ImportOptions::default())?.imported)
+}
+
+/// Import metrics from CSV with the given chunking and deduplication.
+pub fn import_csv_with(db: &Database, csv_str: &str, opts: ImportOptions) -> Result<ImportCounts> {
+    let lines = || {
+        csv_str
+            .lines()
+            .skip(1) // header
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+    };
+    let rows = lines().filter_map(parse_csv_row).map(|row| {
+        row.map(|metric| ImportRow {
+            metric,
+            has_id: false,
+        })
+    });
+    let mut counts = insert_chunked(db, rows, opts)?;
+    counts.errors += lines().filter(|l| l.splitn(4, ',').count() < 3).count();
+    Ok(counts)
+}
+
+/// Parse one `timestamp,type,value,unit,note,tags,source` row; rows with
+/// fewer than three fields are skipped, and a bad timestamp or value is an error.
+fn parse_csv_row(line: &str) -> Option<Result<Metric>> {
+    let mut fields: Vec<&str> = line.splitn(5, ',').collect();
+    if fields.len() < 3 {
+        return None;
+    }
+    if fields.len() == 5
+        && let Some(rest) = fields.pop()
+    {
+        let (note, tags, source) = split_note_tags_source(rest);
+        fields.extend([note, tags, source]);
+    }
+    let field = |i: usize| fields.get(i).copied().filter(|f| !f.is_empty());
+    let parsed = (|| {
         let timestamp: DateTime<Utc> = fields[0].parse()?;
         let metric_type = fields[1].to_string();
         let value: f64 = fields[2].parse()?;
-        let unit = if fields.len() > 3 && !fields[3].is_empty() {
-            fields[3].to_string()
-        } else {
-            default_unit(&metric_type).to_string()
-        };
-        let note = if fields.len() > 4 && !fields[4].is_empty() {
-            Some(fields[4].to_string())
-        } else {
-            None
-        };
-        let tags: Vec<String> = if fields.len() > 5 && !fields[5].is_empty() {
-            serde_json::from_str(fields[5]).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
-        let source = if fields.len() > 6 && !fields[6].is_empty() {
-            fields[6].to_string()
-        } else {
-            "import".to_string()
-        };
-
-        let category = Category::from_type(&metric_type);
-        let m = Metric {
+        let unit = field(3).map_or_else(|| default_unit(&metric_type).to_string(), String::from);
+        let tags: Vec<String> = field(5)
+            .map(|t| serde_json::from_str(t).unwrap_or_default())
+            .unwrap_or_default();
+        Ok(Metric {
             id: uuid::Uuid::new_v4().to_string(),
             timestamp,
-            category,
+            category: Category::from_type(&metric_type),
             metric_type,
             value,
             unit,
-            note,
+            note: field(4).map(String::from),
             tags,
-            source,
-        };
-        db.insert_metric(&m)?;
-        count += 1;
+            source: field(6).unwrap_or("import").to_string(),
+        })
+    })();
+    Some(parsed)
+}
+
+/// Split the `note,tags,source` tail of a CSV row. A note (such as a
+/// `med take --skip` reason) may contain commas, so the source is taken from
+/// the end and the tags are the JSON array before it.
+fn split_note_tags_source(rest: &str) -> (&str, &str, &str) {
+    let (head, source) = match rest.rsplit_once(',') {
+        Some((head, source)) if !source.ends_with(']') => (head, source),
+        _ => (rest, ""),
+    };
+    if head.ends_with(']') {
+        if let Some(i) = head.rfind(",[") {
+            return (&head[..i], &head[i + 1..], source);
+        }
+        if head.starts_with('[') {
+            return ("", head, source);
+        }
+    }
+    let mut parts = rest.splitn(3, ',');
+    (
+        parts.next().unwrap_or(""),
+        parts.next().unwrap_or(""),
+        parts.next().unwrap_or(""),
+    )
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+    pub unknown_types: Vec<String>,
+}
+
+/// Map an Apple Health quantity identifier to an openvital metric type.
+fn apple_health_type(identifier: &str) -> Option<&'static str> {
+    let name = identifier.strip_prefix("HKQuantityTypeIdentifier")?;
+    Some(match name {
+        "BodyMass" => "weight",
+        "BodyFatPercentage" => "body_fat",
+        "WaistCircumference" => "waist",
+        "HeartRate" => "heart_rate",
+        "StepCount" => "steps",
+        "DietaryWater" => "water",
+        "DietaryEnergyConsumed" => "calories_in",
+        "ActiveEnergyBurned" => "calories_burned",
+        "BloodPressureSystolic" => "bp_systolic",
+        "BloodPressureDiastolic" => "bp_diastolic",
+        _ => return None,
+    })
+}
+
+/// Convert a value from Apple's unit into the metric type's stored unit.
+/// Returns `None` for units we don't know how to convert.
+fn apple_health_value(metric_type: &str, value: f64, unit: &str) -> Option<f64> {
+    let factor = match (metric_type, unit) {
+        ("weight", "kg") => 1.0,
+        ("weight", "lb") => 0.453_592_37,
+        ("weight", "g") => 0.001,
+        // Apple stores body fat as a fraction (0.22 = 22%)
+        ("body_fat", "%") => 100.0,
+        ("waist", "cm") => 1.0,
+        ("waist", "in") => 2.54,
+        ("waist", "m") => 100.0,
+        ("heart_rate", "count/min") => 1.0,
+        ("steps", "count") => 1.0,
+        ("water", "mL") => 1.0,
+        ("water", "L") => 1000.0,
+        ("water", "fl_oz_us") => 29.5735,
+        ("calories_in" | "calories_burned", "kcal" | "Cal") => 1.0,
+        ("calories_in" | "calories_burned", "kJ") => 1.0 / 4.184,
+        ("bp_systolic" | "bp_diastolic", "mmHg") => 1.0,
+        _ => return None,
+    };
+    Some(value * factor)
+}
+
+fn attr(e: &BytesStart, name: &[u8]) -> Result<Option<String>> {
+    match e.try_get_attribute(name)? {
+        Some(a) => Ok(Some(a.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+/// Import quantity samples from an Apple Health `export.xml`.
+///
+/// The file is stream-parsed record by record, so multi-GB exports are never held in memory.
+/// Records with unmapped `HKQuantityTypeIdentifier`s are skipped and their identifiers listed
+/// in `unknown_types`; records with unconvertible units or unparseable values count as skipped.
+pub fn import_apple_health(db: &Database, xml_path: &Path) -> Result<ImportResult> {
+    let file = std::fs::File::open(xml_path)
+        .with_context(|| format!("cannot open {}", xml_path.display()))?;
+    let mut reader = quick_xml::Reader::from_reader(BufReader::new(file));
+    let mut buf = Vec::new();
+    let mut result = ImportResult::default();
+    let mut unknown: BTreeSet<String> = BTreeSet::new();
+
+    db.in_transaction(|db| {
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Eof => break,
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Record" => {
+                    import_apple_record(db, &e, &mut result, &mut unknown)?;
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+        Ok(())
+    })?;
+
+    result.unknown_types = unknown.into_iter().collect();
+    Ok(result)
+}
+
+/// Import one `<Record>`, counting it as imported, skipped or of an unknown type.
+/// Kept out of the event loop so the read buffer is cleared after every event.
+fn import_apple_record(
+    db: &Database,
+    e: &BytesStart,
+    result: &mut ImportResult,
+    unknown: &mut BTreeSet<String>,
+) -> Result<()> {
+    let Some(identifier) = attr(e, b"type")? else {
+        return Ok(());
+    };
+    // Only quantity samples carry numeric values; ignore category/workout records.
+    if !identifier.starts_with("HKQuantityTypeIdentifier") {
+        return Ok(());
     }
-    Ok(count)
+    let Some(metric_type) = apple_health_type(&identifier) else {
+        unknown.insert(identifier);
+        result.skipped += 1;
+        return Ok(());
+    };
+    match parse_apple_record(e, metric_type)? {
+        Some(m) => {
+            db.insert_metric(&m)?;
+            result.imported += 1;
+        }
+        None => result.skipped += 1,
+    }
+    Ok(())
+}
+
+fn parse_apple_record(e: &BytesStart, metric_type: &str) -> Result<Option<Metric>> {
+    let value = attr(e, b"value")?.and_then(|v| v.parse::<f64>().ok());
+    let unit = attr(e, b"unit")?.unwrap_or_default();
+    let timestamp = attr(e, b"startDate")?
+        .and_then(|d| DateTime::parse_from_str(&d, "%Y-%m-%d %H:%M:%S %z").ok());
+
+    let (Some(value), Some(timestamp)) = (value, timestamp) else {
+        return Ok(None);
+    };
+    let Some(value) = apple_health_value(metric_type, value, &unit) else {
+        return Ok(None);
+    };
+
+    let mut m = Metric::new(metric_type.to_string(), value);
+    m.timestamp = timestamp.with_timezone(&Utc);
+    m.source = "apple_health".to_string();
+    Ok(Some(m))
+}
+
+/// Split one CSV line, honouring double-quoted fields (Garmin quotes numbers like "1,234").
+fn split_quoted_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut cur = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                cur.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut cur)),
+            _ => cur.push(c),
+        }
+    }
+    fields.push(cur);
+    fields
+}
+
+/// Parse a Garmin numeric cell; "--" and empty cells mean no value.
+fn garmin_number(cell: &str) -> Option<f64> {
+    let cell = cell.trim();
+    if cell.is_empty() || cell == "--" {
+        return None;
+    }
+    cell.replace(',', "").parse().ok()
+}
+
+/// Parse Garmin's "hh:mm:ss" (or "mm:ss", optionally with fractional seconds) into minutes.
+fn garmin_minutes(cell: &str) -> Option<f64> {
+    let parts: Vec<f64> = cell
+        .trim()
+        .split(':')
+        .map(|p| p.parse::<f64>().ok())
+        .collect::<Option<_>>()?;
+    let secs = match parts.as_slice() {
+        [h, m, s] => h * 3600.0 + m * 60.0 + s,
+        [m, s] => m * 60.0 + s,
+        _ => return None,
+    };
+    Some((secs / 60.0 * 10.0).round() / 10.0)
+}
+
+/// Import a Garmin Connect activities CSV export.
+///
+/// Columns are located by header name, so column order doesn't matter. Each activity row
+/// produces up to three metrics: `cardio` (minutes from `Elapsed Time`), `calories_burned`
+/// and `heart_rate` (`Avg HR`), tagged with the activity type. `Date` is read as local time
+/// in the database's timezone. Rows without a parseable date or any usable value are skipped.
+pub fn import_garmin_csv(db: &Database, content: &str) -> Result<ImportResult> {
+    let mut lines = content.lines();
+    let header = split_quoted_csv(
+        lines
+            .next()
+            .ok_or_else(|| crate::error::parse("empty Garmin CSV"))?
+            .trim_start_matches('\u{feff}'),
+    );
+    let col = |name: &str| header.iter().position(|h| h.trim() == name);
+    let date_col =
+        col("Date").ok_or_else(|| crate::error::parse("Garmin CSV is missing a 'Date' column"))?;
+    let type_col = col("Activity Type");
+    let time_col = col("Elapsed Time");
+    let cal_col = col("Calories");
+    let hr_col = col("Avg HR");
+
+    let mut result = ImportResult::default();
+    db.in_transaction(|db| {
+        for line in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = split_quoted_csv(line);
+            let cell = |i: Option<usize>| i.and_then(|i| fields.get(i)).map(|s| s.as_str());
+
+            let timestamp = cell(Some(date_col))
+                .and_then(|d| {
+                    chrono::NaiveDateTime::parse_from_str(d.trim(), "%Y-%m-%d %H:%M:%S").ok()
+                })
+                .and_then(|dt| dt.and_local_timezone(db.timezone()).earliest());
+            let Some(timestamp) = timestamp else {
+                result.skipped += 1;
+                continue;
+            };
+            let tags: Vec<String> = cell(type_col)
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(|t| vec![t.to_string()])
+                .unwrap_or_default();
+
+            let values = [
+                ("cardio", cell(time_col).and_then(garmin_minutes)),
+                ("calories_burned", cell(cal_col).and_then(garmin_number)),
+                ("heart_rate", cell(hr_col).and_then(garmin_number)),
+            ];
+            let mut any = false;
+            for (metric_type, value) in values {
+                let Some(value) = value else { continue };
+                let mut m = Metric::new(metric_type.to_string(), value);
+                m.timestamp = timestamp.with_timezone(&Utc);
+                m.tags = tags.clone();
+                m.source = "garmin".to_string();
+                db.insert_metric(&m)?;
+                result.imported += 1;
+                any = true;
+            }
+            if !any {
+                result.skipped += 1;
+            }
+        }
+        Ok(())
+    })?;
+
+    Ok(result)
 }
@@ -0,0 +1,136 @@
+use anyhow::Result;
+
+/// Evaluate an arithmetic expression such as `350 * 0.9` or `(2 + 3) / 4`.
+///
+/// Only numeric literals, `+ - * /`, unary minus and parentheses are accepted;
+/// there are no identifiers or function calls.
+pub fn eval(expr: &str) -> Result<f64> {
+    let mut parser = Parser {
+        src: expr,
+        bytes: expr.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.expr(0)?;
+    parser.skip_ws();
+    if parser.pos < parser.bytes.len() {
+        return Err(parser.unexpected());
+    }
+    if !value.is_finite() {
+        crate::fail!(
+            validation,
+            "expression result is not a finite number: {}",
+            expr
+        );
+    }
+    Ok(value)
+}
+
+/// Deeper nesting than this is rejected rather than risking the stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self, depth: usize) -> Result<f64> {
+        let mut value = self.term(depth)?;
+        while let Some(op @ (b'+' | b'-')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.term(depth)?;
+            value = if op == b'+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    // term := factor (('*' | '/') factor)*
+    fn term(&mut self, depth: usize) -> Result<f64> {
+        let mut value = self.factor(depth)?;
+        while let Some(op @ (b'*' | b'/')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.factor(depth)?;
+            if op == b'*' {
+                value *= rhs;
+            } else if rhs == 0.0 {
+                crate::fail!(validation, "division by zero in expression: {}", self.src);
+            } else {
+                value /= rhs;
+            }
+        }
+        Ok(value)
+    }
+
+    // factor := ('-' | '+') factor | '(' expr ')' | number
+    fn factor(&mut self, depth: usize) -> Result<f64> {
+        if depth > MAX_DEPTH {
+            crate::fail!(parse, "expression is nested too deeply: {}", self.src);
+        }
+        match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                Ok(-self.factor(depth + 1)?)
+            }
+            Some(b'+') => {
+                self.pos += 1;
+                self.factor(depth + 1)
+            }
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.expr(depth + 1)?;
+                if self.peek() != Some(b')') {
+                    return Err(self.unexpected());
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == b'.' => self.number(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        let start = self.pos;
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_digit() || *c == b'.')
+        {
+            self.pos += 1;
+        }
+        let literal = &self.src[start..self.pos];
+        literal
+            .parse()
+            .map_err(|_| crate::error::parse(format!("invalid number '{}' in expression", literal)))
+    }
+
+    /// Next non-whitespace byte, without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_ws();
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn unexpected(&self) -> anyhow::Error {
+        match self.src[self.pos..].chars().next() {
+            Some(c) => crate::error::parse(format!(
+                "unexpected '{}' at position {} in expression: {}",
+                c,
+                self.pos + 1,
+                self.src
+            )),
+            None => crate::error::parse(format!("incomplete expression: {}", self.src)),
+        }
+    }
+}
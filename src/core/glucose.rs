@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::core::time;
+use crate::models::config::GlucoseConfig;
+use crate::models::metric::Metric;
+
+pub const GLUCOSE_TYPE: &str = "glucose";
+/// Readings below this (mg/dL) are a clinically significant low.
+pub const CRITICAL_LOW: f64 = 54.0;
+/// Readings above this (mg/dL) are a clinically significant high.
+pub const CRITICAL_HIGH: f64 = 250.0;
+
+/// Whether a reading is outside the safe range, whatever the personal baseline.
+pub fn is_critical(metric_type: &str, value: f64) -> bool {
+    metric_type == GLUCOSE_TYPE && !(CRITICAL_LOW..=CRITICAL_HIGH).contains(&value)
+}
+
+/// Glucose readings of one day against the target range.
+#[derive(Debug, Serialize)]
+pub struct GlucoseDay {
+    pub date: NaiveDate,
+    pub readings: u32,
+    pub average: f64,
+    pub in_range_pct: f64,
+    /// Readings below `target_low`.
+    pub lows: u32,
+    /// Readings above `target_high`.
+    pub highs: u32,
+}
+
+/// Time in range over a period: the share of readings inside the target
+/// range, with the average and per-day breakdown. Values are in mg/dL.
+#[derive(Debug, Serialize)]
+pub struct TimeInRange {
+    pub target_low: f64,
+    pub target_high: f64,
+    pub readings: u32,
+    pub average: f64,
+    pub in_range_pct: f64,
+    pub below_pct: f64,
+    pub above_pct: f64,
+    pub lows: u32,
+    pub highs: u32,
+    pub days: Vec<GlucoseDay>,
+}
+
+#[derive(Default)]
+struct Tally {
+    readings: u32,
+    sum: f64,
+    lows: u32,
+    highs: u32,
+}
+
+impl Tally {
+    fn add(&mut self, value: f64, targets: &GlucoseConfig) {
+        self.readings += 1;
+        self.sum += value;
+        if value < targets.target_low {
+            self.lows += 1;
+        } else if value > targets.target_high {
+            self.highs += 1;
+        }
+    }
+
+    fn in_range(&self) -> u32 {
+        self.readings - self.lows - self.highs
+    }
+
+    fn pct(&self, n: u32) -> f64 {
+        round1(n as f64 * 100.0 / self.readings as f64)
+    }
+
+    fn average(&self) -> f64 {
+        round1(self.sum / self.readings as f64)
+    }
+}
+
+/// Time in range of the `glucose` readings among `entries`, or `None` without any.
+pub fn time_in_range(entries: &[Metric], targets: &GlucoseConfig, tz: Tz) -> Option<TimeInRange> {
+    let mut total = Tally::default();
+    let mut by_day: BTreeMap<NaiveDate, Tally> = BTreeMap::new();
+    for m in entries.iter().filter(|m| m.metric_type == GLUCOSE_TYPE) {
+        total.add(m.value, targets);
+        by_day
+            .entry(time::local_date(&m.timestamp, tz))
+            .or_default()
+            .add(m.value, targets);
+    }
+    if total.readings == 0 {
+        return None;
+    }
+    let days = by_day
+        .into_iter()
+        .map(|(date, t)| GlucoseDay {
+            date,
+            readings: t.readings,
+            average: t.average(),
+            in_range_pct: t.pct(t.in_range()),
+            lows: t.lows,
+            highs: t.highs,
+        })
+        .collect();
+    Some(TimeInRange {
+        target_low: targets.target_low,
+        target_high: targets.target_high,
+        readings: total.readings,
+        average: total.average(),
+        in_range_pct: total.pct(total.in_range()),
+        below_pct: total.pct(total.lows),
+        above_pct: total.pct(total.highs),
+        lows: total.lows,
+        highs: total.highs,
+        days,
+    })
+}
+
+fn round1(v: f64) -> f64 {
+    (v * 10.0).round() / 10.0
+}
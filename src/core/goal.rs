@@ -1,8 +1,10 @@
-use crate::db::Database;
+use crate::db::{Database, SortOrder};
 use crate::models::goal::{Direction, Goal, Timeframe};
+use crate::models::metric::Metric;
 use anyhow::Result;
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Set (or replace) a goal for a metric type.
 pub fn set_goal(
@@ -12,11 +14,24 @@ pub fn set_goal(
     direction: Direction,
     timeframe: Timeframe,
 ) -> Result<Goal> {
+    if crate::core::bp::is_bp(&metric_type) {
+        crate::fail!(
+            validation,
+            "blood pressure is stored as bp_systolic and bp_diastolic; set a goal on each, e.g. `goal set bp_systolic 130 below daily`"
+        );
+    }
     // Deactivate existing goal for same type
     if let Some(existing) = db.get_goal_by_type(&metric_type)? {
         db.remove_goal(&existing.id)?;
     }
-    let goal = Goal::new(metric_type, target_value, direction, timeframe);
+    let mut goal = Goal::new(metric_type, target_value, direction, timeframe);
+    // Progress from a starting point only means something for snapshot metrics
+    if !crate::models::metric::is_cumulative(&goal.metric_type) {
+        goal.start_value = db
+            .query_by_type(&goal.metric_type, Some(1), SortOrder::Desc)?
+            .first()
+            .map(|m| m.value);
+    }
     db.insert_goal(&goal)?;
     Ok(goal)
 }
@@ -39,12 +54,21 @@ pub struct GoalStatus {
     pub current_value: Option<f64>,
     pub is_met: bool,
     pub progress: Option<String>,
+    pub start_value: Option<f64>,
+    /// Share of the distance from `start_value` to the target covered so far, 0–100.
+    pub percent_complete: Option<f64>,
+    /// Projected date of reaching the target based on the current trend.
+    pub eta: Option<crate::core::projection::Projection>,
+    /// Whether the trend moves toward the target; `None` with too little data.
+    pub on_track: Option<bool>,
+    /// The projected date, when the trend is converging on the target.
+    pub estimated_completion: Option<NaiveDate>,
 }
 
 /// Get status of all active goals, or a specific metric type.
 pub fn goal_status(db: &Database, metric_type: Option<&str>) -> Result<Vec<GoalStatus>> {
     let goals = db.list_goals(true)?;
-    let today = Local::now().date_naive();
+    let today = crate::core::time::local_today(db.timezone().name());
 
     let mut results = Vec::new();
     for goal in &goals {
@@ -56,6 +80,7 @@ pub fn goal_status(db: &Database, metric_type: Option<&str>) -> Result<Vec<GoalS
         let current = compute_current(db, goal, today)?;
         let is_met = current.map(|v| goal.is_met(v)).unwrap_or(false);
         let progress = current.map(|v| format_progress(goal, v));
+        let eta = crate::core::projection::project(db, goal)?;
 
         results.push(GoalStatus {
             id: goal.id.clone(),
@@ -66,16 +91,51 @@ pub fn goal_status(db: &Database, metric_type: Option<&str>) -> Result<Vec<GoalS
             current_value: current,
             is_met,
             progress,
+            start_value: goal.start_value,
+            percent_complete: current.and_then(|v| goal.percent_complete(v)),
+            on_track: eta.on_track(),
+            estimated_completion: eta.estimated_date,
+            eta: Some(eta),
         });
     }
     Ok(results)
 }
 
+/// A daily goal's value for each day from `from` to `to` with entries, as
+/// [`goal_status`] sees it: the day's total for cumulative metrics and
+/// medications, otherwise its latest reading.
+pub(crate) fn daily_values(
+    db: &Database,
+    goal: &Goal,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<BTreeMap<NaiveDate, f64>> {
+    use crate::models::metric::{Category, is_cumulative};
+    let entries = db.query_all(Some(&goal.metric_type), Some(from), Some(to))?;
+    let is_med = !entries.is_empty() && entries.iter().all(|m| m.category == Category::Medication);
+    let sum = is_cumulative(&goal.metric_type) || is_med;
+
+    let mut days: BTreeMap<NaiveDate, (f64, DateTime<Utc>)> = BTreeMap::new();
+    for m in entries
+        .iter()
+        .filter(|m| is_med == (m.category == Category::Medication))
+    {
+        let d = crate::core::time::local_date(&m.timestamp, db.timezone());
+        let slot = days.entry(d).or_insert((0.0, m.timestamp));
+        if sum {
+            slot.0 += m.value;
+        } else if m.timestamp >= slot.1 {
+            *slot = (m.value, m.timestamp);
+        }
+    }
+    Ok(days.into_iter().map(|(d, (v, _))| (d, v)).collect())
+}
+
 /// Check if a metric type is exclusively a medication (no non-medication entries).
 /// Returns false if non-medication entries exist for this type (name collision).
 fn is_medication_type(db: &Database, metric_type: &str) -> Result<bool> {
     use crate::models::metric::Category;
-    let entries = db.query_by_type(metric_type, Some(20))?;
+    let entries = db.query_by_type(metric_type, Some(20), SortOrder::Desc)?;
     if entries.is_empty() {
         return Ok(false);
     }
@@ -84,22 +144,95 @@ fn is_medication_type(db: &Database, metric_type: &str) -> Result<bool> {
     Ok(!has_non_med)
 }
 
+/// The days a goal's timeframe covers up to `today`: the day, the week so far
+/// (from `week_start`) or the month so far.
+fn window(timeframe: &Timeframe, today: NaiveDate, week_start: Weekday) -> (NaiveDate, NaiveDate) {
+    match timeframe {
+        Timeframe::Daily => (today, today),
+        Timeframe::Weekly => (crate::core::time::week_start_for(today, week_start), today),
+        Timeframe::Monthly => (today.with_day(1).unwrap(), today),
+    }
+}
+
+/// A daily or weekly goal that entries just logged took from unmet to met.
+#[derive(Debug, Serialize)]
+pub struct GoalCompleted {
+    pub goal_id: String,
+    pub metric_type: String,
+    pub timeframe: String,
+    pub target: f64,
+    pub current: f64,
+}
+
+/// Daily and weekly goals that `logged` (entries just inserted) completed:
+/// met with them, but not without them. Costs one goal lookup per type, and
+/// one read of the goal's window for types that have a goal.
+pub fn completed_by(
+    db: &Database,
+    logged: &[Metric],
+    today: NaiveDate,
+) -> Result<Vec<GoalCompleted>> {
+    use crate::models::metric::{Category, is_cumulative};
+    let mut types: Vec<&str> = Vec::new();
+    for m in logged.iter().filter(|m| m.category != Category::Medication) {
+        if !types.contains(&m.metric_type.as_str()) {
+            types.push(&m.metric_type);
+        }
+    }
+
+    let mut completed = Vec::new();
+    for metric_type in types {
+        let Some(goal) = db.get_goal_by_type(metric_type)? else {
+            continue;
+        };
+        if goal.timeframe == Timeframe::Monthly {
+            continue;
+        }
+        let (from, to) = window(&goal.timeframe, today, db.week_start());
+        let entries: Vec<Metric> = db
+            .query_all(Some(metric_type), Some(from), Some(to))?
+            .into_iter()
+            .filter(|m| m.category != Category::Medication)
+            .collect();
+        let value = |new: bool| {
+            let mut values = entries
+                .iter()
+                .filter(|m| new || !logged.iter().any(|l| l.id == m.id))
+                .map(|m| (m.timestamp, m.value))
+                .peekable();
+            values.peek()?;
+            Some(if is_cumulative(metric_type) {
+                values.map(|(_, v)| v).sum()
+            } else {
+                values.max_by_key(|(ts, _)| *ts).map(|(_, v)| v)?
+            })
+        };
+        let met_before = value(false).is_some_and(|v| goal.is_met(v));
+        if let Some(current) = value(true)
+            && goal.is_met(current)
+            && !met_before
+        {
+            completed.push(GoalCompleted {
+                goal_id: goal.id.clone(),
+                metric_type: goal.metric_type.clone(),
+                timeframe: goal.timeframe.to_string(),
+                target: goal.target_value,
+                current,
+            });
+        }
+    }
+    Ok(completed)
+}
+
 /// Compute the current value for a goal based on its timeframe.
 fn compute_current(db: &Database, goal: &Goal, today: NaiveDate) -> Result<Option<f64>> {
     use crate::models::metric::{Category, is_cumulative};
     let is_med = is_medication_type(db, &goal.metric_type)?;
     let cumulative = is_cumulative(&goal.metric_type) || is_med;
 
-    let (start_date, end_date) = match goal.timeframe {
-        Timeframe::Daily => (today, today),
-        Timeframe::Weekly => {
-            let weekday = today.weekday().num_days_from_monday();
-            (today - chrono::Duration::days(weekday as i64), today)
-        }
-        Timeframe::Monthly => (today.with_day(1).unwrap(), today),
-    };
+    let (start_date, end_date) = window(&goal.timeframe, today, db.week_start());
 
-    let entries = db.query_by_date_range(start_date, end_date)?;
+    let entries = db.query_by_date_range(start_date, end_date, SortOrder::Asc)?;
     let filtered_entries: Vec<_> = entries
         .iter()
         .filter(|m| m.metric_type == goal.metric_type)
@@ -219,7 +352,7 @@ mod tests {
 
         db.insert_metric(&make_metric("weight", 75.0, today))?;
         let mut m2 = make_metric("weight", 74.0, today);
-        m2.timestamp = m2.timestamp + chrono::Duration::hours(1);
+        m2.timestamp += chrono::Duration::hours(1);
         db.insert_metric(&m2)?;
 
         let val = compute_current(&db, &goal, today)?;
@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Serialize;
+
+use crate::core::units::{to_display, to_display_rate};
+use crate::core::{goal, med, time, trend};
+use crate::db::Database;
+use crate::models::config::{Config, Units};
+use crate::models::goal::Timeframe;
+use crate::models::metric::JOURNAL_TYPE;
+
+/// Days two types must share before they are correlated; keeps the pairwise
+/// work bounded when many custom types have sparse data.
+pub const MIN_SHARED_DAYS: usize = 10;
+/// Smallest |r| reported as a correlation.
+pub const MIN_CORRELATION: f64 = 0.5;
+/// At most this many correlations are reported, strongest first.
+pub const MAX_CORRELATIONS: usize = 3;
+/// Days with data a type needs for a trend or weekday finding.
+pub const MIN_DAYS: usize = 7;
+/// A trend is reported when its 95% interval excludes zero and it moves the
+/// value by at least this share of its mean over the window.
+pub const MIN_TREND_CHANGE: f64 = 0.03;
+/// A daily goal met on less than this share of its logged days is
+/// consistently missed.
+pub const GOAL_MISS_SHARE: f64 = 0.5;
+/// Medications below this adherence are reported.
+pub const MIN_ADHERENCE: f64 = 0.8;
+/// Readings each side of a weekday comparison needs.
+pub const MIN_WEEKDAY_SAMPLES: usize = 3;
+/// A weekday stands out when its mean differs from the other days by at
+/// least this many standard deviations of the daily values.
+pub const MIN_WEEKDAY_EFFECT: f64 = 0.75;
+
+/// How much attention an insight deserves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Notice,
+    Warning,
+}
+
+/// What an insight is about, with its supporting numbers (stored units).
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum InsightDetail {
+    Correlation {
+        metric_a: String,
+        metric_b: String,
+        coefficient: f64,
+        data_points: usize,
+    },
+    Trend {
+        metric_type: String,
+        /// Change per day with data.
+        rate: f64,
+        confidence_95_lower: f64,
+        confidence_95_upper: f64,
+        mean: f64,
+        days: usize,
+    },
+    GoalMissed {
+        metric_type: String,
+        target_value: f64,
+        direction: String,
+        days_met: usize,
+        days_logged: usize,
+    },
+    LowAdherence {
+        medication: String,
+        adherence: f64,
+    },
+    WeekdayPattern {
+        metric_type: String,
+        weekday: String,
+        weekday_avg: f64,
+        other_avg: f64,
+        difference: f64,
+        weekday_days: usize,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct Insight {
+    pub severity: Severity,
+    pub message: String,
+    #[serde(flatten)]
+    pub detail: InsightDetail,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InsightsResult {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Most severe first.
+    pub insights: Vec<Insight>,
+}
+
+/// Notable findings in the `days` days ending today.
+pub fn generate(db: &Database, config: &Config, days: u32) -> Result<InsightsResult> {
+    let today = time::local_today(db.timezone().name());
+    generate_on(db, config, days, today)
+}
+
+/// [`generate`] as of `today`. Goals and medications are judged through
+/// yesterday so a day still in progress does not count as missed.
+pub fn generate_on(
+    db: &Database,
+    config: &Config,
+    days: u32,
+    today: NaiveDate,
+) -> Result<InsightsResult> {
+    if days == 0 {
+        crate::fail!(validation, "--days must be at least 1");
+    }
+    let from = today - Duration::days(i64::from(days) - 1);
+
+    let mut series: BTreeMap<String, BTreeMap<NaiveDate, f64>> = BTreeMap::new();
+    for t in db.distinct_metric_types()? {
+        if t == JOURNAL_TYPE {
+            continue;
+        }
+        let mut s = trend::daily_series(db, &t)?;
+        s.retain(|d, _| *d >= from && *d <= today);
+        if !s.is_empty() {
+            series.insert(t, s);
+        }
+    }
+
+    let mut insights = Vec::new();
+    insights.extend(missed_goals(db, config, from, today)?);
+    insights.extend(low_adherence(db, config, days, today)?);
+    for (t, s) in &series {
+        insights.extend(trend_insight(t, s, &config.units));
+    }
+    insights.extend(correlations(&series));
+    for (t, s) in &series {
+        insights.extend(weekday_pattern(t, s, &config.units));
+    }
+    // Stable, so findings of equal severity keep the order above
+    insights.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+    Ok(InsightsResult {
+        from,
+        to: today,
+        insights,
+    })
+}
+
+/// Daily goals met on less than [`GOAL_MISS_SHARE`] of the logged days.
+fn missed_goals(
+    db: &Database,
+    config: &Config,
+    from: NaiveDate,
+    today: NaiveDate,
+) -> Result<Vec<Insight>> {
+    let mut out = Vec::new();
+    let until = today - Duration::days(1);
+    if until < from {
+        return Ok(out);
+    }
+    for g in db.list_goals(true)? {
+        if g.timeframe != Timeframe::Daily {
+            continue;
+        }
+        let values = goal::daily_values(db, &g, from, until)?;
+        let days_logged = values.len();
+        let days_met = values.values().filter(|v| g.is_met(**v)).count();
+        if days_logged < MIN_DAYS || days_met as f64 >= days_logged as f64 * GOAL_MISS_SHARE {
+            continue;
+        }
+        let (target, unit) = to_display(g.target_value, &g.metric_type, &config.units);
+        out.push(Insight {
+            severity: Severity::Warning,
+            message: format!(
+                "{} goal ({} {}) met on only {} of {} logged days",
+                g.metric_type,
+                g.direction,
+                with_unit(target, &unit),
+                days_met,
+                days_logged
+            ),
+            detail: InsightDetail::GoalMissed {
+                metric_type: g.metric_type.clone(),
+                target_value: g.target_value,
+                direction: g.direction.to_string(),
+                days_met,
+                days_logged,
+            },
+        });
+    }
+    Ok(out)
+}
+
+/// Active scheduled medications taken below [`MIN_ADHERENCE`].
+fn low_adherence(
+    db: &Database,
+    config: &Config,
+    days: u32,
+    today: NaiveDate,
+) -> Result<Vec<Insight>> {
+    let mut out = Vec::new();
+    let until = today - Duration::days(1);
+    for m in db.list_medications(false)? {
+        let Some(adherence) = med::adherence_window(db, config, &m, until, days - 1)? else {
+            continue;
+        };
+        if adherence >= MIN_ADHERENCE {
+            continue;
+        }
+        let adherence = (adherence * 100.0).round() / 100.0;
+        out.push(Insight {
+            severity: Severity::Warning,
+            message: format!(
+                "{} adherence is {:.0}% (below {:.0}%)",
+                m.name,
+                adherence * 100.0,
+                MIN_ADHERENCE * 100.0
+            ),
+            detail: InsightDetail::LowAdherence {
+                medication: m.name.clone(),
+                adherence,
+            },
+        });
+    }
+    Ok(out)
+}
+
+/// A significant rise or fall in a type's daily values.
+fn trend_insight(
+    metric_type: &str,
+    s: &BTreeMap<NaiveDate, f64>,
+    units: &Units,
+) -> Option<Insight> {
+    if s.len() < MIN_DAYS {
+        return None;
+    }
+    let ys: Vec<f64> = s.values().copied().collect();
+    let (slope, lower, upper) = trend::slope_interval(&ys)?;
+    let mean = ys.iter().sum::<f64>() / ys.len() as f64;
+    let change = slope * (ys.len() - 1) as f64;
+    if (lower <= 0.0 && upper >= 0.0) || change.abs() < mean.abs() * MIN_TREND_CHANGE {
+        return None;
+    }
+    let (_, unit) = to_display(mean, metric_type, units);
+    let rate = to_display_rate(slope, metric_type, units);
+    let rate = match with_unit(rate, &unit).split_once(' ') {
+        Some((_, u)) => format!("{:+.2} {}", rate, u),
+        None => format!("{:+.2}", rate),
+    };
+    Some(Insight {
+        severity: Severity::Notice,
+        message: format!(
+            "{} is trending {} ({} per day over {} days with data)",
+            metric_type,
+            if slope > 0.0 { "up" } else { "down" },
+            rate,
+            ys.len()
+        ),
+        detail: InsightDetail::Trend {
+            metric_type: metric_type.to_string(),
+            rate: round2(slope),
+            confidence_95_lower: round2(lower),
+            confidence_95_upper: round2(upper),
+            mean: round2(mean),
+            days: ys.len(),
+        },
+    })
+}
+
+/// The strongest correlations between types sharing at least
+/// [`MIN_SHARED_DAYS`] days.
+fn correlations(series: &BTreeMap<String, BTreeMap<NaiveDate, f64>>) -> Vec<Insight> {
+    let candidates: Vec<(&String, &BTreeMap<NaiveDate, f64>)> = series
+        .iter()
+        .filter(|(_, s)| s.len() >= MIN_SHARED_DAYS)
+        .collect();
+    let mut found = Vec::new();
+    for (i, (a, sa)) in candidates.iter().enumerate() {
+        for (b, sb) in &candidates[i + 1..] {
+            let pairs: Vec<(f64, f64)> = sa
+                .iter()
+                .filter_map(|(d, x)| sb.get(d).map(|y| (*x, *y)))
+                .collect();
+            if pairs.len() < MIN_SHARED_DAYS {
+                continue;
+            }
+            if let Some(r) = trend::pearson(&pairs)
+                && r.abs() >= MIN_CORRELATION
+            {
+                found.push((a.as_str(), b.as_str(), r, pairs.len()));
+            }
+        }
+    }
+    found.sort_by(|x, y| y.2.abs().total_cmp(&x.2.abs()));
+    found.truncate(MAX_CORRELATIONS);
+
+    found
+        .into_iter()
+        .map(|(a, b, r, n)| Insight {
+            severity: Severity::Info,
+            message: format!(
+                "{} and {} are {} {} correlated (r = {:.2} over {} days)",
+                a,
+                b,
+                trend::strength(r),
+                if r > 0.0 { "positively" } else { "negatively" },
+                r,
+                n
+            ),
+            detail: InsightDetail::Correlation {
+                metric_a: a.to_string(),
+                metric_b: b.to_string(),
+                coefficient: r,
+                data_points: n,
+            },
+        })
+        .collect()
+}
+
+/// The weekday whose mean differs most from the other days, when it stands
+/// out by at least [`MIN_WEEKDAY_EFFECT`] standard deviations.
+fn weekday_pattern(
+    metric_type: &str,
+    s: &BTreeMap<NaiveDate, f64>,
+    units: &Units,
+) -> Option<Insight> {
+    if s.len() < MIN_DAYS {
+        return None;
+    }
+    let values: Vec<f64> = s.values().copied().collect();
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if std_dev < f64::EPSILON {
+        return None;
+    }
+
+    let mut best: Option<(Weekday, f64, f64, usize)> = None;
+    for day in trend::WEEKDAYS {
+        let (on, off): (Vec<_>, Vec<_>) = s.iter().partition(|(d, _)| d.weekday() == day);
+        if on.len() < MIN_WEEKDAY_SAMPLES || off.len() < MIN_WEEKDAY_SAMPLES {
+            continue;
+        }
+        let avg = |xs: &[(_, &f64)]| xs.iter().map(|(_, v)| **v).sum::<f64>() / xs.len() as f64;
+        let (on_avg, off_avg) = (avg(&on), avg(&off));
+        if best.is_none_or(|(_, a, b, _)| (on_avg - off_avg).abs() > (a - b).abs()) {
+            best = Some((day, on_avg, off_avg, on.len()));
+        }
+    }
+    let (day, on_avg, off_avg, count) = best?;
+    let difference = on_avg - off_avg;
+    if difference.abs() < std_dev * MIN_WEEKDAY_EFFECT {
+        return None;
+    }
+
+    let (on_display, unit) = to_display(on_avg, metric_type, units);
+    let (off_display, _) = to_display(off_avg, metric_type, units);
+    Some(Insight {
+        severity: Severity::Info,
+        message: format!(
+            "{} is {} {} on {}s ({:.1} vs {:.1})",
+            metric_type,
+            with_unit((on_display - off_display).abs(), &unit),
+            if difference > 0.0 { "higher" } else { "lower" },
+            capitalized(trend::weekday_name(day)),
+            on_display,
+            off_display
+        ),
+        detail: InsightDetail::WeekdayPattern {
+            metric_type: metric_type.to_string(),
+            weekday: trend::weekday_name(day).to_string(),
+            weekday_avg: round2(on_avg),
+            other_avg: round2(off_avg),
+            difference: round2(difference),
+            weekday_days: count,
+        },
+    })
+}
+
+fn capitalized(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// A value with its unit; scale units ("0-10") are left off.
+fn with_unit(value: f64, unit: &str) -> String {
+    match unit {
+        "" | "0-10" | "1-10" | "1-5" => format!("{:.1}", value),
+        u => format!("{:.1} {}", value, u),
+    }
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::core::logging::{LogEntry, Source, log_metric};
+use crate::db::Database;
+use crate::models::config::Config;
+use crate::models::metric::{JOURNAL_TYPE, Metric};
+
+/// Notes shown by `note show` without `--last`.
+const DEFAULT_LAST: usize = 10;
+
+/// Record a free-form note as a `journal` entry (value 0, no unit).
+pub fn add(
+    db: &Database,
+    config: &Config,
+    text: &str,
+    tags: Option<&str>,
+    date: Option<NaiveDate>,
+) -> Result<Metric> {
+    let text = text.trim();
+    if text.is_empty() {
+        crate::fail!(validation, "note text must not be empty");
+    }
+    log_metric(
+        db,
+        config,
+        LogEntry {
+            metric_type: JOURNAL_TYPE,
+            value: 0.0,
+            note: Some(text),
+            tags,
+            source: Source::Manual,
+            date,
+            unit: None,
+        },
+    )
+}
+
+/// Journal entries in `from..=to` carrying every tag in `tags`, newest first,
+/// capped at `last` (default 10).
+pub fn list(
+    db: &Database,
+    last: Option<usize>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: &[String],
+) -> Result<Vec<Metric>> {
+    let mut entries = db.query_tagged(Some(JOURNAL_TYPE), from, to, tags)?;
+    entries.reverse();
+    entries.truncate(last.unwrap_or(DEFAULT_LAST));
+    Ok(entries)
+}
@@ -1,9 +1,77 @@
 use anyhow::Result;
-use chrono::{NaiveDate, TimeZone, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
-use crate::db::Database;
+use crate::db::{Database, SortOrder};
 use crate::models::config::Config;
-use crate::models::metric::Metric;
+use crate::models::metric::{Aggregation, Metric};
+
+/// Where an entry came from, stored as its `source` string.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Source {
+    #[default]
+    Manual,
+    /// `--source device`: `<hostname>:<tool>` of the machine that logged it.
+    Device(String),
+    /// Any other name, e.g. `garmin` or `agent`.
+    Named(String),
+}
+
+impl Source {
+    /// This machine and program, as `<hostname>:<tool>`.
+    pub fn device() -> Self {
+        let tool = std::env::current_exe()
+            .ok()
+            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "openvital".to_string());
+        Self::Device(format!("{}:{}", hostname(), tool))
+    }
+
+    /// A source name as given on the command line or in config; `device` is
+    /// expanded with [`Source::device`].
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim() {
+            "" => crate::fail!(validation, "source must not be empty"),
+            "manual" => Ok(Self::Manual),
+            "device" => Ok(Self::device()),
+            other => Ok(Self::Named(other.to_string())),
+        }
+    }
+
+    /// `--source` if given, else `log.default_source`, else manual.
+    pub fn resolve(given: Option<&str>, config: &Config) -> Result<Self> {
+        match given.or(config.log.default_source.as_deref()) {
+            Some(name) => Self::parse(name),
+            None => Ok(Self::Manual),
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Manual => write!(f, "manual"),
+            Self::Device(s) | Self::Named(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl serde::Serialize for Source {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The machine's hostname, as reported by the OS.
+fn hostname() -> String {
+    hostname_or_localhost(hostname::get().ok())
+}
+
+/// `raw` trimmed, or `localhost` when it is missing or blank.
+pub fn hostname_or_localhost(raw: Option<std::ffi::OsString>) -> String {
+    raw.map(|h| h.to_string_lossy().trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
 
 /// Parameters for logging a single metric.
 pub struct LogEntry<'a> {
@@ -11,13 +79,42 @@ pub struct LogEntry<'a> {
     pub value: f64,
     pub note: Option<&'a str>,
     pub tags: Option<&'a str>,
-    pub source: Option<&'a str>,
+    pub source: Source,
     pub date: Option<NaiveDate>,
+    /// Stored as given instead of the type's default unit; the value is not converted.
+    pub unit: Option<&'a str>,
+}
+
+/// Resolve an alias, then check the type against the known ones: a likely
+/// typo (e.g. `wheight`) becomes the closest known type with
+/// `log.fuzzy_match`, and is rejected with a suggestion otherwise. Types with
+/// no close match are new custom types.
+pub fn resolve_type(db: &Database, config: &Config, metric_type: &str) -> Result<String> {
+    let resolved = config.resolve_alias(metric_type);
+    match crate::core::types::suggest(db, &resolved)? {
+        Some(closest) if config.log.fuzzy_match => Ok(closest),
+        Some(closest) => crate::fail!(
+            validation,
+            "unknown metric type '{}'. Did you mean: {}? (use --fuzzy to log it as {}, or `openvital types add {}` for a new type)",
+            resolved,
+            closest,
+            closest,
+            resolved
+        ),
+        None => Ok(resolved),
+    }
 }
 
 /// Log a single metric. Returns the created Metric.
 pub fn log_metric(db: &Database, config: &Config, entry: LogEntry<'_>) -> Result<Metric> {
-    let resolved = config.resolve_alias(entry.metric_type);
+    let m = prepare_metric(db, config, entry)?;
+    db.insert_metric(&m)?;
+    Ok(m)
+}
+
+/// The metric [`log_metric`] would store, without storing it.
+pub fn prepare_metric(db: &Database, config: &Config, entry: LogEntry<'_>) -> Result<Metric> {
+    let resolved = resolve_type(db, config, entry.metric_type)?;
     let mut m = Metric::new(resolved, entry.value);
     if let Some(n) = entry.note {
         m.note = Some(n.to_string());
@@ -25,18 +122,69 @@ pub fn log_metric(db: &Database, config: &Config, entry: LogEntry<'_>) -> Result
     if let Some(t) = entry.tags {
         m.tags = t.split(',').map(|s| s.trim().to_string()).collect();
     }
-    if let Some(s) = entry.source {
-        m.source = s.to_string();
+    m.source = entry.source.to_string();
+    if let Some(d) = entry.date {
+        m.timestamp = crate::core::time::local_noon_utc(d, db.timezone());
     }
-    if let Some(d) = entry.date
-        && let Some(dt) = d.and_hms_opt(12, 0, 0)
-    {
-        m.timestamp = Utc.from_utc_datetime(&dt);
+    if let Some(u) = entry.unit {
+        m.unit = u.trim().to_string();
     }
-    db.insert_metric(&m)?;
+    crate::core::types::apply(db, &mut m)?;
     Ok(m)
 }
 
+/// Whether a typed value is relative (`+0.5`, `-0.5`) rather than absolute.
+pub fn is_relative(value: &str) -> bool {
+    value.starts_with(['+', '-'])
+}
+
+/// The value to store for a relative `delta` of `metric_type`, as typed (in
+/// display units unless `as_stored`). Types summed per day take the delta as
+/// a new entry, so only increments are accepted; other types add it to their
+/// latest entry.
+pub fn resolve_delta(
+    db: &Database,
+    config: &Config,
+    metric_type: &str,
+    delta: f64,
+    as_stored: bool,
+) -> Result<f64> {
+    let units = &config.units;
+    if config.aggregation_for(metric_type) == Aggregation::Sum {
+        if delta < 0.0 {
+            crate::fail!(
+                validation,
+                "'{}' is summed per day, so it can only be incremented (got {})",
+                metric_type,
+                delta
+            );
+        }
+        return Ok(if as_stored {
+            delta
+        } else {
+            crate::core::units::from_input(delta, metric_type, units)
+        });
+    }
+    let Some(latest) = db
+        .query_by_type(metric_type, Some(1), SortOrder::Desc)?
+        .into_iter()
+        .next()
+    else {
+        crate::fail!(
+            not_found,
+            "no previous '{}' entry to adjust; log an absolute value first",
+            metric_type
+        );
+    };
+    // Conversions are affine (°F has an offset), so convert the delta as a difference
+    let to_stored = |v| crate::core::units::from_input(v, metric_type, units);
+    Ok(if as_stored {
+        latest.value + delta
+    } else {
+        latest.value + to_stored(delta) - to_stored(0.0)
+    })
+}
+
 /// Log a blood pressure compound value (e.g., "120/80").
 /// Parses the value, converts units, and creates two metric entries (systolic + diastolic).
 pub fn log_blood_pressure(
@@ -45,24 +193,47 @@ pub fn log_blood_pressure(
     value_str: &str,
     note: Option<&str>,
     tags: Option<&str>,
-    source: Option<&str>,
+    source: &Source,
+    date: Option<NaiveDate>,
+) -> Result<(Metric, Metric)> {
+    let (m1, m2) = prepare_blood_pressure(db, config, value_str, note, tags, source, date)?;
+    // Both readings land together or not at all.
+    db.in_transaction(|db| {
+        db.insert_metric(&m1)?;
+        db.insert_metric(&m2)
+    })?;
+    Ok((m1, m2))
+}
+
+/// The systolic and diastolic entries [`log_blood_pressure`] would store,
+/// without storing them.
+pub fn prepare_blood_pressure(
+    db: &Database,
+    config: &Config,
+    value_str: &str,
+    note: Option<&str>,
+    tags: Option<&str>,
+    source: &Source,
     date: Option<NaiveDate>,
 ) -> Result<(Metric, Metric)> {
     let parts: Vec<&str> = value_str.split('/').collect();
     if parts.len() != 2 {
-        anyhow::bail!("blood pressure format must be SYSTOLIC/DIASTOLIC (e.g., 120/80)");
+        crate::fail!(
+            parse,
+            "blood pressure format must be SYSTOLIC/DIASTOLIC (e.g., 120/80)"
+        );
     }
     let systolic: f64 = parts[0]
         .parse()
-        .map_err(|_| anyhow::anyhow!("invalid systolic value"))?;
+        .map_err(|_| crate::error::parse("invalid systolic value"))?;
     let diastolic: f64 = parts[1]
         .parse()
-        .map_err(|_| anyhow::anyhow!("invalid diastolic value"))?;
+        .map_err(|_| crate::error::parse("invalid diastolic value"))?;
 
     let sys_metric = crate::core::units::from_input(systolic, "bp_systolic", &config.units);
     let dia_metric = crate::core::units::from_input(diastolic, "bp_diastolic", &config.units);
 
-    let m1 = log_metric(
+    let m1 = prepare_metric(
         db,
         config,
         LogEntry {
@@ -70,11 +241,12 @@ pub fn log_blood_pressure(
             value: sys_metric,
             note,
             tags,
-            source,
+            source: source.clone(),
             date,
+            unit: None,
         },
     )?;
-    let m2 = log_metric(
+    let m2 = prepare_metric(
         db,
         config,
         LogEntry {
@@ -82,28 +254,135 @@ pub fn log_blood_pressure(
             value: dia_metric,
             note,
             tags,
-            source,
+            source: source.clone(),
             date,
+            unit: None,
         },
     )?;
     Ok((m1, m2))
 }
 
+/// Tag added to every entry logged by `measure`.
+pub const MEASUREMENT_TAG: &str = "measurement-session";
+
+/// Log a set of `type=value` measurements (e.g. `waist=84`) taken together:
+/// every entry shares one timestamp and carries [`MEASUREMENT_TAG`] plus
+/// `tags`. All pairs are validated first and inserted in one transaction, so
+/// a bad pair logs nothing.
+pub fn log_measurements(
+    db: &Database,
+    config: &Config,
+    pairs: &[String],
+    note: Option<&str>,
+    tags: Option<&str>,
+    date: Option<NaiveDate>,
+) -> Result<Vec<Metric>> {
+    let timestamp = match date {
+        Some(d) => crate::core::time::local_noon_utc(d, db.timezone()),
+        None => Utc::now(),
+    };
+    let mut tag_list = vec![MEASUREMENT_TAG.to_string()];
+    if let Some(t) = tags {
+        tag_list.extend(
+            t.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty() && s != MEASUREMENT_TAG),
+        );
+    }
+
+    let mut metrics: Vec<Metric> = Vec::new();
+    for pair in pairs.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let Some((key, value)) = pair.split_once('=') else {
+            crate::fail!(
+                parse,
+                "invalid measurement '{}' (expected type=value)",
+                pair
+            );
+        };
+        let value: f64 = value
+            .trim()
+            .parse()
+            .map_err(|_| crate::error::parse(format!("invalid value in '{}'", pair)))?;
+        if !value.is_finite() {
+            crate::fail!(
+                validation,
+                "invalid value in '{}': not a finite number",
+                pair
+            );
+        }
+        let mut m = batch_metric(config, key.trim(), value);
+        if m.metric_type.is_empty() {
+            crate::fail!(validation, "missing type in '{}'", pair);
+        }
+        if m.metric_type == crate::models::metric::JOURNAL_TYPE {
+            crate::fail!(
+                validation,
+                "cannot log '{}': journal entries are notes",
+                pair
+            );
+        }
+        if metrics.iter().any(|e| e.metric_type == m.metric_type) {
+            crate::fail!(
+                validation,
+                "duplicate measurement '{}': {} is already given",
+                pair,
+                m.metric_type
+            );
+        }
+        m.timestamp = timestamp;
+        m.tags = tag_list.clone();
+        m.note = note.map(String::from);
+        metrics.push(m);
+    }
+    if metrics.is_empty() {
+        crate::fail!(
+            validation,
+            "no measurements given (expected type=value pairs)"
+        );
+    }
+
+    db.in_transaction(|db| {
+        for m in &mut metrics {
+            crate::core::types::apply(db, m)?;
+            db.insert_metric(m)?;
+        }
+        Ok(())
+    })?;
+    Ok(metrics)
+}
+
+/// Batches larger than this are committed in chunks of [`BATCH_CHUNK`].
+pub const LARGE_BATCH: usize = 10_000;
+/// Rows per transaction when logging a large batch.
+pub const BATCH_CHUNK: usize = 500;
+
 /// Batch-log metrics from a JSON array string. Returns created Metrics.
 pub fn log_batch(db: &Database, config: &Config, batch_json: &str) -> Result<Vec<Metric>> {
+    let mut metrics = parse_batch_json(config, batch_json)?;
+    db.in_transaction(|db| {
+        for m in &mut metrics {
+            crate::core::types::apply(db, m)?;
+            db.insert_metric(m)?;
+        }
+        Ok(())
+    })?;
+    Ok(metrics)
+}
+
+/// Build metrics from a JSON batch array of `{type, value, note?, tags?, timestamp?}`,
+/// resolving aliases and converting from the configured units.
+pub fn parse_batch_json(config: &Config, batch_json: &str) -> Result<Vec<Metric>> {
     let entries: Vec<serde_json::Value> = serde_json::from_str(batch_json)?;
     let mut results = Vec::new();
 
     for entry in &entries {
         let metric_type = entry["type"]
             .as_str()
-            .ok_or_else(|| anyhow::anyhow!("missing 'type' in batch entry"))?;
+            .ok_or_else(|| crate::error::parse("missing 'type' in batch entry"))?;
         let value = entry["value"]
             .as_f64()
-            .ok_or_else(|| anyhow::anyhow!("missing 'value' in batch entry"))?;
-        let resolved = config.resolve_alias(metric_type);
-        let value = crate::core::units::from_input(value, &resolved, &config.units);
-        let mut m = Metric::new(resolved, value);
+            .ok_or_else(|| crate::error::parse("missing 'value' in batch entry"))?;
+        let mut m = batch_metric(config, metric_type, value);
         if let Some(n) = entry["note"].as_str() {
             m.note = Some(n.to_string());
         }
@@ -113,13 +392,115 @@ pub fn log_batch(db: &Database, config: &Config, batch_json: &str) -> Result<Vec
                 .filter_map(|t| t.as_str().map(String::from))
                 .collect();
         }
-        db.insert_metric(&m)?;
+        if let Some(ts) = entry["timestamp"].as_str() {
+            m.timestamp = parse_timestamp(ts)?;
+        }
         results.push(m);
     }
 
     Ok(results)
 }
 
+/// Build metrics from CSV in the export schema (`timestamp,type,value,unit,note,tags,source`).
+/// Columns are matched by header name; only `type` and `value` are required, and
+/// rows without a timestamp are logged now. Values are in the configured units.
+pub fn parse_batch_csv(config: &Config, csv: &str) -> Result<Vec<Metric>> {
+    let mut lines = csv.lines().map(str::trim).filter(|l| !l.is_empty());
+    let header: Vec<String> = lines
+        .next()
+        .ok_or_else(|| crate::error::parse("CSV batch is empty"))?
+        .split(',')
+        .map(|h| h.trim().to_ascii_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(type_col), Some(value_col)) = (column("type"), column("value")) else {
+        crate::fail!(
+            parse,
+            "CSV batch header must include 'type' and 'value' columns"
+        );
+    };
+
+    let mut results = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let row = i + 2;
+        // The last column absorbs any extra commas, as in `import --source csv`.
+        let fields: Vec<&str> = line.splitn(header.len(), ',').collect();
+        let field = |col: Option<usize>| {
+            col.and_then(|c| fields.get(c))
+                .map(|f| f.trim())
+                .filter(|f| !f.is_empty())
+        };
+        let metric_type = field(Some(type_col))
+            .ok_or_else(|| crate::error::parse(format!("row {}: missing type", row)))?;
+        let value: f64 = field(Some(value_col))
+            .ok_or_else(|| crate::error::parse(format!("row {}: missing value", row)))?
+            .parse()
+            .map_err(|_| crate::error::parse(format!("row {}: invalid value", row)))?;
+
+        let mut m = batch_metric(config, metric_type, value);
+        if let Some(ts) = field(column("timestamp")) {
+            m.timestamp = parse_timestamp(ts)
+                .map_err(|e| crate::error::parse(format!("row {}: {}", row, e)))?;
+        }
+        m.note = field(column("note")).map(String::from);
+        if let Some(tags) = field(column("tags")) {
+            m.tags = if tags.starts_with('[') {
+                serde_json::from_str(tags)
+                    .map_err(|_| crate::error::parse(format!("row {}: invalid tags", row)))?
+            } else {
+                tags.split(',').map(|t| t.trim().to_string()).collect()
+            };
+        }
+        if let Some(source) = field(column("source")) {
+            m.source = source.to_string();
+        }
+        results.push(m);
+    }
+    Ok(results)
+}
+
+/// Insert already-built metrics: one transaction for ordinary batches, or
+/// chunks of [`BATCH_CHUNK`] past [`LARGE_BATCH`] entries. `progress` gets
+/// `(logged, total)` after each committed chunk. Custom type definitions are
+/// applied to the metrics as they are inserted.
+pub fn insert_batch(
+    db: &Database,
+    metrics: &mut [Metric],
+    mut progress: impl FnMut(usize, usize),
+) -> Result<()> {
+    let chunk = if metrics.len() > LARGE_BATCH {
+        BATCH_CHUNK
+    } else {
+        metrics.len().max(1)
+    };
+    let mut logged = 0;
+    let total = metrics.len();
+    for part in metrics.chunks_mut(chunk) {
+        db.in_transaction(|db| {
+            for m in part.iter_mut() {
+                crate::core::types::apply(db, m)?;
+                db.insert_metric(m)?;
+            }
+            Ok(())
+        })?;
+        logged += part.len();
+        progress(logged, total);
+    }
+    Ok(())
+}
+
+fn batch_metric(config: &Config, metric_type: &str, value: f64) -> Metric {
+    let resolved = config.resolve_alias(metric_type);
+    let value = crate::core::units::from_input(value, &resolved, &config.units);
+    Metric::new(resolved, value)
+}
+
+fn parse_timestamp(ts: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(ts)
+        .map_err(|_| crate::error::parse(format!("invalid timestamp: {}", ts)))?
+        .with_timezone(&Utc))
+}
+
 /// Convert simple batch format ("weight:72.5,sleep:7.5") to JSON array string.
 pub fn parse_simple_batch(input: &str) -> Result<String> {
     let entries: Vec<serde_json::Value> = input
@@ -127,11 +508,15 @@ pub fn parse_simple_batch(input: &str) -> Result<String> {
         .map(|pair| {
             let parts: Vec<&str> = pair.trim().splitn(2, ':').collect();
             if parts.len() != 2 {
-                anyhow::bail!("invalid batch entry: '{}' (expected type:value)", pair);
+                crate::fail!(
+                    parse,
+                    "invalid batch entry: '{}' (expected type:value)",
+                    pair
+                );
             }
             let value: f64 = parts[1]
                 .parse()
-                .map_err(|_| anyhow::anyhow!("invalid value in '{}'", pair))?;
+                .map_err(|_| crate::error::parse(format!("invalid value in '{}'", pair)))?;
             Ok(serde_json::json!({"type": parts[0].trim(), "value": value}))
         })
         .collect::<Result<Vec<_>>>()?;
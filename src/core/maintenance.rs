@@ -0,0 +1,68 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::db::stats::{TableCount, TypeCount};
+
+#[derive(Debug, Serialize)]
+pub struct DbStats {
+    pub schema_version: i64,
+    pub size_bytes: u64,
+    pub free_bytes: u64,
+    pub encrypted: bool,
+    pub tables: Vec<TableCount>,
+    pub metric_types: Vec<TypeCount>,
+    /// Oldest and newest metric timestamps, if any entries exist.
+    pub earliest: Option<DateTime<Utc>>,
+    pub latest: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    pub ok: bool,
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompactResult {
+    pub size_before: u64,
+    pub size_after: u64,
+    pub reclaimed_bytes: u64,
+}
+
+/// Size, row counts per table and per metric type, and the span of logged data.
+pub fn stats(db: &Database) -> Result<DbStats> {
+    let size = db.file_size()?;
+    let metric_types = db.metric_type_counts()?;
+    Ok(DbStats {
+        schema_version: db.schema_version()?,
+        size_bytes: size.size_bytes,
+        free_bytes: size.free_bytes,
+        encrypted: db.is_encrypted(),
+        tables: db.table_counts()?,
+        earliest: metric_types.iter().map(|t| t.earliest).min(),
+        latest: metric_types.iter().map(|t| t.latest).max(),
+        metric_types,
+    })
+}
+
+/// Run SQLite's full integrity check.
+pub fn check(db: &Database) -> Result<CheckResult> {
+    let problems = db.integrity_check()?;
+    Ok(CheckResult {
+        ok: problems.is_empty(),
+        problems,
+    })
+}
+
+/// `VACUUM` the database, reporting the size before and after.
+pub fn compact(db: &Database) -> Result<CompactResult> {
+    let size_before = db.file_size()?.size_bytes;
+    let reclaimed_bytes = db.vacuum()?;
+    Ok(CompactResult {
+        size_before,
+        size_after: db.file_size()?.size_bytes,
+        reclaimed_bytes,
+    })
+}
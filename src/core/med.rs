@@ -1,11 +1,14 @@
-use anyhow::{Result, bail};
-use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::Serialize;
 use uuid::Uuid;
 
-use crate::db::Database;
+use crate::core::time;
+use crate::db::{Database, SortOrder};
 use crate::models::config::Config;
-use crate::models::med::{Frequency, Medication, Route, parse_dose};
+use crate::models::med::{
+    Frequency, Interaction, Medication, MedicationChanges, Refill, Route, parse_dose,
+};
 use crate::models::metric::{Category, Metric};
 
 // ---------------------------------------------------------------------------
@@ -20,11 +23,53 @@ pub struct MedStatus {
     pub frequency: String,
     pub required_today: Option<u32>,
     pub taken_today: u32,
+    /// Doses recorded with `med take --skip` today.
+    pub skipped_today: u32,
+    /// `None` for as-needed medications, and for a day whose missing doses
+    /// were all skipped while `skips_preserve_streak` is on.
     pub adherent_today: Option<bool>,
     pub streak_days: Option<u32>,
     pub adherence_7d: Option<f64>,
+    /// Only computed when a single medication is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub adherence_30d: Option<f64>,
+    /// Only computed when a single medication is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub adherence_history: Option<Vec<DayAdherence>>,
+    /// Refill quantity minus doses taken since the last refill.
+    pub estimated_remaining: Option<u32>,
+    /// Last refill date plus its days supply, "YYYY-MM-DD".
+    pub estimated_empty: Option<String>,
+}
+
+/// Compact per-medication row for the multi-medication `med status` view.
+#[derive(Debug, Serialize)]
+pub struct MedSummary {
+    pub name: String,
+    pub frequency: String,
+    pub required_today: Option<u32>,
+    pub taken_today: u32,
+    pub skipped_today: u32,
+    pub adherent_today: Option<bool>,
+    pub adherence_7d: Option<f64>,
+    pub streak_days: Option<u32>,
+    pub estimated_empty: Option<String>,
+}
+
+impl From<&MedStatus> for MedSummary {
+    fn from(s: &MedStatus) -> Self {
+        Self {
+            name: s.name.clone(),
+            frequency: s.frequency.clone(),
+            required_today: s.required_today,
+            taken_today: s.taken_today,
+            skipped_today: s.skipped_today,
+            adherent_today: s.adherent_today,
+            adherence_7d: s.adherence_7d,
+            streak_days: s.streak_days,
+            estimated_empty: s.estimated_empty.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -32,9 +77,85 @@ pub struct DayAdherence {
     pub date: NaiveDate,
     pub required: u32,
     pub taken: u32,
+    pub skipped: u32,
     pub adherent: bool,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ScheduleSlot {
+    /// Approximate dose time, "HH:MM".
+    pub time: String,
+    pub name: String,
+    pub dose: Option<String>,
+    pub taken: bool,
+}
+
+/// Doses of an as-needed medication over a recent window, with the reasons given.
+#[derive(Debug, Serialize)]
+pub struct PrnSummary {
+    pub name: String,
+    pub days: u32,
+    pub doses_taken: u32,
+    /// Most frequent indication; ties go to the most recent.
+    pub most_common_indication: Option<String>,
+    /// Indications by number of intakes, most frequent first.
+    pub indications: Vec<IndicationCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndicationCount {
+    pub indication: String,
+    pub count: u32,
+}
+
+/// One recorded intake, as listed by `med history`.
+#[derive(Debug, Serialize)]
+pub struct DoseEvent {
+    pub timestamp: DateTime<Utc>,
+    /// The override dose if one was given, otherwise the medication's dose.
+    pub dose_used: Option<String>,
+    pub quantity: u32,
+    pub note: Option<String>,
+}
+
+/// A dose taken too close to one of an interacting medication.
+#[derive(Debug, Serialize)]
+pub struct InteractionWarning {
+    pub medication: String,
+    pub interacts_with: String,
+    pub min_gap_minutes: u32,
+    /// Minutes between this dose and the nearest dose of `interacts_with`.
+    pub gap_minutes: i64,
+    pub message: String,
+}
+
+/// How one medication went over a report period.
+#[derive(Debug, Serialize)]
+pub struct MedPeriodSummary {
+    pub name: String,
+    pub frequency: String,
+    /// Share of scheduled days (weeks) in the period that were adherent;
+    /// `None` for as-needed medications or when nothing was scheduled.
+    pub adherence: Option<f64>,
+    pub doses_taken: u32,
+    /// Doses scheduled in the period, counted from the start date if the
+    /// medication was started within it. `None` for as-needed medications.
+    pub doses_expected: Option<u32>,
+    /// Set when the medication was started within the period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started: Option<NaiveDate>,
+    /// Set when the medication was stopped within the period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<NaiveDate>,
+}
+
+/// Note prefix marking the indication of an as-needed dose.
+const PRN_PREFIX: &str = "prn:";
+
+/// Waking window that fixed-frequency doses are spread over, in minutes after midnight.
+const SCHEDULE_START_MIN: u32 = 6 * 60;
+const SCHEDULE_END_MIN: u32 = 22 * 60;
+
 // ---------------------------------------------------------------------------
 // AddMedicationParams
 // ---------------------------------------------------------------------------
@@ -47,6 +168,8 @@ pub struct AddMedicationParams<'a> {
     pub route: Option<&'a str>,
     pub note: Option<&'a str>,
     pub started: Option<NaiveDate>,
+    /// `other_med[:min_gap_minutes]` entries from `--interacts-with`.
+    pub interacts_with: &'a [String],
 }
 
 // ---------------------------------------------------------------------------
@@ -55,7 +178,7 @@ pub struct AddMedicationParams<'a> {
 
 pub fn add_medication(
     db: &Database,
-    _config: &Config,
+    config: &Config,
     params: AddMedicationParams<'_>,
 ) -> Result<Medication> {
     let frequency: Frequency = params.freq.parse()?;
@@ -79,10 +202,18 @@ pub fn add_medication(
         med.note = Some(n.to_string());
     }
 
-    if let Some(d) = params.started
-        && let Some(dt) = d.and_hms_opt(12, 0, 0)
-    {
-        med.started_at = Utc.from_utc_datetime(&dt);
+    if let Some(d) = params.started {
+        med.started_at = time::local_noon_utc(d, db.timezone());
+    }
+
+    for spec in params.interacts_with {
+        let mut interaction: Interaction = spec.parse()?;
+        interaction.with = config.resolve_alias(&interaction.with);
+        if interaction.with == med.name {
+            crate::fail!(validation, "'{}' cannot interact with itself", med.name);
+        }
+        med.interactions.retain(|i| i.with != interaction.with);
+        med.interactions.push(interaction);
     }
 
     match db.insert_medication(&med) {
@@ -90,7 +221,8 @@ pub fn add_medication(
         Err(e) => {
             let msg = e.to_string();
             if msg.contains("UNIQUE") || msg.contains("unique") || msg.contains("constraint") {
-                bail!(
+                crate::fail!(
+                    conflict,
                     "Medication '{}' is already active. Use `med stop` first, then `med add` to restart with new settings.",
                     params.name
                 );
@@ -100,10 +232,28 @@ pub fn add_medication(
     }
 }
 
+// ---------------------------------------------------------------------------
+// TakeMedicationParams
+// ---------------------------------------------------------------------------
+
+/// Parameters for recording an intake.
+pub struct TakeMedicationParams<'a> {
+    pub name: &'a str,
+    pub dose: Option<&'a str>,
+    pub note: Option<&'a str>,
+    pub tags: Option<&'a str>,
+    pub date: Option<NaiveDate>,
+    /// Doses taken at once; stored as the entry's value.
+    pub quantity: u32,
+    /// Why an as-needed dose was taken; stored as a `prn: <reason>` note prefix.
+    pub indication: Option<&'a str>,
+}
+
 // ---------------------------------------------------------------------------
 // take_medication
 // ---------------------------------------------------------------------------
 
+/// Record a single dose.
 pub fn take_medication(
     db: &Database,
     config: &Config,
@@ -113,16 +263,51 @@ pub fn take_medication(
     tags: Option<&str>,
     date: Option<NaiveDate>,
 ) -> Result<(Metric, Medication)> {
+    take_medication_with(
+        db,
+        config,
+        TakeMedicationParams {
+            name,
+            dose: dose_override,
+            note,
+            tags,
+            date,
+            quantity: 1,
+            indication: None,
+        },
+    )
+}
+
+/// Record `params.quantity` doses as one entry whose value is the quantity.
+pub fn take_medication_with(
+    db: &Database,
+    config: &Config,
+    params: TakeMedicationParams<'_>,
+) -> Result<(Metric, Medication)> {
+    let TakeMedicationParams {
+        name,
+        dose: dose_override,
+        note,
+        tags,
+        date,
+        quantity,
+        indication,
+    } = params;
+    if quantity == 0 {
+        crate::fail!(validation, "quantity must be at least 1");
+    }
+    let indication = indication.map(str::trim).filter(|i| !i.is_empty());
     let resolved = config.resolve_alias(name);
+    let medication = find_medication(db, &resolved)?;
 
-    // Look up medication: active first, then any
-    let medication = match db.get_medication_by_name(&resolved)? {
-        Some(m) => m,
-        None => match db.get_medication_by_name_any(&resolved)? {
-            Some(m) => m,
-            None => bail!("Medication '{}' not found. Use `med add` first.", resolved),
-        },
-    };
+    if indication.is_some() && medication.frequency != Frequency::AsNeeded {
+        crate::fail!(
+            validation,
+            "--indication is only for as-needed medications; '{}' is {}",
+            medication.name,
+            medication.frequency
+        );
+    }
 
     let is_stopped = !medication.active;
 
@@ -143,14 +328,21 @@ pub fn take_medication(
         (None, false, Some(n)) => Some(n.to_string()),
         (None, false, None) => None,
     };
+    let final_note = match indication {
+        Some(ind) => {
+            let ind = ind.replace(';', ",");
+            Some(match final_note {
+                Some(n) => format!("{PRN_PREFIX} {ind}; {n}"),
+                None => format!("{PRN_PREFIX} {ind}"),
+            })
+        }
+        None => final_note,
+    };
 
     // Build timestamp
-    let timestamp = if let Some(d) = date
-        && let Some(dt) = d.and_hms_opt(12, 0, 0)
-    {
-        Utc.from_utc_datetime(&dt)
-    } else {
-        Utc::now()
+    let timestamp = match date {
+        Some(d) => time::local_noon_utc(d, db.timezone()),
+        None => Utc::now(),
     };
 
     // Build tags
@@ -164,7 +356,7 @@ pub fn take_medication(
         timestamp,
         category: Category::Medication,
         metric_type: resolved,
-        value: 1.0,
+        value: quantity as f64,
         unit: "dose".to_string(),
         note: final_note,
         tags: parsed_tags,
@@ -176,6 +368,290 @@ pub fn take_medication(
     Ok((metric, medication))
 }
 
+/// Look up a medication by name: active first, then any.
+fn find_medication(db: &Database, name: &str) -> Result<Medication> {
+    match db.get_medication_by_name(name)? {
+        Some(m) => Ok(m),
+        None => match db.get_medication_by_name_any(name)? {
+            Some(m) => Ok(m),
+            None => crate::fail!(
+                not_found,
+                "Medication '{}' not found. Use `med add` first.",
+                name
+            ),
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// skip_medication
+// ---------------------------------------------------------------------------
+
+/// Record one intentionally skipped dose: value 0, source `med_skip`, and
+/// the reason as the note. Skips never count as doses taken.
+pub fn skip_medication(
+    db: &Database,
+    config: &Config,
+    name: &str,
+    reason: Option<&str>,
+    date: Option<NaiveDate>,
+) -> Result<(Metric, Medication)> {
+    let resolved = config.resolve_alias(name);
+    let medication = find_medication(db, &resolved)?;
+    if medication.frequency == Frequency::AsNeeded {
+        crate::fail!(
+            validation,
+            "--skip is only for scheduled medications; '{}' is {}",
+            medication.name,
+            medication.frequency
+        );
+    }
+
+    let timestamp = match date {
+        Some(d) => time::local_noon_utc(d, db.timezone()),
+        None => Utc::now(),
+    };
+    let metric = Metric {
+        id: Uuid::new_v4().to_string(),
+        timestamp,
+        category: Category::Medication,
+        metric_type: resolved,
+        value: 0.0,
+        unit: "dose".to_string(),
+        note: reason
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(String::from),
+        tags: Vec::new(),
+        source: "med_skip".to_string(),
+    };
+    db.insert_metric(&metric)?;
+
+    Ok((metric, medication))
+}
+
+// ---------------------------------------------------------------------------
+// interaction_warnings
+// ---------------------------------------------------------------------------
+
+/// Doses of medications interacting with `taken` (a `med take` entry) that
+/// fall within the required gap of it, one warning per medication for the
+/// nearest dose. Interactions declared on either side count; the larger gap
+/// wins. Never blocks the take.
+pub fn interaction_warnings(db: &Database, taken: &Metric) -> Result<Vec<InteractionWarning>> {
+    let mut gaps: Vec<(String, u32)> = Vec::new();
+    let mut add = |with: &str, gap: u32| match gaps.iter_mut().find(|(w, _)| w == with) {
+        Some(g) => g.1 = g.1.max(gap),
+        None => gaps.push((with.to_string(), gap)),
+    };
+    for i in &find_medication(db, &taken.metric_type)?.interactions {
+        add(&i.with, i.min_gap_minutes);
+    }
+    for med in db.list_medications(false)? {
+        if let Some(i) = med
+            .interactions
+            .iter()
+            .find(|i| i.with == taken.metric_type)
+        {
+            add(&med.name, i.min_gap_minutes);
+        }
+    }
+
+    let tz = db.timezone();
+    let mut warnings = Vec::new();
+    for (with, min_gap) in gaps {
+        let window = chrono::Duration::minutes(i64::from(min_gap));
+        let from = time::local_date(&(taken.timestamp - window), tz);
+        let to = time::local_date(&(taken.timestamp + window), tz);
+        let nearest = db
+            .query_by_date_range(from, to, SortOrder::Asc)?
+            .into_iter()
+            .filter(|m| m.metric_type == with && m.source == "med_take" && m.id != taken.id)
+            .map(|m| (m.timestamp - taken.timestamp).abs())
+            .min();
+        if let Some(gap) = nearest.filter(|g| *g < window) {
+            let gap_minutes = gap.num_minutes();
+            warnings.push(InteractionWarning {
+                message: format!(
+                    "{} was taken {} min from this dose of {}; keep them at least {} min apart",
+                    with, gap_minutes, taken.metric_type, min_gap
+                ),
+                medication: taken.metric_type.clone(),
+                interacts_with: with,
+                min_gap_minutes: min_gap,
+                gap_minutes,
+            });
+        }
+    }
+    Ok(warnings)
+}
+
+// ---------------------------------------------------------------------------
+// refill_medication
+// ---------------------------------------------------------------------------
+
+/// Record a refill of `quantity` doses lasting `days_supply` days, dated `date`
+/// (default: now).
+pub fn refill_medication(
+    db: &Database,
+    config: &Config,
+    name: &str,
+    quantity: u32,
+    days_supply: u32,
+    date: Option<NaiveDate>,
+) -> Result<(Refill, Medication)> {
+    if quantity == 0 {
+        crate::fail!(validation, "refill quantity must be greater than 0");
+    }
+    if days_supply == 0 {
+        crate::fail!(validation, "days supply must be greater than 0");
+    }
+    let resolved = config.resolve_alias(name);
+    let medication = match db.get_medication_by_name(&resolved)? {
+        Some(m) => m,
+        None => match db.get_medication_by_name_any(&resolved)? {
+            Some(m) => m,
+            None => crate::fail!(
+                not_found,
+                "Medication '{}' not found. Use `med add` first.",
+                resolved
+            ),
+        },
+    };
+
+    let now = Utc::now();
+    let refill = Refill {
+        med_id: medication.id.clone(),
+        quantity,
+        days_supply,
+        refill_date: match date {
+            Some(d) => time::local_noon_utc(d, db.timezone()),
+            None => now,
+        },
+        created_at: now,
+    };
+    db.insert_refill(&refill)?;
+
+    Ok((refill, medication))
+}
+
+/// Estimated doses left and run-out date from the latest refill, counting
+/// intakes between the refill and the end of `today`.
+fn refill_estimate(
+    db: &Database,
+    med: &Medication,
+    today: NaiveDate,
+) -> Result<(Option<u32>, Option<String>)> {
+    let Some(refill) = db.latest_refill(&med.id)? else {
+        return Ok((None, None));
+    };
+    let refill_day = time::local_date(&refill.refill_date, db.timezone());
+    let taken = db
+        .query_all(Some(&med.name), Some(refill_day), Some(today))?
+        .iter()
+        .filter(|m| m.source == "med_take" && m.timestamp >= refill.refill_date)
+        .map(|m| m.value.round() as u32)
+        .sum::<u32>();
+    let empty = refill_day + chrono::Duration::days(refill.days_supply as i64);
+    Ok((
+        Some(refill.quantity.saturating_sub(taken)),
+        Some(empty.format("%Y-%m-%d").to_string()),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// prn_summary
+// ---------------------------------------------------------------------------
+
+/// Doses of `name` taken over the last `days` days (including today) and how
+/// often each indication was given.
+pub fn prn_summary(db: &Database, name: &str, days: u32) -> Result<PrnSummary> {
+    let medication = match db.get_medication_by_name_any(name)? {
+        Some(m) => m,
+        None => crate::fail!(not_found, "Medication '{}' not found", name),
+    };
+    let days = days.max(1);
+    let today = time::local_today(db.timezone().name());
+    let from = today - chrono::Duration::days(days as i64 - 1);
+    let entries = db.query_all(Some(&medication.name), Some(from), Some(today))?;
+
+    // Newest first so ties in the sort below favour the most recent indication.
+    let mut indications: Vec<IndicationCount> = Vec::new();
+    for m in entries.iter().rev().filter(|m| m.source == "med_take") {
+        let Some(ind) = parse_indication(m.note.as_deref()) else {
+            continue;
+        };
+        match indications.iter_mut().find(|c| c.indication == ind) {
+            Some(c) => c.count += 1,
+            None => indications.push(IndicationCount {
+                indication: ind,
+                count: 1,
+            }),
+        }
+    }
+    indications.sort_by(|a, b| b.count.cmp(&a.count));
+
+    Ok(PrnSummary {
+        doses_taken: doses_taken(&entries, &medication.name),
+        most_common_indication: indications.first().map(|c| c.indication.clone()),
+        indications,
+        name: medication.name,
+        days,
+    })
+}
+
+/// The indication recorded in a `prn: <reason>; ...` intake note, if any.
+pub fn parse_indication(note: Option<&str>) -> Option<String> {
+    let rest = note?.trim_start().strip_prefix(PRN_PREFIX)?;
+    let reason = rest.split(';').next().unwrap_or_default().trim();
+    if reason.is_empty() {
+        None
+    } else {
+        Some(reason.to_string())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// dose_history
+// ---------------------------------------------------------------------------
+
+/// Every `med take` event for `name` over the last `last_days` days (including
+/// today), newest first.
+pub fn dose_history(db: &Database, name: &str, last_days: u32) -> Result<Vec<DoseEvent>> {
+    let medication = match db.get_medication_by_name_any(name)? {
+        Some(m) => m,
+        None => crate::fail!(not_found, "Medication '{}' not found", name),
+    };
+    let today = time::local_today(db.timezone().name());
+    let from = today - chrono::Duration::days(last_days.max(1) as i64 - 1);
+
+    // Takes are keyed by name, so a renamed medication only shows events
+    // recorded under its current name.
+    let mut events: Vec<DoseEvent> = db
+        .query_all(Some(&medication.name), Some(from), Some(today))?
+        .into_iter()
+        .filter(|m| m.source == "med_take")
+        .map(|m| DoseEvent {
+            timestamp: m.timestamp,
+            dose_used: dose_override(m.note.as_deref()).or_else(|| medication.dose.clone()),
+            quantity: m.value.round() as u32,
+            note: m.note,
+        })
+        .collect();
+    events.reverse();
+    Ok(events)
+}
+
+/// The dose recorded with `--dose` in an intake note (`<dose> (override)...`).
+fn dose_override(note: Option<&str>) -> Option<String> {
+    let mut note = note?;
+    if let Some(rest) = note.strip_prefix(PRN_PREFIX) {
+        note = rest.split_once(';').map(|(_, r)| r).unwrap_or_default();
+    }
+    let (dose, _) = note.trim_start().split_once(" (override)")?;
+    Some(dose.to_string())
+}
+
 // ---------------------------------------------------------------------------
 // stop_medication
 // ---------------------------------------------------------------------------
@@ -186,17 +662,91 @@ pub fn stop_medication(
     reason: Option<&str>,
     date: Option<NaiveDate>,
 ) -> Result<bool> {
-    let stopped_at = if let Some(d) = date
-        && let Some(dt) = d.and_hms_opt(12, 0, 0)
-    {
-        Utc.from_utc_datetime(&dt)
-    } else {
-        Utc::now()
+    let stopped_at = match date {
+        Some(d) => time::local_noon_utc(d, db.timezone()),
+        None => Utc::now(),
     };
 
     db.stop_medication(name, stopped_at, reason)
 }
 
+// ---------------------------------------------------------------------------
+// edit_medication
+// ---------------------------------------------------------------------------
+
+/// Parameters for `med edit`; `None` leaves a field unchanged.
+pub struct EditMedicationParams<'a> {
+    pub name: &'a str,
+    pub dose: Option<&'a str>,
+    pub freq: Option<&'a str>,
+    pub route: Option<&'a str>,
+    pub note: Option<&'a str>,
+    /// New name; also moves the medication's entries.
+    pub rename: Option<&'a str>,
+}
+
+/// An edited medication, before and after.
+#[derive(Debug)]
+pub struct MedEdit {
+    pub before: Medication,
+    pub medication: Medication,
+    /// Entries moved to the new name, when renamed.
+    pub renamed_entries: Option<usize>,
+}
+
+/// Update an active medication in place, keeping its adherence history.
+pub fn edit_medication(
+    db: &Database,
+    config: &Config,
+    params: EditMedicationParams<'_>,
+) -> Result<MedEdit> {
+    let changes = MedicationChanges {
+        dose: params.dose.map(String::from),
+        frequency: params.freq.map(str::parse).transpose()?,
+        route: params.route.map(|r| r.parse().unwrap_or(Route::Oral)),
+        note: params.note.map(String::from),
+    };
+    if changes.is_empty() && params.rename.is_none() {
+        crate::fail!(
+            validation,
+            "nothing to change; pass --dose, --freq, --route, --note or --rename"
+        );
+    }
+
+    let mut name = config.resolve_alias(params.name);
+    let Some(before) = db.get_medication_by_name(&name)? else {
+        crate::fail!(
+            not_found,
+            "Medication '{}' not found or already stopped.",
+            name
+        );
+    };
+
+    db.in_transaction(|db| {
+        let mut renamed_entries = None;
+        if let Some(new) = params.rename {
+            let new = config.resolve_alias(new.trim());
+            if new.is_empty() || new == name {
+                crate::fail!(validation, "--rename needs a different name");
+            }
+            if db.get_medication_by_name_any(&new)?.is_some() {
+                crate::fail!(conflict, "Medication '{}' already exists.", new);
+            }
+            renamed_entries = Some(db.rename_medication(&name, &new)?);
+            name = new;
+        }
+        if !changes.is_empty() {
+            db.update_medication(&name, &changes)?;
+        }
+        let medication = find_medication(db, &name)?;
+        Ok(MedEdit {
+            before,
+            medication,
+            renamed_entries,
+        })
+    })
+}
+
 // ---------------------------------------------------------------------------
 // remove_medication
 // ---------------------------------------------------------------------------
@@ -213,42 +763,103 @@ pub fn list_medications(db: &Database, include_stopped: bool) -> Result<Vec<Medi
     db.list_medications(include_stopped)
 }
 
+// ---------------------------------------------------------------------------
+// schedule
+// ---------------------------------------------------------------------------
+
+/// Today's dose calendar for active medications. Each dose gets the midpoint of
+/// an equal share of 06:00-22:00; the first `taken_today` slots count as taken.
+/// Weekly medications appear only on the weekday they were started; as-needed
+/// medications have no slots.
+pub fn schedule(db: &Database) -> Result<Vec<ScheduleSlot>> {
+    let today = time::local_today(db.timezone().name());
+    let meds = db.list_medications(false)?;
+    let today_entries = db.query_by_date(today, SortOrder::Asc)?;
+
+    let mut slots = Vec::new();
+    for med in &meds {
+        let taken = doses_taken(&today_entries, &med.name);
+        let doses = match med.frequency {
+            Frequency::Weekly => {
+                let started = time::local_date(&med.started_at, db.timezone());
+                u32::from(started.weekday() == today.weekday())
+            }
+            ref f => f.required_on(today).unwrap_or(0),
+        };
+        for (i, time) in dose_times(doses).into_iter().enumerate() {
+            slots.push(ScheduleSlot {
+                time,
+                name: med.name.clone(),
+                dose: med.dose.clone(),
+                taken: (i as u32) < taken,
+            });
+        }
+    }
+    slots.sort_by(|a, b| a.time.cmp(&b.time).then_with(|| a.name.cmp(&b.name)));
+    Ok(slots)
+}
+
+/// `n` evenly spaced "HH:MM" times across the schedule window.
+pub fn dose_times(n: u32) -> Vec<String> {
+    let span = SCHEDULE_END_MIN - SCHEDULE_START_MIN;
+    (0..n)
+        .map(|i| {
+            let min = SCHEDULE_START_MIN + span * (2 * i + 1) / (2 * n);
+            format!("{:02}:{:02}", min / 60, min % 60)
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // adherence_status
 // ---------------------------------------------------------------------------
 
 pub fn adherence_status(
     db: &Database,
+    config: &Config,
+    name: Option<&str>,
+    last_days: u32,
+) -> Result<Vec<MedStatus>> {
+    let today = time::local_today(db.timezone().name());
+    adherence_status_on(db, config, name, last_days, today)
+}
+
+/// Adherence as of `today`: intakes after that day are ignored.
+pub fn adherence_status_on(
+    db: &Database,
+    config: &Config,
     name: Option<&str>,
     last_days: u32,
+    today: NaiveDate,
 ) -> Result<Vec<MedStatus>> {
     let meds = if let Some(n) = name {
         match db.get_medication_by_name(n)? {
             Some(m) => vec![m],
             None => match db.get_medication_by_name_any(n)? {
                 Some(m) => vec![m],
-                None => bail!("Medication '{}' not found.", n),
+                None => crate::fail!(not_found, "Medication '{}' not found.", n),
             },
         }
     } else {
         db.list_medications(false)?
+            .into_iter()
+            .filter(|m| time::local_date(&m.started_at, db.timezone()) <= today)
+            .collect()
     };
 
     let single_med = name.is_some();
-    let today = Utc::now().date_naive();
+    let skips_preserve = config.medications.skips_preserve_streak;
 
     let mut results = Vec::new();
     for med in &meds {
-        let required_per_day = med.frequency.required_per_day();
+        let required_per_day = med.frequency.required_on(today);
         let is_as_needed = med.frequency == Frequency::AsNeeded;
         let is_weekly = med.frequency == Frequency::Weekly;
 
         // Count today's intakes
-        let today_entries = db.query_by_date(today)?;
-        let taken_today = today_entries
-            .iter()
-            .filter(|m| m.metric_type == med.name && m.source == "med_take")
-            .count() as u32;
+        let today_entries = db.query_by_date(today, SortOrder::Asc)?;
+        let taken_today = doses_taken(&today_entries, &med.name);
+        let skipped_today = doses_skipped(&today_entries, &med.name);
 
         // required_today
         let required_today = if is_weekly || is_as_needed {
@@ -261,31 +872,31 @@ pub fn adherence_status(
         let adherent_today = if is_as_needed {
             None
         } else if is_weekly {
-            let weekday = today.weekday().num_days_from_monday();
-            let week_start = today - chrono::Duration::days(weekday as i64);
-            let week_entries = db.query_by_date_range(week_start, today)?;
-            let taken_this_week = week_entries
-                .iter()
-                .filter(|m| m.metric_type == med.name && m.source == "med_take")
-                .count();
-            Some(taken_this_week >= 1)
+            let week_start = time::week_start_for(today, db.week_start());
+            week_outcome(db, &med.name, week_start, today, skips_preserve)?.adherent()
         } else {
-            Some(taken_today >= required_per_day.unwrap_or(0))
+            outcome(
+                taken_today,
+                skipped_today,
+                required_per_day.unwrap_or(0),
+                skips_preserve,
+            )
+            .adherent()
         };
 
         // Compute streak and adherence over last N days
         let (streak_days, adherence_7d, adherence_30d, adherence_history) = if is_as_needed {
             (None, None, None, None)
         } else {
-            let started_date = med.started_at.date_naive();
-            let stopped_date = med.stopped_at.map(|t| t.date_naive());
+            let started_date = time::local_date(&med.started_at, db.timezone());
+            let stopped_date = med.stopped_at.map(|t| time::local_date(&t, db.timezone()));
 
-            // Streak: count backward from today
+            // Streak: count backward from today; skipped days (weeks) neither
+            // extend nor break it
             let mut streak = 0u32;
             if is_weekly {
                 // For weekly: iterate week by week
-                let weekday = today.weekday().num_days_from_monday();
-                let mut week_start = today - chrono::Duration::days(weekday as i64);
+                let mut week_start = time::week_start_for(today, db.week_start());
                 loop {
                     if week_start < started_date - chrono::Duration::days(6) {
                         break;
@@ -296,15 +907,10 @@ pub fn adherence_status(
                         break;
                     }
                     let week_end = week_start + chrono::Duration::days(6);
-                    let entries = db.query_by_date_range(week_start, week_end)?;
-                    let taken = entries
-                        .iter()
-                        .filter(|m| m.metric_type == med.name && m.source == "med_take")
-                        .count();
-                    if taken >= 1 {
-                        streak += 1;
-                    } else {
-                        break;
+                    match week_outcome(db, &med.name, week_start, week_end, skips_preserve)? {
+                        Outcome::Adherent => streak += 1,
+                        Outcome::Skipped => {}
+                        Outcome::Missed => break,
                     }
                     week_start -= chrono::Duration::days(7);
                 }
@@ -319,37 +925,32 @@ pub fn adherence_status(
                     {
                         break;
                     }
-                    let day_adherent = check_day_adherent(db, &med.name, day, &med.frequency)?;
-                    if day_adherent {
-                        streak += 1;
-                    } else {
-                        break;
+                    // Days off a custom schedule neither extend nor break the streak
+                    if !med.frequency.is_scheduled_on(day) {
+                        continue;
+                    }
+                    match day_outcome(db, &med.name, day, &med.frequency, skips_preserve)? {
+                        Outcome::Adherent => streak += 1,
+                        Outcome::Skipped => {}
+                        Outcome::Missed => break,
                     }
                 }
             }
 
-            // 7-day adherence
-            let adh_7d = compute_adherence_window(
-                db,
-                &med.name,
-                &med.frequency,
-                today,
-                7,
+            let window = AdherenceWindow {
+                med_name: &med.name,
+                frequency: &med.frequency,
                 started_date,
                 stopped_date,
-            )?;
+                skips_preserve,
+            };
+
+            // 7-day adherence
+            let adh_7d = compute_adherence_window(db, &window, today, 7)?;
 
             // 30-day adherence (only for single med)
             let adh_30d = if single_med {
-                compute_adherence_window(
-                    db,
-                    &med.name,
-                    &med.frequency,
-                    today,
-                    30,
-                    started_date,
-                    stopped_date,
-                )?
+                compute_adherence_window(db, &window, today, 30)?
             } else {
                 None
             };
@@ -359,8 +960,7 @@ pub fn adherence_status(
                 let mut days = Vec::new();
                 if is_weekly {
                     // Show weekly history entries
-                    let weekday = today.weekday().num_days_from_monday();
-                    let current_week_start = today - chrono::Duration::days(weekday as i64);
+                    let current_week_start = time::week_start_for(today, db.week_start());
                     let weeks = last_days.div_ceil(7);
                     for i in 0..weeks {
                         let ws = current_week_start - chrono::Duration::days(i as i64 * 7);
@@ -373,15 +973,13 @@ pub fn adherence_status(
                         {
                             continue;
                         }
-                        let entries = db.query_by_date_range(ws, we)?;
-                        let taken = entries
-                            .iter()
-                            .filter(|m| m.metric_type == med.name && m.source == "med_take")
-                            .count() as u32;
+                        let entries = db.query_by_date_range(ws, we, SortOrder::Asc)?;
+                        let taken = doses_taken(&entries, &med.name);
                         days.push(DayAdherence {
                             date: ws,
                             required: 1,
                             taken,
+                            skipped: doses_skipped(&entries, &med.name),
                             adherent: taken >= 1,
                         });
                     }
@@ -396,17 +994,18 @@ pub fn adherence_status(
                         {
                             continue;
                         }
-                        let required = day_required(&med.frequency);
-                        let day_entries = db.query_by_date(day)?;
-                        let taken = day_entries
-                            .iter()
-                            .filter(|m| m.metric_type == med.name && m.source == "med_take")
-                            .count() as u32;
+                        if !med.frequency.is_scheduled_on(day) {
+                            continue;
+                        }
+                        let required = day_required(&med.frequency, day);
+                        let day_entries = db.query_by_date(day, SortOrder::Asc)?;
+                        let taken = doses_taken(&day_entries, &med.name);
                         let adherent = taken >= required;
                         days.push(DayAdherence {
                             date: day,
                             required,
                             taken,
+                            skipped: doses_skipped(&day_entries, &med.name),
                             adherent,
                         });
                     }
@@ -419,6 +1018,8 @@ pub fn adherence_status(
             (Some(streak), adh_7d, adh_30d, history)
         };
 
+        let (estimated_remaining, estimated_empty) = refill_estimate(db, med, today)?;
+
         results.push(MedStatus {
             name: med.name.clone(),
             dose: med.dose.clone(),
@@ -426,114 +1027,295 @@ pub fn adherence_status(
             frequency: med.frequency.to_string(),
             required_today,
             taken_today,
+            skipped_today,
             adherent_today,
             streak_days,
             adherence_7d,
             adherence_30d,
             adherence_history,
+            estimated_remaining,
+            estimated_empty,
         });
     }
 
     Ok(results)
 }
 
+// ---------------------------------------------------------------------------
+// period_summary
+// ---------------------------------------------------------------------------
+
+/// Adherence of every medication active at some point in `from..=to`, for
+/// reports. Days after today are not counted as missed.
+pub fn period_summary(
+    db: &Database,
+    config: &Config,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<MedPeriodSummary>> {
+    let tz = db.timezone();
+    let end = to.min(time::local_today(tz.name()));
+    let mut courses: Vec<Medication> = Vec::new();
+    for med in db.list_medications(true)? {
+        let started = time::local_date(&med.started_at, tz);
+        let stopped = med.stopped_at.map(|t| time::local_date(&t, tz));
+        if started > to || stopped.is_some_and(|d| d < from) {
+            continue;
+        }
+        // A medication stopped and added again: keep the latest course
+        match courses.iter_mut().find(|m| m.name == med.name) {
+            Some(m) if m.started_at < med.started_at => *m = med,
+            Some(_) => {}
+            None => courses.push(med),
+        }
+    }
+    if courses.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let entries = db.query_by_date_range(from, to, SortOrder::Asc)?;
+    let mut results = Vec::new();
+    for med in &courses {
+        let window = AdherenceWindow {
+            med_name: &med.name,
+            frequency: &med.frequency,
+            started_date: time::local_date(&med.started_at, tz),
+            stopped_date: med.stopped_at.map(|t| time::local_date(&t, tz)),
+            skips_preserve: config.medications.skips_preserve_streak,
+        };
+        let (adherence, doses_expected) = if med.frequency == Frequency::AsNeeded || end < from {
+            (None, None)
+        } else {
+            let days = (end - from).num_days() as u32 + 1;
+            (
+                compute_adherence_window(db, &window, end, days)?,
+                Some(expected_doses(&window, from, end, db.week_start())),
+            )
+        };
+        results.push(MedPeriodSummary {
+            name: med.name.clone(),
+            frequency: med.frequency.to_string(),
+            adherence,
+            doses_taken: doses_taken(&entries, &med.name),
+            doses_expected,
+            started: Some(window.started_date).filter(|d| (from..=to).contains(d)),
+            stopped: window.stopped_date.filter(|d| (from..=to).contains(d)),
+        });
+    }
+    Ok(results)
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
-/// Check if a specific day is adherent for a given medication.
-fn check_day_adherent(
+/// How a scheduled day (or week, for weekly medications) went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Adherent,
+    /// Every dose not taken was skipped on purpose: neither adherent nor a
+    /// streak-breaker.
+    Skipped,
+    Missed,
+}
+
+impl Outcome {
+    fn adherent(self) -> Option<bool> {
+        match self {
+            Self::Adherent => Some(true),
+            Self::Skipped => None,
+            Self::Missed => Some(false),
+        }
+    }
+}
+
+/// A day with `required` doses; skips only excuse it with `skips_preserve`.
+fn outcome(taken: u32, skipped: u32, required: u32, skips_preserve: bool) -> Outcome {
+    if taken >= required {
+        Outcome::Adherent
+    } else if skips_preserve && skipped > 0 && taken + skipped >= required {
+        Outcome::Skipped
+    } else {
+        Outcome::Missed
+    }
+}
+
+/// Doses of `med_name` recorded by `med take` among `entries`; an entry taken
+/// with `--quantity N` counts N times.
+fn doses_taken(entries: &[Metric], med_name: &str) -> u32 {
+    entries
+        .iter()
+        .filter(|m| m.metric_type == med_name && m.source == "med_take")
+        .map(|m| m.value.round() as u32)
+        .sum()
+}
+
+/// Doses of `med_name` recorded by `med take --skip` among `entries`.
+fn doses_skipped(entries: &[Metric], med_name: &str) -> u32 {
+    entries
+        .iter()
+        .filter(|m| m.metric_type == med_name && m.source == "med_skip")
+        .count() as u32
+}
+
+/// How a specific day went for a given medication.
+fn day_outcome(
     db: &Database,
     med_name: &str,
     day: NaiveDate,
     frequency: &Frequency,
-) -> Result<bool> {
-    let required = frequency.required_per_day().unwrap_or(1);
-    let entries = db.query_by_date(day)?;
-    let taken = entries
-        .iter()
-        .filter(|m| m.metric_type == med_name && m.source == "med_take")
-        .count() as u32;
-    Ok(taken >= required)
+    skips_preserve: bool,
+) -> Result<Outcome> {
+    let required = day_required(frequency, day);
+    let entries = db.query_by_date(day, SortOrder::Asc)?;
+    Ok(outcome(
+        doses_taken(&entries, med_name),
+        doses_skipped(&entries, med_name),
+        required,
+        skips_preserve,
+    ))
 }
 
-/// Check if a given week is adherent for a weekly medication.
-fn check_week_adherent(
+/// How a given week went for a weekly medication.
+fn week_outcome(
     db: &Database,
     med_name: &str,
     week_start: NaiveDate,
     week_end: NaiveDate,
-) -> Result<bool> {
-    let entries = db.query_by_date_range(week_start, week_end)?;
-    let taken = entries
-        .iter()
-        .filter(|m| m.metric_type == med_name && m.source == "med_take")
-        .count();
-    Ok(taken >= 1)
+    skips_preserve: bool,
+) -> Result<Outcome> {
+    let entries = db.query_by_date_range(week_start, week_end, SortOrder::Asc)?;
+    Ok(outcome(
+        doses_taken(&entries, med_name),
+        doses_skipped(&entries, med_name),
+        1,
+        skips_preserve,
+    ))
 }
 
 /// Compute required doses for a day depending on frequency.
 /// For weekly meds, returns 1 per day (used in daily history display).
-fn day_required(frequency: &Frequency) -> u32 {
-    frequency.required_per_day().unwrap_or(1)
+fn day_required(frequency: &Frequency, day: NaiveDate) -> u32 {
+    frequency.required_on(day).unwrap_or(1)
+}
+
+/// Share of the scheduled days (weeks for weekly medications) in the `days`
+/// days ending `today` on which every dose was taken. Skipped days are left
+/// out when `[medications] skips_preserve_streak` is on. `None` for as-needed
+/// medications or when nothing was scheduled.
+pub fn adherence_window(
+    db: &Database,
+    config: &Config,
+    med: &Medication,
+    today: NaiveDate,
+    days: u32,
+) -> Result<Option<f64>> {
+    if med.frequency == Frequency::AsNeeded {
+        return Ok(None);
+    }
+    let window = AdherenceWindow {
+        med_name: &med.name,
+        frequency: &med.frequency,
+        started_date: time::local_date(&med.started_at, db.timezone()),
+        stopped_date: med.stopped_at.map(|t| time::local_date(&t, db.timezone())),
+        skips_preserve: config.medications.skips_preserve_streak,
+    };
+    compute_adherence_window(db, &window, today, days)
+}
+
+/// Doses scheduled in `from..=to` while the medication was being taken; one
+/// per week (weeks starting on `week_start`) for weekly medications.
+fn expected_doses(
+    med: &AdherenceWindow,
+    from: NaiveDate,
+    to: NaiveDate,
+    week_start: chrono::Weekday,
+) -> u32 {
+    let first = from.max(med.started_date);
+    let last = med.stopped_date.map_or(to, |sd| to.min(sd));
+    first
+        .iter_days()
+        .take_while(|day| *day <= last)
+        .map(|day| match med.frequency {
+            Frequency::Weekly => u32::from(day == first || day.weekday() == week_start),
+            f => f.required_on(day).unwrap_or(0),
+        })
+        .sum()
+}
+
+/// The medication an adherence window is computed for.
+struct AdherenceWindow<'a> {
+    med_name: &'a str,
+    frequency: &'a Frequency,
+    started_date: NaiveDate,
+    stopped_date: Option<NaiveDate>,
+    skips_preserve: bool,
 }
 
 /// Compute adherence percentage over a window.
 /// For weekly meds, iterates by week. For others, by day.
 fn compute_adherence_window(
     db: &Database,
-    med_name: &str,
-    frequency: &Frequency,
+    med: &AdherenceWindow,
     today: NaiveDate,
     window: u32,
-    started_date: NaiveDate,
-    stopped_date: Option<NaiveDate>,
 ) -> Result<Option<f64>> {
     let mut eligible = 0u32;
     let mut adherent_count = 0u32;
+    let mut count = |o: Outcome| match o {
+        Outcome::Adherent => {
+            eligible += 1;
+            adherent_count += 1;
+        }
+        Outcome::Missed => eligible += 1,
+        Outcome::Skipped => {}
+    };
 
-    if *frequency == Frequency::Weekly {
+    if *med.frequency == Frequency::Weekly {
         // Iterate by week for weekly meds
-        let weekday = today.weekday().num_days_from_monday();
-        let current_week_start = today - chrono::Duration::days(weekday as i64);
+        let current_week_start = time::week_start_for(today, db.week_start());
         let weeks = window.div_ceil(7); // Convert day window to weeks
         for i in 0..weeks {
             let week_start = current_week_start - chrono::Duration::days(i as i64 * 7);
             let week_end = week_start + chrono::Duration::days(6);
-            if week_end < started_date {
+            if week_end < med.started_date {
                 continue;
             }
-            if let Some(sd) = stopped_date
+            if let Some(sd) = med.stopped_date
                 && week_start > sd
             {
                 continue;
             }
-            eligible += 1;
-            if check_week_adherent(db, med_name, week_start, week_end)? {
-                adherent_count += 1;
-            }
-        }
-
-        return if eligible == 0 {
-            Ok(None)
-        } else {
-            Ok(Some(f64::from(adherent_count) / f64::from(eligible)))
-        };
-    }
-
-    for i in 0..window {
-        let day = today - chrono::Duration::days(i as i64);
-        if day < started_date {
-            continue;
-        }
-        if let Some(sd) = stopped_date
-            && day > sd
-        {
-            continue;
+            count(week_outcome(
+                db,
+                med.med_name,
+                week_start,
+                week_end,
+                med.skips_preserve,
+            )?);
         }
-        eligible += 1;
-        if check_day_adherent(db, med_name, day, frequency)? {
-            adherent_count += 1;
+    } else {
+        for i in 0..window {
+            let day = today - chrono::Duration::days(i as i64);
+            if day < med.started_date {
+                continue;
+            }
+            if let Some(sd) = med.stopped_date
+                && day > sd
+            {
+                continue;
+            }
+            // Days off a custom schedule are not missed doses
+            if !med.frequency.is_scheduled_on(day) {
+                continue;
+            }
+            count(day_outcome(
+                db,
+                med.med_name,
+                day,
+                med.frequency,
+                med.skips_preserve,
+            )?);
         }
     }
 
@@ -1,11 +1,30 @@
+pub mod analytics;
 pub mod anomaly;
+pub mod backup;
+pub mod bp;
 pub mod context;
+pub mod cycle;
 pub mod export;
+pub mod expr;
+pub mod glucose;
 pub mod goal;
+pub mod insights;
+pub mod journal;
 pub mod logging;
+pub mod maintenance;
 pub mod med;
+pub mod parquet;
+pub mod profile;
+pub mod projection;
 pub mod query;
+pub mod remind;
 pub mod report;
+pub mod search;
+pub mod sleep;
 pub mod status;
+pub mod sync;
+pub mod time;
 pub mod trend;
+pub mod types;
 pub mod units;
+pub mod workout;
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use parquet::data_type::{ByteArray, ByteArrayType, DataType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use parquet::schema::parser::parse_message_type;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::models::metric::Metric;
+
+/// Rows per row group in exports; most databases fit in one.
+pub const ROW_GROUP_ROWS: usize = 65_536;
+
+const SCHEMA: &str = "message metrics {
+    REQUIRED INT64 timestamp (TIMESTAMP(MILLIS, true));
+    REQUIRED BYTE_ARRAY metric_type (UTF8);
+    REQUIRED DOUBLE value;
+    REQUIRED BYTE_ARRAY unit (UTF8);
+    OPTIONAL BYTE_ARRAY note (UTF8);
+    REQUIRED group tags (LIST) {
+        REPEATED group list {
+            REQUIRED BYTE_ARRAY element (UTF8);
+        }
+    }
+}";
+
+/// Encode `entries` as an uncompressed Parquet file with the columns
+/// `timestamp` (INT64, Unix ms), `metric_type`, `value` (DOUBLE), `unit`,
+/// `note` (optional) and `tags` (LIST of strings), `group_rows` rows per
+/// row group.
+pub fn encode_metrics(entries: &[Metric], group_rows: usize) -> Result<Vec<u8>> {
+    let schema = Arc::new(parse_message_type(SCHEMA)?);
+    let props = Arc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(Vec::new(), schema, props)?;
+    for rows in entries.chunks(group_rows.max(1)) {
+        let mut group = writer.next_row_group()?;
+        let timestamps: Vec<i64> = rows
+            .iter()
+            .map(|m| m.timestamp.timestamp_millis())
+            .collect();
+        write_column::<Int64Type>(&mut group, &timestamps, None, None)?;
+        let types: Vec<ByteArray> = rows.iter().map(|m| m.metric_type.as_str().into()).collect();
+        write_column::<ByteArrayType>(&mut group, &types, None, None)?;
+        let values: Vec<f64> = rows.iter().map(|m| m.value).collect();
+        write_column::<DoubleType>(&mut group, &values, None, None)?;
+        let units: Vec<ByteArray> = rows.iter().map(|m| m.unit.as_str().into()).collect();
+        write_column::<ByteArrayType>(&mut group, &units, None, None)?;
+
+        let notes: Vec<ByteArray> = rows
+            .iter()
+            .filter_map(|m| m.note.as_deref().map(ByteArray::from))
+            .collect();
+        let note_defs: Vec<i16> = rows.iter().map(|m| i16::from(m.note.is_some())).collect();
+        write_column::<ByteArrayType>(&mut group, &notes, Some(&note_defs), None)?;
+
+        // An empty list is one level (0, 0) with no value; each tag is
+        // defined (1) and all but a row's first repeat the list (1).
+        let (mut tags, mut tag_defs, mut tag_reps) = (Vec::new(), Vec::new(), Vec::new());
+        for m in rows {
+            if m.tags.is_empty() {
+                tag_defs.push(0);
+                tag_reps.push(0);
+            }
+            for (i, tag) in m.tags.iter().enumerate() {
+                tags.push(ByteArray::from(tag.as_str()));
+                tag_defs.push(1);
+                tag_reps.push(i16::from(i > 0));
+            }
+        }
+        write_column::<ByteArrayType>(&mut group, &tags, Some(&tag_defs), Some(&tag_reps))?;
+        group.close()?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+/// Write the row group's next leaf column, with its definition and
+/// repetition levels when the schema has them.
+fn write_column<T: DataType>(
+    group: &mut SerializedRowGroupWriter<'_, Vec<u8>>,
+    values: &[T::T],
+    defs: Option<&[i16]>,
+    reps: Option<&[i16]>,
+) -> Result<()> {
+    let Some(mut column) = group.next_column()? else {
+        anyhow::bail!("parquet schema has fewer columns than the export");
+    };
+    column.typed::<T>().write_batch(values, defs, reps)?;
+    column.close()?;
+    Ok(())
+}
+
+/// What a Parquet footer says about its file.
+#[derive(Debug)]
+pub struct FileSummary {
+    pub num_rows: i64,
+    pub row_groups: usize,
+    /// Leaf column names, in schema order.
+    pub columns: Vec<String>,
+}
+
+/// Read the row count, row groups and leaf columns from a Parquet file's footer.
+pub fn read_summary(path: &Path) -> Result<FileSummary> {
+    let reader = SerializedFileReader::new(std::fs::File::open(path)?).map_err(|e| {
+        crate::error::parse(format!("{} is not a Parquet file: {}", path.display(), e))
+    })?;
+    let meta = reader.metadata();
+    Ok(FileSummary {
+        num_rows: meta.file_metadata().num_rows(),
+        row_groups: meta.num_row_groups(),
+        columns: meta
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect(),
+    })
+}
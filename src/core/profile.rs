@@ -0,0 +1,140 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::models::config::{Config, DEFAULT_PROFILE, Settings};
+
+#[derive(Debug, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub path: String,
+    pub is_default: bool,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileList {
+    pub active: String,
+    pub default: String,
+    pub profiles: Vec<ProfileInfo>,
+}
+
+/// Profile names are used as directory names: lowercase letters, digits,
+/// `-` and `_`.
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        crate::fail!(
+            validation,
+            "invalid profile name: '{}' (use lowercase letters, digits, '-' or '_')",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Whether a profile exists. [`DEFAULT_PROFILE`] always does.
+pub fn exists(name: &str) -> bool {
+    name == DEFAULT_PROFILE || Config::profile_dir(name).is_dir()
+}
+
+/// Names of all profiles, [`DEFAULT_PROFILE`] first and the rest sorted.
+pub fn names() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let dir = Config::home_dir().join("profiles");
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(&dir)
+            .map_err(|e| crate::error::io(&e, format!("cannot read {}: {}", dir.display(), e)))?
+        {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && let Some(name) = entry.file_name().to_str()
+                && validate_name(name).is_ok()
+                && name != DEFAULT_PROFILE
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    Ok(names)
+}
+
+pub fn list() -> Result<ProfileList> {
+    let settings = Settings::load()?;
+    let default = settings.default_profile().to_string();
+    let active = Config::active_profile();
+    let profiles = names()?
+        .into_iter()
+        .map(|name| ProfileInfo {
+            path: Config::profile_dir(&name).display().to_string(),
+            is_default: name == default,
+            is_active: name == active,
+            name,
+        })
+        .collect();
+    Ok(ProfileList {
+        active,
+        default,
+        profiles,
+    })
+}
+
+/// Create a profile with a default config (and default aliases), returning
+/// its directory.
+pub fn create(name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    if exists(name) {
+        crate::fail!(conflict, "profile '{}' already exists", name);
+    }
+    let dir = Config::profile_dir(name);
+    let config = Config {
+        aliases: Config::default_aliases(),
+        ..Config::default()
+    };
+    config.save_to(&dir.join("config.toml"))?;
+    Ok(dir)
+}
+
+/// Delete a profile's directory with its config, database and backups. The
+/// default profile (and [`DEFAULT_PROFILE`], which holds the data directory
+/// itself) cannot be removed, so at least one profile always remains.
+pub fn remove(name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    if !exists(name) {
+        crate::fail!(not_found, "profile '{}' not found", name);
+    }
+    if name == DEFAULT_PROFILE {
+        crate::fail!(
+            validation,
+            "the '{}' profile lives in the data directory itself and cannot be removed",
+            name
+        );
+    }
+    if name == Settings::load()?.default_profile() {
+        crate::fail!(
+            validation,
+            "cannot remove the default profile '{}'; make another profile the default first",
+            name
+        );
+    }
+    let dir = Config::profile_dir(name);
+    std::fs::remove_dir_all(&dir)
+        .map_err(|e| crate::error::io(&e, format!("cannot remove {}: {}", dir.display(), e)))?;
+    Ok(dir)
+}
+
+/// Make `name` the profile used when no `--profile` is given.
+pub fn set_default(name: &str) -> Result<()> {
+    validate_name(name)?;
+    if !exists(name) {
+        crate::fail!(not_found, "profile '{}' not found", name);
+    }
+    let mut settings = Settings::load()?;
+    settings.default_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+    settings.save()
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::core::trend;
+use crate::db::Database;
+use crate::models::goal::{Direction, Goal, Timeframe};
+use crate::models::metric::is_cumulative;
+
+/// Minimum number of days with data before a projection is attempted.
+pub const MIN_POINTS: usize = 3;
+
+/// Estimated arrival at a goal target, extrapolated from the daily linear trend.
+#[derive(Debug, Clone, Serialize)]
+pub struct Projection {
+    pub target: f64,
+    /// One of: converging, diverging, flat, met, insufficient_data, not_applicable.
+    pub direction: String,
+    pub estimated_date: Option<NaiveDate>,
+    /// R² of the linear fit (0–1); `None` when no fit was made.
+    pub confidence: Option<f64>,
+}
+
+impl Projection {
+    /// Whether the trend heads toward the target: `true` when converging or
+    /// already met, `false` when diverging or flat, `None` without a fit.
+    pub fn on_track(&self) -> Option<bool> {
+        match self.direction.as_str() {
+            "converging" | "met" => Some(true),
+            "diverging" | "flat" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn without_date(goal: &Goal, direction: &str, confidence: Option<f64>) -> Self {
+        Self {
+            target: goal.target_value,
+            direction: direction.to_string(),
+            estimated_date: None,
+            confidence,
+        }
+    }
+}
+
+/// Project when the goal's target will be reached based on the metric's daily values.
+pub fn project(db: &Database, goal: &Goal) -> Result<Projection> {
+    // Cumulative metrics are summed per day, which only lines up with daily targets.
+    if is_cumulative(&goal.metric_type) && goal.timeframe != Timeframe::Daily {
+        return Ok(Projection::without_date(goal, "not_applicable", None));
+    }
+
+    let series = trend::daily_series(db, &goal.metric_type)?;
+    if series.len() < MIN_POINTS {
+        return Ok(Projection::without_date(goal, "insufficient_data", None));
+    }
+
+    let first_date = *series.keys().next().unwrap();
+    let (last_date, current) = series.last_key_value().map(|(d, v)| (*d, *v)).unwrap();
+    let points: Vec<(f64, f64)> = series
+        .iter()
+        .map(|(d, v)| ((*d - first_date).num_days() as f64, *v))
+        .collect();
+    let (slope, intercept, r_squared) = linear_fit(&points);
+    let confidence = Some((r_squared * 100.0).round() / 100.0);
+
+    let target = goal.target_value;
+    let met = match goal.direction {
+        Direction::Below => current <= target,
+        Direction::Above => current >= target,
+        Direction::Equal => (current - target).abs() < 0.01,
+    };
+    if met {
+        return Ok(Projection::without_date(goal, "met", confidence));
+    }
+
+    // Treat a change of under 0.5% of the current level per 30 days as no movement.
+    if (slope * 30.0).abs() <= current.abs() * 0.005 || slope.abs() < f64::EPSILON {
+        return Ok(Projection::without_date(goal, "flat", confidence));
+    }
+
+    // For "equal" goals the needed direction depends on which side of the target we are on.
+    let needs_increase = match goal.direction {
+        Direction::Above => true,
+        Direction::Below => false,
+        Direction::Equal => target > current,
+    };
+    if (slope > 0.0) != needs_increase {
+        return Ok(Projection::without_date(goal, "diverging", confidence));
+    }
+
+    let last_x = (last_date - first_date).num_days() as f64;
+    let fitted_now = slope * last_x + intercept;
+    let days = ((target - fitted_now) / slope).ceil().max(0.0) as i64;
+
+    Ok(Projection {
+        target,
+        direction: "converging".to_string(),
+        estimated_date: Some(last_date + chrono::Duration::days(days)),
+        confidence,
+    })
+}
+
+/// Least-squares fit returning (slope, intercept, r_squared).
+fn linear_fit(points: &[(f64, f64)]) -> (f64, f64, f64) {
+    let n = points.len() as f64;
+    let sum_x: f64 = points.iter().map(|(x, _)| x).sum();
+    let sum_y: f64 = points.iter().map(|(_, y)| y).sum();
+    let sum_xy: f64 = points.iter().map(|(x, y)| x * y).sum();
+    let sum_xx: f64 = points.iter().map(|(x, _)| x * x).sum();
+
+    let denom = n * sum_xx - sum_x * sum_x;
+    if denom.abs() < f64::EPSILON {
+        return (0.0, sum_y / n, 0.0);
+    }
+    let slope = (n * sum_xy - sum_x * sum_y) / denom;
+    let intercept = (sum_y - slope * sum_x) / n;
+
+    let mean_y = sum_y / n;
+    let ss_tot: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    let ss_res: f64 = points
+        .iter()
+        .map(|(x, y)| (y - (slope * x + intercept)).powi(2))
+        .sum();
+    let r_squared = if ss_tot < f64::EPSILON {
+        0.0
+    } else {
+        (1.0 - ss_res / ss_tot).clamp(0.0, 1.0)
+    };
+
+    (slope, intercept, r_squared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_fit_perfect_line() {
+        let (slope, intercept, r2) = linear_fit(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)]);
+        assert!((slope - 2.0).abs() < 1e-9);
+        assert!((intercept - 1.0).abs() < 1e-9);
+        assert!((r2 - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_linear_fit_constant_values() {
+        let (slope, _, r2) = linear_fit(&[(0.0, 5.0), (1.0, 5.0), (2.0, 5.0)]);
+        assert_eq!(slope, 0.0);
+        assert_eq!(r2, 0.0);
+    }
+}
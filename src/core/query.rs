@@ -1,9 +1,12 @@
 use anyhow::Result;
-use chrono::{Local, NaiveDate};
+use chrono::NaiveDate;
+use serde::Serialize;
 
-use crate::db::Database;
+use crate::core::bp::{self, BpCategory};
+use crate::db::{Database, SortOrder};
 use crate::models::config::Config;
 use crate::models::metric::Metric;
+use crate::models::range::RangeStatus;
 
 pub enum ShowResult {
     ByType {
@@ -14,6 +17,73 @@ pub enum ShowResult {
         date: NaiveDate,
         entries: Vec<Metric>,
     },
+    /// `show blood_pressure`: systolic/diastolic entries zipped into readings.
+    BloodPressure {
+        readings: Vec<crate::core::bp::BpReading>,
+    },
+}
+
+/// Entries for one metric type in a multi-type `show`.
+#[derive(Debug, Serialize)]
+pub struct TypeGroup {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub entries: Vec<Metric>,
+}
+
+/// An entry with where its value falls in the type's `[ranges]` band.
+#[derive(Debug, Serialize)]
+pub struct RangedEntry<'a> {
+    #[serde(flatten)]
+    pub entry: &'a Metric,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_status: Option<RangeStatus>,
+    /// Stage of the blood pressure reading a systolic/diastolic entry belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bp_category: Option<BpCategory>,
+}
+
+/// Annotate entries with their range status; types without a range get none.
+pub fn with_range_status<'a>(entries: &'a [Metric], config: &Config) -> Vec<RangedEntry<'a>> {
+    entries
+        .iter()
+        .map(|entry| RangedEntry {
+            entry,
+            range_status: config.range_status(&entry.metric_type, entry.value),
+            bp_category: None,
+        })
+        .collect()
+}
+
+/// [`with_range_status`], plus the stage of the reading each systolic or
+/// diastolic entry forms with its partner (see [`bp::category_of`]).
+pub fn annotate<'a>(
+    db: &Database,
+    entries: &'a [Metric],
+    config: &Config,
+) -> Result<Vec<RangedEntry<'a>>> {
+    let mut ranged = with_range_status(entries, config);
+    for r in &mut ranged {
+        r.bp_category = bp::category_of(db, r.entry, &config.alerts.blood_pressure)?;
+    }
+    Ok(ranged)
+}
+
+/// Filters for [`show`] and [`show_types`]; the default lists the newest 10
+/// entries of a type, newest first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShowOptions<'a> {
+    /// Newest entries to keep: 10 by default, all of `from..=to` when a range is given.
+    pub last: Option<u32>,
+    /// A single day, listing every type's entries for it.
+    pub date: Option<NaiveDate>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Keep only entries carrying all of these.
+    pub tags: &'a [String],
+    /// Order of a type's history; `last` still selects the newest entries. A
+    /// day's entries are always listed oldest first.
+    pub order: SortOrder,
 }
 
 /// Query metrics by type or date.
@@ -21,26 +91,140 @@ pub fn show(
     db: &Database,
     config: &Config,
     metric_type: Option<&str>,
-    last: Option<u32>,
-    date: Option<NaiveDate>,
+    opts: &ShowOptions<'_>,
 ) -> Result<ShowResult> {
+    let ShowOptions {
+        last,
+        date,
+        from,
+        to,
+        tags,
+        order,
+    } = *opts;
     // `show today` or `show` with no args → today's entries
     if metric_type == Some("today") || (metric_type.is_none() && date.is_none()) {
-        let d = date.unwrap_or_else(|| Local::now().date_naive());
-        let entries = db.query_by_date(d)?;
+        let d = date.unwrap_or_else(|| crate::core::time::local_today(db.timezone().name()));
+        let entries = db.query_tagged(None, Some(d), Some(d), tags)?;
         return Ok(ShowResult::ByDate { date: d, entries });
     }
 
     if let Some(d) = date {
-        let entries = db.query_by_date(d)?;
+        let entries = db.query_tagged(None, Some(d), Some(d), tags)?;
         return Ok(ShowResult::ByDate { date: d, entries });
     }
 
     let metric_type = metric_type.unwrap();
     let resolved = config.resolve_alias(metric_type);
-    let entries = db.query_by_type(&resolved, Some(last.unwrap_or(10)))?;
+    if crate::core::bp::is_bp(&resolved) {
+        let mut readings = if from.is_some() || to.is_some() {
+            crate::core::bp::between(db, from, to, last, tags)?
+        } else {
+            crate::core::bp::recent(db, last.unwrap_or(10), tags)?
+        };
+        if order == SortOrder::Asc {
+            readings.reverse();
+        }
+        return Ok(ShowResult::BloodPressure { readings });
+    }
+    let entries = history(db, &resolved, opts)?;
+    if entries.is_empty()
+        && from.is_none()
+        && to.is_none()
+        && let Some(closest) = crate::core::types::suggest(db, &resolved)?
+    {
+        crate::fail!(
+            not_found,
+            "unknown metric type '{}'. Did you mean: {}?",
+            resolved,
+            closest
+        );
+    }
     Ok(ShowResult::ByType {
         metric_type: resolved,
         entries,
     })
 }
+
+/// Query several metric types at once, one group per requested type (in request order).
+/// Each type uses the same filters as single-type [`show`].
+pub fn show_types(
+    db: &Database,
+    config: &Config,
+    metric_types: &[&str],
+    opts: &ShowOptions<'_>,
+) -> Result<Vec<TypeGroup>> {
+    let day_entries = match opts.date {
+        Some(d) => Some(db.query_tagged(None, Some(d), Some(d), opts.tags)?),
+        None => None,
+    };
+
+    let mut groups = Vec::new();
+    for t in metric_types {
+        let resolved = config.resolve_alias(t.trim());
+        let entries = match &day_entries {
+            Some(day) => day
+                .iter()
+                .filter(|m| m.metric_type == resolved)
+                .cloned()
+                .collect(),
+            None => history(db, &resolved, opts)?,
+        };
+        groups.push(TypeGroup {
+            metric_type: resolved,
+            entries,
+        });
+    }
+    Ok(groups)
+}
+
+/// A type's history for `show`: the newest `last` (default 10) entries, or
+/// with a date range every entry in it (the newest `last` when given).
+fn history(db: &Database, metric_type: &str, opts: &ShowOptions<'_>) -> Result<Vec<Metric>> {
+    if opts.from.is_none() && opts.to.is_none() {
+        return recent_in(
+            db,
+            metric_type,
+            opts.last.unwrap_or(10),
+            opts.tags,
+            opts.order,
+        );
+    }
+    let mut entries = db.query_tagged(Some(metric_type), opts.from, opts.to, opts.tags)?;
+    if let Some(n) = opts.last {
+        entries.drain(..entries.len().saturating_sub(n as usize));
+    }
+    if opts.order == SortOrder::Desc {
+        entries.reverse();
+    }
+    Ok(entries)
+}
+
+/// The newest `n` entries of a type (newest first), restricted to `tags`.
+pub(crate) fn recent(
+    db: &Database,
+    metric_type: &str,
+    n: u32,
+    tags: &[String],
+) -> Result<Vec<Metric>> {
+    recent_in(db, metric_type, n, tags, SortOrder::Desc)
+}
+
+/// [`recent`], returned in `order`.
+fn recent_in(
+    db: &Database,
+    metric_type: &str,
+    n: u32,
+    tags: &[String],
+    order: SortOrder,
+) -> Result<Vec<Metric>> {
+    if tags.is_empty() {
+        return db.query_by_type(metric_type, Some(n), order);
+    }
+    let mut entries = db.query_tagged(Some(metric_type), None, None, tags)?;
+    entries.reverse();
+    entries.truncate(n as usize);
+    if order == SortOrder::Asc {
+        entries.reverse();
+    }
+    Ok(entries)
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::core::{med, status, time};
+use crate::db::Database;
+use crate::models::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderKind {
+    Metric,
+    Medication,
+}
+
+/// Something still owed today.
+#[derive(Debug, Serialize)]
+pub struct Reminder {
+    pub item: String,
+    pub kind: ReminderKind,
+    /// Entries (1 for a metric) or doses still missing today.
+    pub missing: u32,
+}
+
+/// What is still missing today.
+pub fn check(db: &Database, config: &Config) -> Result<Vec<Reminder>> {
+    check_on(db, config, time::local_today(db.timezone().name()))
+}
+
+/// Types in `[reminders] daily` with no entry on `day`, then medications on a
+/// fixed daily schedule with doses still owed that day. As-needed and weekly
+/// medications are never reported.
+pub fn check_on(db: &Database, config: &Config, day: NaiveDate) -> Result<Vec<Reminder>> {
+    let logged = status::logged_on(db, day)?;
+    let mut reminders = Vec::new();
+    for item in &config.reminders.daily {
+        let metric_type = config.resolve_alias(item);
+        if !logged.contains(&metric_type)
+            && !reminders.iter().any(|r: &Reminder| r.item == metric_type)
+        {
+            reminders.push(Reminder {
+                item: metric_type,
+                kind: ReminderKind::Metric,
+                missing: 1,
+            });
+        }
+    }
+
+    for s in med::adherence_status_on(db, config, None, 1, day)? {
+        let missing = s
+            .required_today
+            .map_or(0, |req| req.saturating_sub(s.taken_today + s.skipped_today));
+        if missing > 0 {
+            reminders.push(Reminder {
+                item: s.name,
+                kind: ReminderKind::Medication,
+                missing,
+            });
+        }
+    }
+    Ok(reminders)
+}
@@ -1,9 +1,19 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 
-use crate::db::Database;
+use crate::core::anomaly::{self, DEFAULT_ROLLING_WINDOW};
+use crate::core::glucose::{self, TimeInRange};
+use crate::core::med::{self, MedPeriodSummary};
+use crate::core::status::{self, StatusData};
+use crate::core::time;
+use crate::core::workout::{self, WorkoutWeek};
+use crate::db::{Database, SortOrder};
+use crate::models::anomaly::{Anomaly, Method, Threshold};
+use crate::models::config::Config;
+use crate::models::metric::{Aggregation, JOURNAL_TYPE};
+use crate::models::range::RangeStatus;
 
 #[derive(Debug, Serialize)]
 pub struct ReportResult {
@@ -12,6 +22,85 @@ pub struct ReportResult {
     pub days_with_entries: u32,
     pub total_entries: u32,
     pub metrics: Vec<MetricSummary>,
+    /// The preceding period the deltas were computed against (`--compare`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compared_to: Option<ComparedPeriod>,
+    /// Per-metric averages (and totals for sum-aggregated metrics) against
+    /// the previous period (`--compare`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub comparison: Vec<MetricComparison>,
+    /// The named period the range was derived from (`--period`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<String>,
+    /// Only entries carrying all of these tags were summarized.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Current goal status (`--goals`).
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub goals: Option<ReportGoals>,
+    /// Glucose time in range against `[glucose]` targets, when glucose was logged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glucose: Option<TimeInRange>,
+    /// Workout sessions per week, when any were logged.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub workouts: Vec<WorkoutWeek>,
+    /// Adherence of each medication active during the period, when any were.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub medications: Vec<MedPeriodSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportGoals {
+    pub goals: Vec<ReportGoal>,
+    pub goals_met: usize,
+    pub goals_total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportGoal {
+    pub metric_type: String,
+    pub target_value: f64,
+    pub direction: String,
+    pub timeframe: String,
+    pub current_value: Option<f64>,
+    pub is_met: bool,
+    pub progress: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComparedPeriod {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Change of one metric versus the previous period. Sum-aggregated metrics
+/// compare totals; everything else compares averages.
+#[derive(Debug, Default, Serialize)]
+pub struct MetricDelta {
+    pub prev_avg: Option<f64>,
+    pub delta: Option<f64>,
+    pub delta_pct: Option<f64>,
+}
+
+/// One row of the `comparison` section. `delta` is `current_avg - prev_avg`
+/// and is omitted when either period has no data.
+#[derive(Debug, Serialize)]
+pub struct MetricComparison {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub current_avg: Option<f64>,
+    pub prev_avg: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pct_change: Option<f64>,
+    /// Period totals, for sum-aggregated metrics such as water.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_delta: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -22,12 +111,41 @@ pub struct MetricSummary {
     pub avg: f64,
     pub min: f64,
     pub max: f64,
+    /// How `value` combines the period's entries.
+    pub aggregation: Aggregation,
+    pub value: f64,
     pub unit: String,
+    /// Where `avg` falls in the type's `[ranges]` band, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_status: Option<RangeStatus>,
+    /// Present only on compared reports; a metric seen only in the previous
+    /// period is listed with `count: 0`.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<MetricDelta>,
 }
 
 /// Generate a comprehensive report for the given date range.
 pub fn generate(db: &Database, from: NaiveDate, to: NaiveDate) -> Result<ReportResult> {
-    let entries = db.query_by_date_range(from, to)?;
+    generate_with(db, &Config::default(), None, from, to, &[])
+}
+
+/// Generate a report whose per-type `value` uses `agg` for every type, or the
+/// configured/default aggregation for each type when `agg` is `None`.
+/// Non-empty `tags` restrict the report to entries carrying all of them.
+pub fn generate_with(
+    db: &Database,
+    config: &Config,
+    agg: Option<Aggregation>,
+    from: NaiveDate,
+    to: NaiveDate,
+    tags: &[String],
+) -> Result<ReportResult> {
+    let entries = if tags.is_empty() {
+        db.query_by_date_range(from, to, SortOrder::Asc)?
+    } else {
+        db.query_tagged(None, Some(from), Some(to), tags)?
+    };
+    let medications = med::period_summary(db, config, from, to)?;
 
     if entries.is_empty() {
         return Ok(ReportResult {
@@ -36,16 +154,26 @@ pub fn generate(db: &Database, from: NaiveDate, to: NaiveDate) -> Result<ReportR
             days_with_entries: 0,
             total_entries: 0,
             metrics: Vec::new(),
+            compared_to: None,
+            comparison: Vec::new(),
+            period: None,
+            tags: tags.to_vec(),
+            goals: None,
+            glucose: None,
+            workouts: Vec::new(),
+            medications,
         });
     }
 
     // Count distinct days
-    let distinct_days: HashSet<NaiveDate> =
-        entries.iter().map(|e| e.timestamp.date_naive()).collect();
+    let distinct_days: HashSet<NaiveDate> = entries
+        .iter()
+        .map(|e| time::local_date(&e.timestamp, db.timezone()))
+        .collect();
 
-    // Group by metric type
+    // Group by metric type; journal notes count as logged days but carry no values
     let mut grouped: BTreeMap<String, Vec<(f64, String)>> = BTreeMap::new();
-    for entry in &entries {
+    for entry in entries.iter().filter(|e| e.metric_type != JOURNAL_TYPE) {
         grouped
             .entry(entry.metric_type.clone())
             .or_default()
@@ -62,13 +190,19 @@ pub fn generate(db: &Database, from: NaiveDate, to: NaiveDate) -> Result<ReportR
             let min = vals.iter().cloned().fold(f64::INFINITY, f64::min);
             let max = vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
             let unit = values.first().map(|(_, u)| u.clone()).unwrap_or_default();
+            let aggregation = agg.unwrap_or_else(|| config.aggregation_for(&metric_type));
+            let value = aggregation.apply(&vals);
             MetricSummary {
+                range_status: config.range_status(&metric_type, avg),
                 metric_type,
                 count,
                 avg,
                 min,
                 max,
+                aggregation,
+                value,
                 unit,
+                comparison: None,
             }
         })
         .collect();
@@ -79,5 +213,454 @@ pub fn generate(db: &Database, from: NaiveDate, to: NaiveDate) -> Result<ReportR
         days_with_entries: distinct_days.len() as u32,
         total_entries: entries.len() as u32,
         metrics,
+        compared_to: None,
+        comparison: Vec::new(),
+        period: None,
+        tags: tags.to_vec(),
+        goals: None,
+        glucose: glucose::time_in_range(&entries, &config.glucose, db.timezone()),
+        workouts: workout::weekly(&workout::sessions(&entries, db.timezone()), db.week_start()),
+        medications,
+    })
+}
+
+/// Date range for a named report period relative to `reference` (usually today):
+/// `week` (week to date, from the `week_start` day), `month` (month to date), `quarter` (the calendar quarter
+/// containing `reference`) or `ytd` (Jan 1 to `reference`). `year` moves `quarter`
+/// to the same quarter of that year and makes `ytd` cover all of a past year.
+pub fn period_bounds(
+    period: &str,
+    reference: NaiveDate,
+    year: Option<u16>,
+    week_start: chrono::Weekday,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let year = match year {
+        Some(y) if i32::from(y) > reference.year() => {
+            crate::fail!(validation, "--year {} is in the future", y)
+        }
+        Some(y) => i32::from(y),
+        None => reference.year(),
+    };
+    let ymd = |y: i32, m: u32, d: u32| {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .ok_or_else(|| crate::error::validation(format!("invalid year: {}", y)))
+    };
+    match period {
+        "week" => Ok((time::week_start_for(reference, week_start), reference)),
+        "month" => Ok((ymd(reference.year(), reference.month(), 1)?, reference)),
+        "quarter" => {
+            let first_month = reference.month0() / 3 * 3 + 1;
+            let from = ymd(year, first_month, 1)?;
+            let to = if first_month == 10 {
+                ymd(year, 12, 31)?
+            } else {
+                ymd(year, first_month + 3, 1)? - chrono::Duration::days(1)
+            };
+            Ok((from, to))
+        }
+        "ytd" => {
+            let to = if year == reference.year() {
+                reference
+            } else {
+                ymd(year, 12, 31)?
+            };
+            Ok((ymd(year, 1, 1)?, to))
+        }
+        other => crate::fail!(
+            validation,
+            "invalid period: {} (expected week/month/quarter/ytd)",
+            other
+        ),
+    }
+}
+
+/// The period immediately before `from..=to`: the previous calendar month when
+/// `calendar_month` is set, otherwise the preceding range of equal length.
+pub fn previous_period(
+    from: NaiveDate,
+    to: NaiveDate,
+    calendar_month: bool,
+) -> (NaiveDate, NaiveDate) {
+    if calendar_month {
+        let end = NaiveDate::from_ymd_opt(from.year(), from.month(), 1).unwrap()
+            - chrono::Duration::days(1);
+        let start = NaiveDate::from_ymd_opt(end.year(), end.month(), 1).unwrap();
+        return (start, end);
+    }
+    let len = (to - from).num_days() + 1;
+    (
+        from - chrono::Duration::days(len),
+        from - chrono::Duration::days(1),
+    )
+}
+
+/// Generate the report for `from..=to` and annotate each metric with its change
+/// versus the previous period (see [`previous_period`]).
+pub fn generate_compared(
+    db: &Database,
+    config: &Config,
+    agg: Option<Aggregation>,
+    from: NaiveDate,
+    to: NaiveDate,
+    calendar_month: bool,
+    tags: &[String],
+) -> Result<ReportResult> {
+    let (prev_from, prev_to) = previous_period(from, to, calendar_month);
+    let mut current = generate_with(db, config, agg, from, to, tags)?;
+    let previous = generate_with(db, config, agg, prev_from, prev_to, tags)?;
+
+    for m in &mut current.metrics {
+        let delta = match previous
+            .metrics
+            .iter()
+            .find(|p| p.metric_type == m.metric_type)
+        {
+            Some(p) => {
+                let (now, before) = match m.aggregation {
+                    Aggregation::Sum => (m.value, p.value),
+                    _ => (m.avg, p.avg),
+                };
+                let delta = now - before;
+                MetricDelta {
+                    prev_avg: Some(p.avg),
+                    delta: Some(delta),
+                    delta_pct: (before != 0.0).then(|| delta / before.abs() * 100.0),
+                }
+            }
+            None => MetricDelta::default(),
+        };
+        m.comparison = Some(delta);
+    }
+
+    let mut comparison: Vec<MetricComparison> = current
+        .metrics
+        .iter()
+        .map(|m| {
+            let prev = previous
+                .metrics
+                .iter()
+                .find(|p| p.metric_type == m.metric_type);
+            compare_metric(Some(m), prev)
+        })
+        .collect();
+    for p in &previous.metrics {
+        if !current
+            .metrics
+            .iter()
+            .any(|m| m.metric_type == p.metric_type)
+        {
+            comparison.push(compare_metric(None, Some(p)));
+        }
+    }
+    comparison.sort_by(|a, b| a.metric_type.cmp(&b.metric_type));
+    current.comparison = comparison;
+
+    for p in previous.metrics {
+        if current
+            .metrics
+            .iter()
+            .any(|m| m.metric_type == p.metric_type)
+        {
+            continue;
+        }
+        current.metrics.push(MetricSummary {
+            metric_type: p.metric_type,
+            count: 0,
+            avg: 0.0,
+            min: 0.0,
+            max: 0.0,
+            aggregation: p.aggregation,
+            value: 0.0,
+            unit: p.unit,
+            range_status: None,
+            comparison: Some(MetricDelta {
+                prev_avg: Some(p.avg),
+                ..MetricDelta::default()
+            }),
+        });
+    }
+    current
+        .metrics
+        .sort_by(|a, b| a.metric_type.cmp(&b.metric_type));
+
+    current.compared_to = Some(ComparedPeriod {
+        from: prev_from,
+        to: prev_to,
+    });
+    Ok(current)
+}
+
+/// Add the status of every active goal to `report`.
+pub fn attach_goals(db: &Database, report: &mut ReportResult) -> Result<()> {
+    let goals: Vec<ReportGoal> = crate::core::goal::goal_status(db, None)?
+        .into_iter()
+        .map(|g| ReportGoal {
+            metric_type: g.metric_type,
+            target_value: g.target_value,
+            direction: g.direction,
+            timeframe: g.timeframe,
+            current_value: g.current_value,
+            is_met: g.is_met,
+            progress: g.progress,
+        })
+        .collect();
+    report.goals = Some(ReportGoals {
+        goals_met: goals.iter().filter(|g| g.is_met).count(),
+        goals_total: goals.len(),
+        goals,
+    });
+    Ok(())
+}
+
+/// What `report --digest` summarizes: the report compared with the previous
+/// period and carrying goals, plus status (medications, streaks, pain alerts)
+/// as of the period's last day and the period's anomalies with their local date.
+pub struct Digest {
+    pub report: ReportResult,
+    pub status: StatusData,
+    pub anomalies: Vec<(NaiveDate, Anomaly)>,
+}
+
+/// Assemble a [`Digest`] for `from..=to` from the report, status and anomaly
+/// modules. Anomalies use the rolling method, so every reading in the period
+/// is judged against the week before it.
+pub fn digest(
+    db: &Database,
+    config: &Config,
+    from: NaiveDate,
+    to: NaiveDate,
+    calendar_month: bool,
+    tags: &[String],
+) -> Result<Digest> {
+    let mut report = generate_compared(db, config, None, from, to, calendar_month, tags)?;
+    attach_goals(db, &mut report)?;
+
+    let tz = db.timezone();
+    let today = time::local_today(tz.name());
+    let status = status::compute_on(db, config, to.min(today))?;
+    let lookback = (today - from).num_days().max(0) as u32 + DEFAULT_ROLLING_WINDOW;
+    let anomalies = anomaly::detect_with(
+        db,
+        None,
+        lookback,
+        Threshold::Moderate,
+        Method::Rolling,
+        DEFAULT_ROLLING_WINDOW,
+    )?
+    .anomalies
+    .into_iter()
+    .map(|a| (time::local_date(&a.timestamp, tz), a))
+    .filter(|(d, _)| (from..=to).contains(d))
+    .collect();
+
+    Ok(Digest {
+        report,
+        status,
+        anomalies,
     })
 }
+
+/// Compare one metric's summaries for the current and previous period;
+/// at least one of them is present.
+fn compare_metric(
+    current: Option<&MetricSummary>,
+    previous: Option<&MetricSummary>,
+) -> MetricComparison {
+    let any = current.or(previous).expect("metric present in one period");
+    let current_avg = current.map(|m| m.avg);
+    let prev_avg = previous.map(|p| p.avg);
+    let delta = current_avg.zip(prev_avg).map(|(now, before)| now - before);
+    let pct_change = delta
+        .zip(prev_avg)
+        .filter(|(_, before)| *before != 0.0)
+        .map(|(d, before)| d / before.abs() * 100.0);
+
+    let sum = any.aggregation == Aggregation::Sum;
+    let current_total = current.filter(|_| sum).map(|m| m.value);
+    let prev_total = previous.filter(|_| sum).map(|p| p.value);
+
+    MetricComparison {
+        metric_type: any.metric_type.clone(),
+        current_avg,
+        prev_avg,
+        delta,
+        pct_change,
+        current_total,
+        prev_total,
+        total_delta: current_total
+            .zip(prev_total)
+            .map(|(now, before)| now - before),
+    }
+}
+
+/// Render a report as a standalone HTML page: embedded CSS, an inline SVG
+/// sparkline of daily values per metric, current goal status, and medication
+/// adherence. No scripts or external assets.
+pub fn to_html(db: &Database, config: &Config, report: &ReportResult) -> Result<String> {
+    use crate::core::units::to_display;
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>OpenVital Report {} to {}</title>\n",
+        report.from, report.to
+    ));
+    html.push_str(HTML_STYLE);
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!(
+        "<h1>OpenVital Report</h1>\n<p class=\"period\">{} to {}</p>\n",
+        report.from, report.to
+    ));
+    html.push_str(&format!(
+        "<p>Days with entries: <b>{}</b> &middot; Total entries: <b>{}</b></p>\n",
+        report.days_with_entries, report.total_entries
+    ));
+
+    html.push_str("<h2>Metrics</h2>\n");
+    if report.metrics.is_empty() {
+        html.push_str("<p>No data in this period.</p>\n");
+    } else {
+        html.push_str("<table>\n<tr><th>Metric</th><th>Trend</th><th>Avg</th><th>Min</th><th>Max</th><th>Value</th><th>n</th></tr>\n");
+        for m in &report.metrics {
+            let series: Vec<(i64, f64)> =
+                crate::core::trend::daily_series_tagged(db, &m.metric_type, &report.tags)?
+                    .range(report.from..=report.to)
+                    .map(|(d, v)| ((*d - report.from).num_days(), *v))
+                    .collect();
+            let span = (report.to - report.from).num_days();
+            let (avg, _) = to_display(m.avg, &m.metric_type, &config.units);
+            let (min, _) = to_display(m.min, &m.metric_type, &config.units);
+            let (max, _) = to_display(m.max, &m.metric_type, &config.units);
+            let (value, unit) = to_display(m.value, &m.metric_type, &config.units);
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1} {} <span class=\"agg\">({})</span></td><td>{}</td></tr>\n",
+                escape_html(&m.metric_type),
+                sparkline(&series, span),
+                avg,
+                min,
+                max,
+                value,
+                escape_html(&unit),
+                m.aggregation,
+                m.count
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    let goals = crate::core::goal::goal_status(db, None)?;
+    if !goals.is_empty() {
+        let met = goals.iter().filter(|g| g.is_met).count();
+        html.push_str(&format!(
+            "<h2>Goals</h2>\n<p>{} of {} met</p>\n<ul>\n",
+            met,
+            goals.len()
+        ));
+        for g in &goals {
+            html.push_str(&format!(
+                "<li class=\"{}\">{} {} {} ({}){}</li>\n",
+                if g.is_met { "met" } else { "unmet" },
+                escape_html(&g.metric_type),
+                g.direction,
+                g.target_value,
+                g.timeframe,
+                g.progress
+                    .as_ref()
+                    .map(|p| format!(" &mdash; {}", escape_html(p)))
+                    .unwrap_or_default()
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let meds = crate::core::med::adherence_status(db, config, None, 7)?;
+    if !meds.is_empty() {
+        html.push_str("<h2>Medication adherence</h2>\n<table>\n<tr><th>Medication</th><th>Last 7 days</th></tr>\n");
+        for m in &meds {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&m.name),
+                m.adherence_7d
+                    .map(|v| format!("{:.0}%", v * 100.0))
+                    .unwrap_or_else(|| "as needed".to_string())
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+const HTML_STYLE: &str = "<style>
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; color: #222; max-width: 760px; margin: 2em auto; padding: 0 1em; }
+h1 { margin-bottom: 0; }
+.period { color: #666; margin-top: 0.2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { padding: 0.35em 0.6em; border-bottom: 1px solid #e4e4e4; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+.agg { color: #888; font-size: 0.85em; }
+svg.spark { vertical-align: middle; }
+li.met { color: #1a7f37; }
+li.unmet { color: #9a6700; }
+</style>
+";
+
+const SPARK_WIDTH: f64 = 120.0;
+const SPARK_HEIGHT: f64 = 24.0;
+
+/// Inline SVG sparkline of `(day offset, value)` points over `span` days.
+/// A single point renders as a dot.
+fn sparkline(points: &[(i64, f64)], span: i64) -> String {
+    if points.is_empty() {
+        return String::new();
+    }
+    let lo = points.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+    let hi = points
+        .iter()
+        .map(|(_, v)| *v)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let pad = 2.0;
+    let x = |d: i64| {
+        if span == 0 {
+            SPARK_WIDTH / 2.0
+        } else {
+            pad + (SPARK_WIDTH - 2.0 * pad) * d as f64 / span as f64
+        }
+    };
+    let y = |v: f64| {
+        if hi - lo < 1e-12 {
+            SPARK_HEIGHT / 2.0
+        } else {
+            SPARK_HEIGHT - pad - (SPARK_HEIGHT - 2.0 * pad) * (v - lo) / (hi - lo)
+        }
+    };
+    let body = if points.len() == 1 {
+        let (d, v) = points[0];
+        format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"2\" fill=\"#2f6fdf\"/>",
+            x(d),
+            y(v)
+        )
+    } else {
+        let coords: Vec<String> = points
+            .iter()
+            .map(|(d, v)| format!("{:.1},{:.1}", x(*d), y(*v)))
+            .collect();
+        format!(
+            "<polyline fill=\"none\" stroke=\"#2f6fdf\" stroke-width=\"1.5\" points=\"{}\"/>",
+            coords.join(" ")
+        )
+    };
+    format!(
+        "<svg class=\"spark\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+        SPARK_WIDTH, SPARK_HEIGHT, SPARK_WIDTH, SPARK_HEIGHT, body
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
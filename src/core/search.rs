@@ -0,0 +1,135 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::models::config::Config;
+use crate::models::metric::Metric;
+
+/// Characters of context kept on each side of a match in a snippet.
+const SNIPPET_CONTEXT: usize = 30;
+
+/// A metric entry whose note matched the query.
+#[derive(Debug, Serialize)]
+pub struct MetricHit {
+    #[serde(flatten)]
+    pub entry: Metric,
+    /// Note snippet with the matched text wrapped in `**`.
+    #[serde(rename = "match")]
+    pub matched: String,
+}
+
+/// A medication whose note or stop reason matched the query.
+#[derive(Debug, Serialize)]
+pub struct MedicationHit {
+    pub name: String,
+    pub active: bool,
+    /// `note` or `stop_reason`.
+    pub field: &'static str,
+    pub text: String,
+    #[serde(rename = "match")]
+    pub matched: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    pub query: String,
+    pub entries: Vec<MetricHit>,
+    pub medications: Vec<MedicationHit>,
+}
+
+/// Case-insensitive substring search over metric notes and medication
+/// notes / stop reasons. `metric_type` matches a metric type or medication name;
+/// `from`/`to` keep entries logged in range and medications active during it.
+/// `limit` caps each list.
+pub fn search(
+    db: &Database,
+    config: &Config,
+    text: &str,
+    metric_type: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: Option<u32>,
+) -> Result<SearchResult> {
+    let text = text.trim();
+    if text.is_empty() {
+        crate::fail!(validation, "search text must not be empty");
+    }
+    let resolved = metric_type.map(|t| config.resolve_alias(t));
+
+    let entries = db
+        .search_notes(text, resolved.as_deref(), from, to, limit)?
+        .into_iter()
+        .filter_map(|entry| {
+            let matched = snippet(entry.note.as_deref()?, text)?;
+            Some(MetricHit { entry, matched })
+        })
+        .collect();
+
+    let mut medications = Vec::new();
+    for med in db.search_medications(text)? {
+        if resolved.as_deref().is_some_and(|t| t != med.name) {
+            continue;
+        }
+        if let Some(to) = to
+            && db.local_date(&med.started_at) > to
+        {
+            continue;
+        }
+        if let (Some(from), Some(stopped)) = (from, med.stopped_at)
+            && db.local_date(&stopped) < from
+        {
+            continue;
+        }
+        for (field, value) in [("note", &med.note), ("stop_reason", &med.stop_reason)] {
+            if let Some(value) = value
+                && let Some(matched) = snippet(value, text)
+            {
+                medications.push(MedicationHit {
+                    name: med.name.clone(),
+                    active: med.active,
+                    field,
+                    text: value.clone(),
+                    matched,
+                });
+            }
+        }
+    }
+    if let Some(limit) = limit {
+        medications.truncate(limit as usize);
+    }
+
+    Ok(SearchResult {
+        query: text.to_string(),
+        entries,
+        medications,
+    })
+}
+
+/// The first occurrence of `needle` in `haystack` (ASCII case-insensitive, like
+/// SQLite's LIKE) wrapped in `**`, trimmed to a little context on each side.
+pub fn snippet(haystack: &str, needle: &str) -> Option<String> {
+    let start = haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())?;
+    let end = start + needle.len();
+
+    let before: Vec<char> = haystack[..start].chars().collect();
+    let after: Vec<char> = haystack[end..].chars().collect();
+    let lead = before.len().saturating_sub(SNIPPET_CONTEXT);
+    let tail = after.len().min(SNIPPET_CONTEXT);
+
+    let mut out = String::new();
+    if lead > 0 {
+        out.push('…');
+    }
+    out.extend(&before[lead..]);
+    out.push_str("**");
+    out.push_str(&haystack[start..end]);
+    out.push_str("**");
+    out.extend(&after[..tail]);
+    if tail < after.len() {
+        out.push('…');
+    }
+    Some(out)
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveTime, Timelike, Utc};
+
+use crate::core::time;
+use crate::db::Database;
+use crate::models::metric::Metric;
+
+/// Longest night accepted; anything longer is probably a typo.
+pub const MAX_SLEEP_MINUTES: u32 = 16 * 60;
+
+/// A night as given on the command line: clock times `HH:MM`.
+pub struct SleepInput<'a> {
+    pub bed: &'a str,
+    pub wake: &'a str,
+    pub quality: Option<f64>,
+    pub note: Option<&'a str>,
+    pub tags: Option<&'a str>,
+    pub date: Option<NaiveDate>,
+}
+
+/// The entries logged for one night and its length.
+pub struct SleepLog {
+    pub minutes: u32,
+    pub entries: Vec<Metric>,
+}
+
+/// Parse a clock time like `23:30` or `7:10`.
+pub fn parse_clock(s: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M")
+        .map_err(|_| crate::error::parse(format!("invalid time: '{}' (expected HH:MM)", s)))
+}
+
+/// Minutes from `bed` to `wake`, across midnight when `wake` is earlier.
+/// Zero or more than [`MAX_SLEEP_MINUTES`] is rejected.
+pub fn duration_minutes(bed: NaiveTime, wake: NaiveTime) -> Result<u32> {
+    let mut minutes = (wake - bed).num_minutes();
+    if minutes < 0 {
+        minutes += 24 * 60;
+    }
+    if minutes == 0 || minutes > i64::from(MAX_SLEEP_MINUTES) {
+        crate::fail!(
+            validation,
+            "sleep from {} to {} is {} h {} min; expected more than 0 and at most 16 h",
+            bed.format("%H:%M"),
+            wake.format("%H:%M"),
+            minutes / 60,
+            minutes % 60
+        );
+    }
+    Ok(minutes as u32)
+}
+
+/// A clock time as decimal hours since midnight, e.g. 23:30 → 23.5.
+pub fn clock_hours(t: NaiveTime) -> f64 {
+    f64::from(t.hour()) + f64::from(t.minute()) / 60.0
+}
+
+/// Log a night as `sleep_hours` plus `bed_time` and `wake_time` (decimal
+/// clock hours) and, with a quality, `sleep_quality`, all sharing one
+/// timestamp and inserted in one transaction.
+pub fn log_sleep(db: &Database, input: &SleepInput) -> Result<SleepLog> {
+    let bed = parse_clock(input.bed)?;
+    let wake = parse_clock(input.wake)?;
+    let minutes = duration_minutes(bed, wake)?;
+    if let Some(q) = input.quality
+        && !(1.0..=5.0).contains(&q)
+    {
+        crate::fail!(validation, "--quality must be between 1 and 5");
+    }
+
+    let timestamp = match input.date {
+        Some(d) => time::local_noon_utc(d, db.timezone()),
+        None => Utc::now(),
+    };
+    let tags: Vec<String> = input
+        .tags
+        .map(|t| {
+            t.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut values = vec![
+        ("sleep_hours", f64::from(minutes) / 60.0),
+        ("bed_time", clock_hours(bed)),
+        ("wake_time", clock_hours(wake)),
+    ];
+    if let Some(q) = input.quality {
+        values.push(("sleep_quality", q));
+    }
+    let entries: Vec<Metric> = values
+        .into_iter()
+        .map(|(metric_type, value)| {
+            let mut m = Metric::new(metric_type.to_string(), value);
+            m.timestamp = timestamp;
+            m.tags = tags.clone();
+            m.note = input.note.map(String::from);
+            m
+        })
+        .collect();
+
+    db.in_transaction(|db| {
+        for m in &entries {
+            db.insert_metric(m)?;
+        }
+        Ok(())
+    })?;
+    Ok(SleepLog { minutes, entries })
+}
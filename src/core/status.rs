@@ -1,16 +1,25 @@
 use anyhow::Result;
-use chrono::{Duration, Local, NaiveDate};
+use chrono::{Duration, NaiveDate};
 use serde::Serialize;
 use serde_json::Value;
+use std::collections::BTreeMap;
 
-use crate::db::Database;
+use crate::core::bp::{self, BpCategory};
+use crate::core::glucose::{self, GlucoseDay};
+use crate::core::time;
+use crate::db::{Database, SortOrder};
 use crate::models::config::{Alerts, Config};
+use crate::models::goal::Timeframe;
+use crate::models::metric::Metric;
+use crate::models::range::{MetricRange, RangeStatus};
 
 #[derive(Serialize)]
 pub struct MedicationStatus {
     pub active_count: usize,
     pub adherent_today: usize,
     pub non_adherent_today: usize,
+    /// Medications whose missing doses today were all skipped on purpose.
+    pub skipped_today: usize,
     pub as_needed: usize,
     pub missed: Vec<String>,
     pub overall_adherence_7d: Option<f64>,
@@ -25,6 +34,20 @@ pub struct StatusData {
     pub consecutive_pain_alerts: Vec<ConsecutivePainAlert>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub medications: Option<MedicationStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resting_hr: Option<RestingHrStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestingHrStatus {
+    pub latest: f64,
+    pub avg_30d: f64,
+    /// Latest vs the 30-day average: "up", "down" or "flat" (within 1 bpm).
+    pub trend: &'static str,
+    /// Latest vs the average of the 7 days before it, in percent.
+    pub week_change_pct: Option<f64>,
+    /// `week_change_pct` exceeds `alerts.resting_hr_change_pct`.
+    pub alert: bool,
 }
 
 #[derive(Serialize)]
@@ -33,19 +56,79 @@ pub struct ProfileStatus {
     pub latest_weight_kg: Option<f64>,
     pub bmi: Option<f64>,
     pub bmi_category: Option<&'static str>,
+    /// From the latest `weight` and `body_fat` entries.
+    pub fat_mass_kg: Option<f64>,
+    pub lean_mass_kg: Option<f64>,
+    /// ACE category of the latest `body_fat`, when `gender` is male or female.
+    pub body_fat_category: Option<&'static str>,
 }
 
 #[derive(Serialize)]
 pub struct TodayStatus {
     pub logged: Vec<String>,
     pub pain_alerts: Vec<Value>,
+    /// Latest paired reading of the day, staged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blood_pressure: Option<BpStatus>,
+    /// The day's glucose readings against the `[glucose]` target range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glucose: Option<GlucoseDay>,
+    /// Today's value of each type with a configured `[ranges]` band.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ranges: Vec<RangeCheck>,
+}
+
+/// A day's value of a metric against its healthy range.
+#[derive(Debug, Serialize)]
+pub struct RangeCheck {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    /// The day's entries combined with the type's aggregation.
+    pub value: f64,
+    pub range: MetricRange,
+    pub range_status: RangeStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BpStatus {
+    pub systolic: f64,
+    pub diastolic: f64,
+    pub category: BpCategory,
+    /// The reading is in the hypertensive crisis range.
+    pub alert: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct Streaks {
     pub logging_days: u32,
+    /// Per-type streaks for types with a daily goal and types in `[streaks] track`.
+    pub by_type: Vec<TypeStreak>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TypeStreak {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub kind: StreakKind,
+    /// Consecutive days up to today; today counts once it qualifies, and an
+    /// unfinished today does not break the streak.
+    pub current_days: u32,
+    /// Longest run within the lookback window, including the current one.
+    pub best_days: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreakKind {
+    /// Days with at least one entry.
+    Logging,
+    /// Days the daily goal was met.
+    Goal,
+}
+
+/// How far back per-type streaks look for their best run.
+const STREAK_LOOKBACK_DAYS: i64 = 365;
+
 #[derive(Debug, Serialize)]
 pub struct ConsecutivePainAlert {
     pub metric_type: String,
@@ -55,28 +138,38 @@ pub struct ConsecutivePainAlert {
 
 /// Compute the daily status overview.
 pub fn compute(db: &Database, config: &Config) -> Result<StatusData> {
-    let today = Local::now().date_naive();
-    let entries = db.query_by_date(today)?;
+    compute_on(db, config, time::local_today(db.timezone().name()))
+}
 
-    let logged: Vec<String> = entries.iter().map(|m| m.metric_type.clone()).collect();
+/// Compute the status overview as it stood on `today`; entries logged after
+/// that day are ignored. Future dates are rejected.
+pub fn compute_on(db: &Database, config: &Config, today: NaiveDate) -> Result<StatusData> {
+    let actual_today = time::local_today(db.timezone().name());
+    if today > actual_today {
+        crate::fail!(
+            validation,
+            "cannot show status for {}: date is in the future (today is {})",
+            today,
+            actual_today
+        );
+    }
+    let entries = db.query_by_date(today, SortOrder::Asc)?;
 
-    let latest_weight = db.query_by_type("weight", Some(1))?;
-    let weight_val = latest_weight.first().map(|m| m.value);
+    let logged = logged_types(&entries);
 
-    let bmi = match (config.profile.height_cm, weight_val) {
-        (Some(h), Some(w)) => {
-            let h_m = h / 100.0;
-            Some((w / (h_m * h_m) * 10.0).round() / 10.0)
-        }
-        _ => None,
-    };
+    let weight_val = db
+        .query_all(Some("weight"), None, Some(today))?
+        .last()
+        .map(|m| m.value);
 
-    let bmi_category = bmi.map(|b| match b {
-        b if b < 18.5 => "underweight",
-        b if b < 25.0 => "normal",
-        b if b < 30.0 => "overweight",
-        _ => "obese",
-    });
+    let (bmi, bmi_category) = bmi(config.profile.height_cm, weight_val);
+    let body_fat = db
+        .query_all(Some("body_fat"), None, Some(today))?
+        .last()
+        .map(|m| m.value);
+    let (fat_mass_kg, lean_mass_kg) = body_composition(weight_val, body_fat);
+    let body_fat_category =
+        body_fat.and_then(|b| body_fat_category(config.profile.gender.as_deref(), b));
 
     let threshold = config.alerts.pain_threshold as f64;
     let pain_alerts: Vec<Value> = entries
@@ -93,15 +186,17 @@ pub fn compute(db: &Database, config: &Config) -> Result<StatusData> {
         })
         .collect();
 
-    let streaks = compute_streaks(db, today)?;
+    let mut streaks = compute_streaks(db, today)?;
+    streaks.by_type = compute_type_streaks(db, config, today)?;
     let consecutive_pain_alerts = check_consecutive_pain(db, today, &config.alerts)?;
 
     // Compute medication status
-    let medications = match crate::core::med::adherence_status(db, None, 7) {
+    let medications = match crate::core::med::adherence_status_on(db, config, None, 7, today) {
         Ok(med_statuses) if !med_statuses.is_empty() => {
             let active_count = med_statuses.len();
             let mut adherent = 0;
             let mut non_adherent = 0;
+            let mut skipped = 0;
             let mut as_needed_count = 0;
             let mut missed = Vec::new();
 
@@ -118,6 +213,7 @@ pub fn compute(db: &Database, config: &Config) -> Result<StatusData> {
                             missed.push(format!("{} ({} taken this week)", s.name, taken));
                         }
                     }
+                    None if s.skipped_today > 0 => skipped += 1,
                     None => as_needed_count += 1,
                 }
             }
@@ -134,6 +230,7 @@ pub fn compute(db: &Database, config: &Config) -> Result<StatusData> {
                 active_count,
                 adherent_today: adherent,
                 non_adherent_today: non_adherent,
+                skipped_today: skipped,
                 as_needed: as_needed_count,
                 missed,
                 overall_adherence_7d: overall,
@@ -149,17 +246,184 @@ pub fn compute(db: &Database, config: &Config) -> Result<StatusData> {
             latest_weight_kg: weight_val,
             bmi,
             bmi_category,
+            fat_mass_kg,
+            lean_mass_kg,
+            body_fat_category,
         },
         today: TodayStatus {
             logged,
             pain_alerts,
+            blood_pressure: bp_status(&entries, &config.alerts),
+            glucose: glucose::time_in_range(&entries, &config.glucose, db.timezone())
+                .and_then(|tir| tir.days.into_iter().next()),
+            ranges: range_checks(&entries, config),
         },
         streaks,
         consecutive_pain_alerts,
         medications,
+        resting_hr: resting_hr_status(db, today, &config.alerts)?,
     })
 }
 
+/// Check each type with a configured range that has entries among `entries`
+/// (one day's worth), combining them with the type's aggregation.
+pub fn range_checks(entries: &[Metric], config: &Config) -> Vec<RangeCheck> {
+    let mut by_type: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    for e in entries {
+        if config.ranges.contains_key(&e.metric_type) {
+            by_type.entry(&e.metric_type).or_default().push(e.value);
+        }
+    }
+    by_type
+        .into_iter()
+        .map(|(metric_type, values)| {
+            let range = config.ranges[metric_type];
+            let value = config.aggregation_for(metric_type).apply(&values);
+            RangeCheck {
+                metric_type: metric_type.to_string(),
+                value,
+                range,
+                range_status: range.classify(value),
+            }
+        })
+        .collect()
+}
+
+/// Stage the day's latest systolic/diastolic pair; `None` unless both sides were logged together.
+pub fn bp_status(entries: &[Metric], alerts: &Alerts) -> Option<BpStatus> {
+    let side = |t: &str| -> Vec<Metric> {
+        entries
+            .iter()
+            .filter(|m| m.metric_type == t)
+            .cloned()
+            .collect()
+    };
+    let readings = bp::pair(&side(bp::SYSTOLIC), &side(bp::DIASTOLIC));
+    let latest = readings.iter().find(|r| r.is_paired())?;
+    let (systolic, diastolic) = (latest.systolic?, latest.diastolic?);
+    let category = bp::classify(systolic, diastolic, &alerts.blood_pressure);
+    Some(BpStatus {
+        systolic,
+        diastolic,
+        category,
+        alert: category == BpCategory::Crisis,
+    })
+}
+
+/// Latest resting heart rate on or before `today` against its 30-day average,
+/// and whether it jumped more than `alerts.resting_hr_change_pct` above the
+/// week before it (an early illness or overtraining signal).
+pub fn resting_hr_status(
+    db: &Database,
+    today: NaiveDate,
+    alerts: &Alerts,
+) -> Result<Option<RestingHrStatus>> {
+    let tz = db.timezone();
+    let entries = db.query_all(
+        Some("resting_heart_rate"),
+        Some(today - Duration::days(29)),
+        Some(today),
+    )?;
+    let Some(latest) = entries.last() else {
+        return Ok(None);
+    };
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+
+    let all: Vec<f64> = entries.iter().map(|m| m.value).collect();
+    let avg_30d = mean(&all);
+    let trend = if latest.value - avg_30d >= 1.0 {
+        "up"
+    } else if avg_30d - latest.value >= 1.0 {
+        "down"
+    } else {
+        "flat"
+    };
+
+    let latest_day = time::local_date(&latest.timestamp, tz);
+    let week_before: Vec<f64> = entries
+        .iter()
+        .filter(|m| {
+            let d = time::local_date(&m.timestamp, tz);
+            d < latest_day && d >= latest_day - Duration::days(7)
+        })
+        .map(|m| m.value)
+        .collect();
+    let week_change_pct = if week_before.is_empty() {
+        None
+    } else {
+        let base = mean(&week_before);
+        Some(((latest.value - base) / base * 1000.0).round() / 10.0)
+    };
+
+    Ok(Some(RestingHrStatus {
+        latest: latest.value,
+        avg_30d: (avg_30d * 10.0).round() / 10.0,
+        trend,
+        week_change_pct,
+        alert: week_change_pct.is_some_and(|p| p > alerts.resting_hr_change_pct),
+    }))
+}
+
+/// Metric types with at least one entry on `day`, in entry order.
+pub fn logged_on(db: &Database, day: NaiveDate) -> Result<Vec<String>> {
+    Ok(logged_types(&db.query_by_date(day, SortOrder::Asc)?))
+}
+
+fn logged_types(entries: &[Metric]) -> Vec<String> {
+    entries.iter().map(|m| m.metric_type.clone()).collect()
+}
+
+/// BMI rounded to one decimal, with its WHO category, when height and weight are known.
+pub fn bmi(height_cm: Option<f64>, weight_kg: Option<f64>) -> (Option<f64>, Option<&'static str>) {
+    let bmi = match (height_cm, weight_kg) {
+        (Some(h), Some(w)) => {
+            let h_m = h / 100.0;
+            Some((w / (h_m * h_m) * 10.0).round() / 10.0)
+        }
+        _ => None,
+    };
+    let category = bmi.map(|b| match b {
+        b if b < 18.5 => "underweight",
+        b if b < 25.0 => "normal",
+        b if b < 30.0 => "overweight",
+        _ => "obese",
+    });
+    (bmi, category)
+}
+
+/// Fat and lean mass in kg, rounded to one decimal, when weight and body fat
+/// percentage are known.
+pub fn body_composition(
+    weight_kg: Option<f64>,
+    body_fat_pct: Option<f64>,
+) -> (Option<f64>, Option<f64>) {
+    let Some((w, pct)) = weight_kg.zip(body_fat_pct) else {
+        return (None, None);
+    };
+    let round = |v: f64| (v * 10.0).round() / 10.0;
+    let fat = w * pct / 100.0;
+    (Some(round(fat)), Some(round(w - fat)))
+}
+
+/// ACE body fat category for `gender` (male/female, or m/f). Lower bounds are
+/// inclusive: men 6% athlete, 14% fitness, 18% acceptable, 25% obese; women
+/// 14%, 21%, 25%, 32%. Other or unset genders have no category.
+pub fn body_fat_category(gender: Option<&str>, body_fat_pct: f64) -> Option<&'static str> {
+    let bounds: [f64; 4] = match gender?.trim().to_lowercase().as_str() {
+        "male" | "m" => [6.0, 14.0, 18.0, 25.0],
+        "female" | "f" => [14.0, 21.0, 25.0, 32.0],
+        _ => return None,
+    };
+    let category = match body_fat_pct {
+        p if p < bounds[0] => "essential",
+        p if p < bounds[1] => "athlete",
+        p if p < bounds[2] => "fitness",
+        p if p < bounds[3] => "acceptable",
+        _ => "obese",
+    };
+    Some(category)
+}
+
 /// Compute streak of consecutive days with any logged entry, ending at `today`.
 pub fn compute_streaks(db: &Database, today: NaiveDate) -> Result<Streaks> {
     // Look back up to 365 days for streak calculation
@@ -181,9 +445,81 @@ pub fn compute_streaks(db: &Database, today: NaiveDate) -> Result<Streaks> {
 
     Ok(Streaks {
         logging_days: streak,
+        by_type: Vec::new(),
     })
 }
 
+/// Goal streaks for every active daily goal, then logging streaks for each
+/// type in `config.streaks.track`.
+pub fn compute_type_streaks(
+    db: &Database,
+    config: &Config,
+    today: NaiveDate,
+) -> Result<Vec<TypeStreak>> {
+    let from = today - Duration::days(STREAK_LOOKBACK_DAYS - 1);
+    let mut result = Vec::new();
+
+    for goal in db.list_goals(true)? {
+        if goal.timeframe != Timeframe::Daily {
+            continue;
+        }
+        let days = crate::core::goal::daily_values(db, &goal, from, today)?;
+        let met: Vec<NaiveDate> = days
+            .into_iter()
+            .filter(|(_, v)| goal.is_met(*v))
+            .map(|(d, _)| d)
+            .collect();
+        let (current_days, best_days) = runs(&met, today);
+        result.push(TypeStreak {
+            metric_type: goal.metric_type.clone(),
+            kind: StreakKind::Goal,
+            current_days,
+            best_days,
+        });
+    }
+
+    for t in &config.streaks.track {
+        let metric_type = config.resolve_alias(t);
+        let mut dates: Vec<NaiveDate> = db
+            .query_all(Some(&metric_type), Some(from), Some(today))?
+            .iter()
+            .map(|m| time::local_date(&m.timestamp, db.timezone()))
+            .collect();
+        dates.sort();
+        dates.dedup();
+        let (current_days, best_days) = runs(&dates, today);
+        result.push(TypeStreak {
+            metric_type,
+            kind: StreakKind::Logging,
+            current_days,
+            best_days,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Current and best run of consecutive days in sorted, distinct `dates`.
+/// The current run may end today or, if today has no entry yet, yesterday.
+fn runs(dates: &[NaiveDate], today: NaiveDate) -> (u32, u32) {
+    let mut best = 0u32;
+    let mut run = 0u32;
+    let mut prev: Option<NaiveDate> = None;
+    for &d in dates {
+        run = match prev {
+            Some(p) if d - p == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        best = best.max(run);
+        prev = Some(d);
+    }
+    let current = match prev {
+        Some(last) if last == today || last == today - Duration::days(1) => run,
+        _ => 0,
+    };
+    (current, best)
+}
+
 /// Check if pain/soreness has been above threshold for N consecutive days.
 pub fn check_consecutive_pain(
     db: &Database,
@@ -198,16 +534,13 @@ pub fn check_consecutive_pain(
         let mut consecutive = 0u32;
         let mut latest_value = 0.0f64;
 
-        // Widen query range by 1 day on each side to capture entries where
-        // the UTC date differs from the local date (timezone offset).
         let from = today - Duration::days(30);
-        let to = today + Duration::days(1);
-        let entries = db.query_all(Some(pain_type), Some(from), Some(to))?;
+        let entries = db.query_all(Some(pain_type), Some(from), Some(today))?;
 
         let mut has_pain = [None; 30];
         for m in entries {
             if m.value >= threshold {
-                let local_date = m.timestamp.with_timezone(&Local).date_naive();
+                let local_date = time::local_date(&m.timestamp, db.timezone());
                 let diff = (today - local_date).num_days();
                 if (0..30).contains(&diff) {
                     let idx = diff as usize;
@@ -0,0 +1,296 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::core::backup;
+use crate::db::Database;
+use crate::models::config::SyncConfig;
+
+/// Name of the database copy in the remote directory.
+pub const REMOTE_FILE: &str = "data.db";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Put,
+    Get,
+    Head,
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Put => write!(f, "PUT"),
+            Self::Get => write!(f, "GET"),
+            Self::Head => write!(f, "HEAD"),
+        }
+    }
+}
+
+/// BASIC auth credentials; the password comes from `sync.password_env`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+/// One WebDAV request. A PUT sends the file at `body`; a GET writes the
+/// response to `dest`.
+#[derive(Debug)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub credentials: Credentials,
+    pub body: Option<PathBuf>,
+    pub dest: Option<PathBuf>,
+}
+
+/// Sends [`Request`]s. The `reqwest` blocking client is the real one; tests
+/// substitute their own.
+pub trait HttpClient {
+    /// The HTTP status code of the response.
+    fn send(&self, req: &Request) -> Result<u16>;
+}
+
+impl HttpClient for reqwest::blocking::Client {
+    fn send(&self, req: &Request) -> Result<u16> {
+        let builder = match req.method {
+            Method::Put => self.put(&req.url),
+            Method::Get => self.get(&req.url),
+            Method::Head => self.head(&req.url),
+        }
+        .basic_auth(&req.credentials.username, Some(&req.credentials.password));
+        let builder = match &req.body {
+            Some(body) => builder
+                .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                .body(std::fs::File::open(body)?),
+            None => builder,
+        };
+        let failed =
+            |e: reqwest::Error| http_error(format!("{} {} failed: {}", req.method, req.url, e));
+        let mut response = builder.send().map_err(failed)?;
+        if let Some(dest) = &req.dest
+            && response.status().is_success()
+        {
+            response
+                .copy_to(&mut std::fs::File::create(dest)?)
+                .map_err(failed)?;
+        }
+        Ok(response.status().as_u16())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncResult {
+    pub direction: &'static str,
+    pub synced_at: DateTime<Utc>,
+    pub remote_url: String,
+    pub bytes_transferred: u64,
+    /// Where the replaced local database was kept (download only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncStatus {
+    pub remote_url: String,
+    pub http_status: u16,
+    /// The server answered and accepted the credentials.
+    pub reachable: bool,
+    /// A database copy is on the server.
+    pub remote_exists: bool,
+}
+
+/// Copy the live database (online backup API) next to `db_path` and upload it.
+pub fn push(
+    client: &impl HttpClient,
+    db: &Database,
+    db_path: &Path,
+    config: &SyncConfig,
+) -> Result<SyncResult> {
+    let url = remote_url(config)?;
+    credentials(config)?;
+    let staged = backup::sibling(db_path, "sync");
+    let _ = std::fs::remove_file(&staged);
+    let result = db.backup_to(&staged).and_then(|_| {
+        upload_with(client, &staged, config)?;
+        Ok(std::fs::metadata(&staged)?.len())
+    });
+    let _ = std::fs::remove_file(&staged);
+    Ok(SyncResult {
+        direction: "upload",
+        synced_at: Utc::now(),
+        remote_url: url,
+        bytes_transferred: result?,
+        previous: None,
+    })
+}
+
+/// Download the remote copy and restore it over the database at `db_path`,
+/// keeping the replaced one as with `restore`.
+pub fn pull(
+    client: &impl HttpClient,
+    db_path: &Path,
+    key: Option<&str>,
+    config: &SyncConfig,
+) -> Result<SyncResult> {
+    let staged = backup::sibling(db_path, "sync");
+    let _ = std::fs::remove_file(&staged);
+    let result = download_with(client, &staged, config).and_then(|_| {
+        let bytes = std::fs::metadata(&staged)?.len();
+        let restored = backup::restore(&staged, db_path, key)?;
+        Ok((bytes, restored.previous))
+    });
+    let _ = std::fs::remove_file(&staged);
+    let (bytes_transferred, previous) = result?;
+    Ok(SyncResult {
+        direction: "download",
+        synced_at: Utc::now(),
+        remote_url: remote_url(config)?,
+        bytes_transferred,
+        previous,
+    })
+}
+
+/// Check the server with a HEAD request for the remote copy.
+pub fn check(client: &impl HttpClient, config: &SyncConfig) -> Result<SyncStatus> {
+    let http_status = status_with(client, config)?;
+    Ok(SyncStatus {
+        remote_url: remote_url(config)?,
+        http_status,
+        reachable: (200..300).contains(&http_status) || http_status == 404,
+        remote_exists: (200..300).contains(&http_status),
+    })
+}
+
+/// URL of the database copy: `<sync.url>/data.db`.
+pub fn remote_url(config: &SyncConfig) -> Result<String> {
+    let base = config.url.trim().trim_end_matches('/');
+    if base.is_empty() {
+        crate::fail!(
+            validation,
+            "sync.url is not set; set it with `openvital config set sync.url <url>`"
+        );
+    }
+    if !base.starts_with("https://") && !base.starts_with("http://") {
+        crate::fail!(validation, "sync.url must be an http(s) URL: {}", base);
+    }
+    Ok(format!("{}/{}", base, REMOTE_FILE))
+}
+
+/// Check that sync is enabled and can authenticate, returning the BASIC auth
+/// credentials with the password read from `sync.password_env`.
+fn credentials(config: &SyncConfig) -> Result<Credentials> {
+    if !config.enabled {
+        crate::fail!(
+            validation,
+            "sync is disabled; enable it with `openvital config set sync.enabled true`"
+        );
+    }
+    if config.provider != "webdav" {
+        crate::fail!(
+            validation,
+            "unsupported sync provider '{}' (expected webdav)",
+            config.provider
+        );
+    }
+    if config.username.is_empty() {
+        crate::fail!(
+            validation,
+            "sync.username is not set; set it with `openvital config set sync.username <name>`"
+        );
+    }
+    let password = std::env::var(&config.password_env).map_err(|_| {
+        crate::error::validation(format!(
+            "sync password not found: set ${} (sync.password_env)",
+            config.password_env
+        ))
+    })?;
+    Ok(Credentials {
+        username: config.username.clone(),
+        password,
+    })
+}
+
+/// Upload the database copy at `local_path` to the configured WebDAV server.
+pub fn webdav_upload(local_path: &Path, config: &SyncConfig) -> Result<()> {
+    upload_with(&reqwest::blocking::Client::new(), local_path, config)
+}
+
+pub fn upload_with(client: &impl HttpClient, local_path: &Path, config: &SyncConfig) -> Result<()> {
+    let url = remote_url(config)?;
+    let status = client.send(&Request {
+        method: Method::Put,
+        url: url.clone(),
+        credentials: credentials(config)?,
+        body: Some(local_path.to_path_buf()),
+        dest: None,
+    })?;
+    check_status(Method::Put, &url, status)
+}
+
+/// Download the remote database copy to `dest`.
+pub fn webdav_download(dest: &Path, config: &SyncConfig) -> Result<()> {
+    download_with(&reqwest::blocking::Client::new(), dest, config)
+}
+
+pub fn download_with(client: &impl HttpClient, dest: &Path, config: &SyncConfig) -> Result<()> {
+    let url = remote_url(config)?;
+    let status = client.send(&Request {
+        method: Method::Get,
+        url: url.clone(),
+        credentials: credentials(config)?,
+        body: None,
+        dest: Some(dest.to_path_buf()),
+    })?;
+    if status == 404 {
+        let _ = std::fs::remove_file(dest);
+        crate::fail!(
+            not_found,
+            "no database at {}; run `openvital sync` first",
+            url
+        );
+    }
+    check_status(Method::Get, &url, status)
+}
+
+/// HTTP status of a HEAD request for the remote database copy.
+pub fn webdav_status(config: &SyncConfig) -> Result<u16> {
+    status_with(&reqwest::blocking::Client::new(), config)
+}
+
+pub fn status_with(client: &impl HttpClient, config: &SyncConfig) -> Result<u16> {
+    client.send(&Request {
+        method: Method::Head,
+        url: remote_url(config)?,
+        credentials: credentials(config)?,
+        body: None,
+        dest: None,
+    })
+}
+
+fn check_status(method: Method, url: &str, status: u16) -> Result<()> {
+    match status {
+        200..=299 => Ok(()),
+        401 | 403 => crate::fail!(
+            validation,
+            "{} {}: authentication failed (HTTP {})",
+            method,
+            url,
+            status
+        ),
+        _ => Err(http_error(format!("{} {}: HTTP {}", method, url, status))),
+    }
+}
+
+fn http_error(message: String) -> anyhow::Error {
+    crate::error::io(&std::io::Error::other(message.clone()), message)
+}
@@ -0,0 +1,216 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Months, NaiveDate, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// Parse an IANA timezone name (e.g. "America/New_York").
+pub fn parse_timezone(name: &str) -> Result<Tz> {
+    name.parse::<Tz>().map_err(|_| {
+        crate::error::validation(format!(
+            "invalid timezone: '{}' (expected an IANA name like UTC or America/New_York)",
+            name
+        ))
+    })
+}
+
+/// Today's date in the given timezone. Falls back to UTC for unknown names.
+pub fn local_today(tz: &str) -> NaiveDate {
+    let tz = parse_timezone(tz).unwrap_or(Tz::UTC);
+    Utc::now().with_timezone(&tz).date_naive()
+}
+
+/// The local calendar date of a UTC timestamp in the given timezone.
+pub fn local_date(ts: &DateTime<Utc>, tz: Tz) -> NaiveDate {
+    ts.with_timezone(&tz).date_naive()
+}
+
+/// The UTC instant at which a local calendar day begins.
+pub fn day_start_utc(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    // Midnight can be skipped by a DST transition; fall back to the first valid hour.
+    (0..24)
+        .find_map(|h| {
+            tz.from_local_datetime(&(midnight + chrono::Duration::hours(h)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+/// Local noon on the given date, as UTC. Used for entries backdated with `--date`.
+pub fn local_noon_utc(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let noon = date.and_hms_opt(12, 0, 0).unwrap();
+    tz.from_local_datetime(&noon)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&noon))
+}
+
+/// The first day of the week containing `date`, for weeks starting on `first`.
+pub fn week_start_for(date: NaiveDate, first: Weekday) -> NaiveDate {
+    let back = (date.weekday().num_days_from_monday() + 7 - first.num_days_from_monday()) % 7;
+    date - chrono::Duration::days(i64::from(back))
+}
+
+/// Parse a date flag: `YYYY-MM-DD`, or a phrase relative to `today`:
+/// `today`, `yesterday`, `<N> days ago` (also weeks and months), `last <weekday>`
+/// (the most recent one before today), `last week` (its Monday) and
+/// `last month` (its first day).
+pub fn parse_date(s: &str, today: NaiveDate) -> Result<NaiveDate> {
+    parse_date_in_week(s, today, Weekday::Mon)
+}
+
+/// [`parse_date`] with `last week` starting on `week_start`.
+pub fn parse_date_in_week(s: &str, today: NaiveDate, week_start: Weekday) -> Result<NaiveDate> {
+    let phrase = s.trim().to_lowercase();
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let relative = match words.as_slice() {
+        ["today"] => Some(today),
+        ["yesterday"] => today.pred_opt(),
+        ["last", "week"] => Some(week_start_for(today, week_start) - chrono::Duration::days(7)),
+        ["last", "month"] => today
+            .with_day(1)
+            .and_then(|d| d.checked_sub_months(Months::new(1))),
+        ["last", day] => match day.parse::<Weekday>() {
+            Ok(weekday) => {
+                let back = (today.weekday().num_days_from_monday() + 7
+                    - weekday.num_days_from_monday())
+                    % 7;
+                Some(today - chrono::Duration::days(if back == 0 { 7 } else { back as i64 }))
+            }
+            Err(_) => None,
+        },
+        [n, unit, "ago"] => match n.parse::<u32>() {
+            Ok(n) => match *unit {
+                "day" | "days" => today.checked_sub_days(chrono::Days::new(n as u64)),
+                "week" | "weeks" => today.checked_sub_days(chrono::Days::new(n as u64 * 7)),
+                "month" | "months" => today.checked_sub_months(Months::new(n)),
+                _ => None,
+            },
+            Err(_) => None,
+        },
+        _ => None,
+    };
+    if let Some(date) = relative {
+        return Ok(date);
+    }
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").map_err(|_| {
+        crate::error::validation(format!(
+            "invalid date: '{}' (expected YYYY-MM-DD, today, yesterday, N days ago, \
+             last <weekday>, last week or last month)",
+            s
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timezone_valid_and_invalid() {
+        assert_eq!(parse_timezone("UTC").unwrap(), Tz::UTC);
+        assert!(parse_timezone("America/New_York").is_ok());
+        assert!(parse_timezone("Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_local_date_shifts_to_previous_day_west_of_utc() {
+        let tz = parse_timezone("America/New_York").unwrap();
+        let ts = Utc.with_ymd_and_hms(2026, 1, 15, 1, 0, 0).unwrap();
+        assert_eq!(
+            local_date(&ts, tz),
+            NaiveDate::from_ymd_opt(2026, 1, 14).unwrap()
+        );
+        assert_eq!(
+            local_date(&ts, Tz::UTC),
+            NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_day_start_utc_applies_offset() {
+        let tz = parse_timezone("America/New_York").unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        assert_eq!(
+            day_start_utc(date, tz),
+            Utc.with_ymd_and_hms(2026, 1, 15, 5, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_local_noon_utc() {
+        let tz = parse_timezone("Asia/Tokyo").unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        assert_eq!(
+            local_noon_utc(date, tz),
+            Utc.with_ymd_and_hms(2026, 1, 15, 3, 0, 0).unwrap()
+        );
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date_phrases() {
+        // A Wednesday
+        let today = day(2026, 3, 4);
+        assert_eq!(parse_date("today", today).unwrap(), today);
+        assert_eq!(parse_date(" Yesterday ", today).unwrap(), day(2026, 3, 3));
+        assert_eq!(parse_date("3 days ago", today).unwrap(), day(2026, 3, 1));
+        assert_eq!(parse_date("1 day ago", today).unwrap(), day(2026, 3, 3));
+        assert_eq!(parse_date("2 weeks ago", today).unwrap(), day(2026, 2, 18));
+        assert_eq!(parse_date("1 month ago", today).unwrap(), day(2026, 2, 4));
+        assert_eq!(parse_date("last week", today).unwrap(), day(2026, 2, 23));
+        assert_eq!(parse_date("last month", today).unwrap(), day(2026, 2, 1));
+        assert_eq!(
+            parse_date("last month", day(2026, 1, 31)).unwrap(),
+            day(2025, 12, 1)
+        );
+        assert_eq!(parse_date("last fri", today).unwrap(), day(2026, 2, 27));
+        assert_eq!(parse_date("2026-01-15", today).unwrap(), day(2026, 1, 15));
+    }
+
+    #[test]
+    fn test_parse_date_last_monday_from_every_weekday() {
+        // 2026-03-02 is a Monday; last monday from Mon..Sun of that week
+        let expected = [
+            day(2026, 2, 23),
+            day(2026, 3, 2),
+            day(2026, 3, 2),
+            day(2026, 3, 2),
+            day(2026, 3, 2),
+            day(2026, 3, 2),
+            day(2026, 3, 2),
+        ];
+        for (offset, want) in expected.iter().enumerate() {
+            let today = day(2026, 3, 2) + chrono::Duration::days(offset as i64);
+            assert_eq!(
+                parse_date("last monday", today).unwrap(),
+                *want,
+                "{}",
+                today
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_date_rejects_unknown_phrases() {
+        let today = day(2026, 3, 4);
+        for bad in [
+            "tomorrow",
+            "last year",
+            "x days ago",
+            "last funday",
+            "2026-13-01",
+        ] {
+            let err = parse_date(bad, today).unwrap_err();
+            assert_eq!(
+                crate::error::kind_of(&err),
+                crate::error::ErrorKind::Validation,
+                "{}",
+                bad
+            );
+        }
+    }
+}
@@ -1,21 +1,63 @@
 use anyhow::Result;
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, NaiveDate, Weekday};
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::str::FromStr;
 
+use crate::core::cycle;
+use crate::core::projection::{self, Projection};
+use crate::core::time;
 use crate::db::Database;
-use crate::models::metric::Category;
+use crate::models::config::{CycleConfig, TrendConfig};
+use crate::models::goal::Direction;
+use crate::models::metric::{Aggregation, Category, JOURNAL_TYPE, Metric, default_aggregation};
 
 #[derive(Debug, Serialize)]
 pub struct CorrelationResult {
     pub metric_a: String,
     pub metric_b: String,
+    /// Days metric B is shifted: a value of A on day D is paired with B on day D + lag.
+    pub lag: i64,
     pub coefficient: f64,
+    /// Number of matched pairs after shifting.
     pub data_points: usize,
     pub interpretation: String,
+    /// Present when gaps were filled; `synthetic_points` counts pairs with
+    /// at least one filled side.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill: Option<FillReport>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct LagScanResult {
+    /// The lag with the strongest absolute coefficient (ties go to the smallest |lag|).
+    pub best: CorrelationResult,
+    pub lags: Vec<LagCoefficient>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LagCoefficient {
+    pub lag: i64,
+    pub coefficient: f64,
+    pub data_points: usize,
+}
+
+/// Pairwise correlations between every metric type with data in the window.
+#[derive(Debug, Serialize)]
+pub struct CorrelationMatrix {
+    pub types: Vec<String>,
+    /// `coefficients[i][j]` correlates `types[i]` with `types[j]`; `None` when
+    /// the pair has too few date-matched observations.
+    #[serde(rename = "matrix")]
+    pub coefficients: Vec<Vec<Option<f64>>>,
+}
+
+/// Minimum date-matched observations for a pair in [`correlation_matrix`].
+pub const MATRIX_MIN_POINTS: usize = 5;
+
+/// Range of lags (in days) tried by [`scan_lag`].
+pub const LAG_SCAN_RANGE: std::ops::RangeInclusive<i64> = -7..=7;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TrendPeriod {
     Daily,
@@ -30,7 +72,11 @@ impl FromStr for TrendPeriod {
             "daily" => Ok(Self::Daily),
             "weekly" => Ok(Self::Weekly),
             "monthly" => Ok(Self::Monthly),
-            _ => anyhow::bail!("invalid period: {} (expected daily/weekly/monthly)", s),
+            _ => crate::fail!(
+                validation,
+                "invalid period: {} (expected daily/weekly/monthly)",
+                s
+            ),
         }
     }
 }
@@ -40,8 +86,16 @@ pub struct TrendResult {
     #[serde(rename = "type")]
     pub metric_type: String,
     pub period: String,
+    /// How entries within each bucket were combined into `avg`.
+    pub aggregation: Aggregation,
     pub data: Vec<PeriodData>,
     pub trend: TrendSummary,
+    /// Estimated date of reaching the active goal, if one is set for this type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projection: Option<Projection>,
+    /// Present when gaps were filled (`--fill forward|linear`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill: Option<FillReport>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -50,7 +104,108 @@ pub struct PeriodData {
     pub avg: f64,
     pub min: f64,
     pub max: f64,
+    /// Real readings in the bucket.
     pub count: u32,
+    /// Synthetic daily values added by gap filling; they count towards
+    /// `avg`/`min`/`max` but not `count`.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub filled: u32,
+    /// Average of this bucket's `avg` and up to N-1 preceding buckets (with `--smooth N`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moving_avg: Option<f64>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+/// How days without readings are filled for `trend` and `correlate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FillMethod {
+    /// Leave gaps empty.
+    #[default]
+    None,
+    /// Carry the last daily value forward.
+    Forward,
+    /// Interpolate linearly between the surrounding daily values.
+    Linear,
+}
+
+impl FromStr for FillMethod {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "none" => Ok(Self::None),
+            "forward" => Ok(Self::Forward),
+            "linear" => Ok(Self::Linear),
+            _ => crate::fail!(
+                validation,
+                "invalid fill method: {} (expected forward/linear/none)",
+                s
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for FillMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Forward => write!(f, "forward"),
+            Self::Linear => write!(f, "linear"),
+        }
+    }
+}
+
+/// Gap filling settings. Only gaps of at most `max_gap_days` missing days
+/// between two readings are filled; longer gaps (and the days after the last
+/// reading) stay empty. Filled values exist only inside the computation and
+/// are never stored.
+#[derive(Debug, Clone, Copy)]
+pub struct GapFill {
+    pub method: FillMethod,
+    pub max_gap_days: u32,
+}
+
+impl Default for GapFill {
+    fn default() -> Self {
+        Self {
+            method: FillMethod::None,
+            max_gap_days: TrendConfig::default().fill_max_gap_days,
+        }
+    }
+}
+
+/// The fill used for a result and how many synthetic values went into it.
+#[derive(Debug, Clone, Serialize)]
+pub struct FillReport {
+    pub method: FillMethod,
+    pub max_gap_days: u32,
+    pub synthetic_points: usize,
+}
+
+/// Synthetic values for the missing days of a daily series.
+pub fn fill_gaps(series: &BTreeMap<NaiveDate, f64>, fill: GapFill) -> BTreeMap<NaiveDate, f64> {
+    let mut synthetic = BTreeMap::new();
+    if fill.method == FillMethod::None {
+        return synthetic;
+    }
+    for ((d0, v0), (d1, v1)) in series.iter().zip(series.iter().skip(1)) {
+        let span = (*d1 - *d0).num_days();
+        let missing = span - 1;
+        if missing < 1 || missing > i64::from(fill.max_gap_days) {
+            continue;
+        }
+        for i in 1..span {
+            let value = match fill.method {
+                FillMethod::Linear => v0 + (v1 - v0) * i as f64 / span as f64,
+                _ => *v0,
+            };
+            synthetic.insert(*d0 + chrono::Duration::days(i), value);
+        }
+    }
+    synthetic
 }
 
 #[derive(Debug, Serialize)]
@@ -59,6 +214,25 @@ pub struct TrendSummary {
     pub rate: f64,
     pub rate_unit: String,
     pub projected_30d: Option<f64>,
+    /// Standard error of the slope (per period); `None` with fewer than 3 periods.
+    pub std_error: Option<f64>,
+    /// 95% confidence interval for the slope (t-distribution critical value).
+    pub confidence_95_lower: Option<f64>,
+    pub confidence_95_upper: Option<f64>,
+}
+
+/// Optional settings for [`compute_with`].
+#[derive(Debug, Clone, Default)]
+pub struct TrendOptions {
+    /// Number of most recent periods to return (default 12).
+    pub last: Option<u32>,
+    /// Moving-average window in periods. When active (N > 1) the trend
+    /// direction and rate are derived from the smoothed series.
+    pub smooth: Option<u32>,
+    /// How to combine entries within a bucket; the metric's default when `None`.
+    pub aggregation: Option<Aggregation>,
+    /// Fill missing days before bucketing.
+    pub fill: GapFill,
 }
 
 /// Compute trend data for a metric type.
@@ -68,63 +242,99 @@ pub fn compute(
     period: TrendPeriod,
     last: Option<u32>,
 ) -> Result<TrendResult> {
-    // Fetch all entries in ascending order for bucketing
-    let all_entries = db.query_by_type_asc(metric_type, None)?;
-
-    // Separate medication from non-medication entries to handle name collisions.
-    // If non-medication entries exist, use those (the metric predates the medication).
-    // If only medication entries exist, use those with sum aggregation.
-    let has_non_med = all_entries
-        .iter()
-        .any(|e| e.category != Category::Medication);
-    let entries: Vec<_> = if has_non_med {
-        all_entries
-            .into_iter()
-            .filter(|e| e.category != Category::Medication)
-            .collect()
-    } else {
-        all_entries
-    };
-    let is_medication = !has_non_med
-        && entries
-            .first()
-            .is_some_and(|e| e.category == Category::Medication);
+    compute_with(
+        db,
+        metric_type,
+        period,
+        TrendOptions {
+            last,
+            ..Default::default()
+        },
+    )
+}
 
+/// Compute trend data with smoothing and aggregation options.
+pub fn compute_with(
+    db: &Database,
+    metric_type: &str,
+    period: TrendPeriod,
+    opts: TrendOptions,
+) -> Result<TrendResult> {
+    let TrendOptions {
+        last,
+        smooth,
+        aggregation,
+        fill,
+    } = opts;
+    if metric_type == JOURNAL_TYPE {
+        crate::fail!(validation, "journal notes have no values to trend");
+    }
+    let (entries, aggregation) = trend_entries(db, metric_type, aggregation)?;
     let limit = last.unwrap_or(12) as usize;
 
+    let projection = match db.get_goal_by_type(metric_type)? {
+        Some(goal) => Some(projection::project(db, &goal)?),
+        None => None,
+    };
+
     if entries.is_empty() {
+        if let Some(closest) = crate::core::types::suggest(db, metric_type)? {
+            crate::fail!(
+                not_found,
+                "unknown metric type '{}'. Did you mean: {}?",
+                metric_type,
+                closest
+            );
+        }
         return Ok(TrendResult {
             metric_type: metric_type.to_string(),
             period: period_label(&period),
+            aggregation,
             data: Vec::new(),
             trend: TrendSummary {
                 direction: "stable".to_string(),
                 rate: 0.0,
                 rate_unit: format!("per {}", period_noun(&period)),
                 projected_30d: None,
+                std_error: None,
+                confidence_95_lower: None,
+                confidence_95_upper: None,
             },
+            projection,
+            fill: None,
         });
     }
 
-    // Group entries by period bucket
-    let mut buckets: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    // Group entries by period bucket: (values, real readings)
+    let mut buckets: BTreeMap<String, (Vec<f64>, u32)> = BTreeMap::new();
+    let mut days: BTreeMap<NaiveDate, Vec<f64>> = BTreeMap::new();
     for entry in &entries {
-        let date = entry.timestamp.date_naive();
-        let key = period_key(date, &period);
-        buckets.entry(key).or_default().push(entry.value);
+        let date = time::local_date(&entry.timestamp, db.timezone());
+        let bucket = buckets
+            .entry(period_key(date, &period, db.week_start()))
+            .or_default();
+        bucket.0.push(entry.value);
+        bucket.1 += 1;
+        days.entry(date).or_default().push(entry.value);
+    }
+    // A filled day stands in for that day's combined value
+    let daily: BTreeMap<NaiveDate, f64> = days
+        .into_iter()
+        .map(|(d, values)| (d, aggregation.apply(&values)))
+        .collect();
+    for (date, value) in fill_gaps(&daily, fill) {
+        buckets
+            .entry(period_key(date, &period, db.week_start()))
+            .or_default()
+            .0
+            .push(value);
     }
 
     // Convert to PeriodData, sorted by label, limited
     let mut data: Vec<PeriodData> = buckets
         .into_iter()
-        .map(|(label, values)| {
-            let count = values.len() as u32;
-            let sum: f64 = values.iter().sum();
-            let avg = if is_medication {
-                sum
-            } else {
-                sum / values.len() as f64
-            };
+        .map(|(label, (values, count))| {
+            let avg = aggregation.apply(&values);
             let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
             let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
             PeriodData {
@@ -132,33 +342,249 @@ pub fn compute(
                 avg,
                 min,
                 max,
+                filled: values.len() as u32 - count,
                 count,
+                moving_avg: None,
             }
         })
         .collect();
 
+    // Smooth over the full history so the first visible bucket still has a full window
+    let window = smooth.unwrap_or(0) as usize;
+    if window > 1 {
+        let avgs: Vec<f64> = data.iter().map(|d| d.avg).collect();
+        for (i, d) in data.iter_mut().enumerate() {
+            let start = (i + 1).saturating_sub(window);
+            let slice = &avgs[start..=i];
+            d.moving_avg = Some(slice.iter().sum::<f64>() / slice.len() as f64);
+        }
+    }
+
     // Keep only last N periods
     if data.len() > limit {
         let start = data.len() - limit;
         data = data[start..].to_vec();
     }
 
-    // Compute trend (linear regression on period averages)
-    let trend = compute_trend(&data, &period);
+    // Compute trend (linear regression on period averages, or the smoothed series)
+    let series: Vec<f64> = data.iter().map(|d| d.moving_avg.unwrap_or(d.avg)).collect();
+    let trend = compute_trend(&series, &period);
+    let fill = fill_report(fill, data.iter().map(|d| d.filled as usize).sum());
 
     Ok(TrendResult {
         metric_type: metric_type.to_string(),
         period: period_label(&period),
+        aggregation,
         data,
         trend,
+        projection,
+        fill,
+    })
+}
+
+/// Weeks covered by `trend --by weekday` when `--last` is not given.
+pub const DEFAULT_WEEKDAY_WEEKS: u32 = 12;
+
+/// One weekday's entries within a [`WeekdayTrend`] window.
+#[derive(Debug, Serialize)]
+pub struct WeekdayData {
+    /// Lowercase name, e.g. `monday`.
+    pub weekday: String,
+    pub count: u32,
+    /// The entries combined with the trend's aggregation (a total for medications).
+    pub avg: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Entries of the last `weeks` whole weeks bucketed by day of week.
+#[derive(Debug, Serialize)]
+pub struct WeekdayTrend {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub aggregation: Aggregation,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Monday to Sunday, including weekdays without entries.
+    pub weekdays: Vec<WeekdayData>,
+    /// Weekday with the best `avg`: the lowest for a "below" goal, the
+    /// closest for an "equal" goal, otherwise the highest.
+    pub best: Option<String>,
+    pub worst: Option<String>,
+}
+
+/// Bucket the last `weeks` weeks of a type by weekday, ending today.
+pub fn by_weekday(
+    db: &Database,
+    metric_type: &str,
+    weeks: Option<u32>,
+    aggregation: Option<Aggregation>,
+) -> Result<WeekdayTrend> {
+    let today = time::local_today(db.timezone().name());
+    by_weekday_on(db, metric_type, weeks, aggregation, today)
+}
+
+/// [`by_weekday`] ending on `today`. The window is whole weeks, so every
+/// weekday occurs equally often and totals are comparable.
+pub fn by_weekday_on(
+    db: &Database,
+    metric_type: &str,
+    weeks: Option<u32>,
+    aggregation: Option<Aggregation>,
+    today: NaiveDate,
+) -> Result<WeekdayTrend> {
+    if metric_type == JOURNAL_TYPE {
+        crate::fail!(validation, "journal notes have no values to trend");
+    }
+    let weeks = weeks.unwrap_or(DEFAULT_WEEKDAY_WEEKS);
+    if weeks == 0 {
+        crate::fail!(validation, "--last must be at least 1 week");
+    }
+    let from = today - chrono::Duration::days(i64::from(weeks) * 7 - 1);
+    let (entries, aggregation) = trend_entries(db, metric_type, aggregation)?;
+    if entries.is_empty()
+        && let Some(closest) = crate::core::types::suggest(db, metric_type)?
+    {
+        crate::fail!(
+            not_found,
+            "unknown metric type '{}'. Did you mean: {}?",
+            metric_type,
+            closest
+        );
+    }
+
+    let mut buckets: [Vec<f64>; 7] = Default::default();
+    for e in &entries {
+        let date = time::local_date(&e.timestamp, db.timezone());
+        if date >= from && date <= today {
+            buckets[date.weekday().num_days_from_monday() as usize].push(e.value);
+        }
+    }
+    let weekdays: Vec<WeekdayData> = WEEKDAYS
+        .iter()
+        .zip(&buckets)
+        .map(|(day, values)| {
+            let has = !values.is_empty();
+            WeekdayData {
+                weekday: weekday_name(*day).to_string(),
+                count: values.len() as u32,
+                avg: has.then(|| aggregation.apply(values)),
+                min: has.then(|| Aggregation::Min.apply(values)),
+                max: has.then(|| Aggregation::Max.apply(values)),
+            }
+        })
+        .collect();
+
+    // Distance from the ideal: lower is better
+    let goal = db.get_goal_by_type(metric_type)?;
+    let score = |avg: f64| match &goal {
+        Some(g) if g.direction == Direction::Below => avg,
+        Some(g) if g.direction == Direction::Equal => (avg - g.target_value).abs(),
+        _ => -avg,
+    };
+    let ranked: Vec<(&str, f64)> = weekdays
+        .iter()
+        .filter_map(|d| d.avg.map(|a| (d.weekday.as_str(), score(a))))
+        .collect();
+    let best = ranked
+        .iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(d, _)| d.to_string());
+    let worst = ranked
+        .iter()
+        .rev()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(d, _)| d.to_string());
+
+    Ok(WeekdayTrend {
+        metric_type: metric_type.to_string(),
+        aggregation,
+        from,
+        to: today,
+        weekdays,
+        best,
+        worst,
+    })
+}
+
+pub(crate) const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Lowercase weekday name, e.g. `monday`.
+pub(crate) fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+/// A type's entries for trending, oldest first, with the aggregation to use.
+fn trend_entries(
+    db: &Database,
+    metric_type: &str,
+    aggregation: Option<Aggregation>,
+) -> Result<(Vec<Metric>, Aggregation)> {
+    // Fetch all entries in ascending order for bucketing
+    let all_entries = db.query_by_type_asc(metric_type, None)?;
+
+    // Separate medication from non-medication entries to handle name collisions.
+    // If non-medication entries exist, use those (the metric predates the medication).
+    // If only medication entries exist, use those with sum aggregation.
+    let has_non_med = all_entries
+        .iter()
+        .any(|e| e.category != Category::Medication);
+    let entries: Vec<_> = if has_non_med {
+        all_entries
+            .into_iter()
+            .filter(|e| e.category != Category::Medication)
+            .collect()
+    } else {
+        all_entries
+    };
+    let is_medication = !has_non_med
+        && entries
+            .first()
+            .is_some_and(|e| e.category == Category::Medication);
+
+    // Medication doses are always totalled unless explicitly overridden
+    let aggregation = aggregation.unwrap_or(if is_medication {
+        Aggregation::Sum
+    } else {
+        default_aggregation(metric_type)
+    });
+    Ok((entries, aggregation))
+}
+
+/// A [`FillReport`] unless filling is off.
+fn fill_report(fill: GapFill, synthetic_points: usize) -> Option<FillReport> {
+    (fill.method != FillMethod::None).then_some(FillReport {
+        method: fill.method,
+        max_gap_days: fill.max_gap_days,
+        synthetic_points,
     })
 }
 
-fn period_key(date: NaiveDate, period: &TrendPeriod) -> String {
+/// Bucket key of `date`. Weekly buckets are labelled with the ISO week of the
+/// week's Monday, so a Sunday-start week is named after the ISO week it leads into.
+fn period_key(date: NaiveDate, period: &TrendPeriod, week_start: Weekday) -> String {
     match period {
         TrendPeriod::Daily => date.format("%Y-%m-%d").to_string(),
         TrendPeriod::Weekly => {
-            let iso = date.iso_week();
+            let first = time::week_start_for(date, week_start);
+            let monday = time::week_start_for(first + chrono::Duration::days(6), Weekday::Mon);
+            let iso = monday.iso_week();
             format!("{}-W{:02}", iso.year(), iso.week())
         }
         TrendPeriod::Monthly => date.format("%Y-%m").to_string(),
@@ -181,28 +607,30 @@ fn period_noun(period: &TrendPeriod) -> &'static str {
     }
 }
 
-fn compute_trend(data: &[PeriodData], period: &TrendPeriod) -> TrendSummary {
-    if data.len() < 2 {
-        let last_val = data.first().map(|d| d.avg);
+fn compute_trend(ys: &[f64], period: &TrendPeriod) -> TrendSummary {
+    if ys.len() < 2 {
+        let last_val = ys.first().copied();
         return TrendSummary {
             direction: "stable".to_string(),
             rate: 0.0,
             rate_unit: format!("per {}", period_noun(period)),
             projected_30d: last_val,
+            std_error: None,
+            confidence_95_lower: None,
+            confidence_95_upper: None,
         };
     }
 
-    // Simple linear regression: y = slope * x + intercept
-    let n = data.len() as f64;
-    let xs: Vec<f64> = (0..data.len()).map(|i| i as f64).collect();
-    let ys: Vec<f64> = data.iter().map(|d| d.avg).collect();
-
-    let sum_x: f64 = xs.iter().sum();
-    let sum_y: f64 = ys.iter().sum();
-    let sum_xy: f64 = xs.iter().zip(ys.iter()).map(|(x, y)| x * y).sum();
-    let sum_xx: f64 = xs.iter().map(|x| x * x).sum();
-
-    let slope = (n * sum_xy - sum_x * sum_y) / (n * sum_xx - sum_x * sum_x);
+    let xs: Vec<f64> = (0..ys.len()).map(|i| i as f64).collect();
+    let slope = ols_slope(&xs, ys);
+    let std_error = slope_std_error(&xs, ys, slope);
+    let (confidence_95_lower, confidence_95_upper) = match std_error {
+        Some(se) => {
+            let t = t_critical_95(ys.len() - 2);
+            (Some(slope - t * se), Some(slope + t * se))
+        }
+        None => (None, None),
+    };
 
     let direction = if slope < -0.01 {
         "decreasing"
@@ -237,6 +665,64 @@ fn compute_trend(data: &[PeriodData], period: &TrendPeriod) -> TrendSummary {
         rate,
         rate_unit: format!("per {}", period_noun(period)),
         projected_30d: Some(projected),
+        std_error,
+        confidence_95_lower,
+        confidence_95_upper,
+    }
+}
+
+/// Simple linear regression slope of `ys` on `xs`.
+fn ols_slope(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = ys.len() as f64;
+    let sum_x: f64 = xs.iter().sum();
+    let sum_y: f64 = ys.iter().sum();
+    let sum_xy: f64 = xs.iter().zip(ys.iter()).map(|(x, y)| x * y).sum();
+    let sum_xx: f64 = xs.iter().map(|x| x * x).sum();
+    (n * sum_xy - sum_x * sum_y) / (n * sum_xx - sum_x * sum_x)
+}
+
+/// Unrounded slope of `ys` per step with its 95% confidence interval, as
+/// `(slope, lower, upper)`; `None` with fewer than 3 values.
+pub(crate) fn slope_interval(ys: &[f64]) -> Option<(f64, f64, f64)> {
+    let xs: Vec<f64> = (0..ys.len()).map(|i| i as f64).collect();
+    let slope = ols_slope(&xs, ys);
+    let se = slope_std_error(&xs, ys, slope)?;
+    let t = t_critical_95(ys.len() - 2);
+    Some((slope, slope - t * se, slope + t * se))
+}
+
+/// OLS standard error of the slope: sqrt(SSres / ((n - 2) * SSx)).
+fn slope_std_error(xs: &[f64], ys: &[f64], slope: f64) -> Option<f64> {
+    let n = xs.len();
+    if n < 3 {
+        return None;
+    }
+    let mean_x = xs.iter().sum::<f64>() / n as f64;
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+    let intercept = mean_y - slope * mean_x;
+    let ss_x: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let ss_res: f64 = xs
+        .iter()
+        .zip(ys)
+        .map(|(x, y)| (y - (slope * x + intercept)).powi(2))
+        .sum();
+    if ss_x <= 0.0 {
+        return None;
+    }
+    Some((ss_res / ((n - 2) as f64 * ss_x)).sqrt())
+}
+
+/// Two-tailed 95% critical value of Student's t for `df` degrees of freedom.
+/// Exact table values up to 30 df, then the normal approximation (1.96).
+fn t_critical_95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        1..=30 => TABLE[df - 1],
+        _ => 1.96,
     }
 }
 
@@ -247,92 +733,148 @@ pub fn correlate(
     metric_b: &str,
     last_days: Option<u32>,
 ) -> Result<CorrelationResult> {
-    let all_a = db.query_by_type_asc(metric_a, None)?;
-    let all_b = db.query_by_type_asc(metric_b, None)?;
+    correlate_with_lag(db, metric_a, metric_b, last_days, 0)
+}
 
-    // Filter out medication entries when non-medication entries exist (name collision)
-    let has_non_med_a = all_a.iter().any(|e| e.category != Category::Medication);
-    let has_non_med_b = all_b.iter().any(|e| e.category != Category::Medication);
-    let entries_a: Vec<_> = if has_non_med_a {
-        all_a
-            .into_iter()
-            .filter(|e| e.category != Category::Medication)
-            .collect()
-    } else {
-        all_a
-    };
-    let entries_b: Vec<_> = if has_non_med_b {
-        all_b
-            .into_iter()
-            .filter(|e| e.category != Category::Medication)
-            .collect()
-    } else {
-        all_b
+/// Try every lag in [`LAG_SCAN_RANGE`] and report the strongest correlation.
+/// Lags with insufficient data are listed but never chosen as best.
+pub fn scan_lag(
+    db: &Database,
+    metric_a: &str,
+    metric_b: &str,
+    last_days: Option<u32>,
+) -> Result<LagScanResult> {
+    scan_lag_with(
+        db,
+        metric_a,
+        metric_b,
+        last_days,
+        CycleConfig::default().gap_days,
+        GapFill::default(),
+    )
+}
+
+/// [`scan_lag`] with cycles detected using `cycle_gap_days` (for `cycle_day`)
+/// and gaps filled per `fill`.
+pub fn scan_lag_with(
+    db: &Database,
+    metric_a: &str,
+    metric_b: &str,
+    last_days: Option<u32>,
+    cycle_gap_days: u32,
+    fill: GapFill,
+) -> Result<LagScanResult> {
+    let mut best: Option<CorrelationResult> = None;
+    let mut lags = Vec::new();
+    for lag in LAG_SCAN_RANGE {
+        let r = correlate_with(db, metric_a, metric_b, last_days, lag, cycle_gap_days, fill)?;
+        lags.push(LagCoefficient {
+            lag,
+            coefficient: r.coefficient,
+            data_points: r.data_points,
+        });
+        if r.data_points < 3 {
+            continue;
+        }
+        let better = match &best {
+            None => true,
+            Some(b) => {
+                r.coefficient.abs() > b.coefficient.abs()
+                    || (r.coefficient.abs() == b.coefficient.abs() && lag.abs() < b.lag.abs())
+            }
+        };
+        if better {
+            best = Some(r);
+        }
+    }
+    let best = match best {
+        Some(b) => b,
+        None => correlate_with(db, metric_a, metric_b, last_days, 0, cycle_gap_days, fill)?,
     };
+    Ok(LagScanResult { best, lags })
+}
 
-    // Detect medication types: use sum instead of average for daily values
-    let is_med_a = !has_non_med_a
-        && entries_a
-            .first()
-            .is_some_and(|e| e.category == Category::Medication);
-    let is_med_b = !has_non_med_b
-        && entries_b
-            .first()
-            .is_some_and(|e| e.category == Category::Medication);
+/// Pearson correlation with metric B shifted by `lag` days (may be negative).
+/// With `last_days`, both days of a pair must fall inside the window, so pairs
+/// shifted past either edge are dropped before the sufficiency check.
+pub fn correlate_with_lag(
+    db: &Database,
+    metric_a: &str,
+    metric_b: &str,
+    last_days: Option<u32>,
+    lag: i64,
+) -> Result<CorrelationResult> {
+    correlate_with(
+        db,
+        metric_a,
+        metric_b,
+        last_days,
+        lag,
+        CycleConfig::default().gap_days,
+        GapFill::default(),
+    )
+}
 
-    // Group by date, compute daily values (sum for medications, average otherwise)
-    let avg_a = daily_values(&entries_a, is_med_a);
-    let avg_b = daily_values(&entries_b, is_med_b);
+/// [`correlate_with_lag`] with cycles detected using `cycle_gap_days`, so
+/// either side can be the virtual `cycle_day` series, and each daily series
+/// gap-filled per `fill` before pairing.
+pub fn correlate_with(
+    db: &Database,
+    metric_a: &str,
+    metric_b: &str,
+    last_days: Option<u32>,
+    lag: i64,
+    cycle_gap_days: u32,
+    fill: GapFill,
+) -> Result<CorrelationResult> {
+    if metric_a == JOURNAL_TYPE || metric_b == JOURNAL_TYPE {
+        crate::fail!(validation, "journal notes have no values to correlate");
+    }
+    let mut avg_a = correlation_values(db, metric_a, cycle_gap_days)?;
+    let mut avg_b = correlation_values(db, metric_b, cycle_gap_days)?;
+    let filled_a = fill_gaps(&avg_a, fill);
+    let filled_b = fill_gaps(&avg_b, fill);
+    avg_a.extend(filled_a.iter().map(|(d, v)| (*d, *v)));
+    avg_b.extend(filled_b.iter().map(|(d, v)| (*d, *v)));
+    let tz = db.timezone();
 
     // Find matching dates
     let mut pairs: Vec<(f64, f64)> = Vec::new();
-    let cutoff =
-        last_days.map(|d| chrono::Local::now().date_naive() - chrono::Duration::days(d as i64));
+    let mut synthetic = 0;
+    let cutoff = last_days.map(|d| time::local_today(tz.name()) - chrono::Duration::days(d as i64));
 
     for (date, val_a) in &avg_a {
+        let date_b = *date + chrono::Duration::days(lag);
         if let Some(cutoff_date) = cutoff
-            && *date < cutoff_date
+            && (*date < cutoff_date || date_b < cutoff_date)
         {
             continue;
         }
-        if let Some(val_b) = avg_b.get(date) {
+        if let Some(val_b) = avg_b.get(&date_b) {
             pairs.push((*val_a, *val_b));
+            if filled_a.contains_key(date) || filled_b.contains_key(&date_b) {
+                synthetic += 1;
+            }
         }
     }
+    let fill = fill_report(fill, synthetic);
 
     let n = pairs.len();
     if n < 3 {
         return Ok(CorrelationResult {
             metric_a: metric_a.to_string(),
             metric_b: metric_b.to_string(),
+            lag,
             coefficient: 0.0,
             data_points: n,
             interpretation: "insufficient data".to_string(),
+            fill,
         });
     }
 
-    // Pearson correlation coefficient
-    let sum_a: f64 = pairs.iter().map(|(a, _)| a).sum();
-    let sum_b: f64 = pairs.iter().map(|(_, b)| b).sum();
-    let sum_ab: f64 = pairs.iter().map(|(a, b)| a * b).sum();
-    let sum_aa: f64 = pairs.iter().map(|(a, _)| a * a).sum();
-    let sum_bb: f64 = pairs.iter().map(|(_, b)| b * b).sum();
-    let nf = n as f64;
-
-    let numerator = nf * sum_ab - sum_a * sum_b;
-    let denominator = ((nf * sum_aa - sum_a * sum_a) * (nf * sum_bb - sum_b * sum_b)).sqrt();
-
-    let coefficient = if denominator.abs() < 1e-10 {
-        0.0
-    } else {
-        (numerator / denominator * 100.0).round() / 100.0
-    };
+    let coefficient = pearson(&pairs).unwrap_or(0.0);
 
-    let base = match coefficient.abs() {
-        r if r < 0.3 => "weak",
-        r if r < 0.7 => "moderate",
-        _ => "strong",
-    };
+    let base = strength(coefficient);
     let interpretation = if n < 10 {
         format!("{} (low sample size: {} points)", base, n)
     } else {
@@ -342,19 +884,160 @@ pub fn correlate(
     Ok(CorrelationResult {
         metric_a: metric_a.to_string(),
         metric_b: metric_b.to_string(),
+        lag,
         coefficient,
         data_points: n,
         interpretation,
+        fill,
+    })
+}
+
+/// Daily values for correlation: sums for medication doses, averages
+/// otherwise, and the cycle day for [`cycle::CYCLE_DAY`].
+fn correlation_values(
+    db: &Database,
+    metric_type: &str,
+    cycle_gap_days: u32,
+) -> Result<BTreeMap<NaiveDate, f64>> {
+    if metric_type == cycle::CYCLE_DAY {
+        return cycle::day_series(db, cycle_gap_days);
+    }
+    let all = db.query_by_type_asc(metric_type, None)?;
+
+    // Filter out medication entries when non-medication entries exist (name collision)
+    let has_non_med = all.iter().any(|e| e.category != Category::Medication);
+    let entries: Vec<_> = if has_non_med {
+        all.into_iter()
+            .filter(|e| e.category != Category::Medication)
+            .collect()
+    } else {
+        all
+    };
+    // Medication types: use sum instead of average for daily values
+    let is_med = !has_non_med
+        && entries
+            .first()
+            .is_some_and(|e| e.category == Category::Medication);
+    Ok(daily_values(&entries, is_med, db.timezone()))
+}
+
+/// Pearson correlation between every pair of metric types, using daily values
+/// restricted to the last `last` days. Pairs with fewer than
+/// [`MATRIX_MIN_POINTS`] matching days (or no variance) are `None`.
+pub fn correlation_matrix(db: &Database, last: Option<usize>) -> Result<CorrelationMatrix> {
+    let tz = db.timezone();
+    let cutoff = last.map(|d| time::local_today(tz.name()) - chrono::Duration::days(d as i64));
+
+    let mut types = Vec::new();
+    let mut series = Vec::new();
+    for t in db.distinct_metric_types()? {
+        if t == JOURNAL_TYPE {
+            continue;
+        }
+        let mut s = daily_series(db, &t)?;
+        if let Some(cutoff_date) = cutoff {
+            s.retain(|d, _| *d >= cutoff_date);
+        }
+        if !s.is_empty() {
+            types.push(t);
+            series.push(s);
+        }
+    }
+
+    let n = types.len();
+    let mut coefficients = vec![vec![None; n]; n];
+    for i in 0..n {
+        coefficients[i][i] = Some(1.0);
+        for j in (i + 1)..n {
+            let pairs: Vec<(f64, f64)> = series[i]
+                .iter()
+                .filter_map(|(d, a)| series[j].get(d).map(|b| (*a, *b)))
+                .collect();
+            let r = if pairs.len() < MATRIX_MIN_POINTS {
+                None
+            } else {
+                pearson(&pairs)
+            };
+            coefficients[i][j] = r;
+            coefficients[j][i] = r;
+        }
+    }
+
+    Ok(CorrelationMatrix {
+        types,
+        coefficients,
     })
 }
 
+/// `weak` below |r| 0.3, `moderate` below 0.7, `strong` otherwise.
+pub(crate) fn strength(coefficient: f64) -> &'static str {
+    match coefficient.abs() {
+        r if r < 0.3 => "weak",
+        r if r < 0.7 => "moderate",
+        _ => "strong",
+    }
+}
+
+/// Pearson correlation coefficient rounded to two decimals; `None` without variance.
+pub(crate) fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    let sum_a: f64 = pairs.iter().map(|(a, _)| a).sum();
+    let sum_b: f64 = pairs.iter().map(|(_, b)| b).sum();
+    let sum_ab: f64 = pairs.iter().map(|(a, b)| a * b).sum();
+    let sum_aa: f64 = pairs.iter().map(|(a, _)| a * a).sum();
+    let sum_bb: f64 = pairs.iter().map(|(_, b)| b * b).sum();
+    let nf = pairs.len() as f64;
+
+    let numerator = nf * sum_ab - sum_a * sum_b;
+    let denominator = ((nf * sum_aa - sum_a * sum_a) * (nf * sum_bb - sum_b * sum_b)).sqrt();
+
+    if denominator.is_nan() || denominator.abs() < 1e-10 {
+        None
+    } else {
+        Some((numerator / denominator * 100.0).round() / 100.0)
+    }
+}
+
+/// Daily values for a metric type (sum for medications, average otherwise),
+/// keyed by local date. Medication entries are dropped on a name collision.
+pub(crate) fn daily_series(db: &Database, metric_type: &str) -> Result<BTreeMap<NaiveDate, f64>> {
+    daily_series_tagged(db, metric_type, &[])
+}
+
+/// [`daily_series`] over only the entries carrying every tag in `tags`.
+pub(crate) fn daily_series_tagged(
+    db: &Database,
+    metric_type: &str,
+    tags: &[String],
+) -> Result<BTreeMap<NaiveDate, f64>> {
+    let all = if tags.is_empty() {
+        db.query_by_type_asc(metric_type, None)?
+    } else {
+        db.query_tagged(Some(metric_type), None, None, tags)?
+    };
+    let has_non_med = all.iter().any(|e| e.category != Category::Medication);
+    let entries: Vec<_> = if has_non_med {
+        all.into_iter()
+            .filter(|e| e.category != Category::Medication)
+            .collect()
+    } else {
+        all
+    };
+    let use_sum = crate::models::metric::is_cumulative(metric_type)
+        || (!has_non_med
+            && entries
+                .first()
+                .is_some_and(|e| e.category == Category::Medication));
+    Ok(daily_values(&entries, use_sum, db.timezone()))
+}
+
 fn daily_values(
     entries: &[crate::models::metric::Metric],
     use_sum: bool,
+    tz: chrono_tz::Tz,
 ) -> BTreeMap<NaiveDate, f64> {
     let mut day_sums: BTreeMap<NaiveDate, (f64, u32)> = BTreeMap::new();
     for e in entries {
-        let date = e.timestamp.date_naive();
+        let date = time::local_date(&e.timestamp, tz);
         let entry = day_sums.entry(date).or_insert((0.0, 0));
         entry.0 += e.value;
         entry.1 += 1;
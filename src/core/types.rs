@@ -0,0 +1,435 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::models::config::Config;
+use crate::models::metric::{self, BUILTIN_TYPES, Category, CustomMetricType, Metric};
+
+/// One row of the type registry.
+#[derive(Debug, Serialize)]
+pub struct TypeInfo {
+    pub name: String,
+    pub unit: String,
+    pub category: Category,
+    /// Configured aliases that resolve to this type.
+    pub aliases: Vec<String>,
+    pub entries: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MedicationType {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dose: Option<String>,
+    pub active: bool,
+    pub entries: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypesList {
+    pub builtin: Vec<TypeInfo>,
+    pub custom: Vec<TypeInfo>,
+    /// Types with logged entries but no built-in or custom definition.
+    pub observed: Vec<TypeInfo>,
+    pub medications: Vec<MedicationType>,
+    pub aliases: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnitCount {
+    pub unit: String,
+    pub entries: u64,
+}
+
+/// Registry entry for a single type, with its logged range.
+#[derive(Debug, Serialize)]
+pub struct TypeDetail {
+    pub name: String,
+    /// "builtin", "custom", "medication" or "observed".
+    pub kind: &'static str,
+    pub unit: String,
+    pub category: Category,
+    pub aliases: Vec<String>,
+    pub entries: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// Units entries were stored in, most used first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub units: Vec<UnitCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RemoveResult {
+    pub name: String,
+    /// Entries of this type left in place (only non-zero with `force`).
+    pub entries: u64,
+}
+
+fn aliases_for(config: &Config, metric_type: &str) -> Vec<String> {
+    let mut aliases: Vec<String> = config
+        .aliases
+        .iter()
+        .filter(|(_, target)| *target == metric_type)
+        .map(|(alias, _)| alias.clone())
+        .collect();
+    aliases.sort();
+    aliases
+}
+
+/// Built-in, custom and observed types with entry counts, plus medications and aliases.
+pub fn list(db: &Database, config: &Config) -> Result<TypesList> {
+    let usage = db.type_usage()?;
+    let entries = |name: &str| {
+        usage
+            .iter()
+            .find(|u| u.metric_type == name)
+            .map_or(0, |u| u.count)
+    };
+    let info = |name: &str, unit: &str, category: Category| TypeInfo {
+        name: name.to_string(),
+        unit: unit.to_string(),
+        category,
+        aliases: aliases_for(config, name),
+        entries: entries(name),
+    };
+
+    let builtin = BUILTIN_TYPES
+        .iter()
+        .map(|&name| info(name, metric::default_unit(name), Category::from_type(name)))
+        .collect();
+    let custom_types = db.list_custom_types()?;
+    let custom = custom_types
+        .iter()
+        .map(|t| info(&t.name, &t.unit, t.category.clone()))
+        .collect();
+
+    let meds = db.list_medications(true)?;
+    let medications = meds
+        .iter()
+        .map(|m| MedicationType {
+            name: m.name.clone(),
+            dose: m.dose.clone(),
+            active: m.active,
+            entries: entries(&m.name),
+        })
+        .collect();
+
+    let observed = usage
+        .iter()
+        .filter(|u| {
+            u.category != Category::Medication
+                && !metric::is_builtin(&u.metric_type)
+                && !custom_types.iter().any(|t| t.name == u.metric_type)
+                && !meds.iter().any(|m| m.name == u.metric_type)
+        })
+        .map(|u| {
+            let unit = u.units.first().map_or("", |(unit, _)| unit.as_str());
+            info(&u.metric_type, unit, u.category.clone())
+        })
+        .collect();
+
+    Ok(TypesList {
+        builtin,
+        custom,
+        observed,
+        medications,
+        aliases: config
+            .aliases
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+    })
+}
+
+/// Registry entry for one type (aliases resolved), with its first/last entry and value range.
+pub fn detail(db: &Database, config: &Config, name: &str) -> Result<TypeDetail> {
+    let name = config.resolve_alias(name.trim());
+    let usage = db.type_usage()?.into_iter().find(|u| u.metric_type == name);
+
+    let (kind, unit, category) = if metric::is_builtin(&name) {
+        (
+            "builtin",
+            metric::default_unit(&name).to_string(),
+            Category::from_type(&name),
+        )
+    } else if let Some(t) = db.get_custom_type(&name)? {
+        ("custom", t.unit, t.category)
+    } else if db.get_medication_by_name_any(&name)?.is_some() {
+        ("medication", String::new(), Category::Medication)
+    } else if let Some(u) = &usage {
+        let unit = u.units.first().map(|(unit, _)| unit.clone());
+        ("observed", unit.unwrap_or_default(), u.category.clone())
+    } else {
+        crate::fail!(not_found, "unknown metric type '{}'", name);
+    };
+
+    let aliases = aliases_for(config, &name);
+    Ok(match usage {
+        Some(u) => TypeDetail {
+            name,
+            kind,
+            unit,
+            category,
+            aliases,
+            entries: u.count,
+            first: Some(u.first),
+            last: Some(u.last),
+            min: Some(u.min),
+            max: Some(u.max),
+            units: u
+                .units
+                .into_iter()
+                .map(|(unit, entries)| UnitCount { unit, entries })
+                .collect(),
+        },
+        None => TypeDetail {
+            name,
+            kind,
+            unit,
+            category,
+            aliases,
+            entries: 0,
+            first: None,
+            last: None,
+            min: None,
+            max: None,
+            units: Vec::new(),
+        },
+    })
+}
+
+/// What `migrate_type` may touch besides the entries themselves.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MigrateOptions {
+    /// Only count what would change.
+    pub dry_run: bool,
+    /// Move goals on the old type to the new one.
+    pub goals: bool,
+    /// Repoint aliases that resolve to the old type.
+    pub aliases: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrateResult {
+    pub from: String,
+    pub to: String,
+    pub dry_run: bool,
+    /// Whether the target already had entries that the moved ones joined.
+    pub merged: bool,
+    /// Rows changed in `metrics`.
+    pub metrics: u64,
+    /// Moved entries without a unit that took the target's default unit.
+    pub units_filled: u64,
+    /// Rows changed in `goals`.
+    pub goals: u64,
+    /// Aliases repointed in the config.
+    pub aliases: Vec<String>,
+}
+
+/// Rename every entry of metric type `from` to `to`, merging into `to` if it
+/// already has entries. Entries without a unit take the target's default unit,
+/// and entries take the target's category when it is a defined type. Goals and
+/// aliases are only moved when asked; the caller saves `config`.
+pub fn migrate_type(
+    db: &Database,
+    config: &mut Config,
+    from: &str,
+    to: &str,
+    opts: MigrateOptions,
+) -> Result<MigrateResult> {
+    let (from, to) = (from.trim(), to.trim());
+    if from.is_empty() || to.is_empty() {
+        crate::fail!(validation, "type names must not be empty");
+    }
+    if from == to {
+        crate::fail!(validation, "'{}' is already the type name", from);
+    }
+    let metrics = db.count_metrics_of_type(from)?;
+    if metrics == 0 {
+        crate::fail!(not_found, "no entries of type '{}'", from);
+    }
+    if opts.goals && db.get_goal_by_type(from)?.is_some() && db.get_goal_by_type(to)?.is_some() {
+        crate::fail!(
+            conflict,
+            "both '{}' and '{}' have an active goal; remove one first",
+            from,
+            to
+        );
+    }
+
+    let unit = default_unit(db, to)?;
+    let units_filled = if unit.is_empty() {
+        0
+    } else {
+        db.count_unitless_metrics(from)?
+    };
+    let defined = metric::is_builtin(to) || db.get_custom_type(to)?.is_some();
+    let target_category = if defined {
+        Some(category(db, to)?)
+    } else {
+        None
+    };
+    let merged = db.count_metrics_of_type(to)? > 0;
+    let mut aliases: Vec<String> = if opts.aliases {
+        config
+            .aliases
+            .iter()
+            .filter(|(_, target)| *target == from)
+            .map(|(alias, _)| alias.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    aliases.sort();
+
+    let goals = if opts.dry_run {
+        if opts.goals {
+            db.count_goals_of_type(from)?
+        } else {
+            0
+        }
+    } else {
+        db.in_transaction(|db| {
+            db.rename_metric_type(from, to, &unit, target_category.as_ref())?;
+            if opts.goals {
+                db.rename_goal_type(from, to)
+            } else {
+                Ok(0)
+            }
+        })?
+    };
+    if !opts.dry_run {
+        for alias in &aliases {
+            config.aliases.insert(alias.clone(), to.to_string());
+        }
+    }
+
+    Ok(MigrateResult {
+        from: from.to_string(),
+        to: to.to_string(),
+        dry_run: opts.dry_run,
+        merged,
+        metrics,
+        units_filled,
+        goals,
+        aliases,
+    })
+}
+
+/// Define a custom metric type. Built-in names and existing definitions are refused.
+pub fn add(db: &Database, name: &str, unit: &str, category: Category) -> Result<CustomMetricType> {
+    let name = name.trim();
+    if name.is_empty() {
+        crate::fail!(validation, "type name must not be empty");
+    }
+    if metric::is_builtin(name) {
+        crate::fail!(conflict, "'{}' is a built-in metric type", name);
+    }
+    if category == Category::Medication {
+        crate::fail!(
+            validation,
+            "the medication category is reserved for `med take` entries"
+        );
+    }
+    if db.get_custom_type(name)?.is_some() {
+        crate::fail!(conflict, "custom type '{}' already exists", name);
+    }
+    let t = CustomMetricType {
+        name: name.to_string(),
+        unit: unit.trim().to_string(),
+        category,
+    };
+    db.insert_custom_type(&t)?;
+    Ok(t)
+}
+
+/// Remove a custom type definition. Types with logged entries need `force`;
+/// the entries themselves are kept.
+pub fn remove(db: &Database, name: &str, force: bool) -> Result<RemoveResult> {
+    if db.get_custom_type(name)?.is_none() {
+        crate::fail!(not_found, "custom type '{}' not found", name);
+    }
+    let entries = db.count_metrics_of_type(name)?;
+    if entries > 0 && !force {
+        crate::fail!(
+            conflict,
+            "custom type '{}' has {} entries; use --force to remove it anyway",
+            name,
+            entries
+        );
+    }
+    db.remove_custom_type(name)?;
+    Ok(RemoveResult {
+        name: name.to_string(),
+        entries,
+    })
+}
+
+/// Every type name `log` accepts without a suggestion: built-in types, custom
+/// definitions and types that already have entries.
+pub fn known_names(db: &Database) -> Result<Vec<String>> {
+    let mut names: Vec<String> = BUILTIN_TYPES.iter().map(|t| t.to_string()).collect();
+    names.extend(db.list_custom_types()?.into_iter().map(|t| t.name));
+    names.extend(db.distinct_metric_types()?);
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|n| seen.insert(n.clone()));
+    Ok(names)
+}
+
+/// A known type `metric_type` is probably a typo of, when it is not known itself.
+pub fn suggest(db: &Database, metric_type: &str) -> Result<Option<String>> {
+    if metric::is_builtin(metric_type) || crate::core::bp::is_bp(metric_type) {
+        return Ok(None);
+    }
+    let known = known_names(db)?;
+    if known.iter().any(|n| n == metric_type) {
+        return Ok(None);
+    }
+    Ok(crate::core::analytics::closest_metric_type(metric_type, &known).map(|(t, _)| t))
+}
+
+/// Default unit for `metric_type`: the built-in unit, else a custom definition's.
+pub fn default_unit(db: &Database, metric_type: &str) -> Result<String> {
+    if metric::is_builtin(metric_type) {
+        return Ok(metric::default_unit(metric_type).to_string());
+    }
+    Ok(db
+        .get_custom_type(metric_type)?
+        .map(|t| t.unit)
+        .unwrap_or_default())
+}
+
+/// Category for `metric_type`: the built-in one, else a custom definition's.
+pub fn category(db: &Database, metric_type: &str) -> Result<Category> {
+    if metric::is_builtin(metric_type) {
+        return Ok(Category::from_type(metric_type));
+    }
+    Ok(db
+        .get_custom_type(metric_type)?
+        .map(|t| t.category)
+        .unwrap_or(Category::Custom))
+}
+
+/// Fill in the category and (if unset) the unit of a newly built metric from
+/// its custom type definition, if it has one.
+pub fn apply(db: &Database, m: &mut Metric) -> Result<()> {
+    if m.category != Category::Custom || metric::is_builtin(&m.metric_type) {
+        return Ok(());
+    }
+    if let Some(t) = db.get_custom_type(&m.metric_type)? {
+        m.category = t.category;
+        if m.unit.is_empty() {
+            m.unit = t.unit;
+        }
+    }
+    Ok(())
+}
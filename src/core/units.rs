@@ -1,13 +1,23 @@
-use crate::models::config::Units;
+use anyhow::Result;
+
+use crate::models::config::{UnitOverride, Units};
 use crate::models::metric::default_unit;
 
 const KG_TO_LBS: f64 = 2.20462;
 const IN_TO_CM: f64 = 2.54;
 const FT_TO_CM: f64 = 30.48;
 const FLOZ_TO_ML: f64 = 29.5735;
+/// mg/dL of glucose per mmol/L.
+const MMOL_TO_MGDL: f64 = 18.0;
 
 /// Convert a stored (metric) value to display value + display unit string.
 pub fn to_display(value: f64, metric_type: &str, units: &Units) -> (f64, String) {
+    if let Some(o) = units.override_for(metric_type) {
+        return match o.factor {
+            Some(f) => (round1(value / f), o.unit.clone()),
+            None => (value, o.unit.clone()),
+        };
+    }
     if !units.is_imperial() {
         return (value, default_unit(metric_type).to_string());
     }
@@ -29,6 +39,9 @@ pub fn display_unit(metric_type: &str, units: &Units) -> String {
 
 /// Convert a metric-space change/rate to display-space rate.
 pub fn to_display_rate(rate: f64, metric_type: &str, units: &Units) -> f64 {
+    if let Some(o) = units.override_for(metric_type) {
+        return o.factor.map_or(rate, |f| round1(rate / f));
+    }
     if !units.is_imperial() {
         return rate;
     }
@@ -45,6 +58,9 @@ pub fn to_display_rate(rate: f64, metric_type: &str, units: &Units) -> f64 {
 
 /// Convert a user-input value (in their configured unit system) to metric for storage.
 pub fn from_input(value: f64, metric_type: &str, units: &Units) -> f64 {
+    if let Some(o) = units.override_for(metric_type) {
+        return o.factor.map_or(value, |f| value * f);
+    }
     if !units.is_imperial() {
         return value;
     }
@@ -59,6 +75,60 @@ pub fn from_input(value: f64, metric_type: &str, units: &Units) -> f64 {
     }
 }
 
+/// Stored units per `display` unit for a type stored in `canonical`, for the
+/// conversions we know about.
+pub fn known_factor(canonical: &str, display: &str) -> Option<f64> {
+    let factor = match (canonical, display) {
+        (c, d) if c == d => 1.0,
+        ("ml", "oz" | "fl_oz" | "fl oz") => FLOZ_TO_ML,
+        ("ml", "l") => 1000.0,
+        ("ml", "cup") => 236.588,
+        ("kg", "lbs" | "lb") => 1.0 / KG_TO_LBS,
+        ("kg", "g") => 0.001,
+        ("kg", "st") => 6.35029,
+        ("cm", "in") => IN_TO_CM,
+        ("cm", "ft") => FT_TO_CM,
+        ("cm", "m") => 100.0,
+        ("cm", "mm") => 0.1,
+        ("km", "mi") => 1.609344,
+        ("km", "m") => 0.001,
+        ("min", "h" | "hours") => 60.0,
+        ("hours", "min") => 1.0 / 60.0,
+        ("kcal", "kj") => 1.0 / 4.184,
+        ("mg/dL", "mmol/L" | "mmol/l" | "mmol") => MMOL_TO_MGDL,
+        _ => return None,
+    };
+    Some(factor)
+}
+
+/// Parse a `units.<type>` setting: `UNIT` or `UNIT:FACTOR`, where FACTOR is
+/// stored units per display unit. Without a factor, a known conversion from
+/// the type's built-in unit is used; an unknown unit becomes a display-only label.
+pub fn parse_override(metric_type: &str, spec: &str) -> Result<UnitOverride> {
+    let (unit, factor) = match spec.split_once(':') {
+        Some((unit, factor)) => {
+            let f: f64 = factor.trim().parse().map_err(|_| {
+                crate::error::parse(format!("invalid conversion factor in '{}'", spec))
+            })?;
+            if !f.is_finite() || f <= 0.0 {
+                crate::fail!(validation, "conversion factor must be positive: {}", spec);
+            }
+            (unit.trim(), Some(f))
+        }
+        None => {
+            let unit = spec.trim();
+            (unit, known_factor(default_unit(metric_type), unit))
+        }
+    };
+    if unit.is_empty() {
+        crate::fail!(validation, "unit for {} must not be empty", metric_type);
+    }
+    Ok(UnitOverride {
+        unit: unit.to_string(),
+        factor,
+    })
+}
+
 fn round1(v: f64) -> f64 {
     (v * 10.0).round() / 10.0
 }
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::core::time;
+use crate::db::Database;
+use crate::models::config::Config;
+use crate::models::metric::{Category, Metric};
+
+/// Tag carried by every entry of a workout session.
+pub const WORKOUT_TAG: &str = "workout";
+/// Prefix of the tag naming the workout kind, e.g. `workout:run`.
+pub const KIND_PREFIX: &str = "workout:";
+/// Prefix of the tag linking a session's entries, e.g. `session:<uuid>`.
+pub const SESSION_PREFIX: &str = "session:";
+pub const DURATION_TYPE: &str = "cardio";
+pub const INTENSITY_TYPE: &str = "perceived_exertion";
+/// Unit of `<kind>_distance` entries.
+pub const DISTANCE_UNIT: &str = "km";
+
+/// Metric type holding the distance of a workout kind, e.g. `run_distance`.
+pub fn distance_type(kind: &str) -> String {
+    format!("{}_distance", kind)
+}
+
+/// What was done in one workout, as given on the command line.
+pub struct WorkoutInput<'a> {
+    pub kind: &'a str,
+    pub duration: f64,
+    pub distance: Option<f64>,
+    pub intensity: Option<f64>,
+    pub note: Option<&'a str>,
+    pub tags: Option<&'a str>,
+    pub date: Option<NaiveDate>,
+}
+
+/// One workout reassembled from its linked entries. Duration is in minutes,
+/// distance in km.
+#[derive(Debug, Serialize)]
+pub struct WorkoutSession {
+    pub session: String,
+    pub kind: String,
+    pub timestamp: DateTime<Utc>,
+    pub date: NaiveDate,
+    pub duration: Option<f64>,
+    pub distance: Option<f64>,
+    pub intensity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub entry_ids: Vec<String>,
+}
+
+/// Sessions of one week (starting Monday) with their total time and distance.
+#[derive(Debug, Serialize)]
+pub struct WorkoutWeek {
+    pub week_start: NaiveDate,
+    pub sessions: u32,
+    pub total_duration: f64,
+    pub total_distance: f64,
+}
+
+/// Log a workout as linked entries sharing one timestamp: duration as
+/// `cardio`, distance as `<kind>_distance` and intensity as
+/// `perceived_exertion`. Every entry carries [`WORKOUT_TAG`], the kind tag,
+/// a session tag unique to this workout, and `tags`.
+pub fn log_workout(db: &Database, config: &Config, input: &WorkoutInput) -> Result<Vec<Metric>> {
+    let kind = input.kind.trim().to_lowercase();
+    if kind.is_empty()
+        || !kind
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        crate::fail!(
+            validation,
+            "invalid workout kind: '{}' (use letters, digits, '_' or '-')",
+            input.kind
+        );
+    }
+    if !input.duration.is_finite() || input.duration <= 0.0 {
+        crate::fail!(
+            validation,
+            "--duration must be a positive number of minutes"
+        );
+    }
+    if let Some(d) = input.distance
+        && (!d.is_finite() || d < 0.0)
+    {
+        crate::fail!(validation, "--distance must be a non-negative number");
+    }
+    if let Some(i) = input.intensity
+        && !(1.0..=10.0).contains(&i)
+    {
+        crate::fail!(validation, "--intensity must be between 1 and 10");
+    }
+
+    let timestamp = match input.date {
+        Some(d) => time::local_noon_utc(d, db.timezone()),
+        None => Utc::now(),
+    };
+    let mut tag_list = vec![
+        WORKOUT_TAG.to_string(),
+        format!("{}{}", KIND_PREFIX, kind),
+        format!("{}{}", SESSION_PREFIX, Uuid::new_v4()),
+    ];
+    if let Some(t) = input.tags {
+        for tag in t.split(',').map(|s| s.trim().to_string()) {
+            if !tag.is_empty() && !tag_list.contains(&tag) {
+                tag_list.push(tag);
+            }
+        }
+    }
+
+    let mut values = vec![(DURATION_TYPE.to_string(), input.duration)];
+    if let Some(d) = input.distance {
+        values.push((distance_type(&kind), d));
+    }
+    if let Some(i) = input.intensity {
+        values.push((INTENSITY_TYPE.to_string(), i));
+    }
+    let mut metrics: Vec<Metric> = values
+        .into_iter()
+        .map(|(metric_type, value)| {
+            let value = crate::core::units::from_input(value, &metric_type, &config.units);
+            let mut m = Metric::new(metric_type, value);
+            m.timestamp = timestamp;
+            m.tags = tag_list.clone();
+            m.note = input.note.map(String::from);
+            m
+        })
+        .collect();
+
+    db.in_transaction(|db| {
+        for m in &mut metrics {
+            crate::core::types::apply(db, m)?;
+            // Distances have no built-in type; a custom definition wins.
+            if m.category == Category::Custom {
+                m.category = Category::Exercise;
+            }
+            if m.unit.is_empty() {
+                m.unit = DISTANCE_UNIT.to_string();
+            }
+            db.insert_metric(m)?;
+        }
+        Ok(())
+    })?;
+    Ok(metrics)
+}
+
+/// Workout sessions between `from` and `to`, newest first, limited to the
+/// `last` most recent.
+pub fn list(
+    db: &Database,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    last: Option<usize>,
+) -> Result<Vec<WorkoutSession>> {
+    let entries = db.query_tagged(None, from, to, &[WORKOUT_TAG.to_string()])?;
+    let mut found = sessions(&entries, db.timezone());
+    found.reverse();
+    if let Some(n) = last {
+        found.truncate(n);
+    }
+    Ok(found)
+}
+
+/// Group entries carrying a session tag into sessions, oldest first. Entries
+/// without one are ignored.
+pub fn sessions(entries: &[Metric], tz: Tz) -> Vec<WorkoutSession> {
+    let mut by_session: BTreeMap<&str, WorkoutSession> = BTreeMap::new();
+    for e in entries {
+        let Some(id) = e.tags.iter().find_map(|t| t.strip_prefix(SESSION_PREFIX)) else {
+            continue;
+        };
+        let s = by_session.entry(id).or_insert_with(|| WorkoutSession {
+            session: id.to_string(),
+            kind: String::new(),
+            timestamp: e.timestamp,
+            date: time::local_date(&e.timestamp, tz),
+            duration: None,
+            distance: None,
+            intensity: None,
+            note: None,
+            entry_ids: Vec::new(),
+        });
+        if s.kind.is_empty()
+            && let Some(kind) = e.tags.iter().find_map(|t| t.strip_prefix(KIND_PREFIX))
+        {
+            s.kind = kind.to_string();
+        }
+        match e.metric_type.as_str() {
+            DURATION_TYPE => s.duration = Some(e.value),
+            INTENSITY_TYPE => s.intensity = Some(e.value),
+            t if t.ends_with("_distance") => s.distance = Some(e.value),
+            _ => {}
+        }
+        if s.note.is_none() {
+            s.note = e.note.clone();
+        }
+        s.entry_ids.push(e.id.clone());
+    }
+    let mut out: Vec<WorkoutSession> = by_session.into_values().collect();
+    out.sort_by_key(|s| s.timestamp);
+    out
+}
+
+/// Per-week totals of `sessions` for weeks starting on `first`, oldest week first.
+pub fn weekly(sessions: &[WorkoutSession], first: Weekday) -> Vec<WorkoutWeek> {
+    let mut weeks: BTreeMap<NaiveDate, WorkoutWeek> = BTreeMap::new();
+    for s in sessions {
+        let week_start = crate::core::time::week_start_for(s.date, first);
+        let w = weeks.entry(week_start).or_insert(WorkoutWeek {
+            week_start,
+            sessions: 0,
+            total_duration: 0.0,
+            total_distance: 0.0,
+        });
+        w.sessions += 1;
+        w.total_duration += s.duration.unwrap_or(0.0);
+        w.total_distance += s.distance.unwrap_or(0.0);
+    }
+    weeks.into_values().collect()
+}
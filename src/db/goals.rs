@@ -9,8 +9,8 @@ use super::Database;
 impl Database {
     pub fn insert_goal(&self, g: &Goal) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO goals (id, metric_type, target_value, direction, timeframe, active, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO goals (id, metric_type, target_value, direction, timeframe, active, created_at, start_value)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 g.id,
                 g.metric_type,
@@ -19,6 +19,7 @@ impl Database {
                 g.timeframe.to_string(),
                 g.active,
                 g.created_at.to_rfc3339(),
+                g.start_value,
             ],
         )?;
         Ok(())
@@ -26,10 +27,10 @@ impl Database {
 
     pub fn list_goals(&self, active_only: bool) -> Result<Vec<Goal>> {
         let sql = if active_only {
-            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at
+            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at, start_value
              FROM goals WHERE active = 1 ORDER BY created_at"
         } else {
-            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at
+            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at, start_value
              FROM goals ORDER BY created_at"
         };
         let mut stmt = self.conn.prepare(sql)?;
@@ -42,6 +43,7 @@ impl Database {
                 timeframe: row.get(4)?,
                 active: row.get(5)?,
                 created_at: row.get(6)?,
+                start_value: row.get(7)?,
             })
         })?;
 
@@ -55,7 +57,7 @@ impl Database {
 
     pub fn get_goal(&self, id: &str) -> Result<Option<Goal>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at
+            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at, start_value
              FROM goals WHERE id = ?1",
         )?;
         let mut rows = stmt.query_map(params![id], |row| {
@@ -67,6 +69,7 @@ impl Database {
                 timeframe: row.get(4)?,
                 active: row.get(5)?,
                 created_at: row.get(6)?,
+                start_value: row.get(7)?,
             })
         })?;
         match rows.next() {
@@ -77,7 +80,7 @@ impl Database {
 
     pub fn get_goal_by_type(&self, metric_type: &str) -> Result<Option<Goal>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at
+            "SELECT id, metric_type, target_value, direction, timeframe, active, created_at, start_value
              FROM goals WHERE metric_type = ?1 AND active = 1 LIMIT 1",
         )?;
         let mut rows = stmt.query_map(params![metric_type], |row| {
@@ -89,6 +92,7 @@ impl Database {
                 timeframe: row.get(4)?,
                 active: row.get(5)?,
                 created_at: row.get(6)?,
+                start_value: row.get(7)?,
             })
         })?;
         match rows.next() {
@@ -112,6 +116,25 @@ impl Database {
         )?;
         Ok(count > 0)
     }
+
+    /// Number of goals (active or not) on `metric_type`.
+    pub fn count_goals_of_type(&self, metric_type: &str) -> Result<u64> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM goals WHERE metric_type = ?1",
+            params![metric_type],
+            |r| r.get(0),
+        )?;
+        Ok(n as u64)
+    }
+
+    /// Point every goal on `old` at `new`. Returns the number of goals changed.
+    pub fn rename_goal_type(&self, old: &str, new: &str) -> Result<u64> {
+        let n = self.conn.execute(
+            "UPDATE goals SET metric_type = ?2 WHERE metric_type = ?1",
+            params![old, new],
+        )?;
+        Ok(n as u64)
+    }
 }
 
 struct GoalRow {
@@ -122,6 +145,7 @@ struct GoalRow {
     timeframe: String,
     active: bool,
     created_at: String,
+    start_value: Option<f64>,
 }
 
 fn row_to_goal(r: GoalRow) -> Result<Goal> {
@@ -137,5 +161,6 @@ fn row_to_goal(r: GoalRow) -> Result<Goal> {
         timeframe,
         active: r.active,
         created_at,
+        start_value: r.start_value,
     })
 }
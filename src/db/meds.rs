@@ -3,7 +3,9 @@ use chrono::{DateTime, Utc};
 use rusqlite::params;
 use std::str::FromStr;
 
-use crate::models::med::{Frequency, Medication, Route};
+use crate::models::med::{
+    Frequency, Interaction, Medication, MedicationChanges, Refill, Route, parse_dose,
+};
 
 use super::Database;
 
@@ -21,6 +23,9 @@ struct MedicationRow {
     stop_reason: Option<String>,
     note: Option<String>,
     created_at: String,
+    refill_quantity: Option<u32>,
+    refill_date: Option<String>,
+    interactions: Option<String>,
 }
 
 fn row_to_medication(r: MedicationRow) -> Result<Medication> {
@@ -34,6 +39,14 @@ fn row_to_medication(r: MedicationRow) -> Result<Medication> {
     };
     let created_at: DateTime<Utc> =
         DateTime::parse_from_rfc3339(&r.created_at)?.with_timezone(&Utc);
+    let refill_date: Option<DateTime<Utc>> = match r.refill_date {
+        Some(ref s) => Some(DateTime::parse_from_rfc3339(s)?.with_timezone(&Utc)),
+        None => None,
+    };
+    let interactions = match r.interactions {
+        Some(ref s) => serde_json::from_str(s)?,
+        None => Vec::new(),
+    };
 
     Ok(Medication {
         id: r.id,
@@ -49,10 +62,16 @@ fn row_to_medication(r: MedicationRow) -> Result<Medication> {
         stop_reason: r.stop_reason,
         note: r.note,
         created_at,
+        refill_quantity: r.refill_quantity,
+        refill_date,
+        interactions,
     })
 }
 
-const SELECT_COLS: &str = "id, name, dose, dose_value, dose_unit, route, frequency, active, started_at, stopped_at, stop_reason, note, created_at";
+const SELECT_COLS: &str = "id, name, dose, dose_value, dose_unit, route, frequency, active, started_at, stopped_at, stop_reason, note, created_at,
+    (SELECT quantity FROM medications_refills r WHERE r.med_id = medications.id ORDER BY r.refill_date DESC, r.id DESC LIMIT 1),
+    (SELECT refill_date FROM medications_refills r WHERE r.med_id = medications.id ORDER BY r.refill_date DESC, r.id DESC LIMIT 1),
+    interactions";
 
 macro_rules! map_row {
     ($row:expr) => {
@@ -70,15 +89,27 @@ macro_rules! map_row {
             stop_reason: $row.get(10)?,
             note: $row.get(11)?,
             created_at: $row.get(12)?,
+            refill_quantity: $row.get(13)?,
+            refill_date: $row.get(14)?,
+            interactions: $row.get(15)?,
         })
     };
 }
 
+/// Stored as NULL when there are none.
+fn interactions_json(interactions: &[Interaction]) -> Result<Option<String>> {
+    if interactions.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::to_string(interactions)?))
+    }
+}
+
 impl Database {
     pub fn insert_medication(&self, med: &Medication) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO medications (id, name, dose, dose_value, dose_unit, route, frequency, active, started_at, stopped_at, stop_reason, note, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            "INSERT INTO medications (id, name, dose, dose_value, dose_unit, route, frequency, active, started_at, stopped_at, stop_reason, note, created_at, interactions)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
             params![
                 med.id,
                 med.name,
@@ -93,11 +124,21 @@ impl Database {
                 med.stop_reason,
                 med.note,
                 med.created_at.to_rfc3339(),
+                interactions_json(&med.interactions)?,
             ],
         )?;
         Ok(())
     }
 
+    /// Whether a medication with this id is already stored.
+    pub fn medication_id_exists(&self, id: &str) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM medications WHERE id = ?1)",
+            params![id],
+            |r| r.get(0),
+        )?)
+    }
+
     pub fn get_medication_by_name(&self, name: &str) -> Result<Option<Medication>> {
         let sql = format!("SELECT {SELECT_COLS} FROM medications WHERE name = ?1 AND active = 1");
         let mut stmt = self.conn.prepare(&sql)?;
@@ -136,6 +177,25 @@ impl Database {
         Ok(meds)
     }
 
+    /// Medications (active or stopped) whose note or stop reason contains `text`.
+    pub fn search_medications(&self, text: &str) -> Result<Vec<Medication>> {
+        let sql = format!(
+            "SELECT {SELECT_COLS} FROM medications
+             WHERE note LIKE ?1 ESCAPE '\\' OR stop_reason LIKE ?1 ESCAPE '\\'
+             ORDER BY name ASC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![super::metrics::like_pattern(text)], |row| {
+            map_row!(row)
+        })?;
+
+        let mut meds = Vec::new();
+        for row in rows {
+            meds.push(row_to_medication(row?)?);
+        }
+        Ok(meds)
+    }
+
     pub fn stop_medication(
         &self,
         name: &str,
@@ -150,10 +210,118 @@ impl Database {
         Ok(count > 0)
     }
 
+    /// Update the active medication `name`, setting only the fields given in
+    /// `changes`; a new dose also re-derives `dose_value`/`dose_unit`.
+    pub fn update_medication(&self, name: &str, changes: &MedicationChanges) -> Result<bool> {
+        let parsed = changes.dose.as_deref().map(|d| parse_dose(Some(d)));
+        let count = self.conn.execute(
+            "UPDATE medications SET
+                dose = COALESCE(?1, dose),
+                dose_value = CASE WHEN ?1 IS NULL THEN dose_value ELSE ?2 END,
+                dose_unit = CASE WHEN ?1 IS NULL THEN dose_unit ELSE ?3 END,
+                frequency = COALESCE(?4, frequency),
+                route = COALESCE(?5, route),
+                note = COALESCE(?6, note)
+             WHERE name = ?7 AND active = 1",
+            params![
+                changes.dose,
+                parsed.as_ref().and_then(|p| p.value),
+                parsed.map(|p| p.unit),
+                changes.frequency.as_ref().map(|f| f.to_string()),
+                changes.route.as_ref().map(|r| r.to_string()),
+                changes.note,
+                name,
+            ],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Rename every record of medication `old` (stopped ones included), its
+    /// intake and skip entries, and interactions other medications declare
+    /// with it. Returns the number of entries moved.
+    pub fn rename_medication(&self, old: &str, new: &str) -> Result<usize> {
+        self.in_transaction(|db| {
+            db.conn.execute(
+                "UPDATE medications SET name = ?2 WHERE name = ?1",
+                params![old, new],
+            )?;
+            let moved = db.conn.execute(
+                "UPDATE metrics SET type = ?2
+                 WHERE type = ?1 AND (category = 'medication' OR source IN ('med_take', 'med_skip'))",
+                params![old, new],
+            )?;
+            for med in db.list_medications(true)? {
+                if !med.interactions.iter().any(|i| i.with == old) {
+                    continue;
+                }
+                let mut interactions = med.interactions;
+                for i in interactions.iter_mut().filter(|i| i.with == old) {
+                    i.with = new.to_string();
+                }
+                db.conn.execute(
+                    "UPDATE medications SET interactions = ?1 WHERE id = ?2",
+                    params![interactions_json(&interactions)?, med.id],
+                )?;
+            }
+            Ok(moved)
+        })
+    }
+
     pub fn remove_medication(&self, name: &str) -> Result<bool> {
+        self.conn.execute(
+            "DELETE FROM medications_refills
+             WHERE med_id IN (SELECT id FROM medications WHERE name = ?1)",
+            params![name],
+        )?;
         let count = self
             .conn
             .execute("DELETE FROM medications WHERE name = ?1", params![name])?;
         Ok(count > 0)
     }
+
+    pub fn insert_refill(&self, refill: &Refill) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO medications_refills (med_id, quantity, days_supply, refill_date, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                refill.med_id,
+                refill.quantity,
+                refill.days_supply,
+                refill.refill_date.to_rfc3339(),
+                refill.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The most recent refill for a medication, by refill date.
+    pub fn latest_refill(&self, med_id: &str) -> Result<Option<Refill>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT med_id, quantity, days_supply, refill_date, created_at
+             FROM medications_refills WHERE med_id = ?1
+             ORDER BY refill_date DESC, id DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query_map(params![med_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, u32>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        match rows.next() {
+            Some(row) => {
+                let (med_id, quantity, days_supply, refill_date, created_at) = row?;
+                Ok(Some(Refill {
+                    med_id,
+                    quantity,
+                    days_supply,
+                    refill_date: DateTime::parse_from_rfc3339(&refill_date)?.with_timezone(&Utc),
+                    created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+                }))
+            }
+            None => Ok(None),
+        }
+    }
 }
@@ -2,9 +2,9 @@ use anyhow::Result;
 use chrono::{DateTime, NaiveDate, Utc};
 use rusqlite::params;
 
-use crate::models::metric::{Category, Metric};
+use crate::models::metric::{Category, Metric, normalize_tag};
 
-use super::Database;
+use super::{Database, SortOrder};
 
 struct MetricRow {
     id: String,
@@ -47,6 +47,29 @@ fn row_to_metric(r: MetricRow) -> Result<Metric> {
     })
 }
 
+/// `%text%` for LIKE, with `%`, `_` and `\` in `text` escaped by a backslash.
+pub(crate) fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn metric_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<MetricRow> {
+    Ok(MetricRow {
+        id: row.get(0)?,
+        timestamp: row.get(1)?,
+        category: row.get(2)?,
+        metric_type: row.get(3)?,
+        value: row.get(4)?,
+        unit: row.get(5)?,
+        note: row.get(6)?,
+        tags: row.get(7)?,
+        source: row.get(8)?,
+    })
+}
+
 impl Database {
     pub fn insert_metric(&self, m: &Metric) -> Result<()> {
         let tags_json = if m.tags.is_empty() {
@@ -54,10 +77,13 @@ impl Database {
         } else {
             Some(serde_json::to_string(&m.tags)?)
         };
-        self.conn.execute(
-            "INSERT INTO metrics (id, timestamp, category, type, value, unit, note, tags, source)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            params![
+        // Cached statements keep bulk imports from re-preparing per row.
+        self.conn
+            .prepare_cached(
+                "INSERT INTO metrics (id, timestamp, category, type, value, unit, note, tags, source)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?
+            .execute(params![
                 m.id,
                 m.timestamp.to_rfc3339(),
                 m.category.to_string(),
@@ -67,16 +93,145 @@ impl Database {
                 m.note,
                 tags_json,
                 m.source,
-            ],
-        )?;
+            ])?;
+        for tag in &m.tags {
+            let tag = normalize_tag(tag);
+            if !tag.is_empty() {
+                self.conn
+                    .prepare_cached(
+                        "INSERT OR IGNORE INTO metric_tags (metric_id, tag) VALUES (?1, ?2)",
+                    )?
+                    .execute(params![m.id, tag])?;
+            }
+        }
         Ok(())
     }
 
-    pub fn query_by_type(&self, metric_type: &str, limit: Option<u32>) -> Result<Vec<Metric>> {
-        let mut stmt = self.conn.prepare(
+    /// Whether an entry with this id is already stored.
+    pub fn metric_id_exists(&self, id: &str) -> Result<bool> {
+        Ok(self
+            .conn
+            .prepare_cached("SELECT EXISTS(SELECT 1 FROM metrics WHERE id = ?1)")?
+            .query_row(params![id], |r| r.get(0))?)
+    }
+
+    pub fn get_metric(&self, id: &str) -> Result<Option<Metric>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT id, timestamp, category, type, value, unit, note, tags, source
+             FROM metrics WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], metric_row)?;
+        rows.next().transpose()?.map(row_to_metric).transpose()
+    }
+
+    /// The stored entry of `metric_type` closest to `timestamp`, if one lies
+    /// within `tolerance` of it.
+    pub fn find_metric_near(
+        &self,
+        metric_type: &str,
+        timestamp: DateTime<Utc>,
+        tolerance: chrono::Duration,
+    ) -> Result<Option<Metric>> {
+        self.find_metric_near_upto(metric_type, timestamp, tolerance, i64::MAX)
+    }
+
+    /// A mark for [`Database::find_metric_near_upto`]: entries inserted after
+    /// this call lie past it.
+    pub fn metric_mark(&self) -> Result<i64> {
+        Ok(self
+            .conn
+            .prepare_cached("SELECT COALESCE(MAX(rowid), 0) FROM metrics")?
+            .query_row([], |r| r.get(0))?)
+    }
+
+    /// Like [`Database::find_metric_near`], but only among entries stored at
+    /// or before `mark`.
+    pub fn find_metric_near_upto(
+        &self,
+        metric_type: &str,
+        timestamp: DateTime<Utc>,
+        tolerance: chrono::Duration,
+        mark: i64,
+    ) -> Result<Option<Metric>> {
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, timestamp, category, type, value, unit, note, tags, source
-             FROM metrics WHERE type = ?1 ORDER BY timestamp DESC LIMIT ?2",
+             FROM metrics WHERE type = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+               AND rowid <= ?5
+             ORDER BY ABS(julianday(timestamp) - julianday(?4)) LIMIT 1",
         )?;
+        let mut rows = stmt.query_map(
+            params![
+                metric_type,
+                (timestamp - tolerance).to_rfc3339(),
+                (timestamp + tolerance).to_rfc3339(),
+                timestamp.to_rfc3339(),
+                mark,
+            ],
+            metric_row,
+        )?;
+        rows.next().transpose()?.map(row_to_metric).transpose()
+    }
+
+    /// The other half of the blood pressure reading the entry `id` belongs to:
+    /// the closest `bp_diastolic` entry within `window_seconds` of a
+    /// `bp_systolic` one, or vice versa. `None` for other types.
+    pub fn find_paired_bp_entry(&self, id: &str, window_seconds: i64) -> Result<Option<Metric>> {
+        let Some(m) = self.get_metric(id)? else {
+            return Ok(None);
+        };
+        let partner = match m.metric_type.as_str() {
+            "bp_systolic" => "bp_diastolic",
+            "bp_diastolic" => "bp_systolic",
+            _ => return Ok(None),
+        };
+        self.find_metric_near(
+            partner,
+            m.timestamp,
+            chrono::Duration::seconds(window_seconds),
+        )
+    }
+
+    /// Replace the stored entry with `m.id` (and its tags) by `m`.
+    pub fn replace_metric(&self, m: &Metric) -> Result<()> {
+        self.in_transaction(|db| {
+            db.conn
+                .prepare_cached("DELETE FROM metric_tags WHERE metric_id = ?1")?
+                .execute(params![m.id])?;
+            db.conn
+                .prepare_cached("DELETE FROM metrics WHERE id = ?1")?
+                .execute(params![m.id])?;
+            db.insert_metric(m)
+        })
+    }
+
+    /// Whether an entry with the same type, timestamp, value and source is
+    /// already stored (an indexed lookup on `type, timestamp`).
+    pub fn metric_duplicate_exists(&self, m: &Metric) -> Result<bool> {
+        Ok(self
+            .conn
+            .prepare_cached(
+                "SELECT EXISTS(SELECT 1 FROM metrics
+                 WHERE type = ?1 AND timestamp = ?2 AND value = ?3 AND source = ?4)",
+            )?
+            .query_row(
+                params![m.metric_type, m.timestamp.to_rfc3339(), m.value, m.source],
+                |r| r.get(0),
+            )?)
+    }
+
+    /// The newest `limit` entries of a type (default 1), returned in `order`.
+    pub fn query_by_type(
+        &self,
+        metric_type: &str,
+        limit: Option<u32>,
+        order: SortOrder,
+    ) -> Result<Vec<Metric>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, timestamp, category, type, value, unit, note, tags, source
+             FROM (SELECT * FROM metrics WHERE type = ?1 ORDER BY timestamp DESC LIMIT ?2)
+             ORDER BY timestamp {}",
+            order.sql()
+        ))?;
         let limit = limit.unwrap_or(1) as i64;
         let rows = stmt.query_map(params![metric_type, limit], |row| {
             Ok(MetricRow {
@@ -129,24 +284,28 @@ impl Database {
         Ok(metrics)
     }
 
-    pub fn query_by_date(&self, date: NaiveDate) -> Result<Vec<Metric>> {
-        let start = format!("{}T00:00:00", date);
-        let end = format!("{}T23:59:59", date);
-        self.query_by_range_str(&start, &end)
+    /// Query metrics for a single local calendar day.
+    pub fn query_by_date(&self, date: NaiveDate, order: SortOrder) -> Result<Vec<Metric>> {
+        self.query_by_date_range(date, date, order)
     }
 
-    /// Query metrics within a date range (inclusive).
-    pub fn query_by_date_range(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<Metric>> {
-        let start = format!("{}T00:00:00", from);
-        let end = format!("{}T23:59:59", to);
-        self.query_by_range_str(&start, &end)
+    /// Query metrics within a local date range (inclusive).
+    pub fn query_by_date_range(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        order: SortOrder,
+    ) -> Result<Vec<Metric>> {
+        let (start, end) = self.day_bounds(from, to);
+        self.query_by_range_str(&start, &end, order)
     }
 
-    fn query_by_range_str(&self, start: &str, end: &str) -> Result<Vec<Metric>> {
-        let mut stmt = self.conn.prepare(
+    fn query_by_range_str(&self, start: &str, end: &str, order: SortOrder) -> Result<Vec<Metric>> {
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT id, timestamp, category, type, value, unit, note, tags, source
-             FROM metrics WHERE timestamp >= ?1 AND timestamp <= ?2 ORDER BY timestamp",
-        )?;
+             FROM metrics WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp {}",
+            order.sql()
+        ))?;
         let rows = stmt.query_map(params![start, end], |row| {
             Ok(MetricRow {
                 id: row.get(0)?,
@@ -176,15 +335,15 @@ impl Database {
         from: Option<NaiveDate>,
         to: Option<NaiveDate>,
     ) -> Result<Vec<Metric>> {
-        let from_str = from.map(|d| format!("{}T00:00:00", d)).unwrap_or_default();
+        let from_str = from.map(|d| self.day_bounds(d, d).0).unwrap_or_default();
         let to_str = to
-            .map(|d| format!("{}T23:59:59", d))
+            .map(|d| self.day_bounds(d, d).1)
             .unwrap_or_else(|| "9999-12-31T23:59:59".to_string());
 
         let sql = if let Some(t) = metric_type {
             let mut stmt = self.conn.prepare(
                 "SELECT id, timestamp, category, type, value, unit, note, tags, source
-                 FROM metrics WHERE type = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+                 FROM metrics WHERE type = ?1 AND timestamp >= ?2 AND timestamp < ?3
                  ORDER BY timestamp ASC",
             )?;
             let rows = stmt.query_map(params![t, from_str, to_str], |row| {
@@ -207,7 +366,7 @@ impl Database {
             return Ok(metrics);
         } else {
             "SELECT id, timestamp, category, type, value, unit, note, tags, source
-             FROM metrics WHERE timestamp >= ?1 AND timestamp <= ?2
+             FROM metrics WHERE timestamp >= ?1 AND timestamp < ?2
              ORDER BY timestamp ASC"
         };
 
@@ -233,18 +392,136 @@ impl Database {
         Ok(metrics)
     }
 
-    /// Get distinct dates that have any entries, within a range, ordered descending.
+    /// Like [`query_all`](Self::query_all), keeping only entries that carry
+    /// every tag in `tags` (case-insensitive). No tags means no filtering.
+    pub fn query_tagged(
+        &self,
+        metric_type: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        tags: &[String],
+    ) -> Result<Vec<Metric>> {
+        let mut wanted: Vec<String> = tags
+            .iter()
+            .map(|t| normalize_tag(t))
+            .filter(|t| !t.is_empty())
+            .collect();
+        wanted.sort();
+        wanted.dedup();
+        if wanted.is_empty() {
+            return self.query_all(metric_type, from, to);
+        }
+
+        let from_str = from.map(|d| self.day_bounds(d, d).0).unwrap_or_default();
+        let to_str = to
+            .map(|d| self.day_bounds(d, d).1)
+            .unwrap_or_else(|| "9999-12-31T23:59:59".to_string());
+        let placeholders = (0..wanted.len())
+            .map(|i| format!("?{}", i + 4))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT id, timestamp, category, type, value, unit, note, tags, source
+             FROM metrics
+             WHERE (?1 IS NULL OR type = ?1) AND timestamp >= ?2 AND timestamp < ?3
+               AND id IN (SELECT metric_id FROM metric_tags WHERE tag IN ({})
+                          GROUP BY metric_id HAVING COUNT(*) = {})
+             ORDER BY timestamp ASC",
+            placeholders,
+            wanted.len()
+        );
+
+        let mut values: Vec<rusqlite::types::Value> = vec![
+            metric_type.map(str::to_string).into(),
+            from_str.into(),
+            to_str.into(),
+        ];
+        values.extend(wanted.into_iter().map(rusqlite::types::Value::from));
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            Ok(MetricRow {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                category: row.get(2)?,
+                metric_type: row.get(3)?,
+                value: row.get(4)?,
+                unit: row.get(5)?,
+                note: row.get(6)?,
+                tags: row.get(7)?,
+                source: row.get(8)?,
+            })
+        })?;
+
+        let mut metrics = Vec::new();
+        for row in rows {
+            metrics.push(row_to_metric(row?)?);
+        }
+        Ok(metrics)
+    }
+
+    /// Entries whose note contains `text` (ASCII case-insensitive), newest first,
+    /// optionally filtered by type and date range.
+    pub fn search_notes(
+        &self,
+        text: &str,
+        metric_type: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Metric>> {
+        let from_str = from.map(|d| self.day_bounds(d, d).0).unwrap_or_default();
+        let to_str = to
+            .map(|d| self.day_bounds(d, d).1)
+            .unwrap_or_else(|| "9999-12-31T23:59:59".to_string());
+        let limit = limit.map(i64::from).unwrap_or(-1);
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, category, type, value, unit, note, tags, source
+             FROM metrics
+             WHERE note LIKE ?1 ESCAPE '\\' AND (?2 IS NULL OR type = ?2)
+               AND timestamp >= ?3 AND timestamp < ?4
+             ORDER BY timestamp DESC LIMIT ?5",
+        )?;
+        let rows = stmt.query_map(
+            params![like_pattern(text), metric_type, from_str, to_str, limit],
+            |row| {
+                Ok(MetricRow {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    category: row.get(2)?,
+                    metric_type: row.get(3)?,
+                    value: row.get(4)?,
+                    unit: row.get(5)?,
+                    note: row.get(6)?,
+                    tags: row.get(7)?,
+                    source: row.get(8)?,
+                })
+            },
+        )?;
+
+        let mut metrics = Vec::new();
+        for row in rows {
+            metrics.push(row_to_metric(row?)?);
+        }
+        Ok(metrics)
+    }
+
+    /// Get distinct local dates that have any entries, within a range, ordered descending.
     pub fn distinct_entry_dates(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<String>> {
-        let start = format!("{}T00:00:00", from);
-        let end = format!("{}T23:59:59", to);
+        let (start, end) = self.day_bounds(from, to);
         let mut stmt = self.conn.prepare(
-            "SELECT DISTINCT date(timestamp) as d FROM metrics
-             WHERE timestamp >= ?1 AND timestamp <= ?2 ORDER BY d DESC",
+            "SELECT timestamp FROM metrics
+             WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp DESC",
         )?;
         let rows = stmt.query_map(params![start, end], |row| row.get::<_, String>(0))?;
-        let mut dates = Vec::new();
+        let mut dates: Vec<String> = Vec::new();
         for row in rows {
-            dates.push(row?);
+            let ts: DateTime<Utc> = DateTime::parse_from_rfc3339(&row?)?.with_timezone(&Utc);
+            let d = self.local_date(&ts).to_string();
+            if dates.last() != Some(&d) {
+                dates.push(d);
+            }
         }
         Ok(dates)
     }
@@ -261,4 +538,35 @@ impl Database {
         }
         Ok(types)
     }
+
+    /// Move every entry of `old` to `new` in a single UPDATE. Entries stored
+    /// without a unit take `unit` (if non-empty), and `category`, if given,
+    /// replaces the stored one. Returns the number of rows changed.
+    pub fn rename_metric_type(
+        &self,
+        old: &str,
+        new: &str,
+        unit: &str,
+        category: Option<&Category>,
+    ) -> Result<u64> {
+        let n = self.conn.execute(
+            "UPDATE metrics
+             SET type = ?2,
+                 unit = CASE WHEN unit = '' THEN ?3 ELSE unit END,
+                 category = COALESCE(?4, category)
+             WHERE type = ?1",
+            params![old, new, unit, category.map(|c| c.to_string())],
+        )?;
+        Ok(n as u64)
+    }
+
+    /// Number of entries of `metric_type` stored without a unit.
+    pub fn count_unitless_metrics(&self, metric_type: &str) -> Result<u64> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM metrics WHERE type = ?1 AND unit = ''",
+            params![metric_type],
+            |r| r.get(0),
+        )?;
+        Ok(n as u64)
+    }
 }
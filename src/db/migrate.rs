@@ -1,7 +1,11 @@
 use anyhow::Result;
 use rusqlite::Connection;
 
+/// Schema version recorded in `PRAGMA user_version`.
+pub const SCHEMA_VERSION: i64 = 6;
+
 pub fn run(conn: &Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS metrics (
             id         TEXT PRIMARY KEY,
@@ -24,7 +28,8 @@ pub fn run(conn: &Connection) -> Result<()> {
             direction    TEXT NOT NULL,
             timeframe    TEXT NOT NULL,
             active       INTEGER NOT NULL DEFAULT 1,
-            created_at   TEXT NOT NULL
+            created_at   TEXT NOT NULL,
+            start_value  REAL
         );
         CREATE INDEX IF NOT EXISTS idx_goals_type ON goals(metric_type, active);
 
@@ -41,11 +46,63 @@ pub fn run(conn: &Connection) -> Result<()> {
             stopped_at  TEXT,
             stop_reason TEXT,
             note        TEXT,
-            created_at  TEXT NOT NULL
+            created_at  TEXT NOT NULL,
+            interactions TEXT
         );
         CREATE UNIQUE INDEX IF NOT EXISTS idx_medications_name_active
             ON medications(name) WHERE active = 1;
-        CREATE INDEX IF NOT EXISTS idx_medications_active ON medications(active);",
+        CREATE INDEX IF NOT EXISTS idx_medications_active ON medications(active);
+
+        CREATE TABLE IF NOT EXISTS medications_refills (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            med_id      TEXT NOT NULL,
+            quantity    INTEGER NOT NULL,
+            days_supply INTEGER NOT NULL,
+            refill_date TEXT NOT NULL,
+            created_at  TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_medications_refills_med
+            ON medications_refills(med_id, refill_date);
+
+        CREATE TABLE IF NOT EXISTS metric_tags (
+            metric_id TEXT NOT NULL,
+            tag       TEXT NOT NULL,
+            PRIMARY KEY (metric_id, tag)
+        );
+        CREATE INDEX IF NOT EXISTS idx_metric_tags_tag ON metric_tags(tag);
+
+        CREATE TABLE IF NOT EXISTS custom_metric_types (
+            name     TEXT PRIMARY KEY,
+            unit     TEXT NOT NULL DEFAULT '',
+            category TEXT NOT NULL DEFAULT 'custom'
+        );",
+    )?;
+    let has_start_value: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('goals') WHERE name = 'start_value'",
+        [],
+        |r| r.get(0),
+    )?;
+    if has_start_value == 0 {
+        conn.execute_batch("ALTER TABLE goals ADD COLUMN start_value REAL")?;
+    }
+    let has_interactions: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('medications') WHERE name = 'interactions'",
+        [],
+        |r| r.get(0),
     )?;
+    if has_interactions == 0 {
+        // JSON array of {with, min_gap_minutes}
+        conn.execute_batch("ALTER TABLE medications ADD COLUMN interactions TEXT")?;
+    }
+    if version < 3 {
+        // Tags used to live only in metrics.tags (a JSON array); index them.
+        conn.execute_batch(
+            "INSERT OR IGNORE INTO metric_tags (metric_id, tag)
+             SELECT m.id, lower(trim(j.value))
+             FROM metrics m, json_each(m.tags) j
+             WHERE m.tags IS NOT NULL AND trim(j.value) != ''",
+        )?;
+    }
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
     Ok(())
 }
@@ -2,17 +2,101 @@ mod goals;
 pub mod meds;
 mod metrics;
 mod migrate;
+mod pragmas;
+pub mod stats;
+pub mod types;
+
+pub use migrate::SCHEMA_VERSION;
 
 use anyhow::Result;
-use rusqlite::Connection;
+use chrono::Weekday;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
 use std::path::Path;
 
+use crate::core::time;
+use crate::models::config::{Config, JournalMode};
+
+/// Timestamp order of query results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Newest first.
+    #[default]
+    Desc,
+    /// Oldest first.
+    Asc,
+}
+
+impl SortOrder {
+    pub(crate) fn sql(self) -> &'static str {
+        match self {
+            Self::Desc => "DESC",
+            Self::Asc => "ASC",
+        }
+    }
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Desc => write!(f, "desc"),
+            Self::Asc => write!(f, "asc"),
+        }
+    }
+}
+
 pub struct Database {
     pub(crate) conn: Connection,
+    pub(crate) tz: Tz,
+    /// First day of the week for weekly windows (`week_start` config).
+    week_start: Weekday,
+    /// SQLCipher passphrase the connection was keyed with, if encrypted.
+    key: Option<String>,
 }
 
 impl Database {
+    /// Open the database, treating calendar days as UTC days.
     pub fn open(path: &Path) -> Result<Self> {
+        Self::open_with_timezone(path, "UTC")
+    }
+
+    /// Open the database, treating calendar days as days in the given IANA timezone.
+    pub fn open_with_timezone(path: &Path, timezone: &str) -> Result<Self> {
+        Self::open_encrypted(path, timezone, None)
+    }
+
+    /// Open the database with the configured timezone and, if encryption is
+    /// enabled, the configured passphrase.
+    pub fn open_with_config(path: &Path, config: &Config) -> Result<Self> {
+        let key = config.encryption.passphrase()?;
+        let mut db = Self::open_keyed(
+            path,
+            &config.timezone(),
+            key.as_deref(),
+            config.database.journal_mode,
+        )?;
+        if config.database.auto_vacuum {
+            db.enable_incremental_vacuum()?;
+        }
+        db.week_start = config.week_start.weekday();
+        Ok(db)
+    }
+
+    /// Open the database, keying it with `key` (SQLCipher) before any other access.
+    pub fn open_encrypted(path: &Path, timezone: &str, key: Option<&str>) -> Result<Self> {
+        Self::open_keyed(path, timezone, key, JournalMode::default())
+    }
+
+    fn open_keyed(
+        path: &Path,
+        timezone: &str,
+        key: Option<&str>,
+        journal_mode: JournalMode,
+    ) -> Result<Self> {
+        let tz = time::parse_timezone(timezone)?;
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
             #[cfg(unix)]
@@ -23,6 +107,35 @@ impl Database {
         }
 
         let conn = Connection::open(path)?;
+        if let Some(k) = key {
+            conn.pragma_update(None, "key", k)?;
+        }
+        if conn
+            .query_row("SELECT count(*) FROM sqlite_master", [], |r| {
+                r.get::<_, i64>(0)
+            })
+            .is_err()
+        {
+            match key {
+                Some(_) => crate::fail!(
+                    db,
+                    "cannot decrypt database {}: wrong key, or the database is not encrypted (run `openvital encrypt`)",
+                    path.display()
+                ),
+                None if is_encrypted_file(path) => crate::fail!(
+                    db,
+                    "passphrase required: {} is encrypted; set encryption.enabled and its key source (default: $OPENVITAL_DB_KEY)",
+                    path.display()
+                ),
+                None => crate::fail!(
+                    db,
+                    "cannot read database {}: if it is encrypted, set encryption.enabled and its key source",
+                    path.display()
+                ),
+            }
+        }
+
+        pragmas::configure(&conn, journal_mode)?;
 
         #[cfg(unix)]
         {
@@ -33,8 +146,160 @@ impl Database {
                 std::fs::set_permissions(path, perms)?;
             }
         }
-        let db = Self { conn };
+        let db = Self {
+            conn,
+            tz,
+            week_start: Weekday::Mon,
+            key: key.map(str::to_string),
+        };
         migrate::run(&db.conn)?;
         Ok(db)
     }
+
+    /// The timezone used to map stored UTC timestamps onto calendar days.
+    pub fn timezone(&self) -> Tz {
+        self.tz
+    }
+
+    /// The first day of the week weekly windows start on.
+    pub fn week_start(&self) -> Weekday {
+        self.week_start
+    }
+
+    /// Run `f` inside a single transaction; changes are rolled back if it returns an error.
+    /// Nested calls join the enclosing transaction.
+    pub fn in_transaction<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        if !self.conn.is_autocommit() {
+            return f(self);
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        let out = f(self)?;
+        tx.commit()?;
+        Ok(out)
+    }
+
+    /// Whether the connection is keyed with a SQLCipher passphrase.
+    pub fn is_encrypted(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// The schema version recorded in `PRAGMA user_version`.
+    pub fn schema_version(&self) -> Result<i64> {
+        Ok(self
+            .conn
+            .query_row("PRAGMA user_version", [], |r| r.get(0))?)
+    }
+
+    /// Copy the live database to `dest` using SQLite's online backup API.
+    /// An encrypted database produces a backup encrypted with the same key.
+    pub fn backup_to(&self, dest: &Path) -> Result<()> {
+        let mut out = Connection::open(dest)?;
+        if let Some(k) = &self.key {
+            out.pragma_update(None, "key", k)?;
+        }
+        let backup = rusqlite::backup::Backup::new(&self.conn, &mut out)?;
+        backup.run_to_completion(64, std::time::Duration::from_millis(10), None)?;
+        Ok(())
+    }
+
+    /// Write an encrypted copy of this (unencrypted) database to `dest`,
+    /// using SQLCipher's `ATTACH ... KEY` + `sqlcipher_export`.
+    pub fn export_encrypted(&self, dest: &Path, key: &str) -> Result<()> {
+        if self.key.is_some() {
+            crate::fail!(conflict, "database is already encrypted");
+        }
+        self.export_keyed(dest, key)
+    }
+
+    /// Write a plaintext copy of this (encrypted) database to `dest`.
+    pub fn export_decrypted(&self, dest: &Path) -> Result<()> {
+        if self.key.is_none() {
+            crate::fail!(conflict, "database is not encrypted");
+        }
+        // An empty key attaches an unencrypted database.
+        self.export_keyed(dest, "")
+    }
+
+    fn export_keyed(&self, dest: &Path, key: &str) -> Result<()> {
+        let version: i64 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        self.conn.execute(
+            "ATTACH DATABASE ?1 AS exported KEY ?2",
+            rusqlite::params![dest.to_string_lossy(), key],
+        )?;
+        let exported = self
+            .conn
+            .query_row("SELECT sqlcipher_export('exported')", [], |_| Ok(()))
+            .and_then(|_| {
+                self.conn.pragma_update(
+                    Some(rusqlite::DatabaseName::Attached("exported")),
+                    "user_version",
+                    version,
+                )
+            });
+        self.conn.execute("DETACH DATABASE exported", [])?;
+        exported?;
+        Ok(())
+    }
+
+    /// Check that `path` is an intact OpenVital database this build can open,
+    /// returning its schema version. Pass `key` for an encrypted file.
+    pub fn verify_file(path: &Path, key: Option<&str>) -> Result<i64> {
+        let conn =
+            Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|_| {
+                crate::error::validation(format!("not a SQLite database: {}", path.display()))
+            })?;
+        if let Some(k) = key {
+            conn.pragma_update(None, "key", k)?;
+        }
+        let check: String = conn
+            .query_row("PRAGMA quick_check", [], |r| r.get(0))
+            .map_err(|_| {
+                crate::error::validation(format!("not a SQLite database: {}", path.display()))
+            })?;
+        if check != "ok" {
+            crate::fail!(db, "database failed integrity check: {}", check);
+        }
+        let has_metrics: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'metrics')",
+            [],
+            |r| r.get(0),
+        )?;
+        if !has_metrics {
+            crate::fail!(validation, "not an OpenVital database: {}", path.display());
+        }
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version > SCHEMA_VERSION {
+            crate::fail!(
+                validation,
+                "database schema version {} is newer than supported version {}",
+                version,
+                SCHEMA_VERSION
+            );
+        }
+        Ok(version)
+    }
+
+    /// UTC bounds `[start, end)` covering the local days `from..=to`, as RFC3339 strings.
+    pub(crate) fn day_bounds(&self, from: NaiveDate, to: NaiveDate) -> (String, String) {
+        let start = time::day_start_utc(from, self.tz);
+        let end = time::day_start_utc(to + chrono::Duration::days(1), self.tz);
+        (start.to_rfc3339(), end.to_rfc3339())
+    }
+
+    /// The local calendar date of a stored timestamp.
+    pub(crate) fn local_date(&self, ts: &DateTime<Utc>) -> NaiveDate {
+        time::local_date(ts, self.tz)
+    }
+}
+
+/// Whether `path` holds data that is not a plaintext SQLite file, i.e. a
+/// SQLCipher database (whose header is encrypted too).
+fn is_encrypted_file(path: &Path) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|_| &header != b"SQLite format 3\0")
 }
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use rusqlite::Connection;
+
+use crate::models::config::JournalMode;
+
+/// How long a connection waits on another writer before giving up.
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Per-connection settings, applied after every open (and after keying).
+pub(crate) fn configure(conn: &Connection, journal_mode: JournalMode) -> Result<()> {
+    // Let concurrent invocations (e.g. a shell alias and a cron import)
+    // wait for each other instead of failing with "database is locked".
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // `journal_mode` reports the resulting mode as a row.
+    conn.query_row(
+        &format!("PRAGMA journal_mode = {}", journal_mode),
+        [],
+        |_| Ok(()),
+    )?;
+    // NORMAL is only crash-safe with a WAL; rollback journals keep FULL.
+    let synchronous = match journal_mode {
+        JournalMode::Wal => "NORMAL",
+        JournalMode::Delete | JournalMode::Truncate => "FULL",
+    };
+    conn.pragma_update(None, "synchronous", synchronous)?;
+    conn.pragma_update(None, "foreign_keys", "ON")?;
+    conn.pragma_update(None, "temp_store", "MEMORY")?;
+    Ok(())
+}
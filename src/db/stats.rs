@@ -0,0 +1,173 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::Database;
+
+/// `PRAGMA auto_vacuum` value for INCREMENTAL mode.
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+#[derive(Debug, Serialize)]
+pub struct TableCount {
+    pub table: String,
+    pub rows: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypeCount {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub count: u64,
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+}
+
+/// On-disk size from SQLite's page accounting.
+#[derive(Debug, Serialize)]
+pub struct FileSize {
+    pub size_bytes: u64,
+    /// Space in free pages, reclaimable by `VACUUM`.
+    pub free_bytes: u64,
+}
+
+fn parse_ts(s: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(s)?.with_timezone(&Utc))
+}
+
+impl Database {
+    /// Row counts for every table, in one `UNION ALL` query.
+    pub fn table_counts(&self) -> Result<Vec<TableCount>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?;
+        let tables = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if tables.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Table names come from sqlite_master, quoted as identifiers.
+        let sql = tables
+            .iter()
+            .map(|t| {
+                let ident = t.replace('"', "\"\"");
+                format!(
+                    "SELECT '{}', COUNT(*) FROM \"{}\"",
+                    t.replace('\'', "''"),
+                    ident
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" UNION ALL ");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(TableCount {
+                table: row.get(0)?,
+                rows: row.get::<_, i64>(1)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Entry count and first/last timestamp per metric type, most entries first.
+    pub fn metric_type_counts(&self) -> Result<Vec<TypeCount>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT type, COUNT(*), MIN(timestamp), MAX(timestamp)
+             FROM metrics GROUP BY type ORDER BY COUNT(*) DESC, type ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut counts = Vec::new();
+        for row in rows {
+            let (metric_type, count, earliest, latest) = row?;
+            counts.push(TypeCount {
+                metric_type,
+                count: count as u64,
+                earliest: parse_ts(&earliest)?,
+                latest: parse_ts(&latest)?,
+            });
+        }
+        Ok(counts)
+    }
+
+    /// Database size and reclaimable space, in bytes.
+    pub fn file_size(&self) -> Result<FileSize> {
+        let page_size = self.page_size()? as u64;
+        let free_pages: i64 = self
+            .conn
+            .query_row("PRAGMA freelist_count", [], |r| r.get(0))?;
+        Ok(FileSize {
+            size_bytes: self.page_count()? as u64 * page_size,
+            free_bytes: free_pages as u64 * page_size,
+        })
+    }
+
+    /// Pages in the database file (`PRAGMA page_count`).
+    pub fn page_count(&self) -> Result<u32> {
+        Ok(self.conn.query_row("PRAGMA page_count", [], |r| r.get(0))?)
+    }
+
+    /// Bytes per page (`PRAGMA page_size`).
+    pub fn page_size(&self) -> Result<u32> {
+        Ok(self.conn.query_row("PRAGMA page_size", [], |r| r.get(0))?)
+    }
+
+    /// Problems reported by `PRAGMA integrity_check`; empty when the database is intact.
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows.into_iter().filter(|r| r != "ok").collect())
+    }
+
+    /// Rebuild the database file, reclaiming free pages. Returns the bytes freed.
+    pub fn vacuum(&self) -> Result<u64> {
+        let before = self.file_size()?.size_bytes;
+        self.conn.execute_batch("VACUUM")?;
+        Ok(before.saturating_sub(self.file_size()?.size_bytes))
+    }
+
+    /// Switch to `auto_vacuum = INCREMENTAL` (rebuilding the file once if it
+    /// used another mode), then release any free pages.
+    pub fn enable_incremental_vacuum(&self) -> Result<()> {
+        if !self.incremental_vacuum_enabled()? {
+            self.conn
+                .execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+        }
+        self.conn.execute_batch("PRAGMA incremental_vacuum")?;
+        Ok(())
+    }
+
+    /// Whether `auto_vacuum` is in incremental mode.
+    pub fn incremental_vacuum_enabled(&self) -> Result<bool> {
+        let mode: i64 = self
+            .conn
+            .query_row("PRAGMA auto_vacuum", [], |r| r.get(0))?;
+        Ok(mode == AUTO_VACUUM_INCREMENTAL)
+    }
+
+    /// The connection's journal mode, e.g. "wal" or "delete".
+    pub fn journal_mode(&self) -> Result<String> {
+        Ok(self
+            .conn
+            .query_row("PRAGMA journal_mode", [], |r| r.get(0))?)
+    }
+
+    /// Whether foreign key constraints are enforced on this connection.
+    pub fn foreign_keys_enabled(&self) -> Result<bool> {
+        let on: i64 = self
+            .conn
+            .query_row("PRAGMA foreign_keys", [], |r| r.get(0))?;
+        Ok(on == 1)
+    }
+}
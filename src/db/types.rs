@@ -0,0 +1,138 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::params;
+
+use crate::models::metric::{Category, CustomMetricType};
+
+use super::Database;
+
+/// Logged-entry summary for one metric type.
+#[derive(Debug, Clone)]
+pub struct TypeUsage {
+    pub metric_type: String,
+    pub category: Category,
+    pub count: u64,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    pub min: f64,
+    pub max: f64,
+    /// Units the entries were stored in with their counts, most used first.
+    pub units: Vec<(String, u64)>,
+}
+
+fn row_to_type(row: &rusqlite::Row<'_>) -> rusqlite::Result<(String, String, String)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+}
+
+fn to_custom_type((name, unit, category): (String, String, String)) -> Result<CustomMetricType> {
+    Ok(CustomMetricType {
+        name,
+        unit,
+        category: category.parse()?,
+    })
+}
+
+impl Database {
+    pub fn insert_custom_type(&self, t: &CustomMetricType) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO custom_metric_types (name, unit, category) VALUES (?1, ?2, ?3)",
+            params![t.name, t.unit, t.category.to_string()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_custom_type(&self, name: &str) -> Result<Option<CustomMetricType>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT name, unit, category FROM custom_metric_types WHERE name = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![name], row_to_type)?;
+        rows.next().transpose()?.map(to_custom_type).transpose()
+    }
+
+    pub fn list_custom_types(&self) -> Result<Vec<CustomMetricType>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, unit, category FROM custom_metric_types ORDER BY name")?;
+        let rows = stmt.query_map([], row_to_type)?;
+        let mut types = Vec::new();
+        for row in rows {
+            types.push(to_custom_type(row?)?);
+        }
+        Ok(types)
+    }
+
+    /// Delete a custom type definition. Returns whether it existed.
+    pub fn remove_custom_type(&self, name: &str) -> Result<bool> {
+        let n = self.conn.execute(
+            "DELETE FROM custom_metric_types WHERE name = ?1",
+            params![name],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// Number of logged entries of `metric_type`.
+    pub fn count_metrics_of_type(&self, metric_type: &str) -> Result<u64> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM metrics WHERE type = ?1",
+            params![metric_type],
+            |r| r.get(0),
+        )?;
+        Ok(n as u64)
+    }
+
+    /// Per-type entry counts, date range, value range and stored units, by type name.
+    pub fn type_usage(&self) -> Result<Vec<TypeUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT type, MAX(category), COUNT(*), MIN(timestamp), MAX(timestamp),
+                    MIN(value), MAX(value)
+             FROM metrics GROUP BY type ORDER BY type",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, f64>(5)?,
+                    row.get::<_, f64>(6)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT type, unit, COUNT(*) FROM metrics
+             GROUP BY type, unit ORDER BY type, COUNT(*) DESC, unit",
+        )?;
+        let unit_rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut usage = Vec::with_capacity(rows.len());
+        for (metric_type, category, count, first, last, min, max) in rows {
+            let units = unit_rows
+                .iter()
+                .filter(|(t, _, _)| *t == metric_type)
+                .map(|(_, u, n)| (u.clone(), *n as u64))
+                .collect();
+            usage.push(TypeUsage {
+                category: category.parse().unwrap_or(Category::Custom),
+                count: count as u64,
+                first: DateTime::parse_from_rfc3339(&first)?.with_timezone(&Utc),
+                last: DateTime::parse_from_rfc3339(&last)?.with_timezone(&Utc),
+                min,
+                max,
+                units,
+                metric_type,
+            });
+        }
+        Ok(usage)
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+/// Failure category, reported as `error.code` in the JSON envelope and as the
+/// process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    NotFound,
+    Validation,
+    Parse,
+    Io,
+    Db,
+    Conflict,
+    General,
+}
+
+impl ErrorKind {
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Validation => "validation_error",
+            ErrorKind::Parse => "parse_error",
+            ErrorKind::Io => "io_error",
+            ErrorKind::Db => "db_error",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::General => "general_error",
+        }
+    }
+
+    /// 2 usage/validation (as clap uses), 3 not found, 4 I/O or database,
+    /// 5 conflict, 1 anything else.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Validation | ErrorKind::Parse => 2,
+            ErrorKind::NotFound => 3,
+            ErrorKind::Io | ErrorKind::Db => 4,
+            ErrorKind::Conflict => 5,
+            ErrorKind::General => 1,
+        }
+    }
+}
+
+/// Like `anyhow::bail!`, with a category naming one of the constructors in
+/// this module: `fail!(not_found, "goal not found: {}", id)`.
+#[macro_export]
+macro_rules! fail {
+    ($kind:ident, $($arg:tt)+) => {
+        return Err($crate::error::$kind(format!($($arg)+)))
+    };
+}
+
+/// An error with a known category. Build one with the helpers below and
+/// return it through `anyhow`; [`kind_of`] recovers the category.
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn new(kind: ErrorKind, message: impl Into<String>) -> anyhow::Error {
+    Error {
+        kind,
+        message: message.into(),
+    }
+    .into()
+}
+
+pub fn not_found(message: impl Into<String>) -> anyhow::Error {
+    new(ErrorKind::NotFound, message)
+}
+
+pub fn validation(message: impl Into<String>) -> anyhow::Error {
+    new(ErrorKind::Validation, message)
+}
+
+pub fn parse(message: impl Into<String>) -> anyhow::Error {
+    new(ErrorKind::Parse, message)
+}
+
+pub fn db(message: impl Into<String>) -> anyhow::Error {
+    new(ErrorKind::Db, message)
+}
+
+pub fn conflict(message: impl Into<String>) -> anyhow::Error {
+    new(ErrorKind::Conflict, message)
+}
+
+/// An I/O failure with `message`; a missing file is `not_found`.
+pub fn io(err: &std::io::Error, message: impl Into<String>) -> anyhow::Error {
+    let kind = if err.kind() == std::io::ErrorKind::NotFound {
+        ErrorKind::NotFound
+    } else {
+        ErrorKind::Io
+    };
+    new(kind, message)
+}
+
+/// Category of `err`: an explicit [`Error`] anywhere in the chain wins, then
+/// well-known library errors; everything else is `general_error`.
+pub fn kind_of(err: &anyhow::Error) -> ErrorKind {
+    if let Some(e) = err.chain().find_map(|e| e.downcast_ref::<Error>()) {
+        return e.kind;
+    }
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<rusqlite::Error>() {
+            return match e.sqlite_error_code() {
+                Some(rusqlite::ErrorCode::ConstraintViolation) => ErrorKind::Conflict,
+                _ => ErrorKind::Db,
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+            return if e.kind() == std::io::ErrorKind::NotFound {
+                ErrorKind::NotFound
+            } else {
+                ErrorKind::Io
+            };
+        }
+        if cause.is::<serde_json::Error>()
+            || cause.is::<toml::de::Error>()
+            || cause.is::<quick_xml::Error>()
+            || cause.is::<chrono::ParseError>()
+            || cause.is::<std::num::ParseFloatError>()
+            || cause.is::<std::num::ParseIntError>()
+        {
+            return ErrorKind::Parse;
+        }
+    }
+    ErrorKind::General
+}
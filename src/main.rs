@@ -1,13 +1,24 @@
 mod cli;
 mod cmd;
 
-use anyhow::anyhow;
 use clap::Parser;
-use cli::{Cli, Commands, ConfigAction, GoalAction, MedAction};
+use cli::{
+    Cli, Commands, ConfigAction, CycleAction, DbAction, GoalAction, MedAction, NoteAction,
+    ProfileAction, TypesAction, WorkoutAction,
+};
+use openvital::error::validation;
+use openvital::models::config::Config;
+use openvital::output::color::ColorConfig;
 use std::process;
 
 fn main() {
-    let cli = Cli::parse();
+    let args: Vec<String> = std::env::args().collect();
+    Config::set_profile(cli::profile_from_args(&args).as_deref());
+    let cli = Cli::parse_from(&args);
+    if let Err(e) = select_profile(cli.profile.as_deref(), &cli.command) {
+        exit_with(e);
+    }
+    let color = ColorConfig::new(&cli.color);
 
     let result = match cli.command {
         Commands::Init { skip, units } => cmd::init::run(skip, units.as_deref()),
@@ -17,44 +28,139 @@ fn main() {
             note,
             tags,
             source,
+            unit,
+            interactive,
+            fuzzy,
+            confirm,
+            no_goal_check,
+            expression,
             batch,
+            file,
         } => {
             if let Some(batch_json) = batch {
-                cmd::log::run_batch(&batch_json, cli.human)
+                cmd::log::run_batch(&batch_json, !no_goal_check, cli.human)
+            } else if let Some(path) = file {
+                cmd::log::run_file(&path, !no_goal_check, cli.human)
             } else {
-                let t = r#type.as_deref().expect("type is required");
-                let v = value.as_deref().expect("value is required");
-                cmd::log::run(
-                    t,
-                    v,
-                    note.as_deref(),
-                    tags.as_deref(),
-                    source.as_deref(),
-                    cli.date,
-                    cli.human,
-                )
+                let args = cmd::log::LogArgs {
+                    metric_type: r#type.as_deref().expect("type is required"),
+                    value: value.as_deref(),
+                    expression: expression.as_deref(),
+                    note: note.as_deref(),
+                    tags: tags.as_deref(),
+                    source: source.as_deref(),
+                    date: cli.date,
+                    unit_override: unit.as_deref(),
+                    interactive,
+                    fuzzy,
+                    confirm,
+                    goal_check: !no_goal_check,
+                };
+                cmd::log::run(args, cli.human)
             }
         }
+        Commands::Measure { pairs, note, tags } => cmd::measure::run(
+            &pairs,
+            note.as_deref(),
+            tags.as_deref(),
+            cli.date,
+            cli.human,
+        ),
+        Commands::Sleep {
+            bed,
+            wake,
+            quality,
+            note,
+            tags,
+        } => cmd::sleep::run(
+            openvital::core::sleep::SleepInput {
+                bed: &bed,
+                wake: &wake,
+                quality,
+                note: note.as_deref(),
+                tags: tags.as_deref(),
+                date: cli.date,
+            },
+            cli.human,
+        ),
         Commands::Show {
             r#type,
+            types,
             last,
-            from: _,
-            to: _,
-        } => cmd::show::run(r#type.as_deref(), last, cli.date, cli.human),
+            from,
+            to,
+            tags,
+            stats,
+            reverse,
+            format,
+        } => {
+            let args = cmd::show::ShowArgs {
+                last,
+                date: cli.date,
+                from,
+                to,
+                tags: &tags,
+                with_stats: stats,
+                reverse,
+                format: &format,
+            };
+            if let Some(types) = types {
+                cmd::show::run_types(&types, args, cli.human)
+            } else {
+                cmd::show::run(r#type.as_deref(), args, cli.human)
+            }
+        }
+        Commands::Search {
+            query,
+            r#type,
+            from,
+            to,
+            limit,
+        } => cmd::search::run(&query, r#type.as_deref(), from, to, limit, cli.human),
+        Commands::Stats {
+            r#type,
+            last,
+            from,
+            to,
+        } => cmd::stats::run(r#type.as_deref(), last, from, to, cli.human),
         Commands::Trend {
             r#type,
             period,
             last,
+            by,
             correlate,
+            correlate_all,
+            lag,
+            scan_lag,
+            smooth,
+            agg,
+            fill,
         } => {
-            if let Some(corr) = correlate {
-                cmd::trend::run_correlate(&corr, last, cli.human)
+            if correlate_all {
+                cmd::trend::run_correlate_all(last, cli.human)
+            } else if let Some(corr) = correlate {
+                cmd::trend::run_correlate(&corr, last, lag, scan_lag, fill.as_deref(), cli.human)
+            } else if by.is_some() {
+                let t = r#type.as_deref().expect("type is required");
+                cmd::trend::run_by_weekday(t, last, agg.as_deref(), cli.human)
             } else {
                 let t = r#type.as_deref().expect("type is required");
-                cmd::trend::run(t, period.as_deref(), last, cli.human)
+                cmd::trend::run(
+                    t,
+                    period.as_deref(),
+                    last,
+                    smooth,
+                    agg.as_deref(),
+                    fill.as_deref(),
+                    cli.human,
+                )
             }
         }
-        Commands::Status => cmd::status::run(cli.human),
+        Commands::Status { watch } => match watch {
+            Some(secs) => cmd::status::run_watch(secs, cli.date, cli.human, &color),
+            None => cmd::status::run(cli.date, cli.human, &color),
+        },
+        Commands::Remind { quiet } => cmd::remind::run(quiet, cli.human),
         Commands::Goal { action } => match action {
             GoalAction::Set {
                 r#type,
@@ -70,15 +176,19 @@ fn main() {
                 timeframe.or(timeframe_pos),
             ) {
                 (Some(t), Some(d), Some(tf)) => cmd::goal::run_set(&r#type, t, &d, &tf, cli.human),
-                (None, _, _) => Err(anyhow!("target is required (use positional or --target)")),
-                (_, None, _) => Err(anyhow!(
-                    "direction is required (use positional or --direction)"
+                (None, _, _) => Err(validation(
+                    "target is required (use positional or --target)",
                 )),
-                (_, _, None) => Err(anyhow!(
-                    "timeframe is required (use positional or --timeframe)"
+                (_, None, _) => Err(validation(
+                    "direction is required (use positional or --direction)",
+                )),
+                (_, _, None) => Err(validation(
+                    "timeframe is required (use positional or --timeframe)",
                 )),
             },
-            GoalAction::Status { r#type } => cmd::goal::run_status(r#type.as_deref(), cli.human),
+            GoalAction::Status { r#type } => {
+                cmd::goal::run_status(r#type.as_deref(), cli.human, &color)
+            }
             GoalAction::Remove { goal_id } => cmd::goal::run_remove(&goal_id, cli.human),
         },
         Commands::Config { action } => match action {
@@ -88,9 +198,33 @@ fn main() {
         Commands::Report {
             period,
             month,
+            year,
             from,
             to,
-        } => cmd::report::run(period.as_deref(), month.as_deref(), from, to, cli.human),
+            agg,
+            compare,
+            goals,
+            format,
+            output,
+            tags,
+            digest,
+        } => cmd::report::run(
+            cmd::report::ReportArgs {
+                period: period.as_deref(),
+                month: month.as_deref(),
+                year,
+                from,
+                to,
+                agg: agg.as_deref(),
+                compare: compare.is_some(),
+                goals,
+                format: &format,
+                output: output.as_deref(),
+                tags: &tags,
+                digest,
+            },
+            cli.human,
+        ),
         Commands::Export {
             format,
             output,
@@ -98,16 +232,133 @@ fn main() {
             from,
             to,
             with_medications,
+            tags,
+            since_last,
+            compress,
         } => cmd::export::run_export(
-            &format,
-            output.as_deref(),
-            r#type.as_deref(),
+            cmd::export::ExportArgs {
+                format: &format,
+                output: output.as_deref(),
+                metric_type: r#type.as_deref(),
+                from,
+                to,
+                with_medications,
+                tags: &tags,
+                since_last,
+                compress,
+            },
+            cli.human,
+        ),
+        Commands::Import {
+            source,
+            file,
+            chunk_size,
+            dedupe,
+            merge,
+        } => cmd::export::run_import(
+            &source,
+            &file,
+            openvital::core::export::ImportOptions {
+                chunk_size,
+                dedupe,
+                merge: merge.into(),
+            },
+            cli.human,
+        ),
+        Commands::Backup { output, compress } => {
+            cmd::backup::run_backup(output.as_deref(), compress, cli.human)
+        }
+        Commands::Restore { path, file, yes } => {
+            let file = path.or(file).unwrap_or_default();
+            cmd::backup::run_restore(&file, yes, cli.human)
+        }
+        Commands::Sync {
+            download,
+            status,
+            yes,
+        } => cmd::backup::run_sync(download, status, yes, cli.human),
+        Commands::Encrypt { key_source } => {
+            cmd::backup::run_encrypt(key_source.as_deref(), cli.human)
+        }
+        Commands::Db { action } => match action {
+            DbAction::Stats => cmd::db::run_stats(cli.human),
+            DbAction::Check => cmd::db::run_check(cli.human),
+            DbAction::Compact => cmd::db::run_compact(cli.human),
+            DbAction::Decrypt => cmd::db::run_decrypt(cli.human),
+        },
+        Commands::Types { action } => match action {
+            TypesAction::List { r#type } => cmd::types::run_list(r#type.as_deref(), cli.human),
+            TypesAction::Add {
+                name,
+                unit,
+                category,
+            } => cmd::types::run_add(&name, &unit, &category, cli.human),
+            TypesAction::Remove { name, force } => cmd::types::run_remove(&name, force, cli.human),
+        },
+        Commands::MigrateType {
             from,
             to,
-            with_medications,
+            dry_run,
+            yes,
+            goals,
+            aliases,
+        } => cmd::types::run_migrate(
+            &from,
+            &to,
+            openvital::core::types::MigrateOptions {
+                dry_run,
+                goals,
+                aliases,
+            },
+            yes,
             cli.human,
         ),
-        Commands::Import { source, file } => cmd::export::run_import(&source, &file, cli.human),
+        Commands::Note { action } => match action {
+            NoteAction::Add { text, tags } => {
+                cmd::note::run_add(&text, tags.as_deref(), cli.date, cli.human)
+            }
+            NoteAction::Show {
+                last,
+                from,
+                to,
+                tags,
+            } => cmd::note::run_show(last, from, to, &tags, cli.human),
+        },
+        Commands::Workout {
+            action: Some(WorkoutAction::List { last, from, to }),
+            ..
+        } => cmd::workout::run_list(last, from, to, cli.human),
+        Commands::Workout {
+            action: None,
+            kind,
+            duration,
+            distance,
+            intensity,
+            note,
+            tags,
+        } => cmd::workout::run(
+            openvital::core::workout::WorkoutInput {
+                kind: kind.as_deref().expect("kind is required"),
+                duration: duration.expect("duration is required"),
+                distance,
+                intensity,
+                note: note.as_deref(),
+                tags: tags.as_deref(),
+                date: cli.date,
+            },
+            cli.human,
+        ),
+        Commands::Profile { action } => match action {
+            ProfileAction::List => cmd::profile::run_list(cli.human),
+            ProfileAction::Create { name, default } => {
+                cmd::profile::run_create(&name, default, cli.human)
+            }
+            ProfileAction::Default { name } => cmd::profile::run_default(&name, cli.human),
+            ProfileAction::Remove { name, yes } => cmd::profile::run_remove(&name, yes, cli.human),
+        },
+        Commands::Cycle { action } => match action {
+            CycleAction::Status => cmd::cycle::run_status(cli.human),
+        },
         Commands::Med { action } => match action {
             MedAction::Add {
                 name,
@@ -116,29 +367,71 @@ fn main() {
                 route,
                 note,
                 started,
+                interacts_with,
             } => cmd::med::run_add(
-                &name,
-                dose.as_deref(),
-                &freq,
-                &route,
-                note.as_deref(),
-                started,
+                openvital::core::med::AddMedicationParams {
+                    name: &name,
+                    dose: dose.as_deref(),
+                    freq: &freq,
+                    route: Some(&route),
+                    note: note.as_deref(),
+                    started,
+                    interacts_with: &interacts_with,
+                },
                 cli.human,
             ),
+            MedAction::Take {
+                name,
+                skip: true,
+                reason,
+                ..
+            } => cmd::med::run_skip(&name, reason.as_deref(), cli.date, cli.human),
             MedAction::Take {
                 name,
                 dose,
                 note,
                 tags,
+                quantity,
+                indication,
+                ..
             } => cmd::med::run_take(
-                &name,
-                dose.as_deref(),
-                note.as_deref(),
-                tags.as_deref(),
-                cli.date,
+                openvital::core::med::TakeMedicationParams {
+                    name: &name,
+                    dose: dose.as_deref(),
+                    note: note.as_deref(),
+                    tags: tags.as_deref(),
+                    date: cli.date,
+                    quantity: quantity.unwrap_or(1),
+                    indication: indication.as_deref(),
+                },
+                cli.human,
+                &color,
+            ),
+            MedAction::Refill {
+                name,
+                quantity,
+                days_supply,
+            } => cmd::med::run_refill(&name, quantity, days_supply, cli.date, cli.human),
+            MedAction::List { all } => cmd::med::run_list(all, cli.human, &color),
+            MedAction::Edit {
+                name,
+                new_dose,
+                new_freq,
+                new_route,
+                new_note,
+                rename,
+                yes: _,
+            } => cmd::med::run_edit(
+                openvital::core::med::EditMedicationParams {
+                    name: &name,
+                    dose: new_dose.as_deref(),
+                    freq: new_freq.as_deref(),
+                    route: new_route.as_deref(),
+                    note: new_note.as_deref(),
+                    rename: rename.as_deref(),
+                },
                 cli.human,
             ),
-            MedAction::List { all } => cmd::med::run_list(all, cli.human),
             MedAction::Stop { name, reason } => {
                 cmd::med::run_stop(&name, reason.as_deref(), cli.date, cli.human)
             }
@@ -146,22 +439,86 @@ fn main() {
             MedAction::Status { name, last } => {
                 cmd::med::run_status(name.as_deref(), last, cli.human)
             }
+            MedAction::History { name, last } => cmd::med::run_history(&name, last, cli.human),
+            MedAction::Schedule => cmd::med::run_schedule(cli.human),
         },
         Commands::Anomaly {
             r#type,
+            all,
+            include_meds,
             days,
             threshold,
-        } => cmd::anomaly::run(r#type.as_deref(), days, &threshold, cli.human),
-        Commands::Context { days, types } => cmd::context::run(days, types.as_deref(), cli.human),
-        Commands::Completions { shell } => {
-            cli::print_completions(shell);
-            Ok(())
+            method,
+            window,
+        } => cmd::anomaly::run(
+            cmd::anomaly::AnomalyArgs {
+                metric_type: r#type.as_deref(),
+                all,
+                include_meds,
+                days,
+                threshold: &threshold,
+                method: &method,
+                window,
+            },
+            cli.human,
+        ),
+        Commands::WeightHistory { last, from, to } => {
+            cmd::weight_history::run(last, from, to, cli.human)
+        }
+        Commands::Insights { days } => cmd::insights::run(days, cli.human),
+        Commands::Context {
+            days,
+            types,
+            sections,
+            narrative,
+        } => cmd::context::run(
+            days,
+            types.as_deref(),
+            sections.as_deref(),
+            narrative,
+            cli.human,
+        ),
+        Commands::Completions { shell, install } => {
+            if install {
+                cmd::completions::run_install(shell, cli.human)
+            } else {
+                cli::print_completions(shell);
+                Ok(())
+            }
         }
     };
 
     if let Err(e) = result {
-        let err = openvital::output::error("", "general_error", &e.to_string());
-        eprintln!("{}", serde_json::to_string(&err).unwrap());
-        process::exit(1);
+        exit_with(e);
     }
 }
+
+/// Route every command through the `--profile` given, which must exist unless
+/// the command creates it.
+fn select_profile(name: Option<&str>, command: &Commands) -> anyhow::Result<()> {
+    Config::set_profile(name);
+    let Some(name) = name else {
+        return Ok(());
+    };
+    openvital::core::profile::validate_name(name)?;
+    let creates = matches!(
+        command,
+        Commands::Init { .. } | Commands::Profile { .. } | Commands::Completions { .. }
+    );
+    if !creates && !openvital::core::profile::exists(name) {
+        openvital::fail!(
+            not_found,
+            "profile '{}' not found (create it with `openvital profile create {}`)",
+            name,
+            name
+        );
+    }
+    Ok(())
+}
+
+fn exit_with(e: anyhow::Error) -> ! {
+    let kind = openvital::error::kind_of(&e);
+    let err = openvital::output::error("", kind.code(), &e.to_string());
+    eprintln!("{}", serde_json::to_string(&err).unwrap());
+    process::exit(kind.exit_code());
+}
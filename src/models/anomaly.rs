@@ -2,12 +2,40 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
-    Info,
-    Warning,
-    Alert,
+    Mild,
+    Moderate,
+    Severe,
+}
+
+impl Severity {
+    /// Severity of an IQR outlier `distance` IQRs past the nearest quartile:
+    /// under 3 mild (Tukey's 1.5 fence, or closer with a strict threshold),
+    /// under 4.5 moderate, beyond that severe.
+    pub fn from_iqr_distance(distance: f64) -> Self {
+        if distance < 3.0 {
+            Self::Mild
+        } else if distance < 4.5 {
+            Self::Moderate
+        } else {
+            Self::Severe
+        }
+    }
+
+    /// Severity of a z-score outlier: |z| under 3 mild, under 4 moderate,
+    /// beyond that severe.
+    pub fn from_z(z: f64) -> Self {
+        let z = z.abs();
+        if z < 3.0 {
+            Self::Mild
+        } else if z < 4.0 {
+            Self::Moderate
+        } else {
+            Self::Severe
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -15,6 +43,9 @@ pub enum Threshold {
     Relaxed,
     Moderate,
     Strict,
+    /// An explicit multiplier: the IQR fence factor for `iqr` (1.5 = Tukey
+    /// fences, 3.0 = far outliers only), the |z| limit for `zscore`/`rolling`.
+    Custom(f64),
 }
 
 impl Threshold {
@@ -24,6 +55,56 @@ impl Threshold {
             Self::Relaxed => 2.0,
             Self::Moderate => 1.5,
             Self::Strict => 1.0,
+            Self::Custom(k) => k,
+        }
+    }
+
+    /// Z-score limit for the zscore and rolling methods.
+    pub fn z_limit(self) -> f64 {
+        match self {
+            Self::Relaxed => 3.0,
+            Self::Moderate => 2.5,
+            Self::Strict => 2.0,
+            Self::Custom(k) => k,
+        }
+    }
+}
+
+/// How the expected range for a reading is derived.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    /// Quartiles of the whole baseline window; today's readings outside Q1/Q3 ± k·IQR.
+    #[default]
+    Iqr,
+    /// Mean and standard deviation of the whole baseline window.
+    Zscore,
+    /// Each reading in the window against the mean/std of the preceding N days.
+    Rolling,
+}
+
+impl FromStr for Method {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "iqr" => Ok(Self::Iqr),
+            "zscore" => Ok(Self::Zscore),
+            "rolling" => Ok(Self::Rolling),
+            _ => crate::fail!(
+                validation,
+                "invalid method: {} (expected iqr/zscore/rolling)",
+                s
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Iqr => write!(f, "iqr"),
+            Self::Zscore => write!(f, "zscore"),
+            Self::Rolling => write!(f, "rolling"),
         }
     }
 }
@@ -35,10 +116,14 @@ impl FromStr for Threshold {
             "relaxed" => Ok(Self::Relaxed),
             "moderate" => Ok(Self::Moderate),
             "strict" => Ok(Self::Strict),
-            _ => anyhow::bail!(
-                "invalid threshold: {} (expected relaxed/moderate/strict)",
-                s
-            ),
+            _ => match s.parse::<f64>() {
+                Ok(k) if k.is_finite() && k > 0.0 => Ok(Self::Custom(k)),
+                _ => crate::fail!(
+                    validation,
+                    "invalid threshold: {} (expected relaxed/moderate/strict or a positive multiplier)",
+                    s
+                ),
+            },
         }
     }
 }
@@ -49,13 +134,18 @@ impl std::fmt::Display for Threshold {
             Self::Relaxed => write!(f, "relaxed"),
             Self::Moderate => write!(f, "moderate"),
             Self::Strict => write!(f, "strict"),
+            Self::Custom(k) => write!(f, "{}", k),
         }
     }
 }
 
+/// Named levels serialize as strings, custom multipliers as numbers.
 impl Serialize for Threshold {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        serializer.serialize_str(&self.to_string())
+        match self {
+            Self::Custom(k) => serializer.serialize_f64(*k),
+            _ => serializer.serialize_str(&self.to_string()),
+        }
     }
 }
 
@@ -65,6 +155,8 @@ pub struct Baseline {
     pub median: f64,
     pub q3: f64,
     pub iqr: f64,
+    pub mean: f64,
+    pub std_dev: f64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -76,6 +168,8 @@ pub struct Anomaly {
     pub bounds: Bounds,
     pub deviation: String,
     pub severity: Severity,
+    /// Distance beyond the expected range: IQR units for iqr, |z| for zscore/rolling.
+    pub score: f64,
     pub summary: String,
 }
 
@@ -88,13 +182,31 @@ pub struct Bounds {
 #[derive(Debug, Serialize)]
 pub struct AnomalyResult {
     pub period: AnomalyPeriod,
+    pub method: Method,
     pub threshold: Threshold,
+    /// Preceding-days window used by the rolling method.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_days: Option<u32>,
     pub anomalies: Vec<Anomaly>,
     pub scanned_types: Vec<String>,
     pub clean_types: Vec<String>,
+    /// `--all`: the anomalies grouped by type, most severe type first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub by_type: Vec<TypeAnomalies>,
+    /// `--all`: types left out because their values do not vary.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub low_variance_types: Vec<String>,
     pub summary: String,
 }
 
+/// Anomalies of one metric type, most severe first.
+#[derive(Debug, Serialize)]
+pub struct TypeAnomalies {
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub anomalies: Vec<Anomaly>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AnomalyPeriod {
     pub baseline_start: String,
@@ -1,6 +1,50 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::models::metric::{Aggregation, default_aggregation};
+use crate::models::range::{MetricRange, RangeStatus};
+
+/// Profile whose config and database live directly in the data directory.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Profile selected with `--profile` for this process, if any.
+static SELECTED_PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Top-level settings shared by all profiles (`settings.toml` in the data directory).
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// Profile used when no `--profile` is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+}
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        Config::home_dir().join("settings.toml")
+    }
+
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Self::path();
+        if path.exists() {
+            Ok(toml::from_str(&std::fs::read_to_string(&path)?)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        std::fs::create_dir_all(Config::home_dir())?;
+        std::fs::write(Self::path(), toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The configured default profile, or [`DEFAULT_PROFILE`].
+    pub fn default_profile(&self) -> &str {
+        self.default_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+}
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
@@ -12,6 +56,417 @@ pub struct Config {
     pub aliases: HashMap<String, String>,
     #[serde(default)]
     pub alerts: Alerts,
+    /// Per-type overrides of how trend buckets and report summaries combine values.
+    #[serde(default)]
+    pub aggregation: HashMap<String, Aggregation>,
+    /// First day of the week for weekly goals, trends, adherence and workouts.
+    #[serde(default)]
+    pub week_start: WeekStart,
+    /// At-rest encryption of the database (SQLCipher).
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    #[serde(default)]
+    pub streaks: StreaksConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub reminders: RemindersConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub cycle: CycleConfig,
+    #[serde(default)]
+    pub glucose: GlucoseConfig,
+    #[serde(default)]
+    pub log: LogConfig,
+    #[serde(default)]
+    pub trend: TrendConfig,
+    #[serde(default)]
+    pub medications: MedicationsConfig,
+    /// Off-device copy of the database (`openvital sync`).
+    #[serde(default)]
+    pub sync: SyncConfig,
+    /// Healthy bands per metric type (`config set range.sleep_hours 7-9`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub ranges: HashMap<String, MetricRange>,
+}
+
+/// The system's IANA timezone: `TZ`, then the `/etc/localtime` link, then
+/// `/etc/timezone`. Without a named zone (as on Windows) it is the fixed
+/// zone of the current local offset, see [`offset_timezone`].
+pub fn system_timezone() -> String {
+    let from_link = || {
+        let target = std::fs::read_link("/etc/localtime").ok()?;
+        let target = target.to_string_lossy();
+        let (_, name) = target.split_once("zoneinfo/")?;
+        Some(name.to_string())
+    };
+    let candidates = [
+        std::env::var("TZ")
+            .ok()
+            .map(|tz| tz.trim_start_matches(':').to_string()),
+        from_link(),
+        std::fs::read_to_string("/etc/timezone").ok(),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .map(|name| name.trim().to_string())
+        .find(|name| name.parse::<chrono_tz::Tz>().is_ok())
+        .unwrap_or_else(|| {
+            use chrono::Offset;
+            offset_timezone(chrono::Local::now().offset().fix().local_minus_utc())
+        })
+}
+
+/// The `Etc/GMT±N` zone for a UTC offset in seconds (POSIX signs, so UTC+2
+/// is `Etc/GMT-2`); `UTC` for offsets that are not whole hours.
+pub fn offset_timezone(offset_secs: i32) -> String {
+    let hours = offset_secs / 3600;
+    if offset_secs % 3600 != 0 || hours == 0 {
+        return "UTC".to_string();
+    }
+    format!("Etc/GMT{:+}", -hours)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Only `webdav` is supported.
+    #[serde(default = "default_sync_provider")]
+    pub provider: String,
+    /// Remote directory the database is copied to, e.g. `https://dav.example.com/openvital`.
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    /// Environment variable holding the password, which is never stored here.
+    #[serde(default = "default_sync_password_env")]
+    pub password_env: String,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_sync_provider(),
+            url: String::new(),
+            username: String::new(),
+            password_env: default_sync_password_env(),
+        }
+    }
+}
+
+fn default_sync_provider() -> String {
+    "webdav".to_string()
+}
+
+fn default_sync_password_env() -> String {
+    "OPENVITAL_SYNC_PASSWORD".to_string()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StreaksConfig {
+    /// Types whose logging streaks `status` reports, in addition to daily goals.
+    #[serde(default)]
+    pub track: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RemindersConfig {
+    /// Types `remind` expects an entry for every day.
+    #[serde(default)]
+    pub daily: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Timestamped backups kept in the default backup directory; 0 keeps all.
+    #[serde(default = "default_backup_keep")]
+    pub keep: u32,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+fn default_backup_keep() -> u32 {
+    10
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Log a likely typo of a known type (e.g. `wheight`) as that type instead
+    /// of rejecting it with a suggestion.
+    #[serde(default)]
+    pub fuzzy_match: bool,
+    /// Source recorded when `log` is run without `--source`; `device` expands
+    /// to `<hostname>:<tool>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrendConfig {
+    /// Longest run of missing days `--fill forward|linear` fills; longer gaps stay empty.
+    #[serde(default = "default_fill_max_gap_days")]
+    pub fill_max_gap_days: u32,
+}
+
+impl Default for TrendConfig {
+    fn default() -> Self {
+        Self {
+            fill_max_gap_days: default_fill_max_gap_days(),
+        }
+    }
+}
+
+fn default_fill_max_gap_days() -> u32 {
+    3
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MedicationsConfig {
+    /// A day whose missing doses were all recorded with `med take --skip`
+    /// neither counts toward adherence nor breaks the streak; off, it counts
+    /// as missed.
+    #[serde(default = "default_skips_preserve_streak")]
+    pub skips_preserve_streak: bool,
+}
+
+impl Default for MedicationsConfig {
+    fn default() -> Self {
+        Self {
+            skips_preserve_streak: default_skips_preserve_streak(),
+        }
+    }
+}
+
+fn default_skips_preserve_streak() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CycleConfig {
+    /// A `cycle` entry more than this many days after the previous one starts a new cycle.
+    #[serde(default = "default_cycle_gap_days")]
+    pub gap_days: u32,
+}
+
+impl Default for CycleConfig {
+    fn default() -> Self {
+        Self {
+            gap_days: default_cycle_gap_days(),
+        }
+    }
+}
+
+fn default_cycle_gap_days() -> u32 {
+    14
+}
+
+/// Glucose target range in mg/dL, for time-in-range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlucoseConfig {
+    pub target_low: f64,
+    pub target_high: f64,
+}
+
+impl Default for GlucoseConfig {
+    fn default() -> Self {
+        Self {
+            target_low: 70.0,
+            target_high: 180.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DatabaseConfig {
+    /// Use `auto_vacuum = INCREMENTAL`, releasing free pages each time the
+    /// database is opened instead of waiting for `db compact`.
+    #[serde(default)]
+    pub auto_vacuum: bool,
+    #[serde(default)]
+    pub journal_mode: JournalMode,
+}
+
+/// First day of a calendar week.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> chrono::Weekday {
+        match self {
+            Self::Monday => chrono::Weekday::Mon,
+            Self::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
+impl std::fmt::Display for WeekStart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Monday => write!(f, "monday"),
+            Self::Sunday => write!(f, "sunday"),
+        }
+    }
+}
+
+impl std::str::FromStr for WeekStart {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "monday" | "mon" => Ok(Self::Monday),
+            "sunday" | "sun" => Ok(Self::Sunday),
+            _ => crate::fail!(
+                validation,
+                "invalid week start: {} (expected monday/sunday)",
+                s
+            ),
+        }
+    }
+}
+
+/// SQLite journal mode set on every connection.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalMode {
+    /// Write-ahead log: readers don't block the writer.
+    #[default]
+    Wal,
+    Delete,
+    Truncate,
+}
+
+impl std::fmt::Display for JournalMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Wal => write!(f, "wal"),
+            Self::Delete => write!(f, "delete"),
+            Self::Truncate => write!(f, "truncate"),
+        }
+    }
+}
+
+impl std::str::FromStr for JournalMode {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "wal" => Ok(Self::Wal),
+            "delete" => Ok(Self::Delete),
+            "truncate" => Ok(Self::Truncate),
+            _ => crate::fail!(
+                validation,
+                "invalid journal mode: {} (expected wal/delete/truncate)",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub key_source: KeySource,
+}
+
+impl EncryptionConfig {
+    /// The database passphrase, or `None` when encryption is disabled.
+    pub fn passphrase(&self) -> anyhow::Result<Option<String>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        self.key_source.resolve().map(Some)
+    }
+}
+
+/// Where the database passphrase comes from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// An environment variable holding the passphrase.
+    EnvVar(String),
+    /// The OS keychain entry for service "openvital", account "database"
+    /// (macOS `security`, elsewhere `secret-tool`).
+    Keychain,
+    /// Stored in the config file itself. For testing only.
+    PlainText(String),
+}
+
+impl Default for KeySource {
+    fn default() -> Self {
+        Self::EnvVar("OPENVITAL_DB_KEY".to_string())
+    }
+}
+
+impl std::str::FromStr for KeySource {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.split_once(':') {
+            Some(("env", var)) if !var.is_empty() => Ok(Self::EnvVar(var.to_string())),
+            Some(("plain", key)) if !key.is_empty() => Ok(Self::PlainText(key.to_string())),
+            None if s == "keychain" => Ok(Self::Keychain),
+            _ => crate::fail!(
+                validation,
+                "invalid key source: {} (expected env:<VAR>, keychain, or plain:<passphrase>)",
+                s
+            ),
+        }
+    }
+}
+
+impl KeySource {
+    /// Look up the passphrase.
+    pub fn resolve(&self) -> anyhow::Result<String> {
+        let key = match self {
+            Self::EnvVar(var) => std::env::var(var).map_err(|_| {
+                crate::error::validation(format!("encryption key not found: set ${}", var))
+            })?,
+            Self::Keychain => {
+                #[cfg(target_os = "macos")]
+                let output = std::process::Command::new("security")
+                    .args([
+                        "find-generic-password",
+                        "-s",
+                        "openvital",
+                        "-a",
+                        "database",
+                        "-w",
+                    ])
+                    .output();
+                #[cfg(not(target_os = "macos"))]
+                let output = std::process::Command::new("secret-tool")
+                    .args(["lookup", "service", "openvital", "account", "database"])
+                    .output();
+                match output {
+                    Ok(o) if o.status.success() => String::from_utf8(o.stdout)?.trim().to_string(),
+                    _ => crate::fail!(
+                        validation,
+                        "encryption key not found in keychain (service \"openvital\", account \"database\")"
+                    ),
+                }
+            }
+            Self::PlainText(key) => key.clone(),
+        };
+        if key.is_empty() {
+            crate::fail!(validation, "encryption key is empty");
+        }
+        Ok(key)
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -22,6 +477,10 @@ pub struct Profile {
     #[serde(default)]
     pub conditions: Vec<String>,
     pub primary_exercise: Option<String>,
+    /// IANA timezone used to decide which calendar day an entry belongs to;
+    /// the system zone when unset (see [`Config::timezone`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,6 +495,20 @@ pub struct Units {
     pub water: String,
     #[serde(default = "default_temperature_unit")]
     pub temperature: String,
+    /// Per-type display units, taking precedence over `system`
+    /// (`config set units.water oz`, `config set units.custom.run_distance km`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub custom: HashMap<String, UnitOverride>,
+}
+
+/// Display unit for one metric type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitOverride {
+    pub unit: String,
+    /// Stored (canonical) units per display unit, e.g. 29.5735 for water in oz.
+    /// Without one the unit is only a label and values are not converted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factor: Option<f64>,
 }
 
 fn default_system() -> String {
@@ -62,6 +535,7 @@ impl Default for Units {
             height: "cm".to_string(),
             water: "ml".to_string(),
             temperature: "celsius".to_string(),
+            custom: HashMap::new(),
         }
     }
 }
@@ -74,18 +548,34 @@ impl Units {
             height: "ft".to_string(),
             water: "fl_oz".to_string(),
             temperature: "fahrenheit".to_string(),
+            custom: HashMap::new(),
         }
     }
 
     pub fn is_imperial(&self) -> bool {
         self.system == "imperial"
     }
+
+    /// The per-type display unit for `metric_type`, if one is configured.
+    pub fn override_for(&self, metric_type: &str) -> Option<&UnitOverride> {
+        self.custom.get(metric_type)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Alerts {
     pub pain_threshold: u8,
     pub pain_consecutive_days: u8,
+    /// Alert when resting heart rate rises more than this percentage above
+    /// the previous week's average.
+    #[serde(default = "default_resting_hr_change_pct")]
+    pub resting_hr_change_pct: f64,
+    #[serde(default)]
+    pub blood_pressure: BpThresholds,
+}
+
+fn default_resting_hr_change_pct() -> f64 {
+    10.0
 }
 
 impl Default for Alerts {
@@ -93,17 +583,89 @@ impl Default for Alerts {
         Self {
             pain_threshold: 5,
             pain_consecutive_days: 3,
+            resting_hr_change_pct: default_resting_hr_change_pct(),
+            blood_pressure: BpThresholds::default(),
+        }
+    }
+}
+
+/// Lower bounds (mmHg) of each blood pressure stage. A reading is in the
+/// highest stage either side reaches; "elevated" is systolic-only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BpThresholds {
+    pub elevated_systolic: f64,
+    pub stage1_systolic: f64,
+    pub stage1_diastolic: f64,
+    pub stage2_systolic: f64,
+    pub stage2_diastolic: f64,
+    pub crisis_systolic: f64,
+    pub crisis_diastolic: f64,
+}
+
+impl Default for BpThresholds {
+    fn default() -> Self {
+        Self {
+            elevated_systolic: 120.0,
+            stage1_systolic: 130.0,
+            stage1_diastolic: 80.0,
+            stage2_systolic: 140.0,
+            stage2_diastolic: 90.0,
+            crisis_systolic: 180.0,
+            crisis_diastolic: 120.0,
+        }
+    }
+}
+
+impl BpThresholds {
+    pub const FIELDS: &[&str] = &[
+        "elevated_systolic",
+        "stage1_systolic",
+        "stage1_diastolic",
+        "stage2_systolic",
+        "stage2_diastolic",
+        "crisis_systolic",
+        "crisis_diastolic",
+    ];
+
+    /// The threshold named `field` (one of [`Self::FIELDS`]).
+    pub fn field_mut(&mut self, field: &str) -> Option<&mut f64> {
+        match field {
+            "elevated_systolic" => Some(&mut self.elevated_systolic),
+            "stage1_systolic" => Some(&mut self.stage1_systolic),
+            "stage1_diastolic" => Some(&mut self.stage1_diastolic),
+            "stage2_systolic" => Some(&mut self.stage2_systolic),
+            "stage2_diastolic" => Some(&mut self.stage2_diastolic),
+            "crisis_systolic" => Some(&mut self.crisis_systolic),
+            "crisis_diastolic" => Some(&mut self.crisis_diastolic),
+            _ => None,
         }
     }
 }
 
 impl Config {
+    /// `profile.timezone`, or the system zone when it is unset.
+    pub fn timezone(&self) -> String {
+        self.profile
+            .timezone
+            .clone()
+            .unwrap_or_else(system_timezone)
+    }
+
     /// Load config from the standard path, or return defaults.
     pub fn load() -> anyhow::Result<Self> {
         let path = Self::path();
         if path.exists() {
-            let contents = std::fs::read_to_string(&path)?;
-            Ok(toml::from_str(&contents)?)
+            let mut table: toml::Table = toml::from_str(&std::fs::read_to_string(&path)?)?;
+            // `timezone` used to be a top-level key.
+            if let Some(tz) = table.remove("timezone")
+                && let toml::Value::Table(profile) = table
+                    .entry("profile")
+                    .or_insert_with(|| toml::Table::new().into())
+            {
+                profile.entry("timezone").or_insert(tz);
+            }
+            Ok(table.try_into()?)
         } else {
             Ok(Self::default())
         }
@@ -111,7 +673,11 @@ impl Config {
 
     /// Save config to the standard path.
     pub fn save(&self) -> anyhow::Result<()> {
-        let path = Self::path();
+        self.save_to(&Self::path())
+    }
+
+    /// Save config to `path`, creating its directory if needed.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
             #[cfg(unix)]
@@ -130,7 +696,7 @@ impl Config {
 
             let mut options = OpenOptions::new();
             options.write(true).create(true).truncate(true).mode(0o600);
-            let mut file = options.open(&path)?;
+            let mut file = options.open(path)?;
 
             file.write_all(contents.as_bytes())?;
 
@@ -139,12 +705,12 @@ impl Config {
             let mut perms = file.metadata()?.permissions();
             if perms.mode() & 0o777 != 0o600 {
                 perms.set_mode(0o600);
-                fs::set_permissions(&path, perms)?;
+                fs::set_permissions(path, perms)?;
             }
         }
         #[cfg(not(unix))]
         {
-            std::fs::write(&path, contents)?;
+            std::fs::write(path, contents)?;
         }
 
         Ok(())
@@ -158,6 +724,19 @@ impl Config {
             .unwrap_or_else(|| input.to_string())
     }
 
+    /// Aggregation for a metric type: the configured override, else the built-in default.
+    pub fn aggregation_for(&self, metric_type: &str) -> Aggregation {
+        self.aggregation
+            .get(metric_type)
+            .copied()
+            .unwrap_or_else(|| default_aggregation(metric_type))
+    }
+
+    /// Where `value` of `metric_type` falls in its configured range, if it has one.
+    pub fn range_status(&self, metric_type: &str, value: f64) -> Option<RangeStatus> {
+        self.ranges.get(metric_type).map(|r| r.classify(value))
+    }
+
     /// Default aliases from the spec.
     pub fn default_aliases() -> HashMap<String, String> {
         let mut m = HashMap::new();
@@ -175,7 +754,43 @@ impl Config {
         m
     }
 
+    /// Select the profile for the rest of the process (`--profile`); `None`
+    /// falls back to the default profile from [`Settings`].
+    pub fn set_profile(name: Option<&str>) {
+        *SELECTED_PROFILE.write().unwrap_or_else(|e| e.into_inner()) = name.map(String::from);
+    }
+
+    /// The profile commands operate on.
+    pub fn active_profile() -> String {
+        if let Some(name) = SELECTED_PROFILE
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            return name;
+        }
+        Settings::load()
+            .map(|s| s.default_profile().to_string())
+            .unwrap_or_else(|_| DEFAULT_PROFILE.to_string())
+    }
+
+    /// Directory holding a profile's config and database: the data directory
+    /// itself for [`DEFAULT_PROFILE`], else `profiles/<name>` under it.
+    pub fn profile_dir(name: &str) -> PathBuf {
+        if name == DEFAULT_PROFILE {
+            Self::home_dir()
+        } else {
+            Self::home_dir().join("profiles").join(name)
+        }
+    }
+
+    /// Data directory of the active profile.
     pub fn data_dir() -> PathBuf {
+        Self::profile_dir(&Self::active_profile())
+    }
+
+    /// Top-level data directory shared by all profiles.
+    pub fn home_dir() -> PathBuf {
         if let Ok(home) = std::env::var("OPENVITAL_HOME") {
             return PathBuf::from(home);
         }
@@ -28,7 +28,11 @@ impl FromStr for Direction {
             "above" => Ok(Self::Above),
             "below" => Ok(Self::Below),
             "equal" => Ok(Self::Equal),
-            _ => anyhow::bail!("invalid direction: {} (expected above/below/equal)", s),
+            _ => crate::fail!(
+                validation,
+                "invalid direction: {} (expected above/below/equal)",
+                s
+            ),
         }
     }
 }
@@ -58,7 +62,11 @@ impl FromStr for Timeframe {
             "daily" => Ok(Self::Daily),
             "weekly" => Ok(Self::Weekly),
             "monthly" => Ok(Self::Monthly),
-            _ => anyhow::bail!("invalid timeframe: {} (expected daily/weekly/monthly)", s),
+            _ => crate::fail!(
+                validation,
+                "invalid timeframe: {} (expected daily/weekly/monthly)",
+                s
+            ),
         }
     }
 }
@@ -72,6 +80,9 @@ pub struct Goal {
     pub timeframe: Timeframe,
     pub active: bool,
     pub created_at: DateTime<Utc>,
+    /// Latest value of the metric when the goal was set, for percent-complete.
+    #[serde(default)]
+    pub start_value: Option<f64>,
 }
 
 impl Goal {
@@ -89,9 +100,23 @@ impl Goal {
             timeframe,
             active: true,
             created_at: Utc::now(),
+            start_value: None,
         }
     }
 
+    /// How far `current` has moved from `start_value` towards the target, as
+    /// 0–100 (rounded to one decimal). `None` without a start value, or when it
+    /// already equalled the target.
+    pub fn percent_complete(&self, current: f64) -> Option<f64> {
+        let start = self.start_value?;
+        let span = start - self.target_value;
+        if span.abs() < f64::EPSILON {
+            return None;
+        }
+        let pct = ((start - current) / span * 100.0).clamp(0.0, 100.0);
+        Some((pct * 10.0).round() / 10.0)
+    }
+
     /// Check if a value meets the goal target.
     pub fn is_met(&self, value: f64) -> bool {
         match self.direction {
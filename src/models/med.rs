@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
@@ -59,25 +59,55 @@ impl fmt::Display for Route {
 // Frequency
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// How often a medication is taken. Serialised as its `Display` string
+/// ("daily", "2x_daily", "mon_wed_fri", ...).
+#[derive(Debug, Clone, PartialEq)]
 pub enum Frequency {
     Daily,
     TwiceDaily,
     ThreeTimesDaily,
     Weekly,
     AsNeeded,
+    /// Once on each listed weekday, Monday first, no duplicates.
+    Custom(Vec<Weekday>),
 }
 
 impl Frequency {
     /// How many doses are required per day, if the schedule is fixed.
-    /// Returns `None` for `Weekly` and `AsNeeded`.
-    pub fn required_per_day(&self) -> Option<u32> {
+    /// Returns `None` for `Weekly` and `AsNeeded`. For `Custom`, whether today
+    /// in `tz` (the configured timezone) is a scheduled day; prefer
+    /// [`required_on`](Self::required_on).
+    pub fn required_per_day(&self, tz: chrono_tz::Tz) -> Option<u32> {
+        match self {
+            Self::Custom(_) => self.required_on(Utc::now().with_timezone(&tz).date_naive()),
+            _ => self.required_on_any_day(),
+        }
+    }
+
+    /// Doses required on `date`: like [`required_per_day`](Self::required_per_day),
+    /// with `Custom` giving `Some(1)` on a listed weekday and `Some(0)` otherwise.
+    pub fn required_on(&self, date: NaiveDate) -> Option<u32> {
+        match self {
+            Self::Custom(days) => Some(u32::from(days.contains(&date.weekday()))),
+            _ => self.required_on_any_day(),
+        }
+    }
+
+    /// Whether `date` is a dosing day. Only `Custom` schedules have days off.
+    pub fn is_scheduled_on(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Custom(days) => days.contains(&date.weekday()),
+            _ => true,
+        }
+    }
+
+    fn required_on_any_day(&self) -> Option<u32> {
         match self {
             Self::Daily => Some(1),
             Self::TwiceDaily => Some(2),
             Self::ThreeTimesDaily => Some(3),
             Self::Weekly | Self::AsNeeded => None,
+            Self::Custom(_) => Some(1),
         }
     }
 }
@@ -88,11 +118,25 @@ impl FromStr for Frequency {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "daily" => Ok(Self::Daily),
-            "2x_daily" => Ok(Self::TwiceDaily),
-            "3x_daily" => Ok(Self::ThreeTimesDaily),
+            // Older exports used the variant names.
+            "2x_daily" | "twice_daily" => Ok(Self::TwiceDaily),
+            "3x_daily" | "three_times_daily" => Ok(Self::ThreeTimesDaily),
             "weekly" => Ok(Self::Weekly),
             "as_needed" => Ok(Self::AsNeeded),
-            other => Err(anyhow::anyhow!("unknown frequency: {other}")),
+            other => {
+                // Day-of-week schedule: "mon,wed,fri" or "mon_wed_fri"
+                let mut days = Vec::new();
+                for token in other.split([',', '_']).map(str::trim) {
+                    let day: Weekday = token.parse().map_err(|_| {
+                        crate::error::validation(format!("unknown frequency: {other}"))
+                    })?;
+                    if !days.contains(&day) {
+                        days.push(day);
+                    }
+                }
+                days.sort_by_key(|d| d.num_days_from_monday());
+                Ok(Self::Custom(days))
+            }
         }
     }
 }
@@ -105,10 +149,28 @@ impl fmt::Display for Frequency {
             Self::ThreeTimesDaily => write!(f, "3x_daily"),
             Self::Weekly => write!(f, "weekly"),
             Self::AsNeeded => write!(f, "as_needed"),
+            Self::Custom(days) => {
+                let names: Vec<String> =
+                    days.iter().map(|d| d.to_string().to_lowercase()).collect();
+                write!(f, "{}", names.join("_"))
+            }
         }
     }
 }
 
+impl Serialize for Frequency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Frequency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ParsedDose + parse_dose
 // ---------------------------------------------------------------------------
@@ -257,6 +319,15 @@ pub struct Medication {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Quantity from the most recent refill, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refill_quantity: Option<u32>,
+    /// Date of the most recent refill, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refill_date: Option<DateTime<Utc>>,
+    /// Medications that must not be taken close to this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interactions: Vec<Interaction>,
 }
 
 impl Medication {
@@ -277,10 +348,100 @@ impl Medication {
             stop_reason: None,
             note: None,
             created_at: now,
+            refill_quantity: None,
+            refill_date: None,
+            interactions: Vec::new(),
         }
     }
 }
 
+// ---------------------------------------------------------------------------
+// MedicationChanges
+// ---------------------------------------------------------------------------
+
+/// Fields `med edit` sets; `None` leaves a field as it is.
+#[derive(Debug, Default, Clone)]
+pub struct MedicationChanges {
+    pub dose: Option<String>,
+    pub frequency: Option<Frequency>,
+    pub route: Option<Route>,
+    pub note: Option<String>,
+}
+
+impl MedicationChanges {
+    pub fn is_empty(&self) -> bool {
+        self.dose.is_none()
+            && self.frequency.is_none()
+            && self.route.is_none()
+            && self.note.is_none()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Interaction
+// ---------------------------------------------------------------------------
+
+/// Gap assumed when `--interacts-with` names no minutes.
+pub const DEFAULT_INTERACTION_GAP_MINUTES: u32 = 120;
+
+/// Another medication to keep at least `min_gap_minutes` away from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub with: String,
+    pub min_gap_minutes: u32,
+}
+
+/// Parses `name` or `name:minutes`.
+impl FromStr for Interaction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (with, gap) = match s.rsplit_once(':') {
+            Some((with, gap)) => {
+                let minutes: u32 = gap.trim().parse().map_err(|_| {
+                    crate::error::validation(format!(
+                        "invalid interaction gap '{}' in '{}' (expected minutes)",
+                        gap, s
+                    ))
+                })?;
+                (with, minutes)
+            }
+            None => (s, DEFAULT_INTERACTION_GAP_MINUTES),
+        };
+        let with = with.trim();
+        if with.is_empty() {
+            crate::fail!(validation, "interaction '{}' names no medication", s);
+        }
+        if gap == 0 {
+            crate::fail!(validation, "interaction gap must be at least 1 minute");
+        }
+        Ok(Self {
+            with: with.to_string(),
+            min_gap_minutes: gap,
+        })
+    }
+}
+
+impl fmt::Display for Interaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.with, self.min_gap_minutes)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Refill
+// ---------------------------------------------------------------------------
+
+/// A recorded refill: `quantity` doses expected to last `days_supply` days.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Refill {
+    pub med_id: String,
+    pub quantity: u32,
+    pub days_supply: u32,
+    pub refill_date: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -289,6 +450,23 @@ impl Medication {
 mod tests {
     use super::*;
 
+    // -- Interaction ---------------------------------------------------------
+
+    #[test]
+    fn interaction_from_str() {
+        let i: Interaction = "ibuprofen:90".parse().unwrap();
+        assert_eq!(i.with, "ibuprofen");
+        assert_eq!(i.min_gap_minutes, 90);
+        assert_eq!(i.to_string(), "ibuprofen:90");
+
+        let i: Interaction = "aspirin".parse().unwrap();
+        assert_eq!(i.min_gap_minutes, DEFAULT_INTERACTION_GAP_MINUTES);
+
+        assert!("aspirin:soon".parse::<Interaction>().is_err());
+        assert!("aspirin:0".parse::<Interaction>().is_err());
+        assert!(":30".parse::<Interaction>().is_err());
+    }
+
     // -- Route ---------------------------------------------------------------
 
     #[test]
@@ -369,13 +547,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn frequency_custom_from_str_and_display() {
+        let f: Frequency = "Fri, mon,wed,mon".parse().unwrap();
+        assert_eq!(
+            f,
+            Frequency::Custom(vec![Weekday::Mon, Weekday::Wed, Weekday::Fri])
+        );
+        assert_eq!(f.to_string(), "mon_wed_fri");
+        assert_eq!("mon_wed_fri".parse::<Frequency>().unwrap(), f);
+        assert_eq!(
+            "sunday".parse::<Frequency>().unwrap(),
+            Frequency::Custom(vec![Weekday::Sun])
+        );
+        assert!("mon,funday".parse::<Frequency>().is_err());
+        assert!("".parse::<Frequency>().is_err());
+    }
+
+    #[test]
+    fn frequency_serde_uses_display_string() {
+        let f = Frequency::Custom(vec![Weekday::Tue, Weekday::Thu]);
+        assert_eq!(serde_json::to_string(&f).unwrap(), "\"tue_thu\"");
+        assert_eq!(
+            serde_json::to_string(&Frequency::TwiceDaily).unwrap(),
+            "\"2x_daily\""
+        );
+        let back: Frequency = serde_json::from_str("\"tue_thu\"").unwrap();
+        assert_eq!(back, f);
+        // Older exports serialised the variant name
+        let old: Frequency = serde_json::from_str("\"twice_daily\"").unwrap();
+        assert_eq!(old, Frequency::TwiceDaily);
+    }
+
+    #[test]
+    fn frequency_custom_required_on_each_weekday() {
+        // 2026-03-02 is a Monday
+        let week: Vec<NaiveDate> = (2..=8)
+            .map(|d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap())
+            .collect();
+        let cases: [(&str, [u32; 7]); 4] = [
+            ("mon", [1, 0, 0, 0, 0, 0, 0]),
+            ("mon,wed,fri", [1, 0, 1, 0, 1, 0, 0]),
+            ("sat,sun", [0, 0, 0, 0, 0, 1, 1]),
+            ("mon,tue,wed,thu,fri,sat,sun", [1, 1, 1, 1, 1, 1, 1]),
+        ];
+        for (freq, expected) in cases {
+            let f: Frequency = freq.parse().unwrap();
+            for (date, want) in week.iter().zip(expected) {
+                assert_eq!(f.required_on(*date), Some(want), "{freq} on {date}");
+                assert_eq!(f.is_scheduled_on(*date), want == 1);
+            }
+        }
+        let f: Frequency = "mon,wed,fri".parse().unwrap();
+        for tz in [
+            chrono_tz::Tz::Pacific__Kiritimati,
+            chrono_tz::Tz::Pacific__Niue,
+        ] {
+            let today = Utc::now().with_timezone(&tz).date_naive();
+            assert_eq!(f.required_per_day(tz), f.required_on(today), "{tz}");
+        }
+        assert_eq!(Frequency::Daily.required_on(week[5]), Some(1));
+        assert!(Frequency::Weekly.is_scheduled_on(week[5]));
+    }
+
     #[test]
     fn frequency_required_per_day() {
-        assert_eq!(Frequency::Daily.required_per_day(), Some(1));
-        assert_eq!(Frequency::TwiceDaily.required_per_day(), Some(2));
-        assert_eq!(Frequency::ThreeTimesDaily.required_per_day(), Some(3));
-        assert_eq!(Frequency::Weekly.required_per_day(), None);
-        assert_eq!(Frequency::AsNeeded.required_per_day(), None);
+        assert_eq!(
+            Frequency::Daily.required_per_day(chrono_tz::Tz::UTC),
+            Some(1)
+        );
+        assert_eq!(
+            Frequency::TwiceDaily.required_per_day(chrono_tz::Tz::UTC),
+            Some(2)
+        );
+        assert_eq!(
+            Frequency::ThreeTimesDaily.required_per_day(chrono_tz::Tz::UTC),
+            Some(3)
+        );
+        assert_eq!(Frequency::Weekly.required_per_day(chrono_tz::Tz::UTC), None);
+        assert_eq!(
+            Frequency::AsNeeded.required_per_day(chrono_tz::Tz::UTC),
+            None
+        );
     }
 
     // -- parse_dose ----------------------------------------------------------
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -18,8 +19,9 @@ pub enum Category {
 impl Category {
     pub fn from_type(metric_type: &str) -> Self {
         match metric_type {
-            "weight" | "body_fat" | "waist" => Self::Body,
-            "cardio" | "strength" | "calories_burned" => Self::Exercise,
+            "weight" | "body_fat" | "waist" | "heart_rate" | "resting_heart_rate" | "cycle"
+            | "glucose" => Self::Body,
+            "cardio" | "strength" | "calories_burned" | "perceived_exertion" => Self::Exercise,
             "sleep_hours" | "sleep_quality" | "bed_time" | "wake_time" => Self::Sleep,
             "calories" | "calories_in" | "calories_out" | "water" => Self::Nutrition,
             "pain" | "soreness" => Self::Pain,
@@ -44,6 +46,76 @@ impl std::fmt::Display for Category {
     }
 }
 
+impl FromStr for Category {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "body" => Ok(Self::Body),
+            "exercise" => Ok(Self::Exercise),
+            "sleep" => Ok(Self::Sleep),
+            "nutrition" => Ok(Self::Nutrition),
+            "pain" => Ok(Self::Pain),
+            "habit" => Ok(Self::Habit),
+            "medication" => Ok(Self::Medication),
+            "custom" => Ok(Self::Custom),
+            _ => crate::fail!(
+                validation,
+                "invalid category: {} (expected body/exercise/sleep/nutrition/pain/habit/custom)",
+                s
+            ),
+        }
+    }
+}
+
+/// Metric types with a built-in unit or category.
+pub const BUILTIN_TYPES: &[&str] = &[
+    "weight",
+    "body_fat",
+    "waist",
+    "cardio",
+    "strength",
+    "perceived_exertion",
+    "calories",
+    "calories_in",
+    "calories_out",
+    "calories_burned",
+    "sleep_hours",
+    "sleep_quality",
+    "bed_time",
+    "wake_time",
+    "sleep",
+    "water",
+    "steps",
+    "mood",
+    "heart_rate",
+    "resting_heart_rate",
+    "bp_systolic",
+    "bp_diastolic",
+    "glucose",
+    "pain",
+    "soreness",
+    "standing_breaks",
+    "screen_time",
+    "cycle",
+    "journal",
+];
+
+pub fn is_builtin(metric_type: &str) -> bool {
+    BUILTIN_TYPES.contains(&metric_type)
+}
+
+/// A user-defined metric type (`types add`), stored in `custom_metric_types`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomMetricType {
+    pub name: String,
+    pub unit: String,
+    pub category: Category,
+}
+
+/// Metric type for free-form journal notes (`note add`). Entries carry their
+/// text in `note` and a placeholder value of 0, so numeric analyses skip them.
+pub const JOURNAL_TYPE: &str = "journal";
+
 /// Default unit for a known metric type.
 pub fn default_unit(metric_type: &str) -> &str {
     match metric_type {
@@ -51,6 +123,7 @@ pub fn default_unit(metric_type: &str) -> &str {
         "body_fat" => "%",
         "waist" => "cm",
         "cardio" | "strength" => "min",
+        "perceived_exertion" => "1-10",
         "calories" | "calories_out" | "calories_burned" | "calories_in" => "kcal",
         "sleep_hours" => "hours",
         "sleep_quality" => "1-5",
@@ -59,16 +132,24 @@ pub fn default_unit(metric_type: &str) -> &str {
         "sleep" => "hours",
         "steps" => "steps",
         "mood" => "1-10",
-        "heart_rate" => "bpm",
+        "heart_rate" | "resting_heart_rate" => "bpm",
         "bp_systolic" | "bp_diastolic" => "mmHg",
+        "glucose" => "mg/dL",
         "pain" => "0-10",
         "soreness" => "0-10",
         "standing_breaks" => "count",
         "screen_time" => "hours",
+        "cycle" => "1-5",
+        "journal" => "",
         _ => "",
     }
 }
 
+/// Canonical form used for tag matching: trimmed and lowercased.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
 /// Whether a metric type is cumulative (sum values) vs snapshot (use latest).
 pub fn is_cumulative(metric_type: &str) -> bool {
     matches!(
@@ -77,6 +158,67 @@ pub fn is_cumulative(metric_type: &str) -> bool {
     )
 }
 
+/// How values are combined when grouped into a period (trend bucket, report summary).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl std::fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sum => write!(f, "sum"),
+            Self::Avg => write!(f, "avg"),
+            Self::Min => write!(f, "min"),
+            Self::Max => write!(f, "max"),
+        }
+    }
+}
+
+impl FromStr for Aggregation {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "sum" => Ok(Self::Sum),
+            "avg" => Ok(Self::Avg),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            _ => crate::fail!(
+                validation,
+                "invalid aggregation: {} (expected sum/avg/min/max)",
+                s
+            ),
+        }
+    }
+}
+
+impl Aggregation {
+    /// Combine a non-empty set of values.
+    pub fn apply(&self, values: &[f64]) -> f64 {
+        match self {
+            Self::Sum => values.iter().sum(),
+            Self::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            Self::Min => values.iter().cloned().fold(f64::INFINITY, f64::min),
+            Self::Max => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Built-in aggregation for a metric type: totals for cumulative metrics and
+/// exercise minutes, worst-case for pain, average for everything else.
+pub fn default_aggregation(metric_type: &str) -> Aggregation {
+    match metric_type {
+        t if is_cumulative(t) => Aggregation::Sum,
+        "cardio" | "strength" => Aggregation::Sum,
+        "pain" | "soreness" => Aggregation::Max,
+        _ => Aggregation::Avg,
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Metric {
     pub id: String,
@@ -3,5 +3,6 @@ pub mod config;
 pub mod goal;
 pub mod med;
 pub mod metric;
+pub mod range;
 
 pub use metric::Metric;
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A healthy band for a metric (`[ranges]`), e.g. sleep 7–9 hours. Either
+/// bound may be open; both are inclusive. Values are in the stored unit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+/// Where a value falls relative to its [`MetricRange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RangeStatus {
+    Below,
+    Within,
+    Above,
+}
+
+impl MetricRange {
+    pub fn classify(&self, value: f64) -> RangeStatus {
+        if self.min.is_some_and(|min| value < min) {
+            RangeStatus::Below
+        } else if self.max.is_some_and(|max| value > max) {
+            RangeStatus::Above
+        } else {
+            RangeStatus::Within
+        }
+    }
+
+    /// The same range with both bounds passed through `f` (unit conversion).
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        Self {
+            min: self.min.map(&f),
+            max: self.max.map(&f),
+        }
+    }
+}
+
+impl std::fmt::Display for MetricRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, "{}-{}", min, max),
+            (Some(min), None) => write!(f, ">={}", min),
+            (None, Some(max)) => write!(f, "<={}", max),
+            (None, None) => write!(f, "any"),
+        }
+    }
+}
+
+impl std::fmt::Display for RangeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Below => write!(f, "below"),
+            Self::Within => write!(f, "within"),
+            Self::Above => write!(f, "above"),
+        }
+    }
+}
+
+/// Parses `7-9`, `7..9`, `>=7` and `<=9`.
+impl FromStr for MetricRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || {
+            crate::error::validation(format!(
+                "invalid range: {} (expected 7-9, 7..9, >=7 or <=9)",
+                s
+            ))
+        };
+        let num = |v: &str| -> Result<f64, anyhow::Error> {
+            v.trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .ok_or_else(invalid)
+        };
+        let range = if let Some(min) = s.strip_prefix(">=") {
+            Self {
+                min: Some(num(min)?),
+                max: None,
+            }
+        } else if let Some(max) = s.strip_prefix("<=") {
+            Self {
+                min: None,
+                max: Some(num(max)?),
+            }
+        } else {
+            // A leading '-' is the sign of the lower bound, not the separator
+            let (low, high) = s
+                .split_once("..")
+                .or_else(|| {
+                    s.char_indices()
+                        .skip(1)
+                        .find(|(_, c)| *c == '-')
+                        .map(|(i, _)| (&s[..i], &s[i + 1..]))
+                })
+                .ok_or_else(invalid)?;
+            let (min, max) = (num(low)?, num(high)?);
+            if min > max {
+                crate::fail!(validation, "invalid range: {} (lower bound above upper)", s);
+            }
+            Self {
+                min: Some(min),
+                max: Some(max),
+            }
+        };
+        Ok(range)
+    }
+}
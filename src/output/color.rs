@@ -0,0 +1,43 @@
+use std::io::IsTerminal;
+
+/// Whether `--human` output is colored (`--color always|never|auto`). Auto
+/// colors when stdout is a terminal and `NO_COLOR` is unset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColorConfig {
+    pub enabled: bool,
+}
+
+impl ColorConfig {
+    pub fn new(choice: &str) -> Self {
+        let enabled = match choice {
+            "always" => true,
+            "never" => false,
+            _ => std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        };
+        Self { enabled }
+    }
+
+    pub fn green(&self, s: &str) -> String {
+        self.paint("32", s)
+    }
+
+    pub fn red(&self, s: &str) -> String {
+        self.paint("31", s)
+    }
+
+    pub fn yellow(&self, s: &str) -> String {
+        self.paint("33", s)
+    }
+
+    pub fn bold(&self, s: &str) -> String {
+        self.paint("1", s)
+    }
+
+    fn paint(&self, code: &str, s: &str) -> String {
+        if self.enabled {
+            format!("\x1B[{}m{}\x1B[0m", code, s)
+        } else {
+            s.to_string()
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::core::report::{Digest, MetricSummary, ReportGoal};
+use crate::core::status::StreakKind;
+use crate::core::units::{to_display, to_display_rate};
+use crate::models::config::Units;
+use crate::models::metric::Aggregation;
+
+/// Render a digest as plain text for mail or a text message: ASCII only,
+/// under 80 columns, and sections in a fixed order (empty ones left out) so
+/// consecutive digests diff cleanly. `label` names the period ("week",
+/// "month", ...) for the empty-digest line.
+pub fn format_digest(d: &Digest, label: Option<&str>, units: &Units) -> String {
+    let r = &d.report;
+    if r.total_entries == 0 {
+        return match label {
+            Some("ytd") => "No data logged this year\n".to_string(),
+            Some(p) => format!("No data logged this {}\n", p),
+            None => "No data logged in this period\n".to_string(),
+        };
+    }
+
+    let mut out = format!("OpenVital digest {} to {}\n", r.from, r.to);
+    if let Some(prev) = &r.compared_to {
+        out.push_str(&format!("Compared with {} to {}\n", prev.from, prev.to));
+    }
+    out.push_str(&format!(
+        "Days with entries: {}  Entries: {}\n",
+        r.days_with_entries, r.total_entries
+    ));
+
+    out.push_str("\nMetrics\n");
+    for m in &r.metrics {
+        out.push_str(&format!("  {}\n", metric_line(m, units)));
+    }
+
+    if let Some(goals) = r.goals.as_ref().filter(|g| g.goals_total > 0) {
+        out.push_str(&format!(
+            "\nGoals: {}/{} met\n",
+            goals.goals_met, goals.goals_total
+        ));
+        for g in &goals.goals {
+            out.push_str(&format!("  {}\n", goal_line(g, units)));
+        }
+    }
+
+    if let Some(adherence) = d
+        .status
+        .medications
+        .as_ref()
+        .and_then(|m| m.overall_adherence_7d)
+    {
+        out.push_str(&format!(
+            "\nMedication adherence: {:.0}% (last 7 days)\n",
+            adherence * 100.0
+        ));
+    }
+
+    let streaks = &d.status.streaks;
+    if streaks.logging_days > 0 || !streaks.by_type.is_empty() {
+        out.push_str("\nStreaks\n");
+        if streaks.logging_days > 0 {
+            out.push_str(&format!(
+                "  {:24} {} days\n",
+                "logging", streaks.logging_days
+            ));
+        }
+        for s in &streaks.by_type {
+            let name = format!("{} ({})", s.metric_type, kind_label(s.kind));
+            out.push_str(&format!(
+                "  {:24} {} days (best {})\n",
+                name, s.current_days, s.best_days
+            ));
+        }
+    }
+
+    let pain = &d.status.consecutive_pain_alerts;
+    if !pain.is_empty() || !d.anomalies.is_empty() {
+        out.push_str("\nWarnings\n");
+        for p in pain {
+            out.push_str(&format!(
+                "  {} high for {} days in a row (latest {})\n",
+                p.metric_type, p.consecutive_days, p.latest_value
+            ));
+        }
+        for (date, a) in &d.anomalies {
+            let (value, unit) = to_display(a.value, &a.metric_type, units);
+            out.push_str(&format!(
+                "  {} {} on {}: {} usual range\n",
+                a.metric_type,
+                with_unit(value, &unit),
+                date,
+                a.deviation
+            ));
+        }
+    }
+    out
+}
+
+fn metric_line(m: &MetricSummary, units: &Units) -> String {
+    let delta = m.comparison.as_ref();
+    if m.count == 0 {
+        let prev = delta.and_then(|c| c.prev_avg).unwrap_or(0.0);
+        let (prev, unit) = to_display(prev, &m.metric_type, units);
+        return format!(
+            "{:16} not logged (prev avg {})",
+            m.metric_type,
+            with_unit(prev, &unit)
+        );
+    }
+    let (label, value) = match m.aggregation {
+        Aggregation::Sum => ("total", m.value),
+        _ => ("avg", m.avg),
+    };
+    let (value, unit) = to_display(value, &m.metric_type, units);
+    let change = match delta {
+        Some(c) => match c.delta {
+            Some(d) => format!("{:+.1} vs prev", to_display_rate(d, &m.metric_type, units)),
+            None => "new".to_string(),
+        },
+        None => String::new(),
+    };
+    format!(
+        "{:16} {:5} {:14} {}",
+        m.metric_type,
+        label,
+        with_unit(value, &unit),
+        change
+    )
+    .trim_end()
+    .to_string()
+}
+
+fn goal_line(g: &ReportGoal, units: &Units) -> String {
+    let (target, unit) = to_display(g.target_value, &g.metric_type, units);
+    let current = match g.current_value {
+        Some(v) => with_unit(to_display(v, &g.metric_type, units).0, &unit),
+        None => "no data".to_string(),
+    };
+    format!(
+        "{:6} {} {} {} {} (now {})",
+        if g.is_met { "met" } else { "missed" },
+        g.metric_type,
+        g.direction,
+        with_unit(target, &unit),
+        g.timeframe,
+        current
+    )
+}
+
+fn kind_label(kind: StreakKind) -> &'static str {
+    match kind {
+        StreakKind::Logging => "logged",
+        StreakKind::Goal => "goal",
+    }
+}
+
+fn with_unit(value: f64, unit: &str) -> String {
+    match unit {
+        "0-10" | "1-10" => format!("{:.1}/10", value),
+        "1-5" => format!("{:.1}/5", value),
+        "" => format!("{:.1}", value),
+        u => format!("{:.1} {}", value, u),
+    }
+}
@@ -1,10 +1,24 @@
+use chrono_tz::Tz;
+
+use crate::core::analytics::{MetricWithBmi, SummaryStats, TypeStats};
+use crate::core::bp::{BpCategory, BpReading};
 use crate::core::context::ContextResult;
-use crate::core::med::MedStatus;
+use crate::core::cycle::CycleStatus;
+use crate::core::glucose::{GlucoseDay, TimeInRange};
+use crate::core::insights::{InsightsResult, Severity as InsightSeverity};
+use crate::core::maintenance::DbStats;
+use crate::core::med::{DoseEvent, MedEdit, MedStatus, PrnSummary, ScheduleSlot};
+use crate::core::projection::Projection;
 use crate::core::status::StatusData;
+use crate::core::trend::{CorrelationMatrix, WeekdayTrend};
+use crate::core::types::{TypeDetail, TypeInfo, TypesList};
+use crate::core::workout::{self, WorkoutSession, WorkoutWeek};
 use crate::models::Metric;
-use crate::models::anomaly::{AnomalyResult, Severity};
+use crate::models::anomaly::{Anomaly, AnomalyResult, Method, Severity};
 use crate::models::config::Units;
-use crate::models::med::Medication;
+use crate::models::med::{Interaction, Medication};
+use crate::models::range::RangeStatus;
+use crate::output::color::ColorConfig;
 
 /// Format a value with its unit, handling scale units like "0-10" → "7/10".
 fn format_value_with_unit(val: f64, unit: &str) -> String {
@@ -29,13 +43,64 @@ pub fn format_metric(m: &Metric) -> String {
     line
 }
 
+/// Pretty-print a blood pressure reading as "120/80 mmHg", with "?" for a missing side.
+pub fn format_bp_reading(r: &BpReading) -> String {
+    let side = |v: Option<f64>| v.map_or_else(|| "?".to_string(), |v| v.to_string());
+    let mut line = format!(
+        "{} | blood_pressure = {}/{} {}",
+        r.timestamp.format("%Y-%m-%d %H:%M"),
+        side(r.systolic),
+        side(r.diastolic),
+        r.unit
+    );
+    if let Some(ref note) = r.note {
+        line.push_str(&format!("  # {}", note));
+    }
+    line
+}
+
 /// Pretty-print a single metric entry, converting to user's preferred unit system.
 pub fn format_metric_with_units(m: &Metric, user_units: &Units) -> String {
+    format_metric_in_range(m, user_units, None, None)
+}
+
+/// Marker for a value's place in its configured range: ✓ within, ↓ below, ↑ above.
+pub fn range_marker(status: RangeStatus) -> &'static str {
+    match status {
+        RangeStatus::Below => "↓",
+        RangeStatus::Within => "✓",
+        RangeStatus::Above => "↑",
+    }
+}
+
+/// Like [`format_metric_with_units`], with a range marker after the value.
+pub fn format_metric_in_range(
+    m: &Metric,
+    user_units: &Units,
+    range_status: Option<RangeStatus>,
+    bp_category: Option<BpCategory>,
+) -> String {
     let ts = m.timestamp.format("%Y-%m-%d %H:%M");
+    // Entries stored in a non-default unit (`log --unit`, custom types) are shown as
+    // stored, unless a custom type has a configured display unit
+    let builtin = crate::models::metric::is_builtin(&m.metric_type);
+    let overridden = user_units
+        .override_for(&m.metric_type)
+        .is_some_and(|o| !builtin && m.unit != o.unit);
     let (display_val, display_unit) =
-        crate::core::units::to_display(m.value, &m.metric_type, user_units);
+        if m.unit != crate::models::metric::default_unit(&m.metric_type) && !overridden {
+            (m.value, m.unit.clone())
+        } else {
+            crate::core::units::to_display(m.value, &m.metric_type, user_units)
+        };
     let value_display = format_value_with_unit(display_val, &display_unit);
     let mut line = format!("{} | {} = {}", ts, m.metric_type, value_display);
+    if let Some(status) = range_status {
+        line.push_str(&format!(" {}", range_marker(status)));
+    }
+    if let Some(category) = bp_category {
+        line.push_str(&format!(" ({})", category));
+    }
     if let Some(ref note) = m.note {
         line.push_str(&format!("  # {}", note));
     }
@@ -45,6 +110,30 @@ pub fn format_metric_with_units(m: &Metric, user_units: &Units) -> String {
     line
 }
 
+/// Format a journal note: `timestamp | text  [tags]`.
+pub fn format_note(m: &Metric) -> String {
+    let mut line = format!(
+        "{} | {}",
+        m.timestamp.format("%Y-%m-%d %H:%M"),
+        m.note.as_deref().unwrap_or("")
+    );
+    if !m.tags.is_empty() {
+        line.push_str(&format!("  [{}]", m.tags.join(", ")));
+    }
+    line
+}
+
+/// A ten-cell progress bar for a 0–100 percentage: `[######----] 60%`.
+pub fn format_percent_bar(pct: f64) -> String {
+    let filled = ((pct / 10.0).floor() as usize).min(10);
+    format!(
+        "[{}{}] {:.0}%",
+        "#".repeat(filled),
+        "-".repeat(10 - filled),
+        pct
+    )
+}
+
 /// Format goal progress for human-readable output with unit conversion.
 pub fn format_progress_human(status: &crate::core::goal::GoalStatus, units: &Units) -> String {
     let Some(current_raw) = status.current_value else {
@@ -99,9 +188,102 @@ pub fn format_progress_human(status: &crate::core::goal::GoalStatus, units: &Uni
     }
 }
 
+/// One line of a compared report, e.g. `weight: avg 82.5 kg (↓0.5 from prior period)`;
+/// sum-aggregated metrics show their totals.
+pub fn format_metric_comparison(
+    c: &crate::core::report::MetricComparison,
+    units: &Units,
+) -> String {
+    let round = |v: f64| (v * 10.0).round() / 10.0;
+    let (label, current, change) = match (c.current_total, c.current_avg) {
+        (Some(total), _) => ("total", Some(total), c.total_delta),
+        (None, Some(avg)) => ("avg", Some(avg), c.delta),
+        (None, None) => ("avg", None, None),
+    };
+    let Some(current) = current else {
+        let (prev, unit) =
+            crate::core::units::to_display(c.prev_avg.unwrap_or(0.0), &c.metric_type, units);
+        return format!(
+            "{}: not logged (prior avg: {})",
+            c.metric_type,
+            format_value_with_unit(round(prev), &unit)
+        );
+    };
+    let (current, unit) = crate::core::units::to_display(current, &c.metric_type, units);
+    let value = format_value_with_unit(round(current), &unit);
+    let Some(change) = change else {
+        return format!("{}: {} {} (no prior data)", c.metric_type, label, value);
+    };
+    let change = round(crate::core::units::to_display_rate(
+        change,
+        &c.metric_type,
+        units,
+    ));
+    let arrow = if change > 0.0 {
+        "↑"
+    } else if change < 0.0 {
+        "↓"
+    } else {
+        "→"
+    };
+    format!(
+        "{}: {} {} ({}{} from prior period)",
+        c.metric_type,
+        label,
+        value,
+        arrow,
+        change.abs()
+    )
+}
+
+/// One goal line for `report --goals`, e.g. `✓ WEIGHT: 82.5 kg < 85.0 (met)`
+/// or `✗ WATER: 1800 ml / 2000 ml (remaining: 200 ml)`.
+pub fn format_report_goal(g: &crate::core::report::ReportGoal, units: &Units) -> String {
+    let mark = if g.is_met { "✓" } else { "✗" };
+    let name = g.metric_type.to_uppercase();
+    let Some(current_raw) = g.current_value else {
+        return format!("{} {}: no data", mark, name);
+    };
+    let round = |v: f64| (v * 10.0).round() / 10.0;
+    let (current, unit) = crate::core::units::to_display(current_raw, &g.metric_type, units);
+    let (target, _) = crate::core::units::to_display(g.target_value, &g.metric_type, units);
+    let (current, target) = (round(current), round(target));
+
+    if g.is_met {
+        let op = match g.direction.as_str() {
+            "below" => "<",
+            "above" => ">",
+            _ => "=",
+        };
+        return format!(
+            "{} {}: {} {} {:.1} (met)",
+            mark,
+            name,
+            format_value_with_unit(current, &unit),
+            op,
+            target
+        );
+    }
+    let label = match g.direction.as_str() {
+        "below" => "to go",
+        "above" => "remaining",
+        _ => "off by",
+    };
+    format!(
+        "{} {}: {} / {} ({}: {})",
+        mark,
+        name,
+        format_value_with_unit(current, &unit),
+        format_value_with_unit(target, &unit),
+        label,
+        format_value_with_unit(round((current - target).abs()), &unit)
+    )
+}
+
 /// Pretty-print the status overview.
-pub fn format_status(s: &StatusData, user_units: &Units) -> String {
-    let mut out = format!("=== OpenVital Status — {} ===\n\n", s.date);
+pub fn format_status(s: &StatusData, user_units: &Units, color: &ColorConfig) -> String {
+    let mut out = color.bold(&format!("=== OpenVital Status — {} ===", s.date));
+    out.push_str("\n\n");
     if let (Some(w), Some(b)) = (s.profile.latest_weight_kg, s.profile.bmi) {
         let (display_w, display_wu) = crate::core::units::to_display(w, "weight", user_units);
         out.push_str(&format!(
@@ -112,6 +294,18 @@ pub fn format_status(s: &StatusData, user_units: &Units) -> String {
             s.profile.bmi_category.unwrap_or("?")
         ));
     }
+    if let (Some(lean), Some(fat)) = (s.profile.lean_mass_kg, s.profile.fat_mass_kg) {
+        let (lean, unit) = crate::core::units::to_display(lean, "weight", user_units);
+        let (fat, _) = crate::core::units::to_display(fat, "weight", user_units);
+        out.push_str(&format!(
+            "Lean mass: {:.1} {} | Fat mass: {:.1} {}",
+            lean, unit, fat, unit
+        ));
+        if let Some(c) = s.profile.body_fat_category {
+            out.push_str(&format!(" | Body fat: {}", c));
+        }
+        out.push('\n');
+    }
     if s.today.logged.is_empty() {
         out.push_str("No entries logged today.");
     } else {
@@ -131,9 +325,38 @@ pub fn format_status(s: &StatusData, user_units: &Units) -> String {
         out.push_str(&format!("Logged today: {}", parts.join(", ")));
     }
     if !s.today.pain_alerts.is_empty() {
-        out.push_str(&format!(
-            "\nPain alerts: {} active",
+        out.push('\n');
+        out.push_str(&color.red(&format!(
+            "Pain alerts: {} active",
             s.today.pain_alerts.len()
+        )));
+    }
+    if let Some(ref bp) = s.today.blood_pressure {
+        out.push_str(&format!(
+            "\nBlood pressure: {}/{} mmHg ({})",
+            bp.systolic, bp.diastolic, bp.category
+        ));
+        if bp.alert {
+            out.push_str(&alert_line(
+                color,
+                "blood pressure in the hypertensive crisis range",
+            ));
+        }
+    }
+    if let Some(ref g) = s.today.glucose {
+        out.push_str(&format!("\nGlucose: {}", format_glucose_day(g, user_units)));
+    }
+    for r in &s.today.ranges {
+        let (value, unit) = crate::core::units::to_display(r.value, &r.metric_type, user_units);
+        let range = r
+            .range
+            .map(|v| crate::core::units::to_display(v, &r.metric_type, user_units).0);
+        out.push_str(&format!(
+            "\n{}: {} {} (range {})",
+            r.metric_type,
+            format_value_with_unit(value, &unit),
+            range_marker(r.range_status),
+            range
         ));
     }
 
@@ -141,15 +364,53 @@ pub fn format_status(s: &StatusData, user_units: &Units) -> String {
     if s.streaks.logging_days > 0 {
         out.push_str(&format!(
             "\nLogging streak: {} day(s)",
-            s.streaks.logging_days
+            color.bold(&s.streaks.logging_days.to_string())
+        ));
+    }
+    for t in &s.streaks.by_type {
+        let label = match t.kind {
+            crate::core::status::StreakKind::Goal => format!("{} goal", t.metric_type),
+            crate::core::status::StreakKind::Logging => t.metric_type.clone(),
+        };
+        let current = color.bold(&t.current_days.to_string());
+        if t.current_days < t.best_days {
+            out.push_str(&format!(
+                "\n{}: {} days (best: {})",
+                label, current, t.best_days
+            ));
+        } else {
+            out.push_str(&format!("\n{}: {} days", label, current));
+        }
+    }
+
+    if let Some(ref hr) = s.resting_hr {
+        let arrow = match hr.trend {
+            "up" => "↑",
+            "down" => "↓",
+            _ => "→",
+        };
+        out.push_str(&format!(
+            "\nResting HR: {} bpm {} (30d avg {})",
+            hr.latest, arrow, hr.avg_30d
         ));
+        if hr.alert
+            && let Some(pct) = hr.week_change_pct
+        {
+            out.push_str(&alert_line(
+                color,
+                &format!("resting HR up {}% vs the previous week", pct),
+            ));
+        }
     }
 
     // Consecutive pain alerts
     for alert in &s.consecutive_pain_alerts {
-        out.push_str(&format!(
-            "\n!! {} above threshold for {} consecutive days (latest: {})",
-            alert.metric_type, alert.consecutive_days, alert.latest_value
+        out.push_str(&alert_line(
+            color,
+            &format!(
+                "{} above threshold for {} consecutive days (latest: {})",
+                alert.metric_type, alert.consecutive_days, alert.latest_value
+            ),
         ));
     }
 
@@ -157,7 +418,7 @@ pub fn format_status(s: &StatusData, user_units: &Units) -> String {
     if let Some(ref meds) = s.medications {
         out.push_str(&format!("\nMedications: {} active", meds.active_count));
         if !meds.missed.is_empty() {
-            out.push_str(&format!(" | Missed: {}", meds.missed.join(", ")));
+            out.push_str(&color.yellow(&format!(" | Missed: {}", meds.missed.join(", "))));
         }
         if let Some(adherence) = meds.overall_adherence_7d {
             out.push_str(&format!(" | 7d adherence: {:.0}%", adherence * 100.0));
@@ -167,8 +428,13 @@ pub fn format_status(s: &StatusData, user_units: &Units) -> String {
     out
 }
 
+/// A new line with a `!!` warning: bold marker, red text.
+fn alert_line(color: &ColorConfig, message: &str) -> String {
+    format!("\n{} {}", color.bold(&color.red("!!")), color.red(message))
+}
+
 /// Format medication list for human display.
-pub fn format_med_list(meds: &[Medication], include_stopped: bool) -> String {
+pub fn format_med_list(meds: &[Medication], include_stopped: bool, color: &ColorConfig) -> String {
     if meds.is_empty() {
         return "No medications found.".to_string();
     }
@@ -179,36 +445,91 @@ pub fn format_med_list(meds: &[Medication], include_stopped: bool) -> String {
         "Active Medications"
     };
     let separator = "=".repeat(header.len());
-    let mut out = format!("{}\n{}\n", header, separator);
+    let mut out = format!("{}\n{}\n", color.bold(header), separator);
     for med in meds {
         let dose_str = med.dose.as_deref().unwrap_or("");
         let route_str = med.route.to_string();
-        let freq_display = match med.frequency.to_string().as_str() {
-            "daily" => "daily",
-            "2x_daily" => "2x daily",
-            "3x_daily" => "3x daily",
-            "weekly" => "weekly",
-            "as_needed" => "as needed",
-            _ => "unknown",
-        }
-        .to_string();
+        // "2x_daily" → "2x daily", "mon_wed_fri" → "mon wed fri"
+        let freq_display = med.frequency.to_string().replace('_', " ");
         let since = med.started_at.format("%b %d");
         let note_part = med
             .note
             .as_ref()
             .map(|n| format!("  \"{}\"", n))
             .unwrap_or_default();
-        let stopped_marker = if !med.active { " [STOPPED]" } else { "" };
+        let stopped_marker = if med.active {
+            String::new()
+        } else {
+            format!(" {}", color.yellow("[STOPPED]"))
+        };
 
         out.push_str(&format!(
             "  {:<14}{} {}  {:<11}since {}{}{}",
             med.name, dose_str, route_str, freq_display, since, note_part, stopped_marker,
         ));
         out.push('\n');
+        if !med.interactions.is_empty() {
+            out.push_str(&format!(
+                "  {:<14}interacts with {}\n",
+                "",
+                format_interactions(&med.interactions)
+            ));
+        }
     }
     out.trim_end().to_string()
 }
 
+/// Declared interactions, e.g. "ibuprofen (120 min apart), aspirin (60 min apart)".
+pub fn format_interactions(interactions: &[Interaction]) -> String {
+    interactions
+        .iter()
+        .map(|i| format!("{} ({} min apart)", i.with, i.min_gap_minutes))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Format a `med edit` result, e.g.
+/// "Updated medication: ibuprofen (dose: 400mg → 600mg)".
+pub fn format_med_edit(edit: &MedEdit) -> String {
+    let (before, after) = (&edit.before, &edit.medication);
+    let mut changes = Vec::new();
+    let mut field = |label: &str, old: String, new: String| {
+        if old != new {
+            changes.push(format!("{}: {} \u{2192} {}", label, old, new));
+        }
+    };
+    let or_none = |v: &Option<String>| v.clone().unwrap_or_else(|| "none".to_string());
+    field("dose", or_none(&before.dose), or_none(&after.dose));
+    field(
+        "frequency",
+        before.frequency.to_string(),
+        after.frequency.to_string(),
+    );
+    field("route", before.route.to_string(), after.route.to_string());
+    field("note", or_none(&before.note), or_none(&after.note));
+
+    let mut lines = Vec::new();
+    if let Some(n) = edit.renamed_entries {
+        lines.push(format!(
+            "Renamed medication: {} \u{2192} {} ({} {} moved)",
+            before.name,
+            after.name,
+            n,
+            if n == 1 { "entry" } else { "entries" }
+        ));
+    }
+    if !changes.is_empty() {
+        lines.push(format!(
+            "Updated medication: {} ({})",
+            after.name,
+            changes.join(", ")
+        ));
+    } else if lines.is_empty() {
+        lines.push(format!("No changes to medication: {}", after.name));
+    }
+    lines.join("\n")
+}
+
 /// Format medication take confirmation.
 pub fn format_med_take(name: &str, dose: &str, route: &str, timestamp: &str) -> String {
     format!(
@@ -217,6 +538,99 @@ pub fn format_med_take(name: &str, dose: &str, route: &str, timestamp: &str) ->
     )
 }
 
+/// Format a skipped-dose confirmation.
+pub fn format_med_skip(name: &str, reason: Option<&str>, timestamp: &str) -> String {
+    let reason = reason.map(|r| format!(": {}", r)).unwrap_or_default();
+    format!("Skipped {}{}\n  Recorded at {}", name, reason, timestamp)
+}
+
+/// Format today's dose schedule, one line per slot in time order.
+pub fn format_med_schedule(slots: &[ScheduleSlot], date: chrono::NaiveDate) -> String {
+    if slots.is_empty() {
+        return "No scheduled doses today.".to_string();
+    }
+
+    let header = format!("Medication Schedule \u{2014} {}", date.format("%b %d, %Y"));
+    let separator = "=".repeat(header.len());
+    let mut out = format!("{}\n{}\n", header, separator);
+    for s in slots {
+        let marker = if s.taken { "\u{2713}" } else { "\u{25cb}" };
+        let dose = s
+            .dose
+            .as_deref()
+            .map(|d| format!(" {}", d))
+            .unwrap_or_default();
+        out.push_str(&format!("  {} {}  {}{}\n", marker, s.time, s.name, dose));
+    }
+    out.trim_end().to_string()
+}
+
+/// Format the multi-medication status as a compact table.
+pub fn format_med_summary(statuses: &[MedStatus], date: chrono::NaiveDate) -> String {
+    if statuses.is_empty() {
+        return "No active medications.".to_string();
+    }
+
+    let header = format!("Medication Adherence \u{2014} {}", date.format("%b %d, %Y"));
+    let separator = "=".repeat(header.len());
+    let mut out = format!("{}\n{}\n", header, separator);
+    out.push_str(&format!(
+        "  {:<14}{:<11}{:>6}{:>6}{:>8}  {}\n",
+        "Name", "Frequency", "Today", "7d", "Streak", "Runs out"
+    ));
+
+    for s in statuses {
+        let today = match s.required_today {
+            _ if s.skipped_today > 0 && s.adherent_today.is_none() => "skip".to_string(),
+            Some(req) => format!("{}/{}", s.taken_today, req),
+            None => s.taken_today.to_string(),
+        };
+        let adh_7d = s
+            .adherence_7d
+            .map(|a| format!("{:.0}%", a * 100.0))
+            .unwrap_or_else(|| "-".to_string());
+        let streak = s
+            .streak_days
+            .map(|d| format!("{}d", d))
+            .unwrap_or_else(|| "-".to_string());
+        out.push_str(&format!(
+            "  {:<14}{:<11}{:>6}{:>6}{:>8}  {}\n",
+            s.name,
+            s.frequency,
+            today,
+            adh_7d,
+            streak,
+            s.estimated_empty.as_deref().unwrap_or("-")
+        ));
+    }
+
+    let adherence_values: Vec<f64> = statuses.iter().filter_map(|s| s.adherence_7d).collect();
+    if !adherence_values.is_empty() {
+        let overall = adherence_values.iter().sum::<f64>() / adherence_values.len() as f64;
+        out.push_str(&format!(
+            "\nOverall 7-day adherence: {:.0}%",
+            overall * 100.0
+        ));
+    }
+
+    out.trim_end().to_string()
+}
+
+/// Format the as-needed section of `med status`.
+pub fn format_prn_summary(prn: &[PrnSummary]) -> String {
+    let days = prn.first().map(|p| p.days).unwrap_or(7);
+    let mut out = format!("As needed (last {} days)\n", days);
+    for p in prn {
+        let doses = if p.doses_taken == 1 { "dose" } else { "doses" };
+        out.push_str(&format!("  {:<14}{} {}", p.name, p.doses_taken, doses));
+        if let Some(ref ind) = p.most_common_indication {
+            out.push_str(&format!(", mostly for {}", ind));
+        }
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
 /// Format medication status overview.
 pub fn format_med_status(statuses: &[MedStatus], date: chrono::NaiveDate) -> String {
     if statuses.is_empty() {
@@ -228,18 +642,29 @@ pub fn format_med_status(statuses: &[MedStatus], date: chrono::NaiveDate) -> Str
     let mut out = format!("{}\n{}\n", header, separator);
 
     for s in statuses {
-        let taken_display = if let Some(req) = s.required_today {
+        let rest_day = s.required_today == Some(0);
+        let taken_display = if rest_day {
+            format!("no dose today ({})", s.frequency.replace('_', "/"))
+        } else if let Some(req) = s.required_today {
             format!("{}/{} taken today", s.taken_today, req)
         } else {
             format!("{} taken today", s.taken_today)
         };
+        let taken_display = match s.skipped_today {
+            0 => taken_display,
+            n => format!("{}, {} skipped", taken_display, n),
+        };
 
         let adherence_marker = if s.frequency == "as_needed" {
             "(as needed)".to_string()
+        } else if rest_day {
+            String::new()
         } else if let Some(true) = s.adherent_today {
             "OK".to_string()
         } else if let Some(false) = s.adherent_today {
             "MISSED".to_string()
+        } else if s.skipped_today > 0 {
+            "SKIPPED".to_string()
         } else {
             String::new()
         };
@@ -266,6 +691,7 @@ pub fn format_med_status(statuses: &[MedStatus], date: chrono::NaiveDate) -> Str
         .collect();
 
         out.push_str(&format!("  {:<14}{}\n", s.name, parts.join("    ")));
+        out.push_str(&format!("  {:<14}{}\n", "", format_refill(s, date)));
     }
 
     // Overall adherence (exclude as_needed)
@@ -281,6 +707,50 @@ pub fn format_med_status(statuses: &[MedStatus], date: chrono::NaiveDate) -> Str
     out.trim_end().to_string()
 }
 
+/// Describe refill supply, e.g. "Runs out in ~8 days (est. 2026-03-10)".
+fn format_refill(s: &MedStatus, today: chrono::NaiveDate) -> String {
+    let Some(empty) = s
+        .estimated_empty
+        .as_deref()
+        .and_then(|d| d.parse::<chrono::NaiveDate>().ok())
+    else {
+        return "No refill recorded".to_string();
+    };
+    let days = (empty - today).num_days();
+    let remaining = s
+        .estimated_remaining
+        .map(|r| format!(", {} left", r))
+        .unwrap_or_default();
+    if days > 0 {
+        format!("Runs out in ~{} days (est. {}{})", days, empty, remaining)
+    } else {
+        format!("Supply ran out (est. {}{})", empty, remaining)
+    }
+}
+
+/// Format `med history` as one line per dose, newest first.
+pub fn format_med_history(name: &str, history: &[DoseEvent], last_days: u32) -> String {
+    if history.is_empty() {
+        return format!("No doses of {} in the last {} days.", name, last_days);
+    }
+    let mut out = format!("{} \u{2014} last {} days\n", name, last_days);
+    for e in history {
+        let dose = e.dose_used.as_deref().unwrap_or("1 dose");
+        let mut line = format!("  {}  ", e.timestamp.format("%Y-%m-%d %H:%M"));
+        if e.quantity > 1 {
+            line.push_str(&format!("{} × {}", e.quantity, dose));
+        } else {
+            line.push_str(dose);
+        }
+        if let Some(ref note) = e.note {
+            line.push_str(&format!("  # {}", note));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
 /// Format medication stop.
 pub fn format_med_stop(name: &str, reason: Option<&str>) -> String {
     match reason {
@@ -289,28 +759,224 @@ pub fn format_med_stop(name: &str, reason: Option<&str>) -> String {
     }
 }
 
-/// Format anomaly detection results for human display.
-pub fn format_anomaly(result: &AnomalyResult) -> String {
+/// Describe a goal projection in one line, e.g. "reach 75.0 kg by 2026-03-01 (confidence 0.92)".
+pub fn format_projection(p: &Projection, metric_type: &str, units: &Units) -> String {
+    let (target, unit) = crate::core::units::to_display(p.target, metric_type, units);
+    let target = format!("{:.1} {}", target, unit).trim().to_string();
+    match (p.direction.as_str(), p.estimated_date) {
+        ("converging", Some(d)) => match p.confidence {
+            Some(c) => format!("reach {} by {} (confidence {:.2})", target, d, c),
+            None => format!("reach {} by {}", target, d),
+        },
+        ("diverging", _) => format!("moving away from {}", target),
+        ("flat", _) => format!("no progress toward {}", target),
+        ("met", _) => format!("{} reached", target),
+        ("insufficient_data", _) => "not enough data to project".to_string(),
+        _ => "no projection".to_string(),
+    }
+}
+
+/// Whether a goal's trend is on track, e.g. "On track — estimated completion:
+/// 2026-03-15". `None` when the goal is met or there is no trend to judge.
+pub fn format_goal_track(s: &crate::core::goal::GoalStatus) -> Option<String> {
+    let direction = s.eta.as_ref().map(|p| p.direction.as_str());
+    match (s.on_track, s.estimated_completion, direction) {
+        (Some(true), Some(d), _) => Some(format!("On track — estimated completion: {}", d)),
+        (Some(false), _, Some("flat")) => Some("Not on track — trend is flat".to_string()),
+        (Some(false), _, _) => Some("Not on track — trend moving away from goal".to_string()),
+        _ => None,
+    }
+}
+
+/// Banner for a goal an entry just completed, e.g. "🎯 Daily water goal met
+/// (2150/2000 ml)", in display units.
+pub fn format_goal_completed(g: &crate::core::goal::GoalCompleted, units: &Units) -> String {
+    let (current, unit) = crate::core::units::to_display(g.current, &g.metric_type, units);
+    let (target, _) = crate::core::units::to_display(g.target, &g.metric_type, units);
+    let unit = match unit.as_str() {
+        "" | "0-10" | "1-10" | "1-5" => String::new(),
+        u => format!(" {}", u),
+    };
+    format!(
+        "🎯 {}{} {} goal met ({}/{}{})",
+        g.timeframe[..1].to_uppercase(),
+        &g.timeframe[1..],
+        g.metric_type,
+        current,
+        target,
+        unit
+    )
+}
+
+/// `trend --by weekday` as one row per weekday, Monday first.
+pub fn format_weekday_trend(t: &WeekdayTrend, units: &Units) -> String {
+    let unit = crate::core::units::display_unit(&t.metric_type, units);
     let mut out = format!(
-        "=== Anomaly Scan ({} days, {} threshold) ===\n",
-        result.period.days, result.threshold
+        "Trend: {} by weekday ({}, {} to {})\n",
+        t.metric_type, t.aggregation, t.from, t.to
     );
+    for d in &t.weekdays {
+        let day = format!("{}{}", d.weekday[..1].to_uppercase(), &d.weekday[1..3]);
+        let line = match (d.avg, d.min, d.max) {
+            (Some(avg), Some(min), Some(max)) => {
+                let show = |v: f64| crate::core::units::to_display(v, &t.metric_type, units).0;
+                format!(
+                    "  {} | {}: {:.1}  min: {:.1}  max: {:.1}  (n={}) [{}]",
+                    day,
+                    t.aggregation,
+                    show(avg),
+                    show(min),
+                    show(max),
+                    d.count,
+                    unit
+                )
+            }
+            _ => format!("  {} | no data", day),
+        };
+        out.push_str(&format!("\n{}", line));
+    }
+    if let (Some(best), Some(worst)) = (&t.best, &t.worst) {
+        out.push_str(&format!("\n\n  Best: {}  Worst: {}", best, worst));
+    }
+    out
+}
+
+/// Format a correlation matrix as a table with types as row and column headers.
+/// Self-correlations show as `—`, pairs with too little data as `n/a`.
+pub fn format_correlation_matrix(m: &CorrelationMatrix) -> String {
+    if m.types.is_empty() {
+        return "No metrics with data in this window.".to_string();
+    }
+
+    let width = m.types.iter().map(|t| t.chars().count()).max().unwrap_or(0);
+    let col = width.max(5);
+    let mut out = format!("{:width$}", "", width = width);
+    for t in &m.types {
+        out.push_str(&format!("  {:>col$}", t, col = col));
+    }
+    for (i, t) in m.types.iter().enumerate() {
+        out.push_str(&format!("\n{:width$}", t, width = width));
+        for (j, c) in m.coefficients[i].iter().enumerate() {
+            let cell = match c {
+                _ if i == j => "—".to_string(),
+                Some(r) => format!("{:.2}", r),
+                None => "n/a".to_string(),
+            };
+            out.push_str(&format!("  {:>col$}", cell, col = col));
+        }
+    }
+    out
+}
+
+/// Format descriptive statistics as a vertical key-value table per type.
+pub fn format_stats(results: &[TypeStats], user_units: &Units) -> String {
+    if results.is_empty() {
+        return "No entries found.".to_string();
+    }
+
+    let mut sections = Vec::new();
+    for r in results {
+        let s = &r.stats;
+        let mut out = format!("=== {} ===", r.metric_type);
+        if s.count == 0 {
+            out.push_str("\n  No entries");
+            sections.push(out);
+            continue;
+        }
+        let disp = |v: f64| crate::core::units::to_display(v, &r.metric_type, user_units).0;
+        let unit = crate::core::units::display_unit(&r.metric_type, user_units);
+        let std_dev = crate::core::units::to_display_rate(s.std_dev, &r.metric_type, user_units);
+        let rows = [
+            ("mean", disp(s.mean)),
+            ("median", disp(s.median)),
+            ("std_dev", std_dev),
+            ("min", disp(s.min)),
+            ("p25", disp(s.p25)),
+            ("p75", disp(s.p75)),
+            ("p95", disp(s.p95)),
+            ("max", disp(s.max)),
+        ];
+        out.push_str(&format!("\n  {:8} {}", "count", s.count));
+        for (k, v) in rows {
+            out.push_str(&format!("\n  {:8} {:.2}", k, v));
+        }
+        if !unit.is_empty() {
+            out.push_str(&format!("\n  {:8} {}", "unit", unit));
+        }
+        sections.push(out);
+    }
+    sections.join("\n\n")
+}
+
+/// Statistics footer for `show --stats`. `label` names the type when several
+/// are shown together.
+pub fn format_show_statistics(
+    label: Option<&str>,
+    metric_type: &str,
+    s: &SummaryStats,
+    user_units: &Units,
+) -> String {
+    let title = match label {
+        Some(l) => format!("── Statistics: {} (N={}) ", l, s.count),
+        None => format!("── Statistics (N={}) ", s.count),
+    };
+    let width = title.chars().count().max(34);
+    let header = format!("{}{}", title, "─".repeat(width - title.chars().count()));
+    let disp = |v: f64| crate::core::units::to_display(v, metric_type, user_units).0;
+    let unit = crate::core::units::display_unit(metric_type, user_units);
+    let std_dev = crate::core::units::to_display_rate(s.std_dev, metric_type, user_units);
+    let mean = if unit.is_empty() {
+        format!("{:.1}", disp(s.mean))
+    } else {
+        format!("{:.1} {}", disp(s.mean), unit)
+    };
+    format!(
+        "{}\nMean: {}  Min: {:.1}  Max: {:.1}  Std: {:.1}",
+        header,
+        mean,
+        disp(s.min),
+        disp(s.max),
+        std_dev
+    )
+}
+
+/// Format `insights` findings, most severe first.
+pub fn format_insights(result: &InsightsResult) -> String {
+    let mut out = format!("=== Insights {} to {} ===\n", result.from, result.to);
+    if result.insights.is_empty() {
+        out.push_str("\nNothing notable in this period.");
+        return out;
+    }
+    for i in &result.insights {
+        let marker = match i.severity {
+            InsightSeverity::Warning => "!!",
+            InsightSeverity::Notice => "! ",
+            InsightSeverity::Info => "- ",
+        };
+        out.push_str(&format!("\n{} {}", marker, i.message));
+    }
+    out
+}
+
+/// Format anomaly detection results for human display.
+pub fn format_anomaly(result: &AnomalyResult) -> String {
+    let mut out = anomaly_header(result);
 
     if result.anomalies.is_empty() {
         out.push_str(&format!("\n{}", result.summary));
         return out;
     }
 
-    for a in &result.anomalies {
-        let severity_marker = match a.severity {
-            Severity::Alert => "!!!",
-            Severity::Warning => "!!",
-            Severity::Info => "!",
-        };
-        out.push_str(&format!(
-            "\n{} {} {:.1} (typical: {:.1}-{:.1}, {})",
-            severity_marker, a.metric_type, a.value, a.baseline.q1, a.baseline.q3, a.deviation,
-        ));
+    // Most severe first
+    let mut sorted: Vec<_> = result.anomalies.iter().collect();
+    sorted.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(b.score.total_cmp(&a.score))
+    });
+
+    for a in sorted {
+        out.push_str(&format!("\n{}", anomaly_line(a, result.method)));
     }
 
     out.push_str(&format!("\n\n{}", result.summary));
@@ -322,6 +988,66 @@ pub fn format_anomaly(result: &AnomalyResult) -> String {
     out
 }
 
+/// `anomaly --all`: one section per type with anomalies, most severe type
+/// first; types without any are left out.
+pub fn format_anomaly_groups(result: &AnomalyResult) -> String {
+    let mut out = anomaly_header(result);
+    for g in &result.by_type {
+        out.push_str(&format!("\n--- {} ---", g.metric_type));
+        for a in &g.anomalies {
+            out.push_str(&format!("\n{}", anomaly_line(a, result.method)));
+        }
+        out.push('\n');
+    }
+    out.push_str(&format!("\n{}", result.summary));
+    if !result.low_variance_types.is_empty() {
+        out.push_str(&format!(
+            "\nSkipped (no variation): {}",
+            result.low_variance_types.join(", ")
+        ));
+    }
+    out
+}
+
+fn anomaly_header(result: &AnomalyResult) -> String {
+    match result.method {
+        Method::Iqr => format!(
+            "=== Anomaly Scan ({} days, {} threshold) ===\n",
+            result.period.days, result.threshold
+        ),
+        method => format!(
+            "=== Anomaly Scan ({} days, {} threshold, {}) ===\n",
+            result.period.days, result.threshold, method
+        ),
+    }
+}
+
+/// One anomaly, marked `!`, `!!` or `!!!` by severity.
+fn anomaly_line(a: &Anomaly, method: Method) -> String {
+    let severity_marker = match a.severity {
+        Severity::Severe => "!!!",
+        Severity::Moderate => "!!",
+        Severity::Mild => "!",
+    };
+    match method {
+        Method::Iqr => format!(
+            "{} {} {:.1} (typical: {:.1}-{:.1}, {})",
+            severity_marker, a.metric_type, a.value, a.baseline.q1, a.baseline.q3, a.deviation,
+        ),
+        _ => format!(
+            "{} {} {:.1} on {} (expected: {:.1}-{:.1}, {}, z={:.1})",
+            severity_marker,
+            a.metric_type,
+            a.value,
+            a.timestamp.format("%Y-%m-%d"),
+            a.bounds.lower,
+            a.bounds.upper,
+            a.deviation,
+            a.score,
+        ),
+    }
+}
+
 /// Format health context briefing for human display.
 pub fn format_context(result: &ContextResult) -> String {
     let mut out = format!(
@@ -331,6 +1057,23 @@ pub fn format_context(result: &ContextResult) -> String {
 
     out.push_str(&format!("\n{}\n", result.summary));
 
+    // Profile
+    if let Some(ref p) = result.profile {
+        out.push_str("\n--- Profile ---\n");
+        if let Some(h) = p.height_cm {
+            out.push_str(&format!("  Height: {} cm\n", h));
+        }
+        if let Some(w) = p.latest_weight_kg {
+            out.push_str(&format!("  Weight: {} kg\n", w));
+        }
+        if let (Some(b), Some(c)) = (p.bmi, p.bmi_category) {
+            out.push_str(&format!("  BMI: {} ({})\n", b, c));
+        }
+        if !p.conditions.is_empty() {
+            out.push_str(&format!("  Conditions: {}\n", p.conditions.join(", ")));
+        }
+    }
+
     // Metrics
     if !result.metrics.is_empty() {
         out.push_str("\n--- Metrics ---\n");
@@ -342,18 +1085,36 @@ pub fn format_context(result: &ContextResult) -> String {
         }
     }
 
+    if let Some(ref c) = result.cycle {
+        out.push_str(&format!("\n--- Cycle ---\n  {}\n", format_cycle_status(c)));
+    }
+
     // Goals
     if !result.goals.is_empty() {
         out.push_str("\n--- Goals ---\n");
         for g in &result.goals {
             let status = if g.is_met { "MET" } else { "..." };
-            out.push_str(&format!("  [{}] {}\n", status, g.summary));
+            match &g.progress {
+                Some(p) if !g.is_met => {
+                    out.push_str(&format!("  [{}] {} — {}\n", status, g.summary, p))
+                }
+                _ => out.push_str(&format!("  [{}] {}\n", status, g.summary)),
+            }
         }
     }
 
     // Medications
     if let Some(ref meds) = result.medications {
         out.push_str(&format!("\n--- Medications ---\n  {}\n", meds.summary));
+        for c in &meds.changes {
+            match &c.reason {
+                Some(r) => out.push_str(&format!(
+                    "  {} {} on {} ({})\n",
+                    c.change, c.name, c.date, r
+                )),
+                None => out.push_str(&format!("  {} {} on {}\n", c.change, c.name, c.date)),
+            }
+        }
     }
 
     // Streaks
@@ -374,3 +1135,288 @@ pub fn format_context(result: &ContextResult) -> String {
 
     out.trim_end().to_string()
 }
+
+/// "avg 6.2 mmol/L, 75% in range (1 low, 0 high) over 4 readings", in display units.
+pub fn format_glucose_day(g: &GlucoseDay, units: &Units) -> String {
+    format_glucose(
+        g.average,
+        g.in_range_pct,
+        g.lows,
+        g.highs,
+        g.readings,
+        units,
+    )
+}
+
+/// Period time-in-range summary, like [`format_glucose_day`].
+pub fn format_time_in_range(t: &TimeInRange, units: &Units) -> String {
+    format_glucose(
+        t.average,
+        t.in_range_pct,
+        t.lows,
+        t.highs,
+        t.readings,
+        units,
+    )
+}
+
+fn format_glucose(
+    average: f64,
+    in_range_pct: f64,
+    lows: u32,
+    highs: u32,
+    readings: u32,
+    units: &Units,
+) -> String {
+    let (avg, unit) = crate::core::units::to_display(average, "glucose", units);
+    format!(
+        "avg {} {}, {}% in range ({} low, {} high) over {} reading(s)",
+        avg, unit, in_range_pct, lows, highs, readings
+    )
+}
+
+/// One-line cycle summary, e.g. "Day 12 (avg 28.5 days over 6 cycles); next expected 2026-03-01 (in 17 days)".
+pub fn format_cycle_status(c: &CycleStatus) -> String {
+    let Some(day) = c.current_day else {
+        return "No cycles logged".to_string();
+    };
+    let mut out = format!("Day {}", day);
+    if let Some(avg) = c.average_length {
+        out.push_str(&format!(
+            " (avg {} days over {} cycle(s))",
+            avg,
+            c.recent_lengths.len()
+        ));
+    }
+    if let (Some(next), Some(days)) = (c.predicted_next_start, c.days_until_next) {
+        let when = match days {
+            0 => "today".to_string(),
+            d if d > 0 => format!("in {} days", d),
+            d => format!("{} days late", -d),
+        };
+        out.push_str(&format!("; next expected {} ({})", next, when));
+    } else {
+        out.push_str("; log more cycles to predict the next start");
+    }
+    out
+}
+
+/// One workout, e.g. "2026-03-02 run | 45 min, 7.5 km, effort 7/10 (intervals)".
+pub fn format_workout_session(s: &WorkoutSession, units: &Units) -> String {
+    let mut parts = Vec::new();
+    if let Some(d) = s.duration {
+        parts.push(format!("{} min", d));
+    }
+    if let Some(d) = s.distance {
+        let (v, unit) = crate::core::units::to_display(d, &workout::distance_type(&s.kind), units);
+        let unit = if unit.is_empty() {
+            workout::DISTANCE_UNIT.to_string()
+        } else {
+            unit
+        };
+        parts.push(format!("{} {}", v, unit));
+    }
+    if let Some(i) = s.intensity {
+        parts.push(format!("effort {}/10", i));
+    }
+    let mut out = format!("{} {} | {}", s.date, s.kind, parts.join(", "));
+    if let Some(note) = &s.note {
+        out.push_str(&format!(" ({})", note));
+    }
+    out
+}
+
+/// Weekly workout totals, e.g. "week of 2026-03-02 | 3 session(s), 135 min, 21.5 km".
+pub fn format_workout_week(w: &WorkoutWeek) -> String {
+    format!(
+        "week of {} | {} session(s), {:.0} min, {:.1} {}",
+        w.week_start,
+        w.sessions,
+        w.total_duration,
+        w.total_distance,
+        workout::DISTANCE_UNIT
+    )
+}
+
+/// One medication's report line, e.g.
+/// "metformin        | 86% adherent, 24/28 doses (started 2026-03-04)".
+pub fn format_report_medication(m: &crate::core::med::MedPeriodSummary) -> String {
+    let summary = match (m.adherence, m.doses_expected) {
+        (Some(a), Some(expected)) => format!(
+            "{:.0}% adherent, {}/{} doses",
+            a * 100.0,
+            m.doses_taken,
+            expected
+        ),
+        (None, Some(expected)) => format!("{}/{} doses", m.doses_taken, expected),
+        _ => format!("as needed, {} dose(s)", m.doses_taken),
+    };
+    let mut changes = Vec::new();
+    if let Some(d) = m.started {
+        changes.push(format!("started {}", d));
+    }
+    if let Some(d) = m.stopped {
+        changes.push(format!("stopped {}", d));
+    }
+    let changes = if changes.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", changes.join(", "))
+    };
+    format!("{:16} | {}{}", m.name, summary, changes)
+}
+
+/// `weight-history` as a table of date, weight, BMI and BMI category.
+pub fn format_weight_history(entries: &[MetricWithBmi], units: &Units, tz: Tz) -> String {
+    let mut out = format!(
+        "{:10}  {:>10}  {:>5}  {}",
+        "date", "weight", "bmi", "category"
+    );
+    for e in entries {
+        let (weight, unit) = crate::core::units::to_display(e.metric.value, "weight", units);
+        out.push_str(&format!(
+            "\n{:10}  {:>10}  {:>5.1}  {}",
+            crate::core::time::local_date(&e.metric.timestamp, tz),
+            format!("{:.1} {}", weight, unit),
+            e.bmi,
+            e.bmi_category
+        ));
+    }
+    out
+}
+
+/// Byte count with a binary unit, e.g. "1.5 MiB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Format `db stats` as tables of row counts.
+pub fn format_db_stats(stats: &DbStats, path: &std::path::Path) -> String {
+    let mut out = format!("Database: {}\n", path.display());
+    out.push_str(&format!(
+        "  Size: {} ({} free)\n  Schema version: {}\n  Encrypted: {}\n",
+        format_bytes(stats.size_bytes),
+        format_bytes(stats.free_bytes),
+        stats.schema_version,
+        if stats.encrypted { "yes" } else { "no" }
+    ));
+    if let (Some(earliest), Some(latest)) = (stats.earliest, stats.latest) {
+        out.push_str(&format!(
+            "  Data range: {} to {}\n",
+            earliest.format("%Y-%m-%d"),
+            latest.format("%Y-%m-%d")
+        ));
+    }
+
+    out.push_str(&format!("\n  {:<22}{:>10}\n", "Table", "Rows"));
+    for t in &stats.tables {
+        out.push_str(&format!("  {:<22}{:>10}\n", t.table, t.rows));
+    }
+
+    if !stats.metric_types.is_empty() {
+        out.push_str(&format!(
+            "\n  {:<22}{:>10}  {:<10}  {}\n",
+            "Type", "Entries", "First", "Last"
+        ));
+        for t in &stats.metric_types {
+            out.push_str(&format!(
+                "  {:<22}{:>10}  {:<10}  {}\n",
+                t.metric_type,
+                t.count,
+                t.earliest.format("%Y-%m-%d"),
+                t.latest.format("%Y-%m-%d")
+            ));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+fn push_type_rows(out: &mut String, title: &str, rows: &[TypeInfo]) {
+    out.push_str(&format!("{}:\n", title));
+    if rows.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for t in rows {
+        out.push_str(&format!(
+            "  {:<20} {:<8} {:<10} {:>7}  {}\n",
+            t.name,
+            t.unit,
+            t.category,
+            t.entries,
+            t.aliases.join(", ")
+        ));
+    }
+}
+
+/// Format the `types list` registry as per-kind tables.
+pub fn format_types_list(list: &TypesList) -> String {
+    let mut out = format!(
+        "  {:<20} {:<8} {:<10} {:>7}  {}\n",
+        "Type", "Unit", "Category", "Entries", "Aliases"
+    );
+    push_type_rows(&mut out, "Built-in types", &list.builtin);
+    push_type_rows(&mut out, "Custom types", &list.custom);
+    if !list.observed.is_empty() {
+        push_type_rows(&mut out, "Observed (undefined) types", &list.observed);
+    }
+    if !list.medications.is_empty() {
+        out.push_str("Medications:\n");
+        for m in &list.medications {
+            out.push_str(&format!(
+                "  {:<20} {:<19} {:>7}  {}\n",
+                m.name,
+                m.dose.as_deref().unwrap_or(""),
+                m.entries,
+                if m.active { "" } else { "(stopped)" }
+            ));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// Format `types list --type` detail for one type.
+pub fn format_type_detail(d: &TypeDetail) -> String {
+    let mut out = format!("{} ({}, {})\n", d.name, d.kind, d.category);
+    if !d.unit.is_empty() {
+        out.push_str(&format!("  Unit: {}\n", d.unit));
+    }
+    if !d.aliases.is_empty() {
+        out.push_str(&format!("  Aliases: {}\n", d.aliases.join(", ")));
+    }
+    out.push_str(&format!("  Entries: {}\n", d.entries));
+    if let (Some(first), Some(last)) = (d.first, d.last) {
+        out.push_str(&format!(
+            "  Range: {} to {}\n",
+            first.format("%Y-%m-%d"),
+            last.format("%Y-%m-%d")
+        ));
+    }
+    if let (Some(min), Some(max)) = (d.min, d.max) {
+        out.push_str(&format!("  Min: {}  Max: {}\n", min, max));
+    }
+    if d.units.len() > 1 {
+        let units: Vec<String> = d
+            .units
+            .iter()
+            .map(|u| {
+                format!(
+                    "{} ({})",
+                    if u.unit.is_empty() { "-" } else { &u.unit },
+                    u.entries
+                )
+            })
+            .collect();
+        out.push_str(&format!("  Stored units: {}\n", units.join(", ")));
+    }
+    out.trim_end().to_string()
+}
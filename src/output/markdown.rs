@@ -0,0 +1,111 @@
+use crate::core::report::{ReportGoal, ReportResult};
+use crate::core::units::to_display;
+use crate::models::config::Units;
+
+/// Render a report as a GitHub-flavored Markdown document: a metrics table,
+/// the comparison with the previous period when present, and goals as a checklist.
+pub fn format_report(report: &ReportResult, units: &Units) -> String {
+    let mut out = String::from("## OpenVital Report\n\n");
+    out.push_str(&format!("**Period:** {} to {}", report.from, report.to));
+    if let Some(prev) = &report.compared_to {
+        out.push_str(&format!(" (compared with {} to {})", prev.from, prev.to));
+    }
+    out.push_str(&format!(
+        "  \n**Days with entries:** {} · **Total entries:** {}\n",
+        report.days_with_entries, report.total_entries
+    ));
+    if !report.tags.is_empty() {
+        out.push_str(&format!("**Tags:** {}\n", report.tags.join(", ")));
+    }
+
+    out.push_str("\n### Metrics\n\n");
+    let logged: Vec<_> = report.metrics.iter().filter(|m| m.count > 0).collect();
+    if logged.is_empty() {
+        out.push_str("No data in this period.\n");
+    } else {
+        out.push_str("| Metric | Avg | Min | Max | Value | Unit | n |\n");
+        out.push_str("|---|---:|---:|---:|---:|---|---:|\n");
+        for m in logged {
+            let (avg, _) = to_display(m.avg, &m.metric_type, units);
+            let (min, _) = to_display(m.min, &m.metric_type, units);
+            let (max, _) = to_display(m.max, &m.metric_type, units);
+            let (value, unit) = to_display(m.value, &m.metric_type, units);
+            out.push_str(&format!(
+                "| {} | {:.1} | {:.1} | {:.1} | {:.1} ({}) | {} | {} |\n",
+                escape(&m.metric_type),
+                avg,
+                min,
+                max,
+                value,
+                m.aggregation,
+                escape(&unit),
+                m.count
+            ));
+        }
+    }
+
+    if !report.comparison.is_empty() {
+        out.push_str("\n### Compared with prior period\n\n");
+        for c in &report.comparison {
+            out.push_str(&format!(
+                "- {}\n",
+                escape(&super::human::format_metric_comparison(c, units))
+            ));
+        }
+    }
+
+    if let Some(goals) = &report.goals {
+        out.push_str(&format!(
+            "\n### Goals ({}/{} met)\n\n",
+            goals.goals_met, goals.goals_total
+        ));
+        if goals.goals.is_empty() {
+            out.push_str("No active goals.\n");
+        }
+        for g in &goals.goals {
+            out.push_str(&goal_item(g, units));
+        }
+    }
+    out
+}
+
+/// `- [x] Weight below 85 kg (daily)`, with the current value when unmet.
+fn goal_item(g: &ReportGoal, units: &Units) -> String {
+    let mut name = g.metric_type.replace('_', " ");
+    if let Some(first) = name.get(..1) {
+        name = first.to_uppercase() + &name[1..];
+    }
+    let (target, unit) = to_display(g.target_value, &g.metric_type, units);
+    let target = format!("{} {}", (target * 10.0).round() / 10.0, escape(&unit));
+    let mut line = format!(
+        "- [{}] {} {} {} ({})",
+        if g.is_met { "x" } else { " " },
+        escape(&name),
+        g.direction,
+        target.trim_end(),
+        g.timeframe
+    );
+    if !g.is_met {
+        match g.current_value {
+            Some(v) => {
+                let (current, _) = to_display(v, &g.metric_type, units);
+                line.push_str(&format!(" — currently {:.1} {}", current, escape(&unit)));
+            }
+            None => line.push_str(" — no data"),
+        }
+    }
+    line.push('\n');
+    line
+}
+
+/// Escape characters that would break a table cell or start inline markup.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '|' | '*' | '_' | '`' | '[' | ']' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -1,12 +1,18 @@
+pub mod color;
+pub mod digest;
 pub mod human;
+pub mod markdown;
 
 use serde_json::{Value, json};
 
-/// Standard JSON envelope per spec section 5.3.
+use crate::models::config::Config;
+
+/// Standard JSON envelope per spec section 5.3, naming the active profile.
 pub fn success(command: &str, data: Value) -> Value {
     json!({
         "status": "ok",
         "command": command,
+        "profile": Config::active_profile(),
         "data": data,
         "error": null
     })
@@ -16,6 +22,7 @@ pub fn error(command: &str, code: &str, message: &str) -> Value {
     json!({
         "status": "error",
         "command": command,
+        "profile": Config::active_profile(),
         "data": null,
         "error": {
             "code": code,
@@ -2,7 +2,7 @@ mod common;
 
 use chrono::{Duration, Local};
 use openvital::core::anomaly;
-use openvital::models::anomaly::{Severity, Threshold};
+use openvital::models::anomaly::{Method, Severity, Threshold};
 
 #[test]
 fn test_anomaly_detect_flags_outlier() {
@@ -25,7 +25,7 @@ fn test_anomaly_detect_flags_outlier() {
     assert_eq!(result.anomalies[0].metric_type, "heart_rate");
     assert!(matches!(
         result.anomalies[0].severity,
-        Severity::Warning | Severity::Alert
+        Severity::Moderate | Severity::Severe
     ));
 }
 
@@ -281,3 +281,321 @@ fn test_anomaly_clean_types_populated() {
     let result = anomaly::detect(&db, None, 30, Threshold::Moderate).unwrap();
     assert!(result.clean_types.contains(&"weight".to_string()));
 }
+
+#[test]
+fn test_anomaly_default_method_is_iqr() {
+    let (_dir, db) = common::setup_db();
+    let result = anomaly::detect(&db, None, 30, Threshold::Moderate).unwrap();
+    assert_eq!(result.method, Method::Iqr);
+    assert!(result.window_days.is_none());
+}
+
+#[test]
+fn test_anomaly_zscore_flags_outlier_with_range_and_score() {
+    let (_dir, db) = common::setup_db();
+    let today = Local::now().date_naive();
+
+    for i in 1..=14 {
+        let date = today - Duration::days(i);
+        let m = common::make_metric("heart_rate", 70.0 + (i % 5) as f64, date);
+        db.insert_metric(&m).unwrap();
+    }
+    db.insert_metric(&common::make_metric("heart_rate", 95.0, today))
+        .unwrap();
+
+    let result = anomaly::detect_with(
+        &db,
+        Some("heart_rate"),
+        30,
+        Threshold::Moderate,
+        Method::Zscore,
+        7,
+    )
+    .unwrap();
+    assert_eq!(result.method, Method::Zscore);
+    assert_eq!(result.anomalies.len(), 1);
+    let a = &result.anomalies[0];
+    assert_eq!(a.deviation, "above");
+    assert!(a.bounds.upper < 95.0);
+    assert!(a.bounds.lower < a.baseline.mean && a.baseline.mean < a.bounds.upper);
+    assert!(a.score > Threshold::Moderate.z_limit());
+    assert_eq!(a.severity, Severity::Severe);
+}
+
+#[test]
+fn test_anomaly_zscore_normal_value_not_flagged() {
+    let (_dir, db) = common::setup_db();
+    let today = Local::now().date_naive();
+
+    for i in 1..=14 {
+        let date = today - Duration::days(i);
+        let m = common::make_metric("weight", 80.0 + (i % 3) as f64, date);
+        db.insert_metric(&m).unwrap();
+    }
+    db.insert_metric(&common::make_metric("weight", 81.0, today))
+        .unwrap();
+
+    let result = anomaly::detect_with(
+        &db,
+        Some("weight"),
+        30,
+        Threshold::Moderate,
+        Method::Zscore,
+        7,
+    )
+    .unwrap();
+    assert!(result.anomalies.is_empty());
+    assert_eq!(result.clean_types, vec!["weight".to_string()]);
+}
+
+/// Scenario: a spike ten days ago is judged against the week before it,
+/// even though nothing unusual was logged today.
+#[test]
+fn test_anomaly_rolling_flags_past_spike() {
+    let (_dir, db) = common::setup_db();
+    let today = Local::now().date_naive();
+
+    for i in 0..=20 {
+        let date = today - Duration::days(i);
+        let value = if i == 10 {
+            120.0
+        } else {
+            70.0 + (i % 3) as f64
+        };
+        db.insert_metric(&common::make_metric("heart_rate", value, date))
+            .unwrap();
+    }
+
+    let iqr = anomaly::detect(&db, Some("heart_rate"), 30, Threshold::Moderate).unwrap();
+    assert!(iqr.anomalies.is_empty(), "today is normal under iqr");
+
+    let result = anomaly::detect_with(
+        &db,
+        Some("heart_rate"),
+        30,
+        Threshold::Moderate,
+        Method::Rolling,
+        7,
+    )
+    .unwrap();
+    assert_eq!(result.window_days, Some(7));
+    assert_eq!(result.anomalies.len(), 1);
+    assert_eq!(result.anomalies[0].value, 120.0);
+    assert_eq!(
+        result.anomalies[0].timestamp.date_naive(),
+        today - Duration::days(10)
+    );
+}
+
+#[test]
+fn test_anomaly_method_parse() {
+    assert_eq!("zscore".parse::<Method>().unwrap(), Method::Zscore);
+    assert_eq!("rolling".parse::<Method>().unwrap(), Method::Rolling);
+    let err = "mad".parse::<Method>().unwrap_err().to_string();
+    assert!(err.contains("expected iqr/zscore/rolling"));
+}
+
+#[test]
+fn test_detect_iqr_anomalies_flags_clear_outlier() {
+    use openvital::core::analytics::detect_iqr_anomalies;
+
+    let values = [1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 4.0, 4.0, 100.0];
+    let flags = detect_iqr_anomalies(&values);
+    assert_eq!(flags.iter().filter(|f| **f).count(), 1);
+    assert!(flags[8]);
+
+    // z-score agrees here: 100 sits ~2.7 sample standard deviations out
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let sd = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    assert!((100.0 - mean) / sd > Threshold::Moderate.z_limit());
+}
+
+#[test]
+fn test_detect_iqr_anomalies_symmetric_data_clean() {
+    use openvital::core::analytics::{detect_iqr_anomalies, detect_iqr_anomalies_with};
+
+    let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+    assert!(detect_iqr_anomalies(&values).iter().all(|f| !f));
+
+    // Q1 7, Q3 11: 20 is past the 1.5x fence (17) but inside the 3.0x one (23)
+    let values = [5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 20.0];
+    assert!(detect_iqr_anomalies_with(&values, 1.5)[8]);
+    assert!(!detect_iqr_anomalies_with(&values, 3.0)[8]);
+}
+
+#[test]
+fn test_anomaly_numeric_threshold_multiplier() {
+    assert!(matches!(
+        "3".parse::<Threshold>().unwrap(),
+        Threshold::Custom(k) if k == 3.0
+    ));
+    assert!("0".parse::<Threshold>().is_err());
+    assert!("loose".parse::<Threshold>().is_err());
+
+    let (_dir, db) = common::setup_db();
+    let today = Local::now().date_naive();
+    for i in 1..=14 {
+        let date = today - Duration::days(i);
+        db.insert_metric(&common::make_metric(
+            "heart_rate",
+            70.0 + (i % 7) as f64,
+            date,
+        ))
+        .unwrap();
+    }
+    // Q1 71.25, Q3 74.75, IQR 3.5: 82 clears the 1.5x fence (80) but not 3.0x (85.25)
+    db.insert_metric(&common::make_metric("heart_rate", 82.0, today))
+        .unwrap();
+
+    let tukey = anomaly::detect(&db, None, 30, Threshold::Custom(1.5)).unwrap();
+    assert_eq!(tukey.anomalies.len(), 1);
+    let far = anomaly::detect(&db, None, 30, Threshold::Custom(3.0)).unwrap();
+    assert!(far.anomalies.is_empty());
+    assert_eq!(serde_json::to_value(&far).unwrap()["threshold"], 3.0);
+    assert_eq!(serde_json::to_value(&far).unwrap()["method"], "iqr");
+}
+
+#[test]
+fn test_anomaly_severity_levels_reachable() {
+    // Baseline Q1 71.25, Q3 74.75, IQR 3.5
+    for (value, expected) in [
+        (82.0, Severity::Mild),     // 2.1 IQRs past Q3
+        (87.0, Severity::Moderate), // 3.5
+        (95.0, Severity::Severe),   // 5.8
+    ] {
+        let (_dir, db) = common::setup_db();
+        let today = Local::now().date_naive();
+        for i in 1..=14 {
+            let date = today - Duration::days(i);
+            db.insert_metric(&common::make_metric(
+                "heart_rate",
+                70.0 + (i % 7) as f64,
+                date,
+            ))
+            .unwrap();
+        }
+        db.insert_metric(&common::make_metric("heart_rate", value, today))
+            .unwrap();
+
+        let result = anomaly::detect(&db, None, 30, Threshold::Moderate).unwrap();
+        assert_eq!(result.anomalies.len(), 1);
+        assert_eq!(result.anomalies[0].severity, expected, "value {}", value);
+    }
+}
+
+#[test]
+fn test_classify_severity_none_for_normal_values() {
+    use openvital::core::analytics::{classify_iqr, classify_zscore};
+
+    let values = [1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 4.0, 4.0, 100.0];
+    let iqr = classify_iqr(&values, 1.5);
+    assert!(iqr[..8].iter().all(|c| c.severity.is_none()));
+    assert_eq!(iqr[8].severity, Some(Severity::Severe));
+
+    let z = classify_zscore(&values, Threshold::Moderate.z_limit());
+    assert!(z[..8].iter().all(|c| c.severity.is_none()));
+    assert_eq!(z[8].severity, Some(Severity::Mild));
+    assert_eq!(
+        serde_json::to_value(&z[8]).unwrap()["severity"],
+        serde_json::json!("mild")
+    );
+}
+
+#[test]
+fn test_anomaly_glucose_outside_safe_range_is_severe() {
+    let (_dir, db) = common::setup_db();
+    let today = Local::now().date_naive();
+
+    // A swingy baseline wide enough that 262 mg/dL is statistically unremarkable
+    for (i, v) in [100.0, 260.0, 110.0, 270.0, 105.0, 255.0, 115.0, 265.0]
+        .into_iter()
+        .enumerate()
+    {
+        let date = today - Duration::days(i as i64 + 1);
+        db.insert_metric(&common::make_metric("glucose", v, date))
+            .unwrap();
+    }
+    db.insert_metric(&common::make_metric("glucose", 262.0, today))
+        .unwrap();
+
+    for method in [Method::Iqr, Method::Zscore] {
+        let result = anomaly::detect_with(
+            &db,
+            Some("glucose"),
+            30,
+            Threshold::Moderate,
+            method,
+            anomaly::DEFAULT_ROLLING_WINDOW,
+        )
+        .unwrap();
+        assert_eq!(result.anomalies.len(), 1, "{:?}", method);
+        let a = &result.anomalies[0];
+        assert_eq!(a.severity, Severity::Severe);
+        assert_eq!(a.deviation, "above");
+        assert!(a.summary.contains("safe range"), "{}", a.summary);
+    }
+}
+
+#[test]
+fn test_anomaly_detect_all_groups_by_type() {
+    use openvital::models::metric::Category;
+
+    let (_dir, db) = common::setup_db();
+    let today = Local::now().date_naive();
+
+    for i in 1..=14 {
+        let date = today - Duration::days(i);
+        db.insert_metric(&common::make_metric(
+            "heart_rate",
+            70.0 + (i % 7) as f64,
+            date,
+        ))
+        .unwrap();
+        db.insert_metric(&common::make_metric(
+            "weight",
+            80.0 + (i % 7) as f64 * 0.1,
+            date,
+        ))
+        .unwrap();
+        // Never varies: skipped instead of judged against a fallback spread
+        db.insert_metric(&common::make_metric("pain", 3.0, date))
+            .unwrap();
+        let mut dose = common::make_metric("ibuprofen", 1.0, date);
+        dose.category = Category::Medication;
+        db.insert_metric(&dose).unwrap();
+    }
+    db.insert_metric(&common::make_metric("heart_rate", 130.0, today))
+        .unwrap();
+    db.insert_metric(&common::make_metric("weight", 81.5, today))
+        .unwrap();
+    db.insert_metric(&common::make_metric("pain", 3.0, today))
+        .unwrap();
+    let mut dose = common::make_metric("ibuprofen", 2.0, today);
+    dose.category = Category::Medication;
+    db.insert_metric(&dose).unwrap();
+
+    let result = anomaly::detect_all(&db, 30, Threshold::Moderate, Method::Iqr, 7, false).unwrap();
+    let groups: Vec<&str> = result
+        .by_type
+        .iter()
+        .map(|g| g.metric_type.as_str())
+        .collect();
+    assert_eq!(groups, ["heart_rate", "weight"]);
+    assert_eq!(result.by_type[0].anomalies[0].severity, Severity::Severe);
+    assert!(result.anomalies[0].severity >= result.anomalies[1].severity);
+    assert_eq!(result.low_variance_types, ["pain"]);
+    assert!(!result.scanned_types.contains(&"ibuprofen".to_string()));
+
+    let human = openvital::output::human::format_anomaly_groups(&result);
+    assert!(human.contains("--- heart_rate ---"));
+    assert!(!human.contains("--- pain ---"));
+
+    let result = anomaly::detect_all(&db, 30, Threshold::Moderate, Method::Iqr, 7, true).unwrap();
+    assert!(result.scanned_types.contains(&"ibuprofen".to_string()));
+
+    // Without --all the scan is unchanged: medications and flat types are judged
+    let result = anomaly::detect(&db, None, 30, Threshold::Moderate).unwrap();
+    assert!(result.by_type.is_empty());
+    assert!(result.scanned_types.contains(&"pain".to_string()));
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::backup;
+use openvital::db::{Database, SCHEMA_VERSION, SortOrder};
+
+fn seeded() -> (tempfile::TempDir, Database) {
+    let (dir, db) = common::setup_db();
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    db.insert_metric(&common::make_metric("weight", 80.0, date))
+        .unwrap();
+    (dir, db)
+}
+
+/// Scenario: A plain backup restores into a fresh location with its data intact
+#[test]
+fn test_backup_and_restore_roundtrip() {
+    let (dir, db) = seeded();
+    let dest = dir.path().join("backups").join("snap.db");
+
+    let result = backup::backup(&db, &dest, false).unwrap();
+    assert!(dest.exists());
+    assert_eq!(result.size_bytes, std::fs::metadata(&dest).unwrap().len());
+    assert!(!result.compressed);
+
+    let target = dir.path().join("restored").join("data.db");
+    let restored = backup::restore(&dest, &target, None).unwrap();
+    assert_eq!(restored.schema_version, SCHEMA_VERSION);
+
+    let db2 = Database::open(&target).unwrap();
+    let rows = db2
+        .query_by_type("weight", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].value, 80.0);
+}
+
+/// Scenario: Compressed backups are gzip files that restore transparently
+#[test]
+fn test_backup_compressed_roundtrip() {
+    let (dir, db) = seeded();
+    let dest = dir.path().join("snap.db.gz");
+
+    backup::backup(&db, &dest, true).unwrap();
+    let bytes = std::fs::read(&dest).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b], "gzip magic");
+    assert!(!dir.path().join("snap.db.gz.tmp").exists());
+
+    let target = dir.path().join("data.db");
+    backup::restore(&dest, &target, None).unwrap();
+    let db2 = Database::open(&target).unwrap();
+    assert_eq!(
+        db2.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+/// Scenario: Restoring garbage leaves the existing database untouched
+#[test]
+fn test_restore_rejects_non_sqlite_file() {
+    let (dir, db) = seeded();
+    drop(db);
+    let bogus = dir.path().join("bogus.db");
+    std::fs::write(&bogus, "definitely not sqlite").unwrap();
+
+    let target = dir.path().join("test.db");
+    let before = std::fs::read(&target).unwrap();
+    assert!(backup::restore(&bogus, &target, None).is_err());
+    assert_eq!(std::fs::read(&target).unwrap(), before);
+    assert!(!dir.path().join("test.db.restore").exists());
+}
+
+/// Scenario: A backup from a newer schema is refused
+#[test]
+fn test_restore_rejects_newer_schema() {
+    let (dir, db) = seeded();
+    let dest = dir.path().join("snap.db");
+    backup::backup(&db, &dest, false).unwrap();
+    let conn = rusqlite::Connection::open(&dest).unwrap();
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+        .unwrap();
+    drop(conn);
+
+    let err = backup::restore(&dest, &dir.path().join("other.db"), None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("newer"), "{}", err);
+}
+
+#[test]
+fn test_default_backup_path() {
+    let now = chrono::DateTime::parse_from_rfc3339("2026-02-03T04:05:06Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let p = backup::default_path(std::path::Path::new("/data"), now, true);
+    assert_eq!(
+        p,
+        std::path::Path::new("/data/backups/data-20260203-040506.db.gz")
+    );
+}
+
+/// Scenario: Restoring over an existing database keeps it as `.pre-restore-<timestamp>`
+#[test]
+fn test_restore_keeps_previous_database() {
+    let (dir, db) = seeded();
+    let dest = dir.path().join("snap.db");
+    backup::backup(&db, &dest, false).unwrap();
+    let date = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+    db.insert_metric(&common::make_metric("weight", 99.0, date))
+        .unwrap();
+    drop(db);
+
+    let target = dir.path().join("test.db");
+    let result = backup::restore(&dest, &target, None).unwrap();
+    let previous = std::path::PathBuf::from(result.previous.unwrap());
+    let name = previous.file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("test.db.pre-restore-"), "{name}");
+
+    let old = Database::open(&previous).unwrap();
+    assert_eq!(
+        old.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        2
+    );
+    let restored = Database::open(&target).unwrap();
+    assert_eq!(
+        restored
+            .query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // A second restore keeps the first pre-restore copy
+    let again = backup::restore(&dest, &target, None).unwrap();
+    let second = std::path::PathBuf::from(again.previous.unwrap());
+    assert_ne!(second, previous);
+    assert!(second.exists());
+    let old = Database::open(&previous).unwrap();
+    assert_eq!(
+        old.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let fresh = backup::restore(&dest, &dir.path().join("new.db"), None).unwrap();
+    assert_eq!(fresh.previous, None);
+}
+
+/// Scenario: Pruning keeps the newest timestamped backups and ignores other files
+#[test]
+fn test_prune_keeps_newest_backups() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let names = [
+        "data-20260101-000000.db",
+        "data-20260103-000000.db.gz",
+        "openvital-20260102-000000.db",
+        "data-20260104-000000.db",
+        "notes.txt",
+        "data-latest.db",
+    ];
+    for n in names {
+        std::fs::write(dir.path().join(n), "x").unwrap();
+    }
+
+    let mut removed = backup::prune(dir.path(), 2).unwrap();
+    removed.sort();
+    assert_eq!(
+        removed,
+        vec![
+            dir.path().join("data-20260101-000000.db"),
+            dir.path().join("openvital-20260102-000000.db"),
+        ]
+    );
+    assert!(dir.path().join("data-20260104-000000.db").exists());
+    assert!(dir.path().join("data-20260103-000000.db.gz").exists());
+    assert!(dir.path().join("notes.txt").exists());
+    assert!(dir.path().join("data-latest.db").exists());
+
+    assert!(backup::prune(dir.path(), 0).unwrap().is_empty());
+}
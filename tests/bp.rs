@@ -0,0 +1,222 @@
+mod common;
+
+use chrono::{Duration, NaiveDate};
+use openvital::core::bp::{self, BpCategory};
+use openvital::core::goal::set_goal;
+use openvital::core::status::bp_status;
+use openvital::core::trend::{TrendOptions, TrendPeriod};
+use openvital::error::{self, ErrorKind};
+use openvital::models::config::{Alerts, BpThresholds};
+use openvital::models::goal::{Direction, Timeframe};
+use openvital::models::metric::Metric;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 2, d).unwrap()
+}
+
+fn reading(metric_type: &str, value: f64, d: u32, offset_secs: i64) -> Metric {
+    let mut m = common::make_metric(metric_type, value, day(d));
+    m.timestamp += Duration::seconds(offset_secs);
+    m
+}
+
+#[test]
+fn test_pair_zips_entries_within_a_minute() {
+    let systolic = vec![
+        reading(bp::SYSTOLIC, 120.0, 1, 0),
+        reading(bp::SYSTOLIC, 125.0, 2, 0),
+    ];
+    let diastolic = vec![
+        reading(bp::DIASTOLIC, 80.0, 1, 2),
+        reading(bp::DIASTOLIC, 82.0, 2, 30),
+    ];
+
+    let readings = bp::pair(&systolic, &diastolic);
+    assert_eq!(readings.len(), 2);
+    // Newest first
+    assert_eq!(readings[0].systolic, Some(125.0));
+    assert_eq!(readings[0].diastolic, Some(82.0));
+    assert_eq!(readings[1].systolic, Some(120.0));
+    assert_eq!(readings[1].diastolic, Some(80.0));
+    assert!(readings.iter().all(|r| r.is_paired()));
+}
+
+#[test]
+fn test_pair_keeps_unpaired_readings_one_sided() {
+    let systolic = vec![
+        reading(bp::SYSTOLIC, 120.0, 1, 0),
+        reading(bp::SYSTOLIC, 118.0, 3, 0),
+    ];
+    // 90 s apart from the day-1 systolic: too far to pair
+    let diastolic = vec![
+        reading(bp::DIASTOLIC, 80.0, 1, 90),
+        reading(bp::DIASTOLIC, 78.0, 3, -5),
+    ];
+
+    let readings = bp::pair(&systolic, &diastolic);
+    assert_eq!(readings.len(), 3);
+    assert!(readings[0].is_paired());
+    assert_eq!(readings[0].diastolic, Some(78.0));
+    let lone: Vec<_> = readings.iter().filter(|r| !r.is_paired()).collect();
+    assert_eq!(lone.len(), 2);
+    assert!(
+        lone.iter()
+            .any(|r| r.systolic == Some(120.0) && r.diastolic.is_none())
+    );
+    assert!(
+        lone.iter()
+            .any(|r| r.diastolic == Some(80.0) && r.systolic.is_none())
+    );
+}
+
+#[test]
+fn test_recent_trend_and_goal_guard() {
+    let (_dir, db) = common::setup_db();
+    for (d, sys, dia) in [(1, 130.0, 85.0), (8, 126.0, 83.0), (15, 122.0, 80.0)] {
+        db.insert_metric(&reading(bp::SYSTOLIC, sys, d, 0)).unwrap();
+        db.insert_metric(&reading(bp::DIASTOLIC, dia, d, 1))
+            .unwrap();
+    }
+
+    let readings = bp::recent(&db, 2, &[]).unwrap();
+    assert_eq!(readings.len(), 2);
+    assert_eq!(readings[0].systolic, Some(122.0));
+    assert_eq!(readings[0].diastolic, Some(80.0));
+
+    let trend = bp::trend(&db, TrendPeriod::Weekly, TrendOptions::default()).unwrap();
+    assert_eq!(trend.systolic.metric_type, "bp_systolic");
+    assert_eq!(trend.diastolic.metric_type, "bp_diastolic");
+    assert_eq!(trend.systolic.trend.direction, "decreasing");
+    assert!(trend.systolic.trend.rate < trend.diastolic.trend.rate);
+
+    assert_eq!(bp::entries(&db, None, None, &[]).unwrap().len(), 6);
+
+    let err = set_goal(
+        &db,
+        "blood_pressure".into(),
+        130.0,
+        Direction::Below,
+        Timeframe::Daily,
+    )
+    .unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::Validation);
+    assert!(
+        err.to_string()
+            .contains("goal set bp_systolic 130 below daily")
+    );
+}
+
+#[test]
+fn test_classify_stages_at_thresholds() {
+    let t = BpThresholds::default();
+    assert_eq!(bp::classify(119.0, 79.0, &t), BpCategory::Normal);
+    assert_eq!(bp::classify(120.0, 79.0, &t), BpCategory::Elevated);
+    assert_eq!(bp::classify(125.0, 80.0, &t), BpCategory::Stage1);
+    assert_eq!(bp::classify(140.0, 70.0, &t), BpCategory::Stage2);
+    assert_eq!(bp::classify(130.0, 90.0, &t), BpCategory::Stage2);
+    assert_eq!(bp::classify(150.0, 120.0, &t), BpCategory::Crisis);
+    assert_eq!(bp::classify(180.0, 60.0, &t), BpCategory::Crisis);
+
+    let custom = BpThresholds {
+        stage1_systolic: 125.0,
+        ..BpThresholds::default()
+    };
+    assert_eq!(bp::classify(127.0, 75.0, &custom), BpCategory::Stage1);
+    assert_eq!(bp::classify(122.0, 75.0, &custom), BpCategory::Elevated);
+}
+
+#[test]
+fn test_bp_status_uses_latest_paired_reading() {
+    let alerts = Alerts::default();
+
+    // A lone systolic entry is not enough to stage
+    let lone = vec![reading(bp::SYSTOLIC, 150.0, 1, 0)];
+    assert!(bp_status(&lone, &alerts).is_none());
+
+    let entries = vec![
+        reading(bp::SYSTOLIC, 118.0, 1, 0),
+        reading(bp::DIASTOLIC, 76.0, 1, 5),
+        reading(bp::SYSTOLIC, 132.0, 1, 3600),
+        reading(bp::DIASTOLIC, 84.0, 1, 3610),
+        reading(bp::SYSTOLIC, 140.0, 1, 7200),
+    ];
+    let status = bp_status(&entries, &alerts).unwrap();
+    assert_eq!(status.systolic, 132.0);
+    assert_eq!(status.diastolic, 84.0);
+    assert_eq!(status.category, BpCategory::Stage1);
+    assert!(!status.alert);
+
+    let crisis = vec![
+        reading(bp::SYSTOLIC, 185.0, 1, 0),
+        reading(bp::DIASTOLIC, 122.0, 1, 1),
+    ];
+    let status = bp_status(&crisis, &alerts).unwrap();
+    assert_eq!(status.category, BpCategory::Crisis);
+    assert!(status.alert);
+}
+
+#[test]
+fn test_classify_each_category_at_its_boundaries() {
+    let t = BpThresholds::default();
+    let cases = [
+        // Normal: below 120 and below 80
+        (119.0, 79.0, BpCategory::Normal),
+        // Elevated: 120-129 systolic, diastolic still below 80
+        (120.0, 79.0, BpCategory::Elevated),
+        (129.0, 79.0, BpCategory::Elevated),
+        // Stage 1: 130-139 or 80-89
+        (130.0, 79.0, BpCategory::Stage1),
+        (119.0, 80.0, BpCategory::Stage1),
+        (139.0, 89.0, BpCategory::Stage1),
+        // Stage 2: 140+ or 90+
+        (140.0, 79.0, BpCategory::Stage2),
+        (119.0, 90.0, BpCategory::Stage2),
+        (179.0, 119.0, BpCategory::Stage2),
+        // Crisis: 180+ or 120+
+        (180.0, 79.0, BpCategory::Crisis),
+        (119.0, 120.0, BpCategory::Crisis),
+    ];
+    for (systolic, diastolic, expected) in cases {
+        assert_eq!(
+            bp::classify(systolic, diastolic, &t),
+            expected,
+            "{}/{}",
+            systolic,
+            diastolic
+        );
+    }
+}
+
+#[test]
+fn test_find_paired_bp_entry_within_window() {
+    let (_dir, db) = common::setup_db();
+    let t = BpThresholds::default();
+    let sys = reading(bp::SYSTOLIC, 135.0, 1, 0);
+    let dia = reading(bp::DIASTOLIC, 85.0, 1, 4 * 60);
+    let late = reading(bp::DIASTOLIC, 95.0, 1, 20 * 60);
+    let other = reading("weight", 80.0, 1, 0);
+    for m in [&sys, &dia, &late, &other] {
+        db.insert_metric(m).unwrap();
+    }
+
+    let partner = db.find_paired_bp_entry(&sys.id, 300).unwrap().unwrap();
+    assert_eq!(partner.id, dia.id);
+    let partner = db.find_paired_bp_entry(&dia.id, 300).unwrap().unwrap();
+    assert_eq!(partner.id, sys.id);
+    assert!(db.find_paired_bp_entry(&sys.id, 60).unwrap().is_none());
+    assert!(db.find_paired_bp_entry(&late.id, 300).unwrap().is_none());
+    assert!(db.find_paired_bp_entry(&other.id, 300).unwrap().is_none());
+    assert!(db.find_paired_bp_entry("missing", 300).unwrap().is_none());
+
+    // Both halves of a reading get its category; an unpaired entry gets none
+    assert_eq!(
+        bp::category_of(&db, &sys, &t).unwrap(),
+        Some(BpCategory::Stage1)
+    );
+    assert_eq!(
+        bp::category_of(&db, &dia, &t).unwrap(),
+        Some(BpCategory::Stage1)
+    );
+    assert_eq!(bp::category_of(&db, &late, &t).unwrap(), None);
+    assert_eq!(bp::category_of(&db, &other, &t).unwrap(), None);
+}
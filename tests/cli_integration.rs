@@ -15,6 +15,8 @@ use tempfile::TempDir;
 fn cmd_in(dir: &TempDir) -> assert_cmd::Command {
     let mut c = cargo_bin_cmd!("openvital");
     c.env("OPENVITAL_HOME", dir.path());
+    // The timezone defaults to the system zone; pin it so days are UTC days.
+    c.env("TZ", "UTC");
     c
 }
 
@@ -161,6 +163,99 @@ fn test_log_alias_resolves_to_canonical_type() {
     assert_eq!(json["data"]["entry"]["type"], "weight");
 }
 
+#[test]
+fn test_log_file_json_and_csv() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let json_file = dir.path().join("batch.json");
+    fs::write(
+        &json_file,
+        r#"[{"type": "weight", "value": 80.5}, {"type": "water", "value": 500}]"#,
+    )
+    .unwrap();
+    let csv_file = dir.path().join("batch.csv");
+    fs::write(
+        &csv_file,
+        "timestamp,type,value\n2026-01-05T07:00:00+00:00,weight,81\n,pain,3\n",
+    )
+    .unwrap();
+
+    let assert = cmd_in(&dir)
+        .args(["log", "--file", json_file.to_str().unwrap()])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["count"], 2);
+    assert_eq!(json["data"]["entries"][1]["type"], "water");
+
+    cmd_in(&dir)
+        .args(["--human", "log", "--file", csv_file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Logged: 2026-01-05 07:00 | weight = 81 kg",
+        ));
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80", "--file", csv_file.to_str().unwrap()])
+        .assert()
+        .failure();
+    cmd_in(&dir)
+        .args([
+            "log",
+            "--batch",
+            "weight:80",
+            "--file",
+            csv_file.to_str().unwrap(),
+        ])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_log_batch_and_import_read_stdin() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["log", "--batch", "-"])
+        .write_stdin(r#"[{"type": "weight", "value": 72.5}, {"type": "water", "value": 500}]"#)
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["data"]["count"], 2);
+
+    let assert = cmd_in(&dir)
+        .args(["log", "--batch", "-"])
+        .write_stdin("weight:72.0,sleep_hours:7.5\n")
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"][1]["type"], "sleep_hours");
+
+    let assert = cmd_in(&dir)
+        .args(["import", "--source", "json", "--file", "-"])
+        .write_stdin(r#"[{"type": "pain", "value": 3}]"#)
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["data"]["metric_count"], 1);
+
+    // Bad streamed content still yields the JSON error envelope on stderr
+    let output = cmd_in(&dir)
+        .args(["log", "--batch", "-"])
+        .write_stdin("[{\"type\": \"weight\"}]")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let err: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["status"], "error");
+    assert!(
+        err["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("missing 'value'")
+    );
+}
+
 #[test]
 fn test_log_batch_json_output() {
     let dir = TempDir::new().unwrap();
@@ -290,6 +385,162 @@ fn test_show_empty_type_human() {
         .stdout(predicate::str::contains("No entries"));
 }
 
+#[test]
+fn test_show_types_returns_groups() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80.0"])
+        .assert()
+        .success();
+    cmd_in(&dir).args(["log", "pain", "3"]).assert().success();
+
+    let assert = cmd_in(&dir)
+        .args(["show", "--types", "weight,pain,sleep_hours"])
+        .assert()
+        .success();
+
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "show");
+    let groups = json["data"]["groups"].as_array().unwrap();
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[0]["type"], "weight");
+    assert_eq!(groups[0]["entries"].as_array().unwrap().len(), 1);
+    assert_eq!(groups[1]["type"], "pain");
+    assert_eq!(groups[2]["type"], "sleep_hours");
+    assert!(groups[2]["entries"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_show_types_respects_from() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (t, v, date) in [
+        ("weight", "80.0", "2026-01-01"),
+        ("weight", "81.0", "2026-01-05"),
+        ("pain", "3", "2026-01-02"),
+    ] {
+        cmd_in(&dir)
+            .args(["log", t, v, "--date", date])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args([
+            "show",
+            "--types",
+            "weight,pain",
+            "--from",
+            "2026-01-03",
+            "--stats",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let groups = json["data"]["groups"].as_array().unwrap();
+    let weights = groups[0]["entries"].as_array().unwrap();
+    assert_eq!(weights.len(), 1);
+    assert_eq!(weights[0]["value"].as_f64().unwrap(), 81.0);
+    assert!(groups[1]["entries"].as_array().unwrap().is_empty());
+    assert_eq!(json["data"]["statistics"]["weight"]["count"], 1);
+}
+
+#[test]
+fn test_show_types_human_prints_section_per_type() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80.0"])
+        .assert()
+        .success();
+
+    cmd_in(&dir)
+        .args(["--human", "show", "--types", "weight,sleep_hours"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- weight ---"))
+        .stdout(predicate::str::contains("--- sleep_hours ---"))
+        .stdout(predicate::str::contains("No entries"));
+}
+
+#[test]
+fn test_show_types_conflicts_with_positional_type() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["show", "weight", "--types", "pain"])
+        .assert()
+        .failure();
+}
+
+// ── stats ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_stats_single_type_json() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for v in ["80", "82", "84"] {
+        cmd_in(&dir).args(["log", "weight", v]).assert().success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["stats", "--type", "weight"])
+        .assert()
+        .success();
+
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "stats");
+    assert_eq!(json["data"]["type"], "weight");
+    assert_eq!(json["data"]["stats"]["count"], 3);
+    assert_eq!(json["data"]["stats"]["median"], 82.0);
+    assert!(json["data"]["period"].is_object());
+}
+
+#[test]
+fn test_stats_all_types_json() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+    cmd_in(&dir).args(["log", "pain", "3"]).assert().success();
+
+    let assert = cmd_in(&dir).args(["stats"]).assert().success();
+
+    let json = parse_json(&assert);
+    let types = json["data"]["types"].as_array().unwrap();
+    assert_eq!(types.len(), 2);
+    assert_eq!(types[0]["type"], "pain");
+    assert_eq!(types[1]["type"], "weight");
+}
+
+#[test]
+fn test_stats_human() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+
+    cmd_in(&dir)
+        .args(["--human", "stats", "--type", "weight"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== weight ==="))
+        .stdout(predicate::str::contains("median"))
+        .stdout(predicate::str::contains("std_dev"));
+}
+
 // ── status ───────────────────────────────────────────────────────────────────
 
 #[test]
@@ -333,6 +584,102 @@ fn test_status_with_logged_data() {
     assert_eq!(json["status"], "ok");
 }
 
+#[test]
+fn test_status_streaks_by_type() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["config", "set", "streaks.track", "cardio"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "cardio", "30"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["status"]).assert().success();
+    let json = parse_json(&assert);
+    let by_type = json["data"]["streaks"]["by_type"].as_array().unwrap();
+    assert_eq!(by_type[0]["type"], "cardio");
+    assert_eq!(by_type[0]["kind"], "logging");
+    assert_eq!(by_type[0]["current_days"], 1);
+
+    cmd_in(&dir)
+        .args(["--human", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cardio: 1 days"));
+}
+
+#[test]
+fn test_status_with_date_flag() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "80.0", "--date", "2026-01-10"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["--date", "2026-01-10", "status"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["date"], "2026-01-10");
+    assert_eq!(json["data"]["today"]["logged"][0], "weight");
+}
+
+#[test]
+fn test_status_future_date_fails() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["--date", "2999-01-01", "status"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_show_export_report_tag_filter() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    for (value, tags) in [("30", "run,outdoor"), ("40", "running"), ("50", "Run")] {
+        cmd_in(&dir)
+            .args(["log", "cardio", value, "--tags", tags])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["show", "cardio", "--tag", "run"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"].as_array().unwrap().len(), 2);
+
+    let assert = cmd_in(&dir)
+        .args(["show", "cardio", "--tag", "run", "--tag", "outdoor"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"].as_array().unwrap().len(), 1);
+
+    let assert = cmd_in(&dir)
+        .args(["export", "--format", "json", "--tag", "running"])
+        .assert()
+        .success();
+    let exported: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(exported.as_array().unwrap().len(), 1);
+
+    let assert = cmd_in(&dir)
+        .args(["report", "--tag", "run,outdoor"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["total_entries"], 1);
+    assert_eq!(json["data"]["tags"], serde_json::json!(["run", "outdoor"]));
+}
+
 // ── trend ─────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -530,39 +877,268 @@ fn test_trend_correlate_bad_format_fails() {
         .failure();
 }
 
-// ── goal ─────────────────────────────────────────────────────────────────────
-
 #[test]
-fn test_goal_set_json_output() {
+fn test_trend_json_includes_goal_projection() {
     let dir = TempDir::new().unwrap();
     init_dir(&dir);
 
+    for (d, v) in [
+        ("2026-01-01", "80"),
+        ("2026-01-02", "79.5"),
+        ("2026-01-03", "79"),
+    ] {
+        cmd_in(&dir)
+            .args(["--date", d, "log", "weight", v])
+            .assert()
+            .success();
+    }
+    cmd_in(&dir)
+        .args(["goal", "set", "weight", "75", "below", "daily"])
+        .assert()
+        .success();
+
     let assert = cmd_in(&dir)
-        .args([
-            "goal",
-            "set",
-            "weight",
-            "--target",
-            "75.0",
-            "--direction",
-            "below",
-            "--timeframe",
-            "monthly",
-        ])
+        .args(["trend", "weight", "--period", "daily"])
         .assert()
         .success();
 
     let json = parse_json(&assert);
-    assert_eq!(json["status"], "ok");
-    assert_eq!(json["command"], "goal");
-    assert_eq!(json["data"]["goal"]["metric_type"], "weight");
-    assert!((json["data"]["goal"]["target_value"].as_f64().unwrap() - 75.0).abs() < f64::EPSILON);
-}
-
-#[test]
-fn test_goal_set_human_output() {
-    let dir = TempDir::new().unwrap();
-    init_dir(&dir);
+    let projection = &json["data"]["projection"];
+    assert_eq!(projection["target"], 75.0);
+    assert_eq!(projection["direction"], "converging");
+    assert_eq!(projection["estimated_date"], "2026-01-11");
+    assert!(projection["confidence"].is_number());
+
+    let assert = cmd_in(&dir)
+        .args(["goal", "status", "weight"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(
+        json["data"]["goals"][0]["eta"]["estimated_date"],
+        "2026-01-11"
+    );
+}
+
+#[test]
+fn test_trend_smooth_flag() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (d, v) in [
+        ("2026-01-01", "80"),
+        ("2026-01-02", "82"),
+        ("2026-01-03", "84"),
+    ] {
+        cmd_in(&dir)
+            .args(["--date", d, "log", "weight", v])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "weight", "--period", "daily", "--smooth", "2"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let data = json["data"]["data"].as_array().unwrap();
+    assert_eq!(data[0]["moving_avg"], 80.0);
+    assert_eq!(data[2]["moving_avg"], 83.0);
+
+    cmd_in(&dir)
+        .args([
+            "--human", "trend", "weight", "--period", "daily", "--smooth", "2",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("smoothed: 83.0 (84.0)"));
+}
+
+#[test]
+fn test_trend_agg_flag_and_config_override() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for v in ["500", "700"] {
+        cmd_in(&dir)
+            .args(["--date", "2026-01-01", "log", "water", v])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "water", "--period", "daily"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["aggregation"], "sum");
+    assert_eq!(json["data"]["data"][0]["avg"], 1200.0);
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "water", "--period", "daily", "--agg", "max"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["aggregation"], "max");
+    assert_eq!(json["data"]["data"][0]["avg"], 700.0);
+
+    cmd_in(&dir)
+        .args(["config", "set", "aggregation.water", "avg"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir)
+        .args(["trend", "water", "--period", "daily"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["aggregation"], "avg");
+
+    cmd_in(&dir)
+        .args(["trend", "water", "--agg", "median"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_trend_correlate_lag_json() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "--correlate", "sleep_hours,pain", "--lag", "-1"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["lag"], -1);
+    assert_eq!(json["data"]["data_points"], 0);
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "--correlate", "sleep_hours,pain", "--scan-lag"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["lags"].as_array().unwrap().len(), 15);
+    assert!(json["data"]["best"].is_object());
+}
+
+#[test]
+fn test_trend_correlate_all_json_and_human() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (i, d) in [
+        "2026-01-01",
+        "2026-01-02",
+        "2026-01-03",
+        "2026-01-04",
+        "2026-01-05",
+    ]
+    .iter()
+    .enumerate()
+    {
+        cmd_in(&dir)
+            .args(["--date", d, "log", "pain", &(i + 2).to_string()])
+            .assert()
+            .success();
+        cmd_in(&dir)
+            .args(["--date", d, "log", "mood", &(8 - i).to_string()])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "--correlate-all"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let types = json["data"]["types"].as_array().unwrap();
+    // init logs weight on a single day, so its pairs lack data
+    assert!(types.len() >= 2);
+    let matrix = json["data"]["matrix"].as_array().unwrap();
+    assert_eq!(matrix.len(), types.len());
+    for (i, row) in matrix.iter().enumerate() {
+        assert_eq!(row[i], 1.0);
+    }
+
+    cmd_in(&dir)
+        .args(["--human", "trend", "--correlate-all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("—"))
+        .stdout(predicate::str::contains("-1.00"));
+
+    cmd_in(&dir)
+        .args(["trend", "pain", "--correlate-all"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_trend_confidence_interval_json_and_human() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (d, v) in [
+        ("2026-01-01", "80"),
+        ("2026-01-02", "79.6"),
+        ("2026-01-03", "79.1"),
+        ("2026-01-04", "78.4"),
+    ] {
+        cmd_in(&dir)
+            .args(["--date", d, "log", "weight", v])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "weight", "--period", "daily"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let trend = &json["data"]["trend"];
+    assert!(trend["std_error"].is_number());
+    assert!(trend["confidence_95_lower"].as_f64() < trend["confidence_95_upper"].as_f64());
+
+    cmd_in(&dir)
+        .args(["--human", "trend", "weight", "--period", "daily"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("kg/day (95% CI)"));
+}
+
+// ── goal ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_goal_set_json_output() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args([
+            "goal",
+            "set",
+            "weight",
+            "--target",
+            "75.0",
+            "--direction",
+            "below",
+            "--timeframe",
+            "monthly",
+        ])
+        .assert()
+        .success();
+
+    let json = parse_json(&assert);
+    assert_eq!(json["status"], "ok");
+    assert_eq!(json["command"], "goal");
+    assert_eq!(json["data"]["goal"]["metric_type"], "weight");
+    assert!((json["data"]["goal"]["target_value"].as_f64().unwrap() - 75.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_goal_set_human_output() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
 
     cmd_in(&dir)
         .args([
@@ -973,6 +1549,69 @@ fn test_config_persists_across_invocations() {
     assert!((height - 180.0).abs() < f64::EPSILON);
 }
 
+#[test]
+fn test_config_set_timezone() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["config", "set", "timezone", "America/New_York"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["config", "show"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(
+        json["data"]["config"]["profile"]["timezone"],
+        "America/New_York"
+    );
+}
+
+#[test]
+fn test_config_set_timezone_invalid_fails() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["config", "set", "timezone", "Mars/Olympus"])
+        .assert()
+        .failure();
+    let json = parse_stderr_json(&assert);
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("invalid timezone")
+    );
+}
+
+#[test]
+fn test_config_timezone_defaults_to_system_zone() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir)
+        .env("TZ", ":Pacific/Kiritimati")
+        .args(["init", "--skip"])
+        .assert()
+        .success();
+    // Not written at init; the system zone is looked up on every run
+    let assert = cmd_in(&dir).args(["config", "show"]).assert().success();
+    let json = parse_json(&assert);
+    assert!(json["data"]["config"]["profile"]["timezone"].is_null());
+
+    // Noon on the given day in Kiritimati (UTC+14) is 22:00 UTC the day before
+    cmd_in(&dir)
+        .env("TZ", "Pacific/Kiritimati")
+        .args(["--date", "2026-01-15", "log", "weight", "80"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir)
+        .args(["show", "weight", "--date", "2026-01-14"])
+        .assert()
+        .success();
+    let entries = parse_json(&assert)["data"]["entries"].clone();
+    assert_eq!(entries[0]["timestamp"], "2026-01-14T22:00:00Z");
+}
+
 // ── report ───────────────────────────────────────────────────────────────────
 
 #[test]
@@ -1064,6 +1703,47 @@ fn test_report_human_flag() {
         .stdout(predicate::str::contains("OpenVital Report:"));
 }
 
+#[test]
+fn test_report_quarter_with_year() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["--date", "2025-02-10", "log", "weight", "82.0"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["report", "--period", "ytd", "--year", "2025"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["period"], "ytd");
+    assert_eq!(json["data"]["from"], "2025-01-01");
+    assert_eq!(json["data"]["to"], "2025-12-31");
+    assert_eq!(json["data"]["total_entries"], 1);
+
+    let assert = cmd_in(&dir)
+        .args(["report", "--period", "quarter", "--year", "2025"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["period"], "quarter");
+    assert!(json["data"]["from"].as_str().unwrap().starts_with("2025-"));
+
+    cmd_in(&dir)
+        .args(["--human", "report", "--period", "ytd", "--year", "2025"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "OpenVital Report: 2025 YTD (2025-01-01 to 2025-12-31)",
+        ));
+    cmd_in(&dir)
+        .args(["--human", "report", "--period", "quarter", "--year", "2025"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"OpenVital Report: Q[1-4] 2025 \(2025-").unwrap());
+}
+
 #[test]
 fn test_report_invalid_period_fails() {
     let dir = TempDir::new().unwrap();
@@ -1107,24 +1787,247 @@ fn test_report_with_data_shows_metrics_summary() {
     assert!(weight_metric["count"].as_u64().is_some());
 }
 
-// ── export / import ───────────────────────────────────────────────────────────
-
 #[test]
-fn test_export_json_stdout() {
+fn test_report_compare_json_and_human() {
     let dir = TempDir::new().unwrap();
     init_dir(&dir);
 
-    cmd_in(&dir)
-        .args(["log", "weight", "82.0"])
+    for (d, v) in [("2026-01-02", "82"), ("2026-01-09", "81.5")] {
+        cmd_in(&dir)
+            .args(["--date", d, "log", "weight", v])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args([
+            "report",
+            "--from",
+            "2026-01-08",
+            "--to",
+            "2026-01-14",
+            "--compare",
+        ])
         .assert()
         .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["compared_to"]["from"], "2026-01-01");
+    let weight = json["data"]["metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["type"] == "weight")
+        .unwrap();
+    assert_eq!(weight["prev_avg"], 82.0);
+    assert_eq!(weight["delta"], -0.5);
 
-    // Without --output, export prints raw JSON to stdout (not in envelope)
-    let output = cmd_in(&dir)
-        .args(["export", "--format", "json"])
+    cmd_in(&dir)
+        .args([
+            "--human",
+            "report",
+            "--from",
+            "2026-01-08",
+            "--to",
+            "2026-01-14",
+            "--compare",
+        ])
         .assert()
         .success()
-        .get_output()
+        .stdout(predicate::str::contains(
+            "weight: avg 81.5 kg (↓0.5 from prior period)",
+        ));
+
+    let assert = cmd_in(&dir)
+        .args([
+            "report",
+            "--from",
+            "2026-01-08",
+            "--to",
+            "2026-01-14",
+            "--compare",
+            "prev",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let row = &json["data"]["comparison"][0];
+    assert_eq!(row["type"], "weight");
+    assert_eq!(row["current_avg"], 81.5);
+    assert_eq!(row["prev_avg"], 82.0);
+    assert_eq!(row["delta"], -0.5);
+}
+
+#[test]
+fn test_report_compare_prior_period_boundaries() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    for (d, v) in [
+        ("2026-02-20", "250"),
+        ("2026-03-10", "500"),
+        ("2026-03-11", "750"),
+    ] {
+        cmd_in(&dir)
+            .args(["--date", d, "log", "water", v])
+            .assert()
+            .success();
+    }
+
+    let compared = |args: &[&str]| {
+        let assert = cmd_in(&dir)
+            .args(["report", "--compare", "prev"])
+            .args(args)
+            .assert()
+            .success();
+        parse_json(&assert)["data"].clone()
+    };
+
+    // Week: week to date, against the same number of days before it
+    let data = compared(&["--period", "week"]);
+    let (from, to) = (
+        data["from"]
+            .as_str()
+            .unwrap()
+            .parse::<chrono::NaiveDate>()
+            .unwrap(),
+        data["to"]
+            .as_str()
+            .unwrap()
+            .parse::<chrono::NaiveDate>()
+            .unwrap(),
+    );
+    assert_eq!(
+        data["compared_to"]["to"],
+        (from - chrono::Duration::days(1)).to_string()
+    );
+    assert_eq!(
+        data["compared_to"]["from"],
+        (from - (to - from) - chrono::Duration::days(1)).to_string()
+    );
+    assert_eq!(chrono::Datelike::weekday(&from), chrono::Weekday::Mon);
+    assert!((to - from).num_days() < 7);
+
+    // Month: the previous calendar month; water compares totals too
+    let data = compared(&["--period", "month", "--month", "2026-03"]);
+    assert_eq!(data["compared_to"]["from"], "2026-02-01");
+    assert_eq!(data["compared_to"]["to"], "2026-02-28");
+    let water = &data["comparison"][0];
+    assert_eq!(water["current_total"], 1250.0);
+    assert_eq!(water["prev_total"], 250.0);
+    assert_eq!(water["total_delta"], 1000.0);
+
+    // Custom range: the preceding range of equal length, no prior data
+    let data = compared(&["--from", "2026-03-10", "--to", "2026-03-11"]);
+    assert_eq!(data["compared_to"]["from"], "2026-03-08");
+    assert_eq!(data["compared_to"]["to"], "2026-03-09");
+    let water = &data["comparison"][0];
+    assert!(water["prev_avg"].is_null());
+    assert!(water.get("delta").is_none());
+}
+
+#[test]
+fn test_report_html_output_file() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["--date", "2026-01-03", "log", "weight", "80"])
+        .assert()
+        .success();
+    let out = dir.path().join("report.html");
+
+    let assert = cmd_in(&dir)
+        .args([
+            "report",
+            "--from",
+            "2026-01-01",
+            "--to",
+            "2026-01-07",
+            "--format",
+            "html",
+            "--output",
+            out.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["format"], "html");
+    assert_eq!(json["data"]["path"], out.to_str().unwrap());
+
+    let html = fs::read_to_string(&out).unwrap();
+    assert!(html.contains("<svg class=\"spark\""));
+
+    cmd_in(&dir)
+        .args(["report", "--format", "pdf"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_report_markdown_stdout_and_file() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    for (d, v) in [("2026-01-03", "80"), ("2026-01-05", "81")] {
+        cmd_in(&dir)
+            .args(["--date", d, "log", "weight", v])
+            .assert()
+            .success();
+    }
+    cmd_in(&dir)
+        .args(["goal", "set", "weight", "85", "below", "daily"])
+        .assert()
+        .success();
+    let range = ["report", "--from", "2026-01-01", "--to", "2026-01-07"];
+
+    let output = cmd_in(&dir)
+        .args(range)
+        .args(["--format", "markdown"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let md = String::from_utf8(output.stdout).unwrap();
+    assert!(md.starts_with("## OpenVital Report"));
+    let lines: Vec<&str> = md.lines().collect();
+    let header = lines
+        .iter()
+        .position(|l| l.starts_with("| Metric |"))
+        .unwrap();
+    assert!(lines[header + 1].starts_with("|---"));
+    assert!(lines[header + 2].starts_with("| weight | 80.5 |"));
+    // Every table row has the same number of cells as the header
+    let cells = |l: &str| l.matches('|').count();
+    assert_eq!(cells(lines[header]), cells(lines[header + 2]));
+    // The goal's current value is today's weight, which was never logged
+    assert!(md.contains("- [ ] Weight below 85 kg (daily)"));
+
+    let out = dir.path().join("report.md");
+    let assert = cmd_in(&dir)
+        .args(range)
+        .args(["--format", "markdown", "--output", out.to_str().unwrap()])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["format"], "markdown");
+    assert_eq!(json["data"]["path"], out.to_str().unwrap());
+    assert_eq!(fs::read_to_string(&out).unwrap(), md);
+}
+
+// ── export / import ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_export_json_stdout() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "weight", "82.0"])
+        .assert()
+        .success();
+
+    // Without --output, export prints raw JSON to stdout (not in envelope)
+    let output = cmd_in(&dir)
+        .args(["export", "--format", "json"])
+        .assert()
+        .success()
+        .get_output()
         .stdout
         .clone();
 
@@ -1325,6 +2228,122 @@ fn test_import_json_round_trip() {
     assert_eq!(json["data"]["medication_count"], 0);
 }
 
+#[test]
+fn test_import_plain_reimport_adds_nothing() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "82.0"])
+        .assert()
+        .success();
+
+    let export_file = dir.path().join("data.json");
+    cmd_in(&dir)
+        .args(["export", "--format", "json", "--output"])
+        .arg(&export_file)
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["import", "--source", "json", "--file"])
+        .arg(&export_file)
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["metric_count"], 0);
+
+    let assert = cmd_in(&dir)
+        .args(["show", "weight", "--last", "10"])
+        .assert()
+        .success();
+    assert_eq!(
+        parse_json(&assert)["data"]["entries"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_import_csv_chunk_size() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let file = dir.path().join("rows.csv");
+    fs::write(
+        &file,
+        "timestamp,type,value\n\
+         2026-01-05T12:00:00+00:00,weight,78.0\n\
+         2026-01-06T12:00:00+00:00,weight,78.5\n\
+         2026-01-07T12:00:00+00:00,weight,79.0\n",
+    )
+    .unwrap();
+
+    let assert = cmd_in(&dir)
+        .args([
+            "import",
+            "--source",
+            "csv",
+            "--file",
+            file.to_str().unwrap(),
+            "--chunk-size",
+            "2",
+        ])
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["data"]["count"], 3);
+}
+
+#[test]
+fn test_import_dedupe_reports_counts() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let file = dir.path().join("rows.csv");
+    fs::write(
+        &file,
+        "timestamp,type,value\n\
+         2026-01-05T12:00:00+00:00,water,500\n\
+         2026-01-06T12:00:00+00:00,water,750\n",
+    )
+    .unwrap();
+    let import = |extra: &[&str]| {
+        cmd_in(&dir)
+            .args([
+                "import",
+                "--source",
+                "csv",
+                "--file",
+                file.to_str().unwrap(),
+            ])
+            .args(extra)
+            .assert()
+            .success()
+    };
+
+    import(&["--dedupe"]);
+    let json = parse_json(&import(&["--dedupe"]));
+    assert_eq!(json["data"]["imported"], 0);
+    assert_eq!(json["data"]["skipped_duplicates"], 2);
+    assert_eq!(json["data"]["errors"], 0);
+
+    cmd_in(&dir)
+        .args(["--human", "import", "--source", "csv", "--dedupe", "--file"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(2 duplicates skipped)"));
+
+    let json = parse_json(&import(&["--merge", "overwrite"]));
+    assert_eq!(json["data"]["imported"], 0);
+    assert_eq!(json["data"]["overwritten"], 2);
+    cmd_in(&dir)
+        .args(["import", "--source", "csv", "--merge", "latest", "--file"])
+        .arg(&file)
+        .assert()
+        .failure()
+        .code(2);
+}
+
 #[test]
 fn test_import_csv_round_trip() {
     let dir = TempDir::new().unwrap();
@@ -1443,77 +2462,458 @@ fn test_import_invalid_source_fails() {
         .failure();
 }
 
-// ── completions ───────────────────────────────────────────────────────────────
-
 #[test]
-fn test_completions_bash() {
+fn test_import_apple_health_json() {
     let dir = TempDir::new().unwrap();
-    cmd_in(&dir)
-        .args(["completions", "bash"])
+    init_dir(&dir);
+
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<HealthData>
+ <Record type="HKQuantityTypeIdentifierStepCount" unit="count" startDate="2026-01-05 08:00:00 +0000" endDate="2026-01-05 09:00:00 +0000" value="1200"/>
+ <Record type="HKQuantityTypeIdentifierVO2Max" unit="mL/min·kg" startDate="2026-01-05 08:00:00 +0000" endDate="2026-01-05 08:00:00 +0000" value="40"/>
+</HealthData>"#;
+    let path = dir.path().join("export.xml");
+    fs::write(&path, xml).unwrap();
+
+    let assert = cmd_in(&dir)
+        .args([
+            "import",
+            "--source",
+            "apple_health",
+            "--file",
+            path.to_str().unwrap(),
+        ])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("openvital"));
+        .success();
+
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["imported"], 1);
+    assert_eq!(json["data"]["skipped"], 1);
+    assert_eq!(
+        json["data"]["unknown_types"][0],
+        "HKQuantityTypeIdentifierVO2Max"
+    );
 }
 
 #[test]
-fn test_completions_zsh() {
+fn test_import_garmin_csv_json() {
     let dir = TempDir::new().unwrap();
-    cmd_in(&dir)
-        .args(["completions", "zsh"])
+    init_dir(&dir);
+
+    let csv = "Activity Type,Date,Calories,Elapsed Time,Avg HR\nRunning,2026-01-15 07:30:00,300,00:30:00,150\n";
+    let path = dir.path().join("activities.csv");
+    fs::write(&path, csv).unwrap();
+
+    let assert = cmd_in(&dir)
+        .args([
+            "import",
+            "--source",
+            "garmin",
+            "--file",
+            path.to_str().unwrap(),
+        ])
         .assert()
-        .success()
-        .stdout(predicate::str::contains("openvital"));
+        .success();
+
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["imported"], 3);
+    assert_eq!(json["data"]["skipped"], 0);
 }
 
 #[test]
-fn test_completions_fish() {
+fn test_export_fhir_to_stdout() {
     let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
     cmd_in(&dir)
-        .args(["completions", "fish"])
+        .args(["log", "weight", "80"])
         .assert()
-        .success()
-        .stdout(predicate::str::is_empty().not());
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["export", "--format", "fhir"])
+        .assert()
+        .success();
+
+    let bundle = parse_json(&assert);
+    assert_eq!(bundle["resourceType"], "Bundle");
+    assert_eq!(
+        bundle["entry"][0]["resource"]["code"]["coding"][0]["code"],
+        "29463-7"
+    );
 }
 
+// ── backup / restore ─────────────────────────────────────────────────────────
+
 #[test]
-fn test_completions_invalid_shell_fails() {
+fn test_backup_default_path_and_restore() {
     let dir = TempDir::new().unwrap();
+    init_dir(&dir);
     cmd_in(&dir)
-        .args(["completions", "powershell_invalid"])
+        .args(["log", "weight", "81.5"])
         .assert()
-        .failure();
-}
+        .success();
 
-// ── error cases / main.rs error path ─────────────────────────────────────────
+    let assert = cmd_in(&dir).args(["backup"]).assert().success();
+    let json = parse_json(&assert);
+    let path = json["data"]["path"].as_str().unwrap().to_string();
+    assert!(path.contains("backups/data-") && path.ends_with(".db"));
+    assert!(json["data"]["size_bytes"].as_u64().unwrap() > 0);
+    assert!(json["data"]["timestamp"].is_string());
 
-#[test]
-fn test_unknown_subcommand_fails() {
-    let dir = TempDir::new().unwrap();
-    cmd_in(&dir).args(["notacommand"]).assert().failure();
-}
+    cmd_in(&dir)
+        .args(["log", "weight", "99"])
+        .assert()
+        .success();
 
-#[test]
-fn test_no_args_fails() {
-    let dir = TempDir::new().unwrap();
-    cmd_in(&dir).assert().failure();
+    cmd_in(&dir)
+        .args(["restore", "--file", &path])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--yes"));
+
+    cmd_in(&dir)
+        .args(["restore", "--file", &path, "--yes"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["show", "weight", "--last", "10"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let values: Vec<f64> = json["data"]["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["value"].as_f64().unwrap())
+        .collect();
+    assert!(!values.contains(&99.0), "restore should drop later entries");
 }
 
 #[test]
-fn test_error_output_goes_to_stderr_not_stdout() {
+fn test_backup_compress_output() {
     let dir = TempDir::new().unwrap();
     init_dir(&dir);
+    let out = dir.path().join("snap.db.gz");
 
-    // An operation that must fail: remove a non-existent goal
-    cmd_in(&dir)
-        .args(["goal", "remove", "bad-id"])
+    let assert = cmd_in(&dir)
+        .args(["backup", "--compress", "--output", out.to_str().unwrap()])
         .assert()
-        .failure()
-        .stderr(predicate::str::contains("error").or(predicate::str::contains("not found")))
-        .stdout(predicate::str::is_empty());
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["compressed"], true);
+    assert!(out.exists());
 }
 
 #[test]
-fn test_log_then_show_full_pipeline() {
+fn test_backup_prunes_and_restore_keeps_previous() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["config", "set", "backup.keep", "1"])
+        .assert()
+        .success();
+
+    // Non-default names are never pruned, so this one survives for the restore.
+    let snap = dir.path().join("snap.db");
+    let path = snap.to_str().unwrap().to_string();
+    cmd_in(&dir)
+        .args(["backup", "--output", &path])
+        .assert()
+        .success();
+    let backups = dir.path().join("backups");
+    std::fs::create_dir_all(&backups).unwrap();
+    let old = backups.join("data-20200101-000000.db");
+    std::fs::copy(&snap, &old).unwrap();
+
+    let assert = cmd_in(&dir).args(["backup"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["pruned"][0], old.display().to_string());
+    assert!(!old.exists());
+
+    let assert = cmd_in(&dir)
+        .args(["restore", &path, "--yes"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let previous = json["data"]["previous"].as_str().unwrap();
+    assert!(previous.contains(".pre-restore-"), "{previous}");
+    assert!(std::path::Path::new(previous).exists());
+}
+
+#[test]
+fn test_encrypt_command_keeps_data_readable() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "80.5"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["encrypt", "--key-source", "plain:correct horse"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["status"], "ok");
+
+    let header = fs::read(dir.path().join("data.db")).unwrap();
+    assert!(!header.starts_with(b"SQLite format 3"));
+
+    let assert = cmd_in(&dir).args(["show", "weight"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"][0]["value"], 80.5);
+
+    cmd_in(&dir)
+        .args(["encrypt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already enabled"));
+
+    // A wrong key is reported instead of silently reading garbage
+    cmd_in(&dir)
+        .args(["config", "set", "encryption.key_source", "plain:nope"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["show", "weight"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot decrypt"));
+}
+
+#[test]
+fn test_security_encrypt_and_db_decrypt() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "80.5"])
+        .assert()
+        .success();
+    let keyed = || {
+        let mut c = cmd_in(&dir);
+        c.env("OPENVITAL_DB_KEY", "correct horse");
+        c
+    };
+
+    keyed()
+        .args(["config", "set", "security.encrypt", "true"])
+        .assert()
+        .success();
+    let header = fs::read(dir.path().join("data.db")).unwrap();
+    assert!(!header.starts_with(b"SQLite format 3"));
+
+    // Without the key: a clear error, not garbage
+    cmd_in(&dir)
+        .args(["show", "weight"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("OPENVITAL_DB_KEY"));
+
+    // Export is plaintext
+    let assert = keyed()
+        .args(["export", "--format", "csv"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("80.5"));
+
+    let assert = keyed().args(["db", "decrypt"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "db_decrypt");
+    let header = fs::read(dir.path().join("data.db")).unwrap();
+    assert!(header.starts_with(b"SQLite format 3"));
+
+    let assert = cmd_in(&dir).args(["show", "weight"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"][0]["value"], 80.5);
+
+    cmd_in(&dir)
+        .args(["db", "decrypt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not enabled"));
+}
+
+// ── context ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_context_sections_filter() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "80.0"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["context", "--sections", "profile,goals"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(
+        json["data"]["sections"],
+        serde_json::json!(["goals", "profile"])
+    );
+    assert_eq!(json["data"]["metrics"], serde_json::json!({}));
+    assert_eq!(json["data"]["profile"]["latest_weight_kg"], 80.0);
+
+    cmd_in(&dir)
+        .args(["--human", "context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- Profile ---"))
+        .stdout(predicate::str::contains("--- Metrics ---"));
+
+    cmd_in(&dir)
+        .args(["context", "--sections", "vitals"])
+        .assert()
+        .failure();
+}
+
+// ── completions ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_completions_bash() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir)
+        .args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("openvital"));
+}
+
+#[test]
+fn test_completions_zsh() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir)
+        .args(["completions", "zsh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("openvital"));
+}
+
+#[test]
+fn test_completions_fish() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir)
+        .args(["completions", "fish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().not());
+}
+
+#[test]
+fn test_completions_powershell() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir)
+        .args(["completions", "powershell"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("openvital"));
+}
+
+#[test]
+fn test_completions_elvish() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir)
+        .args(["completions", "elvish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("openvital"));
+}
+
+#[test]
+fn test_completions_install() {
+    let dir = TempDir::new().unwrap();
+    let home = dir.path().join("home");
+    let fpath = dir.path().join("zfunc");
+    std::fs::create_dir_all(&fpath).unwrap();
+    // Not a directory, so nothing can be written inside it.
+    let not_dir = dir.path().join("not-a-dir");
+    std::fs::write(&not_dir, "").unwrap();
+
+    for (shell, expected) in [
+        ("bash", home.join(".bash_completion.d/openvital")),
+        ("zsh", fpath.join("_openvital")),
+        ("fish", home.join(".config/fish/completions/openvital.fish")),
+        (
+            "powershell",
+            home.join(".config/powershell/Completions/openvital.ps1"),
+        ),
+    ] {
+        let assert = cmd_in(&dir)
+            .env("HOME", &home)
+            .env(
+                "FPATH",
+                format!("/nonexistent:{}:{}", not_dir.display(), fpath.display()),
+            )
+            .args(["completions", shell, "--install"])
+            .assert()
+            .success();
+        let json = parse_json(&assert);
+        assert_eq!(json["data"]["path"], expected.display().to_string());
+        let script = std::fs::read_to_string(&expected).unwrap();
+        assert!(script.contains("openvital"), "{}", shell);
+    }
+
+    cmd_in(&dir)
+        .env("HOME", &home)
+        .args(["--human", "completions", "bash", "--install"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Installed bash completions to"));
+
+    let assert = cmd_in(&dir)
+        .env("HOME", &home)
+        .env("FPATH", "/nonexistent")
+        .args(["completions", "zsh", "--install"])
+        .assert()
+        .code(2);
+    let err = parse_stderr_json(&assert);
+    assert!(err["error"]["message"].as_str().unwrap().contains("FPATH"));
+}
+
+#[test]
+fn test_completions_invalid_shell_fails() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir)
+        .args(["completions", "powershell_invalid"])
+        .assert()
+        .failure();
+}
+
+// ── error cases / main.rs error path ─────────────────────────────────────────
+
+#[test]
+fn test_unknown_subcommand_fails() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir).args(["notacommand"]).assert().failure();
+}
+
+#[test]
+fn test_no_args_fails() {
+    let dir = TempDir::new().unwrap();
+    cmd_in(&dir).assert().failure();
+}
+
+#[test]
+fn test_error_output_goes_to_stderr_not_stdout() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    // An operation that must fail: remove a non-existent goal
+    cmd_in(&dir)
+        .args(["goal", "remove", "bad-id"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error").or(predicate::str::contains("not found")))
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn test_log_then_show_full_pipeline() {
     let dir = TempDir::new().unwrap();
     init_dir(&dir);
 
@@ -1575,6 +2975,30 @@ fn test_status_with_goals_and_data() {
     assert_eq!(json["status"], "ok");
 }
 
+#[test]
+fn test_anomaly_invalid_method_fails() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["anomaly", "--method", "mad"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid method"));
+}
+
+#[test]
+fn test_anomaly_rolling_reports_method_and_window() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let out = cmd_in(&dir)
+        .args(["anomaly", "--method", "rolling", "--window", "5"])
+        .assert()
+        .success();
+    let json = parse_json(&out);
+    assert_eq!(json["data"]["method"], "rolling");
+    assert_eq!(json["data"]["window_days"], 5);
+}
+
 // ── init (interactive path via stdin pipe) ────────────────────────────────────
 
 #[test]
@@ -2032,7 +3456,7 @@ fn test_goal_set_missing_required_value_returns_json_error_not_panic() {
 
     let json = parse_stderr_json(&assert);
     assert_eq!(json["status"], "error");
-    assert_eq!(json["error"]["code"], "general_error");
+    assert_eq!(json["error"]["code"], "validation_error");
     assert!(
         json["error"]["message"]
             .as_str()
@@ -2059,8 +3483,9 @@ fn test_log_blood_pressure_splits_into_two_entries() {
             value: 120.0,
             note: None,
             tags: None,
-            source: None,
+            source: openvital::core::logging::Source::Manual,
             date: None,
+            unit: None,
         },
     )
     .unwrap();
@@ -2072,8 +3497,9 @@ fn test_log_blood_pressure_splits_into_two_entries() {
             value: 80.0,
             note: None,
             tags: None,
-            source: None,
+            source: openvital::core::logging::Source::Manual,
             date: None,
+            unit: None,
         },
     )
     .unwrap();
@@ -2173,6 +3599,55 @@ fn test_log_blood_pressure_human_output() {
     );
 }
 
+#[test]
+fn test_blood_pressure_combined_show_trend_export() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "blood_pressure", "120/80"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["show", "blood_pressure"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["type"], "blood_pressure");
+    assert_eq!(json["data"]["readings"][0]["systolic"], 120.0);
+    assert_eq!(json["data"]["readings"][0]["diastolic"], 80.0);
+    cmd_in(&dir)
+        .args(["--human", "show", "bp"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("120/80 mmHg"));
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "blood_pressure"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["systolic"]["type"], "bp_systolic");
+    assert_eq!(json["data"]["diastolic"]["type"], "bp_diastolic");
+
+    let assert = cmd_in(&dir)
+        .args(["export", "--format", "csv", "--type", "blood_pressure"])
+        .assert()
+        .success();
+    let csv = String::from_utf8_lossy(&assert.get_output().stdout);
+    assert!(csv.contains("bp_systolic") && csv.contains("bp_diastolic"));
+
+    cmd_in(&dir)
+        .args(["goal", "set", "blood_pressure", "130", "below", "daily"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "goal set bp_systolic 130 below daily",
+        ));
+}
+
 // ── imperial unit conversions ────────────────────────────────────────────────
 
 #[test]
@@ -2752,3 +4227,2619 @@ fn test_med_take_stopped_shows_warning_json() {
         stdout
     );
 }
+
+// ─── med schedule ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_med_schedule_json_lists_slots() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["med", "add", "metformin", "--freq", "2x_daily"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["med", "schedule"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "med_schedule");
+    let slots = json["data"]["schedule"].as_array().unwrap();
+    assert_eq!(slots.len(), 2);
+    assert_eq!(slots[0]["time"], "10:00");
+    assert_eq!(slots[0]["name"], "metformin");
+    assert_eq!(slots[0]["taken"], false);
+}
+
+// ─── med refill ──────────────────────────────────────────────────────────────
+
+#[test]
+fn test_med_refill_shows_supply_in_status() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["med", "add", "metformin", "--freq", "daily"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["--human", "med", "status", "metformin"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No refill recorded"));
+
+    let assert = cmd_in(&dir)
+        .args([
+            "med",
+            "refill",
+            "metformin",
+            "--quantity",
+            "30",
+            "--days-supply",
+            "30",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "med_refill");
+    assert_eq!(json["data"]["quantity"], 30);
+
+    cmd_in(&dir)
+        .args(["med", "take", "metformin"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["med", "status", "metformin"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["estimated_remaining"], 29);
+    assert!(json["data"]["estimated_empty"].is_string());
+
+    cmd_in(&dir)
+        .args(["--human", "med", "status", "metformin"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Runs out in ~30 days"));
+}
+
+// ─── med status summary ──────────────────────────────────────────────────────
+
+#[test]
+fn test_med_status_all_uses_summary_mode() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (name, freq) in [("metformin", "2x_daily"), ("vitd", "daily")] {
+        cmd_in(&dir)
+            .args(["med", "add", name, "--freq", freq])
+            .assert()
+            .success();
+    }
+    cmd_in(&dir)
+        .args(["med", "take", "metformin"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["med", "status"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["summary_mode"], true);
+    let meds = json["data"]["medications"].as_array().unwrap();
+    assert_eq!(meds.len(), 2);
+    assert_eq!(meds[0]["name"], "metformin");
+    assert_eq!(meds[0]["taken_today"], 1);
+    assert!(meds[0].get("adherence_history").is_none());
+    assert!(meds[0].get("route").is_none());
+
+    let assert = cmd_in(&dir)
+        .args(["med", "status", "metformin"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert!(json["data"].get("summary_mode").is_none());
+    assert!(json["data"]["adherence_history"].is_array());
+
+    cmd_in(&dir)
+        .args(["--human", "med", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Frequency"))
+        .stdout(predicate::str::contains("1/2"));
+}
+
+// ─── med take --quantity ─────────────────────────────────────────────────────
+
+#[test]
+fn test_med_take_quantity_returns_entries() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args([
+            "med",
+            "add",
+            "ibuprofen",
+            "--dose",
+            "200mg",
+            "--freq",
+            "2x_daily",
+        ])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["med", "take", "ibuprofen", "--quantity", "2"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["quantity"], 2);
+    assert!(json["data"].get("entry").is_none());
+    let entries = json["data"]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["value"], 2.0);
+
+    let assert = cmd_in(&dir)
+        .args(["med", "status", "ibuprofen"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["taken_today"], 2);
+    assert_eq!(json["data"]["adherent_today"], true);
+
+    cmd_in(&dir)
+        .args(["--human", "med", "take", "ibuprofen", "--quantity", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 × 200mg"));
+}
+
+// ─── search ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_search_notes_json_and_human() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "pain", "6", "--note", "Left knee sore"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "weight", "80", "--note", "after dinner"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args([
+            "med",
+            "add",
+            "ibuprofen",
+            "--freq",
+            "daily",
+            "--note",
+            "knee flare-ups",
+        ])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["search", "KNEE"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "search");
+    let entries = json["data"]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["type"], "pain");
+    assert_eq!(entries[0]["match"], "Left **knee** sore");
+    assert_eq!(json["data"]["medications"][0]["name"], "ibuprofen");
+    assert_eq!(json["data"]["medications"][0]["field"], "note");
+
+    let assert = cmd_in(&dir)
+        .args(["search", "knee", "--type", "weight"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert!(json["data"]["entries"].as_array().unwrap().is_empty());
+
+    cmd_in(&dir)
+        .args(["--human", "search", "knee"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pain = 6/10  # Left knee sore"))
+        .stdout(predicate::str::contains(
+            "med ibuprofen | note  # knee flare-ups",
+        ));
+}
+
+// ─── med take --indication ───────────────────────────────────────────────────
+
+#[test]
+fn test_med_status_separates_prn_medications() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args([
+            "med",
+            "add",
+            "ibuprofen",
+            "--dose",
+            "400mg",
+            "--freq",
+            "as_needed",
+        ])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["med", "add", "metformin", "--freq", "daily"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["med", "take", "ibuprofen", "--indication", "headache"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["note"], "prn: headache; 400mg");
+
+    let assert = cmd_in(&dir).args(["med", "status"]).assert().success();
+    let json = parse_json(&assert);
+    let meds = json["data"]["medications"].as_array().unwrap();
+    assert_eq!(meds.len(), 1);
+    assert_eq!(meds[0]["name"], "metformin");
+    assert_eq!(json["data"]["prn"][0]["name"], "ibuprofen");
+    assert_eq!(json["data"]["prn"][0]["doses_taken"], 1);
+    assert_eq!(json["data"]["prn"][0]["most_common_indication"], "headache");
+
+    let assert = cmd_in(&dir)
+        .args(["med", "status", "ibuprofen"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["prn"]["doses_taken"], 1);
+
+    cmd_in(&dir)
+        .args(["--human", "med", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("As needed (last 7 days)"))
+        .stdout(predicate::str::contains("1 dose, mostly for headache"));
+}
+
+// ─── db ──────────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_db_stats_check_compact() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "weight", "79.5"])
+        .assert()
+        .success();
+    cmd_in(&dir).args(["log", "pain", "3"]).assert().success();
+
+    let assert = cmd_in(&dir).args(["db", "stats"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "db_stats");
+    assert!(json["data"]["size_bytes"].as_u64().unwrap() > 0);
+    assert!(json["data"]["path"].as_str().unwrap().ends_with(".db"));
+    assert_eq!(json["data"]["metric_types"][0]["type"], "weight");
+    assert_eq!(json["data"]["metric_types"][0]["count"], 2);
+    let tables = json["data"]["tables"].as_array().unwrap();
+    let metrics = tables.iter().find(|t| t["table"] == "metrics").unwrap();
+    assert_eq!(metrics["rows"], 3);
+
+    let assert = cmd_in(&dir).args(["db", "check"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["ok"], true);
+
+    let assert = cmd_in(&dir).args(["db", "compact"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "db_compact");
+    assert!(json["data"]["size_after"].is_u64());
+
+    cmd_in(&dir)
+        .args(["--human", "db", "stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Table"))
+        .stdout(predicate::str::contains("weight"));
+    cmd_in(&dir)
+        .args(["--human", "db", "check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Integrity check passed"));
+}
+
+// ─── med history ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_med_history_lists_take_events() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args([
+            "med",
+            "add",
+            "metformin",
+            "--dose",
+            "500mg",
+            "--freq",
+            "2x_daily",
+        ])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["med", "take", "metformin", "--note", "with breakfast"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["med", "take", "metformin", "--dose", "1000mg"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["med", "history", "--name", "metformin"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "med_history");
+    assert_eq!(json["data"]["name"], "metformin");
+    let history = json["data"]["history"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["dose_used"], "1000mg");
+    assert_eq!(history[1]["dose_used"], "500mg");
+    assert_eq!(history[1]["note"], "500mg; with breakfast");
+
+    cmd_in(&dir)
+        .args(["--human", "med", "history", "--name", "metformin"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# 500mg; with breakfast"));
+
+    cmd_in(&dir)
+        .args(["med", "history", "--name", "unknown"])
+        .assert()
+        .failure();
+}
+
+// ─── med add --freq <weekdays> ───────────────────────────────────────────────
+
+#[test]
+fn test_med_add_weekday_frequency() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["med", "add", "methotrexate", "--freq", "mon,wed,fri"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["status"], "ok");
+
+    let assert = cmd_in(&dir).args(["med", "list"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["medications"][0]["frequency"], "mon_wed_fri");
+
+    cmd_in(&dir)
+        .args(["--human", "med", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("mon wed fri"));
+
+    cmd_in(&dir)
+        .args(["med", "add", "other", "--freq", "mon,funday"])
+        .assert()
+        .failure();
+}
+
+// ─── report --goals ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_report_goals_section() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir).args(["report", "--goals"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["goals"], serde_json::json!([]));
+    cmd_in(&dir)
+        .args(["--human", "report", "--goals"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No active goals"));
+
+    cmd_in(&dir)
+        .args(["log", "weight", "82.5"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["goal", "set", "weight", "85", "below", "daily"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["goal", "set", "water", "2000", "above", "daily"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["report", "--goals"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["goals_total"], 2);
+    assert_eq!(json["data"]["goals_met"], 1);
+    let weight = json["data"]["goals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|g| g["metric_type"] == "weight")
+        .unwrap();
+    assert_eq!(weight["is_met"], true);
+    assert_eq!(weight["direction"], "below");
+
+    cmd_in(&dir)
+        .args(["--human", "report", "--goals"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Goals (1/2 met)"))
+        .stdout(predicate::str::contains("✓ WEIGHT: 82.5 kg < 85.0 (met)"))
+        .stdout(predicate::str::contains("✗ WATER: no data"));
+
+    let assert = cmd_in(&dir).args(["report"]).assert().success();
+    assert!(parse_json(&assert)["data"].get("goals").is_none());
+}
+
+// ─── note ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_note_add_and_show_by_tag() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args([
+            "note",
+            "add",
+            "Knee flared after stairs",
+            "--tags",
+            "pain,knee",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "note_add");
+    assert_eq!(json["data"]["entry"]["note"], "Knee flared after stairs");
+    cmd_in(&dir)
+        .args(["note", "add", "Good sleep", "--date", "2026-01-05"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["note", "show", "--tag", "knee"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "note_show");
+    let entries = json["data"]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["tags"], serde_json::json!(["pain", "knee"]));
+
+    cmd_in(&dir)
+        .args(["--human", "note", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "| Knee flared after stairs  [pain, knee]",
+        ))
+        .stdout(predicate::str::contains("2026-01-05"));
+
+    cmd_in(&dir).args(["trend", "journal"]).assert().failure();
+}
+
+// ─── error codes ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_error_codes_and_exit_status() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["goal", "remove", "no-such-goal"])
+        .assert()
+        .failure()
+        .code(3);
+    assert_eq!(parse_stderr_json(&assert)["error"]["code"], "not_found");
+
+    let missing = dir.path().join("missing.csv");
+    let assert = cmd_in(&dir)
+        .args(["import", "--source", "csv", "--file"])
+        .arg(&missing)
+        .assert()
+        .failure()
+        .code(3);
+    assert_eq!(parse_stderr_json(&assert)["error"]["code"], "not_found");
+
+    let assert = cmd_in(&dir)
+        .args(["log", "weight", "heavy"])
+        .assert()
+        .failure()
+        .code(2);
+    assert_eq!(parse_stderr_json(&assert)["error"]["code"], "parse_error");
+
+    let assert = cmd_in(&dir)
+        .args(["export", "--format", "xml"])
+        .assert()
+        .failure()
+        .code(2);
+    assert_eq!(
+        parse_stderr_json(&assert)["error"]["code"],
+        "validation_error"
+    );
+
+    cmd_in(&dir)
+        .args(["med", "add", "ibuprofen", "--freq", "daily"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir)
+        .args(["med", "add", "ibuprofen", "--freq", "daily"])
+        .assert()
+        .failure()
+        .code(5);
+    assert_eq!(parse_stderr_json(&assert)["error"]["code"], "conflict");
+}
+
+// ─── types ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_types_add_list_remove() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["types", "add", "hrv", "--unit", "ms", "--category", "body"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["types", "add", "weight", "--unit", "lb"])
+        .assert()
+        .failure()
+        .code(5);
+
+    let assert = cmd_in(&dir).args(["types", "list"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "types");
+    assert!(
+        json["data"]["builtin"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|t| t["name"] == "weight" && t["unit"] == "kg")
+    );
+    assert_eq!(json["data"]["custom"][0]["name"], "hrv");
+    assert_eq!(json["data"]["custom"][0]["category"], "body");
+    assert_eq!(json["data"]["custom"][0]["entries"], 0);
+
+    let assert = cmd_in(&dir).args(["log", "hrv", "55"]).assert().success();
+    assert_eq!(parse_json(&assert)["data"]["entry"]["unit"], "ms");
+
+    let assert = cmd_in(&dir)
+        .args(["types", "list", "--type", "hrv"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["kind"], "custom");
+    assert_eq!(json["data"]["entries"], 1);
+    assert_eq!(json["data"]["min"], 55.0);
+    cmd_in(&dir)
+        .args(["--human", "types", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Custom types:"))
+        .stdout(predicate::str::contains("hrv"));
+    cmd_in(&dir)
+        .args(["types", "list", "--type", "nonsense"])
+        .assert()
+        .failure()
+        .code(3);
+
+    cmd_in(&dir)
+        .args(["types", "remove", "hrv"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    cmd_in(&dir)
+        .args(["types", "remove", "hrv", "--force"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_migrate_type_requires_yes_and_previews() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir).args(["log", "run", "30"]).assert().success();
+    cmd_in(&dir).args(["log", "run", "40"]).assert().success();
+
+    cmd_in(&dir)
+        .args(["migrate-type", "run", "cardio"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("--yes"));
+
+    let assert = cmd_in(&dir)
+        .args(["migrate-type", "run", "cardio", "--dry-run"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "migrate_type");
+    assert_eq!(json["data"]["dry_run"], true);
+    assert_eq!(json["data"]["metrics"], 2);
+
+    cmd_in(&dir)
+        .args(["--human", "migrate-type", "run", "cardio", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Moved 2 run entries to cardio"));
+    let assert = cmd_in(&dir)
+        .args(["show", "cardio", "--last", "5"])
+        .assert()
+        .success();
+    assert_eq!(
+        parse_json(&assert)["data"]["entries"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+// ─── log --unit ──────────────────────────────────────────────────────────────
+
+#[test]
+fn test_log_unit_override_persists_on_show() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["log", "cardio", "1.5", "--unit", "hours"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["unit"], "hours");
+    assert_eq!(json["data"]["entry"]["value"], 1.5);
+
+    cmd_in(&dir)
+        .args(["--human", "log", "cardio", "2", "--unit", "hours"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Note: logging in 'hours' (default is 'min')",
+        ))
+        .stdout(predicate::str::contains("cardio = 2 hours"));
+    cmd_in(&dir)
+        .args(["--human", "log", "cardio", "30", "--unit", "min"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Note:").not());
+
+    let assert = cmd_in(&dir)
+        .args(["show", "cardio", "--last", "3"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let units: Vec<_> = json["data"]["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["unit"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(units, vec!["min", "hours", "hours"]);
+
+    cmd_in(&dir)
+        .args(["--human", "show", "cardio", "--last", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cardio = 1.5 hours"))
+        .stdout(predicate::str::contains("cardio = 30 min"));
+}
+
+// ─── remind ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_remind_exit_code_and_output() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["config", "set", "reminders.daily", "weight,water"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["remind"]).assert().code(10);
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "remind");
+    assert_eq!(
+        json["data"],
+        serde_json::json!([
+            {"item": "weight", "kind": "metric", "missing": 1},
+            {"item": "water", "kind": "metric", "missing": 1}
+        ])
+    );
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["--human", "remind"])
+        .assert()
+        .code(10)
+        .stdout(predicate::str::contains("Not logged today: water"))
+        .stdout(predicate::str::contains("weight").not());
+
+    cmd_in(&dir)
+        .args(["log", "water", "500"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["remind", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+// ─── log --expression ────────────────────────────────────────────────────────
+
+#[test]
+fn test_log_expression_value() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["log", "calories_burned", "--expr", "350 * 0.9"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["value"], 315.0);
+
+    cmd_in(&dir)
+        .args(["log", "water", "500", "--expression", "2 * 250"])
+        .assert()
+        .failure();
+    let assert = cmd_in(&dir)
+        .args(["log", "water", "--expression", "500 / 0"])
+        .assert()
+        .failure()
+        .code(2);
+    assert!(
+        parse_stderr_json(&assert)["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("division by zero")
+    );
+}
+
+// ─── goal percent complete ───────────────────────────────────────────────────
+
+#[test]
+fn test_goal_status_percent_complete() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["goal", "set", "weight", "75", "below", "monthly"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "weight", "77"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["goal", "status"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["goals"][0]["start_value"], 80.0);
+    assert_eq!(json["data"]["goals"][0]["percent_complete"], 60.0);
+
+    cmd_in(&dir)
+        .args(["goal", "status", "--human"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[######----] 60%"));
+}
+
+// ─── context --narrative ─────────────────────────────────────────────────────
+
+#[test]
+fn test_context_narrative_json_and_human() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "82.4"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["context", "--narrative"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let text = json["data"]["narrative"].as_str().unwrap();
+    assert!(text.contains("82.4 kg"), "{}", text);
+    assert!(json["data"]["metrics"]["weight"].is_object());
+
+    let assert = cmd_in(&dir)
+        .args(["context", "--narrative", "--days", "3", "--human"])
+        .assert()
+        .success();
+    let out = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(out.starts_with("Over the past 3 days"), "{}", out);
+    assert!(!out.contains("=== Health Context"));
+
+    let assert = cmd_in(&dir).args(["context"]).assert().success();
+    assert!(parse_json(&assert)["data"].get("narrative").is_none());
+}
+
+// ─── measure ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_measure_logs_session_and_rejects_bad_pair() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["measure", "waist=84,hips=98", "chest=101"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "measure");
+    assert_eq!(json["data"]["count"], 3);
+    assert_eq!(json["data"]["entries"][0]["type"], "waist");
+    assert_eq!(json["data"]["entries"][2]["value"], 101.0);
+
+    let assert = cmd_in(&dir)
+        .args(["measure", "waist=80,arms=x"])
+        .assert()
+        .failure()
+        .code(2);
+    let err = parse_stderr_json(&assert);
+    assert!(err["error"]["message"].as_str().unwrap().contains("arms=x"));
+
+    let assert = cmd_in(&dir)
+        .args(["show", "waist", "--last", "5"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"].as_array().unwrap().len(), 1);
+}
+
+// ─── per-type units ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_config_units_override_water_oz() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["config", "set", "units.water", "oz"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["config", "set", "units.custom.run_distance", "km"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["config", "show"]).assert().success();
+    let json = parse_json(&assert);
+    let custom = &json["data"]["config"]["units"]["custom"];
+    assert_eq!(custom["water"]["unit"], "oz");
+    assert!((custom["water"]["factor"].as_f64().unwrap() - 29.5735).abs() < 1e-9);
+    assert_eq!(custom["run_distance"]["unit"], "km");
+    assert!(custom["run_distance"].get("factor").is_none());
+
+    let assert = cmd_in(&dir).args(["log", "water", "16"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["unit"], "ml");
+    assert_eq!(
+        json["data"]["entry"]["value"].as_f64().unwrap().round(),
+        473.0
+    );
+
+    cmd_in(&dir)
+        .args(["show", "water", "--human"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("water = 16 oz"));
+    cmd_in(&dir)
+        .args(["log", "run_distance", "5.2", "--human"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("run_distance = 5.2 km"));
+}
+
+// ─── export --since-last ─────────────────────────────────────────────────────
+
+#[test]
+fn test_export_since_last_is_incremental() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let out = dir.path().join("export.json");
+    let export = || {
+        let assert = cmd_in(&dir)
+            .args(["export", "--since-last", "--output"])
+            .arg(&out)
+            .assert()
+            .success();
+        parse_json(&assert)
+    };
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "steps", "9000"])
+        .assert()
+        .success();
+
+    // No state file yet: everything is exported
+    let json = export();
+    assert!(json["data"]["since"].is_null());
+    assert_eq!(json["data"]["count"], 2);
+    let state: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("last_export.json")).unwrap())
+            .unwrap();
+    let recorded = state["timestamp"].as_str().unwrap().to_string();
+    assert!(recorded.parse::<chrono::DateTime<chrono::Utc>>().is_ok());
+
+    cmd_in(&dir)
+        .args(["log", "water", "500"])
+        .assert()
+        .success();
+    let json = export();
+    assert_eq!(json["data"]["since"], recorded.as_str());
+    assert_eq!(json["data"]["count"], 1);
+    let exported = fs::read_to_string(&out).unwrap();
+    assert!(exported.contains("water") && !exported.contains("weight"));
+
+    cmd_in(&dir)
+        .args(["export", "--since-last", "--from", "2026-01-01"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+// ─── status blood pressure stage ────────────────────────────────────────────
+
+#[test]
+fn test_status_stages_blood_pressure() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "blood_pressure", "135/85"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).arg("status").assert().success();
+    let json = parse_json(&assert);
+    let bp = &json["data"]["today"]["blood_pressure"];
+    assert_eq!(bp["systolic"], 135.0);
+    assert_eq!(bp["category"], "stage_1");
+    assert_eq!(bp["alert"], false);
+
+    cmd_in(&dir)
+        .args([
+            "config",
+            "set",
+            "alerts.blood_pressure.stage1_systolic",
+            "abc",
+        ])
+        .assert()
+        .failure()
+        .code(2);
+    cmd_in(&dir)
+        .args([
+            "config",
+            "set",
+            "alerts.blood_pressure.stage3_systolic",
+            "150",
+        ])
+        .assert()
+        .failure()
+        .code(2);
+    cmd_in(&dir)
+        .args([
+            "config",
+            "set",
+            "alerts.blood_pressure.stage2_diastolic",
+            "84",
+        ])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir).arg("status").assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(
+        json["data"]["today"]["blood_pressure"]["category"],
+        "stage_2"
+    );
+}
+
+// ─── export --format jsonl ──────────────────────────────────────────────────
+
+#[test]
+fn test_export_jsonl_round_trip() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "82"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "steps", "9000"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "water", "500"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["export", "--format", "jsonl"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert_eq!(stdout.lines().count(), 3);
+
+    let file = dir.path().join("data.jsonl");
+    fs::write(&file, &stdout).unwrap();
+    let dir2 = TempDir::new().unwrap();
+    init_dir(&dir2);
+    let assert = cmd_in(&dir2)
+        .args(["import", "--source", "jsonl", "--file"])
+        .arg(&file)
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["imported"], 3);
+    assert_eq!(json["data"]["errors"], 0);
+}
+
+// ─── cycle status ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_cycle_status_and_context() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let today = chrono::Utc::now().date_naive();
+    for back in [61, 60, 32, 31, 30, 3, 2] {
+        let day = (today - chrono::Duration::days(back)).to_string();
+        cmd_in(&dir)
+            .args(["log", "cycle", "3", "--date", &day])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir).args(["cycle", "status"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "cycle_status");
+    assert_eq!(json["data"]["cycles_logged"], 3);
+    assert_eq!(json["data"]["current_day"], 4);
+    assert_eq!(json["data"]["average_length"], 29.0);
+
+    let assert = cmd_in(&dir)
+        .args(["context", "--days", "7"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["cycle"]["current_day"], 4);
+
+    cmd_in(&dir)
+        .args(["config", "set", "cycle.gap_days", "0"])
+        .assert()
+        .failure()
+        .code(2);
+    cmd_in(&dir)
+        .args(["config", "set", "cycle.gap_days", "40"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir).args(["cycle", "status"]).assert().success();
+    assert_eq!(parse_json(&assert)["data"]["cycles_logged"], 1);
+}
+
+// ─── export --compress ──────────────────────────────────────────────────────
+
+#[test]
+fn test_export_compressed_round_trip() {
+    use std::io::Read;
+
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "82"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "water", "500"])
+        .assert()
+        .success();
+
+    // .gz output is compressed without --compress
+    let gz = dir.path().join("data.json.gz");
+    let assert = cmd_in(&dir)
+        .args(["export", "--output"])
+        .arg(&gz)
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["format"], "json");
+    assert_eq!(json["data"]["compressed"], true);
+    let bytes = fs::read(&gz).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+    assert_eq!(json["data"]["compressed_size"], bytes.len());
+    let mut original = String::new();
+    flate2::read::GzDecoder::new(bytes.as_slice())
+        .read_to_string(&mut original)
+        .unwrap();
+    assert_eq!(json["data"]["original_size"], original.len());
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&original).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    // --compress on any path, and import detects gzip by content
+    let packed = dir.path().join("data.bin");
+    cmd_in(&dir)
+        .args(["export", "--format", "csv", "--compress", "--output"])
+        .arg(&packed)
+        .assert()
+        .success();
+    assert_eq!(&fs::read(&packed).unwrap()[..2], &[0x1f, 0x8b]);
+
+    let dir2 = TempDir::new().unwrap();
+    init_dir(&dir2);
+    let assert = cmd_in(&dir2)
+        .args(["import", "--source", "json", "--file"])
+        .arg(&gz)
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["data"]["imported"], 2);
+    let dir3 = TempDir::new().unwrap();
+    init_dir(&dir3);
+    let assert = cmd_in(&dir3)
+        .args(["import", "--source", "csv", "--file"])
+        .arg(&packed)
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["data"]["imported"], 2);
+
+    cmd_in(&dir)
+        .args(["export", "--compress"])
+        .assert()
+        .failure();
+}
+
+// ─── glucose time in range ──────────────────────────────────────────────────
+
+#[test]
+fn test_glucose_in_mmol_and_status_time_in_range() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["config", "set", "units.glucose", "mmol/L"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["config", "set", "glucose.target_high", "9"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["config", "set", "glucose.target_low", "12"])
+        .assert()
+        .failure()
+        .code(2);
+
+    let assert = cmd_in(&dir)
+        .args(["log", "glucose", "5.5"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["value"], 99.0);
+    cmd_in(&dir)
+        .args(["log", "glucose", "10"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).arg("status").assert().success();
+    let json = parse_json(&assert);
+    let g = &json["data"]["today"]["glucose"];
+    assert_eq!(g["readings"], 2);
+    assert_eq!(g["highs"], 1);
+    assert_eq!(g["in_range_pct"], 50.0);
+
+    let assert = cmd_in(&dir).args(["--human", "status"]).assert().success();
+    let out = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(
+        out.contains("Glucose: avg 7.8 mmol/L, 50% in range"),
+        "{}",
+        out
+    );
+}
+
+// ─── relative dates ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_relative_date_flags() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let today = chrono::Utc::now().date_naive();
+    let yesterday = today - chrono::Duration::days(1);
+
+    let assert = cmd_in(&dir)
+        .args(["log", "weight", "80", "--date", "yesterday"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let ts = json["data"]["entry"]["timestamp"].as_str().unwrap();
+    assert!(ts.starts_with(&yesterday.to_string()), "{}", ts);
+
+    let assert = cmd_in(&dir)
+        .args(["export", "--from", "3 days ago", "--to", "today"])
+        .assert()
+        .success();
+    let out = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+    assert_eq!(entries.len(), 1);
+
+    cmd_in(&dir)
+        .args(["show", "weight", "--from", "next tuesday"])
+        .assert()
+        .failure();
+}
+
+// ─── workout sessions ───────────────────────────────────────────────────────
+
+#[test]
+fn test_workout_sessions_survive_export_round_trip() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let assert = cmd_in(&dir)
+        .args([
+            "workout",
+            "run",
+            "--duration",
+            "45",
+            "--distance",
+            "7.5",
+            "--intensity",
+            "7",
+            "--note",
+            "intervals",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "workout");
+    assert_eq!(json["data"]["count"], 3);
+    let session = json["data"]["session"].as_str().unwrap().to_string();
+
+    let assert = cmd_in(&dir)
+        .args(["export", "--format", "jsonl"])
+        .assert()
+        .success();
+    let file = dir.path().join("data.jsonl");
+    fs::write(&file, &assert.get_output().stdout).unwrap();
+
+    let dir2 = TempDir::new().unwrap();
+    init_dir(&dir2);
+    cmd_in(&dir2)
+        .args(["import", "--source", "jsonl", "--file"])
+        .arg(&file)
+        .assert()
+        .success();
+    let assert = cmd_in(&dir2).args(["workout", "list"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "workout_list");
+    let sessions = json["data"]["sessions"].as_array().unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["session"], session.as_str());
+    assert_eq!(sessions[0]["kind"], "run");
+    assert_eq!(sessions[0]["duration"], 45.0);
+    assert_eq!(sessions[0]["distance"], 7.5);
+    assert_eq!(sessions[0]["intensity"], 7.0);
+
+    let assert = cmd_in(&dir2).args(["report"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["workouts"][0]["sessions"], 1);
+    assert_eq!(json["data"]["workouts"][0]["total_distance"], 7.5);
+}
+
+// ─── log --interactive ──────────────────────────────────────────────────────
+
+#[test]
+fn test_log_interactive_without_tty_logs_with_defaults() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let assert = cmd_in(&dir)
+        .args(["log", "focus_score", "7", "--interactive"])
+        .write_stdin("1-10\nhabit\ny\n")
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(!stdout.contains("Unit for"));
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["type"], "focus_score");
+    assert_eq!(json["data"]["entry"]["unit"], "");
+
+    let assert = cmd_in(&dir)
+        .args(["types", "list", "--type", "focus_score"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["kind"], "observed");
+}
+
+// ─── profiles ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_profiles_keep_data_apart() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+
+    // An unknown profile is refused rather than silently created
+    cmd_in(&dir)
+        .args(["--profile", "anna", "log", "weight", "61"])
+        .assert()
+        .code(3);
+
+    cmd_in(&dir)
+        .args(["profile", "create", "anna"])
+        .assert()
+        .success();
+    assert!(dir.path().join("profiles/anna/config.toml").exists());
+    let assert = cmd_in(&dir)
+        .args(["--profile", "anna", "log", "weight", "61"])
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["profile"], "anna");
+
+    let assert = cmd_in(&dir).args(["show", "weight"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["profile"], "default");
+    assert_eq!(json["data"]["entries"][0]["value"], 80.0);
+
+    // The default profile is remembered and used without --profile
+    cmd_in(&dir)
+        .args(["profile", "default", "anna"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir).args(["show", "weight"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["profile"], "anna");
+    assert_eq!(json["data"]["entries"][0]["value"], 61.0);
+
+    let assert = cmd_in(&dir).args(["profile", "list"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["default"], "anna");
+    assert_eq!(json["data"]["profiles"].as_array().unwrap().len(), 2);
+
+    // Removal needs --yes and never takes the default profile
+    cmd_in(&dir)
+        .args(["profile", "remove", "anna"])
+        .assert()
+        .code(2);
+    cmd_in(&dir)
+        .args(["profile", "remove", "anna", "--yes"])
+        .assert()
+        .code(2);
+    cmd_in(&dir)
+        .args(["profile", "remove", "default", "--yes"])
+        .assert()
+        .code(2);
+    cmd_in(&dir)
+        .args(["profile", "default", "default"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["profile", "remove", "anna", "--yes"])
+        .assert()
+        .success();
+    assert!(!dir.path().join("profiles/anna").exists());
+}
+
+// ─── fuzzy metric types ─────────────────────────────────────────────────────
+
+#[test]
+fn test_log_suggests_or_resolves_typos() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir).args(["log", "wheight", "80"]).assert().code(2);
+    let err = parse_stderr_json(&assert);
+    assert!(
+        err["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Did you mean: weight?")
+    );
+
+    let assert = cmd_in(&dir)
+        .args(["log", "wheight", "80", "--fuzzy"])
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["data"]["entry"]["type"], "weight");
+
+    cmd_in(&dir)
+        .args(["config", "set", "log.fuzzy_match", "true"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir)
+        .args(["log", "waterr", "500"])
+        .assert()
+        .success();
+    assert_eq!(parse_json(&assert)["data"]["entry"]["type"], "water");
+
+    // Far from every known type: a new custom type, as before
+    let assert = cmd_in(&dir).args(["log", "hrv", "55"]).assert().success();
+    assert_eq!(parse_json(&assert)["data"]["entry"]["type"], "hrv");
+
+    for args in [["show", "sleep_hour"], ["trend", "sleep_hour"]] {
+        let assert = cmd_in(&dir).args(args).assert().code(3);
+        let err = parse_stderr_json(&assert);
+        assert!(
+            err["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("Did you mean: sleep_hours?")
+        );
+    }
+}
+
+// ─── metric ranges ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_ranges_annotate_show_status_and_report_without_touching_goals() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["config", "set", "range.sleep_hours", "7-9"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["config", "set", "range.sleep_hours", "9-7"])
+        .assert()
+        .code(2);
+    cmd_in(&dir)
+        .args([
+            "goal",
+            "set",
+            "sleep_hours",
+            "--target",
+            "6",
+            "--direction",
+            "above",
+            "--timeframe",
+            "daily",
+        ])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "sleep_hours", "6.5"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["show", "sleep_hours"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"][0]["range_status"], "below");
+    let assert = cmd_in(&dir).args(["show", "weight"]).assert().success();
+    let json = parse_json(&assert);
+    assert!(json["data"]["entries"][0].get("range_status").is_none());
+
+    let assert = cmd_in(&dir).args(["status"]).assert().success();
+    let json = parse_json(&assert);
+    let ranges = json["data"]["today"]["ranges"].as_array().unwrap();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0]["range_status"], "below");
+
+    let assert = cmd_in(&dir).args(["report"]).assert().success();
+    let json = parse_json(&assert);
+    let sleep = json["data"]["metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["type"] == "sleep_hours")
+        .unwrap();
+    assert_eq!(sleep["range_status"], "below");
+
+    // The goal is judged on its own target, not the range
+    let assert = cmd_in(&dir)
+        .args(["goal", "status", "sleep_hours"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["goals"][0]["is_met"], true);
+}
+
+// ─── show --stats ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_show_stats_covers_shown_entries_only() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (date, v) in [("2026-01-01", "80.0"), ("2026-01-02", "82.0")] {
+        cmd_in(&dir)
+            .args(["log", "weight", v, "--date", date])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["show", "weight", "--last", "1", "--stats"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let stats = &json["data"]["statistics"];
+    assert_eq!(stats["count"], 1);
+    assert_eq!(stats["mean"].as_f64().unwrap(), 82.0);
+    assert_eq!(stats["min"].as_f64().unwrap(), 82.0);
+    assert_eq!(stats["max"].as_f64().unwrap(), 82.0);
+    assert_eq!(stats["std_dev"].as_f64().unwrap(), 0.0);
+
+    let assert = cmd_in(&dir)
+        .args(["show", "weight", "--last", "1"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert!(json["data"].get("statistics").is_none());
+
+    // Without a type: per-type statistics for the day
+    let assert = cmd_in(&dir)
+        .args(["show", "--date", "2026-01-01", "--stats"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["statistics"]["weight"]["count"], 1);
+    assert_eq!(
+        json["data"]["statistics"]["weight"]["mean"]
+            .as_f64()
+            .unwrap(),
+        80.0
+    );
+
+    cmd_in(&dir)
+        .args(["--human", "show", "weight", "--stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("── Statistics (N=2)"))
+        .stdout(predicate::str::contains(
+            "Mean: 81.0 kg  Min: 80.0  Max: 82.0",
+        ));
+}
+
+#[test]
+fn test_show_stats_covers_from_to_window() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (date, v) in [
+        ("2026-01-01", "80.0"),
+        ("2026-01-02", "82.0"),
+        ("2026-01-03", "84.0"),
+        ("2026-01-04", "90.0"),
+    ] {
+        cmd_in(&dir)
+            .args(["log", "weight", v, "--date", date])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args([
+            "show",
+            "weight",
+            "--from",
+            "2026-01-02",
+            "--to",
+            "2026-01-03",
+            "--stats",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"].as_array().unwrap().len(), 2);
+    let stats = &json["data"]["statistics"];
+    assert_eq!(stats["count"], 2);
+    assert_eq!(stats["mean"].as_f64().unwrap(), 83.0);
+    assert_eq!(stats["min"].as_f64().unwrap(), 82.0);
+    assert_eq!(stats["max"].as_f64().unwrap(), 84.0);
+
+    // --last keeps the newest entries within the window
+    let assert = cmd_in(&dir)
+        .args([
+            "show",
+            "weight",
+            "--from",
+            "2026-01-02",
+            "--last",
+            "1",
+            "--stats",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["statistics"]["count"], 1);
+    assert_eq!(json["data"]["statistics"]["mean"].as_f64().unwrap(), 90.0);
+}
+
+// ─── report --digest ────────────────────────────────────────────────────────
+
+#[test]
+fn test_report_digest_empty_prints_single_line() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["report", "--digest"])
+        .assert()
+        .success()
+        .stdout("No data logged this week\n");
+}
+
+#[test]
+fn test_report_digest_plain_text_with_deltas_and_goals() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (date, v) in [("2026-01-03", "82.0"), ("2026-01-10", "81.0")] {
+        cmd_in(&dir)
+            .args(["log", "weight", v, "--date", date])
+            .assert()
+            .success();
+    }
+    cmd_in(&dir)
+        .args(["goal", "set", "weight", "75", "below", "weekly"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args([
+            "report",
+            "--digest",
+            "--from",
+            "2026-01-08",
+            "--to",
+            "2026-01-14",
+        ])
+        .assert()
+        .success();
+    let text = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(text.starts_with("OpenVital digest 2026-01-08 to 2026-01-14\n"));
+    assert!(text.contains("Compared with 2026-01-01 to 2026-01-07"));
+    assert!(text.contains("weight           avg   81.0 kg        -1.0 vs prev"));
+    assert!(text.contains("Goals: 0/1 met"));
+    assert!(
+        text.lines()
+            .all(|l| l.is_ascii() && l.len() < 80 && !l.contains('─'))
+    );
+}
+
+#[test]
+fn test_report_digest_conflicts_with_format() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["report", "--digest", "--format", "markdown"])
+        .assert()
+        .failure();
+}
+
+// ─── show --reverse ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_show_reverse_lists_oldest_first() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (date, v) in [
+        ("2026-01-01", "81"),
+        ("2026-01-02", "82"),
+        ("2026-01-03", "83"),
+    ] {
+        cmd_in(&dir)
+            .args(["log", "weight", v, "--date", date])
+            .assert()
+            .success();
+    }
+    let values = |json: &serde_json::Value| -> Vec<f64> {
+        json["data"]["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["value"].as_f64().unwrap())
+            .collect()
+    };
+
+    let json = parse_json(&cmd_in(&dir).args(["show", "weight"]).assert().success());
+    assert_eq!(values(&json), vec![83.0, 82.0, 81.0]);
+    assert_eq!(json["data"]["order"], "desc");
+
+    let json = parse_json(
+        &cmd_in(&dir)
+            .args(["show", "weight", "--reverse"])
+            .assert()
+            .success(),
+    );
+    assert_eq!(values(&json), vec![81.0, 82.0, 83.0]);
+    assert_eq!(json["data"]["order"], "asc");
+
+    let assert = cmd_in(&dir)
+        .args(["--human", "show", "weight", "--reverse"])
+        .assert()
+        .success();
+    let text = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(text.find("2026-01-01").unwrap() < text.find("2026-01-03").unwrap());
+}
+
+// ─── trend --fill ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_trend_fill_reported_in_json() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for date in ["2026-01-01", "2026-01-03"] {
+        cmd_in(&dir)
+            .args(["log", "weight", "80", "--date", date])
+            .assert()
+            .success();
+    }
+
+    let json = parse_json(
+        &cmd_in(&dir)
+            .args(["trend", "weight", "--period", "daily", "--fill", "forward"])
+            .assert()
+            .success(),
+    );
+    assert_eq!(json["data"]["fill"]["method"], "forward");
+    assert_eq!(json["data"]["fill"]["max_gap_days"], 3);
+    assert_eq!(json["data"]["fill"]["synthetic_points"], 1);
+    assert_eq!(json["data"]["data"][1]["filled"], 1);
+
+    cmd_in(&dir)
+        .args(["trend", "weight", "--fill", "cubic"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+// ─── show --format ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_show_format_jsonl_one_line_per_entry() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    for (date, v) in [
+        ("2026-01-01", "80.0"),
+        ("2026-01-02", "81.0"),
+        ("2026-01-03", "82.0"),
+    ] {
+        cmd_in(&dir)
+            .args(["log", "weight", v, "--date", date])
+            .assert()
+            .success();
+    }
+
+    let assert = cmd_in(&dir)
+        .args(["show", "weight", "--format", "jsonl"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+    let first: Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(first["type"], "weight");
+    assert_eq!(first["value"].as_f64().unwrap(), 82.0);
+    assert!(first.get("status").is_none());
+
+    cmd_in(&dir)
+        .args(["show", "weight", "--format", "jsonl", "--human"])
+        .assert()
+        .failure();
+    cmd_in(&dir)
+        .args(["--human", "show", "weight", "--format", "jsonl"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_show_format_csv_matches_export_header() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "80.0", "--date", "2026-01-01"])
+        .assert()
+        .success();
+
+    let export = cmd_in(&dir)
+        .args(["export", "--format", "csv"])
+        .assert()
+        .success();
+    let export = String::from_utf8(export.get_output().stdout.clone()).unwrap();
+
+    let assert = cmd_in(&dir)
+        .args(["show", "weight", "--format", "csv"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert_eq!(stdout.lines().next(), export.lines().next());
+    assert_eq!(stdout.lines().count(), 2);
+}
+
+// ─── insights ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_insights_json_and_human() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir).args(["insights"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "insights");
+    assert!(json["data"]["insights"].as_array().unwrap().is_empty());
+
+    cmd_in(&dir)
+        .args(["insights", "--human"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing notable"));
+
+    cmd_in(&dir)
+        .args(["insights", "--days", "0"])
+        .assert()
+        .code(2);
+}
+
+// ─── weight-history ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_weight_history_requires_height() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "81", "--date", "2026-01-01"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir).args(["weight-history"]).assert().code(2);
+    let json = parse_stderr_json(&assert);
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("height is not configured")
+    );
+
+    cmd_in(&dir)
+        .args(["config", "set", "height", "180"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir).args(["weight-history"]).assert().success();
+    let json = parse_json(&assert);
+    let entry = &json["data"]["entries"][0];
+    assert_eq!(entry["value"].as_f64().unwrap(), 81.0);
+    assert_eq!(entry["bmi"].as_f64().unwrap(), 25.0);
+    assert_eq!(entry["bmi_category"], "overweight");
+}
+
+// ─── trend --by weekday ─────────────────────────────────────────────────────
+
+#[test]
+fn test_trend_by_weekday_json_and_conflicts() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "sleep_hours", "7"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["trend", "sleep_hours", "--by", "weekday"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let weekdays = json["data"]["weekdays"].as_array().unwrap();
+    assert_eq!(weekdays.len(), 7);
+    assert_eq!(weekdays[0]["weekday"], "monday");
+    let counted: u64 = weekdays.iter().map(|d| d["count"].as_u64().unwrap()).sum();
+    assert_eq!(counted, 1);
+
+    cmd_in(&dir)
+        .args([
+            "trend",
+            "sleep_hours",
+            "--by",
+            "weekday",
+            "--period",
+            "daily",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+// ─── log --confirm ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_log_confirm_ignored_without_terminal() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let assert = cmd_in(&dir)
+        .args(["log", "weight", "80", "--confirm"])
+        .write_stdin("n\n")
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert!(json["data"]["entry"]["id"].is_string());
+
+    cmd_in(&dir)
+        .args(["log", "bp", "120/80", "--confirm"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bp_diastolic"));
+
+    let assert = cmd_in(&dir).args(["show", "weight"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"].as_array().unwrap().len(), 1);
+}
+
+// ─── log relative values ────────────────────────────────────────────────────
+
+#[test]
+fn test_log_relative_value() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let assert = cmd_in(&dir)
+        .args(["log", "weight", "+0.5"])
+        .assert()
+        .failure()
+        .code(3);
+    let json = parse_stderr_json(&assert);
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("no previous 'weight' entry")
+    );
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir)
+        .args(["log", "weight", "-0.5"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["delta"], -0.5);
+    assert_eq!(json["data"]["entry"]["value"], 79.5);
+
+    cmd_in(&dir)
+        .args(["--human", "log", "water", "+250"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(+250)"));
+}
+
+// ─── sync ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_sync_requires_configuration() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let assert = cmd_in(&dir).args(["sync"]).assert().failure().code(2);
+    let json = parse_stderr_json(&assert);
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("sync.url is not set")
+    );
+
+    cmd_in(&dir)
+        .args(["sync", "--download"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--yes"));
+}
+
+// ─── sleep ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_sleep_logs_bed_and_wake_times() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let assert = cmd_in(&dir)
+        .args(["sleep", "23:30", "07:10", "--quality", "4"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "sleep");
+    assert_eq!(json["data"]["count"], 4);
+    assert_eq!(json["data"]["duration_minutes"], 460);
+    assert_eq!(json["data"]["entries"][0]["type"], "sleep_hours");
+
+    cmd_in(&dir)
+        .args(["--human", "sleep", "22:50", "06:30"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Slept 7 h 40 min"));
+
+    cmd_in(&dir)
+        .args(["sleep", "09:00", "08:00"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+// ─── status --watch ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_status_watch_requires_human() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let assert = cmd_in(&dir)
+        .args(["status", "--watch", "5"])
+        .assert()
+        .failure()
+        .code(2);
+    let json = parse_stderr_json(&assert);
+    assert_eq!(
+        json["error"]["message"],
+        "Watch mode only supported with --human"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_status_watch_stops_on_sigint() {
+    use std::process::{Command, Stdio};
+
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let child = Command::new(env!("CARGO_BIN_EXE_openvital"))
+        .env("OPENVITAL_HOME", dir.path())
+        .args(["--human", "status", "--watch", "0"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    let killed = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // A period of 0 is clamped to 1 second
+    assert!(stdout.contains("Refreshing every 1s (Ctrl-C to stop)"));
+    assert!(stdout.contains("\x1B[2J\x1B[H"));
+    assert!(stdout.trim_end().ends_with("Stopped watching"));
+}
+
+// ─── goal completion on log ─────────────────────────────────────────────────
+
+#[test]
+fn test_log_reports_goal_completion_once() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["goal", "set", "water", "2000", "above", "daily"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["log", "water", "1500"])
+        .assert()
+        .success();
+    assert!(parse_json(&assert)["data"].get("goal_completed").is_none());
+
+    cmd_in(&dir)
+        .args(["--human", "log", "water", "650"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "🎯 Daily water goal met (2150/2000 ml)",
+        ));
+
+    let assert = cmd_in(&dir)
+        .args(["log", "water", "250"])
+        .assert()
+        .success();
+    assert!(parse_json(&assert)["data"].get("goal_completed").is_none());
+}
+
+#[test]
+fn test_log_goal_completed_json_and_skip_flag() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["goal", "set", "water", "2000", "above", "daily"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["log", "--batch", "water:1500", "--no-goal-check"])
+        .assert()
+        .success();
+    assert!(parse_json(&assert)["data"].get("goals_completed").is_none());
+
+    let assert = cmd_in(&dir)
+        .args(["log", "water", "500"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    let done = &json["data"]["goal_completed"];
+    assert!(done["goal_id"].is_string());
+    assert_eq!(done["target"], 2000.0);
+    assert_eq!(done["current"], 2000.0);
+}
+
+// ─── --color ────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_color_flag_controls_ansi_codes() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["goal", "set", "water", "2000", "above", "daily"])
+        .assert()
+        .success();
+
+    let never: [&[&str]; 2] = [
+        &["--human", "--color", "never", "status"],
+        &["--human", "--color", "never", "goal", "status"],
+    ];
+    for args in never {
+        let out = cmd_in(&dir).args(args).assert().success();
+        let stdout = String::from_utf8(out.get_output().stdout.clone()).unwrap();
+        assert!(!stdout.contains('\x1B'), "{:?}: {}", args, stdout);
+    }
+
+    cmd_in(&dir)
+        .args(["--human", "--color", "always", "goal", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[\x1B[31m...\x1B[0m] water"));
+    cmd_in(&dir)
+        .args(["--human", "--color", "always", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1B[1m=== OpenVital Status"));
+
+    // Piped output in auto mode stays plain
+    let out = cmd_in(&dir).args(["--human", "status"]).assert().success();
+    assert!(!String::from_utf8_lossy(&out.get_output().stdout).contains('\x1B'));
+}
+
+// ─── med take --skip ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_med_take_skip_records_skip() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["med", "add", "warfarin", "--dose", "5mg", "--freq", "daily"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args([
+            "med",
+            "take",
+            "warfarin",
+            "--skip",
+            "--reason",
+            "surgery tomorrow",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "med_skip");
+    assert_eq!(json["data"]["reason"], "surgery tomorrow");
+    assert_eq!(json["data"]["entry"]["value"], 0.0);
+    assert_eq!(json["data"]["entry"]["source"], "med_skip");
+
+    let assert = cmd_in(&dir)
+        .args(["med", "status", "warfarin"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["taken_today"], 0);
+    assert_eq!(json["data"]["skipped_today"], 1);
+    assert!(json["data"]["adherent_today"].is_null());
+
+    cmd_in(&dir)
+        .args(["--human", "med", "status", "warfarin"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0/1 taken today, 1 skipped"))
+        .stdout(predicate::str::contains("SKIPPED"));
+
+    // --reason needs --skip; --skip excludes intake options
+    cmd_in(&dir)
+        .args(["med", "take", "warfarin", "--reason", "x"])
+        .assert()
+        .failure();
+    cmd_in(&dir)
+        .args(["med", "take", "warfarin", "--skip", "--quantity", "2"])
+        .assert()
+        .failure();
+}
+
+// ─── med add --interacts-with ────────────────────────────────────────────────
+
+#[test]
+fn test_med_interaction_warns_on_take() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["med", "add", "aspirin", "--freq", "daily"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir)
+        .args([
+            "med",
+            "add",
+            "warfarin",
+            "--freq",
+            "daily",
+            "--interacts-with",
+            "aspirin:120",
+        ])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["interactions"][0]["with"], "aspirin");
+    assert_eq!(json["data"]["interactions"][0]["min_gap_minutes"], 120);
+
+    cmd_in(&dir)
+        .args(["--human", "med", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "interacts with aspirin (120 min apart)",
+        ));
+
+    let assert = cmd_in(&dir)
+        .args(["med", "take", "aspirin"])
+        .assert()
+        .success();
+    assert!(parse_json(&assert)["data"].get("warnings").is_none());
+
+    // The take is recorded anyway, with a warning
+    let assert = cmd_in(&dir)
+        .args(["med", "take", "warfarin"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entry"]["type"], "warfarin");
+    let warnings = json["data"]["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["interacts_with"], "aspirin");
+
+    cmd_in(&dir)
+        .args(["--human", "med", "take", "aspirin"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("! warfarin was taken 0 min from"));
+
+    cmd_in(&dir)
+        .args([
+            "med",
+            "add",
+            "iron",
+            "--freq",
+            "daily",
+            "--interacts-with",
+            "warfarin:soon",
+        ])
+        .assert()
+        .code(2);
+}
+
+// ─── med edit ────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_med_edit_updates_in_place() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args([
+            "med",
+            "add",
+            "ibuprofen",
+            "--dose",
+            "400mg",
+            "--freq",
+            "daily",
+        ])
+        .assert()
+        .success();
+
+    cmd_in(&dir)
+        .args(["--human", "med", "edit", "ibuprofen", "--dose", "600mg"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Updated medication: ibuprofen (dose: 400mg \u{2192} 600mg)",
+        ));
+
+    let assert = cmd_in(&dir)
+        .args(["med", "edit", "ibuprofen", "--freq", "2x_daily"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "med_edit");
+    assert_eq!(json["data"]["name"], "ibuprofen");
+    assert_eq!(json["data"]["dose"], "600mg");
+    assert_eq!(json["data"]["frequency"], "2x_daily");
+    assert_eq!(json["data"]["active"], true);
+
+    // --rename needs --yes
+    cmd_in(&dir)
+        .args(["med", "edit", "ibuprofen", "--rename", "advil"])
+        .assert()
+        .failure();
+    let assert = cmd_in(&dir)
+        .args(["med", "edit", "ibuprofen", "--rename", "advil", "--yes"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["name"], "advil");
+    assert_eq!(json["data"]["renamed_from"], "ibuprofen");
+
+    let assert = cmd_in(&dir)
+        .args(["med", "edit", "tylenol", "--dose", "500mg"])
+        .assert()
+        .code(3);
+    assert_eq!(parse_stderr_json(&assert)["error"]["code"], "not_found");
+}
+
+// ─── report medications ──────────────────────────────────────────────────────
+
+#[test]
+fn test_report_includes_medications() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["report", "--period", "week"])
+        .assert()
+        .success();
+    assert!(parse_json(&assert)["data"].get("medications").is_none());
+
+    cmd_in(&dir)
+        .args(["med", "add", "metformin", "--freq", "daily"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["med", "take", "metformin"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["report", "--period", "week"])
+        .assert()
+        .success();
+    let meds = &parse_json(&assert)["data"]["medications"];
+    assert_eq!(meds[0]["name"], "metformin");
+    assert_eq!(meds[0]["doses_taken"], 1);
+    assert_eq!(meds[0]["doses_expected"], 1);
+    assert_eq!(meds[0]["adherence"], 1.0);
+
+    cmd_in(&dir)
+        .args(["--human", "report", "--period", "week"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Medications:"))
+        .stdout(predicate::str::contains("100% adherent, 1/1 doses"));
+}
+
+// ─── show bp_category ────────────────────────────────────────────────────────
+
+#[test]
+fn test_show_bp_component_includes_category() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "blood_pressure", "135/85"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "weight", "80"])
+        .assert()
+        .success();
+
+    let assert = cmd_in(&dir)
+        .args(["show", "bp_systolic"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["entries"][0]["bp_category"], "stage_1");
+
+    let assert = cmd_in(&dir).args(["show", "weight"]).assert().success();
+    assert!(
+        parse_json(&assert)["data"]["entries"][0]
+            .get("bp_category")
+            .is_none()
+    );
+
+    cmd_in(&dir)
+        .args(["--human", "show", "bp_diastolic"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("85 mmHg (stage 1)"));
+}
+
+// ─── anomaly --all ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_anomaly_all_flags() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    let assert = cmd_in(&dir)
+        .args(["anomaly", "--all", "--days", "30"])
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["command"], "anomaly");
+    assert!(json["data"]["anomalies"].as_array().unwrap().is_empty());
+
+    cmd_in(&dir)
+        .args(["anomaly", "weight", "--all"])
+        .assert()
+        .failure();
+    cmd_in(&dir)
+        .args(["anomaly", "--include-meds"])
+        .assert()
+        .failure();
+}
+
+// ─── log --source ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_log_source_device_and_default_source() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    let latest_source = |t: &str| {
+        let assert = cmd_in(&dir)
+            .args(["show", t, "--last", "1"])
+            .assert()
+            .success();
+        parse_json(&assert)["data"]["entries"][0]["source"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+
+    cmd_in(&dir)
+        .args(["log", "weight", "80", "--source", "device"])
+        .assert()
+        .success();
+    let device = latest_source("weight");
+    assert!(!device.is_empty());
+    assert_ne!(device, "manual");
+    assert!(device.contains(':'), "{}", device);
+
+    cmd_in(&dir)
+        .args(["config", "set", "log.default_source", "garmin"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "water", "500"])
+        .assert()
+        .success();
+    assert_eq!(latest_source("water"), "garmin");
+    cmd_in(&dir)
+        .args(["log", "blood_pressure", "120/80"])
+        .assert()
+        .success();
+    assert_eq!(latest_source("bp_diastolic"), "garmin");
+    cmd_in(&dir)
+        .args(["log", "water", "250", "--source", "manual"])
+        .assert()
+        .success();
+    assert_eq!(latest_source("water"), "manual");
+
+    cmd_in(&dir)
+        .args(["config", "set", "log.default_source", " "])
+        .assert()
+        .code(2);
+}
+
+// ─── week_start ───
+
+#[test]
+fn test_config_set_week_start() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+
+    cmd_in(&dir)
+        .args(["config", "set", "week_start", "sun"])
+        .assert()
+        .success();
+    let assert = cmd_in(&dir).args(["config", "show"]).assert().success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["config"]["week_start"], "sunday");
+
+    cmd_in(&dir)
+        .args(["config", "set", "week_start", "friday"])
+        .assert()
+        .code(2);
+}
+
+// ─── export --format parquet ───
+
+#[test]
+fn test_export_parquet() {
+    let dir = TempDir::new().unwrap();
+    init_dir(&dir);
+    cmd_in(&dir)
+        .args(["log", "weight", "80", "--tags", "am"])
+        .assert()
+        .success();
+    cmd_in(&dir)
+        .args(["log", "water", "500"])
+        .assert()
+        .success();
+
+    let path = dir.path().join("metrics.parquet");
+    let assert = cmd_in(&dir)
+        .args(["export", "--format", "parquet", "--output"])
+        .arg(&path)
+        .assert()
+        .success();
+    let json = parse_json(&assert);
+    assert_eq!(json["data"]["format"], "parquet");
+    assert_eq!(json["data"]["rows"], 2);
+    assert_eq!(json["data"]["path"], path.to_str().unwrap());
+    let summary = openvital::core::parquet::read_summary(&path).unwrap();
+    assert_eq!((summary.num_rows, summary.row_groups), (2, 1));
+
+    // Binary output needs a file and cannot be gzipped
+    cmd_in(&dir)
+        .args(["export", "--format", "parquet"])
+        .assert()
+        .code(2);
+    cmd_in(&dir)
+        .args([
+            "export",
+            "--format",
+            "parquet",
+            "--output",
+            "m.parquet",
+            "--compress",
+        ])
+        .assert()
+        .code(2);
+}
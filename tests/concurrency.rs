@@ -0,0 +1,98 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::db::{Database, SortOrder};
+use openvital::models::config::{Config, JournalMode};
+use tempfile::TempDir;
+
+/// Scenario: several processes write to the same file at once
+#[test]
+fn test_concurrent_writers_all_succeed() {
+    const THREADS: usize = 8;
+    const PER_THREAD: usize = 50;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    // Create the schema up front so the writers only race on inserts.
+    Database::open(&path).unwrap();
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let path = path.clone();
+            std::thread::spawn(move || -> anyhow::Result<()> {
+                let db = Database::open(&path)?;
+                let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+                for i in 0..PER_THREAD {
+                    let m = common::make_metric("water", (t * PER_THREAD + i) as f64, date);
+                    db.insert_metric(&m)?;
+                }
+                Ok(())
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap().unwrap();
+    }
+
+    let db = Database::open(&path).unwrap();
+    let rows = db
+        .table_counts()
+        .unwrap()
+        .into_iter()
+        .find(|t| t.table == "metrics")
+        .unwrap()
+        .rows;
+    assert_eq!(rows as usize, THREADS * PER_THREAD);
+}
+
+/// Scenario: databases are opened in WAL mode
+#[test]
+fn test_open_uses_wal_journal() {
+    let (dir, _db) = common::setup_db();
+    let header = std::fs::read(dir.path().join("test.db")).unwrap();
+    // File format read/write versions are 2 for WAL.
+    assert_eq!(header[18], 2);
+    assert_eq!(header[19], 2);
+}
+
+/// Scenario: two handles open at once can both read
+#[test]
+fn test_simultaneous_handles_read() {
+    let (dir, writer) = common::setup_db();
+    let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+    writer
+        .insert_metric(&common::make_metric("weight", 80.0, date))
+        .unwrap();
+
+    let path = dir.path().join("test.db");
+    let a = Database::open(&path).unwrap();
+    let b = Database::open(&path).unwrap();
+    assert_eq!(
+        a.query_by_type("weight", Some(5), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        b.query_by_type("weight", Some(5), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(a.journal_mode().unwrap(), "wal");
+    assert!(a.foreign_keys_enabled().unwrap());
+}
+
+/// Scenario: database.journal_mode opts out of WAL
+#[test]
+fn test_config_journal_mode_delete() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let mut config = Config::default();
+    config.database.journal_mode = "delete".parse().unwrap();
+
+    let db = Database::open_with_config(&path, &config).unwrap();
+    assert_eq!(db.journal_mode().unwrap(), "delete");
+    assert!(db.foreign_keys_enabled().unwrap());
+    assert!("memory".parse::<JournalMode>().is_err());
+}
@@ -2,7 +2,6 @@ mod common;
 
 use chrono::{Duration, Local};
 use openvital::core::context;
-use openvital::db::Database;
 use openvital::models::config::Config;
 
 fn make_test_config() -> Config {
@@ -168,6 +167,7 @@ fn test_context_medication_integration() {
             route: Some("oral"),
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -271,3 +271,161 @@ fn test_context_multiple_days_of_data() {
     assert!(weight.trend.is_some());
     assert_eq!(weight.trend.as_ref().unwrap().direction, "decreasing");
 }
+
+#[test]
+fn test_context_profile_and_medication_changes() {
+    use openvital::core::med::AddMedicationParams;
+
+    let (_dir, db) = common::setup_db();
+    let mut config = make_test_config();
+    config.profile.conditions = vec!["asthma".to_string()];
+    let today = Local::now().date_naive();
+
+    db.insert_metric(&common::make_metric("weight", 81.0, today))
+        .unwrap();
+    for (name, started) in [
+        ("amoxicillin", today - Duration::days(3)),
+        ("vitd", today - Duration::days(60)),
+    ] {
+        openvital::core::med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq: "daily",
+                route: None,
+                note: None,
+                started: Some(started),
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+    openvital::core::med::stop_medication(&db, "amoxicillin", Some("course done"), Some(today))
+        .unwrap();
+
+    let result = context::compute(&db, &config, 7, None).unwrap();
+
+    let profile = result.profile.as_ref().unwrap();
+    assert_eq!(profile.height_cm, Some(180.0));
+    assert_eq!(profile.latest_weight_kg, Some(81.0));
+    assert_eq!(profile.bmi, Some(25.0));
+    assert_eq!(profile.conditions, vec!["asthma"]);
+
+    let meds = result.medications.as_ref().unwrap();
+    assert_eq!(meds.active_count, 1);
+    let changes: Vec<(&str, &str)> = meds
+        .changes
+        .iter()
+        .map(|c| (c.name.as_str(), c.change))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![("amoxicillin", "started"), ("amoxicillin", "stopped")]
+    );
+    assert_eq!(meds.changes[1].reason.as_deref(), Some("course done"));
+}
+
+#[test]
+fn test_context_sections_filter() {
+    use openvital::core::context::Section;
+    use openvital::models::goal::{Direction, Timeframe};
+
+    let (_dir, db) = common::setup_db();
+    let config = make_test_config();
+    let today = Local::now().date_naive();
+    db.insert_metric(&common::make_metric("weight", 80.0, today))
+        .unwrap();
+    openvital::core::goal::set_goal(
+        &db,
+        "weight".into(),
+        75.0,
+        Direction::Below,
+        Timeframe::Daily,
+    )
+    .unwrap();
+
+    let result = context::compute_with(&db, &config, 7, None, &[Section::Goals]).unwrap();
+    assert_eq!(result.sections, vec![Section::Goals]);
+    assert!(result.metrics.is_empty());
+    assert!(result.profile.is_none());
+    assert_eq!(result.goals.len(), 1);
+    assert!(result.goals[0].progress.is_some());
+
+    assert!("vitals".parse::<Section>().is_err());
+}
+
+// ── narrative ────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_narrative_empty_db_not_empty() {
+    let (_dir, db) = common::setup_db();
+    let config = make_test_config();
+
+    let result = context::compute(&db, &config, 7, None).unwrap();
+    let text = context::generate_narrative(&result, &config);
+
+    assert!(!text.is_empty());
+    assert!(text.starts_with("Over the past 7 days"), "{}", text);
+}
+
+#[test]
+fn test_narrative_mentions_days_trend_and_pain() {
+    let (_dir, db) = common::setup_db();
+    let mut config = make_test_config();
+    config.alerts.pain_threshold = 5;
+    let today = Local::now().date_naive();
+
+    for i in 0..5 {
+        let date = today - Duration::days(i);
+        db.insert_metric(&common::make_metric("weight", 82.0 + i as f64 * 0.1, date))
+            .unwrap();
+        db.insert_metric(&common::make_metric("sleep_hours", 7.0, date))
+            .unwrap();
+        let pain = if i < 3 { 6.0 } else { 2.0 };
+        db.insert_metric(&common::make_metric("pain", pain, date))
+            .unwrap();
+    }
+
+    let result = context::compute(&db, &config, 14, None).unwrap();
+    assert_eq!(result.metrics["pain"].elevated_days, Some(3));
+    let text = context::generate_narrative(&result, &config);
+
+    assert!(text.starts_with("Over the past 14 days"), "{}", text);
+    assert!(
+        text.contains("your weight has decreased by 0.4 kg to 82.0 kg"),
+        "{}",
+        text
+    );
+    assert!(text.contains("Sleep hours averaged 7.0 hours."), "{}", text);
+    assert!(
+        text.contains("Pain was elevated (avg 4.4) on 3 days."),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn test_narrative_uses_imperial_units() {
+    let (_dir, db) = common::setup_db();
+    let mut config = make_test_config();
+    config.units = openvital::models::config::Units::imperial();
+    let today = Local::now().date_naive();
+
+    db.insert_metric(&common::make_metric(
+        "weight",
+        80.0,
+        today - Duration::days(1),
+    ))
+    .unwrap();
+    db.insert_metric(&common::make_metric("weight", 79.0, today))
+        .unwrap();
+
+    let result = context::compute(&db, &config, 7, None).unwrap();
+    let text = context::generate_narrative(&result, &config);
+
+    assert!(text.contains("lbs"), "{}", text);
+    assert!(!text.contains("kg"), "{}", text);
+    assert!(text.contains("to 174.2 lbs"), "{}", text);
+}
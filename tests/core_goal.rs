@@ -304,8 +304,9 @@ fn test_goal_status_monthly_uses_latest_value() {
     )
     .unwrap();
 
-    let d1 = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
-    let d2 = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+    // Both entries fall inside the current month
+    let d2 = chrono::Local::now().date_naive();
+    let d1 = d2.with_day(1).unwrap();
     db.insert_metric(&common::make_metric("weight", 80.0, d1))
         .unwrap();
     db.insert_metric(&common::make_metric("weight", 76.0, d2))
@@ -332,7 +333,7 @@ fn test_goal_status_monthly_met() {
     )
     .unwrap();
 
-    let d = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+    let d = chrono::Local::now().date_naive();
     db.insert_metric(&common::make_metric("weight", 74.5, d))
         .unwrap();
 
@@ -422,7 +423,7 @@ fn test_goal_status_progress_string_below_at_target() {
     )
     .unwrap();
 
-    let d = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+    let d = chrono::Local::now().date_naive();
     db.insert_metric(&common::make_metric("weight", 75.0, d))
         .unwrap();
 
@@ -666,3 +667,143 @@ fn test_weekly_goal_snapshot_uses_latest_value() {
     // For snapshot metrics weekly, use the latest value (72.5), not sum (145.5)
     assert_eq!(statuses[0].current_value, Some(72.5));
 }
+
+// ── start_value / percent_complete ──────────────────────────────────────────
+
+#[test]
+fn test_set_goal_captures_start_value_from_latest_entry() {
+    let (_dir, db) = common::setup_db();
+    let today = chrono::Local::now().date_naive();
+    db.insert_metric(&common::make_metric(
+        "weight",
+        85.0,
+        today - chrono::Duration::days(3),
+    ))
+    .unwrap();
+    db.insert_metric(&common::make_metric("weight", 80.0, today))
+        .unwrap();
+
+    let g = goal::set_goal(
+        &db,
+        "weight".into(),
+        75.0,
+        Direction::Below,
+        Timeframe::Monthly,
+    )
+    .unwrap();
+    assert_eq!(g.start_value, Some(80.0));
+
+    db.insert_metric(&common::make_metric("weight", 78.0, today))
+        .unwrap();
+    let s = &goal::goal_status(&db, Some("weight")).unwrap()[0];
+    assert_eq!(s.start_value, Some(80.0));
+    // (80 - 78) / (80 - 75) = 40%
+    assert_eq!(s.percent_complete, Some(40.0));
+}
+
+#[test]
+fn test_set_goal_without_data_has_no_start_value() {
+    let (_dir, db) = common::setup_db();
+
+    let g = goal::set_goal(
+        &db,
+        "weight".into(),
+        75.0,
+        Direction::Below,
+        Timeframe::Monthly,
+    )
+    .unwrap();
+    assert_eq!(g.start_value, None);
+
+    let s = &goal::goal_status(&db, None).unwrap()[0];
+    assert_eq!(s.percent_complete, None);
+}
+
+#[test]
+fn test_percent_complete_clamped_and_undefined_at_target() {
+    let mut g = openvital::models::goal::Goal::new(
+        "weight".into(),
+        75.0,
+        Direction::Below,
+        Timeframe::Monthly,
+    );
+    g.start_value = Some(80.0);
+    assert_eq!(g.percent_complete(82.0), Some(0.0));
+    assert_eq!(g.percent_complete(70.0), Some(100.0));
+
+    g.start_value = Some(75.0);
+    assert_eq!(g.percent_complete(74.0), None);
+}
+
+// ── completed_by ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_completed_by_fires_only_on_the_completing_entry() {
+    let (_dir, db) = common::setup_db();
+    let today = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+    goal::set_goal(
+        &db,
+        "water".into(),
+        2000.0,
+        Direction::Above,
+        Timeframe::Daily,
+    )
+    .unwrap();
+    let log = |value: f64| {
+        let m = common::make_metric("water", value, today);
+        db.insert_metric(&m).unwrap();
+        goal::completed_by(&db, &[m], today).unwrap()
+    };
+
+    assert!(log(1500.0).is_empty());
+    let done = log(650.0);
+    assert_eq!(done.len(), 1);
+    assert_eq!(done[0].metric_type, "water");
+    assert_eq!(done[0].timeframe, "daily");
+    assert_eq!(done[0].target, 2000.0);
+    assert_eq!(done[0].current, 2150.0);
+    // Already met: no repeat
+    assert!(log(250.0).is_empty());
+}
+
+#[test]
+fn test_completed_by_snapshot_and_untracked_types() {
+    let (_dir, db) = common::setup_db();
+    let today = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        80.0,
+        Direction::Below,
+        Timeframe::Weekly,
+    )
+    .unwrap();
+    goal::set_goal(
+        &db,
+        "steps".into(),
+        10000.0,
+        Direction::Above,
+        Timeframe::Monthly,
+    )
+    .unwrap();
+
+    let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    db.insert_metric(&common::make_metric("weight", 81.0, monday))
+        .unwrap();
+    let m = common::make_metric("weight", 79.5, today);
+    db.insert_metric(&m).unwrap();
+    let done = goal::completed_by(&db, &[m], today).unwrap();
+    assert_eq!(done.len(), 1);
+    assert_eq!(done[0].current, 79.5);
+
+    // Monthly goals and types without a goal are not checked
+    let steps = common::make_metric("steps", 12000.0, today);
+    let mood = common::make_metric("mood", 7.0, today);
+    db.insert_metric(&steps).unwrap();
+    db.insert_metric(&mood).unwrap();
+    assert!(
+        goal::completed_by(&db, &[steps, mood], today)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -1,7 +1,11 @@
 mod common;
 
 use chrono::{NaiveDate, Timelike};
-use openvital::core::logging::{LogEntry, log_batch, log_blood_pressure, log_metric};
+use openvital::core::logging::{
+    LogEntry, Source, hostname_or_localhost, is_relative, log_batch, log_blood_pressure,
+    log_metric, resolve_delta,
+};
+use openvital::db::SortOrder;
 use openvital::models::config::Config;
 
 fn default_config() -> Config {
@@ -20,8 +24,9 @@ fn test_log_metric_basic_fields_persisted() {
         value: 82.5,
         note: None,
         tags: None,
-        source: None,
+        source: Source::Manual,
         date: None,
+        unit: None,
     };
 
     let m = log_metric(&db, &config, entry).unwrap();
@@ -34,7 +39,9 @@ fn test_log_metric_basic_fields_persisted() {
     assert!(m.tags.is_empty());
 
     // Verify persisted to DB
-    let stored = db.query_by_type("weight", Some(1)).unwrap();
+    let stored = db
+        .query_by_type("weight", Some(1), SortOrder::Desc)
+        .unwrap();
     assert_eq!(stored.len(), 1);
     assert!((stored[0].value - 82.5).abs() < f64::EPSILON);
 }
@@ -49,8 +56,9 @@ fn test_log_metric_note_stored() {
         value: 3.0,
         note: Some("lower back"),
         tags: None,
-        source: None,
+        source: Source::Manual,
         date: None,
+        unit: None,
     };
 
     let m = log_metric(&db, &config, entry).unwrap();
@@ -67,8 +75,9 @@ fn test_log_metric_tags_split_on_comma() {
         value: 45.0,
         note: None,
         tags: Some("morning, outdoor, run"),
-        source: None,
+        source: Source::Manual,
         date: None,
+        unit: None,
     };
 
     let m = log_metric(&db, &config, entry).unwrap();
@@ -88,14 +97,85 @@ fn test_log_metric_source_overridden() {
         value: 7.5,
         note: None,
         tags: None,
-        source: Some("apple_health"),
+        source: Source::Named("apple_health".to_string()),
         date: None,
+        unit: None,
     };
 
     let m = log_metric(&db, &config, entry).unwrap();
     assert_eq!(m.source, "apple_health");
 }
 
+#[test]
+fn test_hostname_falls_back_to_localhost() {
+    assert_eq!(hostname_or_localhost(None), "localhost");
+    assert_eq!(hostname_or_localhost(Some(" \n".into())), "localhost");
+    assert_eq!(hostname_or_localhost(Some("box.lan\n".into())), "box.lan");
+}
+
+#[test]
+fn test_source_device_and_config_default() {
+    let device = Source::parse("device").unwrap();
+    assert!(matches!(device, Source::Device(_)));
+    let name = device.to_string();
+    assert!(!name.is_empty());
+    assert_ne!(name, "manual");
+    let (host, tool) = name.split_once(':').unwrap();
+    assert!(!host.is_empty() && !tool.is_empty());
+    assert_eq!(host, hostname_or_localhost(hostname::get().ok()));
+    assert_eq!(serde_json::to_value(&device).unwrap(), name.as_str());
+
+    assert_eq!(Source::parse("manual").unwrap(), Source::Manual);
+    assert_eq!(
+        Source::parse(" garmin ").unwrap(),
+        Source::Named("garmin".to_string())
+    );
+    assert!(Source::parse("  ").is_err());
+
+    let mut config = default_config();
+    assert_eq!(Source::resolve(None, &config).unwrap(), Source::Manual);
+    config.log.default_source = Some("garmin".to_string());
+    assert_eq!(
+        Source::resolve(None, &config).unwrap().to_string(),
+        "garmin"
+    );
+    assert_eq!(
+        Source::resolve(Some("agent"), &config).unwrap().to_string(),
+        "agent"
+    );
+    config.log.default_source = Some("device".to_string());
+    assert!(matches!(
+        Source::resolve(None, &config).unwrap(),
+        Source::Device(_)
+    ));
+}
+
+#[test]
+fn test_log_metric_unit_override_stored_without_conversion() {
+    let (_dir, db) = common::setup_db();
+    let mut config = default_config();
+    config.units = openvital::models::config::Units::imperial();
+
+    let entry = LogEntry {
+        metric_type: "cardio",
+        value: 1.5,
+        note: None,
+        tags: None,
+        source: Source::Manual,
+        date: None,
+        unit: Some("hours"),
+    };
+
+    let m = log_metric(&db, &config, entry).unwrap();
+    assert_eq!(m.unit, "hours");
+    assert_eq!(m.value, 1.5);
+    let stored = db
+        .query_by_type("cardio", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(stored[0].unit, "hours");
+    assert_eq!(stored[0].value, 1.5);
+}
+
 #[test]
 fn test_log_metric_custom_date_sets_noon_utc() {
     let (_dir, db) = common::setup_db();
@@ -107,8 +187,9 @@ fn test_log_metric_custom_date_sets_noon_utc() {
         value: 80.0,
         note: None,
         tags: None,
-        source: None,
+        source: Source::Manual,
         date: Some(date),
+        unit: None,
     };
 
     let m = log_metric(&db, &config, entry).unwrap();
@@ -127,8 +208,9 @@ fn test_log_metric_no_date_defaults_to_now() {
         value: 500.0,
         note: None,
         tags: None,
-        source: None,
+        source: Source::Manual,
         date: None,
+        unit: None,
     };
 
     let before = chrono::Utc::now();
@@ -152,15 +234,18 @@ fn test_log_metric_resolves_alias() {
         value: 79.0,
         note: None,
         tags: None,
-        source: None,
+        source: Source::Manual,
         date: None,
+        unit: None,
     };
 
     let m = log_metric(&db, &config, entry).unwrap();
     assert_eq!(m.metric_type, "weight");
 
     // Stored under the resolved type
-    let stored = db.query_by_type("weight", Some(1)).unwrap();
+    let stored = db
+        .query_by_type("weight", Some(1), SortOrder::Desc)
+        .unwrap();
     assert_eq!(stored.len(), 1);
 }
 
@@ -174,8 +259,9 @@ fn test_log_metric_unknown_alias_passes_through() {
         value: 42.0,
         note: None,
         tags: None,
-        source: None,
+        source: Source::Manual,
         date: None,
+        unit: None,
     };
 
     let m = log_metric(&db, &config, entry).unwrap();
@@ -195,13 +281,16 @@ fn test_log_metric_multiple_entries_accumulate() {
             value: v,
             note: None,
             tags: None,
-            source: None,
+            source: Source::Manual,
             date: None,
+            unit: None,
         };
         log_metric(&db, &config, entry).unwrap();
     }
 
-    let stored = db.query_by_type("water", Some(10)).unwrap();
+    let stored = db
+        .query_by_type("water", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(stored.len(), 3);
 }
 
@@ -272,7 +361,9 @@ fn test_log_batch_all_persisted_to_db() {
 
     log_batch(&db, &config, json).unwrap();
 
-    let stored = db.query_by_type("sleep_hours", Some(10)).unwrap();
+    let stored = db
+        .query_by_type("sleep_hours", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(stored.len(), 2);
 }
 
@@ -318,6 +409,83 @@ fn test_log_batch_empty_array_succeeds() {
     assert!(results.is_empty());
 }
 
+#[test]
+fn test_log_batch_is_all_or_nothing() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+
+    let json = r#"[{"type": "weight", "value": 80.0}, {"type": "water"}]"#;
+    assert!(log_batch(&db, &config, json).is_err());
+    assert!(
+        db.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+// ── batch files ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_parse_batch_csv_export_schema() {
+    let mut config = default_config();
+    config.aliases = Config::default_aliases();
+
+    let csv = "timestamp,type,value,unit,note,tags,source\n\
+               2026-01-05T07:00:00+00:00,w,80.5,kg,morning,[\"am\"],scale\n\
+               ,water,500,,,,\n";
+    let metrics = openvital::core::logging::parse_batch_csv(&config, csv).unwrap();
+    assert_eq!(metrics.len(), 2);
+    assert_eq!(metrics[0].metric_type, "weight");
+    assert_eq!(
+        metrics[0].timestamp.to_rfc3339(),
+        "2026-01-05T07:00:00+00:00"
+    );
+    assert_eq!(metrics[0].note.as_deref(), Some("morning"));
+    assert_eq!(metrics[0].tags, vec!["am"]);
+    assert_eq!(metrics[0].source, "scale");
+    assert_eq!(metrics[1].metric_type, "water");
+    assert_eq!(metrics[1].source, "manual");
+}
+
+#[test]
+fn test_parse_batch_csv_requires_type_and_value() {
+    let config = default_config();
+    let err = openvital::core::logging::parse_batch_csv(&config, "timestamp,type\n,weight\n")
+        .unwrap_err();
+    assert!(err.to_string().contains("'type' and 'value'"));
+
+    let err = openvital::core::logging::parse_batch_csv(&config, "type,value\nweight,heavy\n")
+        .unwrap_err();
+    assert!(err.to_string().contains("row 2: invalid value"));
+}
+
+#[test]
+fn test_insert_batch_chunks_large_batches() {
+    use openvital::core::logging::{BATCH_CHUNK, LARGE_BATCH, insert_batch};
+
+    let (_dir, db) = common::setup_db();
+    let mut metrics: Vec<_> = (0..=LARGE_BATCH)
+        .map(|i| openvital::models::Metric::new("steps".into(), i as f64))
+        .collect();
+    let mut reports = Vec::new();
+    insert_batch(&db, &mut metrics, |logged, total| {
+        reports.push((logged, total))
+    })
+    .unwrap();
+
+    assert_eq!(reports.len(), LARGE_BATCH / BATCH_CHUNK + 1);
+    assert_eq!(reports[0], (BATCH_CHUNK, LARGE_BATCH + 1));
+    assert_eq!(*reports.last().unwrap(), (LARGE_BATCH + 1, LARGE_BATCH + 1));
+
+    // Ordinary batches commit once
+    let mut few: Vec<_> = (0..3)
+        .map(|i| openvital::models::Metric::new("steps".into(), i as f64))
+        .collect();
+    let mut calls = 0;
+    insert_batch(&db, &mut few, |_, _| calls += 1).unwrap();
+    assert_eq!(calls, 1);
+}
+
 // ── log_blood_pressure ──────────────────────────────────────────────────────
 
 #[test]
@@ -325,15 +493,20 @@ fn test_log_blood_pressure_splits_into_systolic_diastolic() {
     let (_dir, db) = common::setup_db();
     let config = default_config();
 
-    let (m1, m2) = log_blood_pressure(&db, &config, "120/80", None, None, None, None).unwrap();
+    let (m1, m2) =
+        log_blood_pressure(&db, &config, "120/80", None, None, &Source::Manual, None).unwrap();
     assert_eq!(m1.metric_type, "bp_systolic");
     assert!((m1.value - 120.0).abs() < 0.1);
     assert_eq!(m2.metric_type, "bp_diastolic");
     assert!((m2.value - 80.0).abs() < 0.1);
 
     // Both persisted
-    let sys = db.query_by_type("bp_systolic", Some(1)).unwrap();
-    let dia = db.query_by_type("bp_diastolic", Some(1)).unwrap();
+    let sys = db
+        .query_by_type("bp_systolic", Some(1), SortOrder::Desc)
+        .unwrap();
+    let dia = db
+        .query_by_type("bp_diastolic", Some(1), SortOrder::Desc)
+        .unwrap();
     assert_eq!(sys.len(), 1);
     assert_eq!(dia.len(), 1);
 }
@@ -343,7 +516,7 @@ fn test_log_blood_pressure_invalid_format_fails() {
     let (_dir, db) = common::setup_db();
     let config = default_config();
 
-    let result = log_blood_pressure(&db, &config, "120/80/60", None, None, None, None);
+    let result = log_blood_pressure(&db, &config, "120/80/60", None, None, &Source::Manual, None);
     assert!(result.is_err());
 }
 
@@ -352,6 +525,128 @@ fn test_log_blood_pressure_invalid_number_fails() {
     let (_dir, db) = common::setup_db();
     let config = default_config();
 
-    let result = log_blood_pressure(&db, &config, "abc/80", None, None, None, None);
+    let result = log_blood_pressure(&db, &config, "abc/80", None, None, &Source::Manual, None);
     assert!(result.is_err());
 }
+
+// ── log_measurements ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_log_measurements_share_timestamp_and_tag() {
+    use openvital::core::logging::{MEASUREMENT_TAG, log_measurements};
+
+    let (_dir, db) = common::setup_db();
+    let mut config = default_config();
+    config.aliases.insert("w".into(), "waist".into());
+
+    let pairs: Vec<String> = ["w=84", "hips=98", " chest = 101 "]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let metrics = log_measurements(&db, &config, &pairs, None, Some("weekly"), None).unwrap();
+
+    assert_eq!(metrics.len(), 3);
+    assert_eq!(metrics[0].metric_type, "waist");
+    assert_eq!(metrics[0].unit, "cm");
+    assert_eq!(metrics[2].metric_type, "chest");
+    assert_eq!(metrics[2].value, 101.0);
+    assert!(metrics.iter().all(|m| m.timestamp == metrics[0].timestamp));
+    assert!(
+        metrics
+            .iter()
+            .all(|m| m.tags == vec![MEASUREMENT_TAG.to_string(), "weekly".to_string()])
+    );
+    assert_eq!(
+        db.query_tagged(None, None, None, &[MEASUREMENT_TAG.to_string()])
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn test_log_measurements_bad_pair_inserts_nothing() {
+    use openvital::core::logging::log_measurements;
+
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+
+    for bad in [
+        ["waist=84", "hips=abc"],
+        ["waist=84", "chest"],
+        ["waist=84", "waist=85"],
+    ] {
+        let pairs: Vec<String> = bad.iter().map(|s| s.to_string()).collect();
+        let err = log_measurements(&db, &config, &pairs, None, None, None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(bad[1]), "{}", err);
+    }
+    assert_eq!(db.count_metrics_of_type("waist").unwrap(), 0);
+}
+
+#[test]
+fn test_log_batch_converts_from_unit_override() {
+    let (_dir, db) = common::setup_db();
+    let mut config = default_config();
+    config.units.custom.insert(
+        "water".into(),
+        openvital::core::units::parse_override("water", "oz").unwrap(),
+    );
+
+    let metrics = log_batch(&db, &config, r#"[{"type": "water", "value": 16}]"#).unwrap();
+    assert_eq!(metrics[0].unit, "ml");
+    assert_eq!(metrics[0].value.round(), 473.0);
+}
+
+// ── relative values ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_resolve_delta_adjusts_latest_entry() {
+    let (_dir, db) = common::setup_db();
+    let mut config = default_config();
+    db.insert_metric(&common::make_metric(
+        "weight",
+        80.0,
+        NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+    ))
+    .unwrap();
+    db.insert_metric(&common::make_metric(
+        "weight",
+        81.0,
+        NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+    ))
+    .unwrap();
+
+    assert!(is_relative("+0.5") && is_relative("-0.5") && !is_relative("0.5"));
+    assert_eq!(
+        resolve_delta(&db, &config, "weight", 0.5, false).unwrap(),
+        81.5
+    );
+    assert_eq!(
+        resolve_delta(&db, &config, "weight", -1.5, false).unwrap(),
+        79.5
+    );
+
+    // The delta is in display units: +1 lb on 81 kg
+    config.units = openvital::models::config::Units::imperial();
+    let v = resolve_delta(&db, &config, "weight", 1.0, false).unwrap();
+    assert!((v - 81.4536).abs() < 1e-3, "{}", v);
+}
+
+#[test]
+fn test_resolve_delta_summed_type_and_missing_entry() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+
+    // Summed types log the increment itself, even with no previous entry
+    assert_eq!(
+        resolve_delta(&db, &config, "water", 250.0, false).unwrap(),
+        250.0
+    );
+    let err = resolve_delta(&db, &config, "water", -250.0, false).unwrap_err();
+    assert!(err.to_string().contains("only be incremented"));
+
+    let err = resolve_delta(&db, &config, "weight", 0.5, false).unwrap_err();
+    assert!(err.to_string().contains("no previous 'weight' entry"));
+}
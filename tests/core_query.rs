@@ -1,7 +1,8 @@
 mod common;
 
 use chrono::NaiveDate;
-use openvital::core::query::{ShowResult, show};
+use openvital::core::query::{ShowOptions, ShowResult, show, show_types};
+use openvital::db::SortOrder;
 use openvital::models::config::Config;
 
 fn default_config() -> Config {
@@ -19,7 +20,7 @@ fn test_show_no_args_returns_today() {
     db.insert_metric(&common::make_metric("weight", 80.0, today))
         .unwrap();
 
-    let result = show(&db, &config, None, None, None).unwrap();
+    let result = show(&db, &config, None, &ShowOptions::default()).unwrap();
 
     match result {
         ShowResult::ByDate { date, entries } => {
@@ -27,7 +28,7 @@ fn test_show_no_args_returns_today() {
             assert_eq!(entries.len(), 1);
             assert_eq!(entries[0].metric_type, "weight");
         }
-        ShowResult::ByType { .. } => panic!("expected ByDate"),
+        _ => panic!("expected ByDate"),
     }
 }
 
@@ -41,11 +42,11 @@ fn test_show_no_args_empty_today_returns_empty_list() {
     db.insert_metric(&common::make_metric("weight", 80.0, past))
         .unwrap();
 
-    let result = show(&db, &config, None, None, None).unwrap();
+    let result = show(&db, &config, None, &ShowOptions::default()).unwrap();
 
     match result {
         ShowResult::ByDate { entries, .. } => assert!(entries.is_empty()),
-        ShowResult::ByType { .. } => panic!("expected ByDate"),
+        _ => panic!("expected ByDate"),
     }
 }
 
@@ -60,14 +61,14 @@ fn test_show_today_keyword_returns_by_date() {
     db.insert_metric(&common::make_metric("pain", 3.0, today))
         .unwrap();
 
-    let result = show(&db, &config, Some("today"), None, None).unwrap();
+    let result = show(&db, &config, Some("today"), &ShowOptions::default()).unwrap();
 
     match result {
         ShowResult::ByDate { date, entries } => {
             assert_eq!(date, today);
             assert_eq!(entries.len(), 1);
         }
-        ShowResult::ByType { .. } => panic!("expected ByDate"),
+        _ => panic!("expected ByDate"),
     }
 }
 
@@ -81,14 +82,23 @@ fn test_show_today_keyword_with_explicit_date_uses_that_date() {
         .unwrap();
 
     // "today" keyword but with an explicit date override
-    let result = show(&db, &config, Some("today"), None, Some(specific_date)).unwrap();
+    let result = show(
+        &db,
+        &config,
+        Some("today"),
+        &ShowOptions {
+            date: Some(specific_date),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     match result {
         ShowResult::ByDate { date, entries } => {
             assert_eq!(date, specific_date);
             assert_eq!(entries.len(), 1);
         }
-        ShowResult::ByType { .. } => panic!("expected ByDate"),
+        _ => panic!("expected ByDate"),
     }
 }
 
@@ -107,7 +117,16 @@ fn test_show_with_explicit_date_returns_by_date() {
     db.insert_metric(&common::make_metric("weight", 79.5, d2))
         .unwrap();
 
-    let result = show(&db, &config, None, None, Some(d1)).unwrap();
+    let result = show(
+        &db,
+        &config,
+        None,
+        &ShowOptions {
+            date: Some(d1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     match result {
         ShowResult::ByDate { date, entries } => {
@@ -115,7 +134,7 @@ fn test_show_with_explicit_date_returns_by_date() {
             assert_eq!(entries.len(), 1);
             assert!((entries[0].value - 80.0).abs() < f64::EPSILON);
         }
-        ShowResult::ByType { .. } => panic!("expected ByDate"),
+        _ => panic!("expected ByDate"),
     }
 }
 
@@ -132,11 +151,20 @@ fn test_show_with_date_returns_all_types_for_that_day() {
     db.insert_metric(&common::make_metric("pain", 2.0, d))
         .unwrap();
 
-    let result = show(&db, &config, None, None, Some(d)).unwrap();
+    let result = show(
+        &db,
+        &config,
+        None,
+        &ShowOptions {
+            date: Some(d),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     match result {
         ShowResult::ByDate { entries, .. } => assert_eq!(entries.len(), 3),
-        ShowResult::ByType { .. } => panic!("expected ByDate"),
+        _ => panic!("expected ByDate"),
     }
 }
 
@@ -151,7 +179,7 @@ fn test_show_by_type_returns_by_type_variant() {
     db.insert_metric(&common::make_metric("weight", 82.0, d))
         .unwrap();
 
-    let result = show(&db, &config, Some("weight"), None, None).unwrap();
+    let result = show(&db, &config, Some("weight"), &ShowOptions::default()).unwrap();
 
     match result {
         ShowResult::ByType {
@@ -162,7 +190,7 @@ fn test_show_by_type_returns_by_type_variant() {
             assert_eq!(entries.len(), 1);
             assert!((entries[0].value - 82.0).abs() < f64::EPSILON);
         }
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
 
@@ -171,11 +199,11 @@ fn test_show_by_type_empty_when_no_entries() {
     let (_dir, db) = common::setup_db();
     let config = default_config();
 
-    let result = show(&db, &config, Some("weight"), None, None).unwrap();
+    let result = show(&db, &config, Some("weight"), &ShowOptions::default()).unwrap();
 
     match result {
         ShowResult::ByType { entries, .. } => assert!(entries.is_empty()),
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
 
@@ -195,13 +223,13 @@ fn test_show_by_type_default_last_is_ten() {
     }
 
     // No `last` param → defaults to 10
-    let result = show(&db, &config, Some("weight"), None, None).unwrap();
+    let result = show(&db, &config, Some("weight"), &ShowOptions::default()).unwrap();
 
     match result {
         ShowResult::ByType { entries, .. } => {
             assert_eq!(entries.len(), 10);
         }
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
 
@@ -219,11 +247,20 @@ fn test_show_by_type_with_last_n() {
             .unwrap();
     }
 
-    let result = show(&db, &config, Some("weight"), Some(3), None).unwrap();
+    let result = show(
+        &db,
+        &config,
+        Some("weight"),
+        &ShowOptions {
+            last: Some(3),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     match result {
         ShowResult::ByType { entries, .. } => assert_eq!(entries.len(), 3),
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
 
@@ -237,11 +274,20 @@ fn test_show_by_type_last_exceeds_available_returns_all() {
         .unwrap();
 
     // Request more than available
-    let result = show(&db, &config, Some("weight"), Some(10), None).unwrap();
+    let result = show(
+        &db,
+        &config,
+        Some("weight"),
+        &ShowOptions {
+            last: Some(10),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     match result {
         ShowResult::ByType { entries, .. } => assert_eq!(entries.len(), 1),
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
 
@@ -258,7 +304,7 @@ fn test_show_resolves_alias_to_canonical_type() {
         .unwrap();
 
     // "w" is the alias for "weight"
-    let result = show(&db, &config, Some("w"), None, None).unwrap();
+    let result = show(&db, &config, Some("w"), &ShowOptions::default()).unwrap();
 
     match result {
         ShowResult::ByType {
@@ -268,7 +314,7 @@ fn test_show_resolves_alias_to_canonical_type() {
             assert_eq!(metric_type, "weight");
             assert_eq!(entries.len(), 1);
         }
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
 
@@ -285,7 +331,16 @@ fn test_show_by_type_only_returns_matching_type() {
     db.insert_metric(&common::make_metric("water", 1200.0, d))
         .unwrap();
 
-    let result = show(&db, &config, Some("water"), Some(5), None).unwrap();
+    let result = show(
+        &db,
+        &config,
+        Some("water"),
+        &ShowOptions {
+            last: Some(5),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     match result {
         ShowResult::ByType {
@@ -296,7 +351,7 @@ fn test_show_by_type_only_returns_matching_type() {
             assert_eq!(entries.len(), 1);
             assert!((entries[0].value - 1200.0).abs() < f64::EPSILON);
         }
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
 
@@ -311,7 +366,16 @@ fn test_show_by_type_works_for_custom_type() {
     db.insert_metric(&common::make_metric("my_custom_metric", 42.0, d))
         .unwrap();
 
-    let result = show(&db, &config, Some("my_custom_metric"), Some(5), None).unwrap();
+    let result = show(
+        &db,
+        &config,
+        Some("my_custom_metric"),
+        &ShowOptions {
+            last: Some(5),
+            ..Default::default()
+        },
+    )
+    .unwrap();
 
     match result {
         ShowResult::ByType {
@@ -321,6 +385,299 @@ fn test_show_by_type_works_for_custom_type() {
             assert_eq!(metric_type, "my_custom_metric");
             assert_eq!(entries.len(), 1);
         }
-        ShowResult::ByDate { .. } => panic!("expected ByType"),
+        _ => panic!("expected ByType"),
     }
 }
+
+// ── show_types – multiple types at once ───────────────────────────────────────
+
+#[test]
+fn test_show_types_groups_in_request_order() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+
+    let d = NaiveDate::from_ymd_opt(2026, 2, 14).unwrap();
+    db.insert_metric(&common::make_metric("weight", 80.0, d))
+        .unwrap();
+    db.insert_metric(&common::make_metric("pain", 3.0, d))
+        .unwrap();
+    db.insert_metric(&common::make_metric("pain", 4.0, d))
+        .unwrap();
+
+    let groups = show_types(
+        &db,
+        &config,
+        &["pain", "weight"],
+        &ShowOptions {
+            last: Some(1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].metric_type, "pain");
+    assert_eq!(groups[0].entries.len(), 1);
+    assert_eq!(groups[1].metric_type, "weight");
+    assert_eq!(groups[1].entries.len(), 1);
+}
+
+#[test]
+fn test_show_types_unknown_type_returns_empty_group() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+
+    let groups = show_types(&db, &config, &["nonexistent"], &ShowOptions::default()).unwrap();
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].metric_type, "nonexistent");
+    assert!(groups[0].entries.is_empty());
+}
+
+#[test]
+fn test_show_types_resolves_aliases_and_filters_by_date() {
+    let (_dir, db) = common::setup_db();
+    let mut config = default_config();
+    config.aliases.insert("w".into(), "weight".into());
+
+    let d1 = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+    let d2 = NaiveDate::from_ymd_opt(2026, 2, 11).unwrap();
+    db.insert_metric(&common::make_metric("weight", 80.0, d1))
+        .unwrap();
+    db.insert_metric(&common::make_metric("weight", 79.5, d2))
+        .unwrap();
+    db.insert_metric(&common::make_metric("water", 500.0, d1))
+        .unwrap();
+
+    let groups = show_types(
+        &db,
+        &config,
+        &["w", "water"],
+        &ShowOptions {
+            date: Some(d1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(groups[0].metric_type, "weight");
+    assert_eq!(groups[0].entries.len(), 1);
+    assert!((groups[0].entries[0].value - 80.0).abs() < f64::EPSILON);
+    assert_eq!(groups[1].metric_type, "water");
+    assert_eq!(groups[1].entries.len(), 1);
+}
+
+// ── show --tag ───────────────────────────────────────────────────────────────
+
+fn tagged(
+    metric_type: &str,
+    value: f64,
+    date: NaiveDate,
+    tags: &[&str],
+) -> openvital::models::Metric {
+    let mut m = common::make_metric(metric_type, value, date);
+    m.tags = tags.iter().map(|t| t.to_string()).collect();
+    m
+}
+
+#[test]
+fn test_show_filters_by_all_tags_case_insensitive() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let d = |day| NaiveDate::from_ymd_opt(2026, 2, day).unwrap();
+
+    db.insert_metric(&tagged("cardio", 30.0, d(1), &["Run", "outdoor"]))
+        .unwrap();
+    db.insert_metric(&tagged("cardio", 40.0, d(2), &["running"]))
+        .unwrap();
+    db.insert_metric(&tagged("cardio", 50.0, d(3), &["run"]))
+        .unwrap();
+    db.insert_metric(&tagged("cardio", 60.0, d(4), &[]))
+        .unwrap();
+
+    let values = |tags: &[String], last| match show(
+        &db,
+        &config,
+        Some("cardio"),
+        &ShowOptions {
+            last,
+            tags,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    {
+        ShowResult::ByType { entries, .. } => entries.iter().map(|m| m.value).collect::<Vec<_>>(),
+        _ => panic!("expected ByType"),
+    };
+
+    // "run" does not match "running"; newest first like untagged show
+    assert_eq!(values(&[" RUN ".into()], None), vec![50.0, 30.0]);
+    assert_eq!(values(&["run".into(), "outdoor".into()], None), vec![30.0]);
+    // `last` applies after filtering
+    assert_eq!(values(&["run".into()], Some(1)), vec![50.0]);
+
+    match show(
+        &db,
+        &config,
+        None,
+        &ShowOptions {
+            date: Some(d(2)),
+            tags: &["run".into()],
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    {
+        ShowResult::ByDate { entries, .. } => assert!(entries.is_empty()),
+        _ => panic!("expected ByDate"),
+    }
+}
+
+#[test]
+fn test_tags_backfilled_when_upgrading_schema() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    {
+        let db = openvital::db::Database::open(&path).unwrap();
+        let d = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        db.insert_metric(&tagged("cardio", 30.0, d, &["Run"]))
+            .unwrap();
+    }
+    // Simulate a pre-tag-index database.
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute("DELETE FROM metric_tags", []).unwrap();
+    conn.pragma_update(None, "user_version", 2).unwrap();
+    drop(conn);
+
+    let db = openvital::db::Database::open(&path).unwrap();
+    let found = db
+        .query_tagged(Some("cardio"), None, None, &["run".into()])
+        .unwrap();
+    assert_eq!(found.len(), 1);
+}
+
+// ── search ───────────────────────────────────────────────────────────────────
+
+fn noted(metric_type: &str, value: f64, date: NaiveDate, note: &str) -> openvital::models::Metric {
+    let mut m = common::make_metric(metric_type, value, date);
+    m.note = Some(note.to_string());
+    m
+}
+
+#[test]
+fn test_search_matches_notes_with_filters_and_snippet() {
+    use openvital::core::search::search;
+
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let d = |day| NaiveDate::from_ymd_opt(2026, 2, day).unwrap();
+
+    db.insert_metric(&noted("pain", 6.0, d(1), "Left KNEE sore after stairs"))
+        .unwrap();
+    db.insert_metric(&noted("cardio", 30.0, d(2), "easy run, knee fine"))
+        .unwrap();
+    db.insert_metric(&noted("cardio", 40.0, d(3), "100% effort"))
+        .unwrap();
+    db.insert_metric(&common::make_metric("pain", 2.0, d(4)))
+        .unwrap();
+
+    // Newest first, case-insensitive, match highlighted in its original case
+    let result = search(&db, &config, "knee", None, None, None, None).unwrap();
+    let values: Vec<f64> = result.entries.iter().map(|h| h.entry.value).collect();
+    assert_eq!(values, vec![30.0, 6.0]);
+    assert_eq!(result.entries[1].matched, "Left **KNEE** sore after stairs");
+
+    let result = search(&db, &config, "knee", Some("pain"), None, None, None).unwrap();
+    assert_eq!(result.entries.len(), 1);
+    let result = search(&db, &config, "knee", None, Some(d(2)), None, None).unwrap();
+    assert_eq!(result.entries.len(), 1);
+    let result = search(&db, &config, "knee", None, None, None, Some(1)).unwrap();
+    assert_eq!(result.entries[0].entry.value, 30.0);
+
+    // LIKE wildcards are literal
+    let result = search(&db, &config, "%", None, None, None, None).unwrap();
+    assert_eq!(result.entries.len(), 1);
+    assert!(search(&db, &config, "  ", None, None, None, None).is_err());
+}
+
+#[test]
+fn test_search_matches_medication_note_and_stop_reason() {
+    use openvital::core::med::{self, AddMedicationParams};
+    use openvital::core::search::search;
+
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    for (name, note) in [("ibuprofen", Some("for knee pain")), ("vitd", None)] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq: "daily",
+                route: None,
+                note,
+                started: None,
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+    med::stop_medication(&db, "vitd", Some("knee surgery"), None).unwrap();
+
+    let result = search(&db, &config, "Knee", None, None, None, None).unwrap();
+    let hits: Vec<(&str, &str)> = result
+        .medications
+        .iter()
+        .map(|h| (h.name.as_str(), h.field))
+        .collect();
+    assert_eq!(hits, vec![("ibuprofen", "note"), ("vitd", "stop_reason")]);
+
+    let result = search(&db, &config, "knee", Some("vitd"), None, None, None).unwrap();
+    assert_eq!(result.medications.len(), 1);
+    assert!(!result.medications[0].active);
+}
+
+#[test]
+fn test_search_snippet_trims_long_notes() {
+    use openvital::core::search::snippet;
+
+    let note = format!("{}knee{}", "a".repeat(40), "b".repeat(40));
+    let s = snippet(&note, "KNEE").unwrap();
+    assert_eq!(s, format!("…{}**knee**{}…", "a".repeat(30), "b".repeat(30)));
+    assert_eq!(snippet("ü knee", "knee").unwrap(), "ü **knee**");
+    assert!(snippet("elbow", "knee").is_none());
+}
+
+// ── show_with – sort order ──────────────────────────────────────────────────
+
+#[test]
+fn test_show_with_asc_keeps_newest_entries_oldest_first() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+
+    for (day, v) in [(1, 83.0), (2, 82.5), (3, 82.0)] {
+        let d = NaiveDate::from_ymd_opt(2026, 2, day).unwrap();
+        db.insert_metric(&common::make_metric("weight", v, d))
+            .unwrap();
+    }
+
+    let values = |order| match show(
+        &db,
+        &config,
+        Some("weight"),
+        &ShowOptions {
+            last: Some(2),
+            order,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    {
+        ShowResult::ByType { entries, .. } => entries.iter().map(|m| m.value).collect::<Vec<_>>(),
+        _ => panic!("expected ByType"),
+    };
+    assert_eq!(values(SortOrder::Desc), vec![82.0, 82.5]);
+    assert_eq!(values(SortOrder::Asc), vec![82.5, 82.0]);
+}
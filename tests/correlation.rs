@@ -63,3 +63,121 @@ fn test_correlation_insufficient_data() {
     );
     assert!(result.data_points <= 1);
 }
+
+fn insert_lagged_sleep_pain(db: &openvital::db::Database) {
+    // Pain the day after is high when sleep was short
+    let sleep = [8.0, 5.0, 7.0, 4.0, 9.0, 6.0, 5.0, 8.0, 4.0, 7.0];
+    for (i, &s) in sleep.iter().enumerate() {
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1 + i as u32).unwrap();
+        let next = day + chrono::Duration::days(1);
+        db.insert_metric(&common::make_metric("sleep_hours", s, day))
+            .unwrap();
+        db.insert_metric(&common::make_metric("pain", 10.0 - s, next))
+            .unwrap();
+    }
+}
+
+/// Scenario: Lagged relationship is found only with the right shift
+#[test]
+fn test_correlate_with_lag_pairs_next_day() {
+    let (_dir, db) = common::setup_db();
+    insert_lagged_sleep_pain(&db);
+
+    let same_day = trend::correlate(&db, "sleep_hours", "pain", None).unwrap();
+    let lagged = trend::correlate_with_lag(&db, "sleep_hours", "pain", None, 1).unwrap();
+
+    assert_eq!(lagged.lag, 1);
+    assert_eq!(lagged.data_points, 10);
+    assert!((lagged.coefficient + 1.0).abs() < 0.01);
+    assert!(same_day.coefficient.abs() < lagged.coefficient.abs());
+}
+
+/// Scenario: Negative lag shifts the other way
+#[test]
+fn test_correlate_with_negative_lag() {
+    let (_dir, db) = common::setup_db();
+    insert_lagged_sleep_pain(&db);
+
+    // pain today vs sleep yesterday is the same relationship seen from the other side
+    let r = trend::correlate_with_lag(&db, "pain", "sleep_hours", None, -1).unwrap();
+    assert_eq!(r.lag, -1);
+    assert!((r.coefficient + 1.0).abs() < 0.01);
+}
+
+/// Scenario: Pairs shifted past the edge of the data are lost
+#[test]
+fn test_correlate_with_lag_loses_edge_pairs() {
+    let (_dir, db) = common::setup_db();
+    for i in 0..4 {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1 + i).unwrap();
+        db.insert_metric(&common::make_metric("pain", i as f64, date))
+            .unwrap();
+        db.insert_metric(&common::make_metric("mood", i as f64 * 2.0, date))
+            .unwrap();
+    }
+
+    let r = trend::correlate_with_lag(&db, "pain", "mood", None, 2).unwrap();
+    assert_eq!(r.data_points, 2);
+    assert_eq!(r.interpretation, "insufficient data");
+}
+
+/// Scenario: Lag scan reports the strongest lag
+#[test]
+fn test_scan_lag_picks_strongest() {
+    let (_dir, db) = common::setup_db();
+    insert_lagged_sleep_pain(&db);
+
+    let scan = trend::scan_lag(&db, "sleep_hours", "pain", None).unwrap();
+
+    assert_eq!(scan.lags.len(), 15);
+    assert_eq!(scan.best.lag, 1);
+    assert!((scan.best.coefficient + 1.0).abs() < 0.01);
+}
+
+/// Scenario: Matrix across three metrics is symmetric with a unit diagonal
+#[test]
+fn test_correlation_matrix_symmetric() {
+    let (_dir, db) = common::setup_db();
+    let screen_values = [8.0, 4.0, 10.0, 6.0, 12.0, 3.0, 7.0];
+    for (i, &screen) in screen_values.iter().enumerate() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1 + i as u32).unwrap();
+        db.insert_metric(&common::make_metric("pain", 2.0 + i as f64, date))
+            .unwrap();
+        db.insert_metric(&common::make_metric("mood", 9.0 - i as f64, date))
+            .unwrap();
+        db.insert_metric(&common::make_metric("screen_time", screen, date))
+            .unwrap();
+    }
+
+    let m = trend::correlation_matrix(&db, None).unwrap();
+    assert_eq!(m.types.len(), 3);
+    for i in 0..3 {
+        assert_eq!(m.coefficients[i][i], Some(1.0));
+        for j in 0..3 {
+            assert_eq!(m.coefficients[i][j], m.coefficients[j][i]);
+        }
+    }
+    let pain = m.types.iter().position(|t| t == "pain").unwrap();
+    let mood = m.types.iter().position(|t| t == "mood").unwrap();
+    assert_eq!(m.coefficients[pain][mood], Some(-1.0));
+}
+
+/// Scenario: Pairs with fewer than five shared days are left empty
+#[test]
+fn test_correlation_matrix_insufficient_overlap() {
+    let (_dir, db) = common::setup_db();
+    for i in 0..7 {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1 + i).unwrap();
+        db.insert_metric(&common::make_metric("pain", i as f64, date))
+            .unwrap();
+        if i < 4 {
+            db.insert_metric(&common::make_metric("mood", i as f64, date))
+                .unwrap();
+        }
+    }
+
+    let m = trend::correlation_matrix(&db, None).unwrap();
+    assert_eq!(m.types.len(), 2);
+    assert_eq!(m.coefficients[0][1], None);
+    assert_eq!(m.coefficients[1][0], None);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use chrono::{Duration, NaiveDate};
+use openvital::core::cycle;
+use openvital::core::trend;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// Bleeding days: `len` consecutive days from each start.
+fn period_days(starts: &[NaiveDate], len: i64) -> BTreeSet<NaiveDate> {
+    starts
+        .iter()
+        .flat_map(|s| (0..len).map(move |i| *s + Duration::days(i)))
+        .collect()
+}
+
+#[test]
+fn test_cycle_starts_split_on_gaps() {
+    let starts = [date(2026, 1, 1), date(2026, 1, 29), date(2026, 2, 28)];
+    let days = period_days(&starts, 5);
+    assert_eq!(cycle::cycle_starts(&days, 14), starts);
+
+    // A skipped day mid-period is not a new cycle
+    let mut spotty = days.clone();
+    spotty.remove(&date(2026, 1, 3));
+    assert_eq!(cycle::cycle_starts(&spotty, 14), starts);
+
+    // With a gap threshold longer than the cycles, everything is one cycle
+    assert_eq!(cycle::cycle_starts(&days, 30), [date(2026, 1, 1)]);
+}
+
+#[test]
+fn test_summarize_averages_irregular_cycles_and_predicts() {
+    // Lengths 26, 32, 28, 35, 27, 30, 29: only the last 6 count
+    let mut starts = vec![date(2025, 6, 1)];
+    for len in [26, 32, 28, 35, 27, 30, 29] {
+        let next = *starts.last().unwrap() + Duration::days(len);
+        starts.push(next);
+    }
+    let last = *starts.last().unwrap();
+    let today = last + Duration::days(9);
+
+    let status = cycle::summarize(&starts, today);
+    assert_eq!(status.cycles_logged, 8);
+    assert_eq!(status.current_start, Some(last));
+    assert_eq!(status.current_day, Some(10));
+    assert_eq!(status.recent_lengths, [32, 28, 35, 27, 30, 29]);
+    assert_eq!(status.average_length, Some(30.2));
+    assert_eq!(status.predicted_next_start, Some(last + Duration::days(30)));
+    assert_eq!(status.days_until_next, Some(21));
+
+    // Past the predicted start, the countdown goes negative
+    let late = cycle::summarize(&starts, last + Duration::days(33));
+    assert_eq!(late.days_until_next, Some(-3));
+    assert_eq!(late.current_day, Some(34));
+}
+
+#[test]
+fn test_single_cycle_has_no_prediction() {
+    let status = cycle::summarize(&[date(2026, 3, 1)], date(2026, 3, 5));
+    assert_eq!(status.current_day, Some(5));
+    assert!(status.recent_lengths.is_empty());
+    assert!(status.average_length.is_none());
+    assert!(status.predicted_next_start.is_none());
+    assert!(status.days_until_next.is_none());
+
+    let none = cycle::summarize(&[], date(2026, 3, 5));
+    assert_eq!(none.cycles_logged, 0);
+    assert!(none.current_day.is_none());
+}
+
+#[test]
+fn test_status_and_cycle_day_correlation_from_db() {
+    let (_dir, db) = common::setup_db();
+    let today = openvital::core::time::local_today("UTC");
+    let first = today - Duration::days(40);
+    let second = today - Duration::days(12);
+    for day in period_days(&[first, second], 4) {
+        db.insert_metric(&common::make_metric("cycle", 3.0, day))
+            .unwrap();
+    }
+    // Pain peaks early in each cycle
+    for offset in 0..20 {
+        let day = second - Duration::days(28) + Duration::days(offset);
+        let pain = 8.0 - (offset as f64 / 3.0).min(6.0);
+        db.insert_metric(&common::make_metric("pain", pain, day))
+            .unwrap();
+    }
+
+    let status = cycle::status_on(&db, 14, today).unwrap();
+    assert_eq!(status.cycles_logged, 2);
+    assert_eq!(status.current_day, Some(13));
+    assert_eq!(status.average_length, Some(28.0));
+    assert_eq!(
+        status.predicted_next_start,
+        Some(second + Duration::days(28))
+    );
+
+    let series = cycle::day_series(&db, 14).unwrap();
+    assert_eq!(series[&first], 1.0);
+    assert_eq!(series[&(second - Duration::days(1))], 28.0);
+    assert_eq!(series[&today], 13.0);
+
+    let r = trend::correlate_with(
+        &db,
+        "pain",
+        cycle::CYCLE_DAY,
+        None,
+        0,
+        14,
+        trend::GapFill::default(),
+    )
+    .unwrap();
+    assert_eq!(r.data_points, 20);
+    assert!(r.coefficient < -0.9);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::maintenance;
+use openvital::db::SCHEMA_VERSION;
+
+/// Scenario: Stats count rows per table and per type, with the data's date range
+#[test]
+fn test_db_stats_counts_and_range() {
+    let (_dir, db) = common::setup_db();
+    let d = |day| NaiveDate::from_ymd_opt(2026, 1, day).unwrap();
+    for day in 1..=3 {
+        db.insert_metric(&common::make_metric("weight", 80.0, d(day)))
+            .unwrap();
+    }
+    let mut tagged = common::make_metric("pain", 3.0, d(5));
+    tagged.tags = vec!["knee".into()];
+    db.insert_metric(&tagged).unwrap();
+
+    let stats = maintenance::stats(&db).unwrap();
+    assert_eq!(stats.schema_version, SCHEMA_VERSION);
+    assert!(stats.size_bytes > 0);
+    assert!(!stats.encrypted);
+
+    let rows = |table: &str| {
+        stats
+            .tables
+            .iter()
+            .find(|t| t.table == table)
+            .map(|t| t.rows)
+    };
+    assert_eq!(rows("metrics"), Some(4));
+    assert_eq!(rows("metric_tags"), Some(1));
+    assert_eq!(rows("medications"), Some(0));
+
+    assert_eq!(stats.metric_types.len(), 2);
+    assert_eq!(stats.metric_types[0].metric_type, "weight");
+    assert_eq!(stats.metric_types[0].count, 3);
+    assert_eq!(stats.metric_types[0].earliest.date_naive(), d(1));
+    assert_eq!(stats.metric_types[0].latest.date_naive(), d(3));
+    assert_eq!(stats.earliest.unwrap().date_naive(), d(1));
+    assert_eq!(stats.latest.unwrap().date_naive(), d(5));
+}
+
+/// Scenario: An empty database has no types and no date range
+#[test]
+fn test_db_stats_empty() {
+    let (_dir, db) = common::setup_db();
+    let stats = maintenance::stats(&db).unwrap();
+    assert!(stats.metric_types.is_empty());
+    assert!(stats.earliest.is_none());
+    assert!(stats.tables.iter().all(|t| t.rows == 0));
+}
+
+/// Scenario: A fresh database passes the integrity check and can be compacted
+#[test]
+fn test_db_check_and_compact() {
+    let (dir, db) = common::setup_db();
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    for i in 0..500 {
+        let mut m = common::make_metric("weight", 80.0, date);
+        m.note = Some(format!("entry {} {}", i, "x".repeat(200)));
+        db.insert_metric(&m).unwrap();
+    }
+    let check = maintenance::check(&db).unwrap();
+    assert!(check.ok);
+    assert!(check.problems.is_empty());
+
+    // Free the pages from a second connection, as deleting outside the app would.
+    let conn = rusqlite::Connection::open(dir.path().join("test.db")).unwrap();
+    conn.execute("DELETE FROM metrics", []).unwrap();
+    drop(conn);
+
+    let result = maintenance::compact(&db).unwrap();
+    assert!(result.size_after < result.size_before);
+    assert_eq!(
+        result.reclaimed_bytes,
+        result.size_before - result.size_after
+    );
+}
+
+/// Scenario: vacuum after deleting many rows reports the bytes it freed
+#[test]
+fn test_vacuum_returns_bytes_freed() {
+    let (dir, db) = common::setup_db();
+    let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    for i in 0..300 {
+        let mut m = common::make_metric("weight", 80.0, date);
+        m.note = Some(format!("entry {} {}", i, "x".repeat(200)));
+        db.insert_metric(&m).unwrap();
+    }
+    let pages = db.page_count().unwrap();
+    assert!(pages > 0);
+    assert_eq!(
+        db.file_size().unwrap().size_bytes,
+        pages as u64 * db.page_size().unwrap() as u64
+    );
+
+    let conn = rusqlite::Connection::open(dir.path().join("test.db")).unwrap();
+    conn.execute("DELETE FROM metrics", []).unwrap();
+    drop(conn);
+
+    let freed = db.vacuum().unwrap();
+    assert!(freed > 0);
+    assert!(db.page_count().unwrap() < pages);
+}
+
+/// Scenario: database.auto_vacuum switches an existing file to incremental mode
+#[test]
+fn test_auto_vacuum_incremental_on_open() {
+    let (dir, db) = common::setup_db();
+    assert!(!db.incremental_vacuum_enabled().unwrap());
+    drop(db);
+
+    let mut config = openvital::models::config::Config::default();
+    config.database.auto_vacuum = true;
+    let db =
+        openvital::db::Database::open_with_config(&dir.path().join("test.db"), &config).unwrap();
+    assert!(db.incremental_vacuum_enabled().unwrap());
+    assert!(maintenance::check(&db).unwrap().ok);
+}
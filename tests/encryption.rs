@@ -0,0 +1,187 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::backup;
+use openvital::db::{Database, SortOrder};
+use openvital::models::config::{Config, KeySource};
+use tempfile::TempDir;
+
+fn day() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+}
+
+fn is_plain_sqlite(path: &std::path::Path) -> bool {
+    std::fs::read(path)
+        .unwrap()
+        .starts_with(b"SQLite format 3\0")
+}
+
+/// Scenario: Data written with a key reads back with the same key only
+#[test]
+fn test_encrypted_roundtrip() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("data.db");
+    {
+        let db = Database::open_encrypted(&path, "UTC", Some("s3cret")).unwrap();
+        assert!(db.is_encrypted());
+        db.insert_metric(&common::make_metric("weight", 80.0, day()))
+            .unwrap();
+    }
+    assert!(!is_plain_sqlite(&path));
+
+    let db = Database::open_encrypted(&path, "UTC", Some("s3cret")).unwrap();
+    let rows = db
+        .query_by_type("weight", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].value, 80.0);
+    drop(db);
+
+    assert!(Database::open(&path).is_err());
+    let err = Database::open_encrypted(&path, "UTC", Some("wrong"))
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("cannot decrypt"), "{}", err);
+}
+
+/// Scenario: An existing plaintext database is encrypted in place
+#[test]
+fn test_encrypt_existing_database() {
+    let (dir, db) = common::setup_db();
+    db.insert_metric(&common::make_metric("pain", 3.0, day()))
+        .unwrap();
+    drop(db);
+    let path = dir.path().join("test.db");
+    assert!(is_plain_sqlite(&path));
+
+    backup::encrypt(&path, "hunter2").unwrap();
+
+    assert!(!is_plain_sqlite(&path));
+    assert!(!dir.path().join("test.db.encrypting").exists());
+    let db = Database::open_encrypted(&path, "UTC", Some("hunter2")).unwrap();
+    assert_eq!(
+        db.query_by_type("pain", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        Database::verify_file(&path, Some("hunter2")).unwrap(),
+        openvital::db::SCHEMA_VERSION
+    );
+}
+
+/// Scenario: Backups of an encrypted database stay encrypted and restore with the key
+#[test]
+fn test_encrypted_backup_restore() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("data.db");
+    let db = Database::open_encrypted(&path, "UTC", Some("k")).unwrap();
+    db.insert_metric(&common::make_metric("weight", 79.0, day()))
+        .unwrap();
+
+    let snap = dir.path().join("snap.db");
+    backup::backup(&db, &snap, false).unwrap();
+    assert!(!is_plain_sqlite(&snap));
+
+    let target = dir.path().join("restored.db");
+    assert!(backup::restore(&snap, &target, None).is_err());
+    backup::restore(&snap, &target, Some("k")).unwrap();
+    let db2 = Database::open_encrypted(&target, "UTC", Some("k")).unwrap();
+    assert_eq!(
+        db2.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_key_source_parse_and_resolve() {
+    assert_eq!(
+        "env:MY_KEY".parse::<KeySource>().unwrap(),
+        KeySource::EnvVar("MY_KEY".into())
+    );
+    assert_eq!(
+        "keychain".parse::<KeySource>().unwrap(),
+        KeySource::Keychain
+    );
+    let plain: KeySource = "plain:abc".parse().unwrap();
+    assert_eq!(plain.resolve().unwrap(), "abc");
+    assert!("env:".parse::<KeySource>().is_err());
+    assert!("vault".parse::<KeySource>().is_err());
+
+    let missing = KeySource::EnvVar("OPENVITAL_TEST_UNSET_KEY_VAR".into());
+    assert!(missing.resolve().is_err());
+}
+
+#[test]
+fn test_encryption_config_toml_roundtrip() {
+    let mut config = Config::default();
+    assert!(config.encryption.passphrase().unwrap().is_none());
+    config.encryption.enabled = true;
+    config.encryption.key_source = KeySource::PlainText("pw".into());
+
+    let toml_str = toml::to_string_pretty(&config).unwrap();
+    let back: Config = toml::from_str(&toml_str).unwrap();
+    assert!(back.encryption.enabled);
+    assert_eq!(back.encryption.passphrase().unwrap().as_deref(), Some("pw"));
+
+    let keychain: Config =
+        toml::from_str("[encryption]\nenabled = false\nkey_source = \"keychain\"\n").unwrap();
+    assert_eq!(keychain.encryption.key_source, KeySource::Keychain);
+}
+
+/// Scenario: An encrypted database opened without a key asks for the passphrase
+#[test]
+fn test_open_encrypted_without_key_requires_passphrase() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("data.db");
+    Database::open_encrypted(&path, "UTC", Some("s3cret")).unwrap();
+
+    let err = Database::open(&path).err().unwrap().to_string();
+    assert!(err.contains("passphrase required"), "{}", err);
+}
+
+/// Scenario: Toggling encryption converts the database both ways
+#[test]
+fn test_set_encryption_roundtrip() {
+    let (dir, db) = common::setup_db();
+    db.insert_metric(&common::make_metric("weight", 80.0, day()))
+        .unwrap();
+    drop(db);
+    let path = dir.path().join("test.db");
+    let mut config = Config::default();
+    config.encryption.key_source = KeySource::PlainText("pw".into());
+
+    assert!(backup::set_encryption(&mut config, &path, true).unwrap());
+    assert!(config.encryption.enabled);
+    assert!(!is_plain_sqlite(&path));
+    assert!(!backup::set_encryption(&mut config, &path, true).unwrap());
+
+    assert!(backup::set_encryption(&mut config, &path, false).unwrap());
+    assert!(!config.encryption.enabled);
+    assert!(is_plain_sqlite(&path));
+    assert!(!dir.path().join("test.db.decrypting").exists());
+    let db = Database::open(&path).unwrap();
+    assert_eq!(
+        db.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+/// Scenario: Decrypting with the wrong key leaves the database untouched
+#[test]
+fn test_decrypt_wrong_key_keeps_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("data.db");
+    Database::open_encrypted(&path, "UTC", Some("right")).unwrap();
+    let before = std::fs::read(&path).unwrap();
+
+    assert!(backup::decrypt(&path, "wrong").is_err());
+    assert_eq!(std::fs::read(&path).unwrap(), before);
+    assert!(!dir.path().join("data.db.decrypting").exists());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::error::{self, ErrorKind};
+
+#[test]
+fn test_kind_of_explicit_and_library_errors() {
+    assert_eq!(
+        error::kind_of(&error::not_found("goal not found")),
+        ErrorKind::NotFound
+    );
+    // Context on top keeps the original category.
+    let wrapped = error::validation("bad flag").context("while parsing");
+    assert_eq!(error::kind_of(&wrapped), ErrorKind::Validation);
+
+    let io = std::fs::read_to_string("/definitely/not/here").unwrap_err();
+    assert_eq!(
+        error::kind_of(&anyhow::Error::from(io)),
+        ErrorKind::NotFound
+    );
+
+    let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+    assert_eq!(error::kind_of(&json.into()), ErrorKind::Parse);
+
+    assert_eq!(
+        error::kind_of(&anyhow::anyhow!("something else")),
+        ErrorKind::General
+    );
+}
+
+#[test]
+fn test_kind_of_database_errors() {
+    let (_dir, db) = common::setup_db();
+    let m = common::make_metric("weight", 80.0, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    db.insert_metric(&m).unwrap();
+    let err = db.insert_metric(&m).unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::Conflict);
+    assert_eq!(ErrorKind::Conflict.code(), "conflict");
+    assert_eq!(ErrorKind::Db.exit_code(), 4);
+}
@@ -2,6 +2,7 @@ mod common;
 
 use chrono::NaiveDate;
 use openvital::core::export;
+use openvital::db::SortOrder;
 
 /// Scenario: Export to CSV includes all entries
 #[test]
@@ -18,7 +19,7 @@ fn test_export_csv() {
     db.insert_metric(&m2).unwrap();
     db.insert_metric(&m3).unwrap();
 
-    let csv = export::to_csv(&db, None, None, None).unwrap();
+    let csv = export::to_csv(&db, None, None, None, &[]).unwrap();
     let lines: Vec<&str> = csv.lines().collect();
     assert_eq!(lines.len(), 4); // header + 3 rows
     assert!(lines[0].contains("timestamp"));
@@ -35,7 +36,7 @@ fn test_export_json() {
     db.insert_metric(&m1).unwrap();
     db.insert_metric(&m2).unwrap();
 
-    let json_str = export::to_json(&db, None, None, None).unwrap();
+    let json_str = export::to_json(&db, None, None, None, &[]).unwrap();
     let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
     assert_eq!(parsed.len(), 2);
 }
@@ -53,7 +54,7 @@ fn test_export_filter_by_type() {
     db.insert_metric(&m1).unwrap();
     db.insert_metric(&m2).unwrap();
 
-    let csv = export::to_csv(&db, Some("weight"), None, None).unwrap();
+    let csv = export::to_csv(&db, Some("weight"), None, None, &[]).unwrap();
     let lines: Vec<&str> = csv.lines().collect();
     assert_eq!(lines.len(), 2); // header + 1 weight row
     assert!(!csv.contains("water"));
@@ -71,7 +72,9 @@ fn test_import_json() {
     let count = export::import_json(&db, json).unwrap();
     assert_eq!(count, 2);
 
-    let weights = db.query_by_type("weight", Some(10)).unwrap();
+    let weights = db
+        .query_by_type("weight", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(weights.len(), 1);
 }
 
@@ -103,7 +106,7 @@ fn test_export_csv_date_range_filter() {
     db.insert_metric(&m3).unwrap();
 
     // Export only from jan1 to jan5 (inclusive)
-    let csv = export::to_csv(&db, None, Some(jan1), Some(jan5)).unwrap();
+    let csv = export::to_csv(&db, None, Some(jan1), Some(jan5), &[]).unwrap();
     let lines: Vec<&str> = csv.lines().collect();
 
     // Should have header + 2 rows (jan1 and jan5), not jan10
@@ -136,7 +139,7 @@ fn test_export_json_date_range_filter() {
     db.insert_metric(&m3).unwrap();
 
     // Export only jan1..=jan3
-    let json_str = export::to_json(&db, None, Some(jan1), Some(jan3)).unwrap();
+    let json_str = export::to_json(&db, None, Some(jan1), Some(jan3), &[]).unwrap();
     let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
 
     assert_eq!(
@@ -164,7 +167,7 @@ fn test_export_csv_type_and_date_range() {
         .unwrap();
 
     // Filter by type=weight, date range jan1..=jan3
-    let csv = export::to_csv(&db, Some("weight"), Some(jan1), Some(jan3)).unwrap();
+    let csv = export::to_csv(&db, Some("weight"), Some(jan1), Some(jan3), &[]).unwrap();
     let lines: Vec<&str> = csv.lines().collect();
 
     // Should have header + 2 weight rows (jan1 and jan3), not jan5 weight, not water
@@ -188,7 +191,7 @@ fn test_export_csv_type_and_date_range() {
 fn test_export_csv_empty_database() {
     let (_dir, db) = common::setup_db();
 
-    let csv = export::to_csv(&db, None, None, None).unwrap();
+    let csv = export::to_csv(&db, None, None, None, &[]).unwrap();
     let lines: Vec<&str> = csv.lines().collect();
 
     assert_eq!(
@@ -207,7 +210,7 @@ fn test_export_csv_empty_database() {
 fn test_export_json_empty_database() {
     let (_dir, db) = common::setup_db();
 
-    let json_str = export::to_json(&db, None, None, None).unwrap();
+    let json_str = export::to_json(&db, None, None, None, &[]).unwrap();
     let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
 
     assert!(
@@ -232,7 +235,9 @@ fn test_import_json_missing_optional_fields() {
         "Should import 2 entries even without optional fields"
     );
 
-    let weights = db.query_by_type("weight", Some(10)).unwrap();
+    let weights = db
+        .query_by_type("weight", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(weights.len(), 1);
     // source should default to "import" when not provided
     assert_eq!(
@@ -240,7 +245,9 @@ fn test_import_json_missing_optional_fields() {
         "source should default to 'import' when not provided"
     );
 
-    let sleep = db.query_by_type("sleep_hours", Some(10)).unwrap();
+    let sleep = db
+        .query_by_type("sleep_hours", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(sleep.len(), 1);
     assert!(
         sleep[0].note.is_none(),
@@ -263,7 +270,9 @@ fn test_import_json_custom_source() {
     let count = export::import_json(&db, json).unwrap();
     assert_eq!(count, 1);
 
-    let entries = db.query_by_type("cardio", Some(10)).unwrap();
+    let entries = db
+        .query_by_type("cardio", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(entries.len(), 1);
     assert_eq!(
         entries[0].source, "garmin_sync",
@@ -288,7 +297,7 @@ fn test_import_json_note_and_tags_preserved() {
     let count = export::import_json(&db, json).unwrap();
     assert_eq!(count, 1);
 
-    let entries = db.query_by_type("pain", Some(10)).unwrap();
+    let entries = db.query_by_type("pain", Some(10), SortOrder::Desc).unwrap();
     assert_eq!(entries.len(), 1);
     assert_eq!(
         entries[0].note.as_deref(),
@@ -312,7 +321,7 @@ fn test_import_csv_full_fields() {
     let count = export::import_csv(&db, csv).unwrap();
     assert_eq!(count, 1);
 
-    let entries = db.query_by_type("pain", Some(10)).unwrap();
+    let entries = db.query_by_type("pain", Some(10), SortOrder::Desc).unwrap();
     assert_eq!(entries.len(), 1);
     assert_eq!(
         entries[0].note.as_deref(),
@@ -341,7 +350,9 @@ fn test_import_csv_minimal_fields() {
     let count = export::import_csv(&db, csv).unwrap();
     assert_eq!(count, 1);
 
-    let entries = db.query_by_type("water", Some(10)).unwrap();
+    let entries = db
+        .query_by_type("water", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(entries.len(), 1);
     // unit should fall back to default for "water" which is "ml"
     assert_eq!(
@@ -388,14 +399,16 @@ fn test_round_trip_csv_export_import() {
     db1.insert_metric(&original).unwrap();
 
     // Export from db1
-    let csv = export::to_csv(&db1, None, None, None).unwrap();
+    let csv = export::to_csv(&db1, None, None, None, &[]).unwrap();
 
     // Import into a fresh db2
     let (_dir2, db2) = common::setup_db();
     let count = export::import_csv(&db2, &csv).unwrap();
     assert_eq!(count, 1);
 
-    let entries = db2.query_by_type("weight", Some(10)).unwrap();
+    let entries = db2
+        .query_by_type("weight", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(entries.len(), 1);
     assert_eq!(entries[0].value, 77.5);
     assert_eq!(
@@ -420,7 +433,7 @@ fn test_import_csv_malformed_tags_uses_default() {
         "Row with malformed tags JSON should still be imported"
     );
 
-    let entries = db.query_by_type("pain", Some(10)).unwrap();
+    let entries = db.query_by_type("pain", Some(10), SortOrder::Desc).unwrap();
     assert_eq!(entries.len(), 1);
     assert!(
         entries[0].tags.is_empty(),
@@ -442,14 +455,16 @@ fn test_round_trip_json_export_import() {
     db1.insert_metric(&original).unwrap();
 
     // Export from db1
-    let json_str = export::to_json(&db1, None, None, None).unwrap();
+    let json_str = export::to_json(&db1, None, None, None, &[]).unwrap();
 
     // Import into fresh db2
     let (_dir2, db2) = common::setup_db();
     let count = export::import_json(&db2, &json_str).unwrap();
     assert_eq!(count, 1);
 
-    let entries = db2.query_by_type("sleep_hours", Some(10)).unwrap();
+    let entries = db2
+        .query_by_type("sleep_hours", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(entries.len(), 1);
     assert_eq!(entries[0].value, 7.0);
     assert_eq!(
@@ -475,7 +490,7 @@ fn test_export_csv_with_tags_serialised_as_json_array() {
     m.tags = vec!["knee".to_string(), "post-run".to_string()];
     db.insert_metric(&m).unwrap();
 
-    let csv = export::to_csv(&db, None, None, None).unwrap();
+    let csv = export::to_csv(&db, None, None, None, &[]).unwrap();
 
     // The tags column should contain a JSON array representation
     assert!(
@@ -486,3 +501,670 @@ fn test_export_csv_with_tags_serialised_as_json_array() {
     let lines: Vec<&str> = csv.lines().collect();
     assert_eq!(lines.len(), 2, "Should have header + 1 data row");
 }
+
+const APPLE_HEALTH_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE HealthData>
+<HealthData locale="en_US">
+ <ExportDate value="2026-01-10 09:00:00 -0500"/>
+ <Record type="HKQuantityTypeIdentifierBodyMass" sourceName="Scale" unit="lb" creationDate="2026-01-05 08:01:00 -0500" startDate="2026-01-05 08:00:00 -0500" endDate="2026-01-05 08:00:00 -0500" value="176.37"/>
+ <Record type="HKQuantityTypeIdentifierHeartRate" sourceName="Watch" unit="count/min" startDate="2026-01-05 09:00:00 -0500" endDate="2026-01-05 09:00:00 -0500" value="62">
+  <MetadataEntry key="HKMetadataKeyHeartRateMotionContext" value="0"/>
+ </Record>
+ <Record type="HKQuantityTypeIdentifierBodyFatPercentage" sourceName="Scale" unit="%" startDate="2026-01-05 08:00:00 -0500" endDate="2026-01-05 08:00:00 -0500" value="0.22"/>
+ <Record type="HKQuantityTypeIdentifierFlightsClimbed" sourceName="Phone" unit="count" startDate="2026-01-05 10:00:00 -0500" endDate="2026-01-05 10:05:00 -0500" value="3"/>
+ <Record type="HKQuantityTypeIdentifierBodyMass" sourceName="Scale" unit="stone" startDate="2026-01-06 08:00:00 -0500" endDate="2026-01-06 08:00:00 -0500" value="12"/>
+ <Record type="HKCategoryTypeIdentifierSleepAnalysis" sourceName="Watch" startDate="2026-01-05 23:00:00 -0500" endDate="2026-01-06 07:00:00 -0500" value="HKCategoryValueSleepAnalysisAsleep"/>
+</HealthData>
+"#;
+
+/// Scenario: Apple Health import maps known identifiers and converts units
+#[test]
+fn test_import_apple_health_maps_and_converts() {
+    let (dir, db) = common::setup_db();
+    let path = dir.path().join("export.xml");
+    std::fs::write(&path, APPLE_HEALTH_XML).unwrap();
+
+    let result = export::import_apple_health(&db, &path).unwrap();
+
+    assert_eq!(result.imported, 3);
+    // FlightsClimbed (unknown) + BodyMass in stone (unknown unit)
+    assert_eq!(result.skipped, 2);
+    assert_eq!(
+        result.unknown_types,
+        vec!["HKQuantityTypeIdentifierFlightsClimbed"]
+    );
+
+    let weight = db.query_by_type("weight", None, SortOrder::Desc).unwrap();
+    assert_eq!(weight.len(), 1);
+    assert!((weight[0].value - 80.0).abs() < 0.01);
+    assert_eq!(weight[0].source, "apple_health");
+    assert_eq!(
+        weight[0].timestamp.to_rfc3339(),
+        "2026-01-05T13:00:00+00:00"
+    );
+
+    let hr = db
+        .query_by_type("heart_rate", None, SortOrder::Desc)
+        .unwrap();
+    assert_eq!(hr[0].value, 62.0);
+    assert_eq!(hr[0].unit, "bpm");
+
+    let fat = db.query_by_type("body_fat", None, SortOrder::Desc).unwrap();
+    assert!((fat[0].value - 22.0).abs() < 1e-9);
+}
+
+/// Scenario: Apple Health import of a missing file errors
+#[test]
+fn test_import_apple_health_missing_file() {
+    let (dir, db) = common::setup_db();
+    let path = dir.path().join("nope.xml");
+    assert!(export::import_apple_health(&db, &path).is_err());
+}
+
+const GARMIN_CSV: &str = "\
+Activity Type,Date,Favorite,Title,Distance,Calories,Elapsed Time,Avg HR,Max HR
+Running,2026-01-15 07:30:00,false,\"Morning Run\",5.02,\"1,034\",01:02:30,152,171
+Cycling,2026-01-16 18:00:00,false,Evening Ride,20.1,450,00:45:00,--,160
+Strength Training,not-a-date,false,Gym,0.00,200,00:30:00,110,130
+";
+
+/// Scenario: Garmin CSV rows map to cardio, calories_burned, heart_rate
+#[test]
+fn test_import_garmin_csv_maps_columns() {
+    let (_dir, db) = common::setup_db();
+
+    let result = export::import_garmin_csv(&db, GARMIN_CSV).unwrap();
+
+    // Running: 3 metrics; Cycling: cardio + calories (no HR); bad date row skipped
+    assert_eq!(result.imported, 5);
+    assert_eq!(result.skipped, 1);
+
+    let cardio = db
+        .query_by_type("cardio", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(cardio.len(), 2);
+    let run = cardio
+        .iter()
+        .find(|m| m.tags == vec!["Running".to_string()])
+        .unwrap();
+    assert!((run.value - 62.5).abs() < 1e-9);
+    assert_eq!(run.source, "garmin");
+    assert_eq!(run.timestamp.to_rfc3339(), "2026-01-15T07:30:00+00:00");
+
+    let calories = db
+        .query_by_type("calories_burned", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert!(calories.iter().any(|m| (m.value - 1034.0).abs() < 1e-9));
+
+    let hr = db
+        .query_by_type("heart_rate", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(hr.len(), 1);
+    assert_eq!(hr[0].value, 152.0);
+}
+
+/// Scenario: Garmin columns are found by header name regardless of order
+#[test]
+fn test_import_garmin_csv_reordered_columns() {
+    let (_dir, db) = common::setup_db();
+    let csv = "Avg HR,Elapsed Time,Date,Activity Type\n140,00:20:00,2026-01-15 07:30:00,Walking\n";
+
+    let result = export::import_garmin_csv(&db, csv).unwrap();
+
+    assert_eq!(result.imported, 2);
+    let cardio = db
+        .query_by_type("cardio", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(cardio[0].value, 20.0);
+    assert_eq!(cardio[0].tags, vec!["Walking".to_string()]);
+}
+
+/// Scenario: Garmin CSV without a Date column is rejected
+#[test]
+fn test_import_garmin_csv_requires_date_column() {
+    let (_dir, db) = common::setup_db();
+    let result = export::import_garmin_csv(&db, "Activity Type,Calories\nRunning,300\n");
+    assert!(result.is_err());
+}
+
+/// Scenario: FHIR export wraps LOINC-coded Observations in a collection Bundle
+#[test]
+fn test_export_fhir_bundle() {
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+    let mut weight = common::make_metric("weight", 80.5, d);
+    weight.note = Some("morning".into());
+    db.insert_metric(&weight).unwrap();
+    db.insert_metric(&common::make_metric("standing_breaks", 4.0, d))
+        .unwrap();
+
+    let out = export::to_fhir(&db, None, None, None, &[]).unwrap();
+    let bundle: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    assert_eq!(bundle["resourceType"], "Bundle");
+    assert_eq!(bundle["type"], "collection");
+    // bdl-1: total only on searchset/history bundles
+    assert!(bundle.get("total").is_none());
+    let entries = bundle["entry"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+
+    let obs = entries
+        .iter()
+        .map(|e| &e["resource"])
+        .find(|r| r["code"]["text"] == "weight")
+        .unwrap();
+    assert_eq!(obs["resourceType"], "Observation");
+    assert_eq!(obs["status"], "final");
+    assert_eq!(obs["code"]["coding"][0]["system"], "http://loinc.org");
+    assert_eq!(obs["code"]["coding"][0]["code"], "29463-7");
+    assert_eq!(obs["effectiveDateTime"], "2026-01-05T12:00:00+00:00");
+    assert_eq!(obs["valueQuantity"]["value"], 80.5);
+    assert_eq!(obs["valueQuantity"]["unit"], "kg");
+    assert_eq!(obs["note"][0]["text"], "morning");
+    assert!(obs.get("extension").is_none());
+}
+
+/// Scenario: FHIR Observations without a LOINC code carry the raw type in an extension
+#[test]
+fn test_export_fhir_unmapped_type_uses_extension() {
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+    db.insert_metric(&common::make_metric("standing_breaks", 4.0, d))
+        .unwrap();
+
+    let out = export::to_fhir(&db, Some("standing_breaks"), None, None, &[]).unwrap();
+    let bundle: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    let obs = &bundle["entry"][0]["resource"];
+    assert!(obs["code"].get("coding").is_none());
+    assert_eq!(obs["extension"][0]["valueString"], "standing_breaks");
+}
+
+/// Scenario: Export with tags writes only entries carrying every tag
+#[test]
+fn test_export_csv_filtered_by_tags() {
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    for (value, tags) in [
+        (30.0, vec!["gym", "am"]),
+        (40.0, vec!["gym"]),
+        (50.0, vec![]),
+    ] {
+        let mut m = common::make_metric("strength", value, d);
+        m.tags = tags.into_iter().map(String::from).collect();
+        db.insert_metric(&m).unwrap();
+    }
+
+    let csv = export::to_csv(&db, None, None, None, &["GYM".into()]).unwrap();
+    assert_eq!(csv.lines().count(), 3);
+    let csv = export::to_csv(&db, None, None, None, &["gym".into(), "am".into()]).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains(",30,"));
+}
+
+/// Scenario: Bulk CSV import of 10k rows runs in one transaction, quickly
+#[test]
+fn test_import_csv_bulk_10k_rows() {
+    let (_dir, db) = common::setup_db();
+    let mut csv = String::from("timestamp,type,value,unit,note,tags,source\n");
+    for i in 0..10_000 {
+        csv.push_str(&format!(
+            "2026-01-01T00:00:{:02}+00:00,heart_rate,{},bpm,,[\"bulk\"],\n",
+            i % 60,
+            60 + i % 40
+        ));
+    }
+
+    let started = std::time::Instant::now();
+    let count = export::import_csv(&db, &csv).unwrap();
+    assert_eq!(count, 10_000);
+    assert!(
+        started.elapsed() < std::time::Duration::from_secs(30),
+        "import took {:?}",
+        started.elapsed()
+    );
+    let rows = db.table_counts().unwrap();
+    let metrics = rows.iter().find(|t| t.table == "metrics").unwrap();
+    assert_eq!(metrics.rows, 10_000);
+}
+
+/// Scenario: Rows with a bad timestamp or value are counted and skipped
+#[test]
+fn test_import_csv_skips_unparseable_rows() {
+    let (_dir, db) = common::setup_db();
+    let csv = "timestamp,type,value\n\
+               2026-01-05T12:00:00+00:00,weight,78.0\n\
+               not-a-date,weight,79.0\n\
+               2026-01-06T12:00:00+00:00,weight,heavy\n\
+               2026-01-07T12:00:00+00:00\n\
+               2026-01-08T12:00:00+00:00,weight,79.5\n";
+
+    let counts = export::import_csv_with(&db, csv, export::ImportOptions::default()).unwrap();
+    assert_eq!(counts.imported, 2);
+    assert_eq!(counts.errors, 3);
+    let values: Vec<f64> = db
+        .query_by_type("weight", Some(10), SortOrder::Asc)
+        .unwrap()
+        .iter()
+        .map(|m| m.value)
+        .collect();
+    assert_eq!(values, [78.0, 79.5]);
+
+    let (_dir, db) = common::setup_db();
+    let jsonl = r#"{"type": "water", "value": 250}
+{"type": "water", "value": 500, "timestamp": "yesterday"}
+not json
+{"type": "water", "value": 300}"#;
+    let counts = export::import_jsonl_with(&db, jsonl, export::ImportOptions::default()).unwrap();
+    assert_eq!((counts.imported, counts.errors), (2, 2));
+}
+
+fn chunked(n: usize) -> export::ImportOptions {
+    export::ImportOptions {
+        chunk_size: Some(n),
+        dedupe: false,
+        merge: export::MergeStrategy::Skip,
+    }
+}
+
+/// Scenario: Chunked import skips bad rows in any chunk and commits the rest
+#[test]
+fn test_import_csv_chunked_skips_bad_rows() {
+    let (_dir, db) = common::setup_db();
+    let csv = "timestamp,type,value\n\
+               2026-01-05T12:00:00+00:00,weight,78.0\n\
+               2026-01-06T12:00:00+00:00,weight,78.5\n\
+               2026-01-07T12:00:00+00:00,weight,79.0\n\
+               not-a-date,weight,79.5\n";
+
+    let counts = export::import_csv_with(&db, csv, chunked(2)).unwrap();
+    assert_eq!((counts.imported, counts.errors), (3, 1));
+    assert_eq!(
+        db.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        3
+    );
+
+    let (_dir, db) = common::setup_db();
+    let json = r#"[{"type": "water", "value": 250}, {"type": "water", "value": 500},
+                   {"type": "water", "value": 300}]"#;
+    assert_eq!(
+        export::import_json_with(&db, json, chunked(2))
+            .unwrap()
+            .imported,
+        3
+    );
+}
+
+/// Scenario: A chunk failing after earlier chunks committed keeps its error kind
+#[test]
+fn test_import_chunk_failure_keeps_error_kind() {
+    let (dir, db) = common::setup_db();
+    drop(db);
+    let path = dir.path().join("test.db");
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch(
+        "CREATE TRIGGER reject_999 BEFORE INSERT ON metrics WHEN NEW.value = 999
+         BEGIN SELECT RAISE(ABORT, 'value 999 rejected'); END;",
+    )
+    .unwrap();
+    drop(conn);
+    let db = openvital::db::Database::open(&path).unwrap();
+
+    let csv = "timestamp,type,value\n\
+               2026-01-05T12:00:00+00:00,weight,78.0\n\
+               2026-01-06T12:00:00+00:00,weight,78.5\n\
+               2026-01-07T12:00:00+00:00,weight,999\n";
+    let err = export::import_csv_with(&db, csv, chunked(2)).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("import stopped after 2 committed rows"),
+        "{message}"
+    );
+    assert!(message.contains("value 999 rejected"), "{}", message);
+    assert_eq!(
+        openvital::error::kind_of(&err),
+        openvital::error::ErrorKind::Conflict
+    );
+}
+
+/// Scenario: Re-importing an overlapping CSV with dedupe skips existing rows
+#[test]
+fn test_import_csv_dedupe_skips_existing_rows() {
+    let (_dir, db) = common::setup_db();
+    let dedupe = export::ImportOptions {
+        chunk_size: None,
+        dedupe: true,
+        merge: export::MergeStrategy::Skip,
+    };
+    let first = "timestamp,type,value\n\
+                 2026-01-05T12:00:00+00:00,water,500\n\
+                 2026-01-06T12:00:00+00:00,water,750\n";
+    let overlap = "timestamp,type,value\n\
+                   2026-01-06T12:00:00+00:00,water,750\n\
+                   2026-01-06T12:00:00+00:00,water,750\n\
+                   2026-01-07T12:00:00+00:00,water,250\n\
+                   broken\n";
+
+    assert_eq!(
+        export::import_csv_with(&db, first, dedupe)
+            .unwrap()
+            .imported,
+        2
+    );
+    let counts = export::import_csv_with(&db, overlap, dedupe).unwrap();
+    assert_eq!(counts.imported, 1);
+    assert_eq!(counts.skipped_duplicates, 2);
+    assert_eq!(counts.errors, 1);
+    assert_eq!(
+        db.query_by_type("water", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        3
+    );
+
+    // Without dedupe the default merge still skips rows matching stored entries
+    let counts = export::import_csv_with(&db, overlap, export::ImportOptions::default()).unwrap();
+    assert_eq!(counts.imported, 0);
+    assert_eq!(counts.skipped_duplicates, 3);
+}
+
+/// Scenario: JSON export round-trips idempotently by id with dedupe
+#[test]
+fn test_import_json_dedupe_by_id_round_trip() {
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+    db.insert_metric(&common::make_metric("weight", 80.0, d))
+        .unwrap();
+    let json = export::to_json(&db, None, None, None, &[]).unwrap();
+
+    let dedupe = export::ImportOptions {
+        chunk_size: None,
+        dedupe: true,
+        merge: export::MergeStrategy::Skip,
+    };
+    let counts = export::import_json_with(&db, &json, dedupe).unwrap();
+    assert_eq!(counts.imported, 0);
+    assert_eq!(counts.skipped_duplicates, 1);
+
+    // A plain re-import skips by default, so it adds nothing
+    assert_eq!(export::import_json(&db, &json).unwrap(), 0);
+    let stored = db
+        .query_by_type("weight", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+}
+
+fn merging(merge: export::MergeStrategy) -> export::ImportOptions {
+    export::ImportOptions {
+        merge,
+        ..Default::default()
+    }
+}
+
+/// Scenario: Importing the same export twice with --merge skip keeps one entry
+#[test]
+fn test_import_json_merge_skip_is_idempotent() {
+    let (_dir, db) = common::setup_db();
+    let json =
+        r#"[{"id": "e1", "timestamp": "2026-01-05T12:00:00Z", "type": "weight", "value": 80.0}]"#;
+
+    let counts = export::import_json_with(&db, json, merging(export::MergeStrategy::Skip)).unwrap();
+    assert_eq!(counts.imported, 1);
+    let counts = export::import_json_with(&db, json, merging(export::MergeStrategy::Skip)).unwrap();
+    assert_eq!(counts.imported, 0);
+    assert_eq!(counts.skipped_duplicates, 1);
+    assert_eq!(
+        db.query_by_type("weight", Some(10), SortOrder::Desc)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+/// Scenario: --merge overwrite replaces the stored entry with the same id
+#[test]
+fn test_import_json_merge_overwrite_replaces_entry() {
+    let (_dir, db) = common::setup_db();
+    let first = r#"[{"id": "e1", "timestamp": "2026-01-05T12:00:00Z", "type": "weight", "value": 80.0, "tags": ["old"]}]"#;
+    let second =
+        r#"[{"id": "e1", "timestamp": "2026-01-05T12:00:00Z", "type": "weight", "value": 79.2}]"#;
+
+    export::import_json(&db, first).unwrap();
+    let counts =
+        export::import_json_with(&db, second, merging(export::MergeStrategy::Overwrite)).unwrap();
+    assert_eq!(counts.imported, 0);
+    assert_eq!(counts.overwritten, 1);
+
+    let stored = db
+        .query_by_type("weight", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].id, "e1");
+    assert_eq!(stored[0].value, 79.2);
+    assert!(stored[0].tags.is_empty());
+}
+
+/// Scenario: --merge newest keeps whichever entry has the later timestamp
+#[test]
+fn test_import_json_merge_newest_keeps_later_entry() {
+    let (_dir, db) = common::setup_db();
+    let stored =
+        r#"[{"id": "e1", "timestamp": "2026-01-05T12:00:00Z", "type": "weight", "value": 80.0}]"#;
+    let older =
+        r#"[{"id": "e1", "timestamp": "2026-01-04T12:00:00Z", "type": "weight", "value": 81.0}]"#;
+    let newer =
+        r#"[{"id": "e1", "timestamp": "2026-01-06T12:00:00Z", "type": "weight", "value": 79.0}]"#;
+
+    export::import_json(&db, stored).unwrap();
+    let counts =
+        export::import_json_with(&db, older, merging(export::MergeStrategy::Newest)).unwrap();
+    assert_eq!(counts.skipped_duplicates, 1);
+    assert_eq!(db.get_metric("e1").unwrap().unwrap().value, 80.0);
+
+    let counts =
+        export::import_json_with(&db, newer, merging(export::MergeStrategy::Newest)).unwrap();
+    assert_eq!(counts.overwritten, 1);
+    let m = db.get_metric("e1").unwrap().unwrap();
+    assert_eq!(m.value, 79.0);
+    assert_eq!(m.timestamp.to_rfc3339(), "2026-01-06T12:00:00+00:00");
+}
+
+/// Scenario: CSV rows have no id, so --merge matches type and timestamp within a second
+#[test]
+fn test_import_csv_merge_matches_type_and_time() {
+    let (_dir, db) = common::setup_db();
+    let first = "timestamp,type,value\n\
+                 2026-01-05T12:00:00+00:00,water,500\n";
+    let again = "timestamp,type,value\n\
+                 2026-01-05T12:00:01+00:00,water,600\n\
+                 2026-01-05T12:00:03+00:00,water,250\n\
+                 2026-01-05T12:00:00+00:00,steps,4000\n";
+
+    export::import_csv(&db, first).unwrap();
+    let counts =
+        export::import_csv_with(&db, again, merging(export::MergeStrategy::Overwrite)).unwrap();
+    assert_eq!(counts.overwritten, 1);
+    assert_eq!(counts.imported, 2);
+
+    let water = db
+        .query_by_type("water", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(water.len(), 2);
+    assert!(water.iter().any(|m| m.value == 600.0));
+    assert!(water.iter().all(|m| m.value != 500.0));
+}
+
+/// Scenario: JSON Lines export has one compact entry per line and round-trips
+#[test]
+fn test_export_jsonl_round_trip() {
+    let (_dir, db) = common::setup_db();
+    assert_eq!(export::to_jsonl(&db, None, None, None, &[]).unwrap(), "");
+
+    let mut m1 = common::make_metric("weight", 85.0, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    m1.tags = vec!["morning".into()];
+    m1.note = Some("after run".into());
+    let m2 = common::make_metric("water", 500.0, NaiveDate::from_ymd_opt(2026, 1, 2).unwrap());
+    db.insert_metric(&m1).unwrap();
+    db.insert_metric(&m2).unwrap();
+
+    let jsonl = export::to_jsonl(&db, None, None, None, &[]).unwrap();
+    assert_eq!(jsonl.lines().count(), 2);
+    assert!(!jsonl.ends_with('\n'));
+    for line in jsonl.lines() {
+        let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(entry["type"].is_string());
+    }
+
+    let (_dir2, fresh) = common::setup_db();
+    let counts = export::import_jsonl_with(&fresh, &jsonl, Default::default()).unwrap();
+    assert_eq!(counts.imported, 2);
+    let weight = fresh.get_metric(&m1.id).unwrap().unwrap();
+    assert_eq!(weight.value, 85.0);
+    assert_eq!(weight.tags, vec!["morning".to_string()]);
+    assert_eq!(weight.note.as_deref(), Some("after run"));
+    assert_eq!(weight.timestamp, m1.timestamp);
+}
+
+/// Scenario: JSON Lines import skips blank and malformed lines
+#[test]
+fn test_import_jsonl_counts_malformed_lines() {
+    let (_dir, db) = common::setup_db();
+    let content = "{\"type\":\"steps\",\"value\":4000}\n\
+                   \n\
+                   not json\n\
+                   {\"value\":3}\n\
+                   {\"type\":\"water\",\"value\":250,\"timestamp\":\"2026-01-05T12:00:00Z\"}\n";
+    let counts = export::import_jsonl_with(&db, content, Default::default()).unwrap();
+    assert_eq!(counts.imported, 2);
+    assert_eq!(counts.errors, 2);
+}
+
+/// Scenario: compressed exports are gzip streams that decode back for import
+#[test]
+fn test_write_compressed_and_decode_input() {
+    use std::io::Read;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("export.json.gz");
+    let data = r#"[{"type": "weight", "value": 80.0}]"#;
+    export::write_compressed(data, &path).unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+    let mut decoded = String::new();
+    flate2::read::GzDecoder::new(bytes.as_slice())
+        .read_to_string(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, data);
+
+    assert_eq!(export::decode_input(bytes).unwrap(), data);
+    assert_eq!(
+        export::decode_input(data.as_bytes().to_vec()).unwrap(),
+        data
+    );
+    assert!(export::is_gz_path(&path));
+    assert!(!export::is_gz_path(std::path::Path::new("export.json")));
+}
+
+/// Scenario: A CSV note containing commas keeps its tags and source
+#[test]
+fn test_import_csv_note_with_commas() {
+    let (_dir, db) = common::setup_db();
+    let csv = "timestamp,type,value,unit,note,tags,source\n\
+               2026-02-01T07:00:00+00:00,pain,6.0,0-10,lower back, after lifting,[\"back\",\"gym\"],physio_app\n\
+               2026-02-02T07:00:00+00:00,pain,4.0,0-10,better,[]\n";
+
+    assert_eq!(export::import_csv(&db, csv).unwrap(), 2);
+    let entries = db.query_by_type("pain", Some(10), SortOrder::Asc).unwrap();
+    assert_eq!(
+        entries[0].note.as_deref(),
+        Some("lower back, after lifting")
+    );
+    assert_eq!(entries[0].tags, vec!["back".to_string(), "gym".to_string()]);
+    assert_eq!(entries[0].source, "physio_app");
+    assert_eq!(entries[1].note.as_deref(), Some("better"));
+    assert_eq!(entries[1].source, "import");
+}
+
+/// Scenario: Parquet export writes one row group with the typed columns
+#[test]
+fn test_export_parquet_row_groups() {
+    use openvital::core::parquet;
+
+    let (dir, db) = common::setup_db();
+    let mut m1 = common::make_metric("weight", 85.0, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    m1.note = Some("after run".into());
+    m1.tags = vec!["am".into(), "fasted".into()];
+    let m2 = common::make_metric("water", 500.0, NaiveDate::from_ymd_opt(2026, 1, 2).unwrap());
+    let m3 = common::make_metric("weight", 84.5, NaiveDate::from_ymd_opt(2026, 1, 3).unwrap());
+    for m in [&m1, &m2, &m3] {
+        db.insert_metric(m).unwrap();
+    }
+
+    let path = dir.path().join("out.parquet");
+    export::to_parquet(&db, None, None, None, &[], &path).unwrap();
+    let summary = parquet::read_summary(&path).unwrap();
+    assert_eq!(summary.num_rows, 3);
+    assert_eq!(summary.row_groups, 1);
+    assert_eq!(
+        summary.columns,
+        [
+            "timestamp",
+            "metric_type",
+            "value",
+            "unit",
+            "note",
+            "element"
+        ]
+    );
+
+    // Rows read back through the parquet crate's own reader
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+    use ::parquet::record::{ListAccessor, RowAccessor};
+    let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let rows: Vec<_> = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    let weight = &rows[0];
+    assert_eq!(
+        weight.get_timestamp_millis(0).unwrap(),
+        m1.timestamp.timestamp_millis()
+    );
+    assert_eq!(weight.get_string(1).unwrap(), "weight");
+    assert_eq!(weight.get_double(2).unwrap(), 85.0);
+    assert_eq!(weight.get_string(3).unwrap(), &m1.unit);
+    assert_eq!(weight.get_string(4).unwrap(), "after run");
+    let tags = weight.get_list(5).unwrap();
+    let tags: Vec<&String> = (0..tags.len())
+        .map(|i| tags.get_string(i).unwrap())
+        .collect();
+    assert_eq!(tags, ["am", "fasted"]);
+    let water = &rows[1];
+    assert_eq!(water.get_double(2).unwrap(), 500.0);
+    assert!(water.get_string(4).is_err());
+    assert_eq!(water.get_list(5).unwrap().len(), 0);
+
+    // Filters apply as for the other formats
+    export::to_parquet(&db, Some("water"), None, None, &[], &path).unwrap();
+    assert_eq!(parquet::read_summary(&path).unwrap().num_rows, 1);
+
+    // Rows are split across row groups of the given size
+    let entries = export::metric_entries(&db, None, None, None, &[]).unwrap();
+    std::fs::write(&path, parquet::encode_metrics(&entries, 2).unwrap()).unwrap();
+    let summary = parquet::read_summary(&path).unwrap();
+    assert_eq!((summary.num_rows, summary.row_groups), (3, 2));
+
+    // An empty export is still a valid file, with no row groups
+    export::to_parquet(&db, Some("steps"), None, None, &[], &path).unwrap();
+    let summary = parquet::read_summary(&path).unwrap();
+    assert_eq!((summary.num_rows, summary.row_groups), (0, 0));
+
+    std::fs::write(&path, "timestamp,type\n").unwrap();
+    assert!(parquet::read_summary(&path).is_err());
+}
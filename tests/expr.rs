@@ -0,0 +1,33 @@
+use openvital::core::expr::eval;
+
+#[test]
+fn test_eval_precedence_and_parentheses() {
+    assert_eq!(eval("2 + 3 * 4").unwrap(), 14.0);
+    assert_eq!(eval("(2 + 3) * 4").unwrap(), 20.0);
+    assert_eq!(eval("10 / 4 - 1").unwrap(), 1.5);
+    assert_eq!(eval("8 - 2 - 1").unwrap(), 5.0);
+    assert_eq!(eval("((1.5))").unwrap(), 1.5);
+    assert!((eval("350 * 0.9").unwrap() - 315.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_eval_negative_results_and_unary_minus() {
+    assert_eq!(eval("3 - 10").unwrap(), -7.0);
+    assert_eq!(eval("-(2 + 3)").unwrap(), -5.0);
+    assert_eq!(eval("4 * -2").unwrap(), -8.0);
+}
+
+#[test]
+fn test_eval_division_by_zero() {
+    let err = eval("5 / (2 - 2)").unwrap_err();
+    assert!(err.to_string().contains("division by zero"));
+}
+
+#[test]
+fn test_eval_rejects_invalid_input() {
+    for bad in [
+        "", "2 +", "(1 + 2", "1 + 2)", "abs(3)", "x * 2", "1..2", "2 ^ 3", "3 4",
+    ] {
+        assert!(eval(bad).is_err(), "accepted {:?}", bad);
+    }
+}
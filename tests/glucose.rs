@@ -0,0 +1,77 @@
+mod common;
+
+use chrono::{Duration, NaiveDate};
+use openvital::core::glucose;
+use openvital::core::report;
+use openvital::models::config::{Config, GlucoseConfig};
+use openvital::models::metric::Metric;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+}
+
+/// Readings spread through a day, an hour apart from noon.
+fn readings(d: u32, values: &[f64]) -> Vec<Metric> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let mut m = common::make_metric("glucose", *v, day(d));
+            m.timestamp += Duration::hours(i as i64);
+            m
+        })
+        .collect()
+}
+
+#[test]
+fn test_time_in_range_over_mixed_days() {
+    let targets = GlucoseConfig::default();
+    let mut entries = readings(1, &[95.0, 140.0, 190.0, 65.0]);
+    entries.extend(readings(2, &[110.0, 70.0, 180.0, 120.0, 100.0, 181.0]));
+    entries.push(common::make_metric("weight", 80.0, day(2)));
+
+    let tir = glucose::time_in_range(&entries, &targets, chrono_tz::UTC).unwrap();
+    assert_eq!(tir.readings, 10);
+    assert_eq!((tir.lows, tir.highs), (1, 2));
+    assert_eq!(tir.in_range_pct, 70.0);
+    assert_eq!(tir.below_pct, 10.0);
+    assert_eq!(tir.above_pct, 20.0);
+    assert_eq!(tir.average, 125.1);
+
+    assert_eq!(tir.days.len(), 2);
+    let d1 = &tir.days[0];
+    assert_eq!(d1.date, day(1));
+    assert_eq!((d1.readings, d1.lows, d1.highs), (4, 1, 1));
+    assert_eq!(d1.in_range_pct, 50.0);
+    assert_eq!(d1.average, 122.5);
+    // Target bounds are inclusive
+    let d2 = &tir.days[1];
+    assert_eq!((d2.lows, d2.highs), (0, 1));
+    assert_eq!(d2.in_range_pct, 83.3);
+
+    assert!(glucose::time_in_range(&entries[..0], &targets, chrono_tz::UTC).is_none());
+}
+
+#[test]
+fn test_report_includes_time_in_range_with_custom_targets() {
+    let (_dir, db) = common::setup_db();
+    for m in readings(3, &[80.0, 150.0, 160.0]) {
+        db.insert_metric(&m).unwrap();
+    }
+    let config = Config {
+        glucose: GlucoseConfig {
+            target_low: 70.0,
+            target_high: 140.0,
+        },
+        ..Config::default()
+    };
+
+    let result = report::generate_with(&db, &config, None, day(1), day(7), &[]).unwrap();
+    let tir = result.glucose.unwrap();
+    assert_eq!(tir.target_high, 140.0);
+    assert_eq!(tir.highs, 2);
+    assert_eq!(tir.in_range_pct, 33.3);
+
+    let empty = report::generate_with(&db, &config, None, day(10), day(17), &[]).unwrap();
+    assert!(empty.glucose.is_none());
+}
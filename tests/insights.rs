@@ -0,0 +1,229 @@
+mod common;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use openvital::core::goal;
+use openvital::core::insights::{self, InsightDetail, Severity};
+use openvital::core::med::{self, AddMedicationParams};
+use openvital::db::Database;
+use openvital::models::config::Config;
+use openvital::models::goal::{Direction, Timeframe};
+
+/// A Tuesday; the 30-day window starts on 2026-03-02.
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, 31).unwrap()
+}
+
+fn log(db: &Database, metric_type: &str, value: f64, date: NaiveDate) {
+    db.insert_metric(&common::make_metric(metric_type, value, date))
+        .unwrap();
+}
+
+fn kinds(result: &insights::InsightsResult) -> Vec<String> {
+    result
+        .insights
+        .iter()
+        .map(|i| {
+            serde_json::to_value(i).unwrap()["type"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_no_data_no_insights() {
+    let (_dir, db) = common::setup_db();
+    let result = insights::generate_on(&db, &Config::default(), 30, today()).unwrap();
+    assert!(result.insights.is_empty());
+    assert_eq!(result.from, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+    assert_eq!(result.to, today());
+}
+
+#[test]
+fn test_correlation_needs_shared_days() {
+    let (_dir, db) = common::setup_db();
+    // Nine shared days: below the minimum, even with a perfect correlation
+    for i in 0..9 {
+        let d = today() - Duration::days(i);
+        log(&db, "calories_in", 2000.0 + 100.0 * i as f64, d);
+        log(&db, "stress", 3.0 + i as f64 * 0.5, d);
+    }
+    let result = insights::generate_on(&db, &Config::default(), 30, today()).unwrap();
+    assert!(!kinds(&result).contains(&"correlation".to_string()));
+
+    let d = today() - Duration::days(9);
+    log(&db, "calories_in", 2900.0, d);
+    log(&db, "stress", 7.5, d);
+    let result = insights::generate_on(&db, &Config::default(), 30, today()).unwrap();
+    let found = result
+        .insights
+        .iter()
+        .find_map(|i| match &i.detail {
+            InsightDetail::Correlation {
+                metric_a,
+                metric_b,
+                coefficient,
+                data_points,
+            } => Some((
+                metric_a.clone(),
+                metric_b.clone(),
+                *coefficient,
+                *data_points,
+            )),
+            _ => None,
+        })
+        .expect("correlation insight");
+    assert_eq!(found, ("calories_in".into(), "stress".into(), 1.0, 10));
+}
+
+#[test]
+fn test_trend_reported_when_significant() {
+    let (_dir, db) = common::setup_db();
+    for i in 0..20 {
+        log(
+            &db,
+            "weight",
+            90.0 - 0.2 * i as f64,
+            today() - Duration::days(19 - i),
+        );
+        // Noise around a flat line is not a trend
+        let wobble = if i % 2 == 0 { 0.5 } else { -0.5 };
+        log(
+            &db,
+            "waist",
+            85.0 + wobble,
+            today() - Duration::days(19 - i),
+        );
+    }
+    let result = insights::generate_on(&db, &Config::default(), 30, today()).unwrap();
+    let trends: Vec<_> = result
+        .insights
+        .iter()
+        .filter_map(|i| match &i.detail {
+            InsightDetail::Trend {
+                metric_type, rate, ..
+            } => Some((metric_type.as_str(), *rate, i.severity, i.message.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(trends.len(), 1);
+    assert_eq!(trends[0].0, "weight");
+    assert_eq!(trends[0].1, -0.2);
+    assert_eq!(trends[0].2, Severity::Notice);
+    assert!(trends[0].3.contains("trending down"));
+}
+
+#[test]
+fn test_goal_consistently_missed() {
+    let (_dir, db) = common::setup_db();
+    goal::set_goal(
+        &db,
+        "water".into(),
+        2000.0,
+        Direction::Above,
+        Timeframe::Daily,
+    )
+    .unwrap();
+    // Met on 1 of the 10 finished days; today is not counted
+    for i in 0..=10 {
+        let v = if i < 2 { 2500.0 } else { 1000.0 };
+        log(&db, "water", v, today() - Duration::days(i));
+    }
+    let result = insights::generate_on(&db, &Config::default(), 30, today()).unwrap();
+    let first = &result.insights[0];
+    assert_eq!(first.severity, Severity::Warning);
+    match &first.detail {
+        InsightDetail::GoalMissed {
+            days_met,
+            days_logged,
+            ..
+        } => {
+            assert_eq!(*days_met, 1);
+            assert_eq!(*days_logged, 10);
+        }
+        other => panic!("unexpected insight: {:?}", other),
+    }
+}
+
+#[test]
+fn test_low_adherence() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+    med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "metformin",
+            dose: Some("500mg"),
+            freq: "daily",
+            route: None,
+            note: None,
+            started: Some(today() - Duration::days(10)),
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+    // 5 of the 10 finished days
+    for i in 1..=5 {
+        med::take_medication(
+            &db,
+            &config,
+            "metformin",
+            None,
+            None,
+            None,
+            Some(today() - Duration::days(i)),
+        )
+        .unwrap();
+    }
+    let result = insights::generate_on(&db, &config, 30, today()).unwrap();
+    let adherence = result
+        .insights
+        .iter()
+        .find_map(|i| match &i.detail {
+            InsightDetail::LowAdherence {
+                medication,
+                adherence,
+            } => Some((medication.as_str(), *adherence)),
+            _ => None,
+        })
+        .expect("adherence insight");
+    assert_eq!(adherence, ("metformin", 0.5));
+}
+
+#[test]
+fn test_weekday_pattern() {
+    let (_dir, db) = common::setup_db();
+    for i in 0..28 {
+        let d = today() - Duration::days(i);
+        let v = if d.weekday() == Weekday::Sun {
+            6.3
+        } else if i % 2 == 0 {
+            7.4
+        } else {
+            7.6
+        };
+        log(&db, "sleep_hours", v, d);
+    }
+    let result = insights::generate_on(&db, &Config::default(), 30, today()).unwrap();
+    let (weekday, difference, message) = result
+        .insights
+        .iter()
+        .find_map(|i| match &i.detail {
+            InsightDetail::WeekdayPattern {
+                weekday,
+                difference,
+                ..
+            } => Some((weekday.clone(), *difference, i.message.clone())),
+            _ => None,
+        })
+        .expect("weekday insight");
+    assert_eq!(weekday, "sunday");
+    assert_eq!(difference, -1.2);
+    assert!(
+        message.contains("1.2 hours lower on Sundays"),
+        "{}",
+        message
+    );
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::journal;
+use openvital::core::trend::{self, TrendPeriod};
+use openvital::models::config::Config;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+}
+
+#[test]
+fn test_note_round_trip_newest_first() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+
+    journal::add(&db, &config, "slept badly", Some("sleep"), Some(day(1))).unwrap();
+    let second = journal::add(&db, &config, "  long run  ", None, Some(day(2))).unwrap();
+    assert_eq!(second.metric_type, "journal");
+    assert_eq!(second.value, 0.0);
+    assert_eq!(second.unit, "");
+    assert_eq!(second.note.as_deref(), Some("long run"));
+
+    let notes = journal::list(&db, None, None, None, &[]).unwrap();
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes[0].note.as_deref(), Some("long run"));
+    assert_eq!(notes[1].tags, vec!["sleep"]);
+
+    let last = journal::list(&db, Some(1), None, None, &[]).unwrap();
+    assert_eq!(last.len(), 1);
+    assert_eq!(last[0].id, second.id);
+
+    assert!(journal::add(&db, &config, "   ", None, None).is_err());
+}
+
+#[test]
+fn test_note_show_filters_by_tag_and_range() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+
+    journal::add(&db, &config, "headache", Some("pain,work"), Some(day(1))).unwrap();
+    journal::add(&db, &config, "knee sore", Some("pain"), Some(day(5))).unwrap();
+    journal::add(&db, &config, "busy day", Some("work"), Some(day(6))).unwrap();
+
+    let pain = journal::list(&db, None, None, None, &["pain".into()]).unwrap();
+    let texts: Vec<_> = pain.iter().filter_map(|m| m.note.as_deref()).collect();
+    assert_eq!(texts, vec!["knee sore", "headache"]);
+
+    let both = journal::list(&db, None, None, None, &["PAIN".into(), "work".into()]).unwrap();
+    assert_eq!(both.len(), 1);
+    assert_eq!(both[0].note.as_deref(), Some("headache"));
+
+    let ranged = journal::list(&db, None, Some(day(2)), Some(day(6)), &["pain".into()]).unwrap();
+    assert_eq!(ranged.len(), 1);
+    assert_eq!(ranged[0].note.as_deref(), Some("knee sore"));
+}
+
+#[test]
+fn test_journal_excluded_from_analysis() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+
+    for d in 1..=5 {
+        journal::add(&db, &config, "note", None, Some(day(d))).unwrap();
+        let m = common::make_metric("weight", 80.0 + d as f64, day(d));
+        db.insert_metric(&m).unwrap();
+    }
+
+    assert!(trend::compute(&db, "journal", TrendPeriod::Daily, None).is_err());
+    assert!(trend::correlate(&db, "journal", "weight", None).is_err());
+
+    let matrix = trend::correlation_matrix(&db, None).unwrap();
+    assert!(!matrix.types.iter().any(|t| t == "journal"));
+}
@@ -1,6 +1,8 @@
 mod common;
 
-use openvital::core::med::{self, AddMedicationParams};
+use chrono::NaiveDate;
+use openvital::core::med::{self, AddMedicationParams, TakeMedicationParams};
+use openvital::db::SortOrder;
 use openvital::models::config::Config;
 use openvital::models::med::Frequency;
 use openvital::models::metric::Category;
@@ -28,6 +30,7 @@ fn add_medication_basic() {
             route: Some("oral"),
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -61,6 +64,7 @@ fn add_medication_topical_with_note() {
             route: Some("topical"),
             note: Some("apply to affected area"),
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -93,6 +97,7 @@ fn add_duplicate_active_errors() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -107,6 +112,7 @@ fn add_duplicate_active_errors() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     );
     assert!(result.is_err());
@@ -136,6 +142,7 @@ fn add_after_stop_allowed() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -151,6 +158,7 @@ fn add_after_stop_allowed() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -177,6 +185,7 @@ fn take_creates_metric_with_count_semantics() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -214,6 +223,7 @@ fn take_with_dose_override() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -268,6 +278,7 @@ fn take_stopped_medication_succeeds() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -308,6 +319,7 @@ fn take_resolves_alias() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -335,6 +347,7 @@ fn stop_medication_with_reason() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -368,6 +381,7 @@ fn remove_preserves_metric_entries() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -384,7 +398,9 @@ fn remove_preserves_metric_entries() {
     assert!(meds.iter().all(|m| m.name != "ibuprofen"));
 
     // But metric entries should remain
-    let metrics = db.query_by_type("ibuprofen", Some(10)).unwrap();
+    let metrics = db
+        .query_by_type("ibuprofen", Some(10), SortOrder::Desc)
+        .unwrap();
     assert_eq!(metrics.len(), 1);
 }
 
@@ -407,6 +423,7 @@ fn adherence_daily_med() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -414,7 +431,7 @@ fn adherence_daily_med() {
     // Take once today
     med::take_medication(&db, &config, "metformin", None, None, None, None).unwrap();
 
-    let statuses = med::adherence_status(&db, Some("metformin"), 7).unwrap();
+    let statuses = med::adherence_status(&db, &config, Some("metformin"), 7).unwrap();
     assert_eq!(statuses.len(), 1);
     let s = &statuses[0];
     assert_eq!(s.name, "metformin");
@@ -443,11 +460,12 @@ fn adherence_as_needed_null() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
 
-    let statuses = med::adherence_status(&db, Some("tylenol"), 7).unwrap();
+    let statuses = med::adherence_status(&db, &config, Some("tylenol"), 7).unwrap();
     assert_eq!(statuses.len(), 1);
     let s = &statuses[0];
     assert!(s.required_today.is_none());
@@ -476,6 +494,7 @@ fn name_conflict_category() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -495,3 +514,803 @@ fn from_type_unchanged() {
     // Ensure the generic from_type hasn't been altered
     assert_eq!(Category::from_type("water"), Category::Nutrition);
 }
+
+// ---------------------------------------------------------------------------
+// 16. schedule_slot_counts_and_taken
+// ---------------------------------------------------------------------------
+
+#[test]
+fn schedule_slot_counts_and_taken() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+
+    for (name, freq) in [
+        ("vitd", "daily"),
+        ("metformin", "2x_daily"),
+        ("amoxicillin", "3x_daily"),
+        ("tylenol", "as_needed"),
+    ] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: Some("400mg"),
+                freq,
+                route: None,
+                note: None,
+                started: None,
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+    med::take_medication(&db, &config, "metformin", None, None, None, None).unwrap();
+
+    let slots = med::schedule(&db).unwrap();
+    let count = |n: &str| slots.iter().filter(|s| s.name == n).count();
+    assert_eq!(count("vitd"), 1);
+    assert_eq!(count("metformin"), 2);
+    assert_eq!(count("amoxicillin"), 3);
+    assert_eq!(count("tylenol"), 0);
+
+    let metformin: Vec<_> = slots.iter().filter(|s| s.name == "metformin").collect();
+    assert!(metformin[0].taken);
+    assert!(!metformin[1].taken);
+    assert_eq!(metformin[0].dose.as_deref(), Some("400mg"));
+
+    // sorted by time
+    assert!(slots.windows(2).all(|w| w[0].time <= w[1].time));
+}
+
+// ---------------------------------------------------------------------------
+// 17. schedule_dose_times_and_weekly_day
+// ---------------------------------------------------------------------------
+
+#[test]
+fn schedule_dose_times_and_weekly_day() {
+    assert_eq!(med::dose_times(1), vec!["14:00"]);
+    assert_eq!(med::dose_times(2), vec!["10:00", "18:00"]);
+    assert_eq!(med::dose_times(3), vec!["08:40", "14:00", "19:20"]);
+
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let today = openvital::core::time::local_today("UTC");
+    for (name, started) in [
+        ("weekly_today", today - chrono::Duration::days(7)),
+        ("weekly_other", today - chrono::Duration::days(3)),
+    ] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq: "weekly",
+                route: None,
+                note: None,
+                started: Some(started),
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+
+    let slots = med::schedule(&db).unwrap();
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].name, "weekly_today");
+}
+
+// ---------------------------------------------------------------------------
+// 18. refill_estimates_from_take_counts
+// ---------------------------------------------------------------------------
+
+#[test]
+fn refill_estimates_from_take_counts() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let today = openvital::core::time::local_today("UTC");
+    let refill_day = today - chrono::Duration::days(3);
+
+    med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "metformin",
+            dose: Some("500mg"),
+            freq: "2x_daily",
+            route: None,
+            note: None,
+            started: Some(today - chrono::Duration::days(10)),
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+
+    let statuses = med::adherence_status(&db, &config, Some("metformin"), 7).unwrap();
+    assert_eq!(statuses[0].estimated_remaining, None);
+    assert_eq!(statuses[0].estimated_empty, None);
+
+    // One take before the refill does not count against it.
+    let before = Some(today - chrono::Duration::days(4));
+    med::take_medication(&db, &config, "metformin", None, None, None, before).unwrap();
+    med::refill_medication(&db, &config, "metformin", 60, 30, Some(refill_day)).unwrap();
+    for i in 0..3 {
+        let day = Some(today - chrono::Duration::days(i));
+        med::take_medication(&db, &config, "metformin", None, None, None, day).unwrap();
+        med::take_medication(&db, &config, "metformin", None, None, None, day).unwrap();
+    }
+
+    let statuses = med::adherence_status(&db, &config, Some("metformin"), 7).unwrap();
+    assert_eq!(statuses[0].estimated_remaining, Some(54));
+    let expected = refill_day + chrono::Duration::days(30);
+    assert_eq!(
+        statuses[0].estimated_empty.as_deref(),
+        Some(expected.format("%Y-%m-%d").to_string().as_str())
+    );
+
+    let err = med::refill_medication(&db, &config, "metformin", 0, 30, None).unwrap_err();
+    assert!(err.to_string().contains("quantity"));
+    assert!(med::refill_medication(&db, &config, "nope", 10, 10, None).is_err());
+}
+
+// ---------------------------------------------------------------------------
+// 19. take_with_quantity_counts_each_dose
+// ---------------------------------------------------------------------------
+
+#[test]
+fn take_with_quantity_counts_each_dose() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "metformin",
+            dose: Some("500mg"),
+            freq: "2x_daily",
+            route: None,
+            note: None,
+            started: None,
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+
+    let params = |quantity| TakeMedicationParams {
+        name: "metformin",
+        dose: None,
+        note: None,
+        tags: None,
+        date: None,
+        quantity,
+        indication: None,
+    };
+    let err = med::take_medication_with(&db, &config, params(0)).unwrap_err();
+    assert!(err.to_string().contains("quantity"));
+
+    let (metric, _) = med::take_medication_with(&db, &config, params(2)).unwrap();
+    assert_eq!(metric.value, 2.0);
+
+    let statuses = med::adherence_status(&db, &config, Some("metformin"), 7).unwrap();
+    assert_eq!(statuses[0].taken_today, 2);
+    assert_eq!(statuses[0].adherent_today, Some(true));
+}
+
+// ---------------------------------------------------------------------------
+// 20. prn_indication_recorded_and_summarised
+// ---------------------------------------------------------------------------
+
+#[test]
+fn prn_indication_recorded_and_summarised() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    for (name, freq) in [("ibuprofen", "as_needed"), ("metformin", "daily")] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: Some("400mg"),
+                freq,
+                route: None,
+                note: None,
+                started: None,
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+
+    let take = |name, indication, note| TakeMedicationParams {
+        name,
+        dose: None,
+        note,
+        tags: None,
+        date: None,
+        quantity: 1,
+        indication,
+    };
+    let (metric, _) =
+        med::take_medication_with(&db, &config, take("ibuprofen", Some(" headache "), None))
+            .unwrap();
+    assert_eq!(metric.note.as_deref(), Some("prn: headache; 400mg"));
+    med::take_medication_with(
+        &db,
+        &config,
+        take("ibuprofen", Some("knee"), Some("after run")),
+    )
+    .unwrap();
+    med::take_medication_with(&db, &config, take("ibuprofen", Some("headache"), None)).unwrap();
+    med::take_medication(&db, &config, "ibuprofen", None, None, None, None).unwrap();
+
+    // Indications only make sense for as-needed medications
+    let err =
+        med::take_medication_with(&db, &config, take("metformin", Some("x"), None)).unwrap_err();
+    assert!(err.to_string().contains("as-needed"));
+
+    let summary = med::prn_summary(&db, "ibuprofen", 7).unwrap();
+    assert_eq!(summary.doses_taken, 4);
+    assert_eq!(summary.most_common_indication.as_deref(), Some("headache"));
+    assert_eq!(summary.indications.len(), 2);
+    assert_eq!(summary.indications[0].count, 2);
+    assert!(med::prn_summary(&db, "nope", 7).is_err());
+}
+
+// ---------------------------------------------------------------------------
+// 21. parse_indication_handles_missing_notes
+// ---------------------------------------------------------------------------
+
+#[test]
+fn parse_indication_handles_missing_notes() {
+    assert_eq!(med::parse_indication(None), None);
+    assert_eq!(med::parse_indication(Some("400mg")), None);
+    assert_eq!(med::parse_indication(Some("prn:")), None);
+    assert_eq!(med::parse_indication(Some("prn: ; 400mg")), None);
+    assert_eq!(
+        med::parse_indication(Some("prn: back pain; 400mg; after run")).as_deref(),
+        Some("back pain")
+    );
+    assert_eq!(
+        med::parse_indication(Some("prn: cramps")).as_deref(),
+        Some("cramps")
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 22. dose_history_lists_takes_newest_first
+// ---------------------------------------------------------------------------
+
+#[test]
+fn dose_history_lists_takes_newest_first() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let today = openvital::core::time::local_today("UTC");
+    med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "ibuprofen",
+            dose: Some("400mg"),
+            freq: "as_needed",
+            route: None,
+            note: None,
+            started: Some(today - chrono::Duration::days(30)),
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+
+    let day = |n| Some(today - chrono::Duration::days(n));
+    med::take_medication(&db, &config, "ibuprofen", None, None, None, day(20)).unwrap();
+    med::take_medication(
+        &db,
+        &config,
+        "ibuprofen",
+        None,
+        Some("after run"),
+        None,
+        day(2),
+    )
+    .unwrap();
+    med::take_medication_with(
+        &db,
+        &config,
+        TakeMedicationParams {
+            name: "ibuprofen",
+            dose: Some("200mg"),
+            note: None,
+            tags: None,
+            date: day(0),
+            quantity: 2,
+            indication: Some("headache"),
+        },
+    )
+    .unwrap();
+    // A manually logged entry of the same type is not a take event
+    db.insert_metric(&common::make_metric("ibuprofen", 1.0, today))
+        .unwrap();
+
+    let history = med::dose_history(&db, "ibuprofen", 7).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].dose_used.as_deref(), Some("200mg"));
+    assert_eq!(history[0].quantity, 2);
+    assert_eq!(
+        history[0].note.as_deref(),
+        Some("prn: headache; 200mg (override)")
+    );
+    assert_eq!(history[1].dose_used.as_deref(), Some("400mg"));
+    assert_eq!(history[1].note.as_deref(), Some("400mg; after run"));
+
+    assert_eq!(med::dose_history(&db, "ibuprofen", 30).unwrap().len(), 3);
+    assert!(med::dose_history(&db, "nope", 7).is_err());
+}
+
+// ---------------------------------------------------------------------------
+// 23. custom_weekday_schedule_adherence
+// ---------------------------------------------------------------------------
+
+#[test]
+fn custom_weekday_schedule_adherence() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let d = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+
+    let med = med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "physio",
+            dose: None,
+            freq: "mon,wed,fri",
+            route: None,
+            note: None,
+            started: Some(d(2)), // Monday
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+    assert_eq!(med.frequency.to_string(), "mon_wed_fri");
+
+    // Every scheduled day for two weeks except Wednesday the 4th
+    for day in [2, 6, 9, 11, 13] {
+        med::take_medication(&db, &config, "physio", None, None, None, Some(d(day))).unwrap();
+    }
+
+    // Saturday: nothing due, so not missed
+    let s = &med::adherence_status_on(&db, &config, Some("physio"), 14, d(14)).unwrap()[0];
+    assert_eq!(s.required_today, Some(0));
+    assert_eq!(s.adherent_today, Some(true));
+    // Streak spans the weekend back to the missed Wednesday: 13, 11, 9, 6
+    assert_eq!(s.streak_days, Some(4));
+    // Only Mon/Wed/Fri count in the window: 9, 11, 13 all taken
+    assert_eq!(s.adherence_7d, Some(1.0));
+    // 30-day window from the start: 5 of 6 scheduled days
+    assert!((s.adherence_30d.unwrap() - 5.0 / 6.0).abs() < 1e-9);
+    let history = s.adherence_history.as_ref().unwrap();
+    let dates: Vec<NaiveDate> = history.iter().map(|h| h.date).collect();
+    assert_eq!(dates, vec![d(13), d(11), d(9), d(6), d(4), d(2)]);
+    assert!(!history[4].adherent);
+
+    // Monday the 16th is due and not yet taken
+    let s = &med::adherence_status_on(&db, &config, Some("physio"), 7, d(16)).unwrap()[0];
+    assert_eq!(s.required_today, Some(1));
+    assert_eq!(s.adherent_today, Some(false));
+    assert_eq!(s.streak_days, Some(0));
+}
+
+// ---------------------------------------------------------------------------
+// 24. skipped_dose_preserves_streak
+// ---------------------------------------------------------------------------
+
+#[test]
+fn skipped_dose_preserves_streak() {
+    let (_dir, db) = common::setup_db();
+    let mut config = default_config();
+    let d = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+    med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "warfarin",
+            dose: Some("5mg"),
+            freq: "daily",
+            route: None,
+            note: None,
+            started: Some(d(1)),
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+
+    // Taken on the 1st-4th, skipped before surgery on the 5th, taken the 6th
+    for day in [1, 2, 3, 4, 6] {
+        med::take_medication(&db, &config, "warfarin", None, None, None, Some(d(day))).unwrap();
+    }
+    let (metric, _) = med::skip_medication(
+        &db,
+        &config,
+        "warfarin",
+        Some("surgery, per Dr. Lee"),
+        Some(d(5)),
+    )
+    .unwrap();
+    assert_eq!(metric.value, 0.0);
+    assert_eq!(metric.source, "med_skip");
+    assert_eq!(metric.note.as_deref(), Some("surgery, per Dr. Lee"));
+
+    let s = &med::adherence_status_on(&db, &config, Some("warfarin"), 7, d(6)).unwrap()[0];
+    assert_eq!(s.streak_days, Some(5));
+    assert_eq!(s.adherence_7d, Some(1.0));
+    let history = s.adherence_history.as_ref().unwrap();
+    assert_eq!(history[1].date, d(5));
+    assert_eq!((history[1].taken, history[1].skipped), (0, 1));
+    assert!(!history[1].adherent);
+
+    // On the day itself: neither adherent nor missed
+    let s = &med::adherence_status_on(&db, &config, Some("warfarin"), 7, d(5)).unwrap()[0];
+    assert_eq!(s.skipped_today, 1);
+    assert_eq!(s.adherent_today, None);
+    assert_eq!(s.streak_days, Some(4));
+
+    // With the setting off, a skip counts as a missed day
+    config.medications.skips_preserve_streak = false;
+    let s = &med::adherence_status_on(&db, &config, Some("warfarin"), 7, d(6)).unwrap()[0];
+    assert_eq!(s.streak_days, Some(1));
+    assert!((s.adherence_7d.unwrap() - 5.0 / 6.0).abs() < 1e-9);
+}
+
+// ---------------------------------------------------------------------------
+// 25. skip_partial_and_as_needed
+// ---------------------------------------------------------------------------
+
+#[test]
+fn skip_partial_and_as_needed() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let d = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+    for (name, freq) in [("metformin", "2x_daily"), ("ibuprofen", "as_needed")] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq,
+                route: None,
+                note: None,
+                started: Some(d(1)),
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+
+    // One of two doses taken and the other skipped: excused
+    med::take_medication(&db, &config, "metformin", None, None, None, Some(d(1))).unwrap();
+    med::skip_medication(&db, &config, "metformin", None, Some(d(1))).unwrap();
+    let s = &med::adherence_status_on(&db, &config, Some("metformin"), 7, d(1)).unwrap()[0];
+    assert_eq!((s.taken_today, s.skipped_today), (1, 1));
+    assert_eq!(s.adherent_today, None);
+    assert_eq!(s.adherence_7d, None);
+
+    // A skip never counts as a take
+    med::skip_medication(&db, &config, "metformin", None, Some(d(2))).unwrap();
+    let s = &med::adherence_status_on(&db, &config, Some("metformin"), 7, d(2)).unwrap()[0];
+    assert_eq!(s.taken_today, 0);
+    assert_eq!(s.adherent_today, Some(false));
+
+    let err = med::skip_medication(&db, &config, "ibuprofen", None, None).unwrap_err();
+    assert!(err.to_string().contains("only for scheduled medications"));
+}
+
+// ---------------------------------------------------------------------------
+// 26. skip_round_trips_through_csv
+// ---------------------------------------------------------------------------
+
+#[test]
+fn skip_round_trips_through_csv() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let d = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+    med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "warfarin",
+            dose: None,
+            freq: "daily",
+            route: None,
+            note: None,
+            started: Some(d(1)),
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+    med::take_medication(&db, &config, "warfarin", None, None, None, Some(d(1))).unwrap();
+    med::skip_medication(
+        &db,
+        &config,
+        "warfarin",
+        Some("surgery, fasting"),
+        Some(d(2)),
+    )
+    .unwrap();
+    let csv = openvital::core::export::to_csv(&db, Some("warfarin"), None, None, &[]).unwrap();
+
+    let (_dir2, db2) = common::setup_db();
+    assert_eq!(openvital::core::export::import_csv(&db2, &csv).unwrap(), 2);
+    let entries = db2
+        .query_by_type("warfarin", Some(10), SortOrder::Asc)
+        .unwrap();
+    let sources: Vec<&str> = entries.iter().map(|m| m.source.as_str()).collect();
+    assert_eq!(sources, vec!["med_take", "med_skip"]);
+    assert_eq!(entries[1].note.as_deref(), Some("surgery, fasting"));
+    assert_eq!(entries[1].value, 0.0);
+}
+
+// ---------------------------------------------------------------------------
+// 27. add_with_interactions
+// ---------------------------------------------------------------------------
+
+#[test]
+fn add_with_interactions() {
+    let (dir, db) = common::setup_db();
+    let mut config = default_config();
+    config
+        .aliases
+        .insert("asa".to_string(), "aspirin".to_string());
+    let params = |name, interacts_with| AddMedicationParams {
+        name,
+        dose: None,
+        freq: "daily",
+        route: None,
+        note: None,
+        started: None,
+        interacts_with,
+    };
+
+    let specs = ["asa:90".to_string(), "iron".to_string()];
+    let m = med::add_medication(&db, &config, params("warfarin", &specs)).unwrap();
+    let declared: Vec<String> = m.interactions.iter().map(|i| i.to_string()).collect();
+    assert_eq!(declared, vec!["aspirin:90", "iron:120"]);
+
+    let stored = db.get_medication_by_name("warfarin").unwrap().unwrap();
+    assert_eq!(stored.interactions, m.interactions);
+
+    let to_self = ["heparin:30".to_string()];
+    let err = med::add_medication(&db, &config, params("heparin", &to_self)).unwrap_err();
+    assert!(err.to_string().contains("itself"));
+    drop(db);
+
+    // A database from before the column existed gains it on open
+    let path = dir.path().join("test.db");
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch("ALTER TABLE medications DROP COLUMN interactions")
+        .unwrap();
+    conn.pragma_update(None, "user_version", 5).unwrap();
+    drop(conn);
+    let db = openvital::db::Database::open(&path).unwrap();
+    let stored = db.get_medication_by_name("warfarin").unwrap().unwrap();
+    assert!(stored.interactions.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// 28. interaction_warning_gap_boundary
+// ---------------------------------------------------------------------------
+
+#[test]
+fn interaction_warning_gap_boundary() {
+    use chrono::{Duration, TimeZone, Utc};
+    use openvital::models::metric::Metric;
+
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    let add = |name, interacts_with: &[String]| {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq: "daily",
+                route: None,
+                note: None,
+                started: None,
+                interacts_with,
+            },
+        )
+        .unwrap();
+    };
+    add("aspirin", &[]);
+    add("warfarin", &["aspirin:120".to_string()]);
+
+    let noon = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+    let take = |name: &str, minutes_before_noon: i64, source: &str| {
+        let mut m = Metric::new(name.to_string(), 1.0);
+        m.category = Category::Medication;
+        m.unit = "dose".to_string();
+        m.source = source.to_string();
+        m.timestamp = noon - Duration::minutes(minutes_before_noon);
+        db.insert_metric(&m).unwrap();
+        m
+    };
+
+    // Exactly 120 minutes apart is allowed; skips never count
+    take("aspirin", 120, "med_take");
+    take("aspirin", 30, "med_skip");
+    let warfarin = take("warfarin", 0, "med_take");
+    assert!(
+        med::interaction_warnings(&db, &warfarin)
+            .unwrap()
+            .is_empty()
+    );
+
+    // 119 minutes is too close
+    take("aspirin", 119, "med_take");
+    let warnings = med::interaction_warnings(&db, &warfarin).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].interacts_with, "aspirin");
+    assert_eq!(warnings[0].gap_minutes, 119);
+    assert_eq!(warnings[0].min_gap_minutes, 120);
+
+    // Declared on warfarin, so taking aspirin warns too
+    let aspirin = take("aspirin", -45, "med_take");
+    let warnings = med::interaction_warnings(&db, &aspirin).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].interacts_with, "warfarin");
+    assert_eq!(warnings[0].gap_minutes, 45);
+}
+
+// ---------------------------------------------------------------------------
+// 29. edit_updates_each_field_independently
+// ---------------------------------------------------------------------------
+
+fn edit_params(name: &str) -> med::EditMedicationParams<'_> {
+    med::EditMedicationParams {
+        name,
+        dose: None,
+        freq: None,
+        route: None,
+        note: None,
+        rename: None,
+    }
+}
+
+#[test]
+fn edit_updates_each_field_independently() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    med::add_medication(
+        &db,
+        &config,
+        AddMedicationParams {
+            name: "ibuprofen",
+            dose: Some("400mg"),
+            freq: "daily",
+            route: Some("oral"),
+            note: Some("with food"),
+            started: None,
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+
+    let edit = med::edit_medication(
+        &db,
+        &config,
+        med::EditMedicationParams {
+            dose: Some("600mg"),
+            ..edit_params("ibuprofen")
+        },
+    )
+    .unwrap();
+    assert_eq!(edit.before.dose.as_deref(), Some("400mg"));
+    let m = &edit.medication;
+    assert_eq!(m.dose.as_deref(), Some("600mg"));
+    assert_eq!(m.dose_value, Some(600.0));
+    assert_eq!(m.frequency, Frequency::Daily);
+    assert_eq!(m.note.as_deref(), Some("with food"));
+
+    let m = med::edit_medication(
+        &db,
+        &config,
+        med::EditMedicationParams {
+            freq: Some("2x_daily"),
+            ..edit_params("ibuprofen")
+        },
+    )
+    .unwrap()
+    .medication;
+    assert_eq!(m.frequency, Frequency::TwiceDaily);
+    assert_eq!(m.dose.as_deref(), Some("600mg"));
+
+    let m = med::edit_medication(
+        &db,
+        &config,
+        med::EditMedicationParams {
+            route: Some("topical"),
+            ..edit_params("ibuprofen")
+        },
+    )
+    .unwrap()
+    .medication;
+    assert_eq!(m.route.to_string(), "topical");
+    assert_eq!(m.frequency, Frequency::TwiceDaily);
+
+    let m = med::edit_medication(
+        &db,
+        &config,
+        med::EditMedicationParams {
+            note: Some("after meals"),
+            ..edit_params("ibuprofen")
+        },
+    )
+    .unwrap()
+    .medication;
+    assert_eq!(m.note.as_deref(), Some("after meals"));
+    assert_eq!(m.route.to_string(), "topical");
+    assert_eq!(m.dose.as_deref(), Some("600mg"));
+
+    let err = med::edit_medication(&db, &config, edit_params("ibuprofen")).unwrap_err();
+    assert!(err.to_string().contains("nothing to change"));
+    let err = med::edit_medication(
+        &db,
+        &config,
+        med::EditMedicationParams {
+            dose: Some("1mg"),
+            ..edit_params("nope")
+        },
+    )
+    .unwrap_err();
+    assert_eq!(openvital::error::kind_of(&err).exit_code(), 3);
+}
+
+// ---------------------------------------------------------------------------
+// 30. edit_rename_moves_entries
+// ---------------------------------------------------------------------------
+
+#[test]
+fn edit_rename_moves_entries() {
+    let (_dir, db) = common::setup_db();
+    let config = default_config();
+    for (name, interacts_with) in [
+        ("ibuprofn", vec![]),
+        ("aspirin", vec!["ibuprofn:60".to_string()]),
+    ] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq: "daily",
+                route: None,
+                note: None,
+                started: None,
+                interacts_with: &interacts_with,
+            },
+        )
+        .unwrap();
+    }
+    med::take_medication(&db, &config, "ibuprofn", None, None, None, None).unwrap();
+    med::skip_medication(&db, &config, "ibuprofn", None, None).unwrap();
+
+    let rename = |to| med::EditMedicationParams {
+        rename: Some(to),
+        ..edit_params("ibuprofn")
+    };
+    let err = med::edit_medication(&db, &config, rename("aspirin")).unwrap_err();
+    assert_eq!(openvital::error::kind_of(&err).exit_code(), 5);
+
+    let edit = med::edit_medication(&db, &config, rename("ibuprofen")).unwrap();
+    assert_eq!(edit.renamed_entries, Some(2));
+    assert_eq!(edit.medication.name, "ibuprofen");
+    assert!(db.get_medication_by_name_any("ibuprofn").unwrap().is_none());
+    assert_eq!(db.count_metrics_of_type("ibuprofen").unwrap(), 2);
+    let aspirin = db.get_medication_by_name("aspirin").unwrap().unwrap();
+    assert_eq!(aspirin.interactions[0].with, "ibuprofen");
+
+    let s = &med::adherence_status(&db, &config, Some("ibuprofen"), 7).unwrap()[0];
+    assert_eq!(s.taken_today, 1);
+}
@@ -1,6 +1,7 @@
 mod common;
 
 use chrono::Utc;
+use openvital::db::SortOrder;
 use openvital::models::med::{Frequency, Medication, Route};
 
 // ---------------------------------------------------------------------------
@@ -250,7 +251,9 @@ fn existing_metrics_preserved_after_migration() {
     db.insert_medication(&med).unwrap();
 
     // Verify metric still retrievable
-    let stored = db.query_by_type("weight", Some(1)).unwrap();
+    let stored = db
+        .query_by_type("weight", Some(1), SortOrder::Desc)
+        .unwrap();
     assert_eq!(stored.len(), 1);
     assert!((stored[0].value - 80.0).abs() < f64::EPSILON);
 }
@@ -276,3 +279,41 @@ fn get_by_name_any_prefers_active() {
     assert!(got.active);
     assert_eq!(got.frequency, Frequency::TwiceDaily);
 }
+
+// ---------------------------------------------------------------------------
+// Refills — latest refill is attached to the medication and removed with it
+// ---------------------------------------------------------------------------
+
+#[test]
+fn refill_latest_attached_and_removed() {
+    use openvital::models::med::Refill;
+
+    let (_dir, db) = common::setup_db();
+    let med = make_med("metformin", Frequency::TwiceDaily);
+    db.insert_medication(&med).unwrap();
+
+    let got = db.get_medication_by_name("metformin").unwrap().unwrap();
+    assert!(got.refill_quantity.is_none());
+    assert!(got.refill_date.is_none());
+
+    let now = Utc::now();
+    for (quantity, days_ago) in [(30, 40), (60, 10)] {
+        db.insert_refill(&Refill {
+            med_id: med.id.clone(),
+            quantity,
+            days_supply: 30,
+            refill_date: now - chrono::Duration::days(days_ago),
+            created_at: now,
+        })
+        .unwrap();
+    }
+
+    let got = db.get_medication_by_name("metformin").unwrap().unwrap();
+    assert_eq!(got.refill_quantity, Some(60));
+    let latest = db.latest_refill(&med.id).unwrap().unwrap();
+    assert_eq!(latest.quantity, 60);
+    assert_eq!(got.refill_date, Some(latest.refill_date));
+
+    db.remove_medication("metformin").unwrap();
+    assert!(db.latest_refill(&med.id).unwrap().is_none());
+}
@@ -6,6 +6,7 @@ use openvital::core::goal;
 use openvital::core::med::{self, AddMedicationParams};
 use openvital::core::status;
 use openvital::core::trend::{self, TrendPeriod};
+use openvital::db::SortOrder;
 use openvital::models::config::Config;
 use openvital::models::goal::{Direction, Timeframe};
 use openvital::models::metric::{Category, Metric};
@@ -52,6 +53,7 @@ fn trend_medication_uses_sum() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -92,6 +94,7 @@ fn goal_medication_cumulative() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -141,6 +144,7 @@ fn status_includes_medications() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -173,12 +177,13 @@ fn export_default_no_medications() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
 
     // Default export should not include medications key
-    let json_str = export::to_json(&db, None, None, None).unwrap();
+    let json_str = export::to_json(&db, None, None, None, &[]).unwrap();
     let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
     // Default to_json returns an array of metrics, no "medications" key
     assert!(
@@ -206,13 +211,14 @@ fn export_with_medications() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
 
     med::take_medication(&db, &config, "ibuprofen", None, None, None, None).unwrap();
 
-    let json_str = export::to_json_with_medications(&db, None, None, None).unwrap();
+    let json_str = export::to_json_with_medications(&db, None, None, None, &[]).unwrap();
     let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
 
     assert!(
@@ -251,6 +257,7 @@ fn import_auto_detect_new_format() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -260,7 +267,7 @@ fn import_auto_detect_new_format() {
     db2.insert_metric(&m).unwrap();
 
     // Export with medications
-    let json_str = export::to_json_with_medications(&db2, None, None, None).unwrap();
+    let json_str = export::to_json_with_medications(&db2, None, None, None, &[]).unwrap();
 
     // Import into the first DB
     let (metric_count, med_count) = export::import_json_auto(&db, &json_str).unwrap();
@@ -311,6 +318,7 @@ fn goal_medication_monthly_uses_sum() {
         route: None,
         note: None,
         started: None,
+        interacts_with: &[],
     };
     openvital::core::med::add_medication(&db, &config, params).unwrap();
 
@@ -361,6 +369,7 @@ fn name_conflict_existing_metric_unchanged() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -373,7 +382,9 @@ fn name_conflict_existing_metric_unchanged() {
     assert_eq!(med_metric.category, Category::Medication);
 
     // The original water metric should still be Nutrition
-    let all_water = db.query_by_type("water", Some(10)).unwrap();
+    let all_water = db
+        .query_by_type("water", Some(10), SortOrder::Desc)
+        .unwrap();
     let nutrition_waters: Vec<_> = all_water
         .iter()
         .filter(|m| m.category == Category::Nutrition)
@@ -412,6 +423,7 @@ fn correlate_medication_uses_daily_sum() {
         route: None,
         note: None,
         started: None,
+        interacts_with: &[],
     };
     med::add_medication(&db, &config, params).unwrap();
 
@@ -427,8 +439,9 @@ fn correlate_medication_uses_daily_sum() {
         value: 5.0,
         note: None,
         tags: None,
-        source: None,
+        source: openvital::core::logging::Source::Manual,
         date: None,
+        unit: None,
     };
     openvital::core::logging::log_metric(&db, &config, entry).unwrap();
 
@@ -445,8 +458,9 @@ fn correlate_medication_uses_daily_sum() {
             value: 3.0,
             note: None,
             tags: None,
-            source: None,
+            source: openvital::core::logging::Source::Manual,
             date: Some(day),
+            unit: None,
         };
         openvital::core::logging::log_metric(&db, &config, entry).unwrap();
     }
@@ -480,8 +494,9 @@ fn trend_excludes_medication_entries_for_non_med_metric() {
         value: 4.0,
         note: None,
         tags: None,
-        source: None,
+        source: openvital::core::logging::Source::Manual,
         date: None,
+        unit: None,
     };
     openvital::core::logging::log_metric(&db, &config, entry).unwrap();
 
@@ -496,6 +511,7 @@ fn trend_excludes_medication_entries_for_non_med_metric() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -531,8 +547,9 @@ fn goal_excludes_medication_entries_for_non_med_metric() {
         value: 4.0,
         note: None,
         tags: None,
-        source: None,
+        source: openvital::core::logging::Source::Manual,
         date: None,
+        unit: None,
     };
     openvital::core::logging::log_metric(&db, &config, entry).unwrap();
 
@@ -547,6 +564,7 @@ fn goal_excludes_medication_entries_for_non_med_metric() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -590,8 +608,9 @@ fn goal_for_medication_excludes_non_med_entries() {
         value: 4.0,
         note: None,
         tags: None,
-        source: None,
+        source: openvital::core::logging::Source::Manual,
         date: None,
+        unit: None,
     };
     openvital::core::logging::log_metric(&db, &config, entry).unwrap();
 
@@ -606,6 +625,7 @@ fn goal_for_medication_excludes_non_med_entries() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -657,12 +677,13 @@ fn weekly_med_no_takes_shows_not_adherent() {
             route: None,
             note: None,
             started: Some(started),
+            interacts_with: &[],
         },
     )
     .unwrap();
 
     // No takes at all — check adherence
-    let statuses = med::adherence_status(&db, Some("weekly_vitamin"), 7).unwrap();
+    let statuses = med::adherence_status(&db, &config, Some("weekly_vitamin"), 7).unwrap();
     assert_eq!(statuses.len(), 1);
     let s = &statuses[0];
 
@@ -700,6 +721,7 @@ fn weekly_med_one_take_shows_adherent() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -708,7 +730,7 @@ fn weekly_med_one_take_shows_adherent() {
     med::take_medication(&db, &config, "weekly_iron", None, None, None, None).unwrap();
 
     // Check adherence
-    let statuses = med::adherence_status(&db, Some("weekly_iron"), 7).unwrap();
+    let statuses = med::adherence_status(&db, &config, Some("weekly_iron"), 7).unwrap();
     assert_eq!(statuses.len(), 1);
     let s = &statuses[0];
 
@@ -745,6 +767,7 @@ fn weekly_adherence_history_uses_weekly_entries() {
             route: None,
             note: None,
             started: Some(started),
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -753,7 +776,7 @@ fn weekly_adherence_history_uses_weekly_entries() {
     med::take_medication(&db, &config, "weekly_b12", None, None, None, None).unwrap();
 
     // Check single-med adherence with last=14 days
-    let statuses = med::adherence_status(&db, Some("weekly_b12"), 14).unwrap();
+    let statuses = med::adherence_status(&db, &config, Some("weekly_b12"), 14).unwrap();
     assert_eq!(statuses.len(), 1);
     let s = &statuses[0];
 
@@ -802,6 +825,7 @@ fn status_weekly_missed_format_no_zero_slash_zero() {
             route: None,
             note: None,
             started: Some(started),
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -857,8 +881,9 @@ fn correlate_name_conflict_b_side_uses_non_med() {
             value: 5.0 + i as f64,
             note: None,
             tags: None,
-            source: None,
+            source: openvital::core::logging::Source::Manual,
             date: Some(day),
+            unit: None,
         };
         openvital::core::logging::log_metric(&db, &config, entry).unwrap();
     }
@@ -874,6 +899,7 @@ fn correlate_name_conflict_b_side_uses_non_med() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -890,8 +916,9 @@ fn correlate_name_conflict_b_side_uses_non_med() {
             value: 3.0,
             note: None,
             tags: None,
-            source: None,
+            source: openvital::core::logging::Source::Manual,
             date: Some(day),
+            unit: None,
         };
         openvital::core::logging::log_metric(&db, &config, entry).unwrap();
     }
@@ -926,6 +953,7 @@ fn status_overall_adherence_7d_computed_correctly() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -939,6 +967,7 @@ fn status_overall_adherence_7d_computed_correctly() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -952,6 +981,7 @@ fn status_overall_adherence_7d_computed_correctly() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -993,6 +1023,7 @@ fn add_medication_with_started_date() {
         route: None,
         note: None,
         started: Some(past),
+        interacts_with: &[],
     };
     let med = med::add_medication(&db, &config, params).unwrap();
     assert_eq!(
@@ -1012,6 +1043,7 @@ fn add_medication_with_started_date() {
 
 #[test]
 fn med_list_all_header() {
+    use openvital::output::color::ColorConfig;
     use openvital::output::human::format_med_list;
 
     let (_dir, db) = common::setup_db();
@@ -1028,6 +1060,7 @@ fn med_list_all_header() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -1042,6 +1075,7 @@ fn med_list_all_header() {
             route: None,
             note: None,
             started: None,
+            interacts_with: &[],
         },
     )
     .unwrap();
@@ -1051,7 +1085,7 @@ fn med_list_all_header() {
 
     // Format with include_stopped=true → should show "All Medications"
     let all_meds = db.list_medications(true).unwrap();
-    let output_all = format_med_list(&all_meds, true);
+    let output_all = format_med_list(&all_meds, true, &ColorConfig::default());
     assert!(
         output_all.starts_with("All Medications"),
         "include_stopped=true should show 'All Medications' header, got: {}",
@@ -1060,7 +1094,7 @@ fn med_list_all_header() {
 
     // Format with include_stopped=false → should show "Active Medications"
     let active_meds = db.list_medications(false).unwrap();
-    let output_active = format_med_list(&active_meds, false);
+    let output_active = format_med_list(&active_meds, false, &ColorConfig::default());
     assert!(
         output_active.starts_with("Active Medications"),
         "include_stopped=false should show 'Active Medications' header, got: {}",
@@ -1,7 +1,7 @@
 mod common;
 
 use chrono::Utc;
-use openvital::models::config::{Alerts, Config, Profile, Units};
+use openvital::models::config::{Alerts, Config, Profile, Units, offset_timezone, system_timezone};
 use openvital::models::goal::{Direction, Goal, Timeframe};
 use openvital::models::metric::{Category, Metric, default_unit};
 use std::collections::HashMap;
@@ -114,8 +114,10 @@ fn test_default_aliases_completeness() {
 /// resolve_alias returns the mapped value when the alias exists.
 #[test]
 fn test_resolve_alias_known() {
-    let mut cfg = Config::default();
-    cfg.aliases = Config::default_aliases();
+    let cfg = Config {
+        aliases: Config::default_aliases(),
+        ..Default::default()
+    };
     assert_eq!(cfg.resolve_alias("w"), "weight");
     assert_eq!(cfg.resolve_alias("bf"), "body_fat");
     assert_eq!(cfg.resolve_alias("p"), "pain");
@@ -230,6 +232,53 @@ fn test_profile_optional_fields() {
     assert!(p.primary_exercise.is_none());
 }
 
+/// An unset profile timezone is not written and resolves to the system zone.
+#[test]
+fn test_profile_timezone_resolves_at_runtime() {
+    let mut cfg = Config::default();
+    assert!(cfg.profile.timezone.is_none());
+    assert!(!toml::to_string_pretty(&cfg).unwrap().contains("timezone"));
+    assert_eq!(cfg.timezone(), system_timezone());
+
+    cfg.profile.timezone = Some("Asia/Tokyo".to_string());
+    assert_eq!(cfg.timezone(), "Asia/Tokyo");
+    let reloaded: Config = toml::from_str(&toml::to_string_pretty(&cfg).unwrap()).unwrap();
+    assert_eq!(reloaded.profile.timezone.as_deref(), Some("Asia/Tokyo"));
+}
+
+/// A top-level `timezone` from older configs moves under `[profile]`.
+#[test]
+fn test_config_load_moves_legacy_timezone() {
+    with_temp_openvital_home(|| {
+        std::fs::create_dir_all(Config::path().parent().unwrap()).unwrap();
+        std::fs::write(
+            Config::path(),
+            "timezone = \"Europe/Paris\"\n\n[profile]\nheight_cm = 170.0\n",
+        )
+        .unwrap();
+        let cfg = Config::load().unwrap();
+        assert_eq!(cfg.profile.timezone.as_deref(), Some("Europe/Paris"));
+        assert_eq!(cfg.profile.height_cm, Some(170.0));
+    });
+}
+
+/// Without a named system zone, the local offset maps onto an `Etc/GMT` zone.
+#[test]
+fn test_offset_timezone() {
+    assert_eq!(offset_timezone(0), "UTC");
+    assert_eq!(offset_timezone(2 * 3600), "Etc/GMT-2");
+    assert_eq!(offset_timezone(-5 * 3600), "Etc/GMT+5");
+    // No fixed zone for half-hour offsets
+    assert_eq!(offset_timezone(19_800), "UTC");
+    for offset in [-12, -1, 1, 14] {
+        assert!(
+            offset_timezone(offset * 3600)
+                .parse::<chrono_tz::Tz>()
+                .is_ok()
+        );
+    }
+}
+
 // ─── Units system tests ──────────────────────────────────────────────────────
 
 #[test]
@@ -826,3 +875,72 @@ system = "imperial"
     assert_eq!(cfg.units.water, "ml");
     assert_eq!(cfg.units.temperature, "celsius");
 }
+
+// ─── Metric ranges ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_metric_range_parses_all_forms() {
+    use openvital::models::range::MetricRange;
+    let closed = MetricRange {
+        min: Some(7.0),
+        max: Some(9.0),
+    };
+    assert_eq!("7-9".parse::<MetricRange>().unwrap(), closed);
+    assert_eq!(" 7..9 ".parse::<MetricRange>().unwrap(), closed);
+    assert_eq!(
+        ">=7".parse::<MetricRange>().unwrap(),
+        MetricRange {
+            min: Some(7.0),
+            max: None
+        }
+    );
+    assert_eq!(
+        "<=9".parse::<MetricRange>().unwrap(),
+        MetricRange {
+            min: None,
+            max: Some(9.0)
+        }
+    );
+    assert_eq!(
+        "-5-5".parse::<MetricRange>().unwrap(),
+        MetricRange {
+            min: Some(-5.0),
+            max: Some(5.0)
+        }
+    );
+    for bad in ["", "7", "9-7", "a-b", ">=", "7..9..11"] {
+        assert!(bad.parse::<MetricRange>().is_err(), "{}", bad);
+    }
+    assert_eq!(closed.to_string(), "7-9");
+}
+
+#[test]
+fn test_metric_range_bounds_are_inclusive() {
+    use openvital::models::range::RangeStatus;
+    let config = Config {
+        ranges: HashMap::from([
+            ("sleep_hours".to_string(), "7-9".parse().unwrap()),
+            ("body_fat".to_string(), ">=15".parse().unwrap()),
+        ]),
+        ..Config::default()
+    };
+    for (value, expected) in [
+        (6.9, RangeStatus::Below),
+        (7.0, RangeStatus::Within),
+        (9.0, RangeStatus::Within),
+        (9.1, RangeStatus::Above),
+    ] {
+        assert_eq!(
+            config.range_status("sleep_hours", value),
+            Some(expected),
+            "{}",
+            value
+        );
+    }
+    assert_eq!(
+        config.range_status("body_fat", 40.0),
+        Some(RangeStatus::Within)
+    );
+    // No configured range, no status
+    assert_eq!(config.range_status("weight", 80.0), None);
+}
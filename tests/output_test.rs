@@ -6,6 +6,7 @@ use openvital::core::status::{
 };
 use openvital::models::Metric;
 use openvital::models::config::Units;
+use openvital::output::color::ColorConfig;
 use openvital::output::human::{format_metric, format_progress_human, format_status};
 use openvital::output::{error, success};
 use serde_json::json;
@@ -136,16 +137,17 @@ fn test_success_and_error_have_distinct_status() {
     assert_ne!(ok["status"], err["status"]);
 }
 
-/// The envelope always contains exactly the four mandated keys.
+/// The envelope always contains exactly the five mandated keys.
 #[test]
 fn test_envelope_has_required_keys() {
     for envelope in [success("x", json!(1)), error("x", "C", "m")] {
         let obj = envelope.as_object().unwrap();
         assert!(obj.contains_key("status"));
         assert!(obj.contains_key("command"));
+        assert!(obj.contains_key("profile"));
         assert!(obj.contains_key("data"));
         assert!(obj.contains_key("error"));
-        assert_eq!(obj.len(), 4, "envelope must have exactly 4 keys");
+        assert_eq!(obj.len(), 5, "envelope must have exactly 5 keys");
     }
 }
 
@@ -238,6 +240,7 @@ fn test_format_metric_custom_type_empty_unit() {
 
 // ─── format_status tests ─────────────────────────────────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 fn make_status(
     date: NaiveDate,
     logged: Vec<String>,
@@ -256,14 +259,24 @@ fn make_status(
             latest_weight_kg,
             bmi,
             bmi_category,
+            fat_mass_kg: None,
+            lean_mass_kg: None,
+            body_fat_category: None,
         },
         today: TodayStatus {
             logged,
             pain_alerts,
+            blood_pressure: None,
+            glucose: None,
+            ranges: Vec::new(),
+        },
+        streaks: Streaks {
+            logging_days,
+            by_type: vec![],
         },
-        streaks: Streaks { logging_days },
         consecutive_pain_alerts,
         medications: None,
+        resting_hr: None,
     }
 }
 
@@ -272,7 +285,7 @@ fn make_status(
 fn test_format_status_contains_date() {
     let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
     let s = make_status(date, vec![], vec![], 0, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("2026-02-15"), "header should contain date");
 }
 
@@ -281,7 +294,7 @@ fn test_format_status_contains_date() {
 fn test_format_status_no_entries() {
     let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
     let s = make_status(date, vec![], vec![], 0, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("No entries logged today"));
 }
 
@@ -291,12 +304,32 @@ fn test_format_status_logged_types() {
     let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
     let logged = vec!["weight".to_string(), "cardio".to_string()];
     let s = make_status(date, logged, vec![], 0, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("weight"), "should list weight");
     assert!(out.contains("cardio"), "should list cardio");
     assert!(out.contains("Logged today"));
 }
 
+/// format_status shows lean and fat mass, in lbs for imperial units.
+#[test]
+fn test_format_status_body_composition() {
+    let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+    let mut s = make_status(date, vec![], vec![], 0, vec![], None, None, None, None);
+    s.profile.lean_mass_kg = Some(68.2);
+    s.profile.fat_mass_kg = Some(14.3);
+    s.profile.body_fat_category = Some("fitness");
+
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
+    assert!(out.contains("Lean mass: 68.2 kg | Fat mass: 14.3 kg | Body fat: fitness"));
+
+    let imperial = Units {
+        system: "imperial".to_string(),
+        ..Default::default()
+    };
+    let out = format_status(&s, &imperial, &ColorConfig::default());
+    assert!(out.contains("Lean mass: 150.4 lbs | Fat mass: 31.5 lbs"));
+}
+
 /// format_status shows weight and BMI line when both are present.
 #[test]
 fn test_format_status_weight_and_bmi() {
@@ -312,7 +345,7 @@ fn test_format_status_weight_and_bmi() {
         Some(24.5),
         Some("normal"),
     );
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("75"), "should show weight");
     assert!(
         out.contains("24.5") || out.contains("BMI"),
@@ -326,7 +359,7 @@ fn test_format_status_weight_and_bmi() {
 fn test_format_status_no_weight_no_bmi_line() {
     let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
     let s = make_status(date, vec![], vec![], 0, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(!out.contains("BMI"), "BMI line should be absent");
     assert!(!out.contains("kg"), "weight line should be absent");
 }
@@ -347,7 +380,7 @@ fn test_format_status_pain_alerts_shown() {
         None,
         None,
     );
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("Pain alerts"), "should mention pain alerts");
     assert!(out.contains('1'), "should show count of 1 alert");
 }
@@ -357,7 +390,7 @@ fn test_format_status_pain_alerts_shown() {
 fn test_format_status_no_pain_alerts_section() {
     let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
     let s = make_status(date, vec![], vec![], 0, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(!out.contains("Pain alerts"));
 }
 
@@ -366,7 +399,7 @@ fn test_format_status_no_pain_alerts_section() {
 fn test_format_status_streak_shown() {
     let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
     let s = make_status(date, vec![], vec![], 7, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("Logging streak"), "should mention streak");
     assert!(out.contains('7'), "should show streak count");
 }
@@ -376,7 +409,7 @@ fn test_format_status_streak_shown() {
 fn test_format_status_streak_zero_omitted() {
     let date = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
     let s = make_status(date, vec![], vec![], 0, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(
         !out.contains("Logging streak"),
         "streak line should be absent when zero"
@@ -393,7 +426,7 @@ fn test_format_status_consecutive_pain_alert() {
         latest_value: 7.0,
     };
     let s = make_status(date, vec![], vec![], 0, vec![alert], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(
         out.contains("!!"),
         "consecutive pain alert should use '!!' prefix"
@@ -420,7 +453,7 @@ fn test_format_status_multiple_consecutive_pain_alerts() {
         },
     ];
     let s = make_status(date, vec![], vec![], 0, alerts, None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("pain"), "should mention pain");
     assert!(out.contains("soreness"), "should mention soreness");
     // Two '!!' markers expected
@@ -432,7 +465,7 @@ fn test_format_status_multiple_consecutive_pain_alerts() {
 fn test_format_status_starts_with_header() {
     let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
     let s = make_status(date, vec![], vec![], 0, vec![], None, None, None, None);
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(
         out.starts_with("=== OpenVital Status"),
         "should start with header"
@@ -460,7 +493,7 @@ fn test_format_status_full() {
         Some(25.9),
         Some("overweight"),
     );
-    let out = format_status(&s, &Units::default());
+    let out = format_status(&s, &Units::default(), &ColorConfig::default());
     assert!(out.contains("2026-02-18"));
     assert!(out.contains("82"));
     assert!(out.contains("25.9"));
@@ -485,11 +518,32 @@ fn test_format_progress_human_below_at_target() {
         current_value: Some(74.0),
         is_met: true,
         progress: None,
+        start_value: None,
+        percent_complete: None,
+        eta: None,
+        on_track: None,
+        estimated_completion: None,
     };
     let result = format_progress_human(&status, &Units::default());
     assert!(result.contains("at target"));
 }
 
+#[test]
+fn test_format_percent_bar() {
+    assert_eq!(
+        openvital::output::human::format_percent_bar(60.0),
+        "[######----] 60%"
+    );
+    assert_eq!(
+        openvital::output::human::format_percent_bar(100.0),
+        "[##########] 100%"
+    );
+    assert_eq!(
+        openvital::output::human::format_percent_bar(4.9),
+        "[----------] 5%"
+    );
+}
+
 #[test]
 fn test_format_progress_human_above_remaining() {
     let status = openvital::core::goal::GoalStatus {
@@ -501,6 +555,11 @@ fn test_format_progress_human_above_remaining() {
         current_value: Some(1500.0),
         is_met: false,
         progress: None,
+        start_value: None,
+        percent_complete: None,
+        eta: None,
+        on_track: None,
+        estimated_completion: None,
     };
     let result = format_progress_human(&status, &Units::default());
     assert!(result.contains("remaining"));
@@ -517,6 +576,11 @@ fn test_format_progress_human_no_data() {
         current_value: None,
         is_met: false,
         progress: None,
+        start_value: None,
+        percent_complete: None,
+        eta: None,
+        on_track: None,
+        estimated_completion: None,
     };
     let result = format_progress_human(&status, &Units::default());
     assert_eq!(result, "no data");
@@ -559,3 +623,112 @@ fn test_mood_display_shows_slash_format() {
         result
     );
 }
+
+// ─── output::markdown ────────────────────────────────────────────────────────
+
+/// Goals render as a checklist; met goals are ticked.
+#[test]
+fn test_markdown_report_goal_checklist() {
+    use openvital::core::report::{ReportGoal, ReportGoals};
+
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+    let mut report = openvital::core::report::generate(&db, d, d).unwrap();
+    let goal =
+        |metric_type: &str, target_value, direction: &str, current_value, is_met| ReportGoal {
+            metric_type: metric_type.to_string(),
+            target_value,
+            direction: direction.to_string(),
+            timeframe: "daily".to_string(),
+            current_value,
+            is_met,
+            progress: None,
+        };
+    report.goals = Some(ReportGoals {
+        goals: vec![
+            goal("weight", 85.0, "below", Some(82.5), true),
+            goal("water", 2000.0, "above", Some(1800.0), false),
+        ],
+        goals_met: 1,
+        goals_total: 2,
+    });
+
+    let md = openvital::output::markdown::format_report(&report, &Units::default());
+    assert!(md.contains("No data in this period."));
+    assert!(md.contains("### Goals (1/2 met)"));
+    assert!(md.contains("- [x] Weight below 85 kg (daily)\n"));
+    assert!(md.contains("- [ ] Water above 2000 ml (daily) — currently 1800.0 ml"));
+}
+
+#[test]
+fn test_format_goal_track() {
+    use openvital::core::projection::Projection;
+    let projection = |direction: &str, date: Option<NaiveDate>| Projection {
+        target: 75.0,
+        direction: direction.to_string(),
+        estimated_date: date,
+        confidence: Some(0.9),
+    };
+    let date = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let mut status = openvital::core::goal::GoalStatus {
+        id: "test".to_string(),
+        metric_type: "weight".to_string(),
+        target_value: 75.0,
+        direction: "below".to_string(),
+        timeframe: "daily".to_string(),
+        current_value: Some(80.0),
+        is_met: false,
+        progress: None,
+        start_value: None,
+        percent_complete: None,
+        eta: Some(projection("converging", Some(date))),
+        on_track: Some(true),
+        estimated_completion: Some(date),
+    };
+    assert_eq!(
+        openvital::output::human::format_goal_track(&status).unwrap(),
+        "On track — estimated completion: 2026-03-15"
+    );
+
+    status.eta = Some(projection("diverging", None));
+    status.on_track = Some(false);
+    status.estimated_completion = None;
+    assert_eq!(
+        openvital::output::human::format_goal_track(&status).unwrap(),
+        "Not on track — trend moving away from goal"
+    );
+
+    status.eta = Some(projection("insufficient_data", None));
+    status.on_track = None;
+    assert!(openvital::output::human::format_goal_track(&status).is_none());
+}
+
+// ─── color ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_color_config_wraps_only_when_enabled() {
+    let on = ColorConfig { enabled: true };
+    assert_eq!(on.green("MET"), "\x1B[32mMET\x1B[0m");
+    assert_eq!(on.bold("3"), "\x1B[1m3\x1B[0m");
+    assert_eq!(ColorConfig::default().red("..."), "...");
+    assert!(ColorConfig::new("always").enabled);
+    assert!(!ColorConfig::new("never").enabled);
+}
+
+#[test]
+fn test_format_status_colors_pain_alerts() {
+    let date = NaiveDate::from_ymd_opt(2026, 2, 17).unwrap();
+    let alert = ConsecutivePainAlert {
+        metric_type: "pain".to_string(),
+        consecutive_days: 3,
+        latest_value: 7.0,
+    };
+    let s = make_status(date, vec![], vec![], 4, vec![alert], None, None, None, None);
+    let plain = format_status(&s, &Units::default(), &ColorConfig::default());
+    assert!(!plain.contains('\x1B'));
+    assert!(plain.contains("!! pain above threshold"));
+
+    let colored = format_status(&s, &Units::default(), &ColorConfig { enabled: true });
+    assert!(colored.contains("\x1B[1m\x1B[31m!!\x1B[0m\x1B[0m \x1B[31mpain above threshold"));
+    assert!(colored.contains("Logging streak: \x1B[1m4\x1B[0m day(s)"));
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::goal;
+use openvital::core::projection;
+use openvital::core::trend::{self, TrendPeriod};
+use openvital::models::goal::{Direction, Goal, Timeframe};
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 1, d).unwrap()
+}
+
+fn log_series(db: &openvital::db::Database, metric_type: &str, values: &[(u32, f64)]) {
+    for (d, v) in values {
+        db.insert_metric(&common::make_metric(metric_type, *v, day(*d)))
+            .unwrap();
+    }
+}
+
+/// Scenario: Weight dropping 0.5/day toward a "below" target
+#[test]
+fn test_projection_converging_estimates_date() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 80.0), (2, 79.5), (3, 79.0), (4, 78.5)]);
+    let g = Goal::new("weight".into(), 75.0, Direction::Below, Timeframe::Daily);
+
+    let p = projection::project(&db, &g).unwrap();
+
+    assert_eq!(p.direction, "converging");
+    // 78.5 → 75.0 at 0.5/day = 7 days after Jan 4
+    assert_eq!(p.estimated_date, Some(day(11)));
+    assert!((p.confidence.unwrap() - 1.0).abs() < 1e-9);
+}
+
+/// Scenario: Weight rising while the goal is "below"
+#[test]
+fn test_projection_diverging_has_no_date() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 80.0), (2, 81.0), (3, 82.0)]);
+    let g = Goal::new("weight".into(), 75.0, Direction::Below, Timeframe::Daily);
+
+    let p = projection::project(&db, &g).unwrap();
+
+    assert_eq!(p.direction, "diverging");
+    assert!(p.estimated_date.is_none());
+}
+
+/// Scenario: Constant values produce a flat projection
+#[test]
+fn test_projection_flat_slope() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 80.0), (2, 80.0), (3, 80.0)]);
+    let g = Goal::new("weight".into(), 75.0, Direction::Below, Timeframe::Daily);
+
+    let p = projection::project(&db, &g).unwrap();
+
+    assert_eq!(p.direction, "flat");
+    assert!(p.estimated_date.is_none());
+}
+
+/// Scenario: Fewer than 3 days of data
+#[test]
+fn test_projection_insufficient_data() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 80.0), (2, 79.0)]);
+    let g = Goal::new("weight".into(), 75.0, Direction::Below, Timeframe::Daily);
+
+    let p = projection::project(&db, &g).unwrap();
+
+    assert_eq!(p.direction, "insufficient_data");
+    assert!(p.confidence.is_none());
+}
+
+/// Scenario: "equal" goals converge from whichever side the value is on
+#[test]
+fn test_projection_equal_direction_from_below() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "sleep_hours", &[(1, 6.0), (2, 6.5), (3, 7.0)]);
+    let g = Goal::new(
+        "sleep_hours".into(),
+        8.0,
+        Direction::Equal,
+        Timeframe::Daily,
+    );
+
+    let p = projection::project(&db, &g).unwrap();
+
+    assert_eq!(p.direction, "converging");
+    assert_eq!(p.estimated_date, Some(day(5)));
+}
+
+/// Scenario: Already at target
+#[test]
+fn test_projection_met() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 76.0), (2, 75.5), (3, 74.5)]);
+    let g = Goal::new("weight".into(), 75.0, Direction::Below, Timeframe::Daily);
+
+    let p = projection::project(&db, &g).unwrap();
+
+    assert_eq!(p.direction, "met");
+}
+
+/// Scenario: Trend output carries the projection when a goal is active
+#[test]
+fn test_trend_includes_projection_for_active_goal() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 80.0), (2, 79.5), (3, 79.0)]);
+
+    let without = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+    assert!(without.projection.is_none());
+
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        75.0,
+        Direction::Below,
+        Timeframe::Daily,
+    )
+    .unwrap();
+    let with = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+    let p = with.projection.expect("projection should be present");
+    assert_eq!(p.target, 75.0);
+    assert_eq!(p.direction, "converging");
+}
+
+/// Scenario: Goal status exposes the projection as `eta`
+#[test]
+fn test_goal_status_includes_eta() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 80.0), (2, 81.0), (3, 82.0)]);
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        75.0,
+        Direction::Below,
+        Timeframe::Daily,
+    )
+    .unwrap();
+
+    let statuses = goal::goal_status(&db, Some("weight")).unwrap();
+    let eta = statuses[0].eta.as_ref().unwrap();
+    assert_eq!(eta.direction, "diverging");
+}
+
+/// Scenario: Goal status estimates completion from a known slope
+#[test]
+fn test_goal_status_estimated_completion_below() {
+    let (_dir, db) = common::setup_db();
+    // slope −1/day, fitted value 88 on Jan 3: (80 − 88) / −1 = 8 days
+    log_series(&db, "weight", &[(1, 90.0), (2, 89.0), (3, 88.0)]);
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        80.0,
+        Direction::Below,
+        Timeframe::Daily,
+    )
+    .unwrap();
+
+    let statuses = goal::goal_status(&db, Some("weight")).unwrap();
+    assert_eq!(statuses[0].on_track, Some(true));
+    assert_eq!(statuses[0].estimated_completion, Some(day(11)));
+}
+
+/// Scenario: An "above" goal needs a rising slope
+#[test]
+fn test_goal_status_estimated_completion_above() {
+    let (_dir, db) = common::setup_db();
+    // slope +0.5/day, fitted value 61 on Jan 4: (65 − 61) / 0.5 = 8 days
+    log_series(&db, "weight", &[(1, 59.5), (2, 60.0), (3, 60.5), (4, 61.0)]);
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        65.0,
+        Direction::Above,
+        Timeframe::Daily,
+    )
+    .unwrap();
+
+    let statuses = goal::goal_status(&db, Some("weight")).unwrap();
+    assert_eq!(statuses[0].on_track, Some(true));
+    assert_eq!(statuses[0].estimated_completion, Some(day(12)));
+}
+
+/// Scenario: A trend moving away from the goal is not on track
+#[test]
+fn test_goal_status_not_on_track_without_estimate() {
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 61.0), (2, 60.5), (3, 60.0)]);
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        65.0,
+        Direction::Above,
+        Timeframe::Daily,
+    )
+    .unwrap();
+
+    let statuses = goal::goal_status(&db, Some("weight")).unwrap();
+    assert_eq!(statuses[0].on_track, Some(false));
+    assert!(statuses[0].estimated_completion.is_none());
+
+    // Too little data to judge either way
+    let (_dir, db) = common::setup_db();
+    log_series(&db, "weight", &[(1, 61.0), (2, 60.5)]);
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        65.0,
+        Direction::Above,
+        Timeframe::Daily,
+    )
+    .unwrap();
+    let statuses = goal::goal_status(&db, Some("weight")).unwrap();
+    assert!(statuses[0].on_track.is_none());
+}
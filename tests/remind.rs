@@ -0,0 +1,68 @@
+mod common;
+
+use openvital::core::logging::{LogEntry, log_metric};
+use openvital::core::med::{self, AddMedicationParams};
+use openvital::core::remind::{self, ReminderKind};
+use openvital::models::config::Config;
+
+fn add_med(db: &openvital::db::Database, config: &Config, name: &str, freq: &str) {
+    med::add_medication(
+        db,
+        config,
+        AddMedicationParams {
+            name,
+            dose: None,
+            freq,
+            route: None,
+            note: None,
+            started: None,
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_remind_lists_unlogged_metrics_and_owed_doses() {
+    let (_dir, db) = common::setup_db();
+    let mut config = Config::default();
+    config.reminders.daily = vec!["weight".into(), "water".into()];
+
+    add_med(&db, &config, "metformin", "2x_daily");
+    add_med(&db, &config, "ibuprofen", "as_needed");
+    add_med(&db, &config, "vitamin_d", "weekly");
+
+    log_metric(
+        &db,
+        &config,
+        LogEntry {
+            metric_type: "weight",
+            value: 80.0,
+            note: None,
+            tags: None,
+            source: openvital::core::logging::Source::Manual,
+            date: None,
+            unit: None,
+        },
+    )
+    .unwrap();
+    med::take_medication(&db, &config, "metformin", None, None, None, None).unwrap();
+
+    let reminders = remind::check(&db, &config).unwrap();
+    assert_eq!(reminders.len(), 2);
+    assert_eq!(reminders[0].item, "water");
+    assert_eq!(reminders[0].kind, ReminderKind::Metric);
+    assert_eq!(reminders[1].item, "metformin");
+    assert_eq!(reminders[1].kind, ReminderKind::Medication);
+    assert_eq!(reminders[1].missing, 1);
+}
+
+#[test]
+fn test_remind_empty_when_caught_up() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+    add_med(&db, &config, "metformin", "daily");
+    med::take_medication(&db, &config, "metformin", None, None, None, None).unwrap();
+
+    assert!(remind::check(&db, &config).unwrap().is_empty());
+}
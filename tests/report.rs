@@ -1,6 +1,6 @@
 mod common;
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, Weekday};
 use openvital::core::report;
 
 /// Scenario: Generate a weekly report with multiple metric types
@@ -86,3 +86,397 @@ fn test_report_counts_distinct_days() {
     let result = report::generate(&db, from, to).unwrap();
     assert_eq!(result.days_with_entries, 3);
 }
+
+/// Scenario: Report summaries state and apply per-type aggregation
+#[test]
+fn test_report_aggregation_defaults_and_override() {
+    use openvital::models::config::Config;
+    use openvital::models::metric::Aggregation;
+
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+    for v in [1000.0, 1500.0] {
+        db.insert_metric(&common::make_metric("water", v, d))
+            .unwrap();
+    }
+    for v in [80.0, 82.0] {
+        db.insert_metric(&common::make_metric("weight", v, d))
+            .unwrap();
+    }
+
+    let result = report::generate(&db, d, d).unwrap();
+    let water = result
+        .metrics
+        .iter()
+        .find(|m| m.metric_type == "water")
+        .unwrap();
+    assert_eq!(water.aggregation, Aggregation::Sum);
+    assert_eq!(water.value, 2500.0);
+    let weight = result
+        .metrics
+        .iter()
+        .find(|m| m.metric_type == "weight")
+        .unwrap();
+    assert_eq!(weight.aggregation, Aggregation::Avg);
+    assert_eq!(weight.value, 81.0);
+
+    // Config override for one type, then a global override for all types
+    let mut config = Config::default();
+    config.aggregation.insert("weight".into(), Aggregation::Max);
+    let result = report::generate_with(&db, &config, None, d, d, &[]).unwrap();
+    let weight = result
+        .metrics
+        .iter()
+        .find(|m| m.metric_type == "weight")
+        .unwrap();
+    assert_eq!(weight.value, 82.0);
+
+    let result = report::generate_with(&db, &config, Some(Aggregation::Min), d, d, &[]).unwrap();
+    assert!(
+        result
+            .metrics
+            .iter()
+            .all(|m| m.aggregation == Aggregation::Min)
+    );
+}
+
+/// Scenario: The previous period for a week is the seven days before it
+#[test]
+fn test_previous_period_equal_length() {
+    let from = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+    let to = NaiveDate::from_ymd_opt(2026, 1, 11).unwrap();
+    let (pf, pt) = report::previous_period(from, to, false);
+    assert_eq!(pf, NaiveDate::from_ymd_opt(2025, 12, 29).unwrap());
+    assert_eq!(pt, NaiveDate::from_ymd_opt(2026, 1, 4).unwrap());
+}
+
+/// Scenario: Month comparisons use the whole previous calendar month
+#[test]
+fn test_previous_period_calendar_month() {
+    let from = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+    let to = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+    let (pf, pt) = report::previous_period(from, to, true);
+    assert_eq!(pf, NaiveDate::from_ymd_opt(2026, 2, 1).unwrap());
+    assert_eq!(pt, NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+}
+
+/// Scenario: Each quarter covers its three calendar months
+#[test]
+fn test_period_bounds_quarters() {
+    let d = |m, day| NaiveDate::from_ymd_opt(2026, m, day).unwrap();
+    for (reference, from, to) in [
+        (d(2, 14), d(1, 1), d(3, 31)),
+        (d(4, 1), d(4, 1), d(6, 30)),
+        (d(9, 30), d(7, 1), d(9, 30)),
+        (d(11, 5), d(10, 1), d(12, 31)),
+    ] {
+        assert_eq!(
+            report::period_bounds("quarter", reference, None, Weekday::Mon).unwrap(),
+            (from, to)
+        );
+    }
+}
+
+/// Scenario: Year-to-date runs from Jan 1 to the reference date
+#[test]
+fn test_period_bounds_week_follows_week_start() {
+    // Tuesday 10 March 2026
+    let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+    let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+    assert_eq!(
+        report::period_bounds("week", today, None, Weekday::Mon).unwrap(),
+        (day(9), today)
+    );
+    assert_eq!(
+        report::period_bounds("week", today, None, Weekday::Sun).unwrap(),
+        (day(8), today)
+    );
+    // A Sunday starts its own week only under a Sunday start
+    assert_eq!(
+        report::period_bounds("week", day(8), None, Weekday::Sun).unwrap(),
+        (day(8), day(8))
+    );
+    assert_eq!(
+        report::period_bounds("week", day(8), None, Weekday::Mon).unwrap(),
+        (day(2), day(8))
+    );
+}
+
+#[test]
+fn test_period_bounds_ytd() {
+    let today = NaiveDate::from_ymd_opt(2026, 5, 20).unwrap();
+    let (from, to) = report::period_bounds("ytd", today, None, Weekday::Mon).unwrap();
+    assert_eq!(from, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    assert_eq!(to, today);
+}
+
+/// Scenario: --year moves quarter and ytd into a past year
+#[test]
+fn test_period_bounds_year_override() {
+    let today = NaiveDate::from_ymd_opt(2026, 5, 20).unwrap();
+    let d = |m, day| NaiveDate::from_ymd_opt(2025, m, day).unwrap();
+    assert_eq!(
+        report::period_bounds("quarter", today, Some(2025), Weekday::Mon).unwrap(),
+        (d(4, 1), d(6, 30))
+    );
+    assert_eq!(
+        report::period_bounds("ytd", today, Some(2025), Weekday::Mon).unwrap(),
+        (d(1, 1), d(12, 31))
+    );
+    assert!(report::period_bounds("ytd", today, Some(2027), Weekday::Mon).is_err());
+    assert!(report::period_bounds("decade", today, None, Weekday::Mon).is_err());
+}
+
+/// Scenario: Compared report carries deltas and keeps one-sided metrics
+#[test]
+fn test_report_compared_deltas() {
+    let (_dir, db) = common::setup_db();
+    let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+    // Previous week (Jan 1-7): weight avg 82, water 1000/day on 2 days, pain only here
+    for (d, v) in [(2, 82.5), (4, 81.5)] {
+        db.insert_metric(&common::make_metric("weight", v, day(d)))
+            .unwrap();
+    }
+    for d in [3, 5] {
+        db.insert_metric(&common::make_metric("water", 1000.0, day(d)))
+            .unwrap();
+    }
+    db.insert_metric(&common::make_metric("pain", 4.0, day(6)))
+        .unwrap();
+    // Current week (Jan 8-14): weight avg 81, water 3 x 1000, mood only here
+    for (d, v) in [(9, 81.5), (11, 80.5)] {
+        db.insert_metric(&common::make_metric("weight", v, day(d)))
+            .unwrap();
+    }
+    for d in [8, 10, 12] {
+        db.insert_metric(&common::make_metric("water", 1000.0, day(d)))
+            .unwrap();
+    }
+    db.insert_metric(&common::make_metric("mood", 7.0, day(13)))
+        .unwrap();
+
+    let config = openvital::models::config::Config::default();
+    let result =
+        report::generate_compared(&db, &config, None, day(8), day(14), false, &[]).unwrap();
+    let prev = result.compared_to.as_ref().unwrap();
+    assert_eq!(prev.from, day(1));
+    assert_eq!(prev.to, day(7));
+
+    let find = |t: &str| result.metrics.iter().find(|m| m.metric_type == t).unwrap();
+
+    let weight = find("weight").comparison.as_ref().unwrap();
+    assert_eq!(weight.prev_avg, Some(82.0));
+    assert!((weight.delta.unwrap() + 1.0).abs() < 1e-9);
+
+    // water sums by default, so totals are compared: 3000 vs 2000
+    let water = find("water").comparison.as_ref().unwrap();
+    assert_eq!(water.delta, Some(1000.0));
+    assert_eq!(water.delta_pct, Some(50.0));
+
+    let mood = find("mood").comparison.as_ref().unwrap();
+    assert!(mood.delta.is_none() && mood.prev_avg.is_none());
+
+    let pain = find("pain");
+    assert_eq!(pain.count, 0);
+    let pain = pain.comparison.as_ref().unwrap();
+    assert_eq!(pain.prev_avg, Some(4.0));
+    assert!(pain.delta.is_none());
+
+    // The comparison section compares averages, plus totals for water
+    let row = |t: &str| {
+        result
+            .comparison
+            .iter()
+            .find(|c| c.metric_type == t)
+            .unwrap()
+    };
+    assert!((row("weight").delta.unwrap() + 1.0).abs() < 1e-9);
+    assert!(row("weight").current_total.is_none());
+    assert_eq!(row("water").delta, Some(0.0));
+    assert_eq!(row("water").total_delta, Some(1000.0));
+    assert!(row("mood").prev_avg.is_none() && row("mood").delta.is_none());
+    assert!(row("pain").current_avg.is_none());
+}
+
+/// Scenario: HTML report is self-contained with a sparkline per metric
+#[test]
+fn test_report_html_sparklines() {
+    let (_dir, db) = common::setup_db();
+    let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+    for (d, v) in [(5, 85.0), (7, 84.5), (9, 84.0)] {
+        db.insert_metric(&common::make_metric("weight", v, day(d)))
+            .unwrap();
+    }
+    db.insert_metric(&common::make_metric("mood", 7.0, day(6)))
+        .unwrap();
+
+    let config = openvital::models::config::Config::default();
+    let result = report::generate_with(&db, &config, None, day(5), day(11), &[]).unwrap();
+    let html = report::to_html(&db, &config, &result).unwrap();
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("<link"));
+    // weight has three days → a line; mood has one → a dot
+    assert_eq!(html.matches("<polyline").count(), 1);
+    assert_eq!(html.matches("<circle").count(), 1);
+    assert!(html.contains("2026-01-05 to 2026-01-11"));
+}
+
+/// Scenario: Report with tags summarizes only the tagged entries
+#[test]
+fn test_report_filtered_by_tags() {
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    for (value, tags) in [
+        (6.0, vec!["knee"]),
+        (2.0, vec!["back"]),
+        (4.0, vec!["knee"]),
+    ] {
+        let mut m = common::make_metric("pain", value, d);
+        m.tags = tags.into_iter().map(String::from).collect();
+        db.insert_metric(&m).unwrap();
+    }
+
+    let config = openvital::models::config::Config::default();
+    let result = report::generate_with(&db, &config, None, d, d, &["knee".into()]).unwrap();
+    assert_eq!(result.total_entries, 2);
+    assert_eq!(result.tags, vec!["knee"]);
+    let pain = &result.metrics[0];
+    assert_eq!(pain.count, 2);
+    assert!((pain.avg - 5.0).abs() < f64::EPSILON);
+}
+
+/// Scenario: `--goals` attaches every active goal with a met/total summary
+#[test]
+fn test_report_attach_goals() {
+    use openvital::core::goal;
+    use openvital::models::goal::{Direction, Timeframe};
+
+    let (_dir, db) = common::setup_db();
+    let today = openvital::core::time::local_today("UTC");
+    db.insert_metric(&common::make_metric("weight", 82.5, today))
+        .unwrap();
+    db.insert_metric(&common::make_metric("water", 1800.0, today))
+        .unwrap();
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        85.0,
+        Direction::Below,
+        Timeframe::Daily,
+    )
+    .unwrap();
+    goal::set_goal(
+        &db,
+        "water".into(),
+        2000.0,
+        Direction::Above,
+        Timeframe::Daily,
+    )
+    .unwrap();
+
+    let mut result = report::generate(&db, today, today).unwrap();
+    assert!(result.goals.is_none());
+    report::attach_goals(&db, &mut result).unwrap();
+    let goals = result.goals.as_ref().unwrap();
+    assert_eq!(goals.goals_total, 2);
+    assert_eq!(goals.goals_met, 1);
+
+    let units = openvital::models::config::Units::default();
+    let lines: Vec<String> = goals
+        .goals
+        .iter()
+        .map(|g| openvital::output::human::format_report_goal(g, &units))
+        .collect();
+    assert!(lines.contains(&"✓ WEIGHT: 82.5 kg < 85.0 (met)".to_string()));
+    assert!(lines.contains(&"✗ WATER: 1800 ml / 2000 ml (remaining: 200 ml)".to_string()));
+}
+
+/// Scenario: `--goals` with no goals set yields an empty list
+#[test]
+fn test_report_attach_goals_none_set() {
+    let (_dir, db) = common::setup_db();
+    let d = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    let mut result = report::generate(&db, d, d).unwrap();
+    report::attach_goals(&db, &mut result).unwrap();
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["goals"], serde_json::json!([]));
+    assert_eq!(json["goals_met"], 0);
+    assert_eq!(json["goals_total"], 0);
+}
+
+/// Scenario: Medication adherence over the report window, prorated from the start date
+#[test]
+fn test_report_medication_adherence() {
+    use openvital::core::med::{self, AddMedicationParams};
+    use openvital::models::config::Config;
+
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+    let today = openvital::core::time::local_today(db.timezone().name());
+    let ago = |n| today - chrono::Duration::days(n);
+    let from = ago(29);
+
+    // No medications: the section is absent, not empty
+    let result = report::generate_with(&db, &config, None, from, today, &[]).unwrap();
+    assert!(result.medications.is_empty());
+    assert!(
+        serde_json::to_value(&result)
+            .unwrap()
+            .get("medications")
+            .is_none()
+    );
+
+    for (name, freq, started) in [
+        ("metformin", "daily", ago(9)),
+        ("vitamin_d", "daily", ago(60)),
+        ("old_med", "daily", ago(90)),
+    ] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq,
+                route: None,
+                note: None,
+                started: Some(started),
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+    for n in 0..8 {
+        med::take_medication(&db, &config, "metformin", None, None, None, Some(ago(n))).unwrap();
+    }
+    med::take_medication(&db, &config, "vitamin_d", None, None, None, Some(ago(40))).unwrap();
+    med::stop_medication(&db, "vitamin_d", None, Some(ago(20))).unwrap();
+    med::stop_medication(&db, "old_med", None, Some(ago(45))).unwrap();
+
+    let result = report::generate_with(&db, &config, None, from, today, &[]).unwrap();
+    let names: Vec<&str> = result.medications.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["metformin", "vitamin_d"]);
+
+    // Started 9 days ago: 10 doses expected, not 30
+    let metformin = &result.medications[0];
+    assert_eq!(metformin.doses_taken, 8);
+    assert_eq!(metformin.doses_expected, Some(10));
+    assert!((metformin.adherence.unwrap() - 0.8).abs() < 1e-9);
+    assert_eq!(metformin.started, Some(ago(9)));
+    assert_eq!(metformin.stopped, None);
+
+    // Stopped 20 days ago: only the 10 days up to the stop count
+    let vitamin_d = &result.medications[1];
+    assert_eq!(vitamin_d.doses_taken, 0);
+    assert_eq!(vitamin_d.doses_expected, Some(10));
+    assert_eq!(vitamin_d.adherence, Some(0.0));
+    assert_eq!(vitamin_d.started, None);
+    assert_eq!(vitamin_d.stopped, Some(ago(20)));
+
+    let line = openvital::output::human::format_report_medication(metformin);
+    assert!(line.contains("80% adherent, 8/10 doses"), "{}", line);
+    assert!(line.contains(&format!("started {}", ago(9))), "{}", line);
+}
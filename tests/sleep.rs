@@ -0,0 +1,68 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::sleep::{SleepInput, duration_minutes, log_sleep, parse_clock};
+
+fn input<'a>(bed: &'a str, wake: &'a str, quality: Option<f64>) -> SleepInput<'a> {
+    SleepInput {
+        bed,
+        wake,
+        quality,
+        note: None,
+        tags: Some("travel"),
+        date: Some(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()),
+    }
+}
+
+#[test]
+fn test_duration_crosses_midnight_and_rejects_typos() {
+    let minutes =
+        |bed, wake| duration_minutes(parse_clock(bed).unwrap(), parse_clock(wake).unwrap());
+    assert_eq!(minutes("23:30", "07:10").unwrap(), 460);
+    assert_eq!(minutes("1:15", "8:00").unwrap(), 405);
+    assert_eq!(minutes("07:00", "23:00").unwrap(), 16 * 60);
+
+    assert!(minutes("08:00", "07:00").is_err());
+    assert!(minutes("23:00", "23:00").is_err());
+    assert!(parse_clock("25:00").is_err());
+    assert!(parse_clock("7.5").is_err());
+}
+
+#[test]
+fn test_log_sleep_stores_linked_entries() {
+    let (_dir, db) = common::setup_db();
+    let logged = log_sleep(&db, &input("23:30", "07:10", Some(4.0))).unwrap();
+    assert_eq!(logged.minutes, 460);
+
+    let types: Vec<&str> = logged
+        .entries
+        .iter()
+        .map(|m| m.metric_type.as_str())
+        .collect();
+    assert_eq!(
+        types,
+        ["sleep_hours", "bed_time", "wake_time", "sleep_quality"]
+    );
+    assert!((logged.entries[0].value - 460.0 / 60.0).abs() < 1e-9);
+    assert_eq!(logged.entries[1].value, 23.5);
+    assert!((logged.entries[2].value - (7.0 + 10.0 / 60.0)).abs() < 1e-9);
+    assert!(
+        logged
+            .entries
+            .iter()
+            .all(|m| m.timestamp == logged.entries[0].timestamp)
+    );
+    assert!(logged.entries.iter().all(|m| m.tags == ["travel"]));
+    assert_eq!(db.count_metrics_of_type("bed_time").unwrap(), 1);
+}
+
+#[test]
+fn test_log_sleep_rejects_bad_quality_without_logging() {
+    let (_dir, db) = common::setup_db();
+    assert!(log_sleep(&db, &input("23:30", "07:10", Some(6.0))).is_err());
+    assert!(log_sleep(&db, &input("10:00", "07:10", None)).is_err());
+    assert_eq!(db.count_metrics_of_type("sleep_hours").unwrap(), 0);
+
+    let logged = log_sleep(&db, &input("22:00", "06:00", None)).unwrap();
+    assert_eq!(logged.entries.len(), 3);
+}
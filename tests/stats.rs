@@ -0,0 +1,152 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::analytics;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 1, d).unwrap()
+}
+
+/// Scenario: Stats for a single type over a date range
+#[test]
+fn test_stats_single_type_in_range() {
+    let (_dir, db) = common::setup_db();
+    for (d, v) in [(1, 90.0), (5, 80.0), (6, 82.0), (7, 84.0), (20, 70.0)] {
+        db.insert_metric(&common::make_metric("weight", v, day(d)))
+            .unwrap();
+    }
+
+    let results = analytics::stats(&db, Some("weight"), Some(day(5)), Some(day(7)), None).unwrap();
+
+    assert_eq!(results.len(), 1);
+    let s = &results[0].stats;
+    assert_eq!(s.count, 3);
+    assert_eq!(s.mean, 82.0);
+    assert_eq!(s.median, 82.0);
+    assert_eq!(s.min, 80.0);
+    assert_eq!(s.max, 84.0);
+}
+
+/// Scenario: --last keeps only the most recent N entries
+#[test]
+fn test_stats_last_uses_most_recent_entries() {
+    let (_dir, db) = common::setup_db();
+    for (d, v) in [(1, 10.0), (2, 20.0), (3, 30.0), (4, 40.0)] {
+        db.insert_metric(&common::make_metric("pain", v, day(d)))
+            .unwrap();
+    }
+
+    let results = analytics::stats(&db, Some("pain"), None, None, Some(2)).unwrap();
+
+    let s = &results[0].stats;
+    assert_eq!(s.count, 2);
+    assert_eq!(s.min, 30.0);
+    assert_eq!(s.max, 40.0);
+}
+
+/// Scenario: Omitting the type returns stats for every type present
+#[test]
+fn test_stats_all_types() {
+    let (_dir, db) = common::setup_db();
+    db.insert_metric(&common::make_metric("weight", 80.0, day(1)))
+        .unwrap();
+    db.insert_metric(&common::make_metric("water", 1500.0, day(1)))
+        .unwrap();
+    db.insert_metric(&common::make_metric("water", 2500.0, day(2)))
+        .unwrap();
+
+    let results = analytics::stats(&db, None, None, None, None).unwrap();
+
+    let types: Vec<&str> = results.iter().map(|r| r.metric_type.as_str()).collect();
+    assert_eq!(types, vec!["water", "weight"]);
+    assert_eq!(results[0].stats.count, 2);
+    assert_eq!(results[0].stats.median, 2000.0);
+}
+
+/// Scenario: A requested type with no data yields an empty stats block
+#[test]
+fn test_stats_unknown_type_is_empty() {
+    let (_dir, db) = common::setup_db();
+
+    let results = analytics::stats(&db, Some("sleep_hours"), None, None, None).unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].metric_type, "sleep_hours");
+    assert_eq!(results[0].stats.count, 0);
+}
+
+#[test]
+fn test_describe_values_tolerates_nan() {
+    // Imported rows don't get log's finite check; sorting must not panic
+    let stats = analytics::describe_values(vec![3.0, f64::NAN, 1.0, 2.0]);
+    assert_eq!(stats.count, 4);
+    assert_eq!(stats.min, 1.0);
+    let checks = analytics::classify_iqr(&[1.0, f64::NAN, 2.0, 3.0], 1.5);
+    assert_eq!(checks.len(), 4);
+}
+
+#[test]
+fn test_closest_metric_type_suggests_common_typos() {
+    let known: Vec<String> = openvital::models::metric::BUILTIN_TYPES
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+    for (typo, expected, distance) in [
+        ("wheight", "weight", 1),
+        ("weigth", "weight", 2),
+        ("sleep_hour", "sleep_hours", 1),
+        ("stpes", "steps", 2),
+        ("weight", "weight", 0),
+    ] {
+        assert_eq!(
+            analytics::closest_metric_type(typo, &known),
+            Some((expected.to_string(), distance)),
+            "{}",
+            typo
+        );
+    }
+    assert_eq!(analytics::levenshtein("kitten", "sitting"), 3);
+    // Three edits away is not a typo
+    assert_eq!(analytics::closest_metric_type("wgt", &known), None);
+    assert_eq!(analytics::closest_metric_type("hrv", &known), None);
+}
+
+/// Scenario: Each weight entry gets its BMI at the configured height
+#[test]
+fn test_enrich_with_bmi_per_entry() {
+    let entries = [
+        common::make_metric("weight", 81.0, day(1)),
+        common::make_metric("weight", 72.9, day(2)),
+    ];
+    let rows = analytics::enrich_with_bmi(&entries, 180.0);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].bmi, 25.0);
+    assert_eq!(rows[1].bmi, 22.5);
+    assert_eq!(rows[1].metric.value, 72.9);
+}
+
+/// Scenario: WHO categories switch exactly at 18.5, 25 and 30
+#[test]
+fn test_enrich_with_bmi_category_boundaries() {
+    // At 100 cm the BMI equals the weight
+    let weights = [18.4, 18.5, 24.9, 25.0, 29.9, 30.0];
+    let entries: Vec<_> = weights
+        .iter()
+        .map(|w| common::make_metric("weight", *w, day(1)))
+        .collect();
+    let categories: Vec<_> = analytics::enrich_with_bmi(&entries, 100.0)
+        .iter()
+        .map(|r| r.bmi_category)
+        .collect();
+    assert_eq!(
+        categories,
+        [
+            "underweight",
+            "normal",
+            "normal",
+            "overweight",
+            "overweight",
+            "obese"
+        ]
+    );
+}
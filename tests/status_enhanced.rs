@@ -113,9 +113,11 @@ fn test_compute_bmi_with_height_and_weight() {
     let weight = make_metric_today("weight", 75.0);
     db.insert_metric(&weight).unwrap();
 
-    let mut config = Config::default();
-    config.profile = Profile {
-        height_cm: Some(180.0),
+    let config = Config {
+        profile: Profile {
+            height_cm: Some(180.0),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -142,9 +144,11 @@ fn test_compute_bmi_with_height_and_weight() {
 fn test_compute_no_bmi_without_weight() {
     let (_dir, db) = common::setup_db();
 
-    let mut config = Config::default();
-    config.profile = Profile {
-        height_cm: Some(175.0),
+    let config = Config {
+        profile: Profile {
+            height_cm: Some(175.0),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -328,9 +332,11 @@ fn test_compute_bmi_categories() {
         let (_dir, db) = common::setup_db();
         let weight = make_metric_today("weight", 55.0);
         db.insert_metric(&weight).unwrap();
-        let mut config = Config::default();
-        config.profile = Profile {
-            height_cm: Some(180.0),
+        let config = Config {
+            profile: Profile {
+                height_cm: Some(180.0),
+                ..Default::default()
+            },
             ..Default::default()
         };
         let status = openvital::core::status::compute(&db, &config).unwrap();
@@ -346,9 +352,11 @@ fn test_compute_bmi_categories() {
         let (_dir, db) = common::setup_db();
         let weight = make_metric_today("weight", 85.0);
         db.insert_metric(&weight).unwrap();
-        let mut config = Config::default();
-        config.profile = Profile {
-            height_cm: Some(180.0),
+        let config = Config {
+            profile: Profile {
+                height_cm: Some(180.0),
+                ..Default::default()
+            },
             ..Default::default()
         };
         let status = openvital::core::status::compute(&db, &config).unwrap();
@@ -364,9 +372,11 @@ fn test_compute_bmi_categories() {
         let (_dir, db) = common::setup_db();
         let weight = make_metric_today("weight", 100.0);
         db.insert_metric(&weight).unwrap();
-        let mut config = Config::default();
-        config.profile = Profile {
-            height_cm: Some(170.0),
+        let config = Config {
+            profile: Profile {
+                height_cm: Some(170.0),
+                ..Default::default()
+            },
             ..Default::default()
         };
         let status = openvital::core::status::compute(&db, &config).unwrap();
@@ -439,6 +449,7 @@ fn test_pain_custom_alert_config() {
     let alerts_config = Alerts {
         pain_threshold: 7,
         pain_consecutive_days: 2,
+        ..Alerts::default()
     };
     let alerts =
         openvital::core::status::check_consecutive_pain(&db, today, &alerts_config).unwrap();
@@ -476,9 +487,11 @@ fn test_compute_uses_latest_weight_for_bmi() {
     let recent_weight = make_metric_today("weight", 75.0);
     db.insert_metric(&recent_weight).unwrap();
 
-    let mut config = Config::default();
-    config.profile = Profile {
-        height_cm: Some(180.0),
+    let config = Config {
+        profile: Profile {
+            height_cm: Some(180.0),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -510,6 +523,9 @@ fn test_status_human_deduplicates_logged_today() {
             latest_weight_kg: None,
             bmi: None,
             bmi_category: None,
+            fat_mass_kg: None,
+            lean_mass_kg: None,
+            body_fat_category: None,
         },
         today: TodayStatus {
             logged: vec![
@@ -521,15 +537,23 @@ fn test_status_human_deduplicates_logged_today() {
                 "sleep".into(),
             ],
             pain_alerts: vec![],
+            blood_pressure: None,
+            glucose: None,
+            ranges: Vec::new(),
+        },
+        streaks: Streaks {
+            logging_days: 1,
+            by_type: vec![],
         },
-        streaks: Streaks { logging_days: 1 },
         consecutive_pain_alerts: vec![],
         medications: None,
+        resting_hr: None,
     };
 
     let output = openvital::output::human::format_status(
         &status,
         &openvital::models::config::Units::default(),
+        &openvital::output::color::ColorConfig::default(),
     );
     assert!(
         output.contains("water(3)"),
@@ -584,3 +608,333 @@ fn test_pain_consecutive_uses_local_date_bucketing() {
     );
     assert_eq!(alerts[0].consecutive_days, 3);
 }
+
+/// Scenario: Daily goal streak counts consecutive days the goal was met and keeps the best run
+#[test]
+fn test_type_streak_daily_goal_reports_best() {
+    use openvital::core::status::{StreakKind, compute_type_streaks};
+    use openvital::models::goal::{Direction, Timeframe};
+
+    let (_dir, db) = common::setup_db();
+    let today = openvital::core::time::local_today("UTC");
+    openvital::core::goal::set_goal(
+        &db,
+        "water".into(),
+        2000.0,
+        Direction::Above,
+        Timeframe::Daily,
+    )
+    .unwrap();
+    // Met on days -9..-5 (5 days), missed on -4, met on -3..-1; today not logged yet
+    for i in 1..=9 {
+        let date = today - chrono::Duration::days(i);
+        let total = if i == 4 { 1500.0 } else { 2500.0 };
+        // Two entries per day: water goals compare the daily total
+        db.insert_metric(&common::make_metric("water", total / 2.0, date))
+            .unwrap();
+        db.insert_metric(&common::make_metric("water", total / 2.0, date))
+            .unwrap();
+    }
+
+    let streaks = compute_type_streaks(&db, &Config::default(), today).unwrap();
+    assert_eq!(streaks.len(), 1);
+    let s = &streaks[0];
+    assert_eq!(s.metric_type, "water");
+    assert_eq!(s.kind, StreakKind::Goal);
+    assert_eq!(s.current_days, 3);
+    assert_eq!(s.best_days, 5);
+}
+
+/// Scenario: Tracked types get logging streaks that reset after a gap
+#[test]
+fn test_type_streak_tracked_logging() {
+    use openvital::core::status::{StreakKind, compute_type_streaks};
+
+    let (_dir, db) = common::setup_db();
+    let today = openvital::core::time::local_today("UTC");
+    for i in [0, 1, 2, 5, 6] {
+        let date = today - chrono::Duration::days(i);
+        db.insert_metric(&common::make_metric("cardio", 30.0, date))
+            .unwrap();
+    }
+    db.insert_metric(&common::make_metric(
+        "strength",
+        20.0,
+        today - chrono::Duration::days(3),
+    ))
+    .unwrap();
+
+    let mut config = Config::default();
+    config.streaks.track = vec!["cardio".into(), "strength".into()];
+    let streaks = compute_type_streaks(&db, &config, today).unwrap();
+
+    assert_eq!(streaks[0].metric_type, "cardio");
+    assert_eq!(streaks[0].kind, StreakKind::Logging);
+    assert_eq!(streaks[0].current_days, 3);
+    assert_eq!(streaks[0].best_days, 3);
+    assert_eq!(streaks[1].metric_type, "strength");
+    assert_eq!(streaks[1].current_days, 0);
+    assert_eq!(streaks[1].best_days, 1);
+}
+
+/// Scenario: Status for a past day reflects that day's entries, weight, and streak
+#[test]
+fn test_status_compute_on_past_date() {
+    let (_dir, db) = common::setup_db();
+    let today = openvital::core::time::local_today("UTC");
+    let past = today - chrono::Duration::days(5);
+    for i in 0..3 {
+        db.insert_metric(&common::make_metric(
+            "weight",
+            80.0 + i as f64,
+            past - chrono::Duration::days(i),
+        ))
+        .unwrap();
+    }
+    db.insert_metric(&common::make_metric("weight", 70.0, today))
+        .unwrap();
+    db.insert_metric(&common::make_metric("pain", 8.0, past))
+        .unwrap();
+
+    let config = Config::default();
+    let status = openvital::core::status::compute_on(&db, &config, past).unwrap();
+
+    assert_eq!(status.date, past);
+    assert!(status.today.logged.contains(&"pain".to_string()));
+    assert_eq!(status.today.pain_alerts.len(), 1);
+    assert_eq!(status.profile.latest_weight_kg, Some(80.0));
+    assert_eq!(status.streaks.logging_days, 3);
+}
+
+/// Scenario: Status for a future date is rejected
+#[test]
+fn test_status_compute_on_future_date_errors() {
+    let (_dir, db) = common::setup_db();
+    let tomorrow = openvital::core::time::local_today("UTC") + chrono::Duration::days(1);
+    let err = openvital::core::status::compute_on(&db, &Config::default(), tomorrow)
+        .err()
+        .expect("future date should be rejected");
+    assert!(err.to_string().contains("future"));
+}
+
+/// Scenario: As-needed medications never show up as missed
+#[test]
+fn test_status_prn_medications_not_missed() {
+    use openvital::core::med::{self, AddMedicationParams};
+
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+    for (name, freq) in [("ibuprofen", "as_needed"), ("metformin", "daily")] {
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name,
+                dose: None,
+                freq,
+                route: None,
+                note: None,
+                started: None,
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+    }
+
+    let status = openvital::core::status::compute(&db, &config).unwrap();
+    let meds = status.medications.unwrap();
+    assert_eq!(meds.as_needed, 1);
+    assert_eq!(meds.missed.len(), 1);
+    assert!(meds.missed[0].starts_with("metformin"));
+}
+
+// ── resting heart rate ──────────────────────────────────────────────────────
+
+/// Log a resting HR of `base` for the 7 days before today and `latest` today.
+fn setup_resting_hr(latest: f64, base: f64) -> (tempfile::TempDir, openvital::db::Database) {
+    let (dir, db) = common::setup_db();
+    let today = chrono::Local::now().date_naive();
+    for i in 1..=7 {
+        let date = today - chrono::Duration::days(i);
+        db.insert_metric(&common::make_metric("resting_heart_rate", base, date))
+            .unwrap();
+    }
+    db.insert_metric(&common::make_metric("resting_heart_rate", latest, today))
+        .unwrap();
+    (dir, db)
+}
+
+#[test]
+fn test_resting_hr_has_bpm_unit_and_body_category() {
+    use openvital::models::metric::{Category, default_unit};
+    for t in ["heart_rate", "resting_heart_rate"] {
+        assert_eq!(default_unit(t), "bpm");
+        assert_eq!(Category::from_type(t), Category::Body);
+    }
+}
+
+#[test]
+fn test_resting_hr_none_without_entries() {
+    let (_dir, db) = common::setup_db();
+    let today = chrono::Local::now().date_naive();
+    let hr = openvital::core::status::resting_hr_status(&db, today, &Alerts::default()).unwrap();
+    assert!(hr.is_none());
+}
+
+#[test]
+fn test_resting_hr_change_at_threshold_does_not_alert() {
+    // 55 vs 50 is exactly +10%, the default threshold
+    let (_dir, db) = setup_resting_hr(55.0, 50.0);
+    let today = chrono::Local::now().date_naive();
+    let hr = openvital::core::status::resting_hr_status(&db, today, &Alerts::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(hr.latest, 55.0);
+    assert_eq!(hr.week_change_pct, Some(10.0));
+    assert_eq!(hr.trend, "up");
+    assert!(!hr.alert);
+}
+
+#[test]
+fn test_resting_hr_change_above_threshold_alerts() {
+    let (_dir, db) = setup_resting_hr(55.5, 50.0);
+    let today = chrono::Local::now().date_naive();
+    let hr = openvital::core::status::resting_hr_status(&db, today, &Alerts::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(hr.week_change_pct, Some(11.0));
+    assert!(hr.alert);
+
+    // A higher configured threshold silences it
+    let alerts = Alerts {
+        resting_hr_change_pct: 12.0,
+        ..Alerts::default()
+    };
+    let hr = openvital::core::status::resting_hr_status(&db, today, &alerts)
+        .unwrap()
+        .unwrap();
+    assert!(!hr.alert);
+}
+
+#[test]
+fn test_resting_hr_in_status_and_human_output() {
+    let (_dir, db) = setup_resting_hr(48.0, 52.0);
+    let config = Config::default();
+    let status = openvital::core::status::compute(&db, &config).unwrap();
+    let hr = status.resting_hr.as_ref().unwrap();
+    assert_eq!(hr.trend, "down");
+    assert!(!hr.alert);
+
+    let out = openvital::output::human::format_status(
+        &status,
+        &config.units,
+        &openvital::output::color::ColorConfig::default(),
+    );
+    assert!(
+        out.contains("Resting HR: 48 bpm ↓ (30d avg 51.5)"),
+        "{}",
+        out
+    );
+}
+
+#[test]
+fn test_range_checks_use_daily_aggregate_and_skip_unranged_types() {
+    use openvital::core::status::range_checks;
+    use openvital::models::range::RangeStatus;
+    let day = chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    let config = Config {
+        ranges: std::collections::HashMap::from([
+            ("water".to_string(), "2000-3000".parse().unwrap()),
+            ("sleep_hours".to_string(), "7-9".parse().unwrap()),
+        ]),
+        ..Config::default()
+    };
+    let entries = vec![
+        common::make_metric("water", 1500.0, day),
+        common::make_metric("water", 500.0, day),
+        common::make_metric("sleep_hours", 9.0, day),
+        common::make_metric("weight", 80.0, day),
+    ];
+    let checks = range_checks(&entries, &config);
+    assert_eq!(checks.len(), 2);
+    assert_eq!(checks[0].metric_type, "sleep_hours");
+    assert_eq!(checks[0].range_status, RangeStatus::Within);
+    // Water is summed: exactly the lower bound
+    assert_eq!(checks[1].metric_type, "water");
+    assert_eq!(checks[1].value, 2000.0);
+    assert_eq!(checks[1].range_status, RangeStatus::Within);
+}
+
+/// Scenario: fat and lean mass follow from the latest weight and body fat
+#[test]
+fn test_body_composition_from_weight_and_body_fat() {
+    use openvital::core::status::body_composition;
+
+    assert_eq!(
+        body_composition(Some(82.5), Some(17.3)),
+        (Some(14.3), Some(68.2))
+    );
+    assert_eq!(body_composition(Some(82.5), None), (None, None));
+    assert_eq!(body_composition(None, Some(17.3)), (None, None));
+}
+
+/// Scenario: body fat categories switch at the ACE boundaries per gender
+#[test]
+fn test_body_fat_category_boundaries() {
+    use openvital::core::status::body_fat_category;
+
+    let male = [
+        (5.9, "essential"),
+        (6.0, "athlete"),
+        (13.9, "athlete"),
+        (14.0, "fitness"),
+        (18.0, "acceptable"),
+        (24.9, "acceptable"),
+        (25.0, "obese"),
+    ];
+    for (pct, expected) in male {
+        assert_eq!(
+            body_fat_category(Some("male"), pct),
+            Some(expected),
+            "{pct}"
+        );
+    }
+    let female = [
+        (13.9, "essential"),
+        (14.0, "athlete"),
+        (21.0, "fitness"),
+        (25.0, "acceptable"),
+        (31.9, "acceptable"),
+        (32.0, "obese"),
+    ];
+    for (pct, expected) in female {
+        assert_eq!(body_fat_category(Some("F"), pct), Some(expected), "{pct}");
+    }
+    assert_eq!(body_fat_category(None, 20.0), None);
+    assert_eq!(body_fat_category(Some("other"), 20.0), None);
+}
+
+/// Scenario: compute() fills body composition into the profile status
+#[test]
+fn test_compute_reports_body_composition() {
+    let (_dir, db) = common::setup_db();
+    db.insert_metric(&make_metric_today("weight", 80.0))
+        .unwrap();
+    db.insert_metric(&make_metric_today("body_fat", 20.0))
+        .unwrap();
+
+    let config = Config {
+        profile: Profile {
+            gender: Some("female".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let status = openvital::core::status::compute(&db, &config).unwrap();
+
+    assert_eq!(status.profile.fat_mass_kg, Some(16.0));
+    assert_eq!(status.profile.lean_mass_kg, Some(64.0));
+    assert_eq!(status.profile.body_fat_category, Some("athlete"));
+    let json = serde_json::to_value(&status).unwrap();
+    assert_eq!(json["profile"]["lean_mass_kg"], 64.0);
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use openvital::core::sync::{
+    Credentials, HttpClient, Method, Request, check, pull, push, remote_url,
+};
+use openvital::db::Database;
+use openvital::models::config::SyncConfig;
+
+/// Method, URL, credentials and body size of a request.
+type Sent = (Method, String, Credentials, Option<u64>);
+
+/// Records requests and answers with a fixed status; a GET copies `serve`
+/// to the destination.
+struct MockClient {
+    status: u16,
+    serve: Option<PathBuf>,
+    sent: RefCell<Vec<Sent>>,
+}
+
+impl MockClient {
+    fn new(status: u16) -> Self {
+        Self {
+            status,
+            serve: None,
+            sent: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl HttpClient for MockClient {
+    fn send(&self, req: &Request) -> Result<u16> {
+        let body_len = match &req.body {
+            Some(p) => Some(std::fs::metadata(p)?.len()),
+            None => None,
+        };
+        if let (Some(dest), Some(src)) = (&req.dest, &self.serve) {
+            std::fs::copy(src, dest)?;
+        }
+        self.sent.borrow_mut().push((
+            req.method,
+            req.url.clone(),
+            req.credentials.clone(),
+            body_len,
+        ));
+        Ok(self.status)
+    }
+}
+
+/// Each test uses its own variable so parallel tests don't race on it.
+fn sync_config(password_env: &str) -> SyncConfig {
+    unsafe { std::env::set_var(password_env, "secret") };
+    SyncConfig {
+        enabled: true,
+        url: "https://dav.example.com/health/".to_string(),
+        username: "user".to_string(),
+        password_env: password_env.to_string(),
+        ..SyncConfig::default()
+    }
+}
+
+#[test]
+fn test_remote_url_construction() {
+    let mut config = SyncConfig::default();
+    assert!(
+        remote_url(&config)
+            .unwrap_err()
+            .to_string()
+            .contains("sync.url is not set")
+    );
+
+    config.url = "https://dav.example.com/health/".to_string();
+    assert_eq!(
+        remote_url(&config).unwrap(),
+        "https://dav.example.com/health/data.db"
+    );
+    config.url = "https://dav.example.com".to_string();
+    assert_eq!(
+        remote_url(&config).unwrap(),
+        "https://dav.example.com/data.db"
+    );
+
+    config.url = "ftp://dav.example.com".to_string();
+    assert!(remote_url(&config).is_err());
+}
+
+#[test]
+fn test_push_sends_put_with_basic_auth() {
+    let (dir, db) = common::setup_db();
+    db.insert_metric(&common::make_metric(
+        "weight",
+        80.0,
+        NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+    ))
+    .unwrap();
+    let db_path = dir.path().join("test.db");
+    let config = sync_config("OPENVITAL_SYNC_TEST_PUSH");
+    let client = MockClient::new(201);
+
+    let result = push(&client, &db, &db_path, &config).unwrap();
+    assert_eq!(result.direction, "upload");
+    assert_eq!(result.remote_url, "https://dav.example.com/health/data.db");
+    assert!(result.bytes_transferred > 0);
+
+    let sent = client.sent.borrow();
+    assert_eq!(sent.len(), 1);
+    let (method, url, credentials, body_len) = &sent[0];
+    assert_eq!(*method, Method::Put);
+    assert_eq!(url, "https://dav.example.com/health/data.db");
+    assert_eq!(
+        *credentials,
+        Credentials {
+            username: "user".to_string(),
+            password: "secret".to_string(),
+        }
+    );
+    assert_eq!(*body_len, Some(result.bytes_transferred));
+    // The staged copy is cleaned up
+    assert!(!dir.path().join("test.db.sync").exists());
+}
+
+#[test]
+fn test_push_errors() {
+    let (dir, db) = common::setup_db();
+    let db_path = dir.path().join("test.db");
+
+    let mut config = sync_config("OPENVITAL_SYNC_TEST_ERRORS");
+    let err = push(&MockClient::new(401), &db, &db_path, &config).unwrap_err();
+    assert!(err.to_string().contains("authentication failed"));
+    let err = push(&MockClient::new(507), &db, &db_path, &config).unwrap_err();
+    assert_eq!(openvital::error::kind_of(&err).exit_code(), 4);
+
+    config.password_env = "OPENVITAL_SYNC_TEST_UNSET".to_string();
+    let client = MockClient::new(201);
+    let err = push(&client, &db, &db_path, &config).unwrap_err();
+    assert!(err.to_string().contains("$OPENVITAL_SYNC_TEST_UNSET"));
+
+    config.enabled = false;
+    let err = push(&client, &db, &db_path, &config).unwrap_err();
+    assert!(err.to_string().contains("sync is disabled"));
+    assert!(client.sent.borrow().is_empty());
+}
+
+#[test]
+fn test_pull_restores_remote_copy() {
+    let (remote_dir, remote_db) = common::setup_db();
+    remote_db
+        .insert_metric(&common::make_metric(
+            "weight",
+            72.0,
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+        ))
+        .unwrap();
+    let served = remote_dir.path().join("served.db");
+    remote_db.backup_to(&served).unwrap();
+
+    let (dir, db) = common::setup_db();
+    drop(db);
+    let db_path = dir.path().join("test.db");
+    let config = sync_config("OPENVITAL_SYNC_TEST_PULL");
+    let mut client = MockClient::new(200);
+    client.serve = Some(served);
+
+    let result = pull(&client, &db_path, None, &config).unwrap();
+    assert_eq!(result.direction, "download");
+    assert!(result.bytes_transferred > 0);
+    assert!(result.previous.is_some());
+    assert_eq!(client.sent.borrow()[0].0, Method::Get);
+
+    let db = Database::open(&db_path).unwrap();
+    assert_eq!(db.count_metrics_of_type("weight").unwrap(), 1);
+
+    let err = pull(&MockClient::new(404), &db_path, None, &config).unwrap_err();
+    assert_eq!(openvital::error::kind_of(&err).exit_code(), 3);
+}
+
+#[test]
+fn test_check_uses_head() {
+    let config = sync_config("OPENVITAL_SYNC_TEST_CHECK");
+    let client = MockClient::new(404);
+    let status = check(&client, &config).unwrap();
+    assert!(status.reachable);
+    assert!(!status.remote_exists);
+    assert_eq!(client.sent.borrow()[0].0, Method::Head);
+
+    let status = check(&MockClient::new(401), &config).unwrap();
+    assert!(!status.reachable);
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use openvital::core::logging::{self, LogEntry};
+use openvital::core::trend::{self, TrendPeriod};
+use openvital::core::{goal, med, report, status, time};
+use openvital::db::{Database, SortOrder};
+use openvital::models::config::Config;
+use openvital::models::goal::{Direction, Timeframe};
+use openvital::models::metric::{Category, Metric};
+use tempfile::TempDir;
+
+fn setup_db_in(tz: &str) -> (TempDir, Database) {
+    let dir = TempDir::new().unwrap();
+    let db = Database::open_with_timezone(&dir.path().join("test.db"), tz).unwrap();
+    (dir, db)
+}
+
+fn metric_at(metric_type: &str, value: f64, h: u32, d: u32) -> Metric {
+    let mut m = Metric::new(metric_type.to_string(), value);
+    m.timestamp = Utc.with_ymd_and_hms(2026, 1, d, h, 0, 0).unwrap();
+    m
+}
+
+/// Scenario: An entry at 01:00 UTC belongs to the previous day in New York
+#[test]
+fn test_entry_at_utc_0100_is_previous_day_in_new_york() {
+    let (_dir, db) = setup_db_in("America/New_York");
+    db.insert_metric(&metric_at("weight", 80.0, 1, 15)).unwrap();
+
+    let jan14 = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    let jan15 = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    assert_eq!(db.query_by_date(jan14, SortOrder::Asc).unwrap().len(), 1);
+    assert!(db.query_by_date(jan15, SortOrder::Asc).unwrap().is_empty());
+}
+
+/// Scenario: The same entry stays on its UTC day with the default timezone
+#[test]
+fn test_entry_at_utc_0100_is_same_day_in_utc() {
+    let (_dir, db) = common::setup_db();
+    db.insert_metric(&metric_at("weight", 80.0, 1, 15)).unwrap();
+
+    let jan15 = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    assert_eq!(db.query_by_date(jan15, SortOrder::Asc).unwrap().len(), 1);
+}
+
+/// Scenario: Distinct entry dates are reported as local dates
+#[test]
+fn test_distinct_entry_dates_use_local_timezone() {
+    let (_dir, db) = setup_db_in("America/New_York");
+    db.insert_metric(&metric_at("weight", 80.0, 1, 15)).unwrap();
+    db.insert_metric(&metric_at("weight", 80.0, 3, 15)).unwrap();
+
+    let from = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+    let to = NaiveDate::from_ymd_opt(2026, 1, 20).unwrap();
+    let dates = db.distinct_entry_dates(from, to).unwrap();
+    assert_eq!(dates, vec!["2026-01-14".to_string()]);
+}
+
+/// Scenario: Report day counting follows the configured timezone
+#[test]
+fn test_report_counts_local_days() {
+    let (_dir, db) = setup_db_in("America/New_York");
+    // 23:00 local on Jan 14 and 09:00 local on Jan 15
+    db.insert_metric(&metric_at("weight", 80.0, 4, 15)).unwrap();
+    db.insert_metric(&metric_at("weight", 79.5, 14, 15))
+        .unwrap();
+
+    let from = NaiveDate::from_ymd_opt(2026, 1, 14).unwrap();
+    let to = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let result = report::generate(&db, from, to).unwrap();
+    assert_eq!(result.days_with_entries, 2);
+}
+
+/// Scenario: Backdated entries are stamped at local noon
+#[test]
+fn test_log_metric_with_date_uses_local_noon() {
+    let (_dir, db) = setup_db_in("Asia/Tokyo");
+    let config = Config::default();
+    let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+
+    let m = logging::log_metric(
+        &db,
+        &config,
+        LogEntry {
+            metric_type: "weight",
+            value: 80.0,
+            note: None,
+            tags: None,
+            source: openvital::core::logging::Source::Manual,
+            date: Some(date),
+            unit: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        m.timestamp,
+        Utc.with_ymd_and_hms(2026, 1, 15, 3, 0, 0).unwrap()
+    );
+    assert_eq!(db.query_by_date(date, SortOrder::Asc).unwrap().len(), 1);
+}
+
+/// 23:30 local on Jan 14 and 00:30 local on Jan 15 in Stockholm (UTC+1):
+/// both on Jan 14 in UTC.
+fn around_stockholm_midnight(metric_type: &str, values: [f64; 2]) -> [Metric; 2] {
+    let at = |h, value| {
+        let mut m = Metric::new(metric_type.to_string(), value);
+        m.timestamp = Utc.with_ymd_and_hms(2026, 1, 14, h, 30, 0).unwrap();
+        m
+    };
+    [at(22, values[0]), at(23, values[1])]
+}
+
+fn jan(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 1, d).unwrap()
+}
+
+/// Scenario: Entries either side of local midnight fall on different local days
+#[test]
+fn test_local_midnight_splits_days() {
+    let (_dir, db) = setup_db_in("Europe/Stockholm");
+    for m in around_stockholm_midnight("weight", [80.0, 81.0]) {
+        db.insert_metric(&m).unwrap();
+    }
+    let on = |d| db.query_by_date(jan(d), SortOrder::Asc).unwrap();
+    assert_eq!(on(14)[0].value, 80.0);
+    assert_eq!(on(15)[0].value, 81.0);
+    assert_eq!(on(14).len() + on(15).len(), 2);
+
+    let result = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+    let labels: Vec<&str> = result.data.iter().map(|p| p.label.as_str()).collect();
+    assert_eq!(labels, ["2026-01-14", "2026-01-15"]);
+
+    let streaks = status::compute_streaks(&db, jan(15)).unwrap();
+    assert_eq!(streaks.logging_days, 2);
+
+    // The same entries share one UTC day
+    let (_dir, utc) = setup_db_in("UTC");
+    for m in around_stockholm_midnight("weight", [80.0, 81.0]) {
+        utc.insert_metric(&m).unwrap();
+    }
+    let result = trend::compute(&utc, "weight", TrendPeriod::Daily, None).unwrap();
+    assert_eq!(result.data.len(), 1);
+}
+
+/// Scenario: A daily goal only sums entries from the local day
+#[test]
+fn test_daily_goal_window_is_local_day() {
+    for (tz, today, completes) in [("Europe/Stockholm", 15, false), ("UTC", 14, true)] {
+        let (_dir, db) = setup_db_in(tz);
+        goal::set_goal(
+            &db,
+            "water".into(),
+            1000.0,
+            Direction::Above,
+            Timeframe::Daily,
+        )
+        .unwrap();
+        let [late, early] = around_stockholm_midnight("water", [600.0, 500.0]);
+        db.insert_metric(&late).unwrap();
+        db.insert_metric(&early).unwrap();
+
+        let done = goal::completed_by(&db, &[early], jan(today)).unwrap();
+        assert_eq!(done.len() == 1, completes, "timezone = {tz}");
+    }
+}
+
+/// Scenario: Doses either side of local midnight count for their own local days
+#[test]
+fn test_med_adherence_days_are_local() {
+    let (_dir, db) = setup_db_in("Europe/Stockholm");
+    let config = Config::default();
+    med::add_medication(
+        &db,
+        &config,
+        med::AddMedicationParams {
+            name: "metformin",
+            dose: None,
+            freq: "daily",
+            route: None,
+            note: None,
+            started: Some(jan(14)),
+            interacts_with: &[],
+        },
+    )
+    .unwrap();
+    for mut dose in around_stockholm_midnight("metformin", [1.0, 1.0]) {
+        dose.category = Category::Medication;
+        dose.unit = "dose".into();
+        dose.source = "med_take".into();
+        db.insert_metric(&dose).unwrap();
+    }
+
+    for d in [14, 15] {
+        let s = &med::adherence_status_on(&db, &config, Some("metformin"), 7, jan(d)).unwrap()[0];
+        assert_eq!(s.adherent_today, Some(true), "Jan {d}");
+    }
+}
+
+/// Scenario: Opening a database with an unknown timezone fails clearly
+#[test]
+fn test_open_with_invalid_timezone_fails() {
+    let dir = TempDir::new().unwrap();
+    let err = Database::open_with_timezone(&dir.path().join("test.db"), "Nowhere/Land")
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("invalid timezone"));
+}
+
+/// Scenario: local_today falls back to UTC for unknown names
+#[test]
+fn test_local_today_invalid_falls_back_to_utc() {
+    assert_eq!(time::local_today("Nowhere/Land"), Utc::now().date_naive());
+}
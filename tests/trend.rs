@@ -1,7 +1,8 @@
 mod common;
 
 use chrono::NaiveDate;
-use openvital::core::trend::{self, TrendPeriod};
+use openvital::core::trend::{self, TrendOptions, TrendPeriod};
+use openvital::models::metric::Aggregation;
 use std::str::FromStr;
 
 #[test]
@@ -63,7 +64,9 @@ fn test_daily_trend_aggregates_same_day() {
     let result = trend::compute(&db, "water", TrendPeriod::Daily, Some(30)).unwrap();
 
     assert_eq!(result.data.len(), 2);
-    assert!((result.data[0].avg - 650.0).abs() < f64::EPSILON);
+    // water is cumulative, so same-day entries are summed
+    assert_eq!(result.aggregation, Aggregation::Sum);
+    assert!((result.data[0].avg - 1300.0).abs() < f64::EPSILON);
     assert_eq!(result.data[0].count, 2);
     assert!((result.data[1].avg - 700.0).abs() < f64::EPSILON);
 }
@@ -379,3 +382,395 @@ fn test_projection_with_negative_values_stays_bounded() {
         projected
     );
 }
+
+#[test]
+fn test_smoothing_moving_average_uses_available_points() {
+    let (_dir, db) = common::setup_db();
+
+    for (day, val) in [(1u32, 80.0_f64), (2, 82.0), (3, 84.0), (4, 86.0)] {
+        let m = common::make_metric(
+            "weight",
+            val,
+            NaiveDate::from_ymd_opt(2026, 1, day).unwrap(),
+        );
+        db.insert_metric(&m).unwrap();
+    }
+
+    let result = trend::compute_with(
+        &db,
+        "weight",
+        TrendPeriod::Daily,
+        TrendOptions {
+            last: None,
+            smooth: Some(3),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mas: Vec<f64> = result.data.iter().map(|d| d.moving_avg.unwrap()).collect();
+    // First bucket only has itself, second averages two, then full 3-bucket windows
+    assert_eq!(mas, vec![80.0, 81.0, 82.0, 84.0]);
+    // Raw averages are preserved alongside
+    assert_eq!(result.data[3].avg, 86.0);
+}
+
+#[test]
+fn test_smoothing_window_covers_buckets_before_last_limit() {
+    let (_dir, db) = common::setup_db();
+
+    for (day, val) in [(1u32, 10.0_f64), (2, 20.0), (3, 30.0)] {
+        let m = common::make_metric(
+            "weight",
+            val,
+            NaiveDate::from_ymd_opt(2026, 1, day).unwrap(),
+        );
+        db.insert_metric(&m).unwrap();
+    }
+
+    let result = trend::compute_with(
+        &db,
+        "weight",
+        TrendPeriod::Daily,
+        TrendOptions {
+            last: Some(1),
+            smooth: Some(3),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(result.data.len(), 1);
+    assert_eq!(result.data[0].moving_avg, Some(20.0));
+}
+
+#[test]
+fn test_smoothing_zero_or_one_behaves_as_unsmoothed() {
+    let (_dir, db) = common::setup_db();
+
+    for (day, val) in [(1u32, 80.0_f64), (2, 90.0), (3, 70.0)] {
+        let m = common::make_metric(
+            "weight",
+            val,
+            NaiveDate::from_ymd_opt(2026, 1, day).unwrap(),
+        );
+        db.insert_metric(&m).unwrap();
+    }
+
+    let plain = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+    for n in [0, 1] {
+        let r = trend::compute_with(
+            &db,
+            "weight",
+            TrendPeriod::Daily,
+            TrendOptions {
+                last: None,
+                smooth: Some(n),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(r.data.iter().all(|d| d.moving_avg.is_none()));
+        assert_eq!(r.trend.rate, plain.trend.rate);
+        assert_eq!(r.trend.direction, plain.trend.direction);
+    }
+}
+
+#[test]
+fn test_smoothing_drives_trend_rate() {
+    let (_dir, db) = common::setup_db();
+
+    // Noisy spike at the end: raw slope is steep, smoothed slope is gentler
+    for (day, val) in [(1u32, 80.0_f64), (2, 80.0), (3, 80.0), (4, 80.0), (5, 90.0)] {
+        let m = common::make_metric(
+            "weight",
+            val,
+            NaiveDate::from_ymd_opt(2026, 1, day).unwrap(),
+        );
+        db.insert_metric(&m).unwrap();
+    }
+
+    let raw = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+    let smoothed = trend::compute_with(
+        &db,
+        "weight",
+        TrendPeriod::Daily,
+        TrendOptions {
+            last: None,
+            smooth: Some(5),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(smoothed.trend.rate < raw.trend.rate);
+}
+
+#[test]
+fn test_weight_defaults_to_avg_aggregation() {
+    let (_dir, db) = common::setup_db();
+
+    let day = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+    db.insert_metric(&common::make_metric("weight", 80.0, day))
+        .unwrap();
+    db.insert_metric(&common::make_metric("weight", 82.0, day))
+        .unwrap();
+
+    let result = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+
+    assert_eq!(result.aggregation, Aggregation::Avg);
+    assert_eq!(result.data[0].avg, 81.0);
+}
+
+#[test]
+fn test_aggregation_override_max() {
+    let (_dir, db) = common::setup_db();
+
+    let day = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+    for v in [80.0, 83.0, 81.0] {
+        db.insert_metric(&common::make_metric("weight", v, day))
+            .unwrap();
+    }
+
+    let result = trend::compute_with(
+        &db,
+        "weight",
+        TrendPeriod::Daily,
+        TrendOptions {
+            aggregation: Some(Aggregation::Max),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(result.aggregation, Aggregation::Max);
+    assert_eq!(result.data[0].avg, 83.0);
+}
+
+#[test]
+fn test_slope_standard_error_hand_computed() {
+    let (_dir, db) = common::setup_db();
+
+    // x = 0..3, y = 1,3,2,5 → slope 1.1, SSres 2.7, SSx 5, SE = sqrt(2.7 / (2 * 5))
+    for (day, val) in [(1u32, 1.0_f64), (2, 3.0), (3, 2.0), (4, 5.0)] {
+        let m = common::make_metric("mood", val, NaiveDate::from_ymd_opt(2026, 1, day).unwrap());
+        db.insert_metric(&m).unwrap();
+    }
+
+    let result = trend::compute(&db, "mood", TrendPeriod::Daily, None).unwrap();
+
+    let se = result.trend.std_error.unwrap();
+    assert!((se - 0.27f64.sqrt()).abs() < 1e-9);
+    // df = 2 → t = 4.303
+    let lo = result.trend.confidence_95_lower.unwrap();
+    let hi = result.trend.confidence_95_upper.unwrap();
+    assert!((lo - (1.1 - 4.303 * se)).abs() < 1e-9);
+    assert!((hi - (1.1 + 4.303 * se)).abs() < 1e-9);
+}
+
+#[test]
+fn test_slope_confidence_null_with_fewer_than_three_points() {
+    let (_dir, db) = common::setup_db();
+
+    for (day, val) in [(1u32, 80.0_f64), (2, 81.0)] {
+        let m = common::make_metric(
+            "weight",
+            val,
+            NaiveDate::from_ymd_opt(2026, 1, day).unwrap(),
+        );
+        db.insert_metric(&m).unwrap();
+    }
+
+    let result = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+
+    assert!(result.trend.std_error.is_none());
+    assert!(result.trend.confidence_95_lower.is_none());
+    assert!(result.trend.confidence_95_upper.is_none());
+}
+
+// ── gap filling ─────────────────────────────────────────────────────────────
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+}
+
+#[test]
+fn test_fill_gaps_forward_linear_and_max_gap() {
+    let series: std::collections::BTreeMap<NaiveDate, f64> =
+        [(day(1), 80.0), (day(4), 83.0), (day(10), 90.0)].into();
+    let fill = |method| trend::GapFill {
+        method,
+        max_gap_days: 3,
+    };
+
+    let forward = trend::fill_gaps(&series, fill(trend::FillMethod::Forward));
+    assert_eq!(forward.get(&day(2)), Some(&80.0));
+    assert_eq!(forward.get(&day(3)), Some(&80.0));
+    // Five missing days between the 4th and the 10th exceed the max gap
+    assert!((5..10).all(|d| !forward.contains_key(&day(d))));
+    assert_eq!(forward.len(), 2);
+
+    let linear = trend::fill_gaps(&series, fill(trend::FillMethod::Linear));
+    assert_eq!(linear.get(&day(2)), Some(&81.0));
+    assert_eq!(linear.get(&day(3)), Some(&82.0));
+    assert_eq!(linear.len(), 2);
+
+    assert!(trend::fill_gaps(&series, fill(trend::FillMethod::None)).is_empty());
+}
+
+#[test]
+fn test_correlate_fill_adds_pairs_and_reports_synthetic_points() {
+    let (_dir, db) = common::setup_db();
+    // mood every day, weight every other day
+    for d in 1..=10 {
+        db.insert_metric(&common::make_metric("mood", d as f64, day(d)))
+            .unwrap();
+        if d % 2 == 1 {
+            db.insert_metric(&common::make_metric("weight", 80.0 + d as f64, day(d)))
+                .unwrap();
+        }
+    }
+
+    let none = trend::correlate(&db, "weight", "mood", None).unwrap();
+    assert_eq!(none.data_points, 5);
+    assert!(none.fill.is_none());
+
+    let filled = trend::correlate_with(
+        &db,
+        "weight",
+        "mood",
+        None,
+        0,
+        14,
+        trend::GapFill {
+            method: trend::FillMethod::Linear,
+            max_gap_days: 3,
+        },
+    )
+    .unwrap();
+    // Days 2, 4, 6 and 8 are interpolated; day 10 has no later weight to reach
+    assert_eq!(filled.data_points, 9);
+    let report = filled.fill.unwrap();
+    assert_eq!(report.method, trend::FillMethod::Linear);
+    assert_eq!(report.synthetic_points, 4);
+}
+
+#[test]
+fn test_trend_fill_counts_synthetic_values_apart_from_readings() {
+    let (_dir, db) = common::setup_db();
+    for (d, v) in [(1, 80.0), (3, 82.0), (10, 84.0)] {
+        db.insert_metric(&common::make_metric("weight", v, day(d)))
+            .unwrap();
+    }
+
+    let result = trend::compute_with(
+        &db,
+        "weight",
+        TrendPeriod::Daily,
+        TrendOptions {
+            fill: trend::GapFill {
+                method: trend::FillMethod::Forward,
+                max_gap_days: 3,
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let labels: Vec<&str> = result.data.iter().map(|d| d.label.as_str()).collect();
+    assert_eq!(
+        labels,
+        ["2026-03-01", "2026-03-02", "2026-03-03", "2026-03-10"]
+    );
+    assert_eq!(result.data[1].count, 0);
+    assert_eq!(result.data[1].filled, 1);
+    assert_eq!(result.data[1].avg, 80.0);
+    assert_eq!(result.fill.unwrap().synthetic_points, 1);
+
+    let plain = trend::compute(&db, "weight", TrendPeriod::Daily, None).unwrap();
+    assert!(plain.fill.is_none());
+    assert_eq!(plain.data.len(), 3);
+}
+
+// ─── by weekday ─────────────────────────────────────────────────────────────
+
+/// Sunday 2026-03-01; two weeks back starts on Monday 2026-02-16.
+fn weekday_today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+}
+
+#[test]
+fn test_by_weekday_buckets_whole_weeks() {
+    let (_dir, db) = common::setup_db();
+    let day = |m: u32, d: u32| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+    for (date, v) in [
+        (day(2, 16), 7.0), // Monday
+        (day(2, 23), 8.0), // Monday
+        (day(2, 17), 6.0), // Tuesday
+        (day(2, 22), 5.0), // Sunday
+        (day(3, 1), 6.0),  // Sunday
+        (day(2, 9), 9.0),  // Monday, before the window
+    ] {
+        db.insert_metric(&common::make_metric("sleep_hours", v, date))
+            .unwrap();
+    }
+
+    let result = trend::by_weekday_on(&db, "sleep_hours", Some(2), None, weekday_today()).unwrap();
+    assert_eq!(result.from, day(2, 16));
+    assert_eq!(result.to, day(3, 1));
+    assert_eq!(result.aggregation, Aggregation::Avg);
+    assert_eq!(result.weekdays.len(), 7);
+
+    let monday = &result.weekdays[0];
+    assert_eq!(monday.weekday, "monday");
+    assert_eq!(monday.count, 2);
+    assert_eq!(monday.avg, Some(7.5));
+    assert_eq!(monday.min, Some(7.0));
+    assert_eq!(monday.max, Some(8.0));
+
+    let wednesday = &result.weekdays[2];
+    assert_eq!(wednesday.count, 0);
+    assert!(wednesday.avg.is_none());
+
+    let sunday = &result.weekdays[6];
+    assert_eq!(sunday.avg, Some(5.5));
+    assert_eq!(result.best.as_deref(), Some("monday"));
+    assert_eq!(result.worst.as_deref(), Some("sunday"));
+}
+
+#[test]
+fn test_by_weekday_medication_sums_and_goal_direction() {
+    use openvital::core::goal;
+    use openvital::models::goal::{Direction, Timeframe};
+    use openvital::models::metric::Category;
+
+    let (_dir, db) = common::setup_db();
+    let day = |d: u32| NaiveDate::from_ymd_opt(2026, 2, d).unwrap();
+    // Two doses on each of two Mondays, one on a Friday
+    for d in [16, 16, 23, 23, 20] {
+        let mut m = common::make_metric("ibuprofen", 1.0, day(d));
+        m.category = Category::Medication;
+        db.insert_metric(&m).unwrap();
+    }
+    let result = trend::by_weekday_on(&db, "ibuprofen", Some(2), None, weekday_today()).unwrap();
+    assert_eq!(result.aggregation, Aggregation::Sum);
+    assert_eq!(result.weekdays[0].avg, Some(4.0));
+    assert_eq!(result.weekdays[4].avg, Some(1.0));
+
+    // With a "below" goal the lowest weekday is the best
+    for (d, v) in [(16, 80.0), (17, 81.0), (18, 82.0)] {
+        db.insert_metric(&common::make_metric("weight", v, day(d)))
+            .unwrap();
+    }
+    goal::set_goal(
+        &db,
+        "weight".into(),
+        78.0,
+        Direction::Below,
+        Timeframe::Daily,
+    )
+    .unwrap();
+    let result = trend::by_weekday_on(&db, "weight", Some(2), None, weekday_today()).unwrap();
+    assert_eq!(result.best.as_deref(), Some("monday"));
+    assert_eq!(result.worst.as_deref(), Some("wednesday"));
+}
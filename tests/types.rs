@@ -0,0 +1,244 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::goal::set_goal;
+use openvital::core::logging::{LogEntry, log_metric};
+use openvital::core::types;
+use openvital::db::SortOrder;
+use openvital::error::{self, ErrorKind};
+use openvital::models::config::Config;
+use openvital::models::goal::{Direction, Timeframe};
+use openvital::models::metric::Category;
+
+fn entry(metric_type: &str, value: f64) -> LogEntry<'_> {
+    LogEntry {
+        metric_type,
+        value,
+        note: None,
+        tags: None,
+        source: openvital::core::logging::Source::Manual,
+        date: None,
+        unit: None,
+    }
+}
+
+#[test]
+fn test_custom_type_supplies_unit_and_category() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+
+    assert_eq!(types::default_unit(&db, "hrv").unwrap(), "");
+    types::add(&db, "hrv", "ms", Category::Body).unwrap();
+    assert_eq!(types::default_unit(&db, "hrv").unwrap(), "ms");
+    assert_eq!(types::category(&db, "hrv").unwrap(), Category::Body);
+    // Built-ins are unaffected
+    assert_eq!(types::default_unit(&db, "weight").unwrap(), "kg");
+
+    let m = log_metric(&db, &config, entry("hrv", 52.0)).unwrap();
+    assert_eq!(m.unit, "ms");
+    assert_eq!(m.category, Category::Body);
+    let stored = db.query_by_type("hrv", Some(10), SortOrder::Desc).unwrap();
+    assert_eq!(stored[0].unit, "ms");
+
+    let list = types::list(&db, &config).unwrap();
+    assert!(list.builtin.iter().any(|t| t.name == "weight"));
+    assert_eq!(list.custom.len(), 1);
+    assert_eq!(list.custom[0].name, "hrv");
+}
+
+#[test]
+fn test_custom_type_add_rejects_builtin_and_duplicates() {
+    let (_dir, db) = common::setup_db();
+
+    let err = types::add(&db, "weight", "lb", Category::Body).unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::Conflict);
+
+    types::add(&db, "hrv", "ms", Category::Custom).unwrap();
+    assert!(types::add(&db, "hrv", "ms", Category::Custom).is_err());
+}
+
+#[test]
+fn test_custom_type_remove_needs_force_with_entries() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+
+    types::add(&db, "hrv", "ms", Category::Custom).unwrap();
+    log_metric(&db, &config, entry("hrv", 48.0)).unwrap();
+
+    let err = types::remove(&db, "hrv", false).unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::Conflict);
+
+    let removed = types::remove(&db, "hrv", true).unwrap();
+    assert_eq!(removed.entries, 1);
+    assert!(db.get_custom_type("hrv").unwrap().is_none());
+    // Entries keep the unit they were logged with
+    assert_eq!(
+        db.query_by_type("hrv", Some(10), SortOrder::Desc).unwrap()[0].unit,
+        "ms"
+    );
+
+    let err = types::remove(&db, "hrv", false).unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::NotFound);
+}
+
+#[test]
+fn test_list_reports_entries_aliases_and_observed_types() {
+    let (_dir, db) = common::setup_db();
+    let config = Config {
+        aliases: Config::default_aliases(),
+        ..Config::default()
+    };
+
+    log_metric(&db, &config, entry("weight", 80.0)).unwrap();
+    log_metric(&db, &config, entry("weight", 79.5)).unwrap();
+    log_metric(
+        &db,
+        &config,
+        LogEntry {
+            unit: Some("beats"),
+            ..entry("skipping", 120.0)
+        },
+    )
+    .unwrap();
+
+    let list = types::list(&db, &config).unwrap();
+    let weight = list.builtin.iter().find(|t| t.name == "weight").unwrap();
+    assert_eq!(weight.entries, 2);
+    assert!(weight.aliases.contains(&"w".to_string()));
+    assert_eq!(list.aliases.get("w").map(String::as_str), Some("weight"));
+
+    assert_eq!(list.observed.len(), 1);
+    assert_eq!(list.observed[0].name, "skipping");
+    assert_eq!(list.observed[0].unit, "beats");
+    assert_eq!(list.observed[0].entries, 1);
+    assert!(list.custom.is_empty());
+}
+
+#[test]
+fn test_detail_resolves_alias_and_reports_range() {
+    let (_dir, db) = common::setup_db();
+    let config = Config {
+        aliases: Config::default_aliases(),
+        ..Config::default()
+    };
+
+    for (value, day) in [(80.0, 1), (78.5, 10), (79.0, 5)] {
+        let date = NaiveDate::from_ymd_opt(2026, 1, day).unwrap();
+        db.insert_metric(&common::make_metric("weight", value, date))
+            .unwrap();
+    }
+
+    let d = types::detail(&db, &config, "w").unwrap();
+    assert_eq!(d.name, "weight");
+    assert_eq!(d.kind, "builtin");
+    assert_eq!(d.entries, 3);
+    assert_eq!(d.min, Some(78.5));
+    assert_eq!(d.max, Some(80.0));
+    assert_eq!(
+        d.first.unwrap().format("%Y-%m-%d").to_string(),
+        "2026-01-01"
+    );
+    assert_eq!(d.last.unwrap().format("%Y-%m-%d").to_string(), "2026-01-10");
+
+    // Known but never logged: no range
+    let d = types::detail(&db, &config, "steps").unwrap();
+    assert_eq!(d.entries, 0);
+    assert!(d.first.is_none());
+
+    let err = types::detail(&db, &config, "nonsense").unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::NotFound);
+}
+
+#[test]
+fn test_migrate_type_merges_entries_goals_and_aliases() {
+    let (_dir, db) = common::setup_db();
+    let mut config = Config::default();
+    config.aliases.insert("r".into(), "run".into());
+
+    log_metric(&db, &config, entry("run", 30.0)).unwrap();
+    log_metric(&db, &config, entry("run", 45.0)).unwrap();
+    log_metric(&db, &config, entry("cardio", 20.0)).unwrap();
+    set_goal(
+        &db,
+        "run".into(),
+        150.0,
+        Direction::Above,
+        Timeframe::Weekly,
+    )
+    .unwrap();
+
+    let opts = types::MigrateOptions {
+        dry_run: true,
+        goals: true,
+        aliases: true,
+    };
+    let preview = types::migrate_type(&db, &mut config, "run", "cardio", opts).unwrap();
+    assert_eq!(preview.metrics, 2);
+    assert_eq!(preview.units_filled, 2);
+    assert_eq!(preview.goals, 1);
+    assert!(preview.merged);
+    // Nothing written yet
+    assert_eq!(db.count_metrics_of_type("run").unwrap(), 2);
+    assert_eq!(config.resolve_alias("r"), "run");
+
+    let opts = types::MigrateOptions {
+        dry_run: false,
+        ..opts
+    };
+    let result = types::migrate_type(&db, &mut config, "run", "cardio", opts).unwrap();
+    assert_eq!(result.metrics, 2);
+    assert_eq!(result.goals, 1);
+    assert_eq!(result.aliases, vec!["r".to_string()]);
+    assert_eq!(config.resolve_alias("r"), "cardio");
+
+    assert_eq!(db.count_metrics_of_type("run").unwrap(), 0);
+    let cardio = db
+        .query_by_type("cardio", Some(10), SortOrder::Desc)
+        .unwrap();
+    assert_eq!(cardio.len(), 3);
+    assert!(
+        cardio
+            .iter()
+            .all(|m| m.unit == "min" && m.category == Category::Exercise)
+    );
+    assert!(db.get_goal_by_type("cardio").unwrap().is_some());
+
+    let err = types::migrate_type(&db, &mut config, "run", "cardio", opts).unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::NotFound);
+}
+
+#[test]
+fn test_migrate_type_refuses_two_active_goals() {
+    let (_dir, db) = common::setup_db();
+    let mut config = Config::default();
+    log_metric(&db, &config, entry("run", 30.0)).unwrap();
+    set_goal(
+        &db,
+        "run".into(),
+        150.0,
+        Direction::Above,
+        Timeframe::Weekly,
+    )
+    .unwrap();
+    set_goal(
+        &db,
+        "cardio".into(),
+        150.0,
+        Direction::Above,
+        Timeframe::Weekly,
+    )
+    .unwrap();
+
+    let opts = types::MigrateOptions {
+        goals: true,
+        ..Default::default()
+    };
+    let err = types::migrate_type(&db, &mut config, "run", "cardio", opts).unwrap_err();
+    assert_eq!(error::kind_of(&err), ErrorKind::Conflict);
+    // Without --goals the entries move and the goals stay put
+    let result =
+        types::migrate_type(&db, &mut config, "run", "cardio", Default::default()).unwrap();
+    assert_eq!(result.metrics, 1);
+    assert_eq!(result.goals, 0);
+    assert!(db.get_goal_by_type("run").unwrap().is_some());
+}
@@ -159,3 +159,78 @@ fn test_calories_category_is_nutrition() {
     assert_eq!(Category::from_type("calories"), Category::Nutrition);
     assert_eq!(Category::from_type("calories_out"), Category::Nutrition);
 }
+
+// ── per-type overrides ──────────────────────────────────────────────────────
+
+#[test]
+fn test_override_water_oz_round_trip() {
+    let mut u = Units::default();
+    u.custom.insert(
+        "water".into(),
+        units::parse_override("water", "oz").unwrap(),
+    );
+
+    let stored = units::from_input(16.0, "water", &u);
+    assert!((stored - 473.2).abs() < 0.1, "{}", stored);
+    assert_eq!(
+        units::to_display(stored, "water", &u),
+        (16.0, "oz".to_string())
+    );
+    // Other types keep the global system
+    assert_eq!(units::display_unit("weight", &u), "kg");
+}
+
+#[test]
+fn test_override_takes_precedence_over_imperial() {
+    let mut u = Units::imperial();
+    u.custom.insert(
+        "weight".into(),
+        units::parse_override("weight", "kg").unwrap(),
+    );
+    assert_eq!(
+        units::to_display(80.0, "weight", &u),
+        (80.0, "kg".to_string())
+    );
+    assert_eq!(units::from_input(80.0, "weight", &u), 80.0);
+}
+
+#[test]
+fn test_override_explicit_factor_and_display_only_label() {
+    let o = units::parse_override("run_distance", "mi:1.609344").unwrap();
+    assert_eq!(o.factor, Some(1.609344));
+
+    // Unknown unit, no factor: a label only
+    let o = units::parse_override("run_distance", "km").unwrap();
+    assert_eq!(o.unit, "km");
+    assert_eq!(o.factor, None);
+    let mut u = Units::default();
+    u.custom.insert("run_distance".into(), o);
+    assert_eq!(units::from_input(5.2, "run_distance", &u), 5.2);
+    assert_eq!(
+        units::to_display(5.2, "run_distance", &u),
+        (5.2, "km".to_string())
+    );
+
+    assert!(units::parse_override("water", "oz:0").is_err());
+    assert!(units::parse_override("water", "oz:x").is_err());
+}
+
+#[test]
+fn test_glucose_mmol_override_converts_mg_dl() {
+    let mut u = Units::default();
+    assert_eq!(units::display_unit("glucose", &u), "mg/dL");
+    u.custom.insert(
+        "glucose".into(),
+        units::parse_override("glucose", "mmol/L").unwrap(),
+    );
+
+    assert!((units::from_input(5.5, "glucose", &u) - 99.0).abs() < 1e-9);
+    assert_eq!(
+        units::to_display(99.0, "glucose", &u),
+        (5.5, "mmol/L".to_string())
+    );
+    assert_eq!(
+        units::to_display(180.0, "glucose", &u),
+        (10.0, "mmol/L".to_string())
+    );
+}
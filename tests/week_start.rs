@@ -0,0 +1,115 @@
+mod common;
+
+use chrono::{NaiveDate, Weekday};
+use openvital::core::med::{self, AddMedicationParams};
+use openvital::core::trend::{self, TrendPeriod};
+use openvital::core::{goal, time};
+use openvital::db::Database;
+use openvital::models::config::{Config, Profile, WeekStart};
+use openvital::models::goal::{Direction, Timeframe};
+use tempfile::TempDir;
+
+fn d(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+}
+
+fn setup_db_with(week_start: WeekStart) -> (TempDir, Database, Config) {
+    let dir = TempDir::new().unwrap();
+    let config = Config {
+        profile: Profile {
+            timezone: Some("UTC".into()),
+            ..Profile::default()
+        },
+        week_start,
+        ..Config::default()
+    };
+    let db = Database::open_with_config(&dir.path().join("test.db"), &config).unwrap();
+    (dir, db, config)
+}
+
+/// Scenario: Sunday 8 March starts its own week only under a Sunday start
+#[test]
+fn test_week_start_for_sunday() {
+    assert_eq!(time::week_start_for(d(8), Weekday::Mon), d(2));
+    assert_eq!(time::week_start_for(d(8), Weekday::Sun), d(8));
+    assert_eq!(time::week_start_for(d(7), Weekday::Sun), d(1));
+    assert_eq!(
+        time::parse_date_in_week("last week", d(8), Weekday::Mon).unwrap(),
+        NaiveDate::from_ymd_opt(2026, 2, 23).unwrap()
+    );
+    assert_eq!(
+        time::parse_date_in_week("last week", d(8), Weekday::Sun).unwrap(),
+        d(1)
+    );
+}
+
+/// Scenario: A Sunday entry completes a weekly goal only when it shares a
+/// week with Saturday's entry
+#[test]
+fn test_weekly_goal_window_follows_week_start() {
+    for (week_start, completes) in [(WeekStart::Monday, true), (WeekStart::Sunday, false)] {
+        let (_dir, db, _) = setup_db_with(week_start);
+        goal::set_goal(
+            &db,
+            "water".into(),
+            1000.0,
+            Direction::Above,
+            Timeframe::Weekly,
+        )
+        .unwrap();
+        db.insert_metric(&common::make_metric("water", 600.0, d(7)))
+            .unwrap();
+        let sunday = common::make_metric("water", 500.0, d(8));
+        db.insert_metric(&sunday).unwrap();
+
+        let done = goal::completed_by(&db, &[sunday], d(8)).unwrap();
+        assert_eq!(done.len() == 1, completes, "week_start = {week_start}");
+    }
+}
+
+/// Scenario: Saturday and Sunday share a weekly trend bucket only with a
+/// Monday start
+#[test]
+fn test_weekly_trend_buckets_follow_week_start() {
+    for (week_start, buckets) in [(WeekStart::Monday, 1), (WeekStart::Sunday, 2)] {
+        let (_dir, db, _) = setup_db_with(week_start);
+        db.insert_metric(&common::make_metric("weight", 80.0, d(7)))
+            .unwrap();
+        db.insert_metric(&common::make_metric("weight", 81.0, d(8)))
+            .unwrap();
+
+        let result = trend::compute(&db, "weight", TrendPeriod::Weekly, None).unwrap();
+        assert_eq!(result.data.len(), buckets, "week_start = {week_start}");
+    }
+}
+
+/// Scenario: A weekly dose taken on Saturday covers Sunday only when both
+/// fall in the same week
+#[test]
+fn test_weekly_med_adherence_follows_week_start() {
+    for (week_start, adherent) in [(WeekStart::Monday, true), (WeekStart::Sunday, false)] {
+        let (_dir, db, config) = setup_db_with(week_start);
+        med::add_medication(
+            &db,
+            &config,
+            AddMedicationParams {
+                name: "vitd",
+                dose: None,
+                freq: "weekly",
+                route: None,
+                note: None,
+                started: Some(NaiveDate::from_ymd_opt(2026, 2, 1).unwrap()),
+                interacts_with: &[],
+            },
+        )
+        .unwrap();
+        med::take_medication(&db, &config, "vitd", None, None, None, Some(d(7))).unwrap();
+
+        let s = &med::adherence_status_on(&db, &config, Some("vitd"), 7, d(8)).unwrap()[0];
+        assert_eq!(
+            s.adherent_today,
+            Some(adherent),
+            "week_start = {week_start}"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use chrono::NaiveDate;
+use openvital::core::workout::{self, WorkoutInput};
+use openvital::models::config::Config;
+use openvital::models::metric::Category;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn input(kind: &str, duration: f64, distance: Option<f64>, day: NaiveDate) -> WorkoutInput<'_> {
+    WorkoutInput {
+        kind,
+        duration,
+        distance,
+        intensity: None,
+        note: None,
+        tags: None,
+        date: Some(day),
+    }
+}
+
+#[test]
+fn test_log_workout_links_entries_and_lists_sessions() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+    let run = WorkoutInput {
+        intensity: Some(7.0),
+        note: Some("intervals"),
+        tags: Some("track"),
+        ..input("Run", 45.0, Some(7.5), date(2026, 3, 2))
+    };
+    let metrics = workout::log_workout(&db, &config, &run).unwrap();
+    let types: Vec<&str> = metrics.iter().map(|m| m.metric_type.as_str()).collect();
+    assert_eq!(types, ["cardio", "run_distance", "perceived_exertion"]);
+    assert!(metrics.iter().all(|m| m.timestamp == metrics[0].timestamp
+        && m.tags == metrics[0].tags
+        && m.category == Category::Exercise));
+    assert_eq!(metrics[1].unit, "km");
+    assert!(metrics[0].tags.contains(&"workout:run".to_string()));
+    assert!(metrics[0].tags.contains(&"track".to_string()));
+
+    let ride = workout::log_workout(
+        &db,
+        &config,
+        &input("ride", 60.0, Some(25.0), date(2026, 3, 4)),
+    )
+    .unwrap();
+    assert_eq!(ride[1].metric_type, "ride_distance");
+    assert_eq!(ride[1].unit, "km");
+    // A plain cardio entry is not a session
+    db.insert_metric(&common::make_metric("cardio", 30.0, date(2026, 3, 3)))
+        .unwrap();
+
+    let sessions = workout::list(&db, None, None, None).unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].kind, "ride");
+    assert_eq!(sessions[1].kind, "run");
+    assert_eq!(sessions[1].duration, Some(45.0));
+    assert_eq!(sessions[1].distance, Some(7.5));
+    assert_eq!(sessions[1].intensity, Some(7.0));
+    assert_eq!(sessions[1].note.as_deref(), Some("intervals"));
+    assert_eq!(sessions[1].entry_ids.len(), 3);
+    assert_eq!(workout::list(&db, None, None, Some(1)).unwrap().len(), 1);
+
+    assert!(
+        workout::log_workout(&db, &config, &input("run", 0.0, None, date(2026, 3, 5))).is_err()
+    );
+    let too_hard = WorkoutInput {
+        intensity: Some(11.0),
+        ..input("run", 30.0, None, date(2026, 3, 5))
+    };
+    assert!(workout::log_workout(&db, &config, &too_hard).is_err());
+    assert!(
+        workout::log_workout(
+            &db,
+            &config,
+            &input("run fast", 30.0, None, date(2026, 3, 5))
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_weekly_totals_in_report() {
+    let (_dir, db) = common::setup_db();
+    let config = Config::default();
+    // Sunday 2026-03-01 closes one week; 03-02 and 03-04 fall in the next
+    for (day, minutes, km) in [(1, 30.0, 5.0), (2, 45.0, 7.5), (4, 60.0, 10.0)] {
+        workout::log_workout(
+            &db,
+            &config,
+            &input("run", minutes, Some(km), date(2026, 3, day)),
+        )
+        .unwrap();
+    }
+
+    let report =
+        openvital::core::report::generate(&db, date(2026, 2, 23), date(2026, 3, 8)).unwrap();
+    assert_eq!(report.workouts.len(), 2);
+    assert_eq!(report.workouts[0].week_start, date(2026, 2, 23));
+    assert_eq!(report.workouts[0].sessions, 1);
+    assert_eq!(report.workouts[1].week_start, date(2026, 3, 2));
+    assert_eq!(report.workouts[1].sessions, 2);
+    assert_eq!(report.workouts[1].total_duration, 105.0);
+    assert_eq!(report.workouts[1].total_distance, 17.5);
+}